@@ -0,0 +1,128 @@
+# Deutsche Übersetzung. Fehlende Meldungen fallen auf en-US zurück.
+
+## Benachrichtigungstitel
+
+notification-backup-completed = Sicherung abgeschlossen
+notification-backup-failed = Sicherung fehlgeschlagen
+notification-unverified-drive-detached = Laufwerk mit ungeprüften Sicherungen entfernt
+notification-off-hours-decrypt = Schlüssel außerhalb der üblichen Zeiten verwendet
+notification-failed-unlock-streak = Wiederholt fehlgeschlagene Entsperrversuche
+notification-drive-modified-externally = Laufwerk außerhalb des Tresors verändert
+notification-drive-nearly-full = Sicherungslaufwerk wird voll
+
+## Benachrichtigungstexte
+
+backup-completed-body = Sicherung { $backup } auf Laufwerk { $drive } geschrieben
+backup-failed-body = Sicherung auf Laufwerk { $drive } fehlgeschlagen: { $error }
+unverified-drive-detached-body = Laufwerk { $drive } wurde entfernt, bevor seine Sicherung geprüft war
+off-hours-decrypt-body = Schlüssel { $key } wurde um { $time } zum Signieren verwendet
+failed-unlock-streak-body = { $count } fehlgeschlagene Entsperrversuche in Folge
+drive-modified-externally-body = Laufwerk { $drive } wurde außerhalb des Tresors verändert: { $anomalies }
+drive-nearly-full-body = Laufwerk { $drive }: { $warning }. Nehmen Sie ein neues Sicherungslaufwerk in Betrieb.
+
+## Anzeichen, dass ein Laufwerk außerhalb des Tresors verändert wurde
+
+anomaly-none = keiner
+anomaly-luks-header-changed = LUKS-Header von { $expected } zu { $found } geändert
+anomaly-seal-missing = das Laufwerkssiegel fehlt
+anomaly-nonce-mismatch = das Laufwerkssiegel enthält eine andere Nonce
+anomaly-seal-touched = das Laufwerkssiegel wurde um { $at } neu geschrieben
+anomaly-modified-after-seal = { $path } wurde um { $at } verändert
+
+## Platzprognosen für Sicherungslaufwerke
+
+forecast-next-backup-wont-fit = die nächste Sicherung passt voraussichtlich nicht mehr
+forecast-filling-up =
+    { $days ->
+        [one] das Laufwerk ist voraussichtlich in { $days } Tag voll
+       *[other] das Laufwerk ist voraussichtlich in { $days } Tagen voll
+    }
+
+## Portfoliobericht
+
+report-title = Portfoliobericht - { $vault }
+report-generated = Erstellt { $at }
+report-values-in = Werte in { $currency }, Preise vom { $at }
+report-no-prices = Keine Preisdaten importiert
+report-holdings = Bestände
+report-no-balances = Keine Salden erfasst.
+report-col-asset = Asset
+report-col-amount-base = Menge (Basiseinheiten)
+report-col-keys = Schlüssel
+report-col-value = Wert
+report-col-key = Schlüssel
+report-col-amount = Menge
+report-col-recorded = Erfasst
+report-total-value = Gesamtwert: { $total } { $currency }
+report-keys = Schlüssel
+report-live-keys = { $count } aktive Schlüssel
+report-backup-coverage = Sicherungsabdeckung
+report-mnemonic-recoverable = { $recoverable } von { $keys } Schlüsseln werden aus der Mnemonic des Tresors abgeleitet
+report-within-gap = { $count } davon liegen innerhalb des Adress-Gap-Limits
+report-backup-drives = Sicherungen auf { $count } Laufwerken
+report-last-backup = Letzte Sicherung: { $at }
+report-last-verified-backup = Letzte geprüfte Sicherung: { $at }
+report-never = nie
+report-balances-by-key = Salden je Schlüssel
+report-warnings = Warnungen
+
+## Zustimmung zu zerstörenden oder offenlegenden Vorgängen
+
+consent-purge-trash = { $count } Schlüssel und Einträge im Papierkorb endgültig vernichten
+consent-ssh-private-key-export = Privaten Schlüssel des SSH-Schlüssels { $name } exportieren
+consent-wireguard-config-export = WireGuard-Konfiguration { $name } samt privatem Schlüssel exportieren
+consent-passkey-export = { $count } Passkeys samt privaten Schlüsseln exportieren
+consent-api-token-reveal = API-Token { $name } im Klartext anzeigen
+consent-env-file-render = { $count } API-Tokens im Klartext nach { $path } schreiben
+consent-plugin-item-reveal = Alle Felder von { $name } anzeigen, auch geheime
+consent-plugin-export = Exporter { $exporter } des Plugins { $plugin } ausführen und die Ausgabe nach { $path } schreiben
+consent-slip39-shares = Master-Seed in SLIP-39-Anteile für { $groups } Gruppen aufteilen, von denen { $threshold } zur Wiederherstellung nötig sind
+consent-frost-share-export = Den Anteil dieses Tresors an der FROST-Gruppe { $group } nach { $path } exportieren
+consent-agent-start = Lokalen Programmen über den Secrets-Agent Zugriff auf { $count } Einträge geben
+consent-repair-orphans = Alle Zeilen entfernen, die auf gelöschte Schlüssel und Einträge verweisen
+consent-rollback-keyset = Schlüsselsatz { $name } zurücknehmen und seine { $count } Schlüssel entfernen
+consent-key-escrow = Schlüssel { $key } samt Geheimnis für { $recipient } versiegeln
+
+## Arbeitsblätter für Schlüsselzeremonien
+
+worksheet-genesis-title = Arbeitsblatt Genesis-Zeremonie - { $name }
+worksheet-frost-title = Arbeitsblatt Multisig-Einrichtung - { $threshold } von { $participants }
+worksheet-subject = Datensatz: { $id }
+worksheet-complete = Alle Schritte sind als erledigt erfasst.
+worksheet-incomplete = Einige Schritte sind noch nicht erfasst; ihre Kästchen bleiben leer.
+worksheet-participants = Beteiligte
+worksheet-col-name = Name
+worksheet-col-role = Rolle
+worksheet-col-fingerprint = Schlüssel-Fingerabdruck
+worksheet-col-words = Wörter
+worksheet-role-operator = Operator
+worksheet-role-participant = Teilnehmer
+worksheet-role-this-vault = Dieser Tresor
+worksheet-participant = Teilnehmer { $index }
+worksheet-commitment = Entropie-Commitment { $commitment }
+worksheet-steps = Schritte
+worksheet-step-set-up = Zeremonie mit { $operators } Operatoren eingerichtet
+worksheet-step-committed = { $name } hat Entropie festgelegt
+worksheet-step-generated = Genesis-Schlüsselsatz mit { $keys } Schlüsseln erzeugt
+worksheet-step-attested = { $name } hat das Manifest signiert
+worksheet-step-group-agreed = Gruppe aus { $participants } vereinbart; { $threshold } signieren gemeinsam
+worksheet-step-dkg-finished = Schlüsselerzeugung abgeschlossen; dieser Tresor hält Anteil { $index }
+worksheet-fingerprints = Fingerabdrücke
+worksheet-manifest = Genesis-Manifest
+worksheet-group-key = Öffentlicher Gruppenschlüssel
+worksheet-output-key = Taproot-Ausgabeschlüssel
+worksheet-signatures = Unterschriften
+worksheet-signature-note = Mit ihrer Unterschrift bestätigen alle Beteiligten, dass die Fingerabdrücke oben mit denen auf ihrem eigenen Gerät übereinstimmen.
+worksheet-signature-line = Unterschrift ______________________________   Datum ______________
+worksheet-witness = Zeuge
+
+## Bericht- und Preiswarnungen
+
+warning-no-price = kein Preis für { $asset }; es fließt nicht in den Gesamtwert ein
+warning-no-price-snapshot = keine Preisdaten importiert; Werte fehlen
+warning-stale-balances = { $count } Salden wurden vor mehr als { $days } Tagen erfasst
+warning-no-verified-backup = keine geprüfte Sicherung dieses Tresors
+warning-keys-not-hd = { $count } Schlüssel stammen nicht aus der Mnemonic des Tresors und lassen sich nur aus einer Sicherung wiederherstellen
+warning-keys-beyond-gap = { $count } Schlüssel liegen jenseits des Adress-Gap-Limits; erhöhen Sie es bei der Wiederherstellung aus der Mnemonic
+warning-prices-very-stale = die Preise sind { $days } Tage alt; importieren Sie aktuelle Preisdaten, bevor Sie sich darauf verlassen
+warning-prices-stale = die Preise sind { $hours } Stunden alt
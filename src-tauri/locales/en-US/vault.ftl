@@ -0,0 +1,129 @@
+# Text the vault backend writes for people. Message ids are shared by every
+# locale; a locale that lacks one falls back to this file.
+
+## Notification titles
+
+notification-backup-completed = Backup completed
+notification-backup-failed = Backup failed
+notification-unverified-drive-detached = Drive with unverified backups removed
+notification-off-hours-decrypt = Key used outside normal hours
+notification-failed-unlock-streak = Repeated failed unlock attempts
+notification-drive-modified-externally = Drive modified outside the vault
+notification-drive-nearly-full = Backup drive running out of space
+
+## Notification bodies
+
+backup-completed-body = Backup { $backup } written to drive { $drive }
+backup-failed-body = Backup to drive { $drive } failed: { $error }
+unverified-drive-detached-body = Drive { $drive } was removed before its backup was verified
+off-hours-decrypt-body = Key { $key } was used to sign at { $time }
+failed-unlock-streak-body = { $count } failed unlock attempts in a row
+drive-modified-externally-body = Drive { $drive } was modified outside the vault: { $anomalies }
+drive-nearly-full-body = Drive { $drive }: { $warning }. Rotate in a new backup drive.
+
+## Signs that a drive changed outside the vault
+
+anomaly-none = none
+anomaly-luks-header-changed = LUKS header changed from { $expected } to { $found }
+anomaly-seal-missing = the drive seal is missing
+anomaly-nonce-mismatch = the drive seal holds another nonce
+anomaly-seal-touched = the drive seal was rewritten at { $at }
+anomaly-modified-after-seal = { $path } was modified at { $at }
+
+## Backup drive space forecasts
+
+forecast-next-backup-wont-fit = the next backup is not expected to fit
+forecast-filling-up =
+    { $days ->
+        [one] the drive is expected to be full in { $days } day
+       *[other] the drive is expected to be full in { $days } days
+    }
+
+## Portfolio report
+
+report-title = Portfolio report - { $vault }
+report-generated = Generated { $at }
+report-values-in = Values in { $currency }, prices as of { $at }
+report-no-prices = No price snapshot imported
+report-holdings = Holdings
+report-no-balances = No balances recorded.
+report-col-asset = Asset
+report-col-amount-base = Amount (base units)
+report-col-keys = Keys
+report-col-value = Value
+report-col-key = Key
+report-col-amount = Amount
+report-col-recorded = Recorded
+report-total-value = Total value: { $total } { $currency }
+report-keys = Keys
+report-live-keys = { $count } live keys
+report-backup-coverage = Backup coverage
+report-mnemonic-recoverable = { $recoverable } of { $keys } keys derive from the vault's mnemonic
+report-within-gap = { $count } of those are within the address gap limit
+report-backup-drives = Backups on { $count } drives
+report-last-backup = Last backup: { $at }
+report-last-verified-backup = Last verified backup: { $at }
+report-never = never
+report-balances-by-key = Balances by key
+report-warnings = Warnings
+
+## Consent prompts for destructive or exposing operations
+
+consent-purge-trash = Permanently destroy { $count } trashed keys and items
+consent-ssh-private-key-export = Export the private key of SSH key { $name }
+consent-wireguard-config-export = Export WireGuard config { $name }, private key included
+consent-passkey-export = Export { $count } passkeys, private keys included
+consent-api-token-reveal = Show API token { $name } in plain text
+consent-env-file-render = Write { $count } API tokens in plain text to { $path }
+consent-plugin-item-reveal = Show every field of { $name }, secret ones included
+consent-plugin-export = Run exporter { $exporter } of plugin { $plugin } and write its output to { $path }
+consent-slip39-shares = Split the master seed into SLIP-39 shares in { $groups } groups, { $threshold } of them needed to restore it
+consent-frost-share-export = Export this vault's share of FROST group { $group } to { $path }
+consent-agent-start = Let local tools reach { $count } items through the secrets agent
+consent-repair-orphans = Remove every row left pointing at deleted keys and items
+consent-rollback-keyset = Roll back keyset { $name } and remove its { $count } keys
+consent-key-escrow = Seal key { $key }, secret included, to { $recipient }
+
+## Key ceremony worksheets
+
+worksheet-genesis-title = Genesis ceremony worksheet - { $name }
+worksheet-frost-title = Multisig setup worksheet - { $threshold } of { $participants }
+worksheet-subject = Record: { $id }
+worksheet-complete = Every step is recorded as done.
+worksheet-incomplete = Some steps are not recorded yet; their boxes are left open.
+worksheet-participants = Participants
+worksheet-col-name = Name
+worksheet-col-role = Role
+worksheet-col-fingerprint = Key fingerprint
+worksheet-col-words = Words
+worksheet-role-operator = Operator
+worksheet-role-participant = Participant
+worksheet-role-this-vault = This vault
+worksheet-participant = Participant { $index }
+worksheet-commitment = Entropy commitment { $commitment }
+worksheet-steps = Steps
+worksheet-step-set-up = Ceremony set up with { $operators } operators
+worksheet-step-committed = { $name } committed entropy
+worksheet-step-generated = Genesis keyset of { $keys } keys generated
+worksheet-step-attested = { $name } signed the manifest
+worksheet-step-group-agreed = Group of { $participants } agreed; { $threshold } sign together
+worksheet-step-dkg-finished = Key generation finished; this vault holds share { $index }
+worksheet-fingerprints = Fingerprints
+worksheet-manifest = Genesis manifest
+worksheet-group-key = Group public key
+worksheet-output-key = Taproot output key
+worksheet-signatures = Signatures
+worksheet-signature-note = By signing, each participant confirms that the fingerprints above match the ones shown on their own device.
+worksheet-signature-line = Signature ______________________________   Date ______________
+worksheet-witness = Witness
+
+## Report and price warnings
+
+warning-no-price = no price for { $asset }; it is left out of the total
+warning-no-price-snapshot = no price snapshot imported; values are left out
+warning-stale-balances = { $count } balances were recorded more than { $days } days ago
+warning-no-verified-backup = no verified backup of this vault
+warning-keys-not-hd = { $count } keys are not derived from the vault's mnemonic and are restored only from a backup
+warning-keys-beyond-gap = { $count } keys lie beyond the address gap limit; raise it when restoring from the mnemonic
+warning-prices-very-stale = prices are { $days } days old; import a fresh snapshot before relying on them
+warning-prices-stale = prices are { $hours } hours old
@@ -0,0 +1,125 @@
+use crate::commands::keys::{atomic_write, save_keys, KeyStore, SessionKey};
+use crate::commands::vault::{persist_vault, VaultMutex};
+use crate::error::{Result, VaultError};
+use crate::models::address::{
+    address_records, address_stats, render_addresses, AddressExportFormat, AddressFilter,
+    AddressStats, MAX_ADDRESS_GAP_LIMIT,
+};
+use crate::models::key::KeyEntryPublic;
+use chrono::Utc;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Mark one or more key addresses as used (or back to unused). Used addresses
+/// advance the gap-limit window for their HD account.
+#[tauri::command]
+pub fn mark_addresses_used(
+    app: AppHandle,
+    key_ids: Vec<String>,
+    used: bool,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<KeyEntryPublic>> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let keys_file = vault.0.lock().unwrap().keys_file.clone();
+
+    let mut store = keystore.0.lock().unwrap();
+    if let Some(missing) = key_ids
+        .iter()
+        .find(|id| !store.iter().any(|k| &k.id == *id))
+    {
+        return Err(VaultError::KeyNotFound(missing.clone()));
+    }
+    let now = Utc::now();
+    for key in store.iter_mut().filter(|k| key_ids.contains(&k.id)) {
+        if key.metadata.used != used {
+            key.metadata.used = used;
+            key.metadata.used_at = used.then_some(now);
+        }
+    }
+    save_keys(&app, &keys_file, &session_key, &store)?;
+    Ok(store
+        .iter()
+        .filter(|k| key_ids.contains(&k.id))
+        .map(|k| k.to_public())
+        .collect())
+}
+
+/// Address statistics for the HD account that `key_id` belongs to.
+#[tauri::command]
+pub fn get_address_stats(
+    key_id: String,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+) -> Result<AddressStats> {
+    let gap_limit = vault.0.lock().unwrap().address_gap_limit;
+    let store = keystore.0.lock().unwrap();
+    let key = store
+        .iter()
+        .find(|k| k.id == key_id)
+        .ok_or(VaultError::KeyNotFound(key_id))?;
+    Ok(address_stats(
+        &store,
+        key.metadata.purpose,
+        key.metadata.account,
+        gap_limit,
+    ))
+}
+
+#[tauri::command]
+pub fn set_address_gap_limit(
+    app: AppHandle,
+    gap_limit: u32,
+    state: State<'_, VaultMutex>,
+) -> Result<u32> {
+    if !(1..=MAX_ADDRESS_GAP_LIMIT).contains(&gap_limit) {
+        return Err(VaultError::InvalidMetadata(format!(
+            "gap limit must be between 1 and {MAX_ADDRESS_GAP_LIMIT}"
+        )));
+    }
+    let mut vault = state.0.lock().unwrap();
+    if !vault.initialized {
+        return Err(VaultError::NotInitialized);
+    }
+    vault.address_gap_limit = gap_limit;
+    persist_vault(&app, &vault)?;
+    Ok(gap_limit)
+}
+
+/// Write the addresses of every key matching `filter` to `path` as CSV or
+/// JSON, for accountants and tax tooling. Only public data is written:
+/// labels, key ids, derivation paths, network and usage. The vault does not
+/// sync chain state, so there are no balances to include; join the file
+/// against an explorer export for those. Returns the number of addresses.
+#[tauri::command]
+pub fn export_addresses(
+    filter: AddressFilter,
+    format: AddressExportFormat,
+    path: String,
+    session: State<'_, SessionKey>,
+    keystore: State<'_, KeyStore>,
+) -> Result<usize> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    let target = Path::new(&path);
+    if !target.is_absolute() {
+        return Err(VaultError::InvalidMetadata(
+            "export path must be absolute".to_string(),
+        ));
+    }
+    let records = address_records(&keystore.0.lock().unwrap(), &filter);
+    let rendered = render_addresses(&records, format).map_err(VaultError::InvalidMetadata)?;
+    atomic_write(target, rendered.as_bytes())?;
+    tracing::info!(
+        target: "audit",
+        count = records.len(),
+        path = %path,
+        "addresses exported"
+    );
+    Ok(records.len())
+}
@@ -0,0 +1,254 @@
+use crate::commands::backup::{create_backup, list_drives};
+use crate::commands::keys::{atomic_write, local_data_dir};
+use crate::commands::profiles::{create_profile, list_profiles};
+use crate::commands::selftest::run_security_selftest;
+use crate::commands::spending::list_approvers;
+use crate::commands::vault::{lock_vault, vault_status, verify_password, VaultMutex};
+use crate::error::{Result, VaultError};
+use crate::models::admin_api::{
+    authorize, AdminApiConfig, AdminApiEnabled, AdminApiStatus, AdminOp, AdminRequest,
+    AdminResponse, ADMIN_API_FILE, ADMIN_SOCKET_FILE,
+};
+use chrono::Utc;
+use rand::RngCore;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+use zeroize::Zeroizing;
+
+struct RunningAdminApi {
+    socket_path: PathBuf,
+    shutdown: Arc<AtomicBool>,
+}
+
+/// The admin API listener, if running. Unlike the secrets agent it outlives
+/// the unlocked session: it serves no secrets, and fleets need to reach
+/// locked workstations.
+#[derive(Default)]
+pub struct AdminApiHandle(Mutex<Option<RunningAdminApi>>);
+
+fn load_config(app: &AppHandle) -> Option<AdminApiConfig> {
+    let data = std::fs::read(local_data_dir(app).ok()?.join(ADMIN_API_FILE)).ok()?;
+    serde_json::from_slice(&data)
+        .inspect_err(|e| tracing::warn!("unreadable {ADMIN_API_FILE}: {e}"))
+        .ok()
+}
+
+fn to_value<T: Serialize>(result: Result<T>) -> std::result::Result<serde_json::Value, String> {
+    result
+        .and_then(|v| Ok(serde_json::to_value(v)?))
+        .map_err(|e| e.to_string())
+}
+
+/// Run `op` through the command it mirrors, so it gets the same checks as
+/// from the UI.
+fn answer(app: &AppHandle, op: &AdminOp) -> std::result::Result<serde_json::Value, String> {
+    match op {
+        AdminOp::VaultStatus => to_value(vault_status(app.clone(), app.state())),
+        AdminOp::RunSecuritySelftest => to_value(run_security_selftest(app.clone(), app.state())),
+        AdminOp::ListProfiles => to_value(list_profiles(app.clone(), app.state())),
+        AdminOp::CreateProfile { name } => {
+            to_value(create_profile(app.clone(), name.clone(), app.state()))
+        }
+        AdminOp::ListApprovers => to_value(list_approvers(app.clone(), app.state())),
+        AdminOp::ListDrives => to_value(list_drives(app.state())),
+        AdminOp::CreateBackup { drive_id } => to_value(create_backup(
+            app.clone(),
+            drive_id.clone(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+        )),
+        AdminOp::LockVault => to_value(lock_vault(
+            app.clone(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+        )),
+    }
+}
+
+fn handle_line(app: &AppHandle, line: &str, config: &AdminApiConfig) -> AdminResponse {
+    let Ok(request) = serde_json::from_str::<AdminRequest>(line) else {
+        return AdminResponse::error("malformed request");
+    };
+    let op = request.op.name();
+    if let Err(reason) = authorize(&request, config) {
+        tracing::warn!(target: "audit", op, reason, "admin API request denied");
+        return AdminResponse::error(reason);
+    }
+    match answer(app, &request.op) {
+        Ok(value) => {
+            tracing::info!(target: "audit", op, "admin API request served");
+            AdminResponse::value(value)
+        }
+        Err(e) => {
+            tracing::info!(target: "audit", op, error = %e, "admin API request failed");
+            AdminResponse::error(e)
+        }
+    }
+}
+
+/// Bind the socket owner-only and serve one request per connection until
+/// `shutdown` is set, one connection at a time.
+#[cfg(unix)]
+fn spawn_listener(
+    app: AppHandle,
+    path: &std::path::Path,
+    config: AdminApiConfig,
+    shutdown: Arc<AtomicBool>,
+) -> Result<()> {
+    use crate::models::admin_api::MAX_ADMIN_REQUEST_BYTES;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+    use std::os::unix::net::UnixListener;
+
+    // A socket left behind by a crash would make `bind` fail.
+    if std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+        let _ = std::fs::remove_file(path);
+    }
+    let listener = UnixListener::bind(path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| VaultError::Storage(e.to_string()))?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            if shutdown.load(Ordering::SeqCst) {
+                break;
+            }
+            let Ok(mut stream) = stream else { continue };
+            let _ = stream.set_read_timeout(Some(std::time::Duration::from_secs(5)));
+            let mut line = Zeroizing::new(String::new());
+            let read = BufReader::new(&stream)
+                .take(MAX_ADMIN_REQUEST_BYTES as u64)
+                .read_line(&mut line);
+            if read.is_err() {
+                continue;
+            }
+            let response = handle_line(&app, line.trim_end(), &config);
+            let Ok(mut reply) = serde_json::to_string(&response) else {
+                continue;
+            };
+            reply.push('\n');
+            let _ = stream.write_all(reply.as_bytes());
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn spawn_listener(
+    _app: AppHandle,
+    _path: &std::path::Path,
+    _config: AdminApiConfig,
+    _shutdown: Arc<AtomicBool>,
+) -> Result<()> {
+    Err(VaultError::Storage(
+        "the admin API needs Unix-domain sockets".to_string(),
+    ))
+}
+
+fn stop(handle: &AdminApiHandle) {
+    let Some(running) = handle.0.lock().unwrap().take() else {
+        return;
+    };
+    running.shutdown.store(true, Ordering::SeqCst);
+    // Wake the blocking accept so the listener thread sees the flag.
+    #[cfg(unix)]
+    let _ = std::os::unix::net::UnixStream::connect(&running.socket_path);
+    let _ = std::fs::remove_file(&running.socket_path);
+}
+
+fn start(app: &AppHandle, handle: &AdminApiHandle, config: AdminApiConfig) -> Result<PathBuf> {
+    stop(handle);
+    let socket_path = local_data_dir(app)?.join(ADMIN_SOCKET_FILE);
+    let shutdown = Arc::new(AtomicBool::new(false));
+    spawn_listener(app.clone(), &socket_path, config, shutdown.clone())?;
+    *handle.0.lock().unwrap() = Some(RunningAdminApi {
+        socket_path: socket_path.clone(),
+        shutdown,
+    });
+    Ok(socket_path)
+}
+
+/// Start the admin API at launch if it was left enabled.
+pub fn start_admin_api(app: &AppHandle) {
+    let Some(config) = load_config(app) else {
+        return;
+    };
+    match start(app, &app.state::<AdminApiHandle>(), config) {
+        Ok(_) => tracing::info!(target: "audit", "admin API started"),
+        Err(e) => tracing::warn!("could not start the admin API: {e}"),
+    }
+}
+
+/// Enable the admin API on `admin.sock` in the local data directory and
+/// return a new token. Tools connect, write one JSON request line carrying
+/// the token and read one JSON reply line. A `read_only` API refuses
+/// operations that change anything. Enabling again replaces the token.
+/// Requires the vault password.
+#[tauri::command]
+pub fn admin_api_enable(
+    app: AppHandle,
+    password: String,
+    read_only: bool,
+    vault: State<'_, VaultMutex>,
+    handle: State<'_, AdminApiHandle>,
+) -> Result<AdminApiEnabled> {
+    verify_password(&app, &vault, &password)?;
+    let mut raw = Zeroizing::new([0u8; 32]);
+    rand::rngs::OsRng.fill_bytes(raw.as_mut());
+    let token = Zeroizing::new(hex::encode(raw.as_ref()));
+    let config = AdminApiConfig {
+        token_blake3_hex: blake3::hash(token.as_bytes()).to_hex().to_string(),
+        read_only,
+        enabled_at: Utc::now(),
+    };
+    atomic_write(
+        &local_data_dir(&app)?.join(ADMIN_API_FILE),
+        &serde_json::to_vec_pretty(&config)?,
+    )?;
+    let socket_path = start(&app, &handle, config)?;
+    tracing::info!(target: "audit", read_only, "admin API enabled");
+    Ok(AdminApiEnabled {
+        socket_path: socket_path.display().to_string(),
+        token: token.to_string(),
+        read_only,
+    })
+}
+
+/// Stop the admin API and forget its token.
+#[tauri::command]
+pub fn admin_api_disable(app: AppHandle, handle: State<'_, AdminApiHandle>) -> Result<()> {
+    stop(&handle);
+    let path = local_data_dir(&app)?.join(ADMIN_API_FILE);
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    }
+    tracing::info!(target: "audit", "admin API disabled");
+    Ok(())
+}
+
+#[tauri::command]
+pub fn admin_api_status(
+    app: AppHandle,
+    handle: State<'_, AdminApiHandle>,
+) -> Result<AdminApiStatus> {
+    let config = load_config(&app);
+    let running = handle.0.lock().unwrap();
+    Ok(AdminApiStatus {
+        enabled: config.is_some(),
+        running: running.is_some(),
+        socket_path: running
+            .as_ref()
+            .map(|r| r.socket_path.display().to_string()),
+        read_only: config.as_ref().is_some_and(|c| c.read_only),
+        enabled_at: config.map(|c| c.enabled_at),
+    })
+}
@@ -0,0 +1,307 @@
+use crate::commands::consent::require_consent;
+use crate::commands::items::ItemStore;
+use crate::commands::keys::{local_data_dir, SessionKey};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::policy::{enforce_item_export, enforce_policy};
+use crate::commands::vault::{verify_password, VaultMutex};
+use crate::crypto::ssh;
+use crate::error::{Result, VaultError};
+use crate::models::agent::{
+    authorize, AgentGrant, AgentInfo, AgentRequest, AgentResponse, AgentStatus, AGENT_SOCKET_DIR,
+    AGENT_SOCKET_FILE,
+};
+use crate::models::consent::ConsentOperation;
+use crate::models::item::ItemPayload;
+use crate::models::policy::PolicyGate;
+use crate::models::rate_limit::SensitiveOp;
+use chrono::Utc;
+use rand::RngCore;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+use zeroize::Zeroizing;
+
+struct RunningAgent {
+    socket_path: PathBuf,
+    grants: Vec<AgentGrant>,
+    shutdown: Arc<AtomicBool>,
+}
+
+/// The running secrets agent, if any. It lives only as long as the unlocked
+/// session: locking the vault stops it and its token dies with it.
+#[derive(Default)]
+pub struct AgentHandle(Mutex<Option<RunningAgent>>);
+
+/// Answer one request with the unlocked item store. Everything that reaches
+/// the store has already passed [`authorize`], the rate limit and, for a
+/// revealed secret, the plaintext-export rules.
+fn answer(app: &AppHandle, request: &AgentRequest) -> std::result::Result<String, String> {
+    let items = app.state::<ItemStore>();
+    let mut store = items.0.lock().unwrap();
+    let item = store
+        .iter_mut()
+        .find(|i| i.id == request.item_id() && i.trashed_at.is_none())
+        .ok_or("item not found; is the vault unlocked?")?;
+    if item.is_expired(Utc::now()) {
+        return Err("item has expired".to_string());
+    }
+    let value = match (request, &item.payload) {
+        (AgentRequest::GetSecret { .. }, ItemPayload::ApiToken(t)) => t.token.clone(),
+        (AgentRequest::Sign { data_hex, .. }, ItemPayload::SshKey(k)) => {
+            let data = hex::decode(data_hex).map_err(|e| e.to_string())?;
+            hex::encode(
+                ssh::sign_challenge(&k.private_key_openssh, &data).map_err(|e| e.to_string())?,
+            )
+        }
+        _ => return Err("operation is not supported for this item kind".to_string()),
+    };
+    item.usage.touch(Utc::now());
+    Ok(value)
+}
+
+fn handle_line(app: &AppHandle, line: &str, token: &str, grants: &[AgentGrant]) -> AgentResponse {
+    let Ok(request) = serde_json::from_str::<AgentRequest>(line) else {
+        return AgentResponse::error("malformed request");
+    };
+    if let Err(reason) = authorize(&request, token, grants) {
+        tracing::info!(
+            target: "audit",
+            op = request.op(),
+            item_id = request.item_id(),
+            reason,
+            "agent request denied"
+        );
+        return AgentResponse::error(reason);
+    }
+    if let Err(e) = enforce(
+        &app.state::<VaultMutex>(),
+        &app.state::<RateLimiter>(),
+        SensitiveOp::Decrypt,
+    ) {
+        return AgentResponse::error(e.to_string());
+    }
+    // A revealed secret leaves the vault in plaintext; the policy and the
+    // item's flag are checked per request, as either may change while the
+    // agent runs.
+    if let AgentRequest::GetSecret { item_id, .. } = &request {
+        let vault = app.state::<VaultMutex>();
+        if let Err(e) = enforce_policy(&vault, PolicyGate::PlaintextExport).and_then(|()| {
+            enforce_item_export(&app.state::<ItemStore>(), std::slice::from_ref(item_id))
+        }) {
+            return AgentResponse::error(e.to_string());
+        }
+    }
+    match answer(app, &request) {
+        Ok(value) => {
+            tracing::info!(
+                target: "audit",
+                op = request.op(),
+                item_id = request.item_id(),
+                "agent request served"
+            );
+            AgentResponse::value(value)
+        }
+        Err(e) => {
+            tracing::info!(
+                target: "audit",
+                op = request.op(),
+                item_id = request.item_id(),
+                error = %e,
+                "agent request failed"
+            );
+            AgentResponse::error(e)
+        }
+    }
+}
+
+/// Bind the socket inside an owner-only directory and serve one request per
+/// connection until
+/// `shutdown` is set. Connections are handled one at a time, so a stuck
+/// client can hold the agent for at most the read timeout.
+#[cfg(unix)]
+fn spawn_listener(
+    app: AppHandle,
+    path: &std::path::Path,
+    token: Zeroizing<String>,
+    grants: Vec<AgentGrant>,
+    shutdown: Arc<AtomicBool>,
+) -> Result<()> {
+    use crate::models::agent::MAX_AGENT_REQUEST_BYTES;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+    use std::os::unix::net::UnixListener;
+
+    // `bind` creates the socket with the process umask, so it is only safe
+    // to bind where nobody else can reach it: a `0700` directory that is
+    // not a symlink.
+    let storage = |e: std::io::Error| VaultError::Storage(e.to_string());
+    let dir = path
+        .parent()
+        .ok_or_else(|| VaultError::Storage("agent socket has no directory".to_string()))?;
+    if let Err(e) = std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        if e.kind() != std::io::ErrorKind::AlreadyExists {
+            return Err(storage(e));
+        }
+    }
+    if !std::fs::symlink_metadata(dir).map_err(storage)?.is_dir() {
+        return Err(VaultError::Storage(format!(
+            "{} is not a directory",
+            dir.display()
+        )));
+    }
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)).map_err(storage)?;
+
+    // A socket left behind by a crash would make `bind` fail.
+    if std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+        let _ = std::fs::remove_file(path);
+    }
+    let listener = UnixListener::bind(path).map_err(storage)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)).map_err(storage)?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            if shutdown.load(Ordering::SeqCst) {
+                break;
+            }
+            let Ok(mut stream) = stream else { continue };
+            let _ = stream.set_read_timeout(Some(std::time::Duration::from_secs(5)));
+            let mut line = Zeroizing::new(String::new());
+            let read = BufReader::new(&stream)
+                .take(MAX_AGENT_REQUEST_BYTES as u64)
+                .read_line(&mut line);
+            if read.is_err() {
+                continue;
+            }
+            let response = handle_line(&app, line.trim_end(), &token, &grants);
+            let Ok(mut reply) = serde_json::to_string(&response).map(Zeroizing::new) else {
+                continue;
+            };
+            reply.push('\n');
+            let _ = stream.write_all(reply.as_bytes());
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn spawn_listener(
+    _app: AppHandle,
+    _path: &std::path::Path,
+    _token: Zeroizing<String>,
+    _grants: Vec<AgentGrant>,
+    _shutdown: Arc<AtomicBool>,
+) -> Result<()> {
+    Err(VaultError::Storage(
+        "the secrets agent needs Unix-domain sockets".to_string(),
+    ))
+}
+
+/// Stop the agent if it is running and remove its socket.
+pub(crate) fn stop_agent(agent: &AgentHandle) {
+    let Some(running) = agent.0.lock().unwrap().take() else {
+        return;
+    };
+    running.shutdown.store(true, Ordering::SeqCst);
+    // Wake the blocking accept so the listener thread sees the flag.
+    #[cfg(unix)]
+    let _ = std::os::unix::net::UnixStream::connect(&running.socket_path);
+    let _ = std::fs::remove_file(&running.socket_path);
+    tracing::info!(target: "audit", "secrets agent stopped");
+}
+
+/// Start the local secrets agent on `agent/agent.sock` in the data
+/// directory. Tools connect, write one JSON request line carrying the
+/// returned token and read one JSON reply line. Only the items in `grants`
+/// are reachable, and only for the operations each grant allows. Starting
+/// again replaces the token and allow list. Requires the vault `password`
+/// and a `consent_token` from `request_consent` for the same grants.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn agent_start(
+    app: AppHandle,
+    password: String,
+    grants: Vec<AgentGrant>,
+    consent_token: String,
+    agent: State<'_, AgentHandle>,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    items: State<'_, ItemStore>,
+) -> Result<AgentInfo> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    verify_password(&app, &vault, &password)?;
+    {
+        let store = items.0.lock().unwrap();
+        if let Some(g) = grants.iter().find(|g| {
+            !store
+                .iter()
+                .any(|i| i.id == g.item_id && i.trashed_at.is_none())
+        }) {
+            return Err(VaultError::KeyNotFound(g.item_id.clone()));
+        }
+    }
+    require_consent(
+        &app,
+        &consent_token,
+        &ConsentOperation::AgentStart {
+            grants: grants.clone(),
+        },
+    )?;
+    stop_agent(&agent);
+
+    let mut raw = Zeroizing::new([0u8; 32]);
+    rand::rngs::OsRng.fill_bytes(raw.as_mut());
+    let token = Zeroizing::new(hex::encode(raw.as_ref()));
+    let socket_path = local_data_dir(&app)?
+        .join(AGENT_SOCKET_DIR)
+        .join(AGENT_SOCKET_FILE);
+    let shutdown = Arc::new(AtomicBool::new(false));
+    spawn_listener(
+        app.clone(),
+        &socket_path,
+        token.clone(),
+        grants.clone(),
+        shutdown.clone(),
+    )?;
+
+    tracing::info!(
+        target: "audit",
+        items = grants.len(),
+        "secrets agent started"
+    );
+    *agent.0.lock().unwrap() = Some(RunningAgent {
+        socket_path: socket_path.clone(),
+        grants: grants.clone(),
+        shutdown,
+    });
+    Ok(AgentInfo {
+        socket_path: socket_path.display().to_string(),
+        token: token.to_string(),
+        grants,
+    })
+}
+
+#[tauri::command]
+pub fn agent_stop(agent: State<'_, AgentHandle>) -> Result<()> {
+    stop_agent(&agent);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn agent_status(agent: State<'_, AgentHandle>) -> Result<AgentStatus> {
+    let running = agent.0.lock().unwrap();
+    Ok(match running.as_ref() {
+        Some(r) => AgentStatus {
+            running: true,
+            socket_path: Some(r.socket_path.display().to_string()),
+            grants: r.grants.clone(),
+        },
+        None => AgentStatus {
+            running: false,
+            socket_path: None,
+            grants: Vec::new(),
+        },
+    })
+}
@@ -1,7 +1,11 @@
 use crate::commands::keys::{secret_hex_for, KeyStore};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::notifications::note_decrypt;
+use crate::commands::vault::VaultMutex;
 use crate::crypto::mldsa87;
 use crate::error::{Result, VaultError};
 use crate::models::airgap::{AirGapEnvelope, TransferType};
+use crate::models::rate_limit::SensitiveOp;
 use chrono::Utc;
 use ml_dsa::{KeyExport, Keypair};
 use rand::rngs::OsRng;
@@ -9,7 +13,7 @@ use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::sync::Mutex;
-use tauri::State;
+use tauri::{AppHandle, State};
 
 /// Current air-gap envelope format version. v2 binds the nonce, timestamp and
 /// transfer type into the signature (v1 signed only the payload, which allowed
@@ -199,13 +203,20 @@ pub fn generate_qr(request: QrRequest) -> Result<String> {
 /// key server-side from the in-memory keystore. The secret never crosses IPC.
 #[tauri::command]
 pub fn generate_qr_with_key(
+    app: AppHandle,
     key_id: String,
     payload_hex: String,
     transfer_type: String,
     keystore: State<'_, KeyStore>,
+    vault: State<'_, VaultMutex>,
+    limiter: State<'_, RateLimiter>,
 ) -> Result<String> {
+    enforce(&vault, &limiter, SensitiveOp::Decrypt)?;
+    let decrypt = note_decrypt(&app, &vault, &key_id);
     let secret_hex = secret_hex_for(&keystore, &key_id)?;
-    build_envelope(&secret_hex, &payload_hex, &transfer_type)
+    let envelope = build_envelope(&secret_hex, &payload_hex, &transfer_type)?;
+    decrypt.finish(&app)?;
+    Ok(envelope)
 }
 
 #[tauri::command]
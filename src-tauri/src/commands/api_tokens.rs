@@ -0,0 +1,224 @@
+use crate::commands::consent::require_consent;
+use crate::commands::items::{push_item, ItemStore};
+use crate::commands::keys::{atomic_write, SessionKey};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::notifications::note_decrypt;
+use crate::commands::policy::{enforce_item_export, enforce_policy, export_watermark};
+use crate::commands::vault::{verify_password, VaultMutex};
+use crate::error::{Result, VaultError};
+use crate::models::consent::ConsentOperation;
+use crate::models::env_file::{render_env, validate_env_var, EnvFileFormat};
+use crate::models::item::{ApiTokenItem, ItemPayload, VaultItem, VaultItemPublic};
+use crate::models::policy::PolicyGate;
+use crate::models::rate_limit::SensitiveOp;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
+
+/// A new API token as entered in the UI.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiTokenRequest {
+    pub provider: String,
+    pub token: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    pub environment: Option<String>,
+    pub env_var: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Drop for ApiTokenRequest {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.token);
+    }
+}
+
+/// Store an API key or token. `expires_at` feeds the same expiry watch as
+/// every other item.
+#[tauri::command]
+pub fn api_token_create(
+    app: AppHandle,
+    label: Option<String>,
+    request: ApiTokenRequest,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<VaultItemPublic> {
+    validate_env_var(&request.env_var).map_err(VaultError::InvalidMetadata)?;
+    if request.provider.trim().is_empty() || request.token.is_empty() {
+        return Err(VaultError::InvalidMetadata(
+            "provider and token are required".to_string(),
+        ));
+    }
+    let mut item = VaultItem::new(
+        label,
+        ItemPayload::ApiToken(ApiTokenItem {
+            provider: request.provider.trim().to_string(),
+            token: request.token.clone(),
+            scopes: request.scopes.clone(),
+            environment: request.environment.clone(),
+            env_var: request.env_var.clone(),
+        }),
+    );
+    item.expires_at = request.expires_at;
+    let public = item.to_public();
+    push_item(&app, &vault, &items, &session, item)?;
+    Ok(public)
+}
+
+/// Stored API tokens, optionally only those for one `environment`.
+#[tauri::command]
+pub fn api_token_list(
+    environment: Option<String>,
+    items: State<'_, ItemStore>,
+) -> Result<Vec<VaultItemPublic>> {
+    let store = items.0.lock().unwrap();
+    Ok(store
+        .iter()
+        .filter(|i| {
+            i.trashed_at.is_none()
+                && matches!(&i.payload, ItemPayload::ApiToken(t)
+                    if environment.is_none() || t.environment == environment)
+        })
+        .map(|i| i.to_public())
+        .collect())
+}
+
+/// Reveal one token, e.g. to paste it into a provider's dashboard. Gated
+/// like every other plaintext export. A bare token has no room for a
+/// watermark, so it is only recorded in the audit entry. `consent_token`
+/// comes from `request_consent`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn api_token_reveal(
+    app: AppHandle,
+    item_id: String,
+    password: String,
+    consent_token: String,
+    state: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<String> {
+    enforce(&state, &limiter, SensitiveOp::Export)?;
+    enforce_policy(&state, PolicyGate::PlaintextExport)?;
+    enforce_item_export(&items, std::slice::from_ref(&item_id))?;
+    verify_password(&app, &state, &password)?;
+    require_consent(
+        &app,
+        &consent_token,
+        &ConsentOperation::ApiTokenReveal {
+            item_id: item_id.clone(),
+        },
+    )?;
+    let mark = export_watermark(&app)?;
+    let decrypt = note_decrypt(&app, &state, &item_id);
+    let mut store = items.0.lock().unwrap();
+    match store
+        .iter_mut()
+        .find(|i| i.id == item_id && i.trashed_at.is_none())
+    {
+        Some(VaultItem {
+            payload: ItemPayload::ApiToken(t),
+            usage,
+            ..
+        }) => {
+            usage.touch(Utc::now());
+            tracing::info!(
+                target: "audit",
+                item_id = %item_id,
+                provider = %t.provider,
+                profile = %mark.profile,
+                instance_id = %mark.instance_id,
+                "api token revealed"
+            );
+            decrypt.finish(&app)?;
+            Ok(t.token.clone())
+        }
+        _ => Err(VaultError::KeyNotFound(item_id)),
+    }
+}
+
+/// Write the tokens in `item_ids` to `path` as a `.env` or JSON secrets file
+/// for a local project. Tokens are read from the store only while the file
+/// is rendered, the file is created owner-only (`0600`), and every token
+/// written gets its own audit entry. Expired tokens are refused so stale
+/// credentials do not end up in a project. `consent_token` comes from
+/// `request_consent`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn render_env_file(
+    app: AppHandle,
+    item_ids: Vec<String>,
+    path: String,
+    format: EnvFileFormat,
+    password: String,
+    consent_token: String,
+    state: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<usize> {
+    enforce(&state, &limiter, SensitiveOp::Export)?;
+    enforce_policy(&state, PolicyGate::PlaintextExport)?;
+    enforce_item_export(&items, &item_ids)?;
+    let target = Path::new(&path);
+    if !target.is_absolute() {
+        return Err(VaultError::InvalidMetadata(
+            "secrets file path must be absolute".to_string(),
+        ));
+    }
+    verify_password(&app, &state, &password)?;
+    require_consent(
+        &app,
+        &consent_token,
+        &ConsentOperation::EnvFileRender {
+            item_ids: item_ids.clone(),
+            path: path.clone(),
+        },
+    )?;
+    let mark = export_watermark(&app)?;
+
+    let now = Utc::now();
+    let mut store = items.0.lock().unwrap();
+    let rendered: Zeroizing<String> = {
+        let mut entries = Vec::with_capacity(item_ids.len());
+        for id in &item_ids {
+            match store.iter().find(|i| &i.id == id && i.trashed_at.is_none()) {
+                Some(
+                    item @ VaultItem {
+                        payload: ItemPayload::ApiToken(t),
+                        ..
+                    },
+                ) => {
+                    if item.is_expired(now) {
+                        return Err(VaultError::InvalidMetadata(format!(
+                            "API token {id} has expired"
+                        )));
+                    }
+                    entries.push((t.env_var.as_str(), t.token.as_str()));
+                }
+                _ => return Err(VaultError::KeyNotFound(id.clone())),
+            }
+        }
+        render_env(&entries, format, Some(&mark)).map_err(VaultError::InvalidMetadata)?
+    };
+    atomic_write(target, rendered.as_bytes())?;
+
+    for item in store.iter_mut().filter(|i| item_ids.contains(&i.id)) {
+        item.usage.touch(now);
+        if let ItemPayload::ApiToken(t) = &item.payload {
+            tracing::info!(
+                target: "audit",
+                item_id = %item.id,
+                provider = %t.provider,
+                env_var = %t.env_var,
+                path = %path,
+                profile = %mark.profile,
+                instance_id = %mark.instance_id,
+                "api token rendered to secrets file"
+            );
+        }
+    }
+    Ok(item_ids.len())
+}
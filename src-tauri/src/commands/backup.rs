@@ -0,0 +1,831 @@
+use crate::commands::capsule::CAPSULES_FILE;
+use crate::commands::ceremony::CEREMONY_FILE;
+use crate::commands::contacts::CONTACTS_FILE;
+use crate::commands::custody::CUSTODY_FILE;
+use crate::commands::drive_seal::{ensure_drive_trusted, reseal_after_write};
+use crate::commands::emergency::EMERGENCY_FILE;
+use crate::commands::frost::FROST_FILE;
+use crate::commands::hooks::{fire_hooks, HOOKS_FILE};
+use crate::commands::instance::unlocked_instance_key;
+use crate::commands::keys::{
+    atomic_write, data_dir, keys_file_path, session_key, MasterSeed, SessionKey,
+};
+use crate::commands::musig2::MUSIG2_FILE;
+use crate::commands::notes::{notes_for_drive, NOTES_FILE};
+use crate::commands::notifications::notify;
+use crate::commands::pairing::PAIRING_FILE;
+use crate::commands::portfolio::BALANCES_FILE;
+use crate::commands::price::PRICES_FILE;
+use crate::commands::public_bundle::WATCH_ONLY_FILE;
+use crate::commands::remote::REMOTE_FILE;
+use crate::commands::signing_requests::SIGNING_REQUESTS_FILE;
+use crate::commands::spending::SPENDING_FILE;
+use crate::commands::treasury::TREASURY_FILE;
+use crate::commands::tx_review::ABI_REGISTRY_FILE;
+use crate::commands::vault::{
+    derive_vault_enc_key, load_vault_if_needed, persist_vault, verify_enc_key, verify_password,
+    VaultMutex, VAULT_FILE,
+};
+use crate::crypto::{attestation, ceremony, emergency, kdf};
+use crate::drive::backup::{self, ProgressReporter, VaultFile};
+use crate::drive::{self, scrub, DriveBackend, DriveError};
+use crate::error::{Result, VaultError};
+use crate::i18n::tr;
+use crate::models::backup::{
+    BackupEstimate, BackupInspection, BackupListing, BackupManifest, BackupMode, BackupProgress,
+    BackupRecord, BackupVerification, FileEncryption, InspectedVault, ProgressStage, ScrubReport,
+    ScrubStatus, SignatureCheck, SignedRecordKind,
+};
+use crate::models::ceremony::Ceremony;
+use crate::models::drive::{DriveDetails, DriveInfo};
+use crate::models::emergency::EmergencyGrant;
+use crate::models::forecast::{self, DriveForecast};
+use crate::models::hook::HookEvent;
+use crate::models::notification::SecurityEvent;
+use crate::models::vault::{KdfProfile, VaultState};
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use tauri::{AppHandle, Emitter, State};
+use zeroize::Zeroizing;
+
+pub const BACKUP_PROGRESS_EVENT: &str = "backup_progress";
+pub const RESTORE_PROGRESS_EVENT: &str = "restore_progress";
+pub const SCRUB_PROGRESS_EVENT: &str = "scrub_progress";
+/// Prefix of the data-directory folder a restore copies the files it
+/// replaces into, followed by the restore's UTC time.
+pub const PRE_RESTORE_DIR_PREFIX: &str = "pre-restore-";
+
+/// The drive backend for this platform, held in managed state so tests and
+/// alternative front-ends can swap it.
+pub struct Drives(pub Box<dyn DriveBackend>);
+
+impl Default for Drives {
+    fn default() -> Self {
+        Self(drive::default_backend())
+    }
+}
+
+/// The optional metadata stores backed up alongside `vault.json` and the
+/// current keystore and item store. `INSTANCE_FILE` and `SYNC_FILE` are left
+/// out on purpose: both describe this installation, not the vault.
+pub const METADATA_FILES: &[&str] = &[
+    EMERGENCY_FILE,
+    CEREMONY_FILE,
+    TREASURY_FILE,
+    REMOTE_FILE,
+    NOTES_FILE,
+    CUSTODY_FILE,
+    CONTACTS_FILE,
+    CAPSULES_FILE,
+    PAIRING_FILE,
+    FROST_FILE,
+    MUSIG2_FILE,
+    SIGNING_REQUESTS_FILE,
+    SPENDING_FILE,
+    PRICES_FILE,
+    BALANCES_FILE,
+    ABI_REGISTRY_FILE,
+    WATCH_ONLY_FILE,
+    HOOKS_FILE,
+];
+
+/// Every data-directory file that makes up the vault. The metadata stores are
+/// optional; `vault.json` and the current keystore are not.
+fn vault_file_names(vault: &VaultState) -> Vec<&str> {
+    let mut names = vec![
+        VAULT_FILE,
+        vault.keys_file.as_str(),
+        vault.items_file.as_str(),
+    ];
+    names.extend_from_slice(METADATA_FILES);
+    names
+}
+
+/// Read the vault's files from the data directory. Call with the vault mutex
+/// held so no command rewrites them mid-copy.
+pub(crate) fn collect_vault_files(app: &AppHandle, vault: &VaultState) -> Result<Vec<VaultFile>> {
+    let mut files = Vec::new();
+    for name in vault_file_names(vault) {
+        let path = keys_file_path(app, name)?;
+        if !path.exists() {
+            continue;
+        }
+        let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+        files.push(VaultFile {
+            name: name.to_string(),
+            data,
+        });
+    }
+    if !files.iter().any(|f| f.name == vault.keys_file) {
+        return Err(VaultError::Storage(format!(
+            "keystore {} is missing",
+            vault.keys_file
+        )));
+    }
+    Ok(files)
+}
+
+/// Run `f` with a progress channel whose updates are emitted to the UI as
+/// `event` while it runs, from a forwarding thread so slow webview delivery
+/// never stalls the drive I/O.
+pub(crate) fn with_progress<T>(
+    app: &AppHandle,
+    event: &'static str,
+    f: impl FnOnce(&Sender<BackupProgress>) -> T,
+) -> T {
+    let (tx, rx) = mpsc::channel::<BackupProgress>();
+    let emitter = app.clone();
+    let forwarder = std::thread::spawn(move || {
+        for update in rx {
+            let _ = emitter.emit(event, update);
+        }
+    });
+    let out = f(&tx);
+    drop(tx);
+    let _ = forwarder.join();
+    out
+}
+
+#[tauri::command]
+pub fn list_drives(drives: State<'_, Drives>) -> Result<Vec<DriveInfo>> {
+    Ok(drives.0.list_drives()?)
+}
+
+/// A drive with every note attached to it or to its backups. Note text is
+/// only filled in while the vault is unlocked.
+#[tauri::command]
+pub fn get_drive_details(
+    app: AppHandle,
+    drive_id: String,
+    session: State<'_, SessionKey>,
+    drives: State<'_, Drives>,
+) -> Result<DriveDetails> {
+    let drive = drives.0.drive(&drive_id)?;
+    let key = session_key(&session).ok();
+    let notes = notes_for_drive(&app, drives.0.as_ref(), key.as_deref(), &drive_id)?;
+    Ok(DriveDetails { drive, notes })
+}
+
+/// How much space a backup of the current vault would take on `drive_id`,
+/// and whether it fits, without writing anything.
+#[tauri::command]
+pub fn estimate_backup_size(
+    app: AppHandle,
+    drive_id: String,
+    state: State<'_, VaultMutex>,
+    drives: State<'_, Drives>,
+) -> Result<BackupEstimate> {
+    let vault = state.0.lock().unwrap();
+    if !vault.initialized {
+        return Err(VaultError::NotInitialized);
+    }
+    let files = collect_vault_files(&app, &vault)?;
+    let drive = drives.0.drive(&drive_id)?;
+    let mut estimate = backup::estimate_backup(drives.0.as_ref(), &drive, &files)?;
+    estimate.capacity_warning = drive_forecast(drives.0.as_ref(), &drive)
+        .ok()
+        .and_then(|f| f.warning);
+    Ok(estimate)
+}
+
+fn drive_forecast(backend: &dyn DriveBackend, drive: &DriveInfo) -> Result<DriveForecast> {
+    let growth = backup::backup_growth(backend, &drive.id)?;
+    Ok(forecast::forecast_drive(drive, &growth, Utc::now()))
+}
+
+/// When `drive_id` is expected to run out of space, from how much each
+/// backup on it added.
+#[tauri::command]
+pub fn get_drive_forecast(drive_id: String, drives: State<'_, Drives>) -> Result<DriveForecast> {
+    let drive = drives.0.drive(&drive_id)?;
+    drive_forecast(drives.0.as_ref(), &drive)
+}
+
+/// Warn after a backup that leaves `drive_id` close to full, so a new drive
+/// can be rotated in before a backup fails. Best effort.
+fn warn_if_filling_up(
+    app: &AppHandle,
+    vault: &VaultState,
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+) {
+    let forecast = backend
+        .drive(drive_id)
+        .map_err(VaultError::from)
+        .and_then(|drive| drive_forecast(backend, &drive));
+    match forecast {
+        Ok(DriveForecast {
+            warning: Some(warning),
+            ..
+        }) => notify(
+            app,
+            &vault.notifications,
+            vault.locale(),
+            SecurityEvent::DriveNearlyFull,
+            &tr(
+                vault.locale(),
+                "drive-nearly-full-body",
+                &[
+                    ("drive", drive_id.into()),
+                    ("warning", warning.describe(vault.locale()).into()),
+                ],
+            ),
+        ),
+        Ok(_) => {}
+        Err(e) => tracing::warn!(drive = %drive_id, "could not forecast drive space: {e}"),
+    }
+}
+
+/// Copy the vault's (already encrypted) files to a backup on `drive_id`,
+/// emitting `backup_progress` events as it goes.
+#[tauri::command(async)]
+pub fn create_backup(
+    app: AppHandle,
+    drive_id: String,
+    state: State<'_, VaultMutex>,
+    drives: State<'_, Drives>,
+    master_seed: State<'_, MasterSeed>,
+    session: State<'_, SessionKey>,
+) -> Result<BackupManifest> {
+    back_up(
+        &app,
+        &drive_id,
+        &state,
+        &drives,
+        &master_seed,
+        &session,
+        None,
+    )
+}
+
+/// Back up to a drive whose filesystem is not encrypted (exFAT, NTFS),
+/// without formatting it: every file is encrypted on its own under a key
+/// derived from `password`, and signed. Needs the vault unlocked; restoring
+/// needs the same password.
+#[tauri::command(async)]
+pub fn create_file_encrypted_backup(
+    app: AppHandle,
+    drive_id: String,
+    password: String,
+    state: State<'_, VaultMutex>,
+    drives: State<'_, Drives>,
+    master_seed: State<'_, MasterSeed>,
+    session: State<'_, SessionKey>,
+) -> Result<BackupManifest> {
+    if session.0.lock().unwrap().is_none() || master_seed.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    verify_password(&app, &state, &password)?;
+    let encryption = FileEncryption {
+        salt_hex: hex::encode(kdf::generate_salt()),
+        kdf: KdfProfile::current(),
+    };
+    let key = backup::file_key(&password, &encryption)?;
+    back_up(
+        &app,
+        &drive_id,
+        &state,
+        &drives,
+        &master_seed,
+        &session,
+        Some((&*key, encryption)),
+    )
+}
+
+/// Write a backup, file-encrypted under `file_key` when given, then record
+/// it and notify. Shared by both backup commands.
+fn back_up(
+    app: &AppHandle,
+    drive_id: &str,
+    state: &State<'_, VaultMutex>,
+    drives: &State<'_, Drives>,
+    master_seed: &State<'_, MasterSeed>,
+    session: &State<'_, SessionKey>,
+    file_key: Option<(&[u8; 32], FileEncryption)>,
+) -> Result<BackupManifest> {
+    let mut vault = state.0.lock().unwrap();
+    if !vault.initialized {
+        return Err(VaultError::NotInitialized);
+    }
+    let files = collect_vault_files(app, &vault)?;
+    // Entries are signed only while unlocked; the identity needs the seed.
+    let identity = master_seed
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|seed| attestation::vault_identity(seed));
+    let instance = unlocked_instance_key(app, master_seed).unwrap_or_else(|e| {
+        tracing::warn!("backup will not carry an instance signature: {e}");
+        None
+    });
+    let identity = identity.as_ref().map(|(pk, sk)| (pk, sk));
+    let manifest = match with_progress(app, BACKUP_PROGRESS_EVENT, |tx| match file_key {
+        Some((key, encryption)) => backup::create_file_encrypted_backup(
+            drives.0.as_ref(),
+            drive_id,
+            &files,
+            Utc::now(),
+            key,
+            encryption,
+            identity,
+            instance.as_ref(),
+            Some(tx),
+        ),
+        None => backup::create_signed_backup(
+            drives.0.as_ref(),
+            drive_id,
+            &files,
+            Utc::now(),
+            identity,
+            instance.as_ref(),
+            Some(tx),
+        ),
+    }) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            notify(
+                app,
+                &vault.notifications,
+                vault.locale(),
+                SecurityEvent::BackupFailed,
+                &tr(
+                    vault.locale(),
+                    "backup-failed-body",
+                    &[("drive", drive_id.into()), ("error", e.to_string().into())],
+                ),
+            );
+            return Err(e.into());
+        }
+    };
+    let mut next = vault.clone();
+    next.record_backup(BackupRecord {
+        drive_id: drive_id.to_string(),
+        backup_id: manifest.id.clone(),
+        created_at: manifest.created_at,
+        verified_at: None,
+    });
+    reseal_after_write(drives.0.as_ref(), &mut next, drive_id);
+    persist_vault(app, &next)?;
+    *vault = next;
+    if let Ok(key) = session_key(session) {
+        if let Err(e) = backup::add_to_index(drives.0.as_ref(), drive_id, &key, &manifest) {
+            tracing::warn!(
+                "could not add backup {} to the drive index: {e}",
+                manifest.id
+            );
+        }
+    }
+    notify(
+        app,
+        &vault.notifications,
+        vault.locale(),
+        SecurityEvent::BackupCompleted,
+        &tr(
+            vault.locale(),
+            "backup-completed-body",
+            &[
+                ("backup", manifest.id.as_str().into()),
+                ("drive", drive_id.into()),
+            ],
+        ),
+    );
+    fire_hooks(
+        app,
+        &vault,
+        HookEvent::BackupCompleted,
+        &manifest.id,
+        &format!("Backup {} written to drive {drive_id}", manifest.id),
+    );
+    warn_if_filling_up(app, &vault, drives.0.as_ref(), drive_id);
+    Ok(manifest)
+}
+
+/// The backups on `drive_id`, each with its notes. Note text is only filled
+/// in while the vault is unlocked. Unlocked, the listing comes from the
+/// drive's encrypted index; locked, every manifest is read.
+#[tauri::command]
+pub fn list_backups(
+    app: AppHandle,
+    drive_id: String,
+    session: State<'_, SessionKey>,
+    drives: State<'_, Drives>,
+) -> Result<Vec<BackupListing>> {
+    let key = session_key(&session).ok();
+    let manifests = match key.as_deref() {
+        Some(key) => backup::list_backups_indexed(drives.0.as_ref(), &drive_id, key)?,
+        None => backup::list_backups(drives.0.as_ref(), &drive_id)?,
+    };
+    let notes = notes_for_drive(&app, drives.0.as_ref(), key.as_deref(), &drive_id)?;
+    Ok(manifests
+        .into_iter()
+        .map(|manifest| {
+            let notes = notes
+                .iter()
+                .filter(|n| n.target.backup_id() == Some(manifest.id.as_str()))
+                .cloned()
+                .collect();
+            BackupListing { manifest, notes }
+        })
+        .collect())
+}
+
+/// Re-read a backup and check it against its manifest. For backups this
+/// vault wrote, the result is remembered for the security policy's
+/// verified-backup requirement.
+#[tauri::command]
+pub fn verify_backup(
+    app: AppHandle,
+    drive_id: String,
+    backup_id: String,
+    state: State<'_, VaultMutex>,
+    drives: State<'_, Drives>,
+) -> Result<BackupVerification> {
+    let verification = backup::verify_backup(drives.0.as_ref(), &drive_id, &backup_id)?;
+    let mut vault = state.0.lock().unwrap();
+    let mut next = vault.clone();
+    if next.record_backup_verification(&drive_id, &backup_id, verification.ok, Utc::now()) {
+        persist_vault(&app, &next)?;
+        *vault = next;
+    }
+    Ok(verification)
+}
+
+/// Parse the plaintext metadata file `name`, if present, noting a parse
+/// failure in `problems`.
+fn parse_metadata<T: serde::de::DeserializeOwned>(
+    files: &[VaultFile],
+    name: &str,
+    problems: &mut Vec<String>,
+) -> Option<T> {
+    let file = files.iter().find(|f| f.name == name)?;
+    serde_json::from_slice(&file.data)
+        .map_err(|e| problems.push(format!("{name}: {e}")))
+        .ok()
+}
+
+fn signature_check<E: std::fmt::Display>(
+    kind: SignedRecordKind,
+    record_id: &str,
+    signer: &str,
+    result: std::result::Result<(), E>,
+) -> SignatureCheck {
+    SignatureCheck {
+        kind,
+        record_id: record_id.to_string(),
+        signer: signer.to_string(),
+        valid: result.is_ok(),
+        error: result.err().map(|e| e.to_string()),
+    }
+}
+
+/// Build the read-only view of a backup from its manifest check and the
+/// files that matched it. Only plaintext metadata is parsed; the keystore and
+/// item store are never decrypted. Pure (no I/O) to keep it unit-testable.
+pub fn inspect_backup_files(
+    manifest: BackupManifest,
+    verification: BackupVerification,
+    files: &[VaultFile],
+) -> BackupInspection {
+    let mut problems = Vec::new();
+    let files = if manifest.mode == BackupMode::FileEncrypted {
+        problems.push("the backup is file-encrypted: its metadata is not readable".to_string());
+        &[][..]
+    } else {
+        files
+    };
+    let vault: Option<VaultState> = parse_metadata(files, VAULT_FILE, &mut problems);
+    let ceremonies: Vec<Ceremony> =
+        parse_metadata(files, CEREMONY_FILE, &mut problems).unwrap_or_default();
+    let grants: Vec<EmergencyGrant> =
+        parse_metadata(files, EMERGENCY_FILE, &mut problems).unwrap_or_default();
+
+    let intact = |name: &str| files.iter().any(|f| f.name == name);
+    let vault = vault.map(|v| InspectedVault {
+        initialized: v.initialized,
+        kdf: v.kdf_profile(),
+        yubikey_enabled: v.yubikey_enabled,
+        has_master_seed: !v.master_seed_enc_hex.is_empty(),
+        air_gap_mode: v.air_gap_mode,
+        address_gap_limit: v.address_gap_limit,
+        keystore_intact: intact(&v.keys_file),
+        items_intact: intact(&v.items_file),
+        keysets: v.keysets,
+    });
+
+    let mut signatures = Vec::new();
+    for c in &ceremonies {
+        let Some(genesis) = &c.manifest else {
+            continue;
+        };
+        for a in &c.attestations {
+            signatures.push(signature_check(
+                SignedRecordKind::CeremonyAttestation,
+                &c.id,
+                &a.operator_id,
+                ceremony::verify_attestation(genesis, a),
+            ));
+        }
+    }
+    for g in &grants {
+        signatures.push(signature_check(
+            SignedRecordKind::EmergencyGrant,
+            &g.id,
+            &g.owner_public_hex,
+            emergency::verify_grant(g),
+        ));
+    }
+
+    BackupInspection {
+        manifest,
+        verification,
+        vault,
+        signatures,
+        problems,
+    }
+}
+
+/// Inspect a backup on `drive_id` without importing it: works for backups
+/// from any vault instance, needs no password and leaves the local vault
+/// untouched. Damaged files are reported rather than failing the call.
+#[tauri::command(async)]
+pub fn inspect_backup(
+    drive_id: String,
+    backup_id: String,
+    drives: State<'_, Drives>,
+) -> Result<BackupInspection> {
+    let (manifest, verification, files) =
+        backup::read_backup_checked(drives.0.as_ref(), &drive_id, &backup_id)?;
+    Ok(inspect_backup_files(manifest, verification, &files))
+}
+
+/// Re-verify everything stored on `drive_id`, repairing damaged files from
+/// other attached drives where possible. Reads the whole backup area, so it
+/// runs off the main thread and reports `scrub_progress` events.
+#[tauri::command(async)]
+pub fn scrub_drive(
+    app: AppHandle,
+    drive_id: String,
+    state: State<'_, VaultMutex>,
+    drives: State<'_, Drives>,
+) -> Result<ScrubReport> {
+    let report = with_progress(&app, SCRUB_PROGRESS_EVENT, |tx| {
+        Ok(scrub::scrub_drive(drives.0.as_ref(), &drive_id, Some(tx))?)
+    })?;
+    if report
+        .issues
+        .iter()
+        .any(|i| i.status == ScrubStatus::Repaired)
+    {
+        let mut vault = state.0.lock().unwrap();
+        let mut next = vault.clone();
+        reseal_after_write(drives.0.as_ref(), &mut next, &drive_id);
+        persist_vault(&app, &next)?;
+        *vault = next;
+    }
+    Ok(report)
+}
+
+/// Replace the local vault with a verified backup. Only allowed while the
+/// vault is locked, and only with the `password` the backup's vault had,
+/// which is also the one a file-encrypted backup needs. The backup may only
+/// hold the files of a vault; anything else refuses the whole restore. The
+/// current vault's files are copied into a `pre-restore-<time>` folder
+/// first. Data files are written next and `vault.json` last, so a crash
+/// leaves the previous vault in place. Emits `restore_progress` events.
+#[tauri::command(async)]
+pub fn restore_backup(
+    app: AppHandle,
+    drive_id: String,
+    backup_id: String,
+    password: String,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    drives: State<'_, Drives>,
+) -> Result<()> {
+    let password = Zeroizing::new(password);
+    let mut vault = state.0.lock().unwrap();
+    if session.0.lock().unwrap().is_some() {
+        return Err(VaultError::AlreadyUnlocked);
+    }
+    load_vault_if_needed(&app, &mut vault);
+    ensure_drive_trusted(&vault, &drive_id)?;
+    with_progress(&app, RESTORE_PROGRESS_EVENT, |tx| {
+        let manifest = backup::load_manifest(drives.0.as_ref(), &drive_id, &backup_id)?;
+        let files = backup::read_backup(drives.0.as_ref(), &drive_id, &backup_id, Some(tx))?;
+        let files = backup::decrypt_files(&manifest, Some(password.as_str()), files)?;
+        install_backup(&app, &mut vault, &files, &password, tx)
+    })?;
+    tracing::warn!(
+        target: "audit",
+        drive_id = %drive_id,
+        backup_id = %backup_id,
+        "vault restored from backup"
+    );
+    Ok(())
+}
+
+/// The vault a full restore of `files` would install, checked before
+/// anything is written: `vault.json` must describe an initialized vault
+/// whose keystore is in the backup, and every file must be one of that
+/// vault's stores under a plain file name. A backup carrying anything else,
+/// such as an admin API or portable-vault file, is refused. Pure, so it is
+/// unit-testable.
+pub fn select_full_restore(files: &[VaultFile]) -> Result<VaultState> {
+    let meta = files
+        .iter()
+        .find(|f| f.name == VAULT_FILE)
+        .ok_or_else(|| DriveError::Malformed(format!("{VAULT_FILE} missing from backup")))?;
+    let restored: VaultState = serde_json::from_slice(&meta.data)?;
+    if !restored.initialized || !files.iter().any(|f| f.name == restored.keys_file) {
+        return Err(DriveError::Malformed("backup does not contain a usable vault".into()).into());
+    }
+    let allowed = vault_file_names(&restored);
+    if let Some(name) = allowed.iter().find(|n| !is_plain_file_name(n)) {
+        return Err(DriveError::Malformed(format!("{name} is not a plain file name")).into());
+    }
+    if let Some(f) = files.iter().find(|f| !allowed.contains(&f.name.as_str())) {
+        return Err(DriveError::Malformed(format!("{} is not a vault file", f.name)).into());
+    }
+    Ok(restored)
+}
+
+/// A bare file name that resolves inside the data directory.
+fn is_plain_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && Path::new(name).file_name() == Some(std::ffi::OsStr::new(name))
+        && !name.contains(['/', '\\'])
+}
+
+/// Copy the data-directory files in `names` that exist into a fresh
+/// [`PRE_RESTORE_DIR_PREFIX`] folder, so a restore never destroys the
+/// files it replaces. The folder is kept; the user removes it once the
+/// restored vault opens.
+fn set_aside(app: &AppHandle, names: &[&str]) -> Result<PathBuf> {
+    let dir = data_dir(app)?.join(format!(
+        "{PRE_RESTORE_DIR_PREFIX}{}",
+        Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+    ));
+    std::fs::create_dir(&dir).map_err(|e| VaultError::Storage(e.to_string()))?;
+    for name in names {
+        let path = keys_file_path(app, name)?;
+        if path.exists() {
+            std::fs::copy(&path, dir.join(name)).map_err(|e| VaultError::Storage(e.to_string()))?;
+        }
+    }
+    tracing::info!(target: "audit", dir = %dir.display(), "vault files set aside before restore");
+    Ok(dir)
+}
+
+/// Copy back the files [`set_aside`] kept, after a restore failed part way.
+fn put_back(app: &AppHandle, dir: &Path, names: &[&str]) {
+    for name in names {
+        let kept = dir.join(name);
+        if !kept.exists() {
+            continue;
+        }
+        let copied = keys_file_path(app, name).and_then(|path| {
+            std::fs::copy(&kept, path).map_err(|e| VaultError::Storage(e.to_string()))
+        });
+        if let Err(e) = copied {
+            tracing::warn!("could not put {name} back after a failed restore: {e}");
+        }
+    }
+}
+
+/// Which of `names` a partial restore may write over the current vault:
+/// plaintext stores freely, the keystore and item store only when the backup
+/// holds the same generation the vault uses now (store files are renamed on
+/// every re-key, so a matching name means the same encryption key).
+/// `vault.json` is refused; restore the whole backup to replace it. Returns
+/// the matching intact files, or an error naming the first file that cannot
+/// be restored. Pure, so it is unit-testable.
+pub fn select_partial_restore(
+    vault: &VaultState,
+    verification: &BackupVerification,
+    intact: &[VaultFile],
+    names: &[String],
+) -> Result<Vec<VaultFile>> {
+    if names.is_empty() {
+        return Err(VaultError::InvalidMetadata(
+            "choose at least one file to restore".to_string(),
+        ));
+    }
+    let allowed = vault_file_names(vault);
+    let mut selected = Vec::with_capacity(names.len());
+    for name in names {
+        if name == VAULT_FILE {
+            return Err(VaultError::InvalidMetadata(format!(
+                "{VAULT_FILE} can only be restored with the whole backup"
+            )));
+        }
+        if !allowed.contains(&name.as_str()) {
+            return Err(VaultError::InvalidMetadata(format!(
+                "{name} is not a store of the current vault generation"
+            )));
+        }
+        match intact.iter().find(|f| &f.name == name) {
+            Some(f) => selected.push(f.clone()),
+            None => {
+                let status = verification
+                    .files
+                    .iter()
+                    .find(|c| &c.name == name)
+                    .map(|c| format!("{:?}", c.status).to_lowercase())
+                    .unwrap_or_else(|| "not in the backup".to_string());
+                return Err(VaultError::InvalidMetadata(format!(
+                    "{name} cannot be restored: {status}"
+                )));
+            }
+        }
+    }
+    Ok(selected)
+}
+
+/// Restore only the named files from a backup, for when the local copy of a
+/// store is lost or damaged, or the backup itself is partly damaged. Each
+/// file is checked on its own, so damage elsewhere in the backup does not
+/// block it. Only allowed while the vault is locked, and only with the
+/// current vault `password`, which is also the one file-encrypted backups
+/// need. The files being replaced are copied into a `pre-restore-<time>`
+/// folder first. Returns the restored file names.
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
+pub fn restore_backup_files(
+    app: AppHandle,
+    drive_id: String,
+    backup_id: String,
+    files: Vec<String>,
+    password: String,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    drives: State<'_, Drives>,
+) -> Result<Vec<String>> {
+    let password = Zeroizing::new(password);
+    verify_password(&app, &state, &password)?;
+    let vault = state.0.lock().unwrap();
+    if !vault.initialized {
+        return Err(VaultError::NotInitialized);
+    }
+    if session.0.lock().unwrap().is_some() {
+        return Err(VaultError::AlreadyUnlocked);
+    }
+    ensure_drive_trusted(&vault, &drive_id)?;
+    let (manifest, verification, intact) =
+        backup::read_backup_checked(drives.0.as_ref(), &drive_id, &backup_id)?;
+    let selected = select_partial_restore(&vault, &verification, &intact, &files)?;
+    let selected = backup::decrypt_files(&manifest, Some(password.as_str()), selected)?;
+    let names: Vec<&str> = selected.iter().map(|f| f.name.as_str()).collect();
+    let aside = set_aside(&app, &names)?;
+    for f in &selected {
+        if let Err(e) = atomic_write(&keys_file_path(&app, &f.name)?, &f.data) {
+            put_back(&app, &aside, &names);
+            return Err(e);
+        }
+    }
+    tracing::info!(
+        target: "audit",
+        drive_id = %drive_id,
+        backup_id = %backup_id,
+        files = ?files,
+        "backup files restored"
+    );
+    Ok(files)
+}
+
+fn install_backup(
+    app: &AppHandle,
+    vault: &mut VaultState,
+    files: &[VaultFile],
+    password: &str,
+    tx: &Sender<BackupProgress>,
+) -> Result<()> {
+    let restored = select_full_restore(files)?;
+    // The backup's own verifier must open with the password, so a backup
+    // the user cannot unlock never replaces their vault.
+    verify_enc_key(&restored, &derive_vault_enc_key(&restored, password)?)?;
+
+    let total = files.iter().map(|f| f.data.len() as u64).sum();
+    let mut progress = ProgressReporter::new(Some(tx), total);
+    progress.advance(total);
+    progress.report(ProgressStage::Finalizing, None);
+    let mut replaced: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+    if vault.initialized {
+        replaced.extend(vault_file_names(vault));
+    }
+    let aside = set_aside(app, &replaced)?;
+    let written = files
+        .iter()
+        .filter(|f| f.name != VAULT_FILE)
+        .try_for_each(|f| atomic_write(&keys_file_path(app, &f.name)?, &f.data));
+    if let Err(e) = written {
+        put_back(app, &aside, &replaced);
+        return Err(e);
+    }
+    persist_vault(app, &restored)?;
+    *vault = restored;
+    progress.report(ProgressStage::Complete, None);
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use crate::commands::contacts::load_contacts;
+use crate::commands::items::{save_items, ItemStore};
+use crate::commands::keys::{atomic_write, keys_file_path, MasterSeed, SessionKey};
+use crate::commands::vault::VaultMutex;
+use crate::crypto::{attestation, capsule};
+use crate::error::{Result, VaultError};
+use crate::models::capsule::{
+    validate_release_at, CapsuleAttestor, CapsuleStatus, TimeAttestation, TimeCapsule,
+};
+use crate::models::item::VaultItemPublic;
+use chrono::{DateTime, Utc};
+use tauri::{AppHandle, State};
+
+/// Time capsules live in plaintext metadata next to `vault.json`; their
+/// contents are sealed, and the file travels with backups.
+pub const CAPSULES_FILE: &str = "time_capsules.json";
+
+pub(crate) fn load_capsules(app: &AppHandle) -> Result<Vec<TimeCapsule>> {
+    let path = keys_file_path(app, CAPSULES_FILE)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+fn save_capsules(app: &AppHandle, capsules: &[TimeCapsule]) -> Result<()> {
+    let path = keys_file_path(app, CAPSULES_FILE)?;
+    atomic_write(&path, &serde_json::to_vec_pretty(capsules)?)
+}
+
+fn seed_copy(master_seed: &State<'_, MasterSeed>) -> Result<zeroize::Zeroizing<[u8; 64]>> {
+    let guard = master_seed.0.lock().unwrap();
+    Ok(guard.as_ref().ok_or(VaultError::NotInitialized)?.clone())
+}
+
+/// Move `item_id` out of the item store into a time capsule that opens no
+/// earlier than `release_at`. With `attestor_contact_id`, opening also needs
+/// a time attestation signed by that verified contact. Requires an unlocked
+/// vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn create_time_capsule(
+    app: AppHandle,
+    item_id: String,
+    release_at: DateTime<Utc>,
+    attestor_contact_id: Option<String>,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<CapsuleStatus> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let seed = seed_copy(&master_seed)?;
+    let now = Utc::now();
+    validate_release_at(release_at, now).map_err(VaultError::InvalidMetadata)?;
+    let attestor = match attestor_contact_id {
+        None => None,
+        Some(id) => {
+            let contact = load_contacts(&app)?
+                .into_iter()
+                .find(|c| c.id == id)
+                .ok_or_else(|| VaultError::KeyNotFound(id.clone()))?;
+            if !contact.verified {
+                return Err(VaultError::InvalidMetadata(
+                    "verify the attestor's fingerprint before relying on them".to_string(),
+                ));
+            }
+            Some(CapsuleAttestor {
+                contact_id: contact.id,
+                name: contact.card.name,
+                sign_public_hex: contact.card.sign_public_hex,
+            })
+        }
+    };
+
+    let items_file = vault.0.lock().unwrap().items_file.clone();
+    let mut store = items.0.lock().unwrap();
+    let idx = store
+        .iter()
+        .position(|i| i.id == item_id && i.trashed_at.is_none())
+        .ok_or_else(|| VaultError::KeyNotFound(item_id.clone()))?;
+    let sealed = capsule::seal_capsule(&seed, &store[idx], release_at, attestor, now)?;
+    // Capsule first: a crash before the item store is rewritten leaves the
+    // item in both places rather than in neither.
+    let mut capsules = load_capsules(&app)?;
+    capsules.push(sealed.clone());
+    save_capsules(&app, &capsules)?;
+    let mut next = store.clone();
+    next.remove(idx);
+    save_items(&app, &items_file, &session_key, &next)?;
+    *store = next;
+    tracing::info!(
+        target: "audit",
+        capsule_id = %sealed.id,
+        item_id = %item_id,
+        release_at = %release_at,
+        "time capsule created"
+    );
+    Ok(sealed.status(now))
+}
+
+/// Every capsule with the time left until it opens. Works while locked.
+#[tauri::command]
+pub fn list_time_capsules(app: AppHandle) -> Result<Vec<CapsuleStatus>> {
+    let now = Utc::now();
+    Ok(load_capsules(&app)?.iter().map(|c| c.status(now)).collect())
+}
+
+/// Open a released capsule and put its item back in the item store.
+/// `attestation` is needed when the capsule names an attestor. Requires an
+/// unlocked vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn open_time_capsule(
+    app: AppHandle,
+    capsule_id: String,
+    attestation: Option<TimeAttestation>,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<VaultItemPublic> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let seed = seed_copy(&master_seed)?;
+    let mut capsules = load_capsules(&app)?;
+    let idx = capsules
+        .iter()
+        .position(|c| c.id == capsule_id)
+        .ok_or_else(|| VaultError::KeyNotFound(capsule_id.clone()))?;
+    let item = capsule::open_capsule(&seed, &capsules[idx], Utc::now(), attestation.as_ref())?;
+    let public = item.to_public();
+
+    let items_file = vault.0.lock().unwrap().items_file.clone();
+    let mut store = items.0.lock().unwrap();
+    let mut next = store.clone();
+    next.retain(|i| i.id != item.id);
+    next.push(item);
+    save_items(&app, &items_file, &session_key, &next)?;
+    *store = next;
+    capsules.remove(idx);
+    save_capsules(&app, &capsules)?;
+    tracing::info!(target: "audit", capsule_id = %capsule_id, "time capsule opened");
+    Ok(public)
+}
+
+/// Attestor side: sign the current time with this vault's identity, for a
+/// contact whose capsule names this vault as its attestor. Check the local
+/// clock before signing; the attestation is only as good as that clock.
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn sign_time_attestation(master_seed: State<'_, MasterSeed>) -> Result<TimeAttestation> {
+    let seed = seed_copy(&master_seed)?;
+    let (pk, sk) = attestation::vault_identity(&seed);
+    let now = Utc::now().timestamp() as u64;
+    let signed = capsule::sign_time_attestation(&sk, &pk, now)?;
+    tracing::info!(target: "audit", time = now, "time attestation signed");
+    Ok(signed)
+}
@@ -0,0 +1,333 @@
+use crate::commands::keys::{atomic_write, keys_file_path, KeyStore, SessionKey};
+use crate::commands::keysets::complete_keyset;
+use crate::commands::policy::enforce_policy;
+use crate::commands::vault::{persist_vault, VaultMutex};
+use crate::crypto::ceremony::{self, CeremonyError, BUNDLE_VERSION, MAX_OPERATORS, MIN_OPERATORS};
+use crate::crypto::mldsa87::SecretKey;
+use crate::crypto::mnemonic;
+use crate::error::{Result, VaultError};
+use crate::models::ceremony::{
+    AttestationBundle, Ceremony, CeremonyGenerated, CeremonyOperator, CeremonyOperatorInput,
+    CeremonyPhase, EntropyContribution, EntropyReveal, GenesisManifest, ManifestKey,
+    ManifestOperator,
+};
+use crate::models::keyset::{
+    plan_keyset, KeysetMemberSpec, KeysetRequest, KeysetStatus, MAX_KEYSET_SIZE,
+};
+use crate::models::policy::PolicyGate;
+use crate::models::worksheet::CeremonyWorksheet;
+use crate::report::ceremony as worksheet;
+use chrono::Utc;
+use std::path::Path;
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
+
+/// Genesis ceremonies live in plaintext metadata next to `vault.json`: they
+/// hold only operator public keys, entropy commitments, the public manifest
+/// and signatures. Revealed entropy is never written anywhere.
+pub const CEREMONY_FILE: &str = "ceremonies.json";
+
+pub(crate) fn load_ceremonies(app: &AppHandle) -> Result<Vec<Ceremony>> {
+    let path = keys_file_path(app, CEREMONY_FILE)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+fn save_ceremonies(app: &AppHandle, ceremonies: &[Ceremony]) -> Result<()> {
+    let path = keys_file_path(app, CEREMONY_FILE)?;
+    atomic_write(&path, &serde_json::to_vec_pretty(ceremonies)?)
+}
+
+fn find_ceremony<'a>(ceremonies: &'a mut [Ceremony], id: &str) -> Result<&'a mut Ceremony> {
+    ceremonies
+        .iter_mut()
+        .find(|c| c.id == id)
+        .ok_or_else(|| VaultError::KeyNotFound(id.to_string()))
+}
+
+/// Set up a genesis ceremony: the operators who will contribute entropy and
+/// sign the result, and the keyset the combined entropy will seed.
+#[tauri::command]
+pub fn ceremony_create(
+    app: AppHandle,
+    name: String,
+    account: u32,
+    members: Vec<KeysetMemberSpec>,
+    operators: Vec<CeremonyOperatorInput>,
+) -> Result<Ceremony> {
+    if !(MIN_OPERATORS..=MAX_OPERATORS).contains(&operators.len()) {
+        return Err(CeremonyError::OperatorCount.into());
+    }
+    for (i, op) in operators.iter().enumerate() {
+        ceremony::validate_operator(op)?;
+        if operators[..i]
+            .iter()
+            .any(|o| o.public_key_hex == op.public_key_hex)
+        {
+            return Err(CeremonyError::InvalidOperator(format!(
+                "{} reuses another operator's key",
+                op.name
+            ))
+            .into());
+        }
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    // Validate the keyset shape now rather than after everyone has committed.
+    plan_keyset(
+        &[],
+        &KeysetRequest {
+            idempotency_key: id.clone(),
+            name: name.clone(),
+            account,
+            members: members.clone(),
+        },
+        MAX_KEYSET_SIZE,
+        Utc::now(),
+    )
+    .map_err(VaultError::Keyset)?;
+
+    let ceremony = Ceremony {
+        id,
+        name,
+        account,
+        members,
+        operators: operators
+            .into_iter()
+            .map(|op| CeremonyOperator {
+                id: uuid::Uuid::new_v4().to_string(),
+                name: op.name,
+                public_key_hex: op.public_key_hex,
+                commitment_hex: None,
+                committed_at: None,
+            })
+            .collect(),
+        phase: CeremonyPhase::Commit,
+        keyset_id: None,
+        manifest: None,
+        attestations: Vec::new(),
+        created_at: Utc::now(),
+    };
+    let mut ceremonies = load_ceremonies(&app)?;
+    ceremonies.push(ceremony.clone());
+    save_ceremonies(&app, &ceremonies)?;
+    Ok(ceremony)
+}
+
+#[tauri::command]
+pub fn ceremony_list(app: AppHandle) -> Result<Vec<Ceremony>> {
+    load_ceremonies(&app)
+}
+
+/// Operator-side: draw entropy and its commitment. The operator submits the
+/// commitment now and keeps the entropy secret until the reveal.
+#[tauri::command]
+pub fn ceremony_contribute(ceremony_id: String, operator_id: String) -> EntropyContribution {
+    ceremony::contribute(&ceremony_id, &operator_id)
+}
+
+#[tauri::command]
+pub fn ceremony_commit(
+    app: AppHandle,
+    ceremony_id: String,
+    operator_id: String,
+    commitment_hex: String,
+) -> Result<Ceremony> {
+    let mut ceremonies = load_ceremonies(&app)?;
+    let c = find_ceremony(&mut ceremonies, &ceremony_id)?;
+    ceremony::record_commitment(c, &operator_id, &commitment_hex, Utc::now())?;
+    let updated = c.clone();
+    save_ceremonies(&app, &ceremonies)?;
+    Ok(updated)
+}
+
+/// Check every operator's revealed entropy against their commitment, combine
+/// it, and generate the genesis keyset from the result as one transactional
+/// keyset (see `generate_keyset`). Returns the ceremony, now collecting
+/// attestations over its manifest, and the backup mnemonic for the combined
+/// entropy. Re-running with the same reveals is safe and yields the same keys.
+#[tauri::command]
+pub fn ceremony_generate(
+    app: AppHandle,
+    ceremony_id: String,
+    reveals: Vec<EntropyReveal>,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    session: State<'_, SessionKey>,
+) -> Result<CeremonyGenerated> {
+    enforce_policy(&vault, PolicyGate::KeyGeneration)?;
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let mut ceremonies = load_ceremonies(&app)?;
+    let c = find_ceremony(&mut ceremonies, &ceremony_id)?;
+    if c.phase == CeremonyPhase::Complete {
+        return Err(CeremonyError::WrongPhase(c.phase).into());
+    }
+    let entropy = ceremony::combine_entropy(c, &reveals)?;
+    let backup = ceremony::backup_mnemonic(&entropy);
+    let seed = Zeroizing::new(mnemonic::mnemonic_to_seed(&backup)?);
+
+    let mut vault = vault.0.lock().unwrap();
+    let mut store = keystore.0.lock().unwrap();
+    let existing = vault
+        .keysets
+        .iter()
+        .find(|k| k.ceremony_id.as_deref() == Some(c.id.as_str()))
+        .cloned();
+    let keyset = match existing {
+        Some(keyset) if keyset.status == KeysetStatus::Complete => keyset,
+        Some(keyset) => {
+            complete_keyset(&app, &mut vault, &mut store, &session_key, &seed, &keyset)?
+        }
+        None => {
+            // A fresh HD tree, so indices start at zero regardless of the
+            // vault's own keys.
+            let mut keyset = plan_keyset(
+                &[],
+                &KeysetRequest {
+                    idempotency_key: format!("ceremony:{}", c.id),
+                    name: c.name.clone(),
+                    account: c.account,
+                    members: c.members.clone(),
+                },
+                MAX_KEYSET_SIZE,
+                Utc::now(),
+            )
+            .map_err(VaultError::Keyset)?;
+            keyset.ceremony_id = Some(c.id.clone());
+            vault.keysets.push(keyset.clone());
+            persist_vault(&app, &vault)?;
+            complete_keyset(&app, &mut vault, &mut store, &session_key, &seed, &keyset)?
+        }
+    };
+
+    if c.manifest.is_none() {
+        let keys = keyset
+            .planned
+            .iter()
+            .filter_map(|p| {
+                store.iter().find(|k| {
+                    k.metadata.keyset_id.as_deref() == Some(keyset.id.as_str())
+                        && k.metadata.derivation_path == p.derivation_path
+                })
+            })
+            .map(|k| ManifestKey {
+                key_type: k.metadata.key_type.clone(),
+                derivation_path: k.metadata.derivation_path.clone(),
+                public_key_hex: k.public_key_hex.clone(),
+                address: k.metadata.address.clone(),
+            })
+            .collect();
+        c.manifest = Some(GenesisManifest {
+            ceremony_id: c.id.clone(),
+            name: c.name.clone(),
+            account: c.account,
+            operators: c
+                .operators
+                .iter()
+                .map(|o| ManifestOperator {
+                    id: o.id.clone(),
+                    name: o.name.clone(),
+                    public_key_hex: o.public_key_hex.clone(),
+                    commitment_hex: o.commitment_hex.clone().unwrap_or_default(),
+                })
+                .collect(),
+            keys,
+            generated_at: keyset.completed_at.unwrap_or_else(Utc::now),
+        });
+    }
+    c.keyset_id = Some(keyset.id.clone());
+    c.phase = CeremonyPhase::Attesting;
+    let ceremony = c.clone();
+    save_ceremonies(&app, &ceremonies)?;
+    Ok(CeremonyGenerated {
+        ceremony,
+        backup_mnemonic: backup.to_string(),
+    })
+}
+
+/// Operator-side: sign a ceremony manifest with the operator's ML-DSA-87 key.
+#[tauri::command]
+pub fn ceremony_sign_manifest(
+    manifest: GenesisManifest,
+    sign_secret_hex: String,
+) -> Result<String> {
+    let sk = SecretKey::from_hex(&sign_secret_hex)?;
+    Ok(ceremony::sign_manifest(&sk, &manifest)?.to_hex())
+}
+
+/// Record an operator's signature over the manifest.
+#[tauri::command]
+pub fn ceremony_attest(
+    app: AppHandle,
+    ceremony_id: String,
+    operator_id: String,
+    signature_hex: String,
+) -> Result<Ceremony> {
+    let mut ceremonies = load_ceremonies(&app)?;
+    let c = find_ceremony(&mut ceremonies, &ceremony_id)?;
+    ceremony::record_attestation(c, &operator_id, &signature_hex, Utc::now())?;
+    let updated = c.clone();
+    save_ceremonies(&app, &ceremonies)?;
+    Ok(updated)
+}
+
+/// The manifest and every operator's attestation as JSON, to ship alongside
+/// the genesis config. Only available once all operators have signed.
+#[tauri::command]
+pub fn ceremony_export_bundle(app: AppHandle, ceremony_id: String) -> Result<String> {
+    let mut ceremonies = load_ceremonies(&app)?;
+    let c = find_ceremony(&mut ceremonies, &ceremony_id)?;
+    let manifest = match (&c.phase, &c.manifest) {
+        (CeremonyPhase::Complete, Some(manifest)) => manifest.clone(),
+        _ => return Err(CeremonyError::WrongPhase(c.phase).into()),
+    };
+    let bundle = AttestationBundle {
+        version: BUNDLE_VERSION,
+        manifest,
+        attestations: c.attestations.clone(),
+    };
+    Ok(serde_json::to_string_pretty(&bundle)?)
+}
+
+/// Write a printable worksheet of the ceremony to `path` as a PDF: the
+/// operators and their key fingerprints, each step with the time it was
+/// recorded, the manifest and key fingerprints, and a signature line per
+/// operator. Works in any phase; steps not yet taken are left unticked.
+/// Returns the worksheet. Requires an unlocked vault.
+#[tauri::command]
+pub fn ceremony_export_worksheet(
+    app: AppHandle,
+    ceremony_id: String,
+    path: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<CeremonyWorksheet> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    let target = Path::new(&path);
+    if !target.is_absolute() {
+        return Err(VaultError::InvalidMetadata(
+            "worksheet path must be absolute".to_string(),
+        ));
+    }
+    let mut ceremonies = load_ceremonies(&app)?;
+    let c = find_ceremony(&mut ceremonies, &ceremony_id)?;
+    let locale = vault.0.lock().unwrap().locale();
+    let sheet = worksheet::genesis(c, locale, Utc::now());
+    atomic_write(target, &worksheet::render(&sheet, locale))?;
+    tracing::info!(
+        target: "audit",
+        ceremony_id = %ceremony_id,
+        path = %path,
+        complete = sheet.complete,
+        "ceremony worksheet written"
+    );
+    Ok(sheet)
+}
@@ -0,0 +1,68 @@
+use crate::commands::items::ItemStore;
+use crate::commands::keys::{atomic_write, KeyStore, SessionKey};
+use crate::commands::profiles::{profile_vault, Profiles};
+use crate::commands::vault::VaultMutex;
+use crate::error::{Result, VaultError};
+use crate::models::compliance::{ComplianceReport, ComplianceReportFormat};
+use crate::report::compliance;
+use chrono::Utc;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Write a compliance report to `path`: counts of keys, items and backups,
+/// backup coverage, overdue rotations, vaults without a second factor and
+/// the security policy. Only aggregates are written, never keys, addresses,
+/// labels or profile names. Other profiles are read from their `vault.json`
+/// without unlocking them. Returns the report. Requires an unlocked vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn export_compliance_report(
+    app: AppHandle,
+    format: ComplianceReportFormat,
+    path: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    profiles: State<'_, Profiles>,
+) -> Result<ComplianceReport> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    let target = Path::new(&path);
+    if !target.is_absolute() {
+        return Err(VaultError::InvalidMetadata(
+            "report path must be absolute".to_string(),
+        ));
+    }
+    let (active, others): (String, Vec<String>) = {
+        let profiles = profiles.0.lock().unwrap();
+        let others = profiles
+            .profiles
+            .iter()
+            .filter(|p| p.id != profiles.active)
+            .map(|p| p.id.clone())
+            .collect();
+        (profiles.active.clone(), others)
+    };
+    let other_vaults: Vec<_> = others
+        .iter()
+        .filter_map(|id| profile_vault(&app, id))
+        .collect();
+    let report = {
+        let vault = vault.0.lock().unwrap();
+        let keys = keystore.0.lock().unwrap();
+        let items = items.0.lock().unwrap();
+        let access = compliance::access_summary(std::iter::once(&*vault).chain(&other_vaults));
+        compliance::build(access, &keys, &items, &vault, Utc::now())
+    };
+    atomic_write(target, &compliance::render(&report, format)?)?;
+    tracing::info!(
+        target: "audit",
+        format = ?format,
+        path = %path,
+        profile_id = %active,
+        "compliance report written"
+    );
+    Ok(report)
+}
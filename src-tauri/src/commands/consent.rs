@@ -0,0 +1,258 @@
+use crate::commands::contacts::load_contacts;
+use crate::commands::items::ItemStore;
+use crate::commands::keys::{KeyStore, SessionKey};
+use crate::commands::trash::collect_trash;
+use crate::commands::vault::{LockEpoch, VaultMutex};
+use crate::error::{Result, VaultError};
+use crate::i18n::tr;
+use crate::models::consent::{ConsentLedger, ConsentOperation, ConsentToken};
+use crate::models::vault::VaultState;
+use chrono::{Duration, Utc};
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+/// Consent tokens issued by `request_consent` and not yet redeemed.
+#[derive(Default)]
+pub struct ConsentTokens(pub Mutex<ConsentLedger>);
+
+fn item_label(items: &State<'_, ItemStore>, item_id: &str) -> Result<String> {
+    items
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|i| i.id == item_id && i.trashed_at.is_none())
+        .map(|i| i.label.clone().unwrap_or_else(|| i.id.clone()))
+        .ok_or_else(|| VaultError::KeyNotFound(item_id.to_string()))
+}
+
+/// What `operation` will do, in the vault's locale, from its current
+/// contents.
+fn describe(
+    app: &AppHandle,
+    operation: &ConsentOperation,
+    vault: &VaultState,
+    keystore: &State<'_, KeyStore>,
+    items: &State<'_, ItemStore>,
+) -> Result<String> {
+    let locale = vault.locale();
+    let retention_days = vault.trash_retention_days;
+    Ok(match operation {
+        ConsentOperation::PurgeTrash { older_than_days } => {
+            let cutoff = Utc::now() - Duration::days((*older_than_days).into());
+            let count = {
+                let keys = keystore.0.lock().unwrap();
+                let items = items.0.lock().unwrap();
+                collect_trash(&keys, &items, retention_days)
+                    .iter()
+                    .filter(|e| e.trashed_at <= cutoff)
+                    .count()
+            };
+            tr(locale, "consent-purge-trash", &[("count", count.into())])
+        }
+        ConsentOperation::SshPrivateKeyExport { item_id } => tr(
+            locale,
+            "consent-ssh-private-key-export",
+            &[("name", item_label(items, item_id)?.into())],
+        ),
+        ConsentOperation::WireguardConfigExport { item_id } => tr(
+            locale,
+            "consent-wireguard-config-export",
+            &[("name", item_label(items, item_id)?.into())],
+        ),
+        ConsentOperation::PasskeyExport { item_ids } => {
+            for id in item_ids {
+                item_label(items, id)?;
+            }
+            tr(
+                locale,
+                "consent-passkey-export",
+                &[("count", item_ids.len().into())],
+            )
+        }
+        ConsentOperation::ApiTokenReveal { item_id } => tr(
+            locale,
+            "consent-api-token-reveal",
+            &[("name", item_label(items, item_id)?.into())],
+        ),
+        ConsentOperation::EnvFileRender { item_ids, path } => {
+            for id in item_ids {
+                item_label(items, id)?;
+            }
+            tr(
+                locale,
+                "consent-env-file-render",
+                &[
+                    ("count", item_ids.len().into()),
+                    ("path", path.as_str().into()),
+                ],
+            )
+        }
+        ConsentOperation::PluginItemReveal { item_id } => tr(
+            locale,
+            "consent-plugin-item-reveal",
+            &[("name", item_label(items, item_id)?.into())],
+        ),
+        ConsentOperation::PluginExport {
+            plugin_id,
+            exporter,
+            path,
+        } => {
+            let plugin = vault
+                .plugins
+                .find(plugin_id)
+                .ok_or_else(|| VaultError::KeyNotFound(plugin_id.clone()))?;
+            tr(
+                locale,
+                "consent-plugin-export",
+                &[
+                    ("exporter", exporter.as_str().into()),
+                    ("plugin", plugin.manifest.name.as_str().into()),
+                    ("path", path.as_str().into()),
+                ],
+            )
+        }
+        ConsentOperation::Slip39Shares {
+            group_threshold,
+            groups,
+        } => tr(
+            locale,
+            "consent-slip39-shares",
+            &[
+                ("groups", groups.len().into()),
+                ("threshold", (*group_threshold).into()),
+            ],
+        ),
+        ConsentOperation::FrostShareExport { group_id, path } => tr(
+            locale,
+            "consent-frost-share-export",
+            &[
+                ("group", group_id.as_str().into()),
+                ("path", path.as_str().into()),
+            ],
+        ),
+        ConsentOperation::AgentStart { grants } => {
+            for g in grants {
+                item_label(items, &g.item_id)?;
+            }
+            tr(
+                locale,
+                "consent-agent-start",
+                &[("count", grants.len().into())],
+            )
+        }
+        ConsentOperation::RepairOrphans => tr(locale, "consent-repair-orphans", &[]),
+        ConsentOperation::RollbackKeyset { keyset_id } => {
+            let name = vault
+                .keysets
+                .iter()
+                .find(|k| &k.id == keyset_id)
+                .map(|k| k.name.clone())
+                .ok_or_else(|| VaultError::Keyset(format!("keyset not found: {keyset_id}")))?;
+            let count = keystore
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|k| k.metadata.keyset_id.as_deref() == Some(keyset_id.as_str()))
+                .count();
+            tr(
+                locale,
+                "consent-rollback-keyset",
+                &[("name", name.into()), ("count", count.into())],
+            )
+        }
+        ConsentOperation::KeyEscrow {
+            key_id,
+            recipient_contact_id,
+        } => {
+            let key = keystore
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|k| &k.id == key_id && k.metadata.trashed_at.is_none())
+                .map(|k| {
+                    k.metadata
+                        .label
+                        .clone()
+                        .unwrap_or_else(|| k.metadata.address.clone())
+                })
+                .ok_or_else(|| VaultError::KeyNotFound(key_id.clone()))?;
+            let recipient = load_contacts(app)?
+                .into_iter()
+                .find(|c| &c.id == recipient_contact_id)
+                .map(|c| c.card.name)
+                .ok_or_else(|| VaultError::KeyNotFound(recipient_contact_id.clone()))?;
+            tr(
+                locale,
+                "consent-key-escrow",
+                &[("key", key.into()), ("recipient", recipient.into())],
+            )
+        }
+    })
+}
+
+/// First step of a destructive or exposing operation: describe it and issue
+/// a single-use token, valid for a minute, that the command for it requires.
+/// Locking the vault voids every token. Requires an unlocked vault.
+#[tauri::command]
+pub fn request_consent(
+    app: AppHandle,
+    operation: ConsentOperation,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+    consent: State<'_, ConsentTokens>,
+) -> Result<ConsentToken> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    let description = describe(
+        &app,
+        &operation,
+        &vault.0.lock().unwrap(),
+        &keystore,
+        &items,
+    )?;
+    let epoch = app.state::<LockEpoch>().0.load(Ordering::SeqCst);
+    let token = consent
+        .0
+        .lock()
+        .unwrap()
+        .issue(operation, description, epoch, Utc::now());
+    tracing::info!(
+        target: "audit",
+        operation = token.operation.as_str(),
+        description = %token.description,
+        "consent requested"
+    );
+    Ok(token)
+}
+
+/// Second step: spend `token` on `operation`, or refuse the command. Call
+/// before doing anything the token is meant to guard.
+pub(crate) fn require_consent(
+    app: &AppHandle,
+    token: &str,
+    operation: &ConsentOperation,
+) -> Result<()> {
+    let epoch = app.state::<LockEpoch>().0.load(Ordering::SeqCst);
+    let consent = app.state::<ConsentTokens>();
+    let redeemed = consent
+        .0
+        .lock()
+        .unwrap()
+        .redeem(token, operation, epoch, Utc::now());
+    redeemed.map_err(|reason| {
+        tracing::warn!(
+            target: "audit",
+            operation = operation.as_str(),
+            reason = %reason,
+            "operation refused without consent"
+        );
+        VaultError::ConsentRequired(reason)
+    })
+}
@@ -0,0 +1,164 @@
+use crate::commands::keys::{atomic_write, keys_file_path, MasterSeed};
+use crate::crypto::{attestation, contact};
+use crate::error::{Result, VaultError};
+use crate::models::contact::{Contact, ContactCard, ContactPublic, OwnContactCard};
+use chrono::Utc;
+use tauri::{AppHandle, State};
+
+/// Imported contact cards live in plaintext metadata next to `vault.json`:
+/// they hold only public keys and signatures, and travel with backups.
+pub const CONTACTS_FILE: &str = "contacts.json";
+
+pub(crate) fn load_contacts(app: &AppHandle) -> Result<Vec<Contact>> {
+    let path = keys_file_path(app, CONTACTS_FILE)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+fn save_contacts(app: &AppHandle, contacts: &[Contact]) -> Result<()> {
+    let path = keys_file_path(app, CONTACTS_FILE)?;
+    atomic_write(&path, &serde_json::to_vec_pretty(contacts)?)
+}
+
+fn to_public(contact: Contact) -> Result<ContactPublic> {
+    let fingerprint = contact::fingerprint(&contact.card)?;
+    Ok(ContactPublic {
+        contact,
+        fingerprint,
+    })
+}
+
+/// This vault's ML-DSA-87 identity public key; requires an unlocked HD vault.
+fn own_identity(master_seed: &State<'_, MasterSeed>) -> Result<String> {
+    let guard = master_seed.0.lock().unwrap();
+    let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
+    Ok(attestation::vault_identity(seed).0.to_hex())
+}
+
+/// Issue this vault's contact card under `name`, signed by the vault identity,
+/// for other vault users to import. Read the returned fingerprint to them
+/// over another channel so they can verify the card.
+#[tauri::command]
+pub fn export_contact_card(
+    name: String,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<OwnContactCard> {
+    let card = {
+        let guard = master_seed.0.lock().unwrap();
+        let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
+        contact::issue_card(seed, &name, Utc::now().timestamp() as u64)?
+    };
+    let fingerprint = contact::fingerprint(&card)?;
+    Ok(OwnContactCard { card, fingerprint })
+}
+
+/// Import another vault user's card after checking its signature. A newer
+/// card for a known identity replaces the old one but keeps its verified
+/// state, since the fingerprint covers only the keys. Requires an unlocked
+/// vault.
+#[tauri::command]
+pub fn import_contact_card(
+    app: AppHandle,
+    card: ContactCard,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<ContactPublic> {
+    if own_identity(&master_seed)? == card.sign_public_hex.to_lowercase() {
+        return Err(VaultError::InvalidMetadata(
+            "this is this vault's own contact card".to_string(),
+        ));
+    }
+    contact::verify_card(&card)?;
+    let fingerprint = contact::fingerprint(&card)?;
+
+    let mut contacts = load_contacts(&app)?;
+    let now = Utc::now().timestamp() as u64;
+    let existing = contacts
+        .iter()
+        .position(|c| contact::fingerprint(&c.card).is_ok_and(|f| f == fingerprint));
+    let entry = match existing {
+        Some(i) => {
+            if card.issued_at < contacts[i].card.issued_at {
+                return Err(VaultError::InvalidMetadata(
+                    "a newer card for this contact is already imported".to_string(),
+                ));
+            }
+            contacts[i].card = card;
+            contacts[i].imported_at = now;
+            contacts[i].clone()
+        }
+        None => {
+            let entry = Contact {
+                id: uuid::Uuid::new_v4().to_string(),
+                card,
+                imported_at: now,
+                verified: false,
+            };
+            contacts.push(entry.clone());
+            entry
+        }
+    };
+    save_contacts(&app, &contacts)?;
+    tracing::info!(
+        target: "audit",
+        contact_id = %entry.id,
+        fingerprint = %fingerprint.hex,
+        "contact card imported"
+    );
+    Ok(ContactPublic {
+        contact: entry,
+        fingerprint,
+    })
+}
+
+#[tauri::command]
+pub fn list_contacts(app: AppHandle) -> Result<Vec<ContactPublic>> {
+    load_contacts(&app)?.into_iter().map(to_public).collect()
+}
+
+/// Compare `fingerprint`, as read out by the contact in hex or word form,
+/// with the stored card and mark the contact verified if it matches.
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn verify_contact_fingerprint(
+    app: AppHandle,
+    contact_id: String,
+    fingerprint: String,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<ContactPublic> {
+    own_identity(&master_seed)?;
+    let mut contacts = load_contacts(&app)?;
+    let entry = contacts
+        .iter_mut()
+        .find(|c| c.id == contact_id)
+        .ok_or_else(|| VaultError::KeyNotFound(contact_id.clone()))?;
+    if !contact::fingerprint(&entry.card)?.matches(&fingerprint) {
+        return Err(VaultError::InvalidMetadata(
+            "fingerprint does not match this contact's card".to_string(),
+        ));
+    }
+    entry.verified = true;
+    let entry = entry.clone();
+    save_contacts(&app, &contacts)?;
+    tracing::info!(target: "audit", contact_id = %contact_id, "contact fingerprint verified");
+    to_public(entry)
+}
+
+/// Remove a contact. Requires an unlocked vault.
+#[tauri::command]
+pub fn delete_contact(
+    app: AppHandle,
+    contact_id: String,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<()> {
+    own_identity(&master_seed)?;
+    let mut contacts = load_contacts(&app)?;
+    let before = contacts.len();
+    contacts.retain(|c| c.id != contact_id);
+    if contacts.len() == before {
+        return Err(VaultError::KeyNotFound(contact_id));
+    }
+    save_contacts(&app, &contacts)
+}
@@ -0,0 +1,116 @@
+use crate::commands::keys::{atomic_write, keys_file_path, require_unlocked, SessionKey};
+use crate::error::{Result, VaultError};
+use crate::models::custody::{CustodyBook, DriveLocation, StaleLocation};
+use chrono::Utc;
+use tauri::{AppHandle, State};
+
+/// Drive custody records live in plaintext metadata next to `vault.json`,
+/// so they travel with backups.
+pub const CUSTODY_FILE: &str = "drive_locations.json";
+
+fn load_book(app: &AppHandle) -> Result<CustodyBook> {
+    let path = keys_file_path(app, CUSTODY_FILE)?;
+    if !path.exists() {
+        return Ok(CustodyBook::default());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+fn save_book(app: &AppHandle, book: &CustodyBook) -> Result<()> {
+    let path = keys_file_path(app, CUSTODY_FILE)?;
+    atomic_write(&path, &serde_json::to_vec_pretty(book)?)
+}
+
+/// Apply `change` to the custody book and save it, returning the drive's
+/// updated record.
+fn update(
+    app: &AppHandle,
+    change: impl FnOnce(&mut CustodyBook) -> std::result::Result<&DriveLocation, String>,
+) -> Result<DriveLocation> {
+    let mut book = load_book(app)?;
+    let entry = change(&mut book)
+        .map_err(VaultError::InvalidMetadata)?
+        .clone();
+    save_book(app, &book)?;
+    tracing::info!(
+        target: "audit",
+        drive_id = %entry.drive_id,
+        action = ?entry.history.last().map(|e| e.action),
+        location = %entry.location,
+        custodian = %entry.custodian,
+        "drive custody changed"
+    );
+    Ok(entry)
+}
+
+#[tauri::command]
+pub fn list_drive_locations(app: AppHandle) -> Result<CustodyBook> {
+    load_book(&app)
+}
+
+/// Record that `drive_id` is stored at `location` in `custodian`'s care.
+/// Checking in also verifies the location. Requires an unlocked vault.
+#[tauri::command]
+pub fn drive_check_in(
+    app: AppHandle,
+    drive_id: String,
+    location: String,
+    custodian: String,
+    session: State<'_, SessionKey>,
+) -> Result<DriveLocation> {
+    require_unlocked(&session)?;
+    update(&app, |book| {
+        book.check_in(&drive_id, &location, &custodian, Utc::now())
+    })
+}
+
+/// Record that `drive_id` left its location with `custodian`. Requires an
+/// unlocked vault.
+#[tauri::command]
+pub fn drive_check_out(
+    app: AppHandle,
+    drive_id: String,
+    custodian: String,
+    session: State<'_, SessionKey>,
+) -> Result<DriveLocation> {
+    require_unlocked(&session)?;
+    update(&app, |book| {
+        book.check_out(&drive_id, &custodian, Utc::now())
+    })
+}
+
+/// Confirm a checked-in drive is still at its location. Requires an
+/// unlocked vault.
+#[tauri::command]
+pub fn verify_drive_location(
+    app: AppHandle,
+    drive_id: String,
+    session: State<'_, SessionKey>,
+) -> Result<DriveLocation> {
+    require_unlocked(&session)?;
+    update(&app, |book| book.verify(&drive_id, Utc::now()))
+}
+
+/// Drives whose location has not been verified within the configured
+/// window, most overdue first.
+#[tauri::command]
+pub fn drive_location_report(app: AppHandle) -> Result<Vec<StaleLocation>> {
+    Ok(load_book(&app)?.stale(Utc::now()))
+}
+
+/// Change how many days a location verification stays current. Requires an
+/// unlocked vault.
+#[tauri::command]
+pub fn set_location_window(
+    app: AppHandle,
+    days: u32,
+    session: State<'_, SessionKey>,
+) -> Result<u32> {
+    require_unlocked(&session)?;
+    CustodyBook::validate_window(days).map_err(VaultError::InvalidMetadata)?;
+    let mut book = load_book(&app)?;
+    book.window_days = days;
+    save_book(&app, &book)?;
+    Ok(days)
+}
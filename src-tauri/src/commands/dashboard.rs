@@ -0,0 +1,26 @@
+use crate::commands::items::ItemStore;
+use crate::commands::keys::KeyStore;
+use crate::commands::vault::{load_vault_if_needed, UnlockState, VaultMutex};
+use crate::error::Result;
+use crate::models::dashboard::{summarize, DashboardSummary};
+use chrono::Utc;
+use tauri::{AppHandle, State};
+
+/// Everything the dashboard shows in one call: key and item counts, the
+/// newest backup per drive, due rotations, recent use and highlights. While
+/// locked the stores are empty, so only the metadata-backed parts are filled.
+#[tauri::command]
+pub fn get_dashboard_summary(
+    app: AppHandle,
+    state: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    unlock: State<'_, UnlockState>,
+) -> Result<DashboardSummary> {
+    let failed_unlocks = unlock.0.lock().unwrap().failures;
+    let mut vault = state.0.lock().unwrap();
+    load_vault_if_needed(&app, &mut vault);
+    let keys = keystore.0.lock().unwrap();
+    let items = items.0.lock().unwrap();
+    Ok(summarize(&keys, &items, &vault, failed_unlocks, Utc::now()))
+}
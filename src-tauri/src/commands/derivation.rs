@@ -0,0 +1,107 @@
+use crate::commands::keys::{KeyStore, MasterSeed};
+use crate::crypto::fingerprint::KeyFingerprint;
+use crate::crypto::{address, hd_derivation, mldsa87, mnemonic};
+use crate::error::{Result, VaultError};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use zeroize::Zeroizing;
+
+/// Most addresses one preview derives; ML-DSA-87 key generation is not free.
+pub const MAX_PREVIEW_COUNT: u32 = 50;
+
+/// One derived address in a preview.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DerivationPreview {
+    pub index: u32,
+    pub path: String,
+    pub address: String,
+    pub fingerprint: KeyFingerprint,
+    /// The vault key already at this path, if any.
+    pub existing_key_id: Option<String>,
+}
+
+/// Derive the first `count` addresses of `path_template` without storing
+/// anything, to check which paths an external wallet used before importing
+/// its phrase. `mnemonic_or_key_id` is either a BIP39 phrase (with an
+/// optional `passphrase`) or the id of a key in this vault, in which case the
+/// vault's own HD tree is previewed.
+///
+/// Only the ZAP derivation (BLAKE3 over the path, ML-DSA-87 keys) exists in
+/// this vault, so addresses from BIP32 secp256k1 wallets will not match.
+#[tauri::command]
+pub fn preview_derivation(
+    mnemonic_or_key_id: String,
+    path_template: String,
+    count: u32,
+    start: Option<u32>,
+    passphrase: Option<String>,
+    keystore: State<'_, KeyStore>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<Vec<DerivationPreview>> {
+    if count == 0 || count > MAX_PREVIEW_COUNT {
+        return Err(VaultError::InvalidMetadata(format!(
+            "count must be between 1 and {MAX_PREVIEW_COUNT}"
+        )));
+    }
+    let start = start.unwrap_or(0);
+    if start
+        .checked_add(count - 1)
+        .is_none_or(|end| end >= hd_derivation::HARDENED_OFFSET)
+    {
+        return Err(VaultError::InvalidMetadata(
+            "index range is too large".to_string(),
+        ));
+    }
+    hd_derivation::expand_template(&path_template, start)?;
+
+    let source = Zeroizing::new(mnemonic_or_key_id);
+    let passphrase = Zeroizing::new(passphrase.unwrap_or_default());
+    let keys = keystore.0.lock().unwrap();
+    let from_vault = keys.iter().find(|k| k.id == source.trim());
+    let seed: Zeroizing<[u8; 64]> = match from_vault {
+        Some(key) => {
+            if key.metadata.derivation_path.is_empty()
+                || key.metadata.ceremony_id.is_some()
+                || key.metadata.escrow_id.is_some()
+            {
+                return Err(VaultError::InvalidMetadata(format!(
+                    "key {} is not from this vault's HD tree",
+                    key.id
+                )));
+            }
+            let guard = master_seed.0.lock().unwrap();
+            guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+        }
+        None => Zeroizing::new(mnemonic::mnemonic_to_seed_with_passphrase(
+            source.trim(),
+            &passphrase,
+        )?),
+    };
+    let own_tree = from_vault.is_some();
+
+    let mut previews = Vec::with_capacity(count as usize);
+    for index in start..start + count {
+        let path = hd_derivation::expand_template(&path_template, index)?;
+        let derived = Zeroizing::new(hd_derivation::derive_seed_from_master(&seed, &path));
+        let (pk, _sk) = mldsa87::from_seed(&derived);
+        let path = path.to_string();
+        let existing_key_id = own_tree
+            .then(|| {
+                keys.iter().find(|k| {
+                    k.metadata.ceremony_id.is_none()
+                        && k.metadata.escrow_id.is_none()
+                        && k.metadata.derivation_path == path
+                })
+            })
+            .flatten()
+            .map(|k| k.id.clone());
+        previews.push(DerivationPreview {
+            index,
+            address: address::derive_address(pk.as_bytes()),
+            fingerprint: KeyFingerprint::of(pk.as_bytes()),
+            path,
+            existing_key_id,
+        });
+    }
+    Ok(previews)
+}
@@ -0,0 +1,111 @@
+use crate::commands::backup::Drives;
+use crate::commands::drive_seal::{ensure_drive_trusted, not_provisioned, reseal_after_write};
+use crate::commands::keys::{atomic_write, MasterSeed};
+use crate::commands::vault::{persist_vault, verify_password, VaultMutex};
+use crate::drive::image;
+use crate::error::{Result, VaultError};
+use crate::models::drive_image::DriveImageSummary;
+use chrono::Utc;
+use std::path::Path;
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
+
+/// BLAKE3 `derive_key` context for the drive image key. Taken from the seed
+/// rather than the password so images survive a password change.
+const IMAGE_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 drive image key v1";
+
+fn image_key(master_seed: &State<'_, MasterSeed>) -> Result<Zeroizing<[u8; 32]>> {
+    let guard = master_seed.0.lock().unwrap();
+    let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
+    Ok(Zeroizing::new(blake3::derive_key(
+        IMAGE_KEY_CONTEXT,
+        seed.as_slice(),
+    )))
+}
+
+/// Write a backup-set image of `drive_id` to `output_path`: the backup files
+/// on the drive, compressed and encrypted, so they can be copied onto a spare
+/// with `write_backup_set_image`. This is a file-level copy, not a block-level
+/// image of the device. The drive must be provisioned and trusted. Requires
+/// an unlocked vault and its password.
+#[tauri::command(async)]
+pub fn export_backup_set_image(
+    app: AppHandle,
+    drive_id: String,
+    output_path: String,
+    password: String,
+    state: State<'_, VaultMutex>,
+    drives: State<'_, Drives>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<DriveImageSummary> {
+    let key = image_key(&master_seed)?;
+    verify_password(&app, &state, &password)?;
+    let target = Path::new(&output_path);
+    if !target.is_absolute() {
+        return Err(VaultError::InvalidMetadata(
+            "image path must be absolute".to_string(),
+        ));
+    }
+    {
+        let vault = state.0.lock().unwrap();
+        if !vault
+            .provisioned_drives
+            .iter()
+            .any(|d| d.drive_id == drive_id)
+        {
+            return Err(not_provisioned(&drive_id));
+        }
+        ensure_drive_trusted(&vault, &drive_id)?;
+    }
+    let (summary, data) = image::create_image(drives.0.as_ref(), &drive_id, &key, Utc::now())?;
+    atomic_write(target, &data)?;
+    tracing::info!(
+        target: "audit",
+        drive = %drive_id,
+        path = %output_path,
+        backups = summary.backups,
+        blake3 = %summary.image_blake3_hex,
+        "backup set image written"
+    );
+    Ok(summary)
+}
+
+/// Copy the backup set in the image at `image`, written by
+/// `export_backup_set_image` from this vault, onto `target_drive`. The target
+/// must be writable and hold no backups yet; provision it afterwards to seal
+/// it. Requires an unlocked vault and its password.
+#[tauri::command(async)]
+pub fn write_backup_set_image(
+    app: AppHandle,
+    image: String,
+    target_drive: String,
+    password: String,
+    state: State<'_, VaultMutex>,
+    drives: State<'_, Drives>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<DriveImageSummary> {
+    let key = image_key(&master_seed)?;
+    verify_password(&app, &state, &password)?;
+    let data = std::fs::read(&image).map_err(|e| VaultError::Storage(e.to_string()))?;
+    let mut vault = state.0.lock().unwrap();
+    let summary = image::write_image(drives.0.as_ref(), &target_drive, &key, &data)?;
+    // A target that was provisioned while empty has just been written to.
+    if vault
+        .provisioned_drives
+        .iter()
+        .any(|d| d.drive_id == target_drive)
+    {
+        let mut next = vault.clone();
+        reseal_after_write(drives.0.as_ref(), &mut next, &target_drive);
+        persist_vault(&app, &next)?;
+        *vault = next;
+    }
+    tracing::info!(
+        target: "audit",
+        source = %summary.source_drive_id,
+        target = %target_drive,
+        backups = summary.backups,
+        "backup set image restored"
+    );
+    Ok(summary)
+}
@@ -0,0 +1,300 @@
+use crate::commands::backup::Drives;
+use crate::commands::keys::SessionKey;
+use crate::commands::notifications::notify;
+use crate::commands::vault::{load_vault_if_needed, persist_vault, verify_password, VaultMutex};
+use crate::drive::backup::{self, BACKUP_ROOT};
+use crate::drive::{seal, DriveBackend, DriveError};
+use crate::error::{Result, VaultError};
+use crate::i18n::tr;
+use crate::models::backup::BackupVerification;
+use crate::models::drive::DriveInfo;
+use crate::models::drive_seal::{
+    DriveReverification, DriveUntrusted, ProvisionedDrive, DRIVE_SEAL_FILE,
+};
+use crate::models::notification::SecurityEvent;
+use crate::models::vault::VaultState;
+use chrono::Utc;
+use tauri::{AppHandle, Manager, State};
+
+pub(crate) fn not_provisioned(drive_id: &str) -> VaultError {
+    VaultError::InvalidMetadata(format!("drive {drive_id} is not provisioned"))
+}
+
+/// Refuse to restore from a drive flagged as modified outside the app.
+pub(crate) fn ensure_drive_trusted(vault: &VaultState, drive_id: &str) -> Result<()> {
+    if vault
+        .provisioned_drives
+        .iter()
+        .any(|d| d.drive_id == drive_id && d.untrusted.is_some())
+    {
+        return Err(VaultError::PolicyDenied(format!(
+            "drive {drive_id} was modified outside the vault; re-verify it first"
+        )));
+    }
+    Ok(())
+}
+
+/// Roll the seal of `drive_id` after the app wrote to it, if it is a
+/// trusted provisioned drive. The caller persists `vault`. Best effort: if
+/// the seal cannot be written, the next check flags the drive.
+pub(crate) fn reseal_after_write(
+    backend: &dyn DriveBackend,
+    vault: &mut VaultState,
+    drive_id: &str,
+) {
+    let Some(record) = vault
+        .provisioned_drives
+        .iter_mut()
+        .find(|d| d.drive_id == drive_id && d.untrusted.is_none())
+    else {
+        return;
+    };
+    if let Err(e) = seal::reseal(backend, record, Utc::now()) {
+        tracing::warn!(drive = %drive_id, "could not reseal the drive: {e}");
+    }
+}
+
+fn store_record(app: &AppHandle, record: &ProvisionedDrive) -> Result<()> {
+    let state = app.state::<VaultMutex>();
+    let mut vault = state.0.lock().unwrap();
+    let mut next = vault.clone();
+    let Some(slot) = next
+        .provisioned_drives
+        .iter_mut()
+        .find(|d| d.drive_id == record.drive_id)
+    else {
+        return Ok(());
+    };
+    *slot = record.clone();
+    persist_vault(app, &next)?;
+    *vault = next;
+    Ok(())
+}
+
+/// Check an attached drive against its seal and record the outcome. A
+/// drive showing changes is flagged untrusted; a clean, writable one is
+/// resealed, so a copy taken while it was away no longer matches. `None`
+/// if the drive is not provisioned.
+pub(crate) fn check_attached(
+    app: &AppHandle,
+    backend: &dyn DriveBackend,
+    drive: &DriveInfo,
+) -> Result<Option<ProvisionedDrive>> {
+    let (record, notifications, locale) = {
+        let state = app.state::<VaultMutex>();
+        let vault = state.0.lock().unwrap();
+        let record = vault
+            .provisioned_drives
+            .iter()
+            .find(|d| d.drive_id == drive.id)
+            .cloned();
+        (record, vault.notifications.clone(), vault.locale())
+    };
+    let Some(mut record) = record else {
+        return Ok(None);
+    };
+    let now = Utc::now();
+    let anomalies = seal::check(backend, drive, &record)?;
+    record.checked_at = Some(now);
+    if !anomalies.is_empty() {
+        let first = record.untrusted.is_none();
+        record.untrusted = Some(DriveUntrusted {
+            detected_at: record.untrusted.as_ref().map_or(now, |u| u.detected_at),
+            anomalies: anomalies.clone(),
+        });
+        if first {
+            let detail: Vec<String> = anomalies.iter().map(|a| a.describe(locale)).collect();
+            tracing::warn!(
+                target: "audit",
+                drive = %drive.id,
+                anomalies = ?anomalies,
+                "drive modified outside the vault"
+            );
+            notify(
+                app,
+                &notifications,
+                locale,
+                SecurityEvent::DriveModifiedExternally,
+                &tr(
+                    locale,
+                    "drive-modified-externally-body",
+                    &[
+                        ("drive", drive.id.as_str().into()),
+                        ("anomalies", detail.join("; ").into()),
+                    ],
+                ),
+            );
+        }
+    } else if record.untrusted.is_none() && drive.is_writable() {
+        if let Err(e) = seal::reseal(backend, &mut record, now) {
+            tracing::warn!(drive = %drive.id, "could not reseal the drive: {e}");
+        }
+    }
+    store_record(app, &record)?;
+    Ok(Some(record))
+}
+
+#[tauri::command]
+pub fn list_provisioned_drives(
+    app: AppHandle,
+    state: State<'_, VaultMutex>,
+) -> Result<Vec<ProvisionedDrive>> {
+    let mut vault = state.0.lock().unwrap();
+    load_vault_if_needed(&app, &mut vault);
+    Ok(vault.provisioned_drives.clone())
+}
+
+/// Seal an encrypted drive, mounted writable, so later changes made outside
+/// the app are noticed when it is attached. Provisioning again starts over
+/// with a trusted drive. Requires an unlocked vault.
+#[tauri::command]
+pub fn provision_drive(
+    app: AppHandle,
+    drive_id: String,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    drives: State<'_, Drives>,
+) -> Result<ProvisionedDrive> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    let drive = drives.0.drive(&drive_id)?;
+    if !drive.encrypted {
+        return Err(VaultError::InvalidMetadata(format!(
+            "drive {drive_id} is not encrypted"
+        )));
+    }
+    if !drive.is_writable() {
+        return Err(DriveError::NotReady(drive_id).into());
+    }
+    let record = seal::provision(drives.0.as_ref(), &drive, Utc::now())?;
+    let mut vault = state.0.lock().unwrap();
+    let mut next = vault.clone();
+    next.provisioned_drives.retain(|d| d.drive_id != drive_id);
+    next.provisioned_drives.push(record.clone());
+    persist_vault(&app, &next)?;
+    *vault = next;
+    tracing::info!(
+        target: "audit",
+        drive = %drive_id,
+        luks_uuid = ?record.luks_uuid,
+        "drive provisioned"
+    );
+    Ok(record)
+}
+
+/// Stop tracking a drive and remove its seal if it is attached. Requires
+/// the vault password.
+#[tauri::command]
+pub fn unprovision_drive(
+    app: AppHandle,
+    password: String,
+    drive_id: String,
+    state: State<'_, VaultMutex>,
+    drives: State<'_, Drives>,
+) -> Result<()> {
+    verify_password(&app, &state, &password)?;
+    let mut vault = state.0.lock().unwrap();
+    let mut next = vault.clone();
+    let before = next.provisioned_drives.len();
+    next.provisioned_drives.retain(|d| d.drive_id != drive_id);
+    if next.provisioned_drives.len() == before {
+        return Err(not_provisioned(&drive_id));
+    }
+    persist_vault(&app, &next)?;
+    *vault = next;
+    if let Err(e) = drives.0.remove_file(&drive_id, DRIVE_SEAL_FILE) {
+        tracing::warn!(drive = %drive_id, "could not remove the drive seal: {e}");
+    }
+    tracing::warn!(target: "audit", drive = %drive_id, "drive unprovisioned");
+    Ok(())
+}
+
+/// Check an attached provisioned drive now rather than waiting for it to
+/// be plugged in again.
+#[tauri::command]
+pub fn check_provisioned_drive(
+    app: AppHandle,
+    drive_id: String,
+    drives: State<'_, Drives>,
+) -> Result<ProvisionedDrive> {
+    let drive = drives.0.drive(&drive_id)?;
+    check_attached(&app, drives.0.as_ref(), &drive)?.ok_or_else(|| not_provisioned(&drive_id))
+}
+
+/// Verify every backup on an untrusted drive. If all pass, the drive is
+/// trusted again, takes its current LUKS header as the expected one and is
+/// resealed. Reads every backup in full, so it runs off the main thread.
+/// Requires the vault password.
+#[tauri::command(async)]
+pub fn reverify_drive(
+    app: AppHandle,
+    password: String,
+    drive_id: String,
+    state: State<'_, VaultMutex>,
+    drives: State<'_, Drives>,
+) -> Result<DriveReverification> {
+    verify_password(&app, &state, &password)?;
+    let backend = drives.0.as_ref();
+    let drive = backend.drive(&drive_id)?;
+    let mut record = state
+        .0
+        .lock()
+        .unwrap()
+        .provisioned_drives
+        .iter()
+        .find(|d| d.drive_id == drive_id)
+        .cloned()
+        .ok_or_else(|| not_provisioned(&drive_id))?;
+
+    let mut backups = Vec::new();
+    for backup_id in backend.list_dir(&drive_id, BACKUP_ROOT)? {
+        let verification =
+            backup::verify_backup(backend, &drive_id, &backup_id).unwrap_or_else(|e| {
+                tracing::warn!(drive = %drive_id, backup = %backup_id, "backup unreadable: {e}");
+                BackupVerification {
+                    backup_id: backup_id.clone(),
+                    ok: false,
+                    files: Vec::new(),
+                    instance_signature_ok: None,
+                }
+            });
+        backups.push(verification);
+    }
+    let trusted = backups.iter().all(|b| b.ok);
+    let now = Utc::now();
+    if trusted {
+        record.luks_uuid = drive.luks_uuid.clone();
+        record.label = drive.label.clone();
+        seal::reseal(backend, &mut record, now)?;
+        record.untrusted = None;
+    }
+    record.checked_at = Some(now);
+
+    let mut vault = state.0.lock().unwrap();
+    let mut next = vault.clone();
+    for b in &backups {
+        next.record_backup_verification(&drive_id, &b.backup_id, b.ok, now);
+    }
+    if let Some(slot) = next
+        .provisioned_drives
+        .iter_mut()
+        .find(|d| d.drive_id == drive_id)
+    {
+        *slot = record.clone();
+    }
+    persist_vault(&app, &next)?;
+    *vault = next;
+    tracing::warn!(
+        target: "audit",
+        drive = %drive_id,
+        backups = backups.len(),
+        trusted,
+        "drive re-verified"
+    );
+    Ok(DriveReverification {
+        drive: record,
+        backups,
+        trusted,
+    })
+}
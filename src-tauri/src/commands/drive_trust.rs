@@ -0,0 +1,50 @@
+use crate::commands::backup::Drives;
+use crate::commands::keys::SessionKey;
+use crate::commands::vault::{persist_vault, VaultMutex};
+use crate::error::{Result, VaultError};
+use crate::models::drive::DriveTrust;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub fn get_drive_trust(vault: State<'_, VaultMutex>) -> Result<Vec<DriveTrust>> {
+    Ok(vault.0.lock().unwrap().drive_trust.clone())
+}
+
+/// Set what detaching `trust.drive_id` does, replacing its earlier
+/// settings. A drive with neither rule set is dropped from the list. A
+/// presence token must be attached when it is set, or the vault would lock
+/// at once. Requires an unlocked vault.
+#[tauri::command]
+pub fn set_drive_trust(
+    app: AppHandle,
+    trust: DriveTrust,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    drives: State<'_, Drives>,
+) -> Result<Vec<DriveTrust>> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    let mut trust = trust;
+    match drives.0.drive(&trust.drive_id) {
+        Ok(drive) => trust.label = drive.label,
+        Err(e) if trust.presence_token => return Err(e.into()),
+        Err(_) => {}
+    }
+    let mut vault = vault.0.lock().unwrap();
+    let mut next = vault.clone();
+    next.drive_trust.retain(|t| t.drive_id != trust.drive_id);
+    if trust.presence_token || trust.lock_on_unsynced_backup {
+        next.drive_trust.push(trust.clone());
+    }
+    persist_vault(&app, &next)?;
+    *vault = next;
+    tracing::info!(
+        target: "audit",
+        drive = %trust.drive_id,
+        presence_token = trust.presence_token,
+        lock_on_unsynced_backup = trust.lock_on_unsynced_backup,
+        "drive trust settings changed"
+    );
+    Ok(vault.drive_trust.clone())
+}
@@ -0,0 +1,270 @@
+use crate::commands::items::ItemStore;
+use crate::commands::keys::{
+    atomic_write, keys_file_path, session_key, KeyStore, MasterSeed, SessionKey,
+};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::vault::VaultMutex;
+use crate::crypto::emergency;
+use crate::crypto::mldsa87::{self, SecretKey};
+use crate::error::{Result, VaultError};
+use crate::models::emergency::{
+    AccessRequest, EmergencyContact, EmergencyContactIdentity, EmergencyGrant,
+    EmergencyGrantPublic, EscrowContents, EscrowPackage,
+};
+use crate::models::rate_limit::SensitiveOp;
+use chrono::Utc;
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
+
+/// Emergency grants live in plaintext metadata next to `vault.json`: they hold
+/// only public keys, signatures and the package sealed under the data key,
+/// and must be readable while the vault is locked (that is when a contact
+/// requests access).
+pub const EMERGENCY_FILE: &str = "emergency.json";
+
+fn now() -> u64 {
+    Utc::now().timestamp() as u64
+}
+
+fn load_grants(app: &AppHandle) -> Result<Vec<EmergencyGrant>> {
+    let path = keys_file_path(app, EMERGENCY_FILE)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+fn save_grants(app: &AppHandle, grants: &[EmergencyGrant]) -> Result<()> {
+    let path = keys_file_path(app, EMERGENCY_FILE)?;
+    atomic_write(&path, &serde_json::to_vec_pretty(grants)?)
+}
+
+fn find_grant<'a>(grants: &'a mut [EmergencyGrant], id: &str) -> Result<&'a mut EmergencyGrant> {
+    grants
+        .iter_mut()
+        .find(|g| g.id == id)
+        .ok_or_else(|| VaultError::KeyNotFound(id.to_string()))
+}
+
+fn to_public(grant: &EmergencyGrant, now: u64) -> EmergencyGrantPublic {
+    EmergencyGrantPublic {
+        id: grant.id.clone(),
+        contact: grant.contact.clone(),
+        waiting_period_secs: grant.waiting_period_secs,
+        created_at: grant.created_at,
+        status: emergency::status(grant, now),
+        veto_count: grant.vetoes.len(),
+    }
+}
+
+/// The owner grant-signing key; requires an unlocked HD vault.
+fn owner_secret(master_seed: &State<'_, MasterSeed>) -> Result<SecretKey> {
+    let guard = master_seed.0.lock().unwrap();
+    let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
+    Ok(emergency::owner_keypair(seed).1)
+}
+
+/// Move the packages of grants from before sealing under the data key, and
+/// re-sign those grants. Returns whether any grant changed.
+fn seal_legacy_grants(
+    grants: &mut [EmergencyGrant],
+    data_key: &[u8; 32],
+    owner_sk: &SecretKey,
+) -> Result<bool> {
+    let mut changed = false;
+    for grant in grants.iter_mut().filter(|g| g.sealed_package.is_none()) {
+        // A grant that no longer verifies is left as it is; release rejects it.
+        if emergency::verify_grant(grant).is_err() {
+            continue;
+        }
+        let Some(package) = grant.package.take() else {
+            continue;
+        };
+        grant.sealed_package = Some(emergency::seal_package(data_key, &package)?);
+        grant.grant_signature_hex =
+            mldsa87::sign(owner_sk, &emergency::grant_message(grant))?.to_hex();
+        changed = true;
+    }
+    Ok(changed)
+}
+
+/// Create a recovery-contact identity. Run this on the *contact's* install and
+/// keep the returned secrets there; only `contact` is given to the vault owner.
+#[tauri::command]
+pub fn emergency_generate_contact(name: String) -> Result<EmergencyContactIdentity> {
+    Ok(emergency::generate_contact_identity(&name))
+}
+
+/// Designate `contact` for emergency access: seal the master seed plus a
+/// snapshot of the keystore and item store to their ML-KEM key, keep that
+/// package encrypted under the data key, and sign the grant terms with the
+/// owner key. The package is only handed out after the contact requests
+/// access and `waiting_period_secs` passes without a veto.
+#[tauri::command]
+pub fn emergency_create_grant(
+    app: AppHandle,
+    contact: EmergencyContact,
+    waiting_period_secs: u64,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<EmergencyGrantPublic> {
+    emergency::validate_contact(&contact)?;
+    emergency::validate_waiting_period(waiting_period_secs)?;
+    let data_key = session_key(&session)?;
+
+    let (owner_pk, owner_sk, master_seed_hex) = {
+        let guard = master_seed.0.lock().unwrap();
+        let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
+        let (pk, sk) = emergency::owner_keypair(seed);
+        (pk, sk, hex::encode(seed.as_ref()))
+    };
+    let contents = EscrowContents {
+        master_seed_hex,
+        keys: keystore.0.lock().unwrap().clone(),
+        items: items.0.lock().unwrap().clone(),
+    };
+    let plaintext = Zeroizing::new(serde_json::to_vec(&contents)?);
+    let package = emergency::seal_escrow(&contact, &plaintext)?;
+    let sealed_package = emergency::seal_package(&data_key, &package)?;
+
+    let now = now();
+    let mut grant = EmergencyGrant {
+        id: uuid::Uuid::new_v4().to_string(),
+        contact,
+        waiting_period_secs,
+        created_at: now,
+        owner_public_hex: owner_pk.to_hex(),
+        grant_signature_hex: String::new(),
+        sealed_package: Some(sealed_package),
+        package: None,
+        request: None,
+        vetoes: Vec::new(),
+    };
+    grant.grant_signature_hex =
+        mldsa87::sign(&owner_sk, &emergency::grant_message(&grant))?.to_hex();
+
+    let mut grants = load_grants(&app)?;
+    seal_legacy_grants(&mut grants, &data_key, &owner_sk)?;
+    grants.push(grant.clone());
+    save_grants(&app, &grants)?;
+    Ok(to_public(&grant, now))
+}
+
+/// List grants with their current status. Works while locked.
+#[tauri::command]
+pub fn emergency_list_grants(app: AppHandle) -> Result<Vec<EmergencyGrantPublic>> {
+    let now = now();
+    Ok(load_grants(&app)?
+        .iter()
+        .map(|g| to_public(g, now))
+        .collect())
+}
+
+/// Contact-side: sign an access request for `grant_id` with the contact's
+/// ML-DSA secret, to be submitted on the owner's install.
+#[tauri::command]
+pub fn emergency_sign_request(grant_id: String, sign_secret_hex: String) -> Result<AccessRequest> {
+    let sk = SecretKey::from_hex(&sign_secret_hex)?;
+    Ok(emergency::sign_request(&sk, &grant_id, now())?)
+}
+
+/// Submit a signed access request, starting the waiting period. Works while
+/// the vault is locked.
+#[tauri::command]
+pub fn emergency_request_access(
+    app: AppHandle,
+    grant_id: String,
+    request: AccessRequest,
+) -> Result<EmergencyGrantPublic> {
+    let now = now();
+    let mut grants = load_grants(&app)?;
+    let grant = find_grant(&mut grants, &grant_id)?;
+    emergency::record_request(grant, request, now)?;
+    let public = to_public(grant, now);
+    save_grants(&app, &grants)?;
+    Ok(public)
+}
+
+/// Owner veto of a pending request. Requires the unlocked vault, which is what
+/// proves the owner is still around.
+#[tauri::command]
+pub fn emergency_veto_request(
+    app: AppHandle,
+    grant_id: String,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<EmergencyGrantPublic> {
+    let owner_sk = owner_secret(&master_seed)?;
+    let data_key = session_key(&session)?;
+    let now = now();
+    let mut grants = load_grants(&app)?;
+    seal_legacy_grants(&mut grants, &data_key, &owner_sk)?;
+    let grant = find_grant(&mut grants, &grant_id)?;
+    emergency::apply_veto(grant, &owner_sk, now)?;
+    let public = to_public(grant, now);
+    save_grants(&app, &grants)?;
+    Ok(public)
+}
+
+/// Delete a grant entirely. Requires the unlocked vault.
+#[tauri::command]
+pub fn emergency_revoke_grant(
+    app: AppHandle,
+    grant_id: String,
+    master_seed: State<'_, MasterSeed>,
+    vault: State<'_, VaultMutex>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<()> {
+    enforce(&vault, &limiter, SensitiveOp::Delete)?;
+    owner_secret(&master_seed)?;
+    let mut grants = load_grants(&app)?;
+    let before = grants.len();
+    grants.retain(|g| g.id != grant_id);
+    if grants.len() == before {
+        return Err(VaultError::KeyNotFound(grant_id));
+    }
+    save_grants(&app, &grants)
+}
+
+/// Hand out the package once the waiting period has elapsed without a veto.
+/// It is decrypted from under the data key, so this needs an unlocked vault;
+/// what is returned is still encrypted to the contact's ML-KEM key.
+#[tauri::command]
+pub fn emergency_release_package(
+    app: AppHandle,
+    grant_id: String,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<EscrowPackage> {
+    let owner_sk = owner_secret(&master_seed)?;
+    let data_key = session_key(&session)?;
+    let mut grants = load_grants(&app)?;
+    if seal_legacy_grants(&mut grants, &data_key, &owner_sk)? {
+        save_grants(&app, &grants)?;
+    }
+    let grant = find_grant(&mut grants, &grant_id)?;
+    emergency::check_releasable(grant, now())?;
+    let package = emergency::unseal_package(&data_key, grant)?;
+    tracing::warn!(
+        target: "audit",
+        grant_id = %grant_id,
+        contact = %grant.contact.name,
+        "emergency package released"
+    );
+    Ok(package)
+}
+
+/// Contact-side: open a released package with the contact's ML-KEM seed.
+#[tauri::command]
+pub fn emergency_open_package(
+    package: EscrowPackage,
+    kem_seed_hex: String,
+) -> Result<EscrowContents> {
+    let seed =
+        Zeroizing::new(hex::decode(&kem_seed_hex).map_err(|e| VaultError::Storage(e.to_string()))?);
+    let plaintext = emergency::open_escrow(&seed, &package)?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
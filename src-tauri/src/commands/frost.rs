@@ -0,0 +1,345 @@
+use crate::commands::consent::require_consent;
+use crate::commands::keys::{atomic_write, load_sealed, save_sealed, session_key, SessionKey};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::password_policy::enforce_password_strength;
+use crate::commands::spending::enforce_spending;
+use crate::commands::tx_review::confirm_reviewed;
+use crate::commands::vault::VaultMutex;
+use crate::crypto::frost;
+use crate::error::{Result, VaultError};
+use crate::models::consent::ConsentOperation;
+use crate::models::frost::{
+    FrostCommitment, FrostDkgRound1, FrostDkgRound2, FrostGroup, FrostPendingNonces,
+    FrostShareExport, FrostShareInfo, FrostSignature, FrostSignatureShare, FrostSigningPackage,
+    FrostStore,
+};
+use crate::models::rate_limit::SensitiveOp;
+use crate::models::worksheet::CeremonyWorksheet;
+use crate::report::ceremony as worksheet;
+use chrono::Utc;
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
+
+/// FROST key generation state, key shares and unused nonces, encrypted with
+/// the session key next to `vault.json`. Each profile has its own, so shares
+/// of one group can live in different profiles.
+pub const FROST_FILE: &str = "frost.json";
+
+pub(crate) fn load_store(app: &AppHandle, key: &[u8; 32]) -> Result<FrostStore> {
+    load_sealed(app, FROST_FILE, key)
+}
+
+fn save_store(app: &AppHandle, key: &[u8; 32], store: &FrostStore) -> Result<()> {
+    save_sealed(app, FROST_FILE, key, store)
+}
+
+/// Start key generation as participant `index` of a `threshold`-of-
+/// `participants` group. Every participant must use the same `group_id`.
+/// Returns the round-1 package to broadcast. Requires an unlocked vault.
+#[tauri::command]
+pub fn frost_dkg_begin(
+    app: AppHandle,
+    group_id: String,
+    threshold: u16,
+    participants: u16,
+    index: u16,
+    session: State<'_, SessionKey>,
+) -> Result<FrostDkgRound1> {
+    let key = session_key(&session)?;
+    let mut store = load_store(&app, &key)?;
+    if store.shares.iter().any(|s| s.group.group_id == group_id)
+        || store.pending.iter().any(|p| p.package.group_id == group_id)
+    {
+        return Err(VaultError::KeyAlreadyExists(group_id));
+    }
+    let secret = frost::dkg_begin(&group_id, threshold, participants, index)?;
+    let package = secret.package.clone();
+    store.pending.push(secret);
+    save_store(&app, &key, &store)?;
+    tracing::info!(
+        target: "audit",
+        group_id = %group_id,
+        index,
+        threshold,
+        participants,
+        "FROST key generation started"
+    );
+    Ok(package)
+}
+
+/// With every participant's round-1 package, return the encrypted shares
+/// for each peer. Requires an unlocked vault.
+#[tauri::command]
+pub fn frost_dkg_round2(
+    app: AppHandle,
+    group_id: String,
+    round1: Vec<FrostDkgRound1>,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<FrostDkgRound2>> {
+    let key = session_key(&session)?;
+    let mut store = load_store(&app, &key)?;
+    let secret = store
+        .pending
+        .iter_mut()
+        .find(|p| p.package.group_id == group_id)
+        .ok_or_else(|| VaultError::KeyNotFound(group_id.clone()))?;
+    let packages = frost::dkg_round2(secret, &round1)?;
+    save_store(&app, &key, &store)?;
+    Ok(packages)
+}
+
+/// Finish key generation with the round-2 packages addressed to this
+/// participant and keep the resulting share. Requires an unlocked vault.
+#[tauri::command]
+pub fn frost_dkg_finish(
+    app: AppHandle,
+    group_id: String,
+    round2: Vec<FrostDkgRound2>,
+    session: State<'_, SessionKey>,
+) -> Result<FrostGroup> {
+    let key = session_key(&session)?;
+    let mut store = load_store(&app, &key)?;
+    let pos = store
+        .pending
+        .iter()
+        .position(|p| p.package.group_id == group_id)
+        .ok_or_else(|| VaultError::KeyNotFound(group_id.clone()))?;
+    let share = frost::dkg_finish(&store.pending[pos], &round2)?;
+    let group = share.group.clone();
+    store.pending.remove(pos);
+    store.shares.push(share);
+    save_store(&app, &key, &store)?;
+    tracing::info!(
+        target: "audit",
+        group_id = %group_id,
+        output_key = %group.taproot_output_key_hex,
+        "FROST key generation finished"
+    );
+    Ok(group)
+}
+
+/// The FROST shares this vault holds, without their secrets.
+#[tauri::command]
+pub fn frost_list_shares(
+    app: AppHandle,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<FrostShareInfo>> {
+    let key = session_key(&session)?;
+    Ok(load_store(&app, &key)?
+        .shares
+        .iter()
+        .map(|s| FrostShareInfo {
+            group: s.group.clone(),
+            index: s.index,
+        })
+        .collect())
+}
+
+/// Commit to fresh nonces for the next signature with `group_id`'s share and
+/// return the commitment for the coordinator. Requires an unlocked vault.
+#[tauri::command]
+pub fn frost_commit(
+    app: AppHandle,
+    group_id: String,
+    session: State<'_, SessionKey>,
+) -> Result<FrostCommitment> {
+    let key = session_key(&session)?;
+    let mut store = load_store(&app, &key)?;
+    let share = store
+        .shares
+        .iter()
+        .find(|s| s.group.group_id == group_id)
+        .ok_or_else(|| VaultError::KeyNotFound(group_id.clone()))?;
+    let nonces = frost::commit(share);
+    let commitment = nonces.commitment.clone();
+    store.nonces.push(FrostPendingNonces { group_id, nonces });
+    save_store(&app, &key, &store)?;
+    Ok(commitment)
+}
+
+/// Sign `package` with this vault's share. The nonces behind this vault's
+/// commitment are deleted before the partial signature is returned, so a
+/// package can never be signed twice with them. `summary_hash_hex` is the
+/// hash `review_transaction` returned for the package's sighash. Requires an
+/// unlocked vault.
+#[tauri::command]
+pub fn frost_sign(
+    app: AppHandle,
+    package: FrostSigningPackage,
+    summary_hash_hex: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<FrostSignatureShare> {
+    enforce(&vault, &limiter, SensitiveOp::Decrypt)?;
+    let message = hex::decode(&package.message_hex)
+        .map_err(|e| VaultError::InvalidMetadata(e.to_string()))?;
+    let summary = confirm_reviewed(&app, &session, None, &message, &summary_hash_hex)?;
+    enforce_spending(&app, &session, &package.group_id, &summary)?;
+    let key = session_key(&session)?;
+    let mut store = load_store(&app, &key)?;
+    let share = store
+        .shares
+        .iter()
+        .find(|s| s.group.group_id == package.group_id)
+        .cloned()
+        .ok_or_else(|| VaultError::KeyNotFound(package.group_id.clone()))?;
+    let pos = store
+        .nonces
+        .iter()
+        .position(|n| {
+            n.group_id == package.group_id && package.commitments.contains(&n.nonces.commitment)
+        })
+        .ok_or_else(|| {
+            VaultError::InvalidMetadata(
+                "no unused nonces match this signing package; commit again".to_string(),
+            )
+        })?;
+    let pending = store.nonces.remove(pos);
+    save_store(&app, &key, &store)?;
+    let partial = frost::sign(&share, &pending.nonces, &package)?;
+    tracing::info!(
+        target: "audit",
+        group_id = %package.group_id,
+        index = share.index,
+        message = %package.message_hex,
+        "FROST signature share produced"
+    );
+    Ok(partial)
+}
+
+/// Coordinator: verify the partial signatures and assemble the taproot
+/// signature. Needs only the public group, so any installation can run it.
+#[tauri::command]
+pub fn frost_aggregate(
+    group: FrostGroup,
+    package: FrostSigningPackage,
+    shares: Vec<FrostSignatureShare>,
+) -> Result<FrostSignature> {
+    Ok(frost::aggregate(&group, &package, &shares)?)
+}
+
+/// Write `group_id`'s share to `path`, sealed under `password`, for import
+/// into another profile or installation. The share stays in this vault;
+/// delete one copy to keep the group's shares apart. Requires a
+/// `consent_token` from `request_consent` and an unlocked vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn frost_export_share(
+    app: AppHandle,
+    group_id: String,
+    path: String,
+    password: String,
+    consent_token: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<FrostShareInfo> {
+    enforce(&vault, &limiter, SensitiveOp::Export)?;
+    enforce_password_strength(&vault.0.lock().unwrap(), &password)?;
+    let password = Zeroizing::new(password);
+    let key = session_key(&session)?;
+    require_consent(
+        &app,
+        &consent_token,
+        &ConsentOperation::FrostShareExport {
+            group_id: group_id.clone(),
+            path: path.clone(),
+        },
+    )?;
+    let store = load_store(&app, &key)?;
+    let share = store
+        .shares
+        .iter()
+        .find(|s| s.group.group_id == group_id)
+        .ok_or_else(|| VaultError::KeyNotFound(group_id.clone()))?;
+    let export = frost::export_share(share, &password, Utc::now())?;
+    atomic_write(
+        std::path::Path::new(&path),
+        &serde_json::to_vec_pretty(&export)?,
+    )?;
+    tracing::info!(
+        target: "audit",
+        group_id = %group_id,
+        index = share.index,
+        "FROST share exported"
+    );
+    Ok(FrostShareInfo {
+        group: share.group.clone(),
+        index: share.index,
+    })
+}
+
+/// Import a share written by [`frost_export_share`]. Requires an unlocked
+/// vault that holds no share of the same group.
+#[tauri::command]
+pub fn frost_import_share(
+    app: AppHandle,
+    path: String,
+    password: String,
+    session: State<'_, SessionKey>,
+) -> Result<FrostShareInfo> {
+    let password = Zeroizing::new(password);
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    let export: FrostShareExport = serde_json::from_slice(&data)?;
+    let share = frost::import_share(&export, &password)?;
+    let key = session_key(&session)?;
+    let mut store = load_store(&app, &key)?;
+    if store
+        .shares
+        .iter()
+        .any(|s| s.group.group_id == share.group.group_id)
+    {
+        return Err(VaultError::KeyAlreadyExists(share.group.group_id.clone()));
+    }
+    let info = FrostShareInfo {
+        group: share.group.clone(),
+        index: share.index,
+    };
+    store.shares.push(share);
+    save_store(&app, &key, &store)?;
+    tracing::info!(
+        target: "audit",
+        group_id = %info.group.group_id,
+        index = info.index,
+        "FROST share imported"
+    );
+    Ok(info)
+}
+
+/// Write a printable worksheet of `group_id`'s key generation to `path` as a
+/// PDF: each participant's verifying share fingerprint, the group and output
+/// key fingerprints, and a signature line per participant. Returns the
+/// worksheet. Requires an unlocked vault holding a share of the group.
+#[tauri::command]
+pub fn frost_export_worksheet(
+    app: AppHandle,
+    group_id: String,
+    path: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<CeremonyWorksheet> {
+    let key = session_key(&session)?;
+    let target = std::path::Path::new(&path);
+    if !target.is_absolute() {
+        return Err(VaultError::InvalidMetadata(
+            "worksheet path must be absolute".to_string(),
+        ));
+    }
+    let store = load_store(&app, &key)?;
+    let share = store
+        .shares
+        .iter()
+        .find(|s| s.group.group_id == group_id)
+        .ok_or_else(|| VaultError::KeyNotFound(group_id.clone()))?;
+    let locale = vault.0.lock().unwrap().locale();
+    let sheet = worksheet::frost_group(&share.group, share.index, locale, Utc::now());
+    atomic_write(target, &worksheet::render(&sheet, locale))?;
+    tracing::info!(
+        target: "audit",
+        group_id = %group_id,
+        path = %path,
+        "FROST worksheet written"
+    );
+    Ok(sheet)
+}
@@ -0,0 +1,162 @@
+use crate::commands::items::{load_items, ItemStore};
+use crate::commands::keys::{load_keys, KeyStore, MasterSeed, SessionKey};
+use crate::commands::vault::VaultMutex;
+use crate::crypto::{address, hd_derivation, mldsa87, passkey, ssh, wireguard};
+use crate::error::{Result, VaultError};
+use crate::models::health::{HealthEntryKind, HealthIssue, HealthProblem, VaultHealthReport};
+use crate::models::item::{ItemPayload, VaultItem};
+use crate::models::key::KeyEntry;
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
+
+fn key_issue(entry: &KeyEntry, problem: HealthProblem, detail: String) -> HealthIssue {
+    HealthIssue {
+        kind: HealthEntryKind::Key,
+        id: entry.id.clone(),
+        label: entry.metadata.label.clone(),
+        problem,
+        detail,
+    }
+}
+
+/// Check one keystore entry: the secret decodes, re-derives the recorded
+/// public key and address, and (for HD keys, when the master seed is given)
+/// matches what the recorded derivation path yields. Ceremony and escrowed
+/// keys come from another seed, so their path is not re-derived. Pure (no
+/// I/O) to keep it unit-testable.
+pub fn check_key(entry: &KeyEntry, master_seed: Option<&[u8; 64]>) -> Option<HealthIssue> {
+    let seed: Option<Zeroizing<[u8; mldsa87::SEED_SIZE]>> =
+        hex::decode(&entry.encrypted_secret_hex)
+            .ok()
+            .and_then(|b| b.as_slice().try_into().ok())
+            .map(Zeroizing::new);
+    let Some(seed) = seed else {
+        return Some(key_issue(
+            entry,
+            HealthProblem::UndecodableSecret,
+            format!("secret is not {} bytes of hex", mldsa87::SEED_SIZE),
+        ));
+    };
+    let (pk, _) = mldsa87::from_seed(&seed);
+    if pk.to_hex() != entry.public_key_hex {
+        return Some(key_issue(
+            entry,
+            HealthProblem::PublicKeyMismatch,
+            "secret does not produce the stored public key".to_string(),
+        ));
+    }
+    let addr = address::derive_address(pk.as_bytes());
+    if addr != entry.metadata.address {
+        return Some(key_issue(
+            entry,
+            HealthProblem::AddressMismatch,
+            format!("public key derives to {addr}"),
+        ));
+    }
+
+    let m = &entry.metadata;
+    let path = &m.derivation_path;
+    let master = master_seed
+        .filter(|_| !path.is_empty() && m.ceremony_id.is_none() && m.escrow_id.is_none())?;
+    let derived = match hd_derivation::KeyPath::parse(path) {
+        Ok(p) => Zeroizing::new(hd_derivation::derive_seed_from_master(master, &p)),
+        Err(e) => {
+            return Some(key_issue(
+                entry,
+                HealthProblem::DerivationMismatch,
+                format!("derivation path {path} does not parse: {e}"),
+            ))
+        }
+    };
+    (*derived != *seed).then(|| {
+        key_issue(
+            entry,
+            HealthProblem::DerivationMismatch,
+            format!("master seed at {path} yields a different key"),
+        )
+    })
+}
+
+/// Check one vault item's private key against its recorded public key.
+/// Pure (no I/O) to keep it unit-testable.
+pub fn check_item(item: &VaultItem) -> Option<HealthIssue> {
+    let (problem, detail) = match &item.payload {
+        ItemPayload::SshKey(k) => match ssh::fingerprint_of_private(&k.private_key_openssh) {
+            Err(e) => (HealthProblem::UndecodableSecret, e.to_string()),
+            Ok(fp) if fp != k.fingerprint => (
+                HealthProblem::PublicKeyMismatch,
+                format!("private key fingerprint is {fp}"),
+            ),
+            Ok(_) => return None,
+        },
+        ItemPayload::WireGuard(wg) => match wireguard::public_key_from_private(&wg.private_key) {
+            Err(e) => (HealthProblem::UndecodableSecret, e.to_string()),
+            Ok(pk) if pk != wg.public_key => (
+                HealthProblem::PublicKeyMismatch,
+                "private key does not produce the stored public key".to_string(),
+            ),
+            Ok(_) => return None,
+        },
+        ItemPayload::Passkey(pk) => match passkey::public_key_cose(pk.algorithm, &pk.private_key) {
+            Err(e) => (HealthProblem::UndecodableSecret, e.to_string()),
+            Ok(cose) if cose != pk.public_key_cose => (
+                HealthProblem::PublicKeyMismatch,
+                "private key does not produce the registered public key".to_string(),
+            ),
+            Ok(_) => return None,
+        },
+        // A bare token or plugin item has nothing to check it against.
+        ItemPayload::ApiToken(_) | ItemPayload::Plugin(_) => return None,
+    };
+    Some(HealthIssue {
+        kind: HealthEntryKind::Item,
+        id: item.id.clone(),
+        label: item.label.clone(),
+        problem,
+        detail,
+    })
+}
+
+/// Check every key and item for corruption or drift without returning any
+/// secret. The stores are re-read and decrypted from disk, so damage the
+/// unlocked session has not noticed yet is caught too; a store that no
+/// longer decrypts is reported and its in-memory copy checked instead.
+#[tauri::command]
+pub fn verify_all_keys(
+    app: AppHandle,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<VaultHealthReport> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let (keys_file, items_file) = {
+        let v = vault.0.lock().unwrap();
+        (v.keys_file.clone(), v.items_file.clone())
+    };
+
+    let mut report = VaultHealthReport::default();
+    let keys = load_keys(&app, &keys_file, &session_key).unwrap_or_else(|_| {
+        report.unreadable_stores.push(keys_file.clone());
+        keystore.0.lock().unwrap().clone()
+    });
+    let vault_items = load_items(&app, &items_file, &session_key).unwrap_or_else(|_| {
+        report.unreadable_stores.push(items_file.clone());
+        items.0.lock().unwrap().clone()
+    });
+
+    let seed = master_seed.0.lock().unwrap().clone();
+    report.keys_checked = keys.len();
+    report.items_checked = vault_items.len();
+    report
+        .issues
+        .extend(keys.iter().filter_map(|k| check_key(k, seed.as_deref())));
+    report
+        .issues
+        .extend(vault_items.iter().filter_map(check_item));
+    Ok(report)
+}
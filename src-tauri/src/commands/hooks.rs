@@ -0,0 +1,359 @@
+use crate::commands::items::ItemStore;
+use crate::commands::keys::{atomic_write, load_sealed, save_sealed, session_key, SessionKey};
+use crate::commands::vault::{persist_vault, verify_password, VaultMutex};
+use crate::error::{Result, VaultError};
+use crate::models::hook::{
+    Hook, HookAction, HookDelivery, HookEvent, HookSettings, HOOK_TIMEOUT_SECS, MAX_HOOKS,
+};
+use crate::models::vault::VaultState;
+use chrono::{DateTime, Utc};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+/// Hooks and the state of their background checks, encrypted with the
+/// session key next to `vault.json`. A hook names a program to run, so it
+/// must not be something a plain edit of the data directory can add.
+pub const HOOKS_FILE: &str = "hooks.json";
+
+fn load_store(app: &AppHandle, key: &[u8; 32]) -> Result<HookSettings> {
+    load_sealed(app, HOOKS_FILE, key)
+}
+
+fn save_store(app: &AppHandle, key: &[u8; 32], store: &HookSettings) -> Result<()> {
+    save_sealed(app, HOOKS_FILE, key, store)
+}
+
+/// Load the hook store, first moving any hooks still kept in `vault.json`
+/// into it. Those are switched off, since anyone able to write the data
+/// directory could have added or changed them; the user turns back on the
+/// ones they recognise.
+fn load_hooks(app: &AppHandle, vault: &mut VaultState, key: &[u8; 32]) -> Result<HookSettings> {
+    let mut store = load_store(app, key)?;
+    if vault.hooks.is_empty() {
+        return Ok(store);
+    }
+    let mut next = vault.clone();
+    let legacy = std::mem::take(&mut next.hooks);
+    let moved = legacy.hooks.len();
+    for mut hook in legacy.hooks {
+        if store.hooks.len() < MAX_HOOKS && store.hooks.iter().all(|h| h.id != hook.id) {
+            hook.enabled = false;
+            store.hooks.push(hook);
+        }
+    }
+    store.item_expiry_checked_at = store
+        .item_expiry_checked_at
+        .or(legacy.item_expiry_checked_at);
+    save_store(app, key, &store)?;
+    persist_vault(app, &next)?;
+    *vault = next;
+    tracing::warn!(
+        target: "audit",
+        hooks = moved,
+        "hooks moved out of vault.json and switched off"
+    );
+    Ok(store)
+}
+
+/// Run `cmd` with `input` on stdin, stopping it after [`HOOK_TIMEOUT_SECS`].
+fn run_with_timeout(mut cmd: Command, input: &[u8]) -> std::result::Result<(), String> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    let deadline = Instant::now() + Duration::from_secs(HOOK_TIMEOUT_SECS);
+    if let Some(mut stdin) = child.stdin.take() {
+        // Written on its own thread, so a program that never reads its input
+        // cannot block the caller past the deadline once the pipe fills. A
+        // program that ignores its input may close stdin early, or be killed
+        // with the write still pending; neither is a failure.
+        let input = input.to_vec();
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(&input);
+        });
+    }
+    loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) if status.success() => return Ok(()),
+            Some(status) => return Err(format!("exited with {status}")),
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("still running after {HOOK_TIMEOUT_SECS} seconds"));
+            }
+            None => std::thread::sleep(Duration::from_millis(100)),
+        }
+    }
+}
+
+/// Carry out one hook. Webhooks go through the system `curl`, since the
+/// vault ships no network stack of its own.
+fn deliver(action: &HookAction, delivery: &HookDelivery) -> std::result::Result<(), String> {
+    let json = serde_json::to_vec_pretty(delivery).map_err(|e| e.to_string())?;
+    match action {
+        HookAction::Script { path } => {
+            let mut cmd = Command::new(path);
+            cmd.env("ZAP_HOOK_EVENT", delivery.event.as_str())
+                .env("ZAP_HOOK_SUBJECT", &delivery.subject_id);
+            run_with_timeout(cmd, &json)
+        }
+        HookAction::EventFile { dir } => {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            atomic_write(&Path::new(dir).join(delivery.file_name()), &json)
+                .map_err(|e| e.to_string())
+        }
+        HookAction::Webhook { url } => {
+            let mut cmd = Command::new("curl");
+            cmd.args(["--silent", "--show-error", "--fail", "--proto", "=https"])
+                .args(["--max-time", &HOOK_TIMEOUT_SECS.to_string()])
+                .args(["--header", "Content-Type: application/json"])
+                .args(["--data-binary", "@-", "--", url]);
+            run_with_timeout(cmd, &json)
+        }
+    }
+}
+
+/// Run every enabled hook for `event` on a background thread, so a slow
+/// script never holds up the command that raised it. Webhooks are skipped
+/// in air-gap mode, and nothing runs while the vault is locked, since the
+/// hook store cannot be read. Results go to the `audit` log.
+pub(crate) fn fire_hooks(
+    app: &AppHandle,
+    vault: &VaultState,
+    event: HookEvent,
+    subject_id: &str,
+    summary: &str,
+) {
+    let Ok(key) = session_key(&app.state::<SessionKey>()) else {
+        return;
+    };
+    let store = match load_store(app, &key) {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::warn!("could not read the hook store: {e}");
+            return;
+        }
+    };
+    let hooks: Vec<Hook> = store
+        .hooks
+        .iter()
+        .filter(|h| h.runs_on(event, vault.air_gap_mode))
+        .cloned()
+        .collect();
+    if hooks.is_empty() {
+        return;
+    }
+    let delivery = HookDelivery::new(event, subject_id, summary, Utc::now());
+    std::thread::spawn(move || {
+        for hook in hooks {
+            match deliver(&hook.action, &delivery) {
+                Ok(()) => tracing::info!(
+                    target: "audit",
+                    hook_id = %hook.id,
+                    event = delivery.event.as_str(),
+                    delivery = %delivery.id,
+                    "hook ran"
+                ),
+                Err(e) => tracing::warn!(
+                    target: "audit",
+                    hook_id = %hook.id,
+                    event = delivery.event.as_str(),
+                    delivery = %delivery.id,
+                    "hook failed: {e}"
+                ),
+            }
+        }
+    });
+}
+
+/// Run `item_expired` hooks for items whose rotation deadline passed since
+/// the last check. Items are only visible while the vault is unlocked, so
+/// deadlines passed while locked are caught up on the next check after an
+/// unlock.
+pub(crate) fn fire_item_expiry_hooks(app: &AppHandle, now: DateTime<Utc>) {
+    let Ok(key) = session_key(&app.state::<SessionKey>()) else {
+        return;
+    };
+    let state = app.state::<VaultMutex>();
+    let mut vault = state.0.lock().unwrap();
+    let mut store = match load_hooks(app, &mut vault, &key) {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::warn!("could not read the hook store: {e}");
+            return;
+        }
+    };
+    if !store.any_runs_on(HookEvent::ItemExpired, vault.air_gap_mode) {
+        return;
+    }
+    let since = store.item_expiry_checked_at;
+    let due: Vec<(String, DateTime<Utc>)> = {
+        let items = app.state::<ItemStore>();
+        let store = items.0.lock().unwrap();
+        store
+            .iter()
+            .filter(|i| i.is_expired(now))
+            .filter_map(|i| Some((i.id.clone(), i.expires_at?)))
+            .filter(|(_, at)| since.is_none_or(|s| *at > s))
+            .collect()
+    };
+    store.item_expiry_checked_at = Some(now);
+    if let Err(e) = save_store(app, &key, &store) {
+        tracing::warn!("could not record the item expiry check: {e}");
+        return;
+    }
+    for (id, at) in due {
+        fire_hooks(
+            app,
+            &vault,
+            HookEvent::ItemExpired,
+            &id,
+            &format!("Item {id} passed its rotation deadline at {at}"),
+        );
+    }
+}
+
+fn find_hook<'a>(store: &'a mut HookSettings, hook_id: &str) -> Result<&'a mut Hook> {
+    store
+        .hooks
+        .iter_mut()
+        .find(|h| h.id == hook_id)
+        .ok_or_else(|| VaultError::InvalidMetadata(format!("no hook with id {hook_id}")))
+}
+
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn list_hooks(
+    app: AppHandle,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<Hook>> {
+    let key = session_key(&session)?;
+    let mut vault = state.0.lock().unwrap();
+    Ok(load_hooks(&app, &mut vault, &key)?.hooks)
+}
+
+/// Add a hook that runs `action` on each of `events`. Scripts run with the
+/// app's user rights, so this requires the unlocked vault and its password.
+/// Webhooks are refused in air-gap mode.
+#[tauri::command]
+pub fn add_hook(
+    app: AppHandle,
+    password: String,
+    name: String,
+    events: Vec<HookEvent>,
+    action: HookAction,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<Hook> {
+    let key = session_key(&session)?;
+    verify_password(&app, &state, &password)?;
+    let mut events = events;
+    events.sort_by_key(|e| e.as_str());
+    events.dedup();
+    let hook = Hook {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.trim().to_string(),
+        events,
+        action,
+        enabled: true,
+        created_at: Utc::now(),
+    };
+    hook.validate().map_err(VaultError::InvalidMetadata)?;
+
+    let mut vault = state.0.lock().unwrap();
+    if vault.air_gap_mode && hook.action.is_networked() {
+        return Err(VaultError::AirGap(
+            "webhooks are disabled in air-gap mode".to_string(),
+        ));
+    }
+    let mut store = load_hooks(&app, &mut vault, &key)?;
+    if store.hooks.len() >= MAX_HOOKS {
+        return Err(VaultError::InvalidMetadata(format!(
+            "a vault holds at most {MAX_HOOKS} hooks"
+        )));
+    }
+    store.hooks.push(hook.clone());
+    save_store(&app, &key, &store)?;
+    tracing::warn!(
+        target: "audit",
+        hook_id = %hook.id,
+        action = ?hook.action,
+        events = ?hook.events,
+        "hook added"
+    );
+    Ok(hook)
+}
+
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn set_hook_enabled(
+    app: AppHandle,
+    hook_id: String,
+    enabled: bool,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<Hook> {
+    let key = session_key(&session)?;
+    let mut vault = state.0.lock().unwrap();
+    let mut store = load_hooks(&app, &mut vault, &key)?;
+    let hook = find_hook(&mut store, &hook_id)?;
+    hook.enabled = enabled;
+    let hook = hook.clone();
+    save_store(&app, &key, &store)?;
+    tracing::info!(target: "audit", hook_id = %hook_id, enabled, "hook toggled");
+    Ok(hook)
+}
+
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn remove_hook(
+    app: AppHandle,
+    hook_id: String,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    let key = session_key(&session)?;
+    let mut vault = state.0.lock().unwrap();
+    let mut store = load_hooks(&app, &mut vault, &key)?;
+    find_hook(&mut store, &hook_id)?;
+    store.hooks.retain(|h| h.id != hook_id);
+    save_store(&app, &key, &store)?;
+    tracing::info!(target: "audit", hook_id = %hook_id, "hook removed");
+    Ok(())
+}
+
+/// Run a hook once now with a test event for its first event type, and
+/// report whether it succeeded. Subject to air-gap mode like any run.
+/// Requires an unlocked vault.
+#[tauri::command(async)]
+pub fn test_hook(
+    app: AppHandle,
+    hook_id: String,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    let key = session_key(&session)?;
+    let (hook, air_gap_mode) = {
+        let mut vault = state.0.lock().unwrap();
+        let mut store = load_hooks(&app, &mut vault, &key)?;
+        (find_hook(&mut store, &hook_id)?.clone(), vault.air_gap_mode)
+    };
+    if air_gap_mode && hook.action.is_networked() {
+        return Err(VaultError::AirGap(
+            "webhooks are disabled in air-gap mode".to_string(),
+        ));
+    }
+    let event = hook
+        .events
+        .first()
+        .copied()
+        .unwrap_or(HookEvent::BackupCompleted);
+    let delivery = HookDelivery::new(event, "test", "Test event from the vault", Utc::now());
+    deliver(&hook.action, &delivery).map_err(VaultError::Hook)
+}
@@ -0,0 +1,108 @@
+use crate::commands::keys::{atomic_write, keys_file_path, MasterSeed};
+use crate::commands::sync::device_id;
+use crate::crypto::instance::{self, InstanceError, InstanceKey};
+use crate::drive::backup;
+use crate::error::{Result, VaultError};
+use crate::models::backup::BackupManifest;
+use crate::models::instance::{InstanceIdentity, InstanceIdentityFile, InstanceSignature};
+use chrono::Utc;
+use tauri::{AppHandle, State};
+
+/// This installation's signing identity, next to `vault.json`. Like the sync
+/// state it is left out of drive backups: a restored installation signs as a
+/// new instance.
+pub const INSTANCE_FILE: &str = "instance_identity.json";
+
+fn load_file(app: &AppHandle) -> Result<Option<InstanceIdentityFile>> {
+    let path = keys_file_path(app, INSTANCE_FILE)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(Some(serde_json::from_slice(&data)?))
+}
+
+/// Generate and save a new identity, replacing any existing one.
+pub(crate) fn create_instance_key(app: &AppHandle, seed: &[u8; 64]) -> Result<InstanceKey> {
+    let (key, file) = instance::generate_instance(seed, &device_id(app)?, Utc::now())?;
+    atomic_write(
+        &keys_file_path(app, INSTANCE_FILE)?,
+        &serde_json::to_vec_pretty(&file)?,
+    )?;
+    tracing::info!(
+        target: "audit",
+        instance_id = %key.identity.instance_id,
+        fingerprint = %key.identity.key_fingerprint(),
+        "instance identity created"
+    );
+    Ok(key)
+}
+
+/// Open this installation's identity, creating it on first use for vaults
+/// made before instance identities existed. An identity wrapped under
+/// another vault's seed (left behind by a restore from another vault's
+/// backup), or issued to a sync device id this installation no longer has,
+/// is replaced.
+pub(crate) fn instance_key(app: &AppHandle, seed: &[u8; 64]) -> Result<InstanceKey> {
+    match load_file(app)? {
+        Some(file) if file.identity.instance_id != device_id(app)? => {
+            create_instance_key(app, seed)
+        }
+        Some(file) => match instance::open_instance(seed, &file) {
+            Err(InstanceError::ForeignVault) => {
+                tracing::warn!("instance identity belongs to another vault; replacing it");
+                create_instance_key(app, seed)
+            }
+            opened => Ok(opened?),
+        },
+        None => create_instance_key(app, seed),
+    }
+}
+
+/// [`instance_key`] when the vault is unlocked, `None` when it is locked.
+pub(crate) fn unlocked_instance_key(
+    app: &AppHandle,
+    master_seed: &State<'_, MasterSeed>,
+) -> Result<Option<InstanceKey>> {
+    let guard = master_seed.0.lock().unwrap();
+    guard
+        .as_ref()
+        .map(|seed| instance_key(app, seed))
+        .transpose()
+}
+
+/// This installation's public identity, for verifiers to pin. Readable while
+/// locked once the identity exists.
+#[tauri::command]
+pub fn get_instance_identity(
+    app: AppHandle,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<InstanceIdentity> {
+    if let Some(file) = load_file(&app)? {
+        return Ok(file.identity);
+    }
+    unlocked_instance_key(&app, &master_seed)?
+        .map(|key| key.identity)
+        .ok_or(VaultError::NotInitialized)
+}
+
+/// Check an instance signature over `message_hex` for `purpose`. Returns the
+/// signing key's fingerprint, for comparing with a pinned
+/// [`get_instance_identity`] result.
+#[tauri::command]
+pub fn verify_instance_signature(
+    signature: InstanceSignature,
+    purpose: String,
+    message_hex: String,
+) -> Result<String> {
+    let message = hex::decode(&message_hex).map_err(|e| VaultError::Storage(e.to_string()))?;
+    instance::verify_instance(&signature, &purpose, &message)?;
+    Ok(signature.key_fingerprint())
+}
+
+/// Check a backup manifest's instance signature, e.g. one copied off a drive.
+/// `None` when the manifest is unsigned.
+#[tauri::command]
+pub fn verify_backup_instance_signature(manifest: BackupManifest) -> Option<bool> {
+    backup::instance_signature_ok(&manifest)
+}
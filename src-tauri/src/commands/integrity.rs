@@ -0,0 +1,142 @@
+use crate::commands::capsule::load_capsules;
+use crate::commands::consent::require_consent;
+use crate::commands::frost;
+use crate::commands::items::ItemStore;
+use crate::commands::keys::{KeyStore, SessionKey};
+use crate::commands::pairing::{load_pairing, save_pairing};
+use crate::commands::portfolio;
+use crate::commands::spending;
+use crate::commands::treasury::{load_treasury, save_treasury};
+use crate::commands::undo::UndoLog;
+use crate::error::{Result, VaultError};
+use crate::models::consent::ConsentOperation;
+use crate::models::integrity::{
+    prune_balances, prune_sites, prune_spending, prune_treasury, OrphanReport, Referents,
+};
+use crate::models::musig2::is_output_key;
+use crate::models::trash::TrashKind;
+use chrono::Utc;
+use tauri::{AppHandle, Manager};
+
+/// Find rows in the balances, spending, treasury and pairing stores that
+/// point at keys or items no longer in the vault, and with `fix` remove them,
+/// or mark them where the row is a record worth keeping. Rows of entries a
+/// purge removed are kept while the purge can still be undone.
+/// Holds the key and item stores for the whole sweep, so nothing is created
+/// behind it; never call it with either locked.
+pub(crate) fn sweep_orphans(app: &AppHandle, fix: bool) -> Result<OrphanReport> {
+    let session_key = {
+        let session = app.state::<SessionKey>();
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let keystore = app.state::<KeyStore>();
+    let items = app.state::<ItemStore>();
+    let keys = keystore.0.lock().unwrap();
+    let store = items.0.lock().unwrap();
+    let mut known = Referents::default();
+    known.keys.extend(keys.iter().map(|k| k.id.clone()));
+    known.items.extend(store.iter().map(|i| i.id.clone()));
+    // An item sealed in a time capsule comes back when the capsule opens.
+    known
+        .items
+        .extend(load_capsules(app)?.into_iter().map(|c| c.item_id));
+    for op in app.state::<UndoLog>().0.lock().unwrap().list(Utc::now()) {
+        for entry in op.entries {
+            match entry.kind {
+                TrashKind::Key => known.keys.insert(entry.id),
+                TrashKind::Item => known.items.insert(entry.id),
+            };
+        }
+    }
+
+    let mut balances = portfolio::load_store(app, &session_key)?;
+    let mut spending = spending::load_store(app, &session_key)?;
+    let mut treasury = load_treasury(app)?;
+    let mut pairing = load_pairing(app)?;
+    // Spending rows may also name a FROST group or a MuSig2 output key.
+    let groups = frost::load_store(app, &session_key)?.shares;
+    known
+        .keys
+        .extend(groups.iter().map(|s| s.group.group_id.clone()));
+    let output_keys = spending
+        .policies
+        .iter()
+        .map(|p| &p.key_id)
+        .chain(spending.approvals.iter().map(|a| &a.key_id))
+        .chain(spending.history.iter().map(|r| &r.key_id))
+        .filter(|id| is_output_key(id))
+        .cloned()
+        .collect::<Vec<_>>();
+    known.keys.extend(output_keys);
+    let from_balances = prune_balances(&mut balances, &known);
+    let from_spending = prune_spending(&mut spending, &known, Utc::now());
+    let from_treasury = prune_treasury(&mut treasury, &known);
+    let from_sites = prune_sites(&mut pairing, &known);
+    if fix {
+        if !from_balances.is_empty() {
+            portfolio::save_store(app, &session_key, &balances)?;
+        }
+        if !from_spending.is_empty() {
+            spending::save_store(app, &session_key, &mut spending)?;
+        }
+        if !from_treasury.is_empty() {
+            save_treasury(app, &treasury)?;
+        }
+        if !from_sites.is_empty() {
+            save_pairing(app, &pairing)?;
+        }
+    }
+
+    let orphans: Vec<_> = from_balances
+        .into_iter()
+        .chain(from_spending)
+        .chain(from_treasury)
+        .chain(from_sites)
+        .collect();
+    if fix {
+        for orphan in &orphans {
+            tracing::warn!(
+                target: "audit",
+                kind = ?orphan.kind,
+                missing_id = %orphan.missing_id,
+                detail = %orphan.detail,
+                "orphaned row cleaned up"
+            );
+        }
+    }
+    Ok(OrphanReport {
+        orphans,
+        fixed: fix,
+    })
+}
+
+/// Report rows left pointing at keys or items that no longer exist, and with
+/// `fix` remove or mark them. Purges and keyset rollbacks clean up after themselves;
+/// this catches what earlier versions left behind. Requires an unlocked
+/// vault, and with `fix` a `consent_token` from `request_consent`.
+#[tauri::command]
+pub fn repair_orphans(
+    app: AppHandle,
+    fix: bool,
+    consent_token: Option<String>,
+) -> Result<OrphanReport> {
+    if fix {
+        require_consent(
+            &app,
+            consent_token.as_deref().unwrap_or_default(),
+            &ConsentOperation::RepairOrphans,
+        )?;
+    }
+    sweep_orphans(&app, fix)
+}
+
+/// The `ON DELETE` step of a purge or keyset rollback: remove the rows of
+/// entries that are now gone for good. Best effort, since the deletion is
+/// already committed; whatever is missed is caught by the next maintenance
+/// run.
+pub(crate) fn cascade_deletes(app: &AppHandle) {
+    if let Err(e) = sweep_orphans(app, true) {
+        tracing::warn!("could not remove orphaned rows: {e}");
+    }
+}
@@ -0,0 +1,295 @@
+use crate::commands::hooks::fire_item_expiry_hooks;
+use crate::commands::keys::{atomic_write, keys_file_path, SessionKey};
+use crate::commands::vault::{verify_password, VaultMutex};
+use crate::crypto::encryption::{self, Ciphertext};
+use crate::error::{Result, VaultError};
+use crate::models::item::{ExpiredItem, RotationHook, VaultItem, VaultItemPublic};
+use crate::models::metadata::MetadataUpdate;
+use chrono::{DateTime, Utc};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Emitted with the ids of items that expired but have not been marked yet.
+pub const ITEMS_EXPIRED_EVENT: &str = "items_expired";
+/// How often the background check looks for expired items.
+pub const EXPIRY_CHECK_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Decrypted non-chain vault items (SSH keys, ...) for the unlocked session.
+/// Cleared on lock, exactly like [`crate::commands::keys::KeyStore`].
+pub struct ItemStore(pub Mutex<Vec<VaultItem>>);
+
+/// Serialize and AES-256-GCM encrypt the item store into a byte blob.
+/// Pure (no I/O) to keep it unit-testable.
+pub fn encrypt_items(key: &[u8; 32], items: &[VaultItem]) -> Result<Vec<u8>> {
+    let json = serde_json::to_vec(items)?;
+    let ct =
+        encryption::encrypt_vault(key, &json).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::to_vec(&ct)?)
+}
+
+/// Decrypt and deserialize an item store blob produced by `encrypt_items`.
+/// Pure (no I/O) to keep it unit-testable.
+pub fn decrypt_items(key: &[u8; 32], data: &[u8]) -> Result<Vec<VaultItem>> {
+    let ct: Ciphertext = serde_json::from_slice(data)?;
+    let json =
+        encryption::decrypt_vault(key, &ct).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// Encrypt and atomically persist the named item store using the session key.
+pub fn save_items(
+    app: &AppHandle,
+    file_name: &str,
+    key: &[u8; 32],
+    items: &[VaultItem],
+) -> Result<()> {
+    let serialized = encrypt_items(key, items)?;
+    let path = keys_file_path(app, file_name)?;
+    atomic_write(&path, &serialized)
+}
+
+/// Decrypt and load the named item store using the session key.
+/// Returns an empty vec if no item store file exists yet.
+pub fn load_items(app: &AppHandle, file_name: &str, key: &[u8; 32]) -> Result<Vec<VaultItem>> {
+    let path = keys_file_path(app, file_name)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    decrypt_items(key, &data)
+}
+
+/// Append a new item to the unlocked item store and persist the store with the
+/// session key.
+pub fn push_item(
+    app: &AppHandle,
+    vault: &State<'_, VaultMutex>,
+    items: &State<'_, ItemStore>,
+    session: &State<'_, SessionKey>,
+    item: VaultItem,
+) -> Result<()> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let items_file = vault.0.lock().unwrap().items_file.clone();
+
+    let mut store = items.0.lock().unwrap();
+    store.push(item);
+    save_items(app, &items_file, &session_key, &store)
+}
+
+/// Edit label / description / tags on one or more vault items. Same
+/// all-or-nothing semantics as [`crate::commands::keys::update_key_metadata`].
+#[tauri::command]
+pub fn update_item_metadata(
+    app: AppHandle,
+    item_ids: Vec<String>,
+    update: MetadataUpdate,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<VaultItemPublic>> {
+    update.validate().map_err(VaultError::InvalidMetadata)?;
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let items_file = vault.0.lock().unwrap().items_file.clone();
+
+    let mut store = items.0.lock().unwrap();
+    let mut staged = Vec::with_capacity(item_ids.len());
+    for id in &item_ids {
+        let idx = store
+            .iter()
+            .position(|i| &i.id == id)
+            .ok_or_else(|| VaultError::KeyNotFound(id.clone()))?;
+        let item = &store[idx];
+        let (mut label, mut description, mut tags) = (
+            item.label.clone(),
+            item.description.clone(),
+            item.tags.clone(),
+        );
+        update
+            .apply(&mut label, &mut description, &mut tags)
+            .map_err(VaultError::InvalidMetadata)?;
+        staged.push((idx, label, description, tags));
+    }
+    for (idx, label, description, tags) in staged {
+        let item = &mut store[idx];
+        item.label = label;
+        item.description = description;
+        item.tags = tags;
+    }
+    save_items(&app, &items_file, &session_key, &store)?;
+    Ok(item_ids
+        .iter()
+        .filter_map(|id| store.iter().find(|i| &i.id == id))
+        .map(|i| i.to_public())
+        .collect())
+}
+
+/// Items expired at `now`, most overdue first. With `mark`, items not yet
+/// marked get `expired_marked_at` set. Pure (no I/O) to keep it unit-testable.
+pub fn collect_expired(
+    items: &mut [VaultItem],
+    now: DateTime<Utc>,
+    mark: bool,
+) -> Vec<ExpiredItem> {
+    let mut expired: Vec<ExpiredItem> = items
+        .iter_mut()
+        .filter(|i| i.is_expired(now))
+        .map(|item| {
+            let newly_marked = mark && item.expired_marked_at.is_none();
+            if newly_marked {
+                item.expired_marked_at = Some(now);
+            }
+            let overdue_secs = item.expires_at.map_or(0, |t| (now - t).num_seconds());
+            ExpiredItem {
+                item: item.to_public(),
+                overdue_secs,
+                newly_marked,
+            }
+        })
+        .collect();
+    expired.sort_by_key(|e| std::cmp::Reverse(e.overdue_secs));
+    expired
+}
+
+/// Set (or with `None`, clear) the expiry and rotation hook of one or more
+/// items. All-or-nothing like [`update_item_metadata`]. Changing the expiry
+/// clears any earlier expired mark.
+#[tauri::command]
+pub fn set_item_expiry(
+    app: AppHandle,
+    item_ids: Vec<String>,
+    expires_at: Option<DateTime<Utc>>,
+    rotation_hook: Option<RotationHook>,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<VaultItemPublic>> {
+    if let Some(hook) = &rotation_hook {
+        hook.validate().map_err(VaultError::InvalidMetadata)?;
+    }
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let items_file = vault.0.lock().unwrap().items_file.clone();
+
+    let mut store = items.0.lock().unwrap();
+    if let Some(missing) = item_ids
+        .iter()
+        .find(|id| !store.iter().any(|i| &i.id == *id))
+    {
+        return Err(VaultError::KeyNotFound(missing.clone()));
+    }
+    for item in store.iter_mut().filter(|i| item_ids.contains(&i.id)) {
+        if item.expires_at != expires_at {
+            item.expired_marked_at = None;
+        }
+        item.expires_at = expires_at;
+        item.rotation_hook = rotation_hook.clone();
+    }
+    save_items(&app, &items_file, &session_key, &store)?;
+    Ok(item_ids
+        .iter()
+        .filter_map(|id| store.iter().find(|i| &i.id == id))
+        .map(|i| i.to_public())
+        .collect())
+}
+
+/// Flag (or unflag) items whose secret must never be exported in plaintext.
+/// All-or-nothing like [`update_item_metadata`]. Clearing the flag loosens
+/// the vault's protection, so it needs the vault password.
+#[tauri::command]
+pub fn set_item_export_policy(
+    app: AppHandle,
+    item_ids: Vec<String>,
+    no_plaintext_export: bool,
+    password: Option<String>,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<VaultItemPublic>> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    if !no_plaintext_export {
+        verify_password(&app, &vault, password.as_deref().unwrap_or_default())?;
+    }
+    let items_file = vault.0.lock().unwrap().items_file.clone();
+
+    let mut store = items.0.lock().unwrap();
+    if let Some(missing) = item_ids
+        .iter()
+        .find(|id| !store.iter().any(|i| &i.id == *id))
+    {
+        return Err(VaultError::KeyNotFound(missing.clone()));
+    }
+    for item in store.iter_mut().filter(|i| item_ids.contains(&i.id)) {
+        item.no_plaintext_export = no_plaintext_export;
+    }
+    save_items(&app, &items_file, &session_key, &store)?;
+    tracing::info!(
+        target: "audit",
+        items = item_ids.len(),
+        no_plaintext_export,
+        "item export policy changed"
+    );
+    Ok(item_ids
+        .iter()
+        .filter_map(|id| store.iter().find(|i| &i.id == id))
+        .map(|i| i.to_public())
+        .collect())
+}
+
+/// List expired items, most overdue first, with their rotation hooks. With
+/// `mark`, newly expired items are marked and the item store is saved.
+#[tauri::command]
+pub fn get_expired_items(
+    app: AppHandle,
+    mark: bool,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<ExpiredItem>> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let items_file = vault.0.lock().unwrap().items_file.clone();
+
+    let mut store = items.0.lock().unwrap();
+    let expired = collect_expired(&mut store, Utc::now(), mark);
+    if expired.iter().any(|e| e.newly_marked) {
+        save_items(&app, &items_file, &session_key, &store)?;
+    }
+    Ok(expired)
+}
+
+/// Periodically emit `items_expired` with the ids of expired items nobody has
+/// marked yet, and run `item_expired` hooks. Only sees items while the vault
+/// is unlocked, since the item store is empty otherwise.
+pub fn spawn_expiry_watch(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(EXPIRY_CHECK_INTERVAL_SECS));
+        let now = Utc::now();
+        let ids: Vec<String> = {
+            let items = app.state::<ItemStore>();
+            let store = items.0.lock().unwrap();
+            store
+                .iter()
+                .filter(|i| i.is_expired(now) && i.expired_marked_at.is_none())
+                .map(|i| i.id.clone())
+                .collect()
+        };
+        if !ids.is_empty() {
+            let _ = app.emit(ITEMS_EXPIRED_EVENT, ids);
+        }
+        fire_item_expiry_hooks(&app, now);
+    });
+}
@@ -0,0 +1,272 @@
+use crate::commands::backup::Drives;
+use crate::commands::items::ItemStore;
+use crate::commands::keys::{KeyStore, MasterSeed, SessionKey};
+use crate::commands::notifications::DRIVE_WATCH_INTERVAL_SECS;
+use crate::commands::vault::{
+    load_vault_if_needed, lock_vault, open_session, persist_vault, record_unlock_failure,
+    verify_password, verify_session_key, UnlockState, VaultMutex,
+};
+use crate::crypto::kdf::KdfParams;
+use crate::crypto::key_drive::{self, KeyDriveError};
+use crate::error::{Result, VaultError};
+use crate::models::key_drive::{check_key_drive, KeyDriveStatus, MAX_KEY_DRIVE_PIN_FAILURES};
+use crate::models::vault::VaultState;
+use chrono::Utc;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Emitted when the key drive goes away and the session it opened is locked.
+pub const KEY_DRIVE_REMOVED_EVENT: &str = "key_drive_removed";
+
+/// The drive id while the session was opened with the key drive; `None`
+/// after a password unlock, which removing the drive does not end.
+#[derive(Default)]
+pub struct KeyDriveSession(pub Mutex<Option<String>>);
+
+fn status(
+    vault: &VaultState,
+    drives: &Drives,
+    key_drive: &KeyDriveSession,
+    session: &SessionKey,
+) -> KeyDriveStatus {
+    let Some(config) = &vault.key_drive else {
+        return KeyDriveStatus {
+            enrolled: false,
+            drive_id: None,
+            label: None,
+            present: false,
+            attempts_left: None,
+            active: false,
+        };
+    };
+    KeyDriveStatus {
+        enrolled: true,
+        drive_id: Some(config.drive_id.clone()),
+        label: config.label.clone(),
+        present: drives
+            .0
+            .drive(&config.drive_id)
+            .is_ok_and(|d| check_key_drive(&d).is_ok()),
+        attempts_left: Some(MAX_KEY_DRIVE_PIN_FAILURES.saturating_sub(config.failures)),
+        active: key_drive.0.lock().unwrap().is_some() && session.0.lock().unwrap().is_some(),
+    }
+}
+
+/// Delete the unlock file if the drive is around. Its binding secret is
+/// gone from `vault.json` by now, so a copy left behind unlocks nothing.
+fn remove_unlock_file(drives: &Drives, drive_id: &str, path: &str) {
+    if let Err(e) = drives.0.remove_file(drive_id, path) {
+        tracing::warn!("could not remove the key drive unlock file: {e}");
+    }
+}
+
+/// Works whether or not the vault is unlocked.
+#[tauri::command]
+pub fn key_drive_status(
+    app: AppHandle,
+    state: State<'_, VaultMutex>,
+    drives: State<'_, Drives>,
+    key_drive: State<'_, KeyDriveSession>,
+    session: State<'_, SessionKey>,
+) -> Result<KeyDriveStatus> {
+    let mut vault = state.0.lock().unwrap();
+    load_vault_if_needed(&app, &mut vault);
+    Ok(status(&vault, &drives, &key_drive, &session))
+}
+
+/// Let the vault unlock with `pin` while the encrypted drive `drive_id` is
+/// mounted, replacing an earlier key drive. Not available with a YubiKey
+/// enrolled, which the PIN would bypass. Requires the vault password and
+/// an unlocked vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn enroll_key_drive(
+    app: AppHandle,
+    password: String,
+    drive_id: String,
+    pin: String,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    drives: State<'_, Drives>,
+    key_drive: State<'_, KeyDriveSession>,
+) -> Result<KeyDriveStatus> {
+    let enc_key = session
+        .0
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or(VaultError::NotInitialized)?;
+    verify_password(&app, &state, &password)?;
+    let mut vault = state.0.lock().unwrap();
+    if vault.yubikey_enabled {
+        return Err(VaultError::PolicyDenied(
+            "key drive unlock cannot be used with a YubiKey enrolled".to_string(),
+        ));
+    }
+    let drive = drives.0.drive(&drive_id)?;
+    check_key_drive(&drive).map_err(VaultError::InvalidMetadata)?;
+
+    let (config, file) = key_drive::enroll(&enc_key, &pin, &drive, KdfParams::high(), Utc::now())?;
+    drives
+        .0
+        .write_file(&drive_id, &config.file_path(), &serde_json::to_vec(&file)?)?;
+    let previous = vault.key_drive.replace(config.clone());
+    if let Err(e) = persist_vault(&app, &vault) {
+        vault.key_drive = previous;
+        remove_unlock_file(&drives, &drive_id, &config.file_path());
+        return Err(e);
+    }
+    if let Some(previous) = previous {
+        remove_unlock_file(&drives, &previous.drive_id, &previous.file_path());
+    }
+    tracing::warn!(
+        target: "audit",
+        drive = %drive_id,
+        label = ?config.label,
+        "key drive unlock enrolled"
+    );
+    Ok(status(&vault, &drives, &key_drive, &session))
+}
+
+/// Switch key-drive unlock off and remove the unlock file if the drive is
+/// mounted. Requires the vault password.
+#[tauri::command]
+pub fn disable_key_drive(
+    app: AppHandle,
+    password: String,
+    state: State<'_, VaultMutex>,
+    drives: State<'_, Drives>,
+    key_drive: State<'_, KeyDriveSession>,
+) -> Result<()> {
+    verify_password(&app, &state, &password)?;
+    let mut vault = state.0.lock().unwrap();
+    let config = vault
+        .key_drive
+        .take()
+        .ok_or_else(|| VaultError::InvalidMetadata("no key drive is enrolled".to_string()))?;
+    persist_vault(&app, &vault)?;
+    // The current session stays open, but no longer ends with the drive.
+    *key_drive.0.lock().unwrap() = None;
+    remove_unlock_file(&drives, &config.drive_id, &config.file_path());
+    tracing::warn!(target: "audit", drive = %config.drive_id, "key drive unlock disabled");
+    Ok(())
+}
+
+/// Unlock with the PIN while the enrolled key drive is mounted. The session
+/// locks when the drive is removed. After
+/// [`MAX_KEY_DRIVE_PIN_FAILURES`] wrong PINs in a row key-drive unlock is
+/// switched off. Wrong PINs also count towards the unlock throttle.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn unlock_with_key_drive(
+    app: AppHandle,
+    pin: String,
+    state: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+    throttle: State<'_, UnlockState>,
+    drives: State<'_, Drives>,
+    key_drive: State<'_, KeyDriveSession>,
+) -> Result<bool> {
+    let now = Utc::now().timestamp() as u64;
+    throttle.0.lock().unwrap().check(now)?;
+
+    let mut vault = state.0.lock().unwrap();
+    load_vault_if_needed(&app, &mut vault);
+    if !vault.initialized {
+        return Err(VaultError::NotInitialized);
+    }
+    let config = vault
+        .key_drive
+        .clone()
+        .ok_or_else(|| VaultError::InvalidMetadata("no key drive is enrolled".to_string()))?;
+    let drive = drives.0.drive(&config.drive_id)?;
+    check_key_drive(&drive).map_err(VaultError::InvalidMetadata)?;
+    let data = drives.0.read_file(&config.drive_id, &config.file_path())?;
+
+    match key_drive::unwrap(&config, &data, &pin) {
+        Ok(enc_key) => {
+            // A key that unwraps but does not open the vault means the
+            // vault was re-keyed behind the file's back.
+            verify_session_key(&vault, &enc_key)?;
+            throttle.0.lock().unwrap().record_success();
+            if config.failures > 0 {
+                if let Some(config) = vault.key_drive.as_mut() {
+                    config.failures = 0;
+                }
+                persist_vault(&app, &vault)?;
+            }
+            open_session(
+                &app,
+                &mut vault,
+                enc_key,
+                &keystore,
+                &items,
+                &session,
+                &master_seed,
+            )?;
+            *key_drive.0.lock().unwrap() = Some(config.drive_id.clone());
+            tracing::info!(target: "audit", drive = %config.drive_id, "vault unlocked with key drive");
+            Ok(true)
+        }
+        Err(KeyDriveError::WrongPin) => {
+            record_unlock_failure(&app, &vault, &throttle, now);
+            let failures = config.failures + 1;
+            if failures >= MAX_KEY_DRIVE_PIN_FAILURES {
+                vault.key_drive = None;
+                tracing::warn!(
+                    target: "audit",
+                    drive = %config.drive_id,
+                    failures,
+                    "key drive unlock switched off after wrong PINs"
+                );
+            } else if let Some(config) = vault.key_drive.as_mut() {
+                config.failures = failures;
+            }
+            persist_vault(&app, &vault)?;
+            if vault.key_drive.is_none() {
+                remove_unlock_file(&drives, &config.drive_id, &config.file_path());
+            }
+            Err(KeyDriveError::WrongPin.into())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Lock a session opened with the key drive as soon as the drive is
+/// removed or unmounted.
+pub fn spawn_key_drive_watch(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(DRIVE_WATCH_INTERVAL_SECS));
+        let Some(drive_id) = app.state::<KeyDriveSession>().0.lock().unwrap().clone() else {
+            continue;
+        };
+        if app.state::<SessionKey>().0.lock().unwrap().is_none() {
+            continue;
+        }
+        let present = app
+            .state::<Drives>()
+            .0
+            .drive(&drive_id)
+            .is_ok_and(|d| check_key_drive(&d).is_ok());
+        if present {
+            continue;
+        }
+        if let Err(e) = lock_vault(
+            app.clone(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+        ) {
+            tracing::warn!("vault locked after its key drive was removed: {e}");
+        }
+        tracing::info!(target: "audit", drive = %drive_id, "vault locked: key drive removed");
+        let _ = app.emit(KEY_DRIVE_REMOVED_EVENT, ());
+    });
+}
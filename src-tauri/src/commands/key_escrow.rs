@@ -0,0 +1,173 @@
+use crate::commands::consent::require_consent;
+use crate::commands::contacts::load_contacts;
+use crate::commands::hooks::fire_hooks;
+use crate::commands::keys::{atomic_write, save_keys, KeyStore, MasterSeed, SessionKey};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::policy::export_watermark;
+use crate::commands::vault::{verify_password, VaultMutex};
+use crate::crypto::{contact, key_escrow};
+use crate::error::{Result, VaultError};
+use crate::models::consent::ConsentOperation;
+use crate::models::hook::HookEvent;
+use crate::models::key_escrow::{EscrowPolicy, KeyEscrowPackage, OpenedKeyEscrow};
+use crate::models::rate_limit::SensitiveOp;
+use crate::models::usage::UsageStats;
+use chrono::Utc;
+use tauri::{AppHandle, State};
+
+/// Seal `key_id`, secret included, to an imported contact and write the
+/// package to `path` (e.g. a USB drive). The contact's fingerprint must have
+/// been verified first. `sender_name` goes on the card embedded for the
+/// recipient; `conditions` are signed into the package but only advisory.
+/// The package carries an export watermark outside the signature. Requires
+/// the vault `password`, a `consent_token` from `request_consent` and an
+/// unlocked vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn create_escrow_package(
+    app: AppHandle,
+    path: String,
+    key_id: String,
+    recipient_contact_id: String,
+    sender_name: String,
+    conditions: String,
+    password: String,
+    consent_token: String,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    master_seed: State<'_, MasterSeed>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<EscrowPolicy> {
+    enforce(&vault, &limiter, SensitiveOp::Export)?;
+    verify_password(&app, &vault, &password)?;
+    require_consent(
+        &app,
+        &consent_token,
+        &ConsentOperation::KeyEscrow {
+            key_id: key_id.clone(),
+            recipient_contact_id: recipient_contact_id.clone(),
+        },
+    )?;
+    let seed = {
+        let guard = master_seed.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let recipient = load_contacts(&app)?
+        .into_iter()
+        .find(|c| c.id == recipient_contact_id)
+        .ok_or_else(|| VaultError::KeyNotFound(recipient_contact_id.clone()))?;
+    if !recipient.verified {
+        return Err(VaultError::InvalidMetadata(
+            "verify the contact's fingerprint before escrowing a key to them".to_string(),
+        ));
+    }
+    let entry = keystore
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|k| k.id == key_id && k.metadata.trashed_at.is_none())
+        .cloned()
+        .ok_or_else(|| VaultError::KeyNotFound(key_id.clone()))?;
+
+    let now = Utc::now().timestamp() as u64;
+    let sender = contact::issue_card(&seed, &sender_name, now)?;
+    let mut package =
+        key_escrow::seal_key_escrow(&seed, &sender, &recipient.card, &entry, &conditions, now)?;
+    let mark = export_watermark(&app)?;
+    package.watermark = Some(mark.clone());
+    atomic_write(
+        std::path::Path::new(&path),
+        &serde_json::to_vec_pretty(&package)?,
+    )?;
+    let policy = key_escrow::verify_key_escrow(&package)?;
+    tracing::info!(
+        target: "audit",
+        key_id = %key_id,
+        contact_id = %recipient_contact_id,
+        package_id = %policy.package_id,
+        profile = %mark.profile,
+        instance_id = %mark.instance_id,
+        "key escrow package created"
+    );
+    Ok(policy)
+}
+
+/// Show the signed terms of the package at `path` without decrypting it.
+#[tauri::command]
+pub fn inspect_escrow_package(path: String) -> Result<EscrowPolicy> {
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    let package: KeyEscrowPackage = serde_json::from_slice(&data)?;
+    Ok(key_escrow::verify_key_escrow(&package)?)
+}
+
+/// Open a package addressed to this vault and add its key to the keystore,
+/// marked with the package id. Requires an unlocked vault.
+#[tauri::command]
+pub fn open_escrow_package(
+    app: AppHandle,
+    path: String,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<OpenedKeyEscrow> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let seed = {
+        let guard = master_seed.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    let package: KeyEscrowPackage = serde_json::from_slice(&data)?;
+    let (policy, mut entry) = key_escrow::open_key_escrow(&seed, &package)?;
+    let sender_fingerprint = contact::fingerprint(&package.sender)?;
+    let sender_contact = load_contacts(&app)?
+        .into_iter()
+        .find(|c| contact::fingerprint(&c.card).is_ok_and(|f| f.hex == sender_fingerprint.hex));
+
+    let keys_file = vault.0.lock().unwrap().keys_file.clone();
+    let mut store = keystore.0.lock().unwrap();
+    if store
+        .iter()
+        .any(|k| k.public_key_hex == entry.public_key_hex)
+    {
+        return Err(VaultError::KeyAlreadyExists(entry.metadata.address.clone()));
+    }
+    entry.id = uuid::Uuid::new_v4().to_string();
+    entry.metadata.created_at = Utc::now();
+    entry.metadata.keyset_id = None;
+    entry.metadata.ceremony_id = None;
+    entry.metadata.escrow_id = Some(policy.package_id.clone());
+    entry.metadata.trashed_at = None;
+    entry.metadata.usage = UsageStats::default();
+    let mut next = store.clone();
+    next.push(entry.clone());
+    save_keys(&app, &keys_file, &session_key, &next)?;
+    *store = next;
+    drop(store);
+    fire_hooks(
+        &app,
+        &vault.0.lock().unwrap(),
+        HookEvent::KeyCreated,
+        &entry.id,
+        &format!("Key {} received in a key escrow package", entry.id),
+    );
+    tracing::info!(
+        target: "audit",
+        key_id = %entry.id,
+        package_id = %policy.package_id,
+        sender = %sender_fingerprint.hex,
+        "key escrow package opened"
+    );
+    Ok(OpenedKeyEscrow {
+        policy,
+        sender_name: package.sender.name.clone(),
+        sender_fingerprint,
+        sender_contact_id: sender_contact.as_ref().map(|c| c.id.clone()),
+        sender_verified: sender_contact.is_some_and(|c| c.verified),
+        key: entry.to_public(),
+    })
+}
@@ -1,15 +1,41 @@
-use crate::commands::vault::VaultMutex;
+use crate::commands::hooks::fire_hooks;
+use crate::commands::policy::enforce_policy;
+use crate::commands::portable::portable_vault_dir;
+use crate::commands::profiles::profile_dir;
+use crate::commands::vault::{LockEpoch, VaultMutex};
 use crate::crypto::encryption::Ciphertext;
 use crate::crypto::{address, encryption, hd_derivation, mldsa87};
 use crate::error::{Result, VaultError};
-use crate::models::key::{KeyEntry, KeyEntryPublic, KeyType};
+use crate::models::address::{key_details, last_backup_of, max_derivable_index};
+use crate::models::display_cache::DisplayCache;
+use crate::models::hook::HookEvent;
+use crate::models::key::{KeyDetails, KeyEntry, KeyEntryPublic, KeyType};
+use crate::models::metadata::MetadataUpdate;
+use crate::models::policy::PolicyGate;
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager, State};
 use zeroize::Zeroizing;
 
 pub struct KeyStore(pub Mutex<Vec<KeyEntry>>);
 
+/// A key's detail view as last built, with the gap limit it was built for.
+#[derive(Debug, Clone)]
+pub struct CachedKeyDetails {
+    gap_limit: u32,
+    details: KeyDetails,
+}
+
+/// Key detail views built this session, by key id, so reopening a key in a
+/// large vault does not rescan its whole account. Cleared whenever a
+/// keystore is saved and when the vault locks.
+#[derive(Default)]
+pub struct KeyDetailCache(pub Mutex<DisplayCache<CachedKeyDetails>>);
+
 /// Holds the AES-256-GCM key derived from the user's password for the current
 /// unlocked session. `None` whenever the vault is locked. The key is wrapped in
 /// `Zeroizing` so its bytes are wiped from memory as soon as the session is
@@ -24,7 +50,7 @@ pub struct MasterSeed(pub Mutex<Option<Zeroizing<[u8; 64]>>>);
 /// The app's local data directory, created if missing. On Unix the directory is
 /// restricted to owner-only access (`0700`) so other local users can't list or
 /// read the vault/keystore files.
-pub fn data_dir(app: &AppHandle) -> Result<PathBuf> {
+pub fn local_data_dir(app: &AppHandle) -> Result<PathBuf> {
     let dir = app
         .path()
         .app_local_data_dir()
@@ -34,6 +60,16 @@ pub fn data_dir(app: &AppHandle) -> Result<PathBuf> {
     Ok(dir)
 }
 
+/// The directory holding the vault's files: the active profile's directory,
+/// or the vault directory on the drive the vault was moved to. Fails while
+/// that drive is not attached, so nothing reads or writes a stale local copy.
+pub fn data_dir(app: &AppHandle) -> Result<PathBuf> {
+    match portable_vault_dir(app)? {
+        Some(dir) => Ok(dir),
+        None => profile_dir(app),
+    }
+}
+
 /// Resolve the on-disk path of a named encrypted keystore file.
 pub fn keys_file_path(app: &AppHandle, file_name: &str) -> Result<PathBuf> {
     Ok(data_dir(app)?.join(file_name))
@@ -92,6 +128,33 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<()> {
     Ok(())
 }
 
+/// Overwrite a file with zeros, fsync, then unlink it, so a retired store's
+/// ciphertext is not left behind in freed blocks. Best-effort: SSD wear
+/// levelling and copy-on-write filesystems can still hold older copies.
+pub fn secure_remove(path: &Path) -> Result<()> {
+    use std::io::Write;
+    let len = std::fs::metadata(path)
+        .map_err(|e| VaultError::Storage(e.to_string()))?
+        .len();
+    {
+        let mut f = std::fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(|e| VaultError::Storage(e.to_string()))?;
+        let zeros = [0u8; 4096];
+        let mut left = len;
+        while left > 0 {
+            let n = left.min(zeros.len() as u64) as usize;
+            f.write_all(&zeros[..n])
+                .map_err(|e| VaultError::Storage(e.to_string()))?;
+            left -= n as u64;
+        }
+        f.sync_all()
+            .map_err(|e| VaultError::Storage(e.to_string()))?;
+    }
+    std::fs::remove_file(path).map_err(|e| VaultError::Storage(e.to_string()))
+}
+
 /// Serialize and AES-256-GCM encrypt the keystore into a byte blob.
 /// Pure (no I/O) to keep it unit-testable.
 pub fn encrypt_keys(key: &[u8; 32], entries: &[KeyEntry]) -> Result<Vec<u8>> {
@@ -119,6 +182,7 @@ pub fn save_keys(
 ) -> Result<()> {
     let serialized = encrypt_keys(key, entries)?;
     let path = keys_file_path(app, file_name)?;
+    app.state::<KeyDetailCache>().0.lock().unwrap().clear();
     atomic_write(&path, &serialized)
 }
 
@@ -133,6 +197,76 @@ pub fn load_keys(app: &AppHandle, file_name: &str, key: &[u8; 32]) -> Result<Vec
     decrypt_keys(key, &data)
 }
 
+/// The session key, or `NotInitialized` while the vault is locked.
+pub(crate) fn session_key(session: &State<'_, SessionKey>) -> Result<Zeroizing<[u8; 32]>> {
+    let guard = session.0.lock().unwrap();
+    Ok(guard.as_ref().ok_or(VaultError::NotInitialized)?.clone())
+}
+
+/// `NotInitialized` while the vault is locked, for commands that need an
+/// unlocked vault without using the session key.
+pub(crate) fn require_unlocked(session: &State<'_, SessionKey>) -> Result<()> {
+    session_key(session).map(|_| ())
+}
+
+/// Decrypt and load a JSON store sealed under `key` in `file_name` next to
+/// `vault.json`. Returns the empty store if the file does not exist yet.
+pub(crate) fn load_sealed<T: DeserializeOwned + Default>(
+    app: &AppHandle,
+    file_name: &str,
+    key: &[u8; 32],
+) -> Result<T> {
+    let path = keys_file_path(app, file_name)?;
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    let ct: Ciphertext = serde_json::from_slice(&data)?;
+    let json = Zeroizing::new(
+        encryption::decrypt_vault(key, &ct).map_err(|e| VaultError::Storage(e.to_string()))?,
+    );
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// Encrypt `store` under `key` and atomically write it to `file_name`.
+pub(crate) fn save_sealed<T: Serialize>(
+    app: &AppHandle,
+    file_name: &str,
+    key: &[u8; 32],
+    store: &T,
+) -> Result<()> {
+    let json = Zeroizing::new(serde_json::to_vec(store)?);
+    let ct =
+        encryption::encrypt_vault(key, &json).map_err(|e| VaultError::Storage(e.to_string()))?;
+    atomic_write(&keys_file_path(app, file_name)?, &serde_json::to_vec(&ct)?)
+}
+
+/// Deterministically derive the key at `purpose/account/index` from the HD
+/// master seed, so the same path always yields the same key and the whole tree
+/// is recoverable from the mnemonic.
+pub(crate) fn derive_key_entry(
+    master_seed: &[u8; 64],
+    key_type: KeyType,
+    purpose: u32,
+    account: u32,
+    index: u32,
+) -> KeyEntry {
+    let path = hd_derivation::zap_path(purpose, account, index);
+    let derived = Zeroizing::new(hd_derivation::derive_seed_from_master(master_seed, &path));
+    let (pk, sk) = mldsa87::from_seed(&derived);
+    let addr = address::derive_address(pk.as_bytes());
+    KeyEntry::new(
+        key_type,
+        purpose,
+        account,
+        index,
+        &pk.to_hex(),
+        &sk.to_hex(),
+        &addr,
+        &path.to_string(),
+    )
+}
+
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
 pub fn generate_key(
@@ -146,87 +280,212 @@ pub fn generate_key(
     session: State<'_, SessionKey>,
     master_seed: State<'_, MasterSeed>,
 ) -> Result<KeyEntryPublic> {
+    enforce_policy(&vault, PolicyGate::KeyGeneration)?;
     let session_key = {
         let guard = session.0.lock().unwrap();
         guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
     };
-    let keys_file = vault.0.lock().unwrap().keys_file.clone();
-
-    // Deterministically derive the key from the HD master seed at the requested
-    // path, so the same purpose/account/index always yields the same key and the
-    // whole tree is recoverable from the mnemonic.
-    let path = hd_derivation::zap_path(purpose, account, index);
-    let path_str = path.to_string();
+    let (keys_file, gap_limit) = {
+        let v = vault.0.lock().unwrap();
+        (v.keys_file.clone(), v.address_gap_limit)
+    };
 
-    let (pk, sk) = {
+    let entry = {
         let guard = master_seed.0.lock().unwrap();
         let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
-        let derived = hd_derivation::derive_seed_from_master(seed, &path);
-        mldsa87::from_seed(&derived)
-    };
-    let addr = address::derive_address(pk.as_bytes());
-
-    let kt = match key_type.as_str() {
-        "genesis" => KeyType::Genesis,
-        "validator" => KeyType::Validator,
-        "governance" => KeyType::Governance,
-        "treasury" => KeyType::Treasury,
-        "security" => KeyType::SecurityAdmin,
-        "user" => KeyType::User,
-        "quantum" => KeyType::QuantumSafe,
-        "custom" => KeyType::Custom,
-        _ => KeyType::Custom,
+        derive_key_entry(seed, KeyType::parse(&key_type), purpose, account, index)
     };
+    let path_str = entry.metadata.derivation_path.clone();
 
     let mut store = keystore.0.lock().unwrap();
     // Deterministic derivation means re-using a path would silently duplicate an
     // existing key; reject it so the user picks a fresh index instead.
-    if store.iter().any(|k| k.metadata.derivation_path == path_str) {
+    if store.iter().any(|k| {
+        k.metadata.ceremony_id.is_none()
+            && k.metadata.escrow_id.is_none()
+            && k.metadata.derivation_path == path_str
+    }) {
         return Err(VaultError::Storage(format!(
             "a key already exists at {path_str}; choose a different index"
         )));
     }
-
-    let entry = KeyEntry::new(
-        kt,
-        purpose,
-        account,
-        index,
-        &pk.to_hex(),
-        &sk.to_hex(),
-        &addr,
-        &path_str,
-    );
+    let max_index = max_derivable_index(&store, purpose, account, gap_limit);
+    if index > max_index {
+        return Err(VaultError::InvalidMetadata(format!(
+            "index {index} is beyond the address gap limit ({gap_limit}); \
+             the highest derivable index in this account is {max_index}"
+        )));
+    }
 
     store.push(entry.clone());
     save_keys(&app, &keys_file, &session_key, &store)?;
+    drop(store);
+    fire_hooks(
+        &app,
+        &vault.0.lock().unwrap(),
+        HookEvent::KeyCreated,
+        &entry.id,
+        &format!("Key {} created", entry.id),
+    );
     Ok(entry.to_public())
 }
 
 #[tauri::command]
 pub fn list_keys(keystore: State<'_, KeyStore>) -> Result<Vec<KeyEntryPublic>> {
     let store = keystore.0.lock().unwrap();
-    Ok(store.iter().map(|k| k.to_public()).collect())
+    Ok(store
+        .iter()
+        .filter(|k| k.metadata.trashed_at.is_none())
+        .map(|k| k.to_public())
+        .collect())
 }
 
+/// Full detail for one key: public fields and metadata, address stats for
+/// its HD account, and backup coverage including the last backup holding it.
+/// Opening a key's detail view counts as a use for quick-access ranking. The
+/// stats come from the session's [`KeyDetailCache`] when nothing has been
+/// saved since they were built.
 #[tauri::command]
-pub fn get_key_detail(key_id: String, keystore: State<'_, KeyStore>) -> Result<KeyEntryPublic> {
-    let store = keystore.0.lock().unwrap();
-    store
-        .iter()
+pub fn get_key_detail(
+    app: AppHandle,
+    key_id: String,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+) -> Result<KeyDetails> {
+    let (gap_limit, history) = {
+        let vault = vault.0.lock().unwrap();
+        (vault.address_gap_limit, vault.backup_history.clone())
+    };
+    let mut store = keystore.0.lock().unwrap();
+    let entry = store
+        .iter_mut()
         .find(|k| k.id == key_id)
+        .ok_or(VaultError::KeyNotFound(key_id))?;
+    let now = Utc::now();
+    entry.metadata.usage.touch(now);
+    let entry = entry.clone();
+
+    let epoch = app.state::<LockEpoch>().0.load(Ordering::SeqCst);
+    let cache = app.state::<KeyDetailCache>();
+    let mut cache = cache.0.lock().unwrap();
+    if let Some(cached) = cache
+        .get(&entry.id, epoch, now)
+        .filter(|c| c.gap_limit == gap_limit)
+    {
+        // Usage counts change in memory without a save, so the key itself is
+        // never taken from the cache; nor is the backup history, which lives
+        // in `vault.json` rather than the keystore.
+        let mut details = KeyDetails {
+            key: entry.to_public(),
+            ..cached.details
+        };
+        details.backup.last_backup = last_backup_of(&history, &entry);
+        return Ok(details);
+    }
+    let details = key_details(&store, &entry, gap_limit, &history);
+    cache.insert(
+        entry.id.clone(),
+        epoch,
+        now,
+        CachedKeyDetails {
+            gap_limit,
+            details: details.clone(),
+        },
+    );
+    Ok(details)
+}
+
+/// Edit label / description / tags on one or more keys (bulk labeling). All ids
+/// are resolved and the update applied to copies first, so either every key is
+/// updated and persisted or none is.
+#[tauri::command]
+pub fn update_key_metadata(
+    app: AppHandle,
+    key_ids: Vec<String>,
+    update: MetadataUpdate,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<KeyEntryPublic>> {
+    update.validate().map_err(VaultError::InvalidMetadata)?;
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let keys_file = vault.0.lock().unwrap().keys_file.clone();
+
+    let mut store = keystore.0.lock().unwrap();
+    let mut staged = Vec::with_capacity(key_ids.len());
+    for id in &key_ids {
+        let idx = store
+            .iter()
+            .position(|k| &k.id == id)
+            .ok_or_else(|| VaultError::KeyNotFound(id.clone()))?;
+        let mut meta = store[idx].metadata.clone();
+        update
+            .apply(&mut meta.label, &mut meta.description, &mut meta.tags)
+            .map_err(VaultError::InvalidMetadata)?;
+        staged.push((idx, meta));
+    }
+    for (idx, meta) in staged {
+        store[idx].metadata = meta;
+    }
+    save_keys(&app, &keys_file, &session_key, &store)?;
+    Ok(key_ids
+        .iter()
+        .filter_map(|id| store.iter().find(|k| &k.id == id))
         .map(|k| k.to_public())
-        .ok_or(VaultError::KeyNotFound(key_id))
+        .collect())
 }
 
 /// Resolve the plaintext secret key hex for a key id from the in-memory
 /// keystore. Kept private to this crate so secrets are only ever used
 /// server-side (e.g. by signing / air-gap commands) and never returned to the UI.
+/// Every resolution is a use of the key and is counted for quick access.
+/// Trashed keys do not resolve.
 pub fn secret_hex_for(keystore: &State<'_, KeyStore>, key_id: &str) -> Result<Zeroizing<String>> {
-    let store = keystore.0.lock().unwrap();
-    store
-        .iter()
-        .find(|k| k.id == key_id)
-        .map(|k| Zeroizing::new(k.encrypted_secret_hex.clone()))
-        .ok_or_else(|| VaultError::KeyNotFound(key_id.to_string()))
+    let mut store = keystore.0.lock().unwrap();
+    let entry = store
+        .iter_mut()
+        .find(|k| k.id == key_id && k.metadata.trashed_at.is_none())
+        .ok_or_else(|| VaultError::KeyNotFound(key_id.to_string()))?;
+    entry.metadata.usage.touch(Utc::now());
+    Ok(Zeroizing::new(entry.encrypted_secret_hex.clone()))
+}
+
+/// Resolve the secrets of every key in `key_ids` at once, in order, for an
+/// operation that uses several keys behind one authorization. All or
+/// nothing: an unknown or trashed id fails the batch before any secret is
+/// handed out, rather than leaving the caller with a partial set. Each key
+/// is counted as used, and the batch is audited as one entry listing every
+/// id.
+pub fn secrets_hex_for(
+    keystore: &State<'_, KeyStore>,
+    key_ids: &[String],
+) -> Result<Vec<Zeroizing<String>>> {
+    let mut store = keystore.0.lock().unwrap();
+    let mut positions = Vec::with_capacity(key_ids.len());
+    for key_id in key_ids {
+        let idx = store
+            .iter()
+            .position(|k| &k.id == key_id && k.metadata.trashed_at.is_none())
+            .ok_or_else(|| VaultError::KeyNotFound(key_id.clone()))?;
+        positions.push(idx);
+    }
+    let now = Utc::now();
+    let secrets = positions
+        .into_iter()
+        .map(|idx| {
+            let entry = &mut store[idx];
+            entry.metadata.usage.touch(now);
+            Zeroizing::new(entry.encrypted_secret_hex.clone())
+        })
+        .collect();
+    tracing::info!(
+        target: "audit",
+        key_ids = %key_ids.join(","),
+        count = key_ids.len(),
+        "key secrets resolved as a batch"
+    );
+    Ok(secrets)
 }
@@ -0,0 +1,538 @@
+use crate::commands::ceremony::load_ceremonies;
+use crate::commands::consent::require_consent;
+use crate::commands::hooks::fire_hooks;
+use crate::commands::instance::instance_key;
+use crate::commands::integrity::cascade_deletes;
+use crate::commands::keys::{derive_key_entry, save_keys, KeyStore, MasterSeed, SessionKey};
+use crate::commands::policy::enforce_policy;
+use crate::commands::undo::stash_purge;
+use crate::commands::vault::{persist_vault, verify_password, VaultMutex};
+use crate::crypto::attestation::{self, ATTESTATION_VERSION};
+use crate::error::{Result, VaultError};
+use crate::models::attestation::{
+    AttestedKey, EntropySource, KeyAttestation, SignedKeyAttestation,
+};
+use crate::models::consent::ConsentOperation;
+use crate::models::hook::HookEvent;
+use crate::models::key::KeyEntry;
+use crate::models::keyset::{missing_planned, plan_keyset, Keyset, KeysetRequest, KeysetStatus};
+use crate::models::policy::PolicyGate;
+use crate::models::template::{
+    build_template, plan_template, template_keyset_key, KeysetTemplate, KeysetTemplateDraft,
+};
+use crate::models::trash::{TrashKind, TrashedEntry};
+use crate::models::undo::UndoKind;
+use crate::models::vault::VaultState;
+use chrono::Utc;
+use tauri::{AppHandle, State};
+
+/// Generate a set of keys as one unit. The keyset is first recorded as
+/// `Pending` in the vault metadata, then all of its keys are derived and
+/// written to the keystore in a single atomic save, and only then marked
+/// `Complete`. A retry with the same idempotency key returns a complete
+/// keyset unchanged, or resumes a pending one by deriving whatever is missing
+/// (derivation is deterministic, so the paths and keys are the same).
+#[tauri::command]
+pub fn generate_keyset(
+    app: AppHandle,
+    request: KeysetRequest,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<Keyset> {
+    enforce_policy(&vault, PolicyGate::KeyGeneration)?;
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+
+    // Held for the whole operation so two retries of the same request cannot
+    // interleave.
+    let mut vault = vault.0.lock().unwrap();
+    let mut store = keystore.0.lock().unwrap();
+
+    let keyset = match vault
+        .keysets
+        .iter()
+        .find(|k| k.idempotency_key == request.idempotency_key)
+    {
+        // Ceremony keysets hang off their own seed and are finished through
+        // the ceremony, never from the vault master seed.
+        Some(existing) if existing.ceremony_id.is_some() || !existing.matches(&request) => {
+            return Err(VaultError::Keyset(format!(
+                "idempotency key {} was already used for a different keyset",
+                request.idempotency_key
+            )));
+        }
+        Some(existing) if existing.status == KeysetStatus::Complete => {
+            return Ok(existing.clone());
+        }
+        Some(existing) => existing.clone(),
+        None => {
+            let keyset = plan_keyset(&store, &request, vault.address_gap_limit, Utc::now())
+                .map_err(VaultError::Keyset)?;
+            vault.keysets.push(keyset.clone());
+            persist_vault(&app, &vault)?;
+            keyset
+        }
+    };
+
+    let guard = master_seed.0.lock().unwrap();
+    let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
+    complete_keyset(&app, &mut vault, &mut store, &session_key, seed, &keyset)
+}
+
+/// Derive whatever keys of the pending `keyset` are not yet stored from
+/// `seed`, write them in one atomic keystore save, then mark the keyset
+/// `Complete`. Safe to call again after a crash at any point.
+pub(crate) fn complete_keyset(
+    app: &AppHandle,
+    vault: &mut VaultState,
+    store: &mut Vec<KeyEntry>,
+    session_key: &[u8; 32],
+    seed: &[u8; 64],
+    keyset: &Keyset,
+) -> Result<Keyset> {
+    let mut done = complete_keysets(
+        app,
+        vault,
+        store,
+        session_key,
+        seed,
+        std::slice::from_ref(keyset),
+    )?;
+    Ok(done.remove(0))
+}
+
+/// [`complete_keyset`] for several keysets at once: the missing keys of all
+/// of them go into the same keystore save, so either every keyset gets its
+/// keys or none does.
+pub(crate) fn complete_keysets(
+    app: &AppHandle,
+    vault: &mut VaultState,
+    store: &mut Vec<KeyEntry>,
+    session_key: &[u8; 32],
+    seed: &[u8; 64],
+    keysets: &[Keyset],
+) -> Result<Vec<Keyset>> {
+    // Stage on a copy so a failed save leaves the in-memory keystore as it
+    // was on disk.
+    let mut next = store.clone();
+    for keyset in keysets {
+        for planned in missing_planned(store, keyset).map_err(VaultError::Keyset)? {
+            let mut entry = derive_key_entry(
+                seed,
+                planned.key_type.clone(),
+                planned.purpose,
+                planned.account,
+                planned.index,
+            );
+            entry.metadata.keyset_id = Some(keyset.id.clone());
+            entry.metadata.ceremony_id = keyset.ceremony_id.clone();
+            entry.metadata.tags = keyset.tags.clone();
+            next.push(entry);
+        }
+    }
+    let created: Vec<String> = next[store.len()..].iter().map(|k| k.id.clone()).collect();
+    if !created.is_empty() {
+        save_keys(app, &vault.keys_file, session_key, &next)?;
+        *store = next;
+    }
+
+    let now = Utc::now();
+    let mut done = Vec::with_capacity(keysets.len());
+    for keyset in keysets {
+        let record = vault
+            .keysets
+            .iter_mut()
+            .find(|k| k.id == keyset.id)
+            .ok_or_else(|| VaultError::Keyset(format!("keyset {} disappeared", keyset.id)))?;
+        record.status = KeysetStatus::Complete;
+        record.completed_at = Some(now);
+        done.push(record.clone());
+    }
+    persist_vault(app, vault)?;
+    for id in &created {
+        fire_hooks(
+            app,
+            vault,
+            HookEvent::KeyCreated,
+            id,
+            &format!("Key {id} created for a keyset"),
+        );
+    }
+    Ok(done)
+}
+
+#[tauri::command]
+pub fn list_keysets(vault: State<'_, VaultMutex>) -> Result<Vec<Keyset>> {
+    Ok(vault.0.lock().unwrap().keysets.clone())
+}
+
+/// Remove a keyset and every key generated for it. The record is flipped back
+/// to `Pending` first, so an interrupted rollback can be finished by calling
+/// it again or undone by retrying `generate_keyset`. A `Pending` keyset only
+/// needs an unlocked vault; a `Complete` one holds keys in use, so it also
+/// needs the vault `password` and a `consent_token` from `request_consent`.
+/// The removed keys can be put back with `undo_last_operation` for the
+/// vault's undo window.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn rollback_keyset(
+    app: AppHandle,
+    keyset_id: String,
+    password: Option<String>,
+    consent_token: Option<String>,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    session: State<'_, SessionKey>,
+) -> Result<usize> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let complete = keyset_status(&vault, &keyset_id)? != KeysetStatus::Pending;
+    if complete {
+        let password = password.ok_or(VaultError::InvalidPassword)?;
+        verify_password(&app, &vault, &password)?;
+        require_consent(
+            &app,
+            consent_token.as_deref().unwrap_or_default(),
+            &ConsentOperation::RollbackKeyset {
+                keyset_id: keyset_id.clone(),
+            },
+        )?;
+    }
+    let mut vault = vault.0.lock().unwrap();
+    let mut store = keystore.0.lock().unwrap();
+
+    let pos = vault
+        .keysets
+        .iter()
+        .position(|k| k.id == keyset_id)
+        .ok_or_else(|| VaultError::Keyset(format!("keyset not found: {keyset_id}")))?;
+    if vault.keysets[pos].status != KeysetStatus::Pending {
+        if !complete {
+            return Err(VaultError::Keyset(format!(
+                "keyset {keyset_id} was completed meanwhile; roll it back with the password"
+            )));
+        }
+        vault.keysets[pos].status = KeysetStatus::Pending;
+        vault.keysets[pos].completed_at = None;
+        persist_vault(&app, &vault)?;
+    }
+
+    let (removed, next): (Vec<KeyEntry>, Vec<KeyEntry>) = store
+        .iter()
+        .cloned()
+        .partition(|k| k.metadata.keyset_id.as_deref() == Some(keyset_id.as_str()));
+    if !removed.is_empty() {
+        save_keys(&app, &vault.keys_file, &session_key, &next)?;
+        *store = next;
+    }
+
+    vault.keysets.remove(pos);
+    persist_vault(&app, &vault)?;
+    let count = removed.len();
+    if count > 0 {
+        let now = Utc::now();
+        let entries: Vec<TrashedEntry> = removed
+            .iter()
+            .map(|k| TrashedEntry {
+                kind: TrashKind::Key,
+                id: k.id.clone(),
+                label: k.metadata.label.clone(),
+                trashed_at: now,
+                purge_after: None,
+            })
+            .collect();
+        stash_purge(
+            &app,
+            &session_key,
+            UndoKind::RollbackKeyset,
+            vault.undo_window_secs,
+            &entries,
+            removed,
+            Vec::new(),
+        );
+    }
+    drop(store);
+    drop(vault);
+    if count > 0 {
+        cascade_deletes(&app);
+    }
+    Ok(count)
+}
+
+fn keyset_status(vault: &State<'_, VaultMutex>, keyset_id: &str) -> Result<KeysetStatus> {
+    vault
+        .0
+        .lock()
+        .unwrap()
+        .keysets
+        .iter()
+        .find(|k| k.id == keyset_id)
+        .map(|k| k.status)
+        .ok_or_else(|| VaultError::Keyset(format!("keyset not found: {keyset_id}")))
+}
+
+/// Save a keyset template. Requires an unlocked vault.
+#[tauri::command]
+pub fn save_keyset_template(
+    app: AppHandle,
+    draft: KeysetTemplateDraft,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<KeysetTemplate> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    let template = build_template(&draft, Utc::now()).map_err(VaultError::Keyset)?;
+    let mut vault = vault.0.lock().unwrap();
+    if vault
+        .keyset_templates
+        .iter()
+        .any(|t| t.name.eq_ignore_ascii_case(&template.name))
+    {
+        return Err(VaultError::Keyset(format!(
+            "a template named {} already exists",
+            template.name
+        )));
+    }
+    let mut next = vault.clone();
+    next.keyset_templates.push(template.clone());
+    persist_vault(&app, &next)?;
+    *vault = next;
+    Ok(template)
+}
+
+#[tauri::command]
+pub fn list_keyset_templates(vault: State<'_, VaultMutex>) -> Result<Vec<KeysetTemplate>> {
+    Ok(vault.0.lock().unwrap().keyset_templates.clone())
+}
+
+/// Delete a template. Keysets already provisioned from it are kept.
+#[tauri::command]
+pub fn delete_keyset_template(
+    app: AppHandle,
+    template_id: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    let mut vault = vault.0.lock().unwrap();
+    let mut next = vault.clone();
+    next.keyset_templates.retain(|t| t.id != template_id);
+    if next.keyset_templates.len() == vault.keyset_templates.len() {
+        return Err(VaultError::Keyset(format!(
+            "template not found: {template_id}"
+        )));
+    }
+    persist_vault(&app, &next)?;
+    *vault = next;
+    Ok(())
+}
+
+/// Provision every keyset of a template as one unit. All keysets are planned
+/// and recorded as `Pending` together, their keys are written in a single
+/// keystore save, and then they are all marked `Complete`, so a failure never
+/// leaves part of the layout generated. Retrying with the same idempotency
+/// key returns the finished keysets or resumes the pending ones.
+#[tauri::command]
+pub fn create_keysets_from_template(
+    app: AppHandle,
+    template_id: String,
+    idempotency_key: String,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<Vec<Keyset>> {
+    enforce_policy(&vault, PolicyGate::KeyGeneration)?;
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let mut vault = vault.0.lock().unwrap();
+    let mut store = keystore.0.lock().unwrap();
+
+    let template = vault
+        .keyset_templates
+        .iter()
+        .find(|t| t.id == template_id)
+        .cloned()
+        .ok_or_else(|| VaultError::Keyset(format!("template not found: {template_id}")))?;
+    let existing: Vec<Option<Keyset>> = (0..template.keysets.len())
+        .map(|position| {
+            let key = template_keyset_key(&idempotency_key, position);
+            vault
+                .keysets
+                .iter()
+                .find(|k| k.idempotency_key == key)
+                .cloned()
+        })
+        .collect();
+
+    let keysets = if existing.iter().all(Option::is_none) {
+        let planned = plan_template(
+            &store,
+            &template,
+            &idempotency_key,
+            vault.address_gap_limit,
+            Utc::now(),
+        )
+        .map_err(VaultError::Keyset)?;
+        vault.keysets.extend(planned.iter().cloned());
+        persist_vault(&app, &vault)?;
+        planned
+    } else {
+        existing
+            .into_iter()
+            .map(|k| {
+                k.filter(|k| k.template_id.as_deref() == Some(template.id.as_str()))
+                    .ok_or_else(|| {
+                        VaultError::Keyset(format!(
+                            "idempotency key {idempotency_key} was already used for a different \
+                             template run"
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let pending: Vec<Keyset> = keysets
+        .iter()
+        .filter(|k| k.status == KeysetStatus::Pending)
+        .cloned()
+        .collect();
+    if pending.is_empty() {
+        return Ok(keysets);
+    }
+    let guard = master_seed.0.lock().unwrap();
+    let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
+    complete_keysets(&app, &mut vault, &mut store, &session_key, seed, &pending)?;
+    tracing::info!(
+        target: "audit",
+        template = %template.name,
+        keysets = keysets.len(),
+        "keysets provisioned from template"
+    );
+    Ok(keysets
+        .iter()
+        .filter_map(|k| vault.keysets.iter().find(|v| v.id == k.id).cloned())
+        .collect())
+}
+
+/// The vault identity public key that signs key attestations, for verifiers
+/// to pin.
+#[tauri::command]
+pub fn get_vault_identity(master_seed: State<'_, MasterSeed>) -> Result<String> {
+    let guard = master_seed.0.lock().unwrap();
+    let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
+    Ok(attestation::vault_identity(seed).0.to_hex())
+}
+
+/// Export a document listing every public key of a completed keyset with its
+/// role, derivation path, generation time and entropy source, signed by the
+/// vault identity, so third parties can check the keys' provenance without
+/// seeing any private material.
+#[tauri::command]
+pub fn export_zap_key_attestation(
+    app: AppHandle,
+    key_set_id: String,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<SignedKeyAttestation> {
+    let keyset = vault
+        .0
+        .lock()
+        .unwrap()
+        .keysets
+        .iter()
+        .find(|k| k.id == key_set_id)
+        .cloned()
+        .ok_or_else(|| VaultError::Keyset(format!("keyset not found: {key_set_id}")))?;
+    let generated_at = match (keyset.status, keyset.completed_at) {
+        (KeysetStatus::Complete, Some(at)) => at,
+        _ => {
+            return Err(VaultError::Keyset(format!(
+                "keyset {key_set_id} is not complete"
+            )))
+        }
+    };
+
+    let entropy_source = match &keyset.ceremony_id {
+        None => EntropySource::VaultMasterSeed,
+        Some(ceremony_id) => {
+            let ceremony = load_ceremonies(&app)?
+                .into_iter()
+                .find(|c| &c.id == ceremony_id)
+                .ok_or_else(|| VaultError::KeyNotFound(ceremony_id.clone()))?;
+            EntropySource::GenesisCeremony {
+                ceremony_id: ceremony.id,
+                operators: ceremony.manifest.map(|m| m.operators).unwrap_or_default(),
+                attestation_count: ceremony.attestations.len(),
+            }
+        }
+    };
+
+    let keys = {
+        let store = keystore.0.lock().unwrap();
+        keyset
+            .planned
+            .iter()
+            .map(|p| {
+                let key = store
+                    .iter()
+                    .find(|k| {
+                        k.metadata.keyset_id.as_deref() == Some(keyset.id.as_str())
+                            && k.metadata.derivation_path == p.derivation_path
+                    })
+                    .ok_or_else(|| {
+                        VaultError::Keyset(format!(
+                            "{} is missing from the keystore",
+                            p.derivation_path
+                        ))
+                    })?;
+                Ok(AttestedKey {
+                    key_id: key.id.clone(),
+                    role: key.metadata.key_type.clone(),
+                    derivation_path: key.metadata.derivation_path.clone(),
+                    public_key_hex: key.public_key_hex.clone(),
+                    address: key.metadata.address.clone(),
+                    created_at: key.metadata.created_at,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let guard = master_seed.0.lock().unwrap();
+    let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
+    let (identity_pk, identity_sk) = attestation::vault_identity(seed);
+    let doc = KeyAttestation {
+        version: ATTESTATION_VERSION,
+        keyset_id: keyset.id,
+        keyset_name: keyset.name,
+        generated_at,
+        entropy_source,
+        keys,
+        issued_at: Utc::now(),
+        vault_public_key_hex: identity_pk.to_hex(),
+    };
+    let instance = instance_key(&app, seed)?;
+    Ok(attestation::sign_attestation(
+        &identity_sk,
+        Some(&instance),
+        &doc,
+    )?)
+}
+
+/// Check a key attestation's signature and return the attested document.
+#[tauri::command]
+pub fn verify_zap_key_attestation(signed: SignedKeyAttestation) -> Result<KeyAttestation> {
+    Ok(attestation::verify_attestation(&signed)?)
+}
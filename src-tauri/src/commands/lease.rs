@@ -0,0 +1,207 @@
+use crate::commands::keys::{atomic_write, keys_file_path, SessionKey};
+use crate::commands::vault::lock_vault;
+use crate::error::{Result, VaultError};
+use crate::models::lease::{
+    check_claim, WriteLease, WriteLeaseStatus, LEASE_HEARTBEAT_SECS, WRITE_LEASE_FILE,
+};
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Emitted when another instance took over the write lease and the vault was
+/// locked here.
+pub const WRITE_LEASE_LOST_EVENT: &str = "write_lease_lost";
+
+/// The lease this instance holds while a session is open, and where.
+pub struct HeldLease {
+    path: PathBuf,
+    lease: WriteLease,
+}
+
+#[derive(Default)]
+pub struct WriteLeaseHolder(pub Mutex<Option<HeldLease>>);
+
+/// Best-effort name of this host, so a lease left by a crashed process here
+/// can be recognised without waiting for it to go stale.
+fn local_host() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .or_else(|_| std::fs::read_to_string("/etc/hostname"))
+        .map(|h| h.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Whether `pid` is a running process other than this one. Only Linux can
+/// tell cheaply; elsewhere a lease is judged by its heartbeat alone.
+#[cfg(target_os = "linux")]
+fn process_alive(pid: u32) -> bool {
+    pid != std::process::id() && Path::new(&format!("/proc/{pid}")).exists()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_alive(pid: u32) -> bool {
+    pid != std::process::id()
+}
+
+fn read_lease(path: &Path) -> Result<Option<WriteLease>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = std::fs::read(path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    // A torn or foreign file is no lease; it is overwritten on acquire.
+    Ok(serde_json::from_slice(&data).ok())
+}
+
+fn in_use(lease: &WriteLease) -> VaultError {
+    let host = if lease.host.is_empty() {
+        "an unknown host"
+    } else {
+        lease.host.as_str()
+    };
+    VaultError::VaultInUse(format!(
+        "process {} on {host} has held it since {}",
+        lease.pid, lease.acquired_at
+    ))
+}
+
+/// Take the write lease for the vault's data directory, or refuse with
+/// `VaultInUse` while another live instance holds it. A stale lease is
+/// taken over. Called by every path that opens a session.
+pub(crate) fn acquire_write_lease(app: &AppHandle) -> Result<()> {
+    let path = keys_file_path(app, WRITE_LEASE_FILE)?;
+    let holder = app.state::<WriteLeaseHolder>();
+    let mut held = holder.0.lock().unwrap();
+    let holder_id = match held.as_ref() {
+        Some(h) if h.path == path => h.lease.holder_id.clone(),
+        _ => uuid::Uuid::new_v4().to_string(),
+    };
+    let now = Utc::now();
+    let host = local_host();
+    let existing = read_lease(&path)?;
+    if let Err(lease) = check_claim(existing.as_ref(), &holder_id, now, &host, process_alive) {
+        tracing::warn!(
+            target: "audit",
+            pid = lease.pid,
+            host = %lease.host,
+            "session refused: the vault is open in another instance"
+        );
+        return Err(in_use(&lease));
+    }
+    if let Some(stale) = existing.filter(|l| l.holder_id != holder_id) {
+        tracing::warn!(
+            target: "audit",
+            pid = stale.pid,
+            host = %stale.host,
+            heartbeat_at = %stale.heartbeat_at,
+            "stale write lease taken over"
+        );
+    }
+
+    let lease = WriteLease::new(holder_id, std::process::id(), host, now);
+    atomic_write(&path, &serde_json::to_vec_pretty(&lease)?)?;
+    // Two instances taking a free lease at once both write it; the last
+    // rename wins and the other backs off here. A loser that re-reads too
+    // early notices on its next heartbeat instead.
+    match read_lease(&path)? {
+        Some(on_disk) if on_disk.holder_id != lease.holder_id => return Err(in_use(&on_disk)),
+        _ => {}
+    }
+    *held = Some(HeldLease { path, lease });
+    Ok(())
+}
+
+/// Give up the write lease, if this instance holds it. Best effort: the
+/// file may already be gone with its drive, and a lease left behind goes
+/// stale on its own.
+pub(crate) fn release_write_lease(app: &AppHandle) {
+    let Some(held) = app.state::<WriteLeaseHolder>().0.lock().unwrap().take() else {
+        return;
+    };
+    match read_lease(&held.path) {
+        Ok(Some(on_disk)) if on_disk.holder_id == held.lease.holder_id => {
+            if let Err(e) = std::fs::remove_file(&held.path) {
+                tracing::warn!("could not release the write lease: {e}");
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether this instance holds a write lease, so may write the vault's
+/// files.
+pub(crate) fn holds_write_lease(app: &AppHandle) -> bool {
+    app.state::<WriteLeaseHolder>().0.lock().unwrap().is_some()
+}
+
+/// Renew the held lease. Returns `false` when another instance has taken
+/// it over, in which case this instance no longer holds it.
+fn renew_write_lease(app: &AppHandle) -> Result<bool> {
+    let holder = app.state::<WriteLeaseHolder>();
+    let mut held = holder.0.lock().unwrap();
+    let Some(current) = held.as_mut() else {
+        return Ok(true);
+    };
+    if let Some(on_disk) = read_lease(&current.path)? {
+        if on_disk.holder_id != current.lease.holder_id {
+            *held = None;
+            return Ok(false);
+        }
+    }
+    current.lease.heartbeat_at = Utc::now();
+    atomic_write(&current.path, &serde_json::to_vec_pretty(&current.lease)?)?;
+    Ok(true)
+}
+
+/// Whether this instance holds the write lease for the vault's data
+/// directory, and who does if not.
+#[tauri::command]
+pub fn write_lease_status(app: AppHandle) -> Result<WriteLeaseStatus> {
+    let path = keys_file_path(&app, WRITE_LEASE_FILE)?;
+    let lease = read_lease(&path)?;
+    let held = match (
+        app.state::<WriteLeaseHolder>().0.lock().unwrap().as_ref(),
+        &lease,
+    ) {
+        (Some(h), Some(l)) => h.path == path && h.lease.holder_id == l.holder_id,
+        _ => false,
+    };
+    let stale = !held
+        && lease
+            .as_ref()
+            .is_some_and(|l| l.is_stale(Utc::now(), &local_host(), process_alive));
+    Ok(WriteLeaseStatus { held, lease, stale })
+}
+
+/// Renew the lease while a session is open. If another instance has taken
+/// it over, for example after this machine slept past the stale timeout,
+/// lock the vault here so the two never write at once.
+pub fn spawn_lease_heartbeat(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(StdDuration::from_secs(LEASE_HEARTBEAT_SECS));
+        match renew_write_lease(&app) {
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!("could not renew the write lease: {e}");
+                continue;
+            }
+        }
+        if app.state::<SessionKey>().0.lock().unwrap().is_some() {
+            if let Err(e) = lock_vault(
+                app.clone(),
+                app.state(),
+                app.state(),
+                app.state(),
+                app.state(),
+                app.state(),
+                app.state(),
+            ) {
+                tracing::warn!("vault locked after losing the write lease: {e}");
+            }
+        }
+        tracing::warn!(target: "audit", "vault locked: write lease taken over by another instance");
+        let _ = app.emit(WRITE_LEASE_LOST_EVENT, ());
+    });
+}
@@ -0,0 +1,125 @@
+use crate::commands::keys::SessionKey;
+use crate::commands::policy::enforce_policy_at;
+use crate::commands::vault::{persist_vault, verify_password, VaultMutex};
+use crate::error::{Result, VaultError};
+use crate::models::policy::PolicyGate;
+use crate::models::rate_limit::{RateLimit, RateLimits, SensitiveOp};
+use chrono::Utc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+/// Sliding-window call log for sensitive commands, plus when the password was
+/// last re-entered for the security policy. In-memory (per process), like
+/// [`crate::commands::vault::UnlockThrottle`]; pure logic taking `now`
+/// explicitly so it is unit-testable.
+#[derive(Debug, Default)]
+pub struct RateWindow {
+    calls: HashMap<SensitiveOp, VecDeque<u64>>,
+    reauthenticated_at: Option<u64>,
+}
+
+impl RateWindow {
+    pub fn record_reauth(&mut self, now: u64) {
+        self.reauthenticated_at = Some(now);
+    }
+
+    pub fn clear_reauth(&mut self) {
+        self.reauthenticated_at = None;
+    }
+
+    pub fn reauthenticated_at(&self) -> Option<u64> {
+        self.reauthenticated_at
+    }
+
+    /// Record a call to `op` at `now`, or reject it if `limit` calls already
+    /// happened within the window. Rejected calls are not recorded, so a
+    /// caller hammering the limit does not extend its own lockout.
+    pub fn check(&mut self, op: SensitiveOp, limit: RateLimit, now: u64) -> Result<()> {
+        let calls = self.calls.entry(op).or_default();
+        while calls
+            .front()
+            .is_some_and(|t| now >= t.saturating_add(limit.window_secs))
+        {
+            calls.pop_front();
+        }
+        if calls.len() >= limit.max_calls as usize {
+            let oldest = calls.front().copied().unwrap_or(now);
+            return Err(VaultError::RateLimited {
+                operation: op.as_str(),
+                retry_after_secs: oldest.saturating_add(limit.window_secs) - now,
+            });
+        }
+        calls.push_back(now);
+        Ok(())
+    }
+}
+
+pub struct RateLimiter(pub Mutex<RateWindow>);
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter(Mutex::new(RateWindow::default()))
+    }
+}
+
+/// Count one `op` against the vault's configured limit. Exceeded attempts
+/// are written to the `audit` tracing target. A `Decrypt` is also checked
+/// against the security policy's re-authentication rule first. Call before
+/// taking any other vault lock.
+pub(crate) fn enforce(
+    vault: &State<'_, VaultMutex>,
+    limiter: &State<'_, RateLimiter>,
+    op: SensitiveOp,
+) -> Result<()> {
+    let now = Utc::now().timestamp() as u64;
+    let limit = vault.0.lock().unwrap().rate_limits.get(op);
+    let mut window = limiter.0.lock().unwrap();
+    if op == SensitiveOp::Decrypt {
+        enforce_policy_at(vault, PolicyGate::Decrypt, window.reauthenticated_at(), now)?;
+    }
+    let result = window.check(op, limit, now);
+    if let Err(VaultError::RateLimited {
+        retry_after_secs, ..
+    }) = &result
+    {
+        tracing::warn!(
+            target: "audit",
+            operation = op.as_str(),
+            max_calls = limit.max_calls,
+            window_secs = limit.window_secs,
+            retry_after_secs,
+            "sensitive operation rate limit exceeded"
+        );
+    }
+    result
+}
+
+#[tauri::command]
+pub fn get_rate_limits(vault: State<'_, VaultMutex>) -> Result<RateLimits> {
+    Ok(vault.0.lock().unwrap().rate_limits)
+}
+
+/// Change the per-operation limits. Requires an unlocked vault and the
+/// password, so neither a locked session nor an unattended unlocked one can
+/// loosen them.
+#[tauri::command]
+pub fn set_rate_limits(
+    app: AppHandle,
+    limits: RateLimits,
+    password: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<RateLimits> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    limits.validate().map_err(VaultError::InvalidMetadata)?;
+    verify_password(&app, &vault, &password)?;
+    let mut vault = vault.0.lock().unwrap();
+    let mut next = vault.clone();
+    next.rate_limits = limits;
+    persist_vault(&app, &next)?;
+    *vault = next;
+    Ok(limits)
+}
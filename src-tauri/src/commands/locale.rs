@@ -0,0 +1,50 @@
+use crate::commands::keys::SessionKey;
+use crate::commands::vault::{persist_vault, VaultMutex};
+use crate::error::{Result, VaultError};
+use crate::i18n::{self, LocaleInfo};
+use tauri::{AppHandle, Manager, State};
+
+/// The locale backend text is written in, for helpers without the vault at
+/// hand. Takes the vault lock, so call it before holding it.
+pub(crate) fn app_locale(app: &AppHandle) -> &'static str {
+    app.state::<VaultMutex>().0.lock().unwrap().locale()
+}
+
+/// Locales notifications, warnings and reports can be written in.
+#[tauri::command]
+pub fn list_locales() -> Vec<LocaleInfo> {
+    i18n::supported_locales()
+}
+
+/// The locale in use, after falling back from the stored setting.
+#[tauri::command]
+pub fn get_locale(vault: State<'_, VaultMutex>) -> String {
+    vault.0.lock().unwrap().locale().to_string()
+}
+
+/// Set the locale for backend text, or clear it with `None` to use the
+/// default. Must be one of [`list_locales`]. Requires an unlocked vault.
+#[tauri::command]
+pub fn set_locale(
+    app: AppHandle,
+    locale: Option<String>,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<String> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    if let Some(requested) = &locale {
+        if !i18n::supported_locales().iter().any(|l| &l.id == requested) {
+            return Err(VaultError::InvalidMetadata(format!(
+                "unsupported locale {requested:?}"
+            )));
+        }
+    }
+    let mut vault = vault.0.lock().unwrap();
+    let mut next = vault.clone();
+    next.locale = locale;
+    persist_vault(&app, &next)?;
+    *vault = next;
+    Ok(vault.locale().to_string())
+}
@@ -0,0 +1,81 @@
+use crate::commands::items::ItemStore;
+use crate::commands::keys::{atomic_write, KeyStore, MasterSeed};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::password_policy::enforce_password_strength;
+use crate::commands::vault::VaultMutex;
+use crate::crypto::mobile;
+use crate::error::{Result, VaultError};
+use crate::models::item::VaultItemPublic;
+use crate::models::mobile::{MobileBundleContents, MobileExportSummary, MobileKey};
+use crate::models::rate_limit::SensitiveOp;
+use chrono::Utc;
+use tauri::State;
+
+/// Write a password-encrypted, signed read-only bundle for a mobile
+/// companion to `path`. It holds every key's address and path plus the
+/// public view of the items in `item_ids`; never a secret. Requires an
+/// unlocked vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn export_mobile_bundle(
+    path: String,
+    password: String,
+    item_ids: Vec<String>,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    master_seed: State<'_, MasterSeed>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<MobileExportSummary> {
+    enforce(&vault, &limiter, SensitiveOp::Export)?;
+    enforce_password_strength(&vault.0.lock().unwrap(), &password)?;
+    let seed = {
+        let guard = master_seed.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let keys: Vec<MobileKey> = keystore
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|k| k.metadata.trashed_at.is_none())
+        .map(|k| MobileKey {
+            id: k.id.clone(),
+            label: k.metadata.label.clone(),
+            key_type: k.metadata.key_type.clone(),
+            address: k.metadata.address.clone(),
+            derivation_path: k.metadata.derivation_path.clone(),
+            tags: k.metadata.tags.clone(),
+        })
+        .collect();
+    let selected: Vec<VaultItemPublic> = {
+        let items = items.0.lock().unwrap();
+        item_ids
+            .iter()
+            .map(|id| {
+                items
+                    .iter()
+                    .find(|i| &i.id == id && i.trashed_at.is_none())
+                    .map(|i| i.to_public())
+                    .ok_or_else(|| VaultError::KeyNotFound(id.clone()))
+            })
+            .collect::<Result<_>>()?
+    };
+    let contents = MobileBundleContents {
+        keys,
+        items: selected,
+    };
+    let bundle = mobile::seal_bundle(&seed, &password, &contents, Utc::now())?;
+    atomic_write(std::path::Path::new(&path), &serde_json::to_vec(&bundle)?)?;
+    tracing::info!(
+        target: "audit",
+        keys = contents.keys.len(),
+        items = contents.items.len(),
+        "mobile bundle exported"
+    );
+    Ok(MobileExportSummary {
+        keys: contents.keys.len(),
+        items: contents.items.len(),
+        public_key_hex: bundle.public_key_hex,
+    })
+}
@@ -1,5 +1,61 @@
+pub mod addresses;
+pub mod admin_api;
+pub mod agent;
 pub mod airgap;
+pub mod api_tokens;
+pub mod backup;
+pub mod capsule;
+pub mod ceremony;
+pub mod compliance;
+pub mod consent;
+pub mod contacts;
+pub mod custody;
+pub mod dashboard;
+pub mod derivation;
+pub mod drive_image;
+pub mod drive_seal;
+pub mod drive_trust;
+pub mod emergency;
+pub mod frost;
+pub mod health;
+pub mod hooks;
+pub mod instance;
+pub mod integrity;
+pub mod items;
+pub mod key_drive;
+pub mod key_escrow;
 pub mod keys;
+pub mod keysets;
+pub mod lease;
+pub mod limits;
+pub mod locale;
+pub mod mobile;
+pub mod musig2;
+pub mod notes;
+pub mod notifications;
+pub mod pairing;
+pub mod passkey;
+pub mod password_policy;
+pub mod plugins;
+pub mod policy;
+pub mod portable;
+pub mod portfolio;
+pub mod price;
+pub mod profiles;
+pub mod public_bundle;
+pub mod quick_access;
+pub mod recovery;
+pub mod remote;
+pub mod selftest;
 pub mod signing;
+pub mod signing_requests;
+pub mod spending;
+pub mod ssh;
+pub mod sync;
+pub mod trash;
+pub mod treasury;
+pub mod tx_review;
+pub mod undo;
 pub mod vault;
+pub mod wireguard;
 pub mod yubikey;
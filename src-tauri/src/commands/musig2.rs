@@ -0,0 +1,179 @@
+use crate::commands::keys::{load_sealed, save_sealed, session_key, MasterSeed, SessionKey};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::spending::enforce_spending;
+use crate::commands::tx_review::confirm_reviewed;
+use crate::commands::vault::VaultMutex;
+use crate::crypto::musig2;
+use crate::error::{Result, VaultError};
+use crate::models::musig2::{
+    MuSig2PartialSignature, MuSig2PublicNonce, MuSig2SessionInfo, MuSig2Signature, MuSig2Store,
+};
+use crate::models::rate_limit::SensitiveOp;
+use chrono::Utc;
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
+
+/// Open MuSig2 sessions, encrypted with the session key next to
+/// `vault.json`. Secret nonces live here until they are used.
+pub const MUSIG2_FILE: &str = "musig2.json";
+
+fn master_seed(master_seed: &State<'_, MasterSeed>) -> Result<Zeroizing<[u8; 64]>> {
+    let guard = master_seed.0.lock().unwrap();
+    Ok(guard.as_ref().ok_or(VaultError::NotInitialized)?.clone())
+}
+
+fn load_store(app: &AppHandle, key: &[u8; 32]) -> Result<MuSig2Store> {
+    load_sealed(app, MUSIG2_FILE, key)
+}
+
+fn save_store(app: &AppHandle, key: &[u8; 32], store: &MuSig2Store) -> Result<()> {
+    save_sealed(app, MUSIG2_FILE, key, store)
+}
+
+/// This vault's individual MuSig2 key, to give to co-signers. Requires an
+/// unlocked HD vault.
+#[tauri::command]
+pub fn musig2_public_key(seed: State<'_, MasterSeed>) -> Result<String> {
+    Ok(musig2::public_key_hex(&master_seed(&seed)?))
+}
+
+/// The taproot output key `public_keys` aggregate to. Key order does not
+/// matter.
+#[tauri::command]
+pub fn musig2_output_key(
+    public_keys: Vec<String>,
+    merkle_root_hex: Option<String>,
+) -> Result<String> {
+    Ok(musig2::output_key_hex(
+        &public_keys,
+        merkle_root_hex.as_deref(),
+    )?)
+}
+
+/// Open a session to co-sign the 32-byte sighash `message_hex` with
+/// `public_keys`, one of which must be this vault's. Send the returned public
+/// nonce to the other signers. Requires an unlocked HD vault.
+#[tauri::command]
+pub fn musig2_create_session(
+    app: AppHandle,
+    public_keys: Vec<String>,
+    message_hex: String,
+    merkle_root_hex: Option<String>,
+    session: State<'_, SessionKey>,
+    seed: State<'_, MasterSeed>,
+) -> Result<MuSig2SessionInfo> {
+    let key = session_key(&session)?;
+    let created = musig2::new_session(
+        &master_seed(&seed)?,
+        &public_keys,
+        &message_hex,
+        merkle_root_hex.as_deref(),
+        Utc::now(),
+    )?;
+    let info = musig2::session_info(&created)?;
+    let mut store = load_store(&app, &key)?;
+    store.sessions.push(created);
+    save_store(&app, &key, &store)?;
+    tracing::info!(
+        target: "audit",
+        session_id = %info.session_id,
+        output_key = %info.output_key_hex,
+        "MuSig2 session created"
+    );
+    Ok(info)
+}
+
+/// Open MuSig2 sessions. Requires an unlocked vault.
+#[tauri::command]
+pub fn musig2_list_sessions(
+    app: AppHandle,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<MuSig2SessionInfo>> {
+    let key = session_key(&session)?;
+    let store = load_store(&app, &key)?;
+    store
+        .sessions
+        .iter()
+        .map(|s| Ok(musig2::session_info(s)?))
+        .collect()
+}
+
+/// Aggregate every signer's public nonce and return this vault's partial
+/// signature. The secret nonce is deleted and the session saved before the
+/// partial signature is returned, so no nonce is ever used twice.
+/// `summary_hash_hex` is the hash `review_transaction` returned for the
+/// session's sighash. Requires an unlocked HD vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn musig2_sign(
+    app: AppHandle,
+    session_id: String,
+    public_nonces: Vec<MuSig2PublicNonce>,
+    summary_hash_hex: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    seed: State<'_, MasterSeed>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<MuSig2PartialSignature> {
+    enforce(&vault, &limiter, SensitiveOp::Decrypt)?;
+    let key = session_key(&session)?;
+    let mut store = load_store(&app, &key)?;
+    let open = store
+        .sessions
+        .iter_mut()
+        .find(|s| s.session_id == session_id)
+        .ok_or_else(|| VaultError::KeyNotFound(session_id.clone()))?;
+    let message =
+        hex::decode(&open.message_hex).map_err(|e| VaultError::InvalidMetadata(e.to_string()))?;
+    let summary = confirm_reviewed(&app, &session, None, &message, &summary_hash_hex)?;
+    let output_key = musig2::output_key_hex(&open.public_keys, open.merkle_root_hex.as_deref())?;
+    enforce_spending(&app, &session, &output_key, &summary)?;
+    let partial = musig2::sign(&master_seed(&seed)?, open, &public_nonces)?;
+    let message = open.message_hex.clone();
+    save_store(&app, &key, &store)?;
+    tracing::info!(
+        target: "audit",
+        session_id = %session_id,
+        message = %message,
+        "MuSig2 partial signature produced"
+    );
+    Ok(partial)
+}
+
+/// Verify every signer's partial signature and assemble the final taproot
+/// signature. This vault must have signed the session first. Requires an
+/// unlocked vault.
+#[tauri::command]
+pub fn musig2_finalize(
+    app: AppHandle,
+    session_id: String,
+    partials: Vec<MuSig2PartialSignature>,
+    session: State<'_, SessionKey>,
+) -> Result<MuSig2Signature> {
+    let key = session_key(&session)?;
+    let store = load_store(&app, &key)?;
+    let open = store
+        .sessions
+        .iter()
+        .find(|s| s.session_id == session_id)
+        .ok_or_else(|| VaultError::KeyNotFound(session_id.clone()))?;
+    Ok(musig2::finalize(open, &partials)?)
+}
+
+/// Drop a session, signed or not. An unused nonce goes with it. Requires an
+/// unlocked vault.
+#[tauri::command]
+pub fn musig2_delete_session(
+    app: AppHandle,
+    session_id: String,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    let key = session_key(&session)?;
+    let mut store = load_store(&app, &key)?;
+    let before = store.sessions.len();
+    store.sessions.retain(|s| s.session_id != session_id);
+    if store.sessions.len() == before {
+        return Err(VaultError::KeyNotFound(session_id));
+    }
+    save_store(&app, &key, &store)
+}
@@ -0,0 +1,233 @@
+use crate::commands::backup::Drives;
+use crate::commands::keys::{atomic_write, keys_file_path, session_key, SessionKey};
+use crate::commands::vault::LockEpoch;
+use crate::crypto::encryption;
+use crate::drive::{DriveBackend, DriveError};
+use crate::error::{Result, VaultError};
+use crate::models::display_cache::DisplayCache;
+use crate::models::note::{validate_note_text, Note, NoteBody, NoteTarget, StoredNote};
+use chrono::{DateTime, Utc};
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+use zeroize::Zeroizing;
+
+/// Notes on drives and backups live in metadata next to `vault.json`; only
+/// their text is encrypted, under the vault key.
+pub const NOTES_FILE: &str = "notes.json";
+/// Directory on a drive holding encrypted copies of the notes attached to it.
+pub const NOTE_MIRROR_ROOT: &str = "zap-vault-notes";
+
+/// A decrypted note's text, with what it was decrypted from, so a note
+/// filed under another target or re-sealed is decrypted again.
+#[derive(Debug, Clone)]
+pub struct OpenedNote {
+    target: NoteTarget,
+    nonce: Vec<u8>,
+    text: Zeroizing<String>,
+}
+
+/// Note text decrypted this session, by note id, so drive details and
+/// backup listings do not decrypt every note on every call. Cleared when
+/// the vault locks.
+#[derive(Default)]
+pub struct NoteCache(pub Mutex<DisplayCache<OpenedNote>>);
+
+fn load_notes(app: &AppHandle) -> Result<Vec<StoredNote>> {
+    let path = keys_file_path(app, NOTES_FILE)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+fn save_notes(app: &AppHandle, notes: &[StoredNote]) -> Result<()> {
+    let path = keys_file_path(app, NOTES_FILE)?;
+    atomic_write(&path, &serde_json::to_vec_pretty(notes)?)
+}
+
+pub fn mirror_path(note_id: &str) -> String {
+    format!("{NOTE_MIRROR_ROOT}/{note_id}.json")
+}
+
+/// Encrypt `text` for `target` under the vault key.
+pub fn seal_note(
+    key: &[u8; 32],
+    target: NoteTarget,
+    text: &str,
+    now: DateTime<Utc>,
+) -> Result<StoredNote> {
+    let body = Zeroizing::new(serde_json::to_vec(&NoteBody {
+        target: target.clone(),
+        text: text.to_string(),
+    })?);
+    let ciphertext =
+        encryption::encrypt_vault(key, &body).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(StoredNote {
+        id: uuid::Uuid::new_v4().to_string(),
+        target,
+        ciphertext,
+        created_at: now,
+        mirrored: false,
+    })
+}
+
+/// Decrypt a note, refusing one whose sealed target does not match the
+/// target it is filed under.
+pub fn open_note(key: &[u8; 32], note: &StoredNote) -> Result<String> {
+    let plain = Zeroizing::new(
+        encryption::decrypt_vault(key, &note.ciphertext)
+            .map_err(|e| VaultError::Storage(e.to_string()))?,
+    );
+    let body: NoteBody = serde_json::from_slice(&plain)?;
+    if body.target != note.target {
+        return Err(VaultError::Storage(format!(
+            "note {} was sealed for a different target",
+            note.id
+        )));
+    }
+    Ok(body.text)
+}
+
+fn to_note(text: Option<String>, stored: &StoredNote) -> Note {
+    Note {
+        id: stored.id.clone(),
+        target: stored.target.clone(),
+        text,
+        created_at: stored.created_at,
+        mirrored: stored.mirrored,
+    }
+}
+
+/// [`open_note`] through the session's [`NoteCache`].
+fn open_cached(app: &AppHandle, key: &[u8; 32], note: &StoredNote) -> Option<String> {
+    let epoch = app.state::<LockEpoch>().0.load(Ordering::SeqCst);
+    let now = Utc::now();
+    let cache = app.state::<NoteCache>();
+    let mut cache = cache.0.lock().unwrap();
+    if let Some(opened) = cache.get(&note.id, epoch, now) {
+        if opened.target == note.target && opened.nonce == note.ciphertext.nonce {
+            return Some(opened.text.to_string());
+        }
+    }
+    let text = open_note(key, note).ok()?;
+    cache.insert(
+        note.id.clone(),
+        epoch,
+        now,
+        OpenedNote {
+            target: note.target.clone(),
+            nonce: note.ciphertext.nonce.clone(),
+            text: Zeroizing::new(text.clone()),
+        },
+    );
+    Some(text)
+}
+
+/// Notes mirrored onto `drive_id`. Files that do not parse are skipped.
+pub fn read_mirrored_notes(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+) -> std::result::Result<Vec<StoredNote>, DriveError> {
+    let mut notes = Vec::new();
+    for name in backend.list_dir(drive_id, NOTE_MIRROR_ROOT)? {
+        if !name.ends_with(".json") {
+            continue;
+        }
+        let data = backend.read_file(drive_id, &format!("{NOTE_MIRROR_ROOT}/{name}"))?;
+        match serde_json::from_slice::<StoredNote>(&data) {
+            Ok(note) if note.target.drive_id() == drive_id => notes.push(note),
+            Ok(_) => {}
+            Err(e) => tracing::warn!(drive_id, file = %name, error = %e, "unreadable note"),
+        }
+    }
+    Ok(notes)
+}
+
+/// Every note for `drive_id`: the local ones, plus any mirrored onto the
+/// drive that this installation does not hold (e.g. after a restore).
+pub(crate) fn notes_for_drive(
+    app: &AppHandle,
+    backend: &dyn DriveBackend,
+    key: Option<&[u8; 32]>,
+    drive_id: &str,
+) -> Result<Vec<Note>> {
+    let mut stored: Vec<StoredNote> = load_notes(app)?
+        .into_iter()
+        .filter(|n| n.target.drive_id() == drive_id)
+        .collect();
+    if let Ok(mirrored) = read_mirrored_notes(backend, drive_id) {
+        for note in mirrored {
+            if !stored.iter().any(|n| n.id == note.id) {
+                stored.push(note);
+            }
+        }
+    }
+    stored.sort_by_key(|n| n.created_at);
+    Ok(stored
+        .iter()
+        .map(|n| to_note(key.and_then(|k| open_cached(app, k, n)), n))
+        .collect())
+}
+
+/// Attach an encrypted note to a drive or backup. With `mirror` an encrypted
+/// copy is also written onto the drive, so the note travels with it.
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn attach_note(
+    app: AppHandle,
+    target: NoteTarget,
+    text: String,
+    mirror: bool,
+    session: State<'_, SessionKey>,
+    drives: State<'_, Drives>,
+) -> Result<Note> {
+    let key = session_key(&session)?;
+    target.validate().map_err(VaultError::InvalidMetadata)?;
+    validate_note_text(&text).map_err(VaultError::InvalidMetadata)?;
+    let mut note = seal_note(&key, target, &text, Utc::now())?;
+    if mirror {
+        note.mirrored = true;
+        drives.0.write_file(
+            note.target.drive_id(),
+            &mirror_path(&note.id),
+            &serde_json::to_vec_pretty(&note)?,
+        )?;
+    }
+    let mut notes = load_notes(&app)?;
+    notes.push(note.clone());
+    save_notes(&app, &notes)?;
+    Ok(to_note(Some(text), &note))
+}
+
+/// Delete a note. Its copy on the drive is removed too if the drive is
+/// attached; otherwise it is left there. Requires an unlocked vault.
+#[tauri::command]
+pub fn delete_note(
+    app: AppHandle,
+    note_id: String,
+    session: State<'_, SessionKey>,
+    drives: State<'_, Drives>,
+) -> Result<()> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    let mut notes = load_notes(&app)?;
+    let pos = notes
+        .iter()
+        .position(|n| n.id == note_id)
+        .ok_or_else(|| VaultError::InvalidMetadata(format!("note {note_id} not found")))?;
+    let note = notes.remove(pos);
+    save_notes(&app, &notes)?;
+    app.state::<NoteCache>().0.lock().unwrap().remove(&note.id);
+    if note.mirrored {
+        if let Err(e) = drives
+            .0
+            .remove_file(note.target.drive_id(), &mirror_path(&note.id))
+        {
+            tracing::warn!(note_id, error = %e, "could not remove mirrored note");
+        }
+    }
+    Ok(())
+}
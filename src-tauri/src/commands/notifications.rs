@@ -0,0 +1,179 @@
+use crate::commands::backup::Drives;
+use crate::commands::drive_seal::check_attached;
+use crate::commands::keys::SessionKey;
+use crate::commands::vault::{lock_vault, persist_vault, InFlightDecrypt, VaultMutex};
+use crate::error::{Result, VaultError};
+use crate::i18n::tr;
+use crate::models::drive::{drive_lock_trigger, DriveInfo, DriveLockTrigger, DriveStatus};
+use crate::models::notification::{NotificationSettings, SecurityEvent};
+use chrono::{Local, Timelike};
+use std::collections::HashSet;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+/// How often the drive watch looks for unplugged drives.
+pub const DRIVE_WATCH_INTERVAL_SECS: u64 = 5;
+/// Emitted with a [`DriveLockTrigger`] when a drive trust rule locks the
+/// vault.
+pub const DRIVE_AUTO_LOCK_EVENT: &str = "drive_auto_lock";
+
+/// Raise `event` as an OS notification, titled in `locale`, unless
+/// `settings` opt out of it. Every event is written to the `audit` tracing
+/// target either way. Takes the settings rather than the vault so callers
+/// holding the vault lock can notify.
+pub(crate) fn notify(
+    app: &AppHandle,
+    settings: &NotificationSettings,
+    locale: &str,
+    event: SecurityEvent,
+    body: &str,
+) {
+    tracing::info!(target: "audit", event = ?event, detail = body, "security event");
+    if !settings.enabled(event) {
+        return;
+    }
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(event.title(locale))
+        .body(body)
+        .show()
+    {
+        tracing::warn!(event = ?event, error = %e, "could not show notification");
+    }
+}
+
+/// Start a decrypt with `key_id`, notifying if it is outside the configured
+/// normal hours. Takes the vault lock, so call it before holding any.
+pub(crate) fn note_decrypt(
+    app: &AppHandle,
+    vault: &State<'_, VaultMutex>,
+    key_id: &str,
+) -> InFlightDecrypt {
+    let decrypt = InFlightDecrypt::begin(app);
+    let (settings, locale) = {
+        let vault = vault.0.lock().unwrap();
+        (vault.notifications.clone(), vault.locale())
+    };
+    let now = Local::now();
+    if settings.is_off_hours(now.hour()) {
+        notify(
+            app,
+            &settings,
+            locale,
+            SecurityEvent::OffHoursDecrypt,
+            &tr(
+                locale,
+                "off-hours-decrypt-body",
+                &[
+                    ("key", key_id.into()),
+                    ("time", now.format("%H:%M").to_string().into()),
+                ],
+            ),
+        );
+    }
+    decrypt
+}
+
+#[tauri::command]
+pub fn get_notification_settings(vault: State<'_, VaultMutex>) -> Result<NotificationSettings> {
+    Ok(vault.0.lock().unwrap().notifications.clone())
+}
+
+/// Change which events notify and the normal signing hours. Requires an
+/// unlocked vault.
+#[tauri::command]
+pub fn set_notification_settings(
+    app: AppHandle,
+    settings: NotificationSettings,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<NotificationSettings> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    settings.validate().map_err(VaultError::InvalidMetadata)?;
+    let mut vault = vault.0.lock().unwrap();
+    let mut next = vault.clone();
+    next.notifications = settings.clone();
+    persist_vault(&app, &next)?;
+    *vault = next;
+    Ok(settings)
+}
+
+/// Watch drives come and go. Checks provisioned drives against their seal
+/// once they are mounted, notifies when an unplugged drive still holds a
+/// backup of this vault that has not been verified, and locks the vault
+/// when a drive's trust settings say to.
+pub fn spawn_drive_watch(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut present: HashSet<String> = HashSet::new();
+        let mut checked: HashSet<String> = HashSet::new();
+        loop {
+            std::thread::sleep(Duration::from_secs(DRIVE_WATCH_INTERVAL_SECS));
+            let backend = app.state::<Drives>();
+            let Ok(drives) = backend.0.list_drives() else {
+                continue;
+            };
+            let mounted: Vec<&DriveInfo> = drives
+                .iter()
+                .filter(|d| {
+                    d.mount_point.is_some()
+                        && matches!(d.status, DriveStatus::Ready | DriveStatus::ReadOnly)
+                })
+                .collect();
+            checked.retain(|id| mounted.iter().any(|d| &d.id == id));
+            for drive in mounted {
+                if checked.insert(drive.id.clone()) {
+                    if let Err(e) = check_attached(&app, backend.0.as_ref(), drive) {
+                        tracing::warn!(drive = %drive.id, "could not check the drive seal: {e}");
+                    }
+                }
+            }
+            let now: HashSet<String> = drives.into_iter().map(|d| d.id).collect();
+            let trigger = {
+                let vault = app.state::<VaultMutex>();
+                let vault = vault.0.lock().unwrap();
+                for drive_id in present.difference(&now) {
+                    if vault.has_unverified_backup_on(drive_id) {
+                        notify(
+                            &app,
+                            &vault.notifications,
+                            vault.locale(),
+                            SecurityEvent::UnverifiedDriveDetached,
+                            &tr(
+                                vault.locale(),
+                                "unverified-drive-detached-body",
+                                &[("drive", drive_id.as_str().into())],
+                            ),
+                        );
+                    }
+                }
+                drive_lock_trigger(&vault.drive_trust, &now, &present, |id| {
+                    vault.has_unverified_backup_on(id)
+                })
+            };
+            present = now;
+            let Some(trigger) = trigger else {
+                continue;
+            };
+            if app.state::<SessionKey>().0.lock().unwrap().is_none() {
+                continue;
+            }
+            if let Err(e) = lock_vault(
+                app.clone(),
+                app.state(),
+                app.state(),
+                app.state(),
+                app.state(),
+                app.state(),
+                app.state(),
+            ) {
+                tracing::warn!("vault locked by a drive trust rule: {e}");
+            }
+            tracing::info!(target: "audit", trigger = ?trigger, "vault locked: {}", trigger.describe());
+            let _ = app.emit(DRIVE_AUTO_LOCK_EVENT, &trigger);
+        }
+    });
+}
@@ -0,0 +1,617 @@
+use crate::commands::items::{save_items, ItemStore};
+use crate::commands::keys::{atomic_write, keys_file_path, local_data_dir, MasterSeed, SessionKey};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::notifications::note_decrypt;
+use crate::commands::policy::{enforce_item_export, enforce_policy};
+use crate::commands::vault::VaultMutex;
+use crate::crypto::{pairing, passkey};
+use crate::error::{Result, VaultError};
+use crate::models::item::{ItemPayload, VaultItem};
+use crate::models::pairing::{
+    normalize_origin, origin_matches_rp_id, BrowserMessage, BrowserReply, FillAction, FillRequest,
+    FillResponse, PairedExtension, PairedExtensionPublic, PairingOverview, PairingPrompt,
+    PairingStore, SiteApproval, SiteRequest, BROWSER_SOCKET_FILE, MAX_EXTENSION_NAME_CHARS,
+};
+use crate::models::policy::PolicyGate;
+use crate::models::rate_limit::SensitiveOp;
+use chrono::{DateTime, Utc};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+use zeroize::Zeroizing;
+
+/// Paired extensions and site approvals. Pairing keys inside are wrapped
+/// under the master seed; the rest is plaintext metadata that travels with
+/// backups.
+pub const PAIRING_FILE: &str = "browser_pairing.json";
+/// Emitted with a [`PairingPrompt`] when an extension asks to pair.
+pub const PAIRING_REQUESTED_EVENT: &str = "browser_pairing_requested";
+/// Emitted with a [`SiteRequest`] when a paired extension asks to fill an
+/// origin that has no approved item.
+pub const SITE_APPROVAL_EVENT: &str = "browser_site_approval_requested";
+/// Unconfirmed pairings kept at once; older ones are dropped.
+const MAX_PENDING_PAIRINGS: usize = 4;
+
+struct PendingPairing {
+    id: String,
+    name: String,
+    key: Zeroizing<[u8; 32]>,
+}
+
+struct Listener {
+    socket_path: PathBuf,
+    shutdown: Arc<AtomicBool>,
+}
+
+/// The browser socket, if listening, and pairings waiting for the user to
+/// compare codes. Neither outlives the unlocked session.
+#[derive(Default)]
+pub struct BrowserPairing {
+    listener: Mutex<Option<Listener>>,
+    pending: Mutex<Vec<PendingPairing>>,
+}
+
+pub(crate) fn load_pairing(app: &AppHandle) -> Result<PairingStore> {
+    let path = keys_file_path(app, PAIRING_FILE)?;
+    if !path.exists() {
+        return Ok(PairingStore::default());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+pub(crate) fn save_pairing(app: &AppHandle, store: &PairingStore) -> Result<()> {
+    let path = keys_file_path(app, PAIRING_FILE)?;
+    atomic_write(&path, &serde_json::to_vec_pretty(store)?)
+}
+
+fn to_public(ext: &PairedExtension) -> PairedExtensionPublic {
+    PairedExtensionPublic {
+        id: ext.id.clone(),
+        name: ext.name.clone(),
+        paired_at: ext.paired_at,
+        last_seen_at: ext.last_seen_at,
+    }
+}
+
+/// Whether `item` can serve `action` on `origin`.
+fn item_serves(item: &VaultItem, action: &FillAction, origin: &str) -> bool {
+    match (&item.payload, action) {
+        (ItemPayload::ApiToken(_), FillAction::ApiToken) => true,
+        (ItemPayload::Passkey(pk), FillAction::PasskeyAssertion { .. }) => {
+            origin_matches_rp_id(origin, &pk.rp_id)
+        }
+        _ => false,
+    }
+}
+
+fn handle_pair_request(app: &AppHandle, extension_public_hex: &str, name: &str) -> BrowserReply {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_EXTENSION_NAME_CHARS {
+        return BrowserReply::Error {
+            message: format!("extension name must be 1 to {MAX_EXTENSION_NAME_CHARS} characters"),
+        };
+    }
+    let offer = match pairing::accept_pairing(extension_public_hex) {
+        Ok(offer) => offer,
+        Err(e) => {
+            return BrowserReply::Error {
+                message: e.to_string(),
+            }
+        }
+    };
+    let pending_id = uuid::Uuid::new_v4().to_string();
+    {
+        let state = app.state::<BrowserPairing>();
+        let mut pending = state.pending.lock().unwrap();
+        if pending.len() >= MAX_PENDING_PAIRINGS {
+            pending.remove(0);
+        }
+        pending.push(PendingPairing {
+            id: pending_id.clone(),
+            name: name.to_string(),
+            key: offer.key,
+        });
+    }
+    tracing::info!(target: "audit", pending_id = %pending_id, name, "browser pairing requested");
+    let _ = app.emit(
+        PAIRING_REQUESTED_EVENT,
+        PairingPrompt {
+            pending_id: pending_id.clone(),
+            name: name.to_string(),
+            code: offer.code,
+        },
+    );
+    BrowserReply::PairPending {
+        pending_id,
+        app_public_hex: offer.app_public_hex,
+    }
+}
+
+/// Serve one approved fill from the unlocked item store. A passkey
+/// assertion bumps the sign counter, so the store is saved before replying.
+/// An API token leaves the vault in plaintext, so it is refused under the
+/// policy's `deny_plaintext_export` or the item's `no_plaintext_export`.
+fn serve_fill(
+    app: &AppHandle,
+    item_id: &str,
+    request: &FillRequest,
+    now: DateTime<Utc>,
+) -> std::result::Result<FillResponse, String> {
+    if matches!(request.action, FillAction::ApiToken) {
+        enforce_policy(&app.state::<VaultMutex>(), PolicyGate::PlaintextExport)
+            .and_then(|()| enforce_item_export(&app.state::<ItemStore>(), &[item_id.to_string()]))
+            .map_err(|e| e.to_string())?;
+    }
+    let session_key = app
+        .state::<SessionKey>()
+        .0
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("vault is locked")?;
+    let items_file = app
+        .state::<VaultMutex>()
+        .0
+        .lock()
+        .unwrap()
+        .items_file
+        .clone();
+    let items = app.state::<ItemStore>();
+    let mut store = items.0.lock().unwrap();
+    let item = store
+        .iter_mut()
+        .find(|i| i.id == item_id && i.trashed_at.is_none())
+        .ok_or("approved item no longer exists")?;
+    if item.is_expired(now) {
+        return Err("approved item has expired".to_string());
+    }
+    item.usage.touch(now);
+    let seq = request.seq;
+    let response = match (&mut item.payload, &request.action) {
+        (ItemPayload::ApiToken(t), FillAction::ApiToken) => FillResponse::Token {
+            seq,
+            value: t.token.clone(),
+        },
+        (
+            ItemPayload::Passkey(pk),
+            FillAction::PasskeyAssertion {
+                client_data_hash_hex,
+            },
+        ) => {
+            let hash: [u8; 32] = hex::decode(client_data_hash_hex)
+                .ok()
+                .and_then(|b| b.try_into().ok())
+                .ok_or("client data hash must be 32 bytes of hex")?;
+            let assertion = passkey::sign_assertion(pk, &hash).map_err(|e| e.to_string())?;
+            FillResponse::Assertion { seq, assertion }
+        }
+        _ => return Err("approved item does not match the request".to_string()),
+    };
+    save_items(app, &items_file, &session_key, &store).map_err(|e| e.to_string())?;
+    Ok(response)
+}
+
+/// Decide a fill request from a paired extension: check the origin, the
+/// rate limit and the per-site approval, then serve it.
+fn handle_fill(
+    app: &AppHandle,
+    store: &mut PairingStore,
+    extension_id: &str,
+    request: &FillRequest,
+) -> FillResponse {
+    let seq = request.seq;
+    let denied = |reason: String| FillResponse::Denied { seq, reason };
+    let origin = match normalize_origin(&request.origin) {
+        Ok(o) => o,
+        Err(e) => return denied(e),
+    };
+    let vault = app.state::<VaultMutex>();
+    if let Err(e) = enforce(&vault, &app.state::<RateLimiter>(), SensitiveOp::Decrypt) {
+        return denied(e.to_string());
+    }
+    let approved: Option<String> = {
+        let items = app.state::<ItemStore>();
+        let items = items.0.lock().unwrap();
+        store
+            .sites
+            .iter()
+            .filter(|s| s.origin == origin)
+            .find(|s| {
+                items
+                    .iter()
+                    .any(|i| i.id == s.item_id && item_serves(i, &request.action, &origin))
+            })
+            .map(|s| s.item_id.clone())
+    };
+    let Some(item_id) = approved else {
+        let already = store
+            .pending_sites
+            .iter()
+            .any(|p| p.origin == origin && p.extension_id == extension_id);
+        if !already {
+            let pending = SiteRequest {
+                origin: origin.clone(),
+                extension_id: extension_id.to_string(),
+                requested_at: Utc::now(),
+            };
+            let _ = app.emit(SITE_APPROVAL_EVENT, &pending);
+            store.pending_sites.push(pending);
+        }
+        tracing::info!(target: "audit", extension_id, origin = %origin, "browser fill awaiting site approval");
+        return FillResponse::ApprovalRequired { seq, origin };
+    };
+    let decrypt = note_decrypt(app, &vault, &item_id);
+    match serve_fill(app, &item_id, request, Utc::now()).and_then(|response| {
+        decrypt
+            .finish(app)
+            .map(|()| response)
+            .map_err(|e| e.to_string())
+    }) {
+        Ok(response) => {
+            tracing::info!(target: "audit", extension_id, origin = %origin, item_id = %item_id, "browser fill served");
+            response
+        }
+        Err(e) => {
+            tracing::info!(target: "audit", extension_id, origin = %origin, item_id = %item_id, error = %e, "browser fill failed");
+            denied(e)
+        }
+    }
+}
+
+fn handle_sealed(
+    app: &AppHandle,
+    extension_id: &str,
+    nonce_hex: &str,
+    ciphertext_hex: &str,
+) -> Result<BrowserReply> {
+    let seed = app
+        .state::<MasterSeed>()
+        .0
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or(VaultError::NotInitialized)?;
+    let mut store = load_pairing(app)?;
+    let ext = store
+        .extensions
+        .iter()
+        .find(|e| e.id == extension_id)
+        .ok_or_else(|| VaultError::KeyNotFound(extension_id.to_string()))?;
+    let key = pairing::unwrap_pairing_key(&seed, &ext.wrapped_key)?;
+    let request: FillRequest = pairing::open(&key, nonce_hex, ciphertext_hex)?;
+    if request.seq <= ext.last_seq {
+        tracing::info!(target: "audit", extension_id, seq = request.seq, "replayed browser request rejected");
+        return Err(VaultError::InvalidMetadata(
+            "request sequence number was already used".to_string(),
+        ));
+    }
+    if let Some(ext) = store.extensions.iter_mut().find(|e| e.id == extension_id) {
+        ext.last_seq = request.seq;
+        ext.last_seen_at = Some(Utc::now());
+    }
+    let response = handle_fill(app, &mut store, extension_id, &request);
+    save_pairing(app, &store)?;
+    let (nonce_hex, ciphertext_hex) = pairing::seal(&key, &response)?;
+    Ok(BrowserReply::Sealed {
+        nonce_hex,
+        ciphertext_hex,
+    })
+}
+
+fn handle_frame(app: &AppHandle, frame: &[u8]) -> BrowserReply {
+    let reply = match serde_json::from_slice::<BrowserMessage>(frame) {
+        Err(_) => Err(VaultError::InvalidMetadata("malformed message".to_string())),
+        Ok(BrowserMessage::PairRequest {
+            extension_public_hex,
+            name,
+        }) => Ok(handle_pair_request(app, &extension_public_hex, &name)),
+        Ok(BrowserMessage::Sealed {
+            extension_id,
+            nonce_hex,
+            ciphertext_hex,
+        }) => handle_sealed(app, &extension_id, &nonce_hex, &ciphertext_hex),
+    };
+    reply.unwrap_or_else(|e| BrowserReply::Error {
+        message: e.to_string(),
+    })
+}
+
+/// Serve framed messages on the browser socket until `shutdown` is set.
+/// Each connection is one native-messaging host and may send many frames.
+#[cfg(unix)]
+fn spawn_listener(app: AppHandle, path: &std::path::Path, shutdown: Arc<AtomicBool>) -> Result<()> {
+    use crate::models::pairing::{read_frame, write_frame};
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+    use std::os::unix::net::UnixListener;
+
+    if std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+        let _ = std::fs::remove_file(path);
+    }
+    let listener = UnixListener::bind(path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| VaultError::Storage(e.to_string()))?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            if shutdown.load(Ordering::SeqCst) {
+                break;
+            }
+            let Ok(mut stream) = stream else { continue };
+            let _ = stream.set_read_timeout(Some(std::time::Duration::from_secs(60)));
+            while let Ok(Some(frame)) = read_frame(&mut stream) {
+                if shutdown.load(Ordering::SeqCst) {
+                    break;
+                }
+                let reply = handle_frame(&app, &frame);
+                let Ok(json) = serde_json::to_vec(&reply) else {
+                    break;
+                };
+                if write_frame(&mut stream, &json).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn spawn_listener(
+    _app: AppHandle,
+    _path: &std::path::Path,
+    _shutdown: Arc<AtomicBool>,
+) -> Result<()> {
+    Err(VaultError::Storage(
+        "browser integration needs Unix-domain sockets".to_string(),
+    ))
+}
+
+/// Stop listening, drop unconfirmed pairings and remove the socket.
+pub(crate) fn stop_pairing(state: &BrowserPairing) {
+    state.pending.lock().unwrap().clear();
+    let Some(listener) = state.listener.lock().unwrap().take() else {
+        return;
+    };
+    listener.shutdown.store(true, Ordering::SeqCst);
+    #[cfg(unix)]
+    let _ = std::os::unix::net::UnixStream::connect(&listener.socket_path);
+    let _ = std::fs::remove_file(&listener.socket_path);
+    tracing::info!(target: "audit", "browser integration stopped");
+}
+
+/// Start listening for the native-messaging host on `browser.sock` in the
+/// data directory. Requires an unlocked vault; locking stops it.
+#[tauri::command]
+pub fn pairing_enable(
+    app: AppHandle,
+    state: State<'_, BrowserPairing>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<()> {
+    if master_seed.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    stop_pairing(&state);
+    let socket_path = local_data_dir(&app)?.join(BROWSER_SOCKET_FILE);
+    let shutdown = Arc::new(AtomicBool::new(false));
+    spawn_listener(app.clone(), &socket_path, shutdown.clone())?;
+    *state.listener.lock().unwrap() = Some(Listener {
+        socket_path,
+        shutdown,
+    });
+    tracing::info!(target: "audit", "browser integration started");
+    Ok(())
+}
+
+#[tauri::command]
+pub fn pairing_disable(state: State<'_, BrowserPairing>) -> Result<()> {
+    stop_pairing(&state);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn pairing_list(app: AppHandle, state: State<'_, BrowserPairing>) -> Result<PairingOverview> {
+    let store = load_pairing(&app)?;
+    Ok(PairingOverview {
+        listening: state.listener.lock().unwrap().is_some(),
+        extensions: store.extensions.iter().map(to_public).collect(),
+        sites: store.sites,
+        pending_sites: store.pending_sites,
+    })
+}
+
+/// Confirm a pairing after the user has checked that the app and the
+/// extension show the same code.
+#[tauri::command]
+pub fn pairing_confirm(
+    app: AppHandle,
+    pending_id: String,
+    state: State<'_, BrowserPairing>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<PairedExtensionPublic> {
+    let pending = {
+        let mut pending = state.pending.lock().unwrap();
+        let idx = pending
+            .iter()
+            .position(|p| p.id == pending_id)
+            .ok_or_else(|| VaultError::KeyNotFound(pending_id.clone()))?;
+        pending.remove(idx)
+    };
+    let wrapped_key = {
+        let guard = master_seed.0.lock().unwrap();
+        let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
+        pairing::wrap_pairing_key(seed, &pending.key)?
+    };
+    let ext = PairedExtension {
+        id: pending.id.clone(),
+        name: pending.name.clone(),
+        paired_at: Utc::now(),
+        last_seen_at: None,
+        last_seq: 0,
+        wrapped_key,
+    };
+    let public = to_public(&ext);
+    let mut store = load_pairing(&app)?;
+    store.extensions.push(ext);
+    save_pairing(&app, &store)?;
+    tracing::info!(target: "audit", extension_id = %public.id, name = %public.name, "browser extension paired");
+    Ok(public)
+}
+
+/// Forget a paired extension, or drop a pairing that was never confirmed.
+#[tauri::command]
+pub fn pairing_revoke(
+    app: AppHandle,
+    extension_id: String,
+    state: State<'_, BrowserPairing>,
+) -> Result<()> {
+    state
+        .pending
+        .lock()
+        .unwrap()
+        .retain(|p| p.id != extension_id);
+    let mut store = load_pairing(&app)?;
+    let before = store.extensions.len();
+    store.extensions.retain(|e| e.id != extension_id);
+    store
+        .pending_sites
+        .retain(|p| p.extension_id != extension_id);
+    if store.extensions.len() != before {
+        save_pairing(&app, &store)?;
+        tracing::info!(target: "audit", extension_id = %extension_id, "browser extension revoked");
+    }
+    Ok(())
+}
+
+/// Allow `origin` to be filled from `item_id`. Passkeys are only accepted
+/// for origins within their RP ID. An earlier approval for the same origin
+/// and item kind is replaced.
+#[tauri::command]
+pub fn pairing_approve_site(
+    app: AppHandle,
+    origin: String,
+    item_id: String,
+    items: State<'_, ItemStore>,
+) -> Result<SiteApproval> {
+    let origin = normalize_origin(&origin).map_err(VaultError::InvalidMetadata)?;
+    let same_kind: Vec<String> = {
+        let store = items.0.lock().unwrap();
+        let item = store
+            .iter()
+            .find(|i| i.id == item_id && i.trashed_at.is_none())
+            .ok_or_else(|| VaultError::KeyNotFound(item_id.clone()))?;
+        match &item.payload {
+            ItemPayload::ApiToken(_) => {}
+            ItemPayload::Passkey(pk) if origin_matches_rp_id(&origin, &pk.rp_id) => {}
+            ItemPayload::Passkey(pk) => {
+                return Err(VaultError::InvalidMetadata(format!(
+                    "passkey for {} cannot be used on {origin}",
+                    pk.rp_id
+                )))
+            }
+            _ => {
+                return Err(VaultError::InvalidMetadata(
+                    "only API tokens and passkeys can be filled in the browser".to_string(),
+                ))
+            }
+        }
+        let kind = std::mem::discriminant(&item.payload);
+        store
+            .iter()
+            .filter(|i| std::mem::discriminant(&i.payload) == kind)
+            .map(|i| i.id.clone())
+            .collect()
+    };
+    let approval = SiteApproval {
+        origin: origin.clone(),
+        item_id: item_id.clone(),
+        approved_at: Utc::now(),
+    };
+    let mut store = load_pairing(&app)?;
+    store
+        .sites
+        .retain(|s| !(s.origin == origin && same_kind.contains(&s.item_id)));
+    store.sites.push(approval.clone());
+    store.pending_sites.retain(|p| p.origin != origin);
+    save_pairing(&app, &store)?;
+    tracing::info!(target: "audit", origin = %origin, item_id = %item_id, "browser site approved");
+    Ok(approval)
+}
+
+#[tauri::command]
+pub fn pairing_remove_site(app: AppHandle, origin: String, item_id: String) -> Result<()> {
+    let origin = normalize_origin(&origin).map_err(VaultError::InvalidMetadata)?;
+    let mut store = load_pairing(&app)?;
+    store
+        .sites
+        .retain(|s| !(s.origin == origin && s.item_id == item_id));
+    store.pending_sites.retain(|p| p.origin != origin);
+    save_pairing(&app, &store)?;
+    tracing::info!(target: "audit", origin = %origin, item_id = %item_id, "browser site approval removed");
+    Ok(())
+}
+
+/// Whether the process was started by a browser as a native-messaging host.
+/// Chrome passes the calling extension's origin as the first argument;
+/// Firefox passes the manifest path and then the extension id.
+pub fn is_native_host_invocation(args: &[String]) -> bool {
+    match args.get(1) {
+        Some(a) if a.starts_with("chrome-extension://") => true,
+        Some(a) => a.ends_with(".json") && args.len() == 3,
+        None => false,
+    }
+}
+
+/// The data directory Tauri uses for this app, resolved without a running
+/// app so the native-messaging host can find the socket.
+fn host_data_dir() -> Option<PathBuf> {
+    const IDENTIFIER: &str = "com.zapblockchain.quantumvault";
+    let base = if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|h| PathBuf::from(h).join("Library/Application Support"))
+    } else if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))
+    };
+    base.map(|b| b.join(IDENTIFIER))
+}
+
+/// Run as the browser's native-messaging host: relay every frame from
+/// stdin to the running app's browser socket and its reply to stdout. The
+/// host never sees plaintext fills; they are sealed under the pairing key.
+pub fn run_native_host() -> std::io::Result<()> {
+    use crate::models::pairing::{read_frame, write_frame};
+    let mut stdin = std::io::stdin().lock();
+    let mut stdout = std::io::stdout().lock();
+    let unavailable = || {
+        serde_json::to_vec(&BrowserReply::Error {
+            message: "the vault is locked or browser integration is off".to_string(),
+        })
+        .unwrap_or_default()
+    };
+    #[cfg(unix)]
+    let mut conn = host_data_dir()
+        .and_then(|d| std::os::unix::net::UnixStream::connect(d.join(BROWSER_SOCKET_FILE)).ok());
+    #[cfg(not(unix))]
+    let mut conn: Option<std::fs::File> = {
+        let _ = host_data_dir();
+        None
+    };
+    while let Some(frame) = read_frame(&mut stdin)? {
+        let reply = match conn.as_mut() {
+            Some(c) => match write_frame(c, &frame).and_then(|_| read_frame(c)) {
+                Ok(Some(reply)) => reply,
+                _ => {
+                    conn = None;
+                    unavailable()
+                }
+            },
+            None => unavailable(),
+        };
+        write_frame(&mut stdout, &reply)?;
+    }
+    Ok(())
+}
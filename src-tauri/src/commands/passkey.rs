@@ -0,0 +1,237 @@
+use crate::commands::consent::require_consent;
+use crate::commands::items::{push_item, save_items, ItemStore};
+use crate::commands::keys::SessionKey;
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::notifications::note_decrypt;
+use crate::commands::policy::{enforce_item_export, enforce_policy, export_watermark};
+use crate::commands::vault::{verify_password, VaultMutex};
+use crate::crypto::passkey::{self, PasskeyError};
+use crate::error::{Result, VaultError};
+use crate::models::consent::ConsentOperation;
+use crate::models::item::{ItemPayload, PasskeyAlgorithm, VaultItem, VaultItemPublic};
+use crate::models::passkey::{PasskeyAssertion, PasskeyExport, PASSKEY_EXPORT_FORMAT};
+use crate::models::policy::PolicyGate;
+use crate::models::rate_limit::SensitiveOp;
+use chrono::Utc;
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
+
+/// Whether the store already holds a live passkey for this relying party and
+/// credential id.
+fn has_credential(store: &[VaultItem], rp_id: &str, credential_id: &str) -> bool {
+    store.iter().any(|i| {
+        i.trashed_at.is_none()
+            && matches!(&i.payload, ItemPayload::Passkey(pk)
+                if pk.rp_id == rp_id && pk.credential_id == credential_id)
+    })
+}
+
+/// Generate a discoverable passkey for `rp_id` with the vault's software
+/// authenticator. The returned public view carries the credential id and
+/// COSE public key the relying party needs for registration.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn passkey_generate(
+    app: AppHandle,
+    rp_id: String,
+    rp_name: Option<String>,
+    user_handle: String,
+    user_name: String,
+    user_display_name: Option<String>,
+    algorithm: PasskeyAlgorithm,
+    label: Option<String>,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<VaultItemPublic> {
+    enforce_policy(&vault, PolicyGate::KeyGeneration)?;
+    let pk = passkey::generate(
+        algorithm,
+        &rp_id,
+        rp_name,
+        &user_handle,
+        &user_name,
+        user_display_name,
+    )?;
+    let item = VaultItem::new(label.or(Some(rp_id)), ItemPayload::Passkey(pk));
+    let public = item.to_public();
+    push_item(&app, &vault, &items, &session, item)?;
+    Ok(public)
+}
+
+/// Import the credentials of a passkey export document (see
+/// `docs/PASSKEYS.md`). All-or-nothing: one invalid or already stored
+/// credential rejects the whole document.
+#[tauri::command]
+pub fn passkey_import(
+    app: AppHandle,
+    export_json: String,
+    provider: Option<String>,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<VaultItemPublic>> {
+    let export_json = Zeroizing::new(export_json);
+    let export: PasskeyExport = serde_json::from_str(&export_json)?;
+    if export.format != PASSKEY_EXPORT_FORMAT || export.version != 1 {
+        return Err(VaultError::InvalidMetadata(format!(
+            "unsupported passkey export {} v{}",
+            export.format, export.version
+        )));
+    }
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let items_file = vault.0.lock().unwrap().items_file.clone();
+
+    let mut store = items.0.lock().unwrap();
+    let mut imported: Vec<VaultItem> = Vec::with_capacity(export.credentials.len());
+    for cred in &export.credentials {
+        let pk = passkey::from_export(cred, provider.clone())?;
+        let duplicate = has_credential(&store, &pk.rp_id, &pk.credential_id)
+            || imported.iter().any(|i| {
+                matches!(&i.payload, ItemPayload::Passkey(p)
+                    if p.rp_id == pk.rp_id && p.credential_id == pk.credential_id)
+            });
+        if duplicate {
+            return Err(VaultError::KeyAlreadyExists(format!(
+                "passkey {} for {}",
+                pk.credential_id, pk.rp_id
+            )));
+        }
+        imported.push(VaultItem::new(
+            Some(pk.rp_id.clone()),
+            ItemPayload::Passkey(pk),
+        ));
+    }
+    let public: Vec<VaultItemPublic> = imported.iter().map(VaultItem::to_public).collect();
+    store.extend(imported);
+    save_items(&app, &items_file, &session_key, &store)?;
+    tracing::info!(
+        target: "audit",
+        count = public.len(),
+        provider = provider.as_deref().unwrap_or(""),
+        "passkeys imported"
+    );
+    Ok(public)
+}
+
+/// Stored passkeys, optionally only those for `rp_id`.
+#[tauri::command]
+pub fn passkey_list(
+    rp_id: Option<String>,
+    items: State<'_, ItemStore>,
+) -> Result<Vec<VaultItemPublic>> {
+    let store = items.0.lock().unwrap();
+    Ok(store
+        .iter()
+        .filter(|i| {
+            i.trashed_at.is_none()
+                && matches!(&i.payload, ItemPayload::Passkey(pk)
+                    if rp_id.as_deref().is_none_or(|r| r == pk.rp_id))
+        })
+        .map(|i| i.to_public())
+        .collect())
+}
+
+/// Sign a WebAuthn assertion with a stored passkey. `client_data_hash_hex`
+/// is the SHA-256 of the browser's `clientDataJSON`. The bumped sign counter
+/// is persisted before the assertion is returned, so a crash can never make
+/// the counter go back.
+#[tauri::command]
+pub fn passkey_sign_assertion(
+    app: AppHandle,
+    item_id: String,
+    client_data_hash_hex: String,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<PasskeyAssertion> {
+    enforce(&vault, &limiter, SensitiveOp::Decrypt)?;
+    let client_data_hash: [u8; 32] = hex::decode(&client_data_hash_hex)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| {
+            PasskeyError::InvalidField("client_data_hash", "must be 32 bytes of hex".to_string())
+        })?;
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let items_file = vault.0.lock().unwrap().items_file.clone();
+    let decrypt = note_decrypt(&app, &vault, &item_id);
+
+    let mut store = items.0.lock().unwrap();
+    let assertion = match store
+        .iter_mut()
+        .find(|i| i.id == item_id && i.trashed_at.is_none())
+    {
+        Some(VaultItem {
+            payload: ItemPayload::Passkey(pk),
+            usage,
+            ..
+        }) => {
+            usage.touch(Utc::now());
+            passkey::sign_assertion(pk, &client_data_hash)?
+        }
+        _ => return Err(VaultError::KeyNotFound(item_id)),
+    };
+    decrypt.finish(&app)?;
+    save_items(&app, &items_file, &session_key, &store)?;
+    Ok(assertion)
+}
+
+/// Export passkeys, private keys included, as a passkey export document for
+/// another provider. Re-verifies the vault password like every other
+/// plaintext export, and requires a consent token for exactly `item_ids`.
+#[tauri::command]
+pub fn passkey_export(
+    app: AppHandle,
+    item_ids: Vec<String>,
+    password: String,
+    consent_token: String,
+    state: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<String> {
+    enforce(&state, &limiter, SensitiveOp::Export)?;
+    enforce_policy(&state, PolicyGate::PlaintextExport)?;
+    enforce_item_export(&items, &item_ids)?;
+    verify_password(&app, &state, &password)?;
+    require_consent(
+        &app,
+        &consent_token,
+        &ConsentOperation::PasskeyExport {
+            item_ids: item_ids.clone(),
+        },
+    )?;
+    let mark = export_watermark(&app)?;
+    let store = items.0.lock().unwrap();
+    let mut credentials = Vec::with_capacity(item_ids.len());
+    for id in &item_ids {
+        match store.iter().find(|i| &i.id == id && i.trashed_at.is_none()) {
+            Some(VaultItem {
+                payload: ItemPayload::Passkey(pk),
+                ..
+            }) => credentials.push(passkey::to_export(pk)),
+            _ => return Err(VaultError::KeyNotFound(id.clone())),
+        }
+    }
+    let export = PasskeyExport {
+        format: PASSKEY_EXPORT_FORMAT.to_string(),
+        version: 1,
+        exported_at: mark.exported_at,
+        credentials,
+        watermark: Some(mark.clone()),
+    };
+    tracing::info!(
+        target: "audit",
+        count = item_ids.len(),
+        profile = %mark.profile,
+        instance_id = %mark.instance_id,
+        "passkeys exported"
+    );
+    Ok(serde_json::to_string_pretty(&export)?)
+}
@@ -0,0 +1,68 @@
+use crate::commands::keys::SessionKey;
+use crate::commands::vault::{load_vault_if_needed, persist_vault, verify_password, VaultMutex};
+use crate::crypto::password_strength::{self, PasswordFeedback, MAX_SCORE};
+use crate::error::{Result, VaultError};
+use crate::models::vault::VaultState;
+use tauri::{AppHandle, State};
+
+fn password_feedback(vault: &VaultState, password: &str) -> PasswordFeedback {
+    password_strength::check(password, &[], vault.min_password_score)
+}
+
+/// Refuse a new vault or export password that scores below the vault's
+/// strength floor. Refusals are written to the `audit` tracing target,
+/// without the password.
+pub(crate) fn enforce_password_strength(vault: &VaultState, password: &str) -> Result<()> {
+    let feedback = password_feedback(vault, password);
+    if feedback.acceptable {
+        return Ok(());
+    }
+    tracing::warn!(
+        target: "audit",
+        score = feedback.score,
+        min_score = feedback.min_score,
+        "weak password refused"
+    );
+    Err(VaultError::WeakPassword(feedback.summary()))
+}
+
+/// Score a candidate password against the vault's strength floor, for
+/// feedback while it is typed. Works before a vault exists and while locked.
+#[tauri::command]
+pub fn check_password_strength(
+    app: AppHandle,
+    password: String,
+    vault: State<'_, VaultMutex>,
+) -> Result<PasswordFeedback> {
+    let mut vault = vault.0.lock().unwrap();
+    load_vault_if_needed(&app, &mut vault);
+    Ok(password_feedback(&vault, &password))
+}
+
+/// Change the score new passwords must reach. Requires an unlocked vault and
+/// the password, so an unattended session cannot lower it.
+#[tauri::command]
+pub fn set_min_password_score(
+    app: AppHandle,
+    password: String,
+    min_score: u8,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<u8> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    if min_score > MAX_SCORE {
+        return Err(VaultError::InvalidMetadata(format!(
+            "min_score must be at most {MAX_SCORE}"
+        )));
+    }
+    verify_password(&app, &vault, &password)?;
+    let mut vault = vault.0.lock().unwrap();
+    let mut next = vault.clone();
+    next.min_password_score = min_score;
+    persist_vault(&app, &next)?;
+    *vault = next;
+    tracing::warn!(target: "audit", min_score, "password strength floor changed");
+    Ok(min_score)
+}
@@ -0,0 +1,541 @@
+use crate::commands::consent::require_consent;
+use crate::commands::items::{push_item, ItemStore};
+use crate::commands::keys::{atomic_write, data_dir, require_unlocked, KeyStore, SessionKey};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::notifications::note_decrypt;
+use crate::commands::policy::{enforce_item_export, enforce_policy, export_watermark};
+use crate::commands::vault::{load_vault_if_needed, persist_vault, verify_password, VaultMutex};
+use crate::error::{Result, VaultError};
+use crate::models::consent::ConsentOperation;
+use crate::models::item::{ItemPayload, PluginItem, VaultItem, VaultItemPublic};
+use crate::models::plugin::{
+    InstalledPlugin, PluginCapability, PluginItemContents, PluginItemMetadata, PluginKey,
+    PluginPackage, PluginRequest, PluginSettings, TrustedPublisher, MAX_PLUGINS,
+};
+use crate::models::policy::PolicyGate;
+use crate::models::rate_limit::SensitiveOp;
+use crate::plugin::{self, runtime, PluginError};
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+/// Directory holding installed plugin modules, next to `vault.json`.
+fn plugins_dir(app: &AppHandle) -> Result<PathBuf> {
+    let dir = data_dir(app)?.join("plugins");
+    std::fs::create_dir_all(&dir).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(dir)
+}
+
+/// An installed plugin and its module, checked against the digest recorded
+/// at install. Plugins whose publisher is no longer trusted do not run.
+fn load_plugin(
+    app: &AppHandle,
+    vault: &State<'_, VaultMutex>,
+    plugin_id: &str,
+) -> Result<(InstalledPlugin, Vec<u8>)> {
+    let installed = {
+        let v = vault.0.lock().unwrap();
+        let installed = v
+            .plugins
+            .find(plugin_id)
+            .cloned()
+            .ok_or_else(|| VaultError::KeyNotFound(plugin_id.to_string()))?;
+        if v.plugins.trusts(&installed.publisher_fingerprint).is_none() {
+            return Err(PluginError::UntrustedPublisher(installed.manifest.publisher).into());
+        }
+        installed
+    };
+    let wasm = std::fs::read(plugins_dir(app)?.join(installed.wasm_file_name()))
+        .map_err(|e| VaultError::Storage(e.to_string()))?;
+    if plugin::wasm_digest_hex(&wasm) != installed.wasm_sha256_hex {
+        tracing::warn!(target: "audit", plugin_id = %plugin_id, "plugin module tampered with");
+        return Err(PluginError::Tampered.into());
+    }
+    Ok((installed, wasm))
+}
+
+#[tauri::command]
+pub fn list_plugins(app: AppHandle, state: State<'_, VaultMutex>) -> Result<PluginSettings> {
+    let mut vault = state.0.lock().unwrap();
+    load_vault_if_needed(&app, &mut vault);
+    Ok(vault.plugins.clone())
+}
+
+/// Pin a publisher's key fingerprint, obtained from the publisher out of
+/// band, so their plugins can be installed. Requires the vault password.
+#[tauri::command]
+pub fn trust_plugin_publisher(
+    app: AppHandle,
+    password: String,
+    name: String,
+    key_fingerprint: String,
+    state: State<'_, VaultMutex>,
+) -> Result<TrustedPublisher> {
+    verify_password(&app, &state, &password)?;
+    let key_fingerprint = key_fingerprint.trim().to_ascii_lowercase();
+    if key_fingerprint.len() != 32 || !key_fingerprint.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(VaultError::InvalidMetadata(
+            "publisher fingerprint must be 32 hex characters".to_string(),
+        ));
+    }
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(VaultError::InvalidMetadata(
+            "publisher name is required".to_string(),
+        ));
+    }
+    let publisher = TrustedPublisher {
+        name,
+        key_fingerprint,
+        added_at: Utc::now(),
+    };
+    let mut vault = state.0.lock().unwrap();
+    if vault.plugins.trusts(&publisher.key_fingerprint).is_some() {
+        return Err(VaultError::KeyAlreadyExists(publisher.key_fingerprint));
+    }
+    let mut next = vault.clone();
+    next.plugins.trusted_publishers.push(publisher.clone());
+    persist_vault(&app, &next)?;
+    *vault = next;
+    tracing::warn!(
+        target: "audit",
+        publisher = %publisher.name,
+        fingerprint = %publisher.key_fingerprint,
+        "plugin publisher trusted"
+    );
+    Ok(publisher)
+}
+
+/// Stop trusting a publisher. Their installed plugins stay installed but no
+/// longer run. Requires an unlocked vault.
+#[tauri::command]
+pub fn distrust_plugin_publisher(
+    app: AppHandle,
+    key_fingerprint: String,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    require_unlocked(&session)?;
+    let mut vault = state.0.lock().unwrap();
+    if vault.plugins.trusts(&key_fingerprint).is_none() {
+        return Err(VaultError::KeyNotFound(key_fingerprint));
+    }
+    let mut next = vault.clone();
+    next.plugins
+        .trusted_publishers
+        .retain(|p| p.key_fingerprint != key_fingerprint);
+    persist_vault(&app, &next)?;
+    *vault = next;
+    tracing::warn!(target: "audit", fingerprint = %key_fingerprint, "plugin publisher distrusted");
+    Ok(())
+}
+
+/// Install the `.zplugin` package at `path`. The package must be signed by
+/// a trusted publisher, and `granted` must be a subset of the capabilities
+/// it requests. Installing a plugin id that is already installed upgrades
+/// it, if the same publisher signed both. Requires the vault password.
+#[tauri::command(async)]
+pub fn install_plugin(
+    app: AppHandle,
+    password: String,
+    path: String,
+    granted: Vec<PluginCapability>,
+    state: State<'_, VaultMutex>,
+) -> Result<InstalledPlugin> {
+    verify_password(&app, &state, &password)?;
+    let bytes = std::fs::read(Path::new(&path)).map_err(|e| VaultError::Storage(e.to_string()))?;
+    let package: PluginPackage =
+        serde_json::from_slice(&bytes).map_err(|e| PluginError::Malformed(e.to_string()))?;
+    let wasm = plugin::open_package(&package)?;
+    let manifest = package.manifest;
+    let fingerprint = package.signature.key_fingerprint();
+
+    let mut granted = granted;
+    granted.sort();
+    granted.dedup();
+    if let Some(extra) = granted.iter().find(|c| !manifest.capabilities.contains(c)) {
+        return Err(VaultError::InvalidMetadata(format!(
+            "plugin does not request {extra:?}"
+        )));
+    }
+
+    let mut vault = state.0.lock().unwrap();
+    if vault.plugins.trusts(&fingerprint).is_none() {
+        return Err(PluginError::UntrustedPublisher(format!(
+            "{} ({fingerprint})",
+            manifest.publisher
+        ))
+        .into());
+    }
+    match vault.plugins.find(&manifest.id) {
+        Some(existing) if existing.publisher_fingerprint != fingerprint => {
+            return Err(VaultError::KeyAlreadyExists(manifest.id));
+        }
+        None if vault.plugins.installed.len() >= MAX_PLUGINS => {
+            return Err(VaultError::InvalidMetadata(format!(
+                "a vault holds at most {MAX_PLUGINS} plugins"
+            )));
+        }
+        _ => {}
+    }
+    let installed = InstalledPlugin {
+        manifest,
+        granted,
+        wasm_sha256_hex: plugin::wasm_digest_hex(&wasm),
+        publisher_fingerprint: fingerprint,
+        installed_at: Utc::now(),
+    };
+    atomic_write(&plugins_dir(&app)?.join(installed.wasm_file_name()), &wasm)?;
+    let mut next = vault.clone();
+    next.plugins
+        .installed
+        .retain(|p| p.manifest.id != installed.manifest.id);
+    next.plugins.installed.push(installed.clone());
+    persist_vault(&app, &next)?;
+    *vault = next;
+    tracing::warn!(
+        target: "audit",
+        plugin_id = %installed.manifest.id,
+        version = %installed.manifest.version,
+        fingerprint = %installed.publisher_fingerprint,
+        granted = ?installed.granted,
+        "plugin installed"
+    );
+    Ok(installed)
+}
+
+/// Uninstall a plugin. Items of its types stay in the vault and can still
+/// be revealed, but no new ones can be created. Requires an unlocked vault.
+#[tauri::command]
+pub fn remove_plugin(
+    app: AppHandle,
+    plugin_id: String,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    require_unlocked(&session)?;
+    let mut vault = state.0.lock().unwrap();
+    let installed = vault
+        .plugins
+        .find(&plugin_id)
+        .cloned()
+        .ok_or_else(|| VaultError::KeyNotFound(plugin_id.clone()))?;
+    let mut next = vault.clone();
+    next.plugins
+        .installed
+        .retain(|p| p.manifest.id != plugin_id);
+    persist_vault(&app, &next)?;
+    *vault = next;
+    let _ = std::fs::remove_file(plugins_dir(&app)?.join(installed.wasm_file_name()));
+    tracing::warn!(target: "audit", plugin_id = %plugin_id, "plugin removed");
+    Ok(())
+}
+
+/// Store an item of a plugin's item type. The fields are checked against
+/// the type's declaration and then by the plugin itself; fields the type
+/// marks secret are only returned by [`plugin_item_reveal`].
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
+pub fn plugin_item_create(
+    app: AppHandle,
+    label: Option<String>,
+    plugin_id: String,
+    item_type: String,
+    fields: BTreeMap<String, String>,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<VaultItemPublic> {
+    require_unlocked(&session)?;
+    let (installed, wasm) = load_plugin(&app, &vault, &plugin_id)?;
+    let declared = installed
+        .manifest
+        .item_type(&item_type)
+        .ok_or_else(|| VaultError::InvalidMetadata(format!("no item type {item_type}")))?;
+    if let Some(unknown) = fields
+        .keys()
+        .find(|k| !declared.fields.iter().any(|f| &f.name == *k))
+    {
+        return Err(VaultError::InvalidMetadata(format!(
+            "{item_type} has no field {unknown}"
+        )));
+    }
+    if let Some(missing) = declared
+        .fields
+        .iter()
+        .find(|f| f.required && fields.get(&f.name).is_none_or(|v| v.is_empty()))
+    {
+        return Err(VaultError::InvalidMetadata(format!(
+            "{} is required",
+            missing.label
+        )));
+    }
+    runtime::call(
+        &wasm,
+        &PluginRequest::ValidateItem {
+            item_type: item_type.clone(),
+            fields: fields.clone(),
+        },
+    )?;
+
+    let (secret_fields, fields): (BTreeMap<_, _>, BTreeMap<_, _>) = fields
+        .into_iter()
+        .partition(|(name, _)| declared.fields.iter().any(|f| &f.name == name && f.secret));
+    let item = VaultItem::new(
+        label,
+        ItemPayload::Plugin(PluginItem {
+            plugin_id,
+            item_type,
+            fields,
+            secret_fields,
+        }),
+    );
+    let public = item.to_public();
+    push_item(&app, &vault, &items, &session, item)?;
+    Ok(public)
+}
+
+/// Reveal all fields of a plugin item, secret ones included. Gated like
+/// every other plaintext export. `consent_token` comes from
+/// `request_consent`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn plugin_item_reveal(
+    app: AppHandle,
+    item_id: String,
+    password: String,
+    consent_token: String,
+    state: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<BTreeMap<String, String>> {
+    enforce(&state, &limiter, SensitiveOp::Export)?;
+    enforce_policy(&state, PolicyGate::PlaintextExport)?;
+    enforce_item_export(&items, std::slice::from_ref(&item_id))?;
+    verify_password(&app, &state, &password)?;
+    require_consent(
+        &app,
+        &consent_token,
+        &ConsentOperation::PluginItemReveal {
+            item_id: item_id.clone(),
+        },
+    )?;
+    let mark = export_watermark(&app)?;
+    let decrypt = note_decrypt(&app, &state, &item_id);
+    let mut store = items.0.lock().unwrap();
+    match store
+        .iter_mut()
+        .find(|i| i.id == item_id && i.trashed_at.is_none())
+    {
+        Some(VaultItem {
+            payload: ItemPayload::Plugin(p),
+            usage,
+            ..
+        }) => {
+            usage.touch(Utc::now());
+            tracing::info!(
+                target: "audit",
+                item_id = %item_id,
+                plugin_id = %p.plugin_id,
+                item_type = %p.item_type,
+                profile = %mark.profile,
+                instance_id = %mark.instance_id,
+                "plugin item revealed"
+            );
+            decrypt.finish(&app)?;
+            let mut all = p.fields.clone();
+            all.extend(p.secret_fields.clone());
+            Ok(all)
+        }
+        _ => Err(VaultError::KeyNotFound(item_id)),
+    }
+}
+
+/// Run a plugin's exporter and write what it renders to `path`. The plugin
+/// receives only what its granted capabilities cover. When it may read its
+/// own items' secrets, the export is gated like a plaintext export.
+/// Requires the vault password and a `consent_token` from
+/// `request_consent`.
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
+pub fn run_plugin_exporter(
+    app: AppHandle,
+    password: String,
+    plugin_id: String,
+    exporter: String,
+    path: String,
+    consent_token: String,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<usize> {
+    require_unlocked(&session)?;
+    enforce(&state, &limiter, SensitiveOp::Export)?;
+    let target = Path::new(&path);
+    if !target.is_absolute() {
+        return Err(VaultError::InvalidMetadata(
+            "export path must be absolute".to_string(),
+        ));
+    }
+    let (installed, wasm) = load_plugin(&app, &state, &plugin_id)?;
+    if !installed
+        .manifest
+        .exporters
+        .iter()
+        .any(|e| e.name == exporter)
+    {
+        return Err(VaultError::InvalidMetadata(format!(
+            "plugin has no exporter {exporter}"
+        )));
+    }
+    let own_item_ids: Vec<String> = items
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|i| {
+            i.trashed_at.is_none()
+                && matches!(&i.payload, ItemPayload::Plugin(p) if p.plugin_id == plugin_id)
+        })
+        .map(|i| i.id.clone())
+        .collect();
+    if installed.has(PluginCapability::ReadPluginItems) {
+        enforce_policy(&state, PolicyGate::PlaintextExport)?;
+        enforce_item_export(&items, &own_item_ids)?;
+    }
+    verify_password(&app, &state, &password)?;
+    require_consent(
+        &app,
+        &consent_token,
+        &ConsentOperation::PluginExport {
+            plugin_id: plugin_id.clone(),
+            exporter: exporter.clone(),
+            path: path.clone(),
+        },
+    )?;
+
+    let keys = installed.has(PluginCapability::ReadPublicKeys).then(|| {
+        keystore
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|k| k.metadata.trashed_at.is_none())
+            .map(|k| PluginKey {
+                id: k.id.clone(),
+                key_type: k.metadata.key_type.as_str().to_string(),
+                label: k.metadata.label.clone(),
+                public_key_hex: k.public_key_hex.clone(),
+                address: k.metadata.address.clone(),
+                derivation_path: k.metadata.derivation_path.clone(),
+                created_at: k.metadata.created_at,
+            })
+            .collect()
+    });
+    let (item_metadata, plugin_items) = {
+        let store = items.0.lock().unwrap();
+        let live: Vec<&VaultItem> = store.iter().filter(|i| i.trashed_at.is_none()).collect();
+        let metadata = installed.has(PluginCapability::ReadItemMetadata).then(|| {
+            live.iter()
+                .map(|i| PluginItemMetadata {
+                    id: i.id.clone(),
+                    kind: i.payload.kind().to_string(),
+                    label: i.label.clone(),
+                    tags: i.tags.clone(),
+                    created_at: i.created_at,
+                    expires_at: i.expires_at,
+                })
+                .collect()
+        });
+        let own = installed.has(PluginCapability::ReadPluginItems).then(|| {
+            live.iter()
+                .filter_map(|i| match &i.payload {
+                    ItemPayload::Plugin(p) if p.plugin_id == plugin_id => {
+                        let mut fields = p.fields.clone();
+                        fields.extend(p.secret_fields.clone());
+                        Some(PluginItemContents {
+                            id: i.id.clone(),
+                            item_type: p.item_type.clone(),
+                            label: i.label.clone(),
+                            fields,
+                        })
+                    }
+                    _ => None,
+                })
+                .collect()
+        });
+        (metadata, own)
+    };
+
+    let rendered = runtime::call(
+        &wasm,
+        &PluginRequest::Export {
+            exporter: exporter.clone(),
+            keys,
+            items: item_metadata,
+            plugin_items,
+        },
+    )?;
+    let rendered = rendered.as_str().ok_or_else(|| {
+        PluginError::Failed("exporter did not answer with file contents".to_string())
+    })?;
+    atomic_write(target, rendered.as_bytes())?;
+    tracing::info!(
+        target: "audit",
+        plugin_id = %plugin_id,
+        exporter = %exporter,
+        path = %path,
+        granted = ?installed.granted,
+        "plugin export written"
+    );
+    Ok(rendered.len())
+}
+
+/// Derive the address of a vault key on a chain a plugin integrates. The
+/// plugin receives the key's public key and derivation path only. Requires
+/// an unlocked vault.
+#[tauri::command(async)]
+pub fn plugin_derive_address(
+    app: AppHandle,
+    plugin_id: String,
+    chain: String,
+    key_id: String,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    keystore: State<'_, KeyStore>,
+) -> Result<String> {
+    require_unlocked(&session)?;
+    let (installed, wasm) = load_plugin(&app, &state, &plugin_id)?;
+    if !installed.manifest.chains.iter().any(|c| c.name == chain) {
+        return Err(VaultError::InvalidMetadata(format!(
+            "plugin has no chain {chain}"
+        )));
+    }
+    let (public_key_hex, derivation_path) = {
+        let store = keystore.0.lock().unwrap();
+        let key = store
+            .iter()
+            .find(|k| k.id == key_id && k.metadata.trashed_at.is_none())
+            .ok_or_else(|| VaultError::KeyNotFound(key_id.clone()))?;
+        (
+            key.public_key_hex.clone(),
+            key.metadata.derivation_path.clone(),
+        )
+    };
+    let address = runtime::call(
+        &wasm,
+        &PluginRequest::DeriveAddress {
+            chain,
+            public_key_hex,
+            derivation_path,
+        },
+    )?;
+    address
+        .as_str()
+        .filter(|a| !a.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| PluginError::Failed("plugin did not answer with an address".into()).into())
+}
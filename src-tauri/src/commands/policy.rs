@@ -0,0 +1,137 @@
+use crate::commands::items::ItemStore;
+use crate::commands::keys::SessionKey;
+use crate::commands::limits::RateLimiter;
+use crate::commands::profiles::Profiles;
+use crate::commands::sync::device_id;
+use crate::commands::vault::{persist_vault, verify_password, KdfCache, VaultMutex};
+use crate::error::{Result, VaultError};
+use crate::models::policy::{ExportWatermark, PolicyGate, SecurityPolicy};
+use chrono::{DateTime, Duration, Utc};
+use tauri::{AppHandle, Manager, State};
+
+/// Refuse `gate` if the vault's security policy forbids it at `now` (unix
+/// seconds). Denials are written to the `audit` tracing target. Takes the
+/// vault lock, so call it before holding any.
+pub(crate) fn enforce_policy_at(
+    vault: &State<'_, VaultMutex>,
+    gate: PolicyGate,
+    reauthenticated_at: Option<u64>,
+    now: u64,
+) -> Result<()> {
+    let (policy, has_verified_backup) = {
+        let v = vault.0.lock().unwrap();
+        (v.security_policy, v.has_verified_backup())
+    };
+    policy
+        .check(gate, reauthenticated_at, has_verified_backup, now)
+        .map_err(|reason| {
+            tracing::warn!(
+                target: "audit",
+                gate = gate.as_str(),
+                reason = %reason,
+                "operation denied by security policy"
+            );
+            VaultError::PolicyDenied(reason)
+        })
+}
+
+/// [`enforce_policy_at`] for gates that do not depend on re-authentication.
+pub(crate) fn enforce_policy(vault: &State<'_, VaultMutex>, gate: PolicyGate) -> Result<()> {
+    enforce_policy_at(vault, gate, None, Utc::now().timestamp() as u64)
+}
+
+/// Refuse a plaintext export that includes an item flagged
+/// `no_plaintext_export`, audited like a policy denial. Unknown ids are left
+/// to the caller's own lookup.
+pub(crate) fn enforce_item_export(items: &State<'_, ItemStore>, item_ids: &[String]) -> Result<()> {
+    let store = items.0.lock().unwrap();
+    for item in store.iter().filter(|i| item_ids.contains(&i.id)) {
+        item.check_plaintext_export().map_err(|reason| {
+            tracing::warn!(
+                target: "audit",
+                item_id = %item.id,
+                reason = %reason,
+                "plaintext export denied by item policy"
+            );
+            VaultError::PolicyDenied(reason)
+        })?;
+    }
+    Ok(())
+}
+
+/// The watermark for a plaintext export made now from the active profile.
+pub(crate) fn export_watermark(app: &AppHandle) -> Result<ExportWatermark> {
+    let profile = {
+        let profiles = app.state::<Profiles>();
+        let store = profiles.0.lock().unwrap();
+        store
+            .get(&store.active)
+            .map_or_else(|| store.active.clone(), |p| p.name.clone())
+    };
+    Ok(ExportWatermark {
+        profile,
+        instance_id: device_id(app)?,
+        exported_at: Utc::now(),
+    })
+}
+
+#[tauri::command]
+pub fn get_security_policy(vault: State<'_, VaultMutex>) -> Result<SecurityPolicy> {
+    Ok(vault.0.lock().unwrap().security_policy)
+}
+
+/// Replace the security policy. Requires an unlocked vault and the password,
+/// so an unattended session cannot loosen it.
+#[tauri::command]
+pub fn set_security_policy(
+    app: AppHandle,
+    password: String,
+    policy: SecurityPolicy,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<SecurityPolicy> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    policy.validate().map_err(VaultError::InvalidMetadata)?;
+    verify_password(&app, &vault, &password)?;
+    let mut vault = vault.0.lock().unwrap();
+    let mut next = vault.clone();
+    next.security_policy = policy;
+    persist_vault(&app, &next)?;
+    *vault = next;
+    app.state::<KdfCache>().0.lock().unwrap().clear();
+    tracing::warn!(
+        target: "audit",
+        require_reauth_for_decrypt = policy.require_reauth_for_decrypt,
+        reauth_window_secs = policy.reauth_window_secs,
+        deny_plaintext_export = policy.deny_plaintext_export,
+        require_verified_backup = policy.require_verified_backup,
+        "security policy changed"
+    );
+    Ok(policy)
+}
+
+/// Re-enter the password to open the policy's re-authentication window for
+/// signing. Returns when the window closes. Locking the vault closes it early.
+#[tauri::command]
+pub fn reauthenticate(
+    app: AppHandle,
+    password: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<DateTime<Utc>> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    verify_password(&app, &vault, &password)?;
+    let window = vault.0.lock().unwrap().security_policy.reauth_window_secs;
+    let now = Utc::now();
+    limiter
+        .0
+        .lock()
+        .unwrap()
+        .record_reauth(now.timestamp() as u64);
+    Ok(now + Duration::seconds(window as i64))
+}
@@ -0,0 +1,291 @@
+use crate::commands::backup::{collect_vault_files, Drives};
+use crate::commands::keys::{atomic_write, restrict_dir_permissions, secure_remove, SessionKey};
+use crate::commands::notifications::DRIVE_WATCH_INTERVAL_SECS;
+use crate::commands::profiles::profile_dir;
+use crate::commands::vault::{lock_vault, verify_password, VaultMutex, VAULT_FILE};
+use crate::drive::backup::VaultFile;
+use crate::drive::DriveBackend;
+use crate::error::{Result, VaultError};
+use crate::models::drive::{
+    check_portable_drive, portable_root, PortableVault, PortableVaultStatus,
+};
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// File in the profile's directory naming the drive the vault was moved to. Never part of the
+/// vault's own files, so it is not copied to the drive or into backups.
+pub const PORTABLE_FILE: &str = "portable_vault.json";
+/// Emitted when the drive holding the vault goes away and the vault is
+/// locked because of it.
+pub const VAULT_DRIVE_DETACHED_EVENT: &str = "vault_drive_detached";
+
+/// The drive the vault lives on, with its vault directory while attached.
+struct ActivePortable {
+    config: PortableVault,
+    root: Option<PathBuf>,
+}
+
+/// `None` while the vault's files are in the local data directory.
+#[derive(Default)]
+pub struct PortableState(Mutex<Option<ActivePortable>>);
+
+/// The vault directory on `config`'s drive, if the drive is mounted and
+/// holds a vault.
+fn resolve_root(drives: &dyn DriveBackend, config: &PortableVault) -> Option<PathBuf> {
+    let drive = drives.drive(&config.drive_id).ok()?;
+    portable_root(&drive).filter(|root| root.join(VAULT_FILE).is_file())
+}
+
+/// Read the active profile's `portable_vault.json`. Call at startup and after
+/// switching profiles, before anything resolves the data directory.
+pub fn init_portable(app: &AppHandle) {
+    let active = profile_dir(app)
+        .ok()
+        .and_then(|dir| std::fs::read(dir.join(PORTABLE_FILE)).ok())
+        .and_then(
+            |data| match serde_json::from_slice::<PortableVault>(&data) {
+                Ok(config) => Some(config),
+                Err(e) => {
+                    tracing::warn!("unreadable {PORTABLE_FILE}: {e}");
+                    None
+                }
+            },
+        )
+        .map(|config| ActivePortable {
+            root: resolve_root(app.state::<Drives>().0.as_ref(), &config),
+            config,
+        });
+    *app.state::<PortableState>().0.lock().unwrap() = active;
+}
+
+/// The vault directory on the drive, `None` if the vault is stored locally.
+/// Re-detects the drive when it is not at its last known mount point.
+pub(crate) fn portable_vault_dir(app: &AppHandle) -> Result<Option<PathBuf>> {
+    let portable = app.state::<PortableState>();
+    let mut guard = portable.0.lock().unwrap();
+    let Some(active) = guard.as_mut() else {
+        return Ok(None);
+    };
+    if !active
+        .root
+        .as_ref()
+        .is_some_and(|r| r.join(VAULT_FILE).is_file())
+    {
+        active.root = resolve_root(app.state::<Drives>().0.as_ref(), &active.config);
+    }
+    match &active.root {
+        Some(root) => Ok(Some(root.clone())),
+        None => Err(VaultError::Storage(format!(
+            "the drive holding this vault ({}) is not attached",
+            active
+                .config
+                .label
+                .as_deref()
+                .unwrap_or(&active.config.drive_id)
+        ))),
+    }
+}
+
+fn status(app: &AppHandle) -> PortableVaultStatus {
+    let attached = portable_vault_dir(app);
+    let portable = app.state::<PortableState>();
+    let guard = portable.0.lock().unwrap();
+    PortableVaultStatus {
+        enabled: guard.is_some(),
+        drive_id: guard.as_ref().map(|a| a.config.drive_id.clone()),
+        label: guard.as_ref().and_then(|a| a.config.label.clone()),
+        attached: matches!(attached, Ok(Some(_))),
+        path: attached
+            .ok()
+            .flatten()
+            .map(|p| p.to_string_lossy().into_owned()),
+    }
+}
+
+/// Write `files` into `dir` and read each back. On any failure the files
+/// written so far are removed again, so a retry starts clean.
+fn copy_vault_files(files: &[VaultFile], dir: &Path) -> Result<()> {
+    let mut written = Vec::with_capacity(files.len());
+    let result = files.iter().try_for_each(|f| {
+        let path = dir.join(&f.name);
+        atomic_write(&path, &f.data)?;
+        written.push(path.clone());
+        match std::fs::read(&path) {
+            Ok(back) if back == f.data => Ok(()),
+            Ok(_) => Err(VaultError::Storage(format!(
+                "{} did not read back intact",
+                f.name
+            ))),
+            Err(e) => Err(VaultError::Storage(e.to_string())),
+        }
+    });
+    let result = result.and_then(|_| {
+        // Removable media is often pulled right after a write; make the
+        // renames durable as well.
+        std::fs::File::open(dir)
+            .and_then(|d| d.sync_all())
+            .map_err(|e| VaultError::Storage(e.to_string()))
+    });
+    if result.is_err() {
+        for path in &written {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    result
+}
+
+/// Remove the copies a move left behind. The move has already committed, so
+/// failures are logged rather than returned.
+fn remove_vault_files(files: &[VaultFile], dir: &Path) {
+    for f in files {
+        if let Err(e) = secure_remove(&dir.join(&f.name)) {
+            tracing::warn!("could not remove {} after moving the vault: {e}", f.name);
+        }
+    }
+}
+
+/// Whether the vault is on a drive and whether that drive is attached.
+#[tauri::command]
+pub fn portable_vault_status(app: AppHandle) -> Result<PortableVaultStatus> {
+    Ok(status(&app))
+}
+
+/// Move the vault's files onto an unlocked, mounted encrypted drive and run
+/// the vault from there. The local copies are wiped once the drive copies
+/// read back intact. From then on the vault cannot be opened, and is locked,
+/// whenever the drive is absent.
+#[tauri::command]
+pub fn move_vault_to_drive(
+    app: AppHandle,
+    drive_id: String,
+    password: String,
+    state: State<'_, VaultMutex>,
+    drives: State<'_, Drives>,
+    portable: State<'_, PortableState>,
+) -> Result<PortableVaultStatus> {
+    verify_password(&app, &state, &password)?;
+    if let Some(active) = portable.0.lock().unwrap().as_ref() {
+        return Err(VaultError::InvalidMetadata(format!(
+            "the vault is already on drive {}",
+            active.config.drive_id
+        )));
+    }
+    let drive = drives.0.drive(&drive_id)?;
+    let root = check_portable_drive(&drive).map_err(VaultError::InvalidMetadata)?;
+    if root.join(VAULT_FILE).exists() {
+        return Err(VaultError::InvalidMetadata(format!(
+            "drive {drive_id} already holds a vault"
+        )));
+    }
+
+    let vault = state.0.lock().unwrap();
+    let local = profile_dir(&app)?;
+    let files = collect_vault_files(&app, &vault)?;
+    std::fs::create_dir_all(&root).map_err(|e| VaultError::Storage(e.to_string()))?;
+    restrict_dir_permissions(&root)?;
+    copy_vault_files(&files, &root)?;
+
+    let config = PortableVault {
+        drive_id: drive_id.clone(),
+        label: drive.label.clone(),
+        moved_at: Utc::now(),
+    };
+    let pointer = serde_json::to_vec_pretty(&config)?;
+    if let Err(e) = atomic_write(&local.join(PORTABLE_FILE), &pointer) {
+        remove_vault_files(&files, &root);
+        return Err(e);
+    }
+    *portable.0.lock().unwrap() = Some(ActivePortable {
+        config,
+        root: Some(root.clone()),
+    });
+    remove_vault_files(&files, &local);
+    tracing::info!(
+        target: "audit",
+        drive_id = %drive_id,
+        files = files.len(),
+        "vault moved to drive"
+    );
+    drop(vault);
+    Ok(status(&app))
+}
+
+/// Move the vault's files from its drive back into the local data directory.
+/// The drive must be attached; its copies are wiped afterwards.
+#[tauri::command]
+pub fn move_vault_from_drive(
+    app: AppHandle,
+    password: String,
+    state: State<'_, VaultMutex>,
+    portable: State<'_, PortableState>,
+) -> Result<PortableVaultStatus> {
+    verify_password(&app, &state, &password)?;
+    let Some(root) = portable_vault_dir(&app)? else {
+        return Err(VaultError::InvalidMetadata(
+            "the vault is not on a drive".to_string(),
+        ));
+    };
+    let local = profile_dir(&app)?;
+    if local.join(VAULT_FILE).exists() {
+        return Err(VaultError::InvalidMetadata(
+            "the local data directory already holds a vault".to_string(),
+        ));
+    }
+
+    let vault = state.0.lock().unwrap();
+    let files = collect_vault_files(&app, &vault)?;
+    copy_vault_files(&files, &local)?;
+    if let Err(e) = std::fs::remove_file(local.join(PORTABLE_FILE)) {
+        remove_vault_files(&files, &local);
+        return Err(VaultError::Storage(e.to_string()));
+    }
+    let drive_id = portable
+        .0
+        .lock()
+        .unwrap()
+        .take()
+        .map(|a| a.config.drive_id)
+        .unwrap_or_default();
+    remove_vault_files(&files, &root);
+    let _ = std::fs::remove_dir(&root);
+    tracing::info!(
+        target: "audit",
+        drive_id = %drive_id,
+        files = files.len(),
+        "vault moved back from drive"
+    );
+    drop(vault);
+    Ok(status(&app))
+}
+
+/// Lock the vault as soon as the drive it runs from goes away, so no key
+/// operation runs against a vault whose files are gone.
+pub fn spawn_portable_watch(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(DRIVE_WATCH_INTERVAL_SECS));
+        if app.state::<SessionKey>().0.lock().unwrap().is_none() {
+            continue;
+        }
+        if portable_vault_dir(&app).is_ok() {
+            continue;
+        }
+        // Usage counters cannot be flushed without the drive; the lock itself
+        // always happens.
+        if let Err(e) = lock_vault(
+            app.clone(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+        ) {
+            tracing::warn!("vault locked after its drive was removed: {e}");
+        }
+        tracing::info!(target: "audit", "vault locked: drive removed");
+        let _ = app.emit(VAULT_DRIVE_DETACHED_EVENT, ());
+    });
+}
@@ -0,0 +1,159 @@
+use crate::commands::keys::{
+    atomic_write, load_sealed, save_sealed, session_key, KeyStore, SessionKey,
+};
+use crate::commands::price::stored_snapshot;
+use crate::commands::profiles::Profiles;
+use crate::commands::vault::VaultMutex;
+use crate::crypto::price;
+use crate::error::{Result, VaultError};
+use crate::models::portfolio::{
+    BalanceStore, PortfolioReport, PortfolioReportFormat, RecordedBalance,
+};
+use crate::report;
+use chrono::Utc;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Recorded balances, encrypted with the session key next to `vault.json`.
+pub const BALANCES_FILE: &str = "balances.json";
+
+pub(crate) fn load_store(app: &AppHandle, key: &[u8; 32]) -> Result<BalanceStore> {
+    load_sealed(app, BALANCES_FILE, key)
+}
+
+pub(crate) fn save_store(app: &AppHandle, key: &[u8; 32], store: &BalanceStore) -> Result<()> {
+    save_sealed(app, BALANCES_FILE, key, store)
+}
+
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn list_balances(
+    app: AppHandle,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<RecordedBalance>> {
+    Ok(load_store(&app, &session_key(&session)?)?.balances)
+}
+
+/// Record what a key holds of `asset`, in base units, replacing an earlier
+/// figure for the same key and asset. The vault does not sync chain state;
+/// take the figure from an explorer or a watch-only wallet. Requires an
+/// unlocked vault.
+#[tauri::command]
+pub fn record_balance(
+    app: AppHandle,
+    key_id: String,
+    asset: String,
+    amount: String,
+    note: Option<String>,
+    session: State<'_, SessionKey>,
+    keystore: State<'_, KeyStore>,
+) -> Result<RecordedBalance> {
+    let key = session_key(&session)?;
+    if !keystore
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|k| k.id == key_id && k.metadata.trashed_at.is_none())
+    {
+        return Err(VaultError::KeyNotFound(key_id));
+    }
+    let asset = price::normalize_asset(&asset)
+        .map_err(|_| VaultError::InvalidMetadata(format!("invalid asset {asset:?}")))?;
+    let amount = amount
+        .trim()
+        .parse::<u128>()
+        .map_err(|_| VaultError::InvalidMetadata(format!("invalid amount {amount:?}")))?
+        .to_string();
+    let balance = RecordedBalance {
+        key_id,
+        asset,
+        amount,
+        recorded_at: Utc::now(),
+        note: note.filter(|n| !n.trim().is_empty()),
+    };
+    let mut store = load_store(&app, &key)?;
+    store
+        .balances
+        .retain(|b| !(b.key_id == balance.key_id && b.asset == balance.asset));
+    store.balances.push(balance.clone());
+    save_store(&app, &key, &store)?;
+    Ok(balance)
+}
+
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn remove_balance(
+    app: AppHandle,
+    key_id: String,
+    asset: String,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    let key = session_key(&session)?;
+    let asset = asset.trim().to_ascii_uppercase();
+    let mut store = load_store(&app, &key)?;
+    let before = store.balances.len();
+    store
+        .balances
+        .retain(|b| !(b.key_id == key_id && b.asset == asset));
+    if store.balances.len() == before {
+        return Err(VaultError::KeyNotFound(format!("{key_id}/{asset}")));
+    }
+    save_store(&app, &key, &store)
+}
+
+/// Write a report of the active vault to `path`: key counts, backup
+/// coverage, recorded balances and their value from the stored price
+/// snapshot. Only public data is written, but balances and addresses
+/// together say a lot about a holder, so treat the file accordingly.
+/// Returns the report. Requires an unlocked vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_portfolio_report(
+    app: AppHandle,
+    format: PortfolioReportFormat,
+    path: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    keystore: State<'_, KeyStore>,
+    profiles: State<'_, Profiles>,
+) -> Result<PortfolioReport> {
+    let key = session_key(&session)?;
+    let target = Path::new(&path);
+    if !target.is_absolute() {
+        return Err(VaultError::InvalidMetadata(
+            "report path must be absolute".to_string(),
+        ));
+    }
+    let vault_name = {
+        let profiles = profiles.0.lock().unwrap();
+        profiles
+            .get(&profiles.active)
+            .map_or_else(|| profiles.active.clone(), |p| p.name.clone())
+    };
+    let balances = load_store(&app, &key)?.balances;
+    let prices = stored_snapshot(&app, &key)?;
+    let (report, locale) = {
+        let vault = vault.0.lock().unwrap();
+        let keys = keystore.0.lock().unwrap();
+        let report = report::build(
+            &vault_name,
+            &keys,
+            &balances,
+            &vault,
+            prices.as_ref(),
+            Utc::now(),
+        );
+        (report, vault.locale())
+    };
+    atomic_write(target, &report::render(&report, format, locale)?)?;
+    tracing::info!(
+        target: "audit",
+        format = ?format,
+        path = %path,
+        keys = report.total_keys,
+        holdings = report.holdings.len(),
+        "portfolio report written"
+    );
+    Ok(report)
+}
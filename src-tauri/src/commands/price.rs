@@ -0,0 +1,248 @@
+use crate::commands::keys::{load_sealed, save_sealed, session_key, SessionKey};
+use crate::commands::locale::app_locale;
+use crate::commands::vault::{verify_password, VaultMutex};
+use crate::crypto::fingerprint::KeyFingerprint;
+use crate::crypto::mldsa87::PublicKey;
+use crate::crypto::price::{self, PriceError};
+use crate::error::{Result, VaultError};
+use crate::models::price::{
+    FiatValue, PriceSigner, PriceSnapshot, PriceSnapshotInfo, PriceStore, MAX_PRICE_SNAPSHOT_BYTES,
+};
+use chrono::{DateTime, Utc};
+use tauri::{AppHandle, State};
+
+/// Trusted price publishers and the latest snapshot, encrypted with the
+/// session key next to `vault.json`.
+pub const PRICES_FILE: &str = "prices.json";
+
+fn load_store(app: &AppHandle, key: &[u8; 32]) -> Result<PriceStore> {
+    load_sealed(app, PRICES_FILE, key)
+}
+
+fn save_store(app: &AppHandle, key: &[u8; 32], store: &PriceStore) -> Result<()> {
+    save_sealed(app, PRICES_FILE, key, store)
+}
+
+fn info(store: &PriceStore, now: DateTime<Utc>, locale: &str) -> Option<PriceSnapshotInfo> {
+    let snapshot = store.snapshot.as_ref()?;
+    let signer = store
+        .signers
+        .iter()
+        .find(|s| s.public_key_hex == snapshot.public_key_hex)?;
+    Some(PriceSnapshotInfo {
+        created_at: snapshot.created_at,
+        imported_at: store.imported_at,
+        currency: snapshot.currency.clone(),
+        signer_name: signer.name.clone(),
+        signer_fingerprint: signer.fingerprint.clone(),
+        prices: snapshot.prices.clone(),
+        age_hours: (now - snapshot.created_at).num_hours(),
+        warnings: price::age_warnings(snapshot.created_at, now, locale),
+    })
+}
+
+fn value_of(
+    store: &PriceStore,
+    asset: &str,
+    amount: u128,
+    now: DateTime<Utc>,
+    locale: &str,
+) -> Option<FiatValue> {
+    let snapshot = store.snapshot.as_ref()?;
+    let asset = price::normalize_asset(asset).ok()?;
+    let entry = snapshot.prices.iter().find(|p| p.asset == asset)?;
+    Some(FiatValue {
+        asset,
+        amount: amount.to_string(),
+        value: price::fiat_value(amount, entry)?,
+        currency: snapshot.currency.clone(),
+        as_of: snapshot.created_at,
+        warnings: price::age_warnings(snapshot.created_at, now, locale),
+    })
+}
+
+/// `amount` base units of `asset` in fiat, if the stored snapshot prices
+/// it. For other commands that show values, such as spending approvals.
+/// Takes the vault lock.
+pub(crate) fn fiat_estimate(
+    app: &AppHandle,
+    key: &[u8; 32],
+    asset: &str,
+    amount: u128,
+) -> Result<Option<FiatValue>> {
+    Ok(value_of(
+        &load_store(app, key)?,
+        asset,
+        amount,
+        Utc::now(),
+        app_locale(app),
+    ))
+}
+
+/// The stored snapshot, for reports that value several holdings at once.
+pub(crate) fn stored_snapshot(app: &AppHandle, key: &[u8; 32]) -> Result<Option<PriceSnapshot>> {
+    Ok(load_store(app, key)?.snapshot)
+}
+
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn list_price_signers(
+    app: AppHandle,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<PriceSigner>> {
+    Ok(load_store(&app, &session_key(&session)?)?.signers)
+}
+
+/// Trust a publisher's ML-DSA-87 key for price snapshots. Compare the
+/// returned fingerprint with the publisher's before importing anything.
+/// Requires the vault password and an unlocked vault.
+#[tauri::command]
+pub fn trust_price_signer(
+    app: AppHandle,
+    password: String,
+    name: String,
+    public_key_hex: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<PriceSigner> {
+    let key = session_key(&session)?;
+    verify_password(&app, &vault, &password)?;
+    if name.trim().is_empty() {
+        return Err(VaultError::InvalidMetadata(
+            "a price signer needs a name".to_string(),
+        ));
+    }
+    let public_key_hex = public_key_hex.trim().to_ascii_lowercase();
+    let pk = PublicKey::from_hex(&public_key_hex).map_err(PriceError::from)?;
+    let mut store = load_store(&app, &key)?;
+    if store
+        .signers
+        .iter()
+        .any(|s| s.public_key_hex == public_key_hex)
+    {
+        return Err(VaultError::KeyAlreadyExists(public_key_hex));
+    }
+    let signer = PriceSigner {
+        name: name.trim().to_string(),
+        public_key_hex,
+        fingerprint: KeyFingerprint::of(pk.as_bytes()),
+        added_at: Utc::now(),
+    };
+    store.signers.push(signer.clone());
+    save_store(&app, &key, &store)?;
+    tracing::warn!(
+        target: "audit",
+        name = %signer.name,
+        fingerprint = %signer.fingerprint.hex,
+        "price signer trusted"
+    );
+    Ok(signer)
+}
+
+/// Stop trusting a publisher. A stored snapshot they signed is dropped with
+/// them. Requires the vault password and an unlocked vault.
+#[tauri::command]
+pub fn remove_price_signer(
+    app: AppHandle,
+    password: String,
+    public_key_hex: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    let key = session_key(&session)?;
+    verify_password(&app, &vault, &password)?;
+    let public_key_hex = public_key_hex.trim().to_ascii_lowercase();
+    let mut store = load_store(&app, &key)?;
+    let before = store.signers.len();
+    store.signers.retain(|s| s.public_key_hex != public_key_hex);
+    if store.signers.len() == before {
+        return Err(VaultError::KeyNotFound(public_key_hex));
+    }
+    if store
+        .snapshot
+        .as_ref()
+        .is_some_and(|s| s.public_key_hex == public_key_hex)
+    {
+        store.snapshot = None;
+        store.imported_at = None;
+    }
+    save_store(&app, &key, &store)?;
+    tracing::warn!(
+        target: "audit",
+        fingerprint = %KeyFingerprint::of_hex(&public_key_hex).hex,
+        "price signer removed"
+    );
+    Ok(())
+}
+
+/// Import a signed price snapshot (JSON or CSV) from `path`. It must verify
+/// against a trusted signer and be newer than the stored snapshot, which it
+/// replaces. Requires an unlocked vault.
+#[tauri::command]
+pub fn import_price_snapshot(
+    app: AppHandle,
+    path: String,
+    session: State<'_, SessionKey>,
+) -> Result<PriceSnapshotInfo> {
+    let key = session_key(&session)?;
+    let size = std::fs::metadata(&path)
+        .map_err(|e| VaultError::Storage(e.to_string()))?
+        .len();
+    if size > MAX_PRICE_SNAPSHOT_BYTES as u64 {
+        return Err(PriceError::Malformed(format!(
+            "file is larger than {MAX_PRICE_SNAPSHOT_BYTES} bytes"
+        ))
+        .into());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    let snapshot = price::parse(&data)?;
+    let mut store = load_store(&app, &key)?;
+    let signer = price::verify(&snapshot, &store.signers)
+        .inspect_err(|e| {
+            tracing::warn!(target: "audit", error = %e, "price snapshot rejected");
+        })?
+        .clone();
+    let now = Utc::now();
+    price::check_newer(&snapshot, store.snapshot.as_ref(), now)?;
+    store.snapshot = Some(snapshot);
+    store.imported_at = Some(now);
+    save_store(&app, &key, &store)?;
+    let info = info(&store, now, app_locale(&app)).expect("snapshot was just stored");
+    tracing::info!(
+        target: "audit",
+        signer = %signer.name,
+        fingerprint = %signer.fingerprint.hex,
+        created_at = %info.created_at,
+        prices = info.prices.len(),
+        "price snapshot imported"
+    );
+    Ok(info)
+}
+
+/// The stored snapshot with its age warnings, if any. Requires an unlocked
+/// vault.
+#[tauri::command]
+pub fn get_price_snapshot(
+    app: AppHandle,
+    session: State<'_, SessionKey>,
+) -> Result<Option<PriceSnapshotInfo>> {
+    let store = load_store(&app, &session_key(&session)?)?;
+    Ok(info(&store, Utc::now(), app_locale(&app)))
+}
+
+/// `amount` base units of `asset` (a decimal string, so wei amounts keep
+/// their precision) in the snapshot's currency. `None` when the stored
+/// snapshot does not price the asset. Requires an unlocked vault.
+#[tauri::command]
+pub fn price_value(
+    app: AppHandle,
+    asset: String,
+    amount: String,
+    session: State<'_, SessionKey>,
+) -> Result<Option<FiatValue>> {
+    let amount: u128 = amount
+        .trim()
+        .parse()
+        .map_err(|_| VaultError::InvalidMetadata(format!("invalid amount {amount:?}")))?;
+    fiat_estimate(&app, &session_key(&session)?, &asset, amount)
+}
@@ -0,0 +1,159 @@
+use crate::commands::agent::{stop_agent, AgentHandle};
+use crate::commands::items::ItemStore;
+use crate::commands::keys::{
+    atomic_write, local_data_dir, restrict_dir_permissions, KeyStore, MasterSeed, SessionKey,
+};
+use crate::commands::pairing::{stop_pairing, BrowserPairing};
+use crate::commands::portable::init_portable;
+use crate::commands::vault::{lock_vault, VaultMutex, VAULT_FILE};
+use crate::error::{Result, VaultError};
+use crate::models::profile::{
+    Profile, ProfileInfo, ProfileStore, DEFAULT_PROFILE_ID, PROFILES_DIR,
+};
+use crate::models::vault::VaultState;
+use chrono::Utc;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+/// Profile list and active profile, in the local data directory.
+pub const PROFILES_FILE: &str = "profiles.json";
+
+#[derive(Default)]
+pub struct Profiles(pub Mutex<ProfileStore>);
+
+/// Read `profiles.json` at startup, before anything resolves the data
+/// directory.
+pub fn init_profiles(app: &AppHandle) {
+    let loaded = local_data_dir(app)
+        .ok()
+        .and_then(|dir| std::fs::read(dir.join(PROFILES_FILE)).ok())
+        .and_then(|data| match serde_json::from_slice::<ProfileStore>(&data) {
+            Ok(store) => Some(store.normalized()),
+            Err(e) => {
+                tracing::warn!("unreadable {PROFILES_FILE}: {e}");
+                None
+            }
+        });
+    if let Some(store) = loaded {
+        *app.state::<Profiles>().0.lock().unwrap() = store;
+    }
+}
+
+fn save_profiles(app: &AppHandle, store: &ProfileStore) -> Result<()> {
+    let data = serde_json::to_vec_pretty(store)?;
+    atomic_write(&local_data_dir(app)?.join(PROFILES_FILE), &data)
+}
+
+/// Directory of profile `id`: the local data directory itself for the
+/// default profile, `profiles/<id>` for the others.
+fn dir_of(app: &AppHandle, id: &str) -> Result<PathBuf> {
+    let root = local_data_dir(app)?;
+    if id == DEFAULT_PROFILE_ID {
+        return Ok(root);
+    }
+    let dir = root.join(PROFILES_DIR).join(id);
+    std::fs::create_dir_all(&dir).map_err(|e| VaultError::Storage(e.to_string()))?;
+    restrict_dir_permissions(&dir)?;
+    Ok(dir)
+}
+
+/// The `vault.json` of profile `id`, for reading another profile's settings
+/// without switching to it. `None` if it has no vault or it is unreadable.
+pub(crate) fn profile_vault(app: &AppHandle, id: &str) -> Option<VaultState> {
+    let data = std::fs::read(dir_of(app, id).ok()?.join(VAULT_FILE)).ok()?;
+    serde_json::from_slice(&data)
+        .inspect_err(|e| tracing::warn!(profile_id = %id, "unreadable {VAULT_FILE}: {e}"))
+        .ok()
+}
+
+/// The active profile's directory, created if missing.
+pub(crate) fn profile_dir(app: &AppHandle) -> Result<PathBuf> {
+    let active = app.state::<Profiles>().0.lock().unwrap().active.clone();
+    dir_of(app, &active)
+}
+
+fn info(app: &AppHandle, store: &ProfileStore, profile: &Profile) -> ProfileInfo {
+    ProfileInfo {
+        profile: profile.clone(),
+        active: profile.id == store.active,
+        initialized: dir_of(app, &profile.id).is_ok_and(|d| d.join(VAULT_FILE).is_file()),
+    }
+}
+
+#[tauri::command]
+pub fn list_profiles(app: AppHandle, profiles: State<'_, Profiles>) -> Result<Vec<ProfileInfo>> {
+    let store = profiles.0.lock().unwrap();
+    Ok(store
+        .profiles
+        .iter()
+        .map(|p| info(&app, &store, p))
+        .collect())
+}
+
+/// Add an empty profile. Switch to it and create a vault there as usual.
+#[tauri::command]
+pub fn create_profile(
+    app: AppHandle,
+    name: String,
+    profiles: State<'_, Profiles>,
+) -> Result<ProfileInfo> {
+    let mut store = profiles.0.lock().unwrap();
+    let mut updated = store.clone();
+    let profile = updated
+        .add(&name, Utc::now())
+        .map_err(VaultError::InvalidMetadata)?;
+    dir_of(&app, &profile.id)?;
+    save_profiles(&app, &updated)?;
+    *store = updated;
+    tracing::info!(target: "audit", profile_id = %profile.id, "profile created");
+    Ok(info(&app, &store, &profile))
+}
+
+/// Make `profile_id` the active profile. The current vault is locked first,
+/// flushing its stores and wiping its keys, seed and session from memory,
+/// and the agent and browser pairing are stopped. The other profile's vault
+/// then loads on its next command, locked.
+#[tauri::command]
+pub fn switch_profile(
+    app: AppHandle,
+    profile_id: String,
+    profiles: State<'_, Profiles>,
+) -> Result<ProfileInfo> {
+    if profiles.0.lock().unwrap().get(&profile_id).is_none() {
+        return Err(VaultError::InvalidMetadata(format!(
+            "no profile with id {profile_id}"
+        )));
+    }
+    if app.state::<SessionKey>().0.lock().unwrap().is_some() {
+        lock_vault(
+            app.clone(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+            app.state(),
+        )?;
+    }
+    stop_agent(&app.state::<AgentHandle>());
+    stop_pairing(&app.state::<BrowserPairing>());
+    *app.state::<VaultMutex>().0.lock().unwrap() = VaultState::default();
+    *app.state::<SessionKey>().0.lock().unwrap() = None;
+    *app.state::<MasterSeed>().0.lock().unwrap() = None;
+    app.state::<KeyStore>().0.lock().unwrap().clear();
+    app.state::<ItemStore>().0.lock().unwrap().clear();
+
+    let mut store = profiles.0.lock().unwrap();
+    let mut updated = store.clone();
+    updated.active = profile_id.clone();
+    save_profiles(&app, &updated)?;
+    *store = updated;
+    drop(store);
+    init_portable(&app);
+    tracing::info!(target: "audit", profile_id = %profile_id, "profile switched");
+
+    let store = profiles.0.lock().unwrap();
+    let profile = store.get(&profile_id).expect("checked above");
+    Ok(info(&app, &store, profile))
+}
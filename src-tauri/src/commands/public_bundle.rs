@@ -0,0 +1,191 @@
+use crate::commands::instance::instance_key;
+use crate::commands::keys::{
+    atomic_write, load_sealed, save_sealed, session_key, KeyStore, MasterSeed, SessionKey,
+};
+use crate::commands::profiles::Profiles;
+use crate::crypto::attestation;
+use crate::crypto::fingerprint::KeyFingerprint;
+use crate::crypto::public_bundle::{self, PUBLIC_BUNDLE_VERSION};
+use crate::error::{Result, VaultError};
+use crate::models::address::ADDRESS_NETWORK;
+use crate::models::public_bundle::{
+    PublicBundle, PublicBundleKey, PublicBundleSummary, SignedPublicBundle, WatchOnlySourcePublic,
+    WatchOnlyStore,
+};
+use chrono::Utc;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Watch-only keys imported from other vaults, encrypted with the session
+/// key next to `vault.json`.
+pub const WATCH_ONLY_FILE: &str = "watch_only.json";
+
+fn load_store(app: &AppHandle, key: &[u8; 32]) -> Result<WatchOnlyStore> {
+    load_sealed(app, WATCH_ONLY_FILE, key)
+}
+
+fn save_store(app: &AppHandle, key: &[u8; 32], store: &WatchOnlyStore) -> Result<()> {
+    save_sealed(app, WATCH_ONLY_FILE, key, store)
+}
+
+fn absolute(path: &str) -> Result<&Path> {
+    let path = Path::new(path);
+    if !path.is_absolute() {
+        return Err(VaultError::InvalidMetadata(
+            "bundle path must be absolute".to_string(),
+        ));
+    }
+    Ok(path)
+}
+
+/// Write a signed bundle of the public keys, addresses and labels of every
+/// key not in the trash to `path`, for an accountant or a watch-only setup
+/// on another vault instance. `vault_id` is the profile to export; only the
+/// active profile's vault can be unlocked, so it must be that one. Never
+/// holds a secret. Requires an unlocked vault.
+#[tauri::command]
+pub fn export_public_bundle(
+    app: AppHandle,
+    vault_id: String,
+    path: String,
+    profiles: State<'_, Profiles>,
+    keystore: State<'_, KeyStore>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<PublicBundleSummary> {
+    let source_name = {
+        let store = profiles.0.lock().unwrap();
+        let profile = store
+            .get(&vault_id)
+            .ok_or_else(|| VaultError::InvalidMetadata(format!("no profile with id {vault_id}")))?;
+        if store.active != vault_id {
+            return Err(VaultError::InvalidMetadata(format!(
+                "profile {vault_id} is not active; switch to it and unlock it first"
+            )));
+        }
+        profile.name.clone()
+    };
+    let target = absolute(&path)?;
+    let guard = master_seed.0.lock().unwrap();
+    let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
+    let (identity_pk, identity_sk) = attestation::vault_identity(seed);
+
+    let keys: Vec<PublicBundleKey> = keystore
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|k| k.metadata.trashed_at.is_none())
+        .map(|k| PublicBundleKey::of(k, ADDRESS_NETWORK))
+        .collect();
+    let bundle = PublicBundle {
+        version: PUBLIC_BUNDLE_VERSION,
+        bundle_id: uuid::Uuid::new_v4().to_string(),
+        source_name,
+        exported_at: Utc::now(),
+        vault_public_key_hex: identity_pk.to_hex(),
+        keys,
+    };
+    let instance = instance_key(&app, seed)?;
+    let signed = public_bundle::sign_bundle(&identity_sk, Some(&instance), &bundle)?;
+    atomic_write(target, &serde_json::to_vec_pretty(&signed)?)?;
+
+    let fingerprint = KeyFingerprint::of(identity_pk.as_bytes());
+    tracing::info!(
+        target: "audit",
+        bundle_id = %bundle.bundle_id,
+        keys = bundle.keys.len(),
+        path = %path,
+        "public bundle exported"
+    );
+    Ok(PublicBundleSummary {
+        bundle_id: bundle.bundle_id,
+        keys: bundle.keys.len(),
+        fingerprint,
+    })
+}
+
+/// Import another vault's public bundle from `path` as watch-only keys,
+/// after checking its signatures. With `expected_fingerprint`, as read out
+/// by the exporter in hex or word form, a bundle signed by any other vault
+/// is refused. A later bundle from the same vault replaces its earlier
+/// import. Requires an unlocked vault.
+#[tauri::command]
+pub fn import_public_bundle(
+    app: AppHandle,
+    path: String,
+    expected_fingerprint: Option<String>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<WatchOnlySourcePublic> {
+    let key = session_key(&session)?;
+    let data = std::fs::read(absolute(&path)?).map_err(|e| VaultError::Storage(e.to_string()))?;
+    let signed: SignedPublicBundle = serde_json::from_slice(&data)?;
+    let bundle = public_bundle::verify_bundle(&signed)?;
+
+    let own = {
+        let guard = master_seed.0.lock().unwrap();
+        let seed = guard.as_ref().ok_or(VaultError::NotInitialized)?;
+        attestation::vault_identity(seed).0.to_hex()
+    };
+    if own == bundle.vault_public_key_hex.to_lowercase() {
+        return Err(VaultError::InvalidMetadata(
+            "this bundle was exported from this vault".to_string(),
+        ));
+    }
+    let fingerprint = KeyFingerprint::of_hex(&bundle.vault_public_key_hex);
+    if let Some(expected) = expected_fingerprint {
+        if !fingerprint.matches(&expected) {
+            return Err(VaultError::InvalidMetadata(
+                "fingerprint does not match the bundle's signing vault".to_string(),
+            ));
+        }
+    }
+
+    let mut store = load_store(&app, &key)?;
+    let source = store
+        .import(bundle, Utc::now())
+        .map_err(VaultError::InvalidMetadata)?
+        .to_public();
+    save_store(&app, &key, &store)?;
+    tracing::info!(
+        target: "audit",
+        source_id = %source.source.id,
+        keys = source.source.keys.len(),
+        fingerprint = %fingerprint.hex,
+        "public bundle imported as watch-only"
+    );
+    Ok(source)
+}
+
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn list_watch_only(
+    app: AppHandle,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<WatchOnlySourcePublic>> {
+    Ok(load_store(&app, &session_key(&session)?)?
+        .sources
+        .iter()
+        .map(|s| s.to_public())
+        .collect())
+}
+
+/// Drop every watch-only key imported from one vault. Requires an unlocked
+/// vault.
+#[tauri::command]
+pub fn remove_watch_only(
+    app: AppHandle,
+    source_id: String,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    let key = session_key(&session)?;
+    let mut store = load_store(&app, &key)?;
+    let before = store.sources.len();
+    store.sources.retain(|s| s.id != source_id);
+    if store.sources.len() == before {
+        return Err(VaultError::KeyNotFound(source_id));
+    }
+    save_store(&app, &key, &store)?;
+    tracing::info!(target: "audit", source_id = %source_id, "watch-only source removed");
+    Ok(())
+}
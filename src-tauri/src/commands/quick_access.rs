@@ -0,0 +1,161 @@
+use crate::commands::items::{save_items, ItemStore};
+use crate::commands::keys::{save_keys, session_key, KeyStore, SessionKey};
+use crate::commands::lease::holds_write_lease;
+use crate::commands::vault::VaultMutex;
+use crate::error::{Result, VaultError};
+use crate::models::item::{VaultItem, VaultItemPublic};
+use crate::models::key::{KeyEntry, KeyEntryPublic};
+use crate::models::usage::UsageStats;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+/// How often, in seconds, usage counters are written back while the vault
+/// is unlocked, so a crash or power loss costs at most this much history.
+pub const USAGE_FLUSH_INTERVAL_SECS: u64 = 300;
+
+/// A dashboard quick-access entry: either a chain key or a vault item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum QuickAccessItem {
+    Key(KeyEntryPublic),
+    Item(VaultItemPublic),
+}
+
+impl QuickAccessItem {
+    fn usage(&self) -> &UsageStats {
+        match self {
+            QuickAccessItem::Key(k) => &k.metadata.usage,
+            QuickAccessItem::Item(i) => &i.usage,
+        }
+    }
+}
+
+/// Merge keys and items that are favorited or have been used, rank them
+/// (favorites first, then frecency) and keep the top `limit`. Pure, so the
+/// ranking is unit-testable without Tauri state.
+pub fn rank_quick_access(
+    keys: &[KeyEntry],
+    items: &[VaultItem],
+    limit: usize,
+    now: DateTime<Utc>,
+) -> Vec<QuickAccessItem> {
+    let mut ranked: Vec<QuickAccessItem> = keys
+        .iter()
+        .filter(|k| k.metadata.trashed_at.is_none() && k.metadata.usage.is_quick_access_candidate())
+        .map(|k| QuickAccessItem::Key(k.to_public()))
+        .chain(
+            items
+                .iter()
+                .filter(|i| i.trashed_at.is_none() && i.usage.is_quick_access_candidate())
+                .map(|i| QuickAccessItem::Item(i.to_public())),
+        )
+        .collect();
+    ranked.sort_by(|a, b| a.usage().rank_cmp(b.usage(), now));
+    ranked.truncate(limit);
+    ranked
+}
+
+/// The most-used and favorited keys and items across all types, for the
+/// dashboard. Listing is not counted as a use (it would bump every entry).
+#[tauri::command]
+pub fn get_quick_access_items(
+    limit: usize,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+) -> Result<Vec<QuickAccessItem>> {
+    let keys = keystore.0.lock().unwrap();
+    let items = items.0.lock().unwrap();
+    Ok(rank_quick_access(&keys, &items, limit, Utc::now()))
+}
+
+#[tauri::command]
+pub fn set_key_favorite(
+    app: AppHandle,
+    key_id: String,
+    favorite: bool,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    session: State<'_, SessionKey>,
+) -> Result<KeyEntryPublic> {
+    let session_key = session_key(&session)?;
+    let keys_file = vault.0.lock().unwrap().keys_file.clone();
+
+    let mut store = keystore.0.lock().unwrap();
+    let entry = store
+        .iter_mut()
+        .find(|k| k.id == key_id)
+        .ok_or(VaultError::KeyNotFound(key_id))?;
+    entry.metadata.usage.favorite = favorite;
+    let public = entry.to_public();
+    save_keys(&app, &keys_file, &session_key, &store)?;
+    Ok(public)
+}
+
+#[tauri::command]
+pub fn set_item_favorite(
+    app: AppHandle,
+    item_id: String,
+    favorite: bool,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<VaultItemPublic> {
+    let session_key = session_key(&session)?;
+    let items_file = vault.0.lock().unwrap().items_file.clone();
+
+    let mut store = items.0.lock().unwrap();
+    let item = store
+        .iter_mut()
+        .find(|i| i.id == item_id)
+        .ok_or(VaultError::KeyNotFound(item_id))?;
+    item.usage.favorite = favorite;
+    let public = item.to_public();
+    save_items(&app, &items_file, &session_key, &store)?;
+    Ok(public)
+}
+
+/// Every use counted so far, to tell whether anything was used since the
+/// last flush.
+fn total_uses(keys: &[KeyEntry], items: &[VaultItem]) -> u64 {
+    let keys = keys.iter().map(|k| k.metadata.usage.use_count);
+    let items = items.iter().map(|i| i.usage.use_count);
+    keys.chain(items).fold(0, u64::saturating_add)
+}
+
+/// Periodically write usage counters back to the keystore and item store.
+/// Uses are only counted in memory, and `lock_vault` is not the only way a
+/// session ends. Skips the write when nothing was used since the last one,
+/// and while another instance holds the write lease.
+pub fn spawn_usage_flush(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut flushed: Option<u64> = None;
+        loop {
+            std::thread::sleep(Duration::from_secs(USAGE_FLUSH_INTERVAL_SECS));
+            let vault = app.state::<VaultMutex>();
+            let vault = vault.0.lock().unwrap();
+            let Some(key) = app.state::<SessionKey>().0.lock().unwrap().clone() else {
+                flushed = None;
+                continue;
+            };
+            if !holds_write_lease(&app) {
+                continue;
+            }
+            let keystore = app.state::<KeyStore>();
+            let keys = keystore.0.lock().unwrap();
+            let items = app.state::<ItemStore>();
+            let items = items.0.lock().unwrap();
+            let total = total_uses(&keys, &items);
+            if flushed == Some(total) {
+                continue;
+            }
+            let saved = save_keys(&app, &vault.keys_file, &key, &keys)
+                .and_then(|_| save_items(&app, &vault.items_file, &key, &items));
+            match saved {
+                Ok(()) => flushed = Some(total),
+                Err(e) => tracing::warn!("could not write usage counters back: {e}"),
+            }
+        }
+    });
+}
@@ -0,0 +1,206 @@
+use crate::commands::keys::{data_dir, SessionKey};
+use crate::commands::password_policy::enforce_password_strength;
+use crate::commands::vault::{
+    derive_vault_enc_key, finish_rekey, load_vault_if_needed, persist_vault, record_unlock_failure,
+    rewrap_data_key, verify_password, verify_rekeyed, verify_session_key, UnlockState, VaultMutex,
+    VAULT_FILE,
+};
+use crate::crypto::encryption::Ciphertext;
+use crate::crypto::kdf;
+use crate::crypto::recovery::{self, RecoveryError};
+use crate::error::{Result, VaultError};
+use crate::models::recovery::{RecoveryCodesStatus, RecoveryReport, UnrecoverableData};
+use crate::models::vault::VaultState;
+use chrono::Utc;
+use tauri::{AppHandle, State};
+
+/// Everything sealed under the data key, which cannot be opened without the
+/// password when no escrow copy exists. Stores with their own file are
+/// found by reading the data directory, so new stores are listed without
+/// changes here.
+fn unrecoverable_data(app: &AppHandle, vault: &VaultState) -> Result<Vec<UnrecoverableData>> {
+    let dir = data_dir(app)?;
+    let mut data = Vec::new();
+    if dir.join(&vault.keys_file).exists() {
+        data.push(UnrecoverableData {
+            name: "keystore".to_string(),
+            file: Some(vault.keys_file.clone()),
+            note: Some(
+                "HD-derived keys can be generated again after restoring from the recovery \
+                 phrase; imported keys cannot"
+                    .to_string(),
+            ),
+        });
+    }
+    if dir.join(&vault.items_file).exists() {
+        data.push(UnrecoverableData {
+            name: "items".to_string(),
+            file: Some(vault.items_file.clone()),
+            note: None,
+        });
+    }
+    if !vault.master_seed_enc_hex.is_empty() {
+        data.push(UnrecoverableData {
+            name: "master seed".to_string(),
+            file: None,
+            note: Some("restore it from the recovery phrase".to_string()),
+        });
+    }
+    let mut sealed: Vec<String> = std::fs::read_dir(&dir)
+        .map_err(|e| VaultError::Storage(e.to_string()))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".json") && name != VAULT_FILE)
+        .filter(|name| {
+            std::fs::read(dir.join(name))
+                .is_ok_and(|data| serde_json::from_slice::<Ciphertext>(&data).is_ok())
+        })
+        .collect();
+    sealed.sort();
+    data.extend(sealed.into_iter().map(|name| UnrecoverableData {
+        name: name.clone(),
+        file: Some(name),
+        note: None,
+    }));
+    Ok(data)
+}
+
+/// Issue a new set of recovery codes, replacing any earlier set, and return
+/// them to show once. With `escrow`, the codes also carry a copy of the data
+/// key and can reset a forgotten password. Without it, a code can only
+/// report what a reset would lose. Escrow is the default unless a YubiKey is
+/// enrolled: an escrowed code opens the data key without the YubiKey, so
+/// then it has to be asked for. Requires the vault password and an unlocked
+/// vault.
+#[tauri::command]
+pub fn generate_recovery_codes(
+    app: AppHandle,
+    password: String,
+    escrow: Option<bool>,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<String>> {
+    let data_key = session
+        .0
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or(VaultError::NotInitialized)?;
+    verify_password(&app, &state, &password)?;
+    let mut vault = state.0.lock().unwrap();
+    let escrow = escrow.unwrap_or(!vault.yubikey_enabled);
+    let (set, codes) = recovery::issue(escrow.then_some(&*data_key), Utc::now())?;
+    let previous = vault.recovery_codes.replace(set);
+    if let Err(e) = persist_vault(&app, &vault) {
+        vault.recovery_codes = previous;
+        return Err(e);
+    }
+    tracing::warn!(
+        target: "audit",
+        codes = codes.len(),
+        escrow,
+        yubikey = vault.yubikey_enabled,
+        "recovery codes issued"
+    );
+    Ok(codes.iter().map(|c| c.to_string()).collect())
+}
+
+/// Works whether or not the vault is unlocked.
+#[tauri::command]
+pub fn recovery_codes_status(
+    app: AppHandle,
+    state: State<'_, VaultMutex>,
+) -> Result<RecoveryCodesStatus> {
+    let mut vault = state.0.lock().unwrap();
+    load_vault_if_needed(&app, &mut vault);
+    Ok(RecoveryCodesStatus::of(vault.recovery_codes.as_ref()))
+}
+
+/// Reset a forgotten password with a recovery code. When the code carries
+/// an escrow copy of the data key, the data key is wrapped under
+/// `new_password` with a fresh salt and checked the way
+/// `change_master_password` checks it, and the code is used up. Nothing is
+/// re-encrypted. With a YubiKey enrolled it must be present. Without an
+/// escrow copy, or without recovery codes at all, nothing is changed and
+/// the report lists the data that cannot be opened without the old
+/// password. Wrong codes count towards the unlock throttle. Works while the
+/// vault is locked.
+#[tauri::command]
+pub fn recover_account(
+    app: AppHandle,
+    code: String,
+    new_password: String,
+    state: State<'_, VaultMutex>,
+    throttle: State<'_, UnlockState>,
+) -> Result<RecoveryReport> {
+    let now = Utc::now();
+    let secs = now.timestamp() as u64;
+    throttle.0.lock().unwrap().check(secs)?;
+
+    let mut vault = state.0.lock().unwrap();
+    load_vault_if_needed(&app, &mut vault);
+    if !vault.initialized {
+        return Err(VaultError::NotInitialized);
+    }
+    let Some(set) = vault.recovery_codes.clone() else {
+        return Ok(RecoveryReport {
+            recovered: false,
+            codes_remaining: 0,
+            unrecoverable: unrecoverable_data(&app, &vault)?,
+        });
+    };
+    let opened = match recovery::open(&set, &code) {
+        Ok(opened) => opened,
+        Err(RecoveryError::UnknownCode) => {
+            record_unlock_failure(&app, &vault, &throttle, secs);
+            tracing::warn!(target: "audit", "account recovery refused: unknown code");
+            return Err(RecoveryError::UnknownCode.into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let Some(data_key) = opened.data_key else {
+        tracing::warn!(
+            target: "audit",
+            code_id = %opened.id,
+            "account recovery found no escrow copy"
+        );
+        return Ok(RecoveryReport {
+            recovered: false,
+            codes_remaining: set.remaining(),
+            unrecoverable: unrecoverable_data(&app, &vault)?,
+        });
+    };
+    throttle.0.lock().unwrap().record_success();
+    // An escrow copy of another data key would re-key the vault to one
+    // that opens none of its stores.
+    verify_session_key(&vault, &data_key)?;
+    enforce_password_strength(&vault, &new_password)?;
+
+    let mut next = vault.clone();
+    next.salt_hex = hex::encode(kdf::generate_salt());
+    let new_enc = derive_vault_enc_key(&next, &new_password)?;
+    if let Some(codes) = next.recovery_codes.as_mut() {
+        codes.consume(&opened.id, now);
+    }
+    let key_drive_dropped = rewrap_data_key(&mut next, &data_key, &new_enc)?;
+    verify_rekeyed(&app, &next, &new_enc, &data_key)?;
+    persist_vault(&app, &next)?;
+    *vault = next;
+    finish_rekey(&app, key_drive_dropped);
+
+    let codes_remaining = vault
+        .recovery_codes
+        .as_ref()
+        .map_or(0, |codes| codes.remaining());
+    tracing::warn!(
+        target: "audit",
+        code_id = %opened.id,
+        codes_remaining,
+        "vault password reset with a recovery code"
+    );
+    Ok(RecoveryReport {
+        recovered: true,
+        codes_remaining,
+        unrecoverable: Vec::new(),
+    })
+}
@@ -0,0 +1,152 @@
+use crate::commands::backup::{with_progress, Drives};
+use crate::commands::keys::{atomic_write, keys_file_path, SessionKey};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::vault::{persist_vault, verify_password, VaultMutex};
+use crate::drive::remote::{self, DirectoryStore};
+use crate::error::{Result, VaultError};
+use crate::models::rate_limit::SensitiveOp;
+use crate::models::remote::{RemoteCatalogEntry, RemoteTarget, RemoteTargetKind};
+use chrono::Utc;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+/// Configured remote targets live in plaintext metadata next to `vault.json`:
+/// names and mount paths only, no credentials (those belong to the mount).
+pub const REMOTE_FILE: &str = "remote_targets.json";
+pub const REMOTE_UPLOAD_PROGRESS_EVENT: &str = "remote_upload_progress";
+
+fn load_targets(app: &AppHandle) -> Result<Vec<RemoteTarget>> {
+    let path = keys_file_path(app, REMOTE_FILE)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+fn save_targets(app: &AppHandle, targets: &[RemoteTarget]) -> Result<()> {
+    let path = keys_file_path(app, REMOTE_FILE)?;
+    atomic_write(&path, &serde_json::to_vec_pretty(targets)?)
+}
+
+fn ensure_online_allowed(vault: &State<'_, VaultMutex>) -> Result<()> {
+    if vault.0.lock().unwrap().air_gap_mode {
+        return Err(VaultError::AirGap(
+            "remote backup targets are disabled in air-gap mode".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Turn air-gap mode on or off. Leaving it requires an unlocked vault.
+#[tauri::command]
+pub fn set_air_gap_mode(
+    app: AppHandle,
+    enabled: bool,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    let mut vault = vault.0.lock().unwrap();
+    let mut next = vault.clone();
+    next.air_gap_mode = enabled;
+    persist_vault(&app, &next)?;
+    *vault = next;
+    Ok(())
+}
+
+/// Register a directory where an SFTP, S3-compatible or WebDAV remote is
+/// mounted. Needs the unlocked vault and its password: a target decides
+/// where backups leave the machine.
+#[tauri::command]
+pub fn remote_add_target(
+    app: AppHandle,
+    name: String,
+    path: String,
+    password: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<RemoteTarget> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    ensure_online_allowed(&vault)?;
+    verify_password(&app, &vault, &password)?;
+    if !PathBuf::from(&path).is_dir() {
+        return Err(VaultError::Storage(format!("{path} is not a directory")));
+    }
+    let mut targets = load_targets(&app)?;
+    let target = RemoteTarget {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        target: RemoteTargetKind::MountedDirectory { path },
+        created_at: Utc::now(),
+    };
+    targets.push(target.clone());
+    save_targets(&app, &targets)?;
+    Ok(target)
+}
+
+#[tauri::command]
+pub fn remote_list_targets(app: AppHandle) -> Result<Vec<RemoteTarget>> {
+    load_targets(&app)
+}
+
+#[tauri::command]
+pub fn remote_remove_target(
+    app: AppHandle,
+    target_id: String,
+    vault: State<'_, VaultMutex>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<()> {
+    enforce(&vault, &limiter, SensitiveOp::Delete)?;
+    let mut targets = load_targets(&app)?;
+    let before = targets.len();
+    targets.retain(|t| t.id != target_id);
+    if targets.len() == before {
+        return Err(VaultError::KeyNotFound(target_id));
+    }
+    save_targets(&app, &targets)
+}
+
+/// Upload a file-encrypted backup from a drive to a remote target, resuming
+/// any earlier interrupted upload. Container backups are refused, since their
+/// files are protected only by the drive. Needs the unlocked vault. Emits
+/// `remote_upload_progress` events.
+#[tauri::command(async)]
+pub fn remote_upload_backup(
+    app: AppHandle,
+    drive_id: String,
+    backup_id: String,
+    target_id: String,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    drives: State<'_, Drives>,
+) -> Result<RemoteCatalogEntry> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    ensure_online_allowed(&vault)?;
+    let target = load_targets(&app)?
+        .into_iter()
+        .find(|t| t.id == target_id)
+        .ok_or_else(|| VaultError::KeyNotFound(target_id.clone()))?;
+    let store = match target.target {
+        RemoteTargetKind::MountedDirectory { path } => DirectoryStore {
+            root: PathBuf::from(path),
+        },
+    };
+
+    with_progress(&app, REMOTE_UPLOAD_PROGRESS_EVENT, |tx| {
+        Ok(remote::upload_backup(
+            drives.0.as_ref(),
+            &drive_id,
+            &backup_id,
+            &store,
+            Utc::now(),
+            Some(tx),
+        )?)
+    })
+}
@@ -0,0 +1,271 @@
+use crate::commands::vault::{load_vault_if_needed, VaultMutex};
+use crate::crypto::encryption::Ciphertext;
+use crate::error::{Result, VaultError};
+use crate::models::selftest::{CheckStatus, SelfTestCheck, SelfTestCheckId, SelfTestReport};
+use crate::models::vault::VaultState;
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+/// The data directory as it is, without the permission fix-up `data_dir`
+/// applies, so the check sees what is actually on disk.
+fn raw_data_dir(app: &AppHandle) -> Result<PathBuf> {
+    app.path()
+        .app_local_data_dir()
+        .map_err(|e| VaultError::Storage(e.to_string()))
+}
+
+fn check_store_encryption(dir: &Path, vault: &VaultState) -> SelfTestCheck {
+    let id = SelfTestCheckId::StoreEncryption;
+    let mut plaintext = Vec::new();
+    for name in [&vault.keys_file, &vault.items_file] {
+        let Ok(data) = std::fs::read(dir.join(name)) else {
+            continue;
+        };
+        if serde_json::from_slice::<Ciphertext>(&data).is_err() {
+            plaintext.push(name.as_str());
+        }
+    }
+    if !plaintext.is_empty() {
+        return SelfTestCheck::problem(
+            id,
+            CheckStatus::Fail,
+            format!(
+                "not in the encrypted store format: {}",
+                plaintext.join(", ")
+            ),
+            "Restore the vault from a verified backup or its recovery phrase",
+        );
+    }
+    if vault.verifier_hash_hex.is_empty() {
+        return SelfTestCheck::problem(
+            id,
+            CheckStatus::Fail,
+            "vault metadata has no password verifier",
+            "Restore the vault from a verified backup or its recovery phrase",
+        );
+    }
+    SelfTestCheck::pass(id, "keystore and item store are AES-256-GCM encrypted")
+}
+
+fn check_kdf(vault: &VaultState) -> SelfTestCheck {
+    let id = SelfTestCheckId::KdfParameters;
+    let profile = vault.kdf_profile();
+    let detail = format!(
+        "Argon2id v{} with {} KiB, {} iterations, {} lanes",
+        profile.version, profile.memory_kib, profile.iterations, profile.parallelism
+    );
+    if vault.needs_kdf_upgrade() {
+        SelfTestCheck::problem(
+            id,
+            CheckStatus::Warn,
+            detail,
+            "Run migrate_encryption to re-key the vault onto the current profile",
+        )
+    } else {
+        SelfTestCheck::pass(id, detail)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn check_swap() -> SelfTestCheck {
+    use crate::models::selftest::{is_protected_swap, parse_swaps};
+    let id = SelfTestCheckId::Swap;
+    let Ok(swaps) = std::fs::read_to_string("/proc/swaps") else {
+        return SelfTestCheck::skipped(id, "/proc/swaps is not readable");
+    };
+    let exposed: Vec<String> = parse_swaps(&swaps)
+        .into_iter()
+        .filter(|d| !is_protected_swap(d))
+        .collect();
+    if exposed.is_empty() {
+        SelfTestCheck::pass(id, "no unencrypted swap is active")
+    } else {
+        SelfTestCheck::problem(
+            id,
+            CheckStatus::Warn,
+            format!("unencrypted swap is active: {}", exposed.join(", ")),
+            "Encrypt swap with dm-crypt, switch to zram, or disable swap",
+        )
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn check_swap() -> SelfTestCheck {
+    SelfTestCheck::skipped(SelfTestCheckId::Swap, "not checked on this platform")
+}
+
+#[cfg(target_os = "linux")]
+fn check_core_dumps() -> SelfTestCheck {
+    use crate::models::selftest::core_limit;
+    let id = SelfTestCheckId::CoreDumps;
+    let limit = std::fs::read_to_string("/proc/self/limits")
+        .ok()
+        .and_then(|l| core_limit(&l));
+    match limit {
+        None => SelfTestCheck::skipped(id, "/proc/self/limits is not readable"),
+        Some(Some(0)) => SelfTestCheck::pass(id, "core dumps are disabled"),
+        Some(_) => SelfTestCheck::problem(
+            id,
+            CheckStatus::Warn,
+            "a crash may write a core dump holding unlocked secrets",
+            "Set `ulimit -c 0` for the session or disable core dumps system-wide",
+        ),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn check_core_dumps() -> SelfTestCheck {
+    SelfTestCheck::skipped(SelfTestCheckId::CoreDumps, "not checked on this platform")
+}
+
+#[cfg(unix)]
+fn check_data_dir(dir: &Path) -> SelfTestCheck {
+    use std::os::unix::fs::PermissionsExt;
+    let id = SelfTestCheckId::DataDirPermissions;
+    let Ok(meta) = std::fs::metadata(dir) else {
+        return SelfTestCheck::skipped(id, "the data directory does not exist yet");
+    };
+    let mut open: Vec<String> = Vec::new();
+    if meta.permissions().mode() & 0o077 != 0 {
+        open.push(dir.display().to_string());
+    }
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            if entry
+                .metadata()
+                .is_ok_and(|m| m.permissions().mode() & 0o077 != 0)
+            {
+                open.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+    }
+    if open.is_empty() {
+        SelfTestCheck::pass(id, "the data directory is owner-only")
+    } else {
+        SelfTestCheck::problem(
+            id,
+            CheckStatus::Fail,
+            format!("readable by other users: {}", open.join(", ")),
+            format!("Run `chmod -R go-rwx {}`", dir.display()),
+        )
+    }
+}
+
+#[cfg(not(unix))]
+fn check_data_dir(_dir: &Path) -> SelfTestCheck {
+    SelfTestCheck::skipped(
+        SelfTestCheckId::DataDirPermissions,
+        "relies on the per-user profile ACLs on this platform",
+    )
+}
+
+fn check_formats(dir: &Path, vault: &VaultState) -> SelfTestCheck {
+    let id = SelfTestCheckId::OutdatedFormats;
+    if vault.master_seed_enc_hex.is_empty() {
+        return SelfTestCheck::problem(
+            id,
+            CheckStatus::Warn,
+            "legacy password-only vault without an HD master seed",
+            "Create a new vault so keys can be restored from a recovery phrase",
+        );
+    }
+    if vault.data_key.is_none() {
+        return SelfTestCheck::problem(
+            id,
+            CheckStatus::Warn,
+            "vault encrypted directly under its password key, without a data key",
+            "Unlock the vault with its password to move it onto a data key",
+        );
+    }
+    let retired: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .filter(|n| vault.is_retired_store_file(n))
+                .collect()
+        })
+        .unwrap_or_default();
+    if retired.is_empty() {
+        SelfTestCheck::pass(id, "no outdated vault files found")
+    } else {
+        SelfTestCheck::problem(
+            id,
+            CheckStatus::Warn,
+            format!("retired store files remain: {}", retired.join(", ")),
+            "Run compact_storage to wipe them",
+        )
+    }
+}
+
+/// Check the vault and its environment. Pure apart from reading the data
+/// directory and, on Linux, `/proc`.
+pub fn run_selftest(dir: &Path, vault: &VaultState) -> SelfTestReport {
+    let mut checks = Vec::with_capacity(6);
+    if vault.initialized {
+        checks.push(check_store_encryption(dir, vault));
+        checks.push(check_kdf(vault));
+    } else {
+        checks.push(SelfTestCheck::skipped(
+            SelfTestCheckId::StoreEncryption,
+            "no vault yet",
+        ));
+        checks.push(SelfTestCheck::skipped(
+            SelfTestCheckId::KdfParameters,
+            "no vault yet",
+        ));
+    }
+    checks.push(check_swap());
+    checks.push(check_core_dumps());
+    checks.push(check_data_dir(dir));
+    if vault.initialized {
+        checks.push(check_formats(dir, vault));
+    } else {
+        checks.push(SelfTestCheck::skipped(
+            SelfTestCheckId::OutdatedFormats,
+            "no vault yet",
+        ));
+    }
+    SelfTestReport::new(checks, Utc::now())
+}
+
+/// Score the vault's at-rest protection and the machine's hardening, with a
+/// remediation hint for every problem found. Never touches secret material.
+#[tauri::command]
+pub fn run_security_selftest(
+    app: AppHandle,
+    state: State<'_, VaultMutex>,
+) -> Result<SelfTestReport> {
+    let dir = raw_data_dir(&app)?;
+    let mut vault = state.0.lock().unwrap();
+    load_vault_if_needed(&app, &mut vault);
+    Ok(run_selftest(&dir, &vault))
+}
+
+/// Run the self-test once at startup and log every problem it finds.
+pub fn spawn_startup_selftest(app: AppHandle) {
+    std::thread::spawn(move || {
+        let Ok(dir) = raw_data_dir(&app) else {
+            return;
+        };
+        let report = {
+            let state = app.state::<VaultMutex>();
+            let mut vault = state.0.lock().unwrap();
+            load_vault_if_needed(&app, &mut vault);
+            run_selftest(&dir, &vault)
+        };
+        for check in &report.checks {
+            if matches!(check.status, CheckStatus::Warn | CheckStatus::Fail) {
+                tracing::warn!(
+                    check = ?check.id,
+                    status = ?check.status,
+                    detail = %check.detail,
+                    remediation = check.remediation.as_deref().unwrap_or(""),
+                    "security self-test problem"
+                );
+            }
+        }
+        tracing::info!(score = report.score, "security self-test finished");
+    });
+}
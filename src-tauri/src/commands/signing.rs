@@ -1,9 +1,15 @@
-use crate::commands::keys::{secret_hex_for, KeyStore};
+use crate::commands::keys::{secret_hex_for, KeyStore, SessionKey};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::notifications::note_decrypt;
+use crate::commands::spending::enforce_spending;
+use crate::commands::tx_review::confirm_reviewed;
+use crate::commands::vault::VaultMutex;
 use crate::crypto::hybrid_signing::{HybridSignature, HybridSigner};
 use crate::crypto::mldsa87;
 use crate::error::{Result, VaultError};
+use crate::models::rate_limit::SensitiveOp;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, State};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SignRequest {
@@ -29,16 +35,29 @@ pub fn sign_message(request: SignRequest) -> Result<String> {
 
 /// Sign a message with a stored key, resolving the secret key server-side from
 /// the in-memory keystore. The secret never crosses the IPC boundary.
+/// `summary_hash_hex` is the hash `review_transaction` returned for the same
+/// message.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn sign_message_with_key(
+    app: AppHandle,
     key_id: String,
     message_hex: String,
+    summary_hash_hex: String,
     keystore: State<'_, KeyStore>,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    limiter: State<'_, RateLimiter>,
 ) -> Result<String> {
+    enforce(&vault, &limiter, SensitiveOp::Decrypt)?;
+    let message = hex::decode(&message_hex).map_err(|e| VaultError::Storage(e.to_string()))?;
+    let summary = confirm_reviewed(&app, &session, None, &message, &summary_hash_hex)?;
+    enforce_spending(&app, &session, &key_id, &summary)?;
+    let decrypt = note_decrypt(&app, &vault, &key_id);
     let secret_hex = secret_hex_for(&keystore, &key_id)?;
     let sk = mldsa87::SecretKey::from_hex(&secret_hex)?;
-    let message = hex::decode(&message_hex).map_err(|e| VaultError::Storage(e.to_string()))?;
     let sig = mldsa87::sign(&sk, &message)?;
+    decrypt.finish(&app)?;
     Ok(sig.to_hex())
 }
 
@@ -97,18 +116,30 @@ impl HybridSignatureHex {
 /// Produce a hybrid signature (post-quantum ML-DSA-87 + classical Ed25519) for a
 /// stored key. The Ed25519 key is deterministically derived from the same HD
 /// seed, so a break in either algorithm alone cannot forge the signature. The
-/// secret never crosses the IPC boundary.
+/// secret never crosses the IPC boundary. Confirmed like
+/// [`sign_message_with_key`].
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn sign_message_hybrid_with_key(
+    app: AppHandle,
     key_id: String,
     message_hex: String,
+    summary_hash_hex: String,
     keystore: State<'_, KeyStore>,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    limiter: State<'_, RateLimiter>,
 ) -> Result<HybridSignatureHex> {
+    enforce(&vault, &limiter, SensitiveOp::Decrypt)?;
+    let message = hex::decode(&message_hex).map_err(|e| VaultError::Storage(e.to_string()))?;
+    let summary = confirm_reviewed(&app, &session, None, &message, &summary_hash_hex)?;
+    enforce_spending(&app, &session, &key_id, &summary)?;
+    let decrypt = note_decrypt(&app, &vault, &key_id);
     let secret_hex = secret_hex_for(&keystore, &key_id)?;
     let sk = mldsa87::SecretKey::from_hex(&secret_hex)?;
-    let message = hex::decode(&message_hex).map_err(|e| VaultError::Storage(e.to_string()))?;
     let signer = HybridSigner::from_secret(&sk)?;
     let sig = signer.sign(&message)?;
+    decrypt.finish(&app)?;
     Ok(HybridSignatureHex::from_signature(&sig))
 }
 
@@ -0,0 +1,375 @@
+use crate::commands::contacts::load_contacts;
+use crate::commands::keys::{
+    atomic_write, load_sealed, save_sealed, secret_hex_for, session_key, KeyStore, MasterSeed,
+    SessionKey,
+};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::notifications::note_decrypt;
+use crate::commands::spending::enforce_spending;
+use crate::commands::tx_review::confirm_reviewed;
+use crate::commands::vault::VaultMutex;
+use crate::crypto::{contact, mldsa87, signing_request};
+use crate::error::{Result, VaultError};
+use crate::models::rate_limit::SensitiveOp;
+use crate::models::signing_request::{
+    ExportedEnvelope, OpenedSigningRequest, OpenedSigningResponse, ReceivedSigningRequest,
+    SentSigningRequest, SigningEnvelope, SigningPayloadKind, SigningRequestBody,
+    SigningRequestLedger, SigningResponseBody,
+};
+use chrono::Utc;
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
+
+/// Signing requests sent and received, encrypted with the session key next
+/// to `vault.json`.
+pub const SIGNING_REQUESTS_FILE: &str = "signing_requests.json";
+
+fn unlocked(
+    session: &State<'_, SessionKey>,
+    master_seed: &State<'_, MasterSeed>,
+) -> Result<(Zeroizing<[u8; 32]>, Zeroizing<[u8; 64]>)> {
+    let key = session_key(session)?;
+    let seed = {
+        let guard = master_seed.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    Ok((key, seed))
+}
+
+fn load_ledger(app: &AppHandle, key: &[u8; 32]) -> Result<SigningRequestLedger> {
+    load_sealed(app, SIGNING_REQUESTS_FILE, key)
+}
+
+fn save_ledger(app: &AppHandle, key: &[u8; 32], ledger: &SigningRequestLedger) -> Result<()> {
+    save_sealed(app, SIGNING_REQUESTS_FILE, key, ledger)
+}
+
+/// Serialize `envelope`, writing it to `path` when one is given.
+fn export(envelope: &SigningEnvelope, path: Option<String>) -> Result<ExportedEnvelope> {
+    let envelope_json = serde_json::to_string(envelope)?;
+    if let Some(path) = &path {
+        atomic_write(std::path::Path::new(path), envelope_json.as_bytes())?;
+    }
+    Ok(ExportedEnvelope {
+        envelope_id: envelope.envelope_id.clone(),
+        envelope_json,
+        path,
+    })
+}
+
+/// Refuse an envelope this vault has accepted before.
+fn check_fresh(ledger: &SigningRequestLedger, envelope: &SigningEnvelope) -> Result<()> {
+    if ledger.seen_envelopes.contains(&envelope.envelope_id) {
+        return Err(VaultError::InvalidMetadata(
+            "this envelope has already been imported".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn to_opened(app: &AppHandle, request: &ReceivedSigningRequest) -> Result<OpenedSigningRequest> {
+    let fingerprint = contact::fingerprint(&request.sender)?;
+    let known = load_contacts(app)?
+        .into_iter()
+        .find(|c| contact::fingerprint(&c.card).is_ok_and(|f| f.hex == fingerprint.hex));
+    Ok(OpenedSigningRequest {
+        envelope_id: request.envelope_id.clone(),
+        sender_name: request.sender.name.clone(),
+        sender_fingerprint: fingerprint,
+        sender_contact_id: known.as_ref().map(|c| c.id.clone()),
+        sender_verified: known.is_some_and(|c| c.verified),
+        body: request.body.clone(),
+        expires_at: request.expires_at,
+        answered: request.answered_at.is_some(),
+    })
+}
+
+/// Seal a request to sign `payload_hex` to an imported, verified contact
+/// (usually this vault's own air-gapped instance). `sender_name` goes on the
+/// card embedded for the reply. Writes the envelope to `path` if given and
+/// returns its JSON for display as a QR code. Requires an unlocked vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn signing_request_export(
+    app: AppHandle,
+    recipient_contact_id: String,
+    sender_name: String,
+    kind: SigningPayloadKind,
+    payload_hex: String,
+    description: String,
+    key_id: Option<String>,
+    ttl_secs: u64,
+    path: Option<String>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<ExportedEnvelope> {
+    let (key, seed) = unlocked(&session, &master_seed)?;
+    let recipient = load_contacts(&app)?
+        .into_iter()
+        .find(|c| c.id == recipient_contact_id)
+        .ok_or_else(|| VaultError::KeyNotFound(recipient_contact_id.clone()))?;
+    if !recipient.verified {
+        return Err(VaultError::InvalidMetadata(
+            "verify the contact's fingerprint before sending it signing requests".to_string(),
+        ));
+    }
+    let now = Utc::now().timestamp() as u64;
+    let sender = contact::issue_card(&seed, &sender_name, now)?;
+    let body = SigningRequestBody {
+        kind,
+        payload_hex,
+        description,
+        key_id,
+    };
+    let envelope =
+        signing_request::seal_request(&seed, &sender, &recipient.card, &body, now, ttl_secs)?;
+
+    let mut ledger = load_ledger(&app, &key)?;
+    ledger.sent.push(SentSigningRequest {
+        envelope_id: envelope.envelope_id.clone(),
+        recipient_fingerprint: envelope.recipient_fingerprint.clone(),
+        kind,
+        created_at: envelope.created_at,
+        expires_at: envelope.expires_at,
+        answered_at: None,
+    });
+    save_ledger(&app, &key, &ledger)?;
+    tracing::info!(
+        target: "audit",
+        envelope_id = %envelope.envelope_id,
+        contact_id = %recipient_contact_id,
+        kind = kind.as_str(),
+        "signing request exported"
+    );
+    export(&envelope, path)
+}
+
+/// Verify and decrypt a signing request addressed to this vault and keep it
+/// for review. Each envelope is accepted once. Requires an unlocked vault.
+#[tauri::command]
+pub fn signing_request_import(
+    app: AppHandle,
+    envelope_json: String,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<OpenedSigningRequest> {
+    let (key, seed) = unlocked(&session, &master_seed)?;
+    let envelope: SigningEnvelope = serde_json::from_str(&envelope_json)?;
+    let mut ledger = load_ledger(&app, &key)?;
+    check_fresh(&ledger, &envelope)?;
+    let now = Utc::now().timestamp() as u64;
+    let body = signing_request::open_request(&seed, &envelope, now)?;
+
+    let received = ReceivedSigningRequest {
+        envelope_id: envelope.envelope_id.clone(),
+        sender: envelope.sender.clone(),
+        body,
+        received_at: now,
+        expires_at: envelope.expires_at,
+        answered_at: None,
+    };
+    ledger.seen_envelopes.push(envelope.envelope_id.clone());
+    ledger.received.push(received.clone());
+    save_ledger(&app, &key, &ledger)?;
+    tracing::info!(
+        target: "audit",
+        envelope_id = %envelope.envelope_id,
+        sender = %contact::fingerprint(&envelope.sender)?.hex,
+        kind = received.body.kind.as_str(),
+        "signing request imported"
+    );
+    to_opened(&app, &received)
+}
+
+/// Received signing requests, newest first. Requires an unlocked vault.
+#[tauri::command]
+pub fn signing_request_list(
+    app: AppHandle,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<Vec<OpenedSigningRequest>> {
+    let (key, _) = unlocked(&session, &master_seed)?;
+    let ledger = load_ledger(&app, &key)?;
+    ledger
+        .received
+        .iter()
+        .rev()
+        .map(|r| to_opened(&app, r))
+        .collect()
+}
+
+/// Answer a received request and seal the response back to its sender.
+/// With `approve` and `key_id`, the payload is signed with that stored key
+/// once `summary_hash_hex` confirms it was reviewed with
+/// `review_transaction`; with `approve` and `result_hex`, a signature made
+/// elsewhere is returned as is. A request is answered once. Requires an unlocked vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn signing_request_respond(
+    app: AppHandle,
+    envelope_id: String,
+    sender_name: String,
+    approve: bool,
+    key_id: Option<String>,
+    summary_hash_hex: Option<String>,
+    result_hex: Option<String>,
+    reason: Option<String>,
+    path: Option<String>,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<ExportedEnvelope> {
+    let (key, seed) = unlocked(&session, &master_seed)?;
+    let mut ledger = load_ledger(&app, &key)?;
+    let now = Utc::now().timestamp() as u64;
+    let request = ledger
+        .received
+        .iter()
+        .find(|r| r.envelope_id == envelope_id)
+        .cloned()
+        .ok_or_else(|| VaultError::KeyNotFound(envelope_id.clone()))?;
+    if request.answered_at.is_some() {
+        return Err(VaultError::InvalidMetadata(
+            "this request has already been answered".to_string(),
+        ));
+    }
+
+    let body = match (approve, key_id, result_hex) {
+        (false, _, _) => SigningResponseBody {
+            approved: false,
+            reason,
+            public_key_hex: None,
+            result_hex: None,
+        },
+        (true, Some(key_id), None) => {
+            enforce(&vault, &limiter, SensitiveOp::Decrypt)?;
+            let payload = hex::decode(&request.body.payload_hex)
+                .map_err(|e| VaultError::InvalidMetadata(e.to_string()))?;
+            let summary_hash_hex = summary_hash_hex.ok_or_else(|| {
+                VaultError::InvalidMetadata(
+                    "review the payload and pass its summary_hash_hex to sign it".to_string(),
+                )
+            })?;
+            let summary = confirm_reviewed(
+                &app,
+                &session,
+                Some(request.body.kind),
+                &payload,
+                &summary_hash_hex,
+            )?;
+            enforce_spending(&app, &session, &key_id, &summary)?;
+            let decrypt = note_decrypt(&app, &vault, &key_id);
+            let secret_hex = secret_hex_for(&keystore, &key_id)?;
+            let sk = mldsa87::SecretKey::from_hex(&secret_hex)?;
+            let signature = mldsa87::sign(&sk, &payload)?.to_hex();
+            decrypt.finish(&app)?;
+            let public_key_hex = keystore
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|k| k.id == key_id)
+                .map(|k| k.public_key_hex.clone());
+            SigningResponseBody {
+                approved: true,
+                reason,
+                public_key_hex,
+                result_hex: Some(signature),
+            }
+        }
+        (true, None, Some(result_hex)) => {
+            hex::decode(&result_hex).map_err(|e| VaultError::InvalidMetadata(e.to_string()))?;
+            SigningResponseBody {
+                approved: true,
+                reason,
+                public_key_hex: None,
+                result_hex: Some(result_hex),
+            }
+        }
+        (true, _, _) => {
+            return Err(VaultError::InvalidMetadata(
+                "an approval needs exactly one of key_id or result_hex".to_string(),
+            ))
+        }
+    };
+
+    let sender = contact::issue_card(&seed, &sender_name, now)?;
+    let envelope = signing_request::seal_response(
+        &seed,
+        &sender,
+        &request.sender,
+        &request.envelope_id,
+        request.expires_at,
+        &body,
+        now,
+    )?;
+    if let Some(r) = ledger
+        .received
+        .iter_mut()
+        .find(|r| r.envelope_id == envelope_id)
+    {
+        r.answered_at = Some(now);
+    }
+    save_ledger(&app, &key, &ledger)?;
+    tracing::info!(
+        target: "audit",
+        envelope_id = %envelope_id,
+        approved = body.approved,
+        "signing request answered"
+    );
+    export(&envelope, path)
+}
+
+/// Import the response to a request this vault sent. It must come from the
+/// vault the request was addressed to, and each request takes one response.
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn signing_response_import(
+    app: AppHandle,
+    envelope_json: String,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<OpenedSigningResponse> {
+    let (key, seed) = unlocked(&session, &master_seed)?;
+    let envelope: SigningEnvelope = serde_json::from_str(&envelope_json)?;
+    let mut ledger = load_ledger(&app, &key)?;
+    check_fresh(&ledger, &envelope)?;
+    let now = Utc::now().timestamp() as u64;
+    let body = signing_request::open_response(&seed, &envelope, now)?;
+
+    let request_id = envelope.in_reply_to.clone().unwrap_or_default();
+    let sender_fingerprint = contact::fingerprint(&envelope.sender)?;
+    let sent = ledger
+        .sent
+        .iter_mut()
+        .find(|s| s.envelope_id == request_id)
+        .ok_or_else(|| VaultError::KeyNotFound(request_id.clone()))?;
+    if sent.recipient_fingerprint != sender_fingerprint.hex {
+        return Err(VaultError::InvalidMetadata(
+            "response comes from a vault the request was not sent to".to_string(),
+        ));
+    }
+    if sent.answered_at.is_some() {
+        return Err(VaultError::InvalidMetadata(
+            "this request has already been answered".to_string(),
+        ));
+    }
+    sent.answered_at = Some(now);
+    ledger.seen_envelopes.push(envelope.envelope_id.clone());
+    save_ledger(&app, &key, &ledger)?;
+    tracing::info!(
+        target: "audit",
+        envelope_id = %envelope.envelope_id,
+        request_id = %request_id,
+        approved = body.approved,
+        "signing response imported"
+    );
+    Ok(OpenedSigningResponse {
+        request_id,
+        sender_name: envelope.sender.name.clone(),
+        sender_fingerprint,
+        body,
+    })
+}
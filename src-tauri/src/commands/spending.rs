@@ -0,0 +1,399 @@
+use crate::commands::frost::load_store as frost_store;
+use crate::commands::keys::{load_sealed, save_sealed, session_key, KeyStore, SessionKey};
+use crate::commands::password_policy::enforce_password_strength;
+use crate::commands::price::fiat_estimate;
+use crate::commands::vault::{verify_password, VaultMutex};
+use crate::crypto::spending::{self, SpendingError};
+use crate::error::{Result, VaultError};
+use crate::models::musig2::is_output_key;
+use crate::models::spending::{
+    ApprovalStatus, ApproverAuth, ApproverInfo, SpendApproval, SpendApprovalView, SpendRecord,
+    SpendingPolicy, SpendingStore,
+};
+use crate::models::tx_review::TxSummary;
+use chrono::{DateTime, Duration, Utc};
+use tauri::{AppHandle, State};
+
+/// Spending policies, approvers and the approvals table, encrypted with the
+/// session key next to `vault.json`.
+pub const SPENDING_FILE: &str = "spending.json";
+
+pub(crate) fn load_store(app: &AppHandle, key: &[u8; 32]) -> Result<SpendingStore> {
+    load_sealed(app, SPENDING_FILE, key)
+}
+
+/// Spends older than a day no longer count against any limit and are
+/// dropped; the approvals table is kept as the record of co-approvals.
+pub(crate) fn save_store(app: &AppHandle, key: &[u8; 32], store: &mut SpendingStore) -> Result<()> {
+    let cutoff = Utc::now() - Duration::hours(24);
+    store.history.retain(|r| r.signed_at > cutoff);
+    save_sealed(app, SPENDING_FILE, key, store)
+}
+
+fn view(approval: &SpendApproval, now: DateTime<Utc>) -> SpendApprovalView {
+    SpendApprovalView {
+        approval: approval.clone(),
+        status: approval.status(now),
+        expires_at: approval.expires_at(),
+    }
+}
+
+/// Check an approver's passphrase. Returns their name for the audit log.
+fn check_approver(store: &SpendingStore, auth: &ApproverAuth) -> Result<String> {
+    let approver = store
+        .approvers
+        .iter()
+        .find(|a| a.id == auth.approver_id)
+        .ok_or_else(|| VaultError::KeyNotFound(auth.approver_id.clone()))?;
+    spending::verify_approver(approver, &auth.passphrase).inspect_err(|_| {
+        tracing::warn!(
+            target: "audit",
+            approver_id = %approver.id,
+            "approver passphrase rejected"
+        );
+    })?;
+    Ok(approver.name.clone())
+}
+
+/// Once any approver is enrolled, changing policies or approvers needs one
+/// of them as well as the vault password, so the password alone cannot lift
+/// a limit.
+fn authorize_change(
+    app: &AppHandle,
+    vault: &State<'_, VaultMutex>,
+    password: &str,
+    store: &SpendingStore,
+    approver: Option<&ApproverAuth>,
+) -> Result<Option<String>> {
+    verify_password(app, vault, password)?;
+    if store.approvers.is_empty() {
+        return Ok(None);
+    }
+    let auth = approver.ok_or_else(|| {
+        VaultError::PolicyDenied("an approver must authorize this change".to_string())
+    })?;
+    check_approver(store, auth).map(Some)
+}
+
+/// Gate for signing commands: check a reviewed transaction against the
+/// spending policy of `key_id`: a stored key's id, a FROST group id, or a
+/// MuSig2 output key. Over the limits, the signature goes ahead only on
+/// an unused co-approval of this exact transaction; otherwise one is
+/// requested and the signature refused with its id.
+pub(crate) fn enforce_spending(
+    app: &AppHandle,
+    session: &State<'_, SessionKey>,
+    key_id: &str,
+    summary: &TxSummary,
+) -> Result<()> {
+    let key = session_key(session)?;
+    let mut store = load_store(app, &key)?;
+    let Some(policy) = store.policies.iter().find(|p| p.key_id == key_id) else {
+        return Ok(());
+    };
+    let now = Utc::now();
+    let outflow = spending::outflow(summary);
+    let reasons = spending::evaluate(policy, &outflow, &store.history, now)?;
+    let amount = outflow.amount.map(|a| a.to_string());
+    let asset = policy.asset.clone();
+
+    if !reasons.is_empty() {
+        let existing = store.approvals.iter_mut().find(|a| {
+            a.key_id == key_id
+                && a.summary_hash_hex == summary.summary_hash_hex
+                && matches!(
+                    a.status(now),
+                    ApprovalStatus::Pending | ApprovalStatus::Approved
+                )
+        });
+        match existing {
+            Some(approval) if approval.status(now) == ApprovalStatus::Approved => {
+                approval.used_at = Some(now);
+                tracing::warn!(
+                    target: "audit",
+                    approval_id = %approval.id,
+                    key_id = %key_id,
+                    approved_by = approval.approved_by.as_deref().unwrap_or_default(),
+                    "co-approved signature over spending limits"
+                );
+            }
+            Some(approval) => {
+                return Err(SpendingError::ApprovalRequired {
+                    approval_id: approval.id.clone(),
+                    reasons: approval.reasons.join("; "),
+                }
+                .into())
+            }
+            None => {
+                let fiat_value = match (&asset, outflow.amount) {
+                    (Some(asset), Some(amount)) => fiat_estimate(app, &key, asset, amount)?,
+                    _ => None,
+                };
+                let approval = SpendApproval {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    key_id: key_id.to_string(),
+                    summary_hash_hex: summary.summary_hash_hex.clone(),
+                    amount,
+                    fiat_value,
+                    destinations: outflow.destinations,
+                    reasons,
+                    requested_at: now,
+                    approved_by: None,
+                    approved_at: None,
+                    used_at: None,
+                    orphaned_at: None,
+                };
+                let err = SpendingError::ApprovalRequired {
+                    approval_id: approval.id.clone(),
+                    reasons: approval.reasons.join("; "),
+                };
+                tracing::warn!(
+                    target: "audit",
+                    approval_id = %approval.id,
+                    key_id = %key_id,
+                    reasons = %approval.reasons.join("; "),
+                    fiat_value = approval
+                        .fiat_value
+                        .as_ref()
+                        .map(|v| format!("{} {}", v.value, v.currency))
+                        .unwrap_or_default(),
+                    "signature over spending limits held for approval"
+                );
+                store.approvals.push(approval);
+                save_store(app, &key, &mut store)?;
+                return Err(err.into());
+            }
+        }
+    }
+    if let Some(amount) = amount {
+        store.history.push(SpendRecord {
+            key_id: key_id.to_string(),
+            amount,
+            signed_at: now,
+        });
+    }
+    save_store(app, &key, &mut store)
+}
+
+/// Whether `key_id` names something that signs: a stored key, a FROST group
+/// with a share in this vault, or a MuSig2 output key (64 hex digits, from
+/// `musig2_output_key`).
+fn policy_target_exists(
+    app: &AppHandle,
+    key: &[u8; 32],
+    keystore: &State<'_, KeyStore>,
+    key_id: &str,
+) -> Result<bool> {
+    if keystore.0.lock().unwrap().iter().any(|k| k.id == key_id) {
+        return Ok(true);
+    }
+    if frost_store(app, key)?
+        .shares
+        .iter()
+        .any(|s| s.group.group_id == key_id)
+    {
+        return Ok(true);
+    }
+    Ok(is_output_key(key_id))
+}
+
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn list_spending_policies(
+    app: AppHandle,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<SpendingPolicy>> {
+    Ok(load_store(&app, &session_key(&session)?)?.policies)
+}
+
+/// Set or replace the spending policy of a stored key, a FROST group or a
+/// MuSig2 output key. Needs the vault password and, once approvers are
+/// enrolled, one of them. Requires an unlocked vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn set_spending_policy(
+    app: AppHandle,
+    password: String,
+    mut policy: SpendingPolicy,
+    approver: Option<ApproverAuth>,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    keystore: State<'_, KeyStore>,
+) -> Result<SpendingPolicy> {
+    let key = session_key(&session)?;
+    if !policy_target_exists(&app, &key, &keystore, &policy.key_id)? {
+        return Err(VaultError::KeyNotFound(policy.key_id));
+    }
+    spending::validate_policy(&mut policy)?;
+    policy.updated_at = Utc::now();
+    let mut store = load_store(&app, &key)?;
+    let authorized_by = authorize_change(&app, &vault, &password, &store, approver.as_ref())?;
+    store.policies.retain(|p| p.key_id != policy.key_id);
+    store.policies.push(policy.clone());
+    save_store(&app, &key, &mut store)?;
+    tracing::warn!(
+        target: "audit",
+        key_id = %policy.key_id,
+        max_per_tx = policy.max_per_tx.as_deref().unwrap_or("none"),
+        max_per_day = policy.max_per_day.as_deref().unwrap_or("none"),
+        allowed_destinations = policy.allowed_destinations.len(),
+        authorized_by = authorized_by.as_deref().unwrap_or("none"),
+        "spending policy set"
+    );
+    Ok(policy)
+}
+
+/// Authorised like [`set_spending_policy`]. Requires an unlocked vault.
+#[tauri::command]
+pub fn remove_spending_policy(
+    app: AppHandle,
+    password: String,
+    key_id: String,
+    approver: Option<ApproverAuth>,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    let key = session_key(&session)?;
+    let mut store = load_store(&app, &key)?;
+    let authorized_by = authorize_change(&app, &vault, &password, &store, approver.as_ref())?;
+    let before = store.policies.len();
+    store.policies.retain(|p| p.key_id != key_id);
+    if store.policies.len() == before {
+        return Err(VaultError::KeyNotFound(key_id));
+    }
+    save_store(&app, &key, &mut store)?;
+    tracing::warn!(
+        target: "audit",
+        key_id = %key_id,
+        authorized_by = authorized_by.as_deref().unwrap_or("none"),
+        "spending policy removed"
+    );
+    Ok(())
+}
+
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn list_approvers(app: AppHandle, session: State<'_, SessionKey>) -> Result<Vec<ApproverInfo>> {
+    let store = load_store(&app, &session_key(&session)?)?;
+    Ok(store.approvers.iter().map(ApproverInfo::from).collect())
+}
+
+/// Enroll a second person who can co-approve signatures. Their passphrase
+/// must differ from the vault password and meet the password policy. The
+/// first approver needs only the password; each later one also an existing
+/// approver. Requires an unlocked vault.
+#[tauri::command]
+pub fn add_approver(
+    app: AppHandle,
+    password: String,
+    name: String,
+    passphrase: String,
+    approver: Option<ApproverAuth>,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<ApproverInfo> {
+    let key = session_key(&session)?;
+    if name.trim().is_empty() {
+        return Err(VaultError::InvalidMetadata(
+            "an approver needs a name".to_string(),
+        ));
+    }
+    let mut store = load_store(&app, &key)?;
+    let authorized_by = authorize_change(&app, &vault, &password, &store, approver.as_ref())?;
+    if verify_password(&app, &vault, &passphrase).is_ok() {
+        return Err(VaultError::InvalidMetadata(
+            "an approver's passphrase must differ from the vault password".to_string(),
+        ));
+    }
+    enforce_password_strength(&vault.0.lock().unwrap(), &passphrase)?;
+    let enrolled = spending::enroll_approver(&name, &passphrase, Utc::now())?;
+    let info = ApproverInfo::from(&enrolled);
+    store.approvers.push(enrolled);
+    save_store(&app, &key, &mut store)?;
+    tracing::warn!(
+        target: "audit",
+        approver_id = %info.id,
+        name = %info.name,
+        authorized_by = authorized_by.as_deref().unwrap_or("none"),
+        "spending approver added"
+    );
+    Ok(info)
+}
+
+/// Authorised like [`add_approver`]; an approver may remove themselves.
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn remove_approver(
+    app: AppHandle,
+    password: String,
+    approver_id: String,
+    approver: Option<ApproverAuth>,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    let key = session_key(&session)?;
+    let mut store = load_store(&app, &key)?;
+    let authorized_by = authorize_change(&app, &vault, &password, &store, approver.as_ref())?;
+    let before = store.approvers.len();
+    store.approvers.retain(|a| a.id != approver_id);
+    if store.approvers.len() == before {
+        return Err(VaultError::KeyNotFound(approver_id));
+    }
+    save_store(&app, &key, &mut store)?;
+    tracing::warn!(
+        target: "audit",
+        approver_id = %approver_id,
+        authorized_by = authorized_by.as_deref().unwrap_or("none"),
+        "spending approver removed"
+    );
+    Ok(())
+}
+
+/// The approvals table, newest first. Requires an unlocked vault.
+#[tauri::command]
+pub fn list_spend_approvals(
+    app: AppHandle,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<SpendApprovalView>> {
+    let store = load_store(&app, &session_key(&session)?)?;
+    let now = Utc::now();
+    let mut views: Vec<_> = store.approvals.iter().map(|a| view(a, now)).collect();
+    views.sort_by(|a, b| b.approval.requested_at.cmp(&a.approval.requested_at));
+    Ok(views)
+}
+
+/// An approver co-approves a held signature. The signer then repeats the
+/// signing command with the same summary hash. Requires an unlocked vault.
+#[tauri::command]
+pub fn approve_spend(
+    app: AppHandle,
+    approval_id: String,
+    approver: ApproverAuth,
+    session: State<'_, SessionKey>,
+) -> Result<SpendApprovalView> {
+    let key = session_key(&session)?;
+    let mut store = load_store(&app, &key)?;
+    let name = check_approver(&store, &approver)?;
+    let now = Utc::now();
+    let approval = store
+        .approvals
+        .iter_mut()
+        .find(|a| a.id == approval_id)
+        .ok_or_else(|| VaultError::KeyNotFound(approval_id.clone()))?;
+    if approval.status(now) != ApprovalStatus::Pending {
+        return Err(SpendingError::NotPending(approval_id).into());
+    }
+    approval.approved_by = Some(approver.approver_id.clone());
+    approval.approved_at = Some(now);
+    let approved = view(approval, now);
+    save_store(&app, &key, &mut store)?;
+    tracing::warn!(
+        target: "audit",
+        approval_id = %approval_id,
+        key_id = %approved.approval.key_id,
+        approver_id = %approver.approver_id,
+        approver = %name,
+        "signature over spending limits co-approved"
+    );
+    Ok(approved)
+}
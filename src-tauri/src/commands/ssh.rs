@@ -0,0 +1,167 @@
+use crate::commands::consent::require_consent;
+use crate::commands::items::{push_item, ItemStore};
+use crate::commands::keys::SessionKey;
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::notifications::note_decrypt;
+use crate::commands::password_policy::enforce_password_strength;
+use crate::commands::policy::{enforce_item_export, enforce_policy, export_watermark};
+use crate::commands::vault::{verify_password, VaultMutex};
+use crate::crypto::ssh;
+use crate::error::{Result, VaultError};
+use crate::models::consent::ConsentOperation;
+use crate::models::item::{ItemPayload, SshKeyAlgorithm, SshKeyItem, VaultItem, VaultItemPublic};
+use crate::models::policy::PolicyGate;
+use crate::models::rate_limit::SensitiveOp;
+use chrono::Utc;
+use tauri::{AppHandle, State};
+
+/// Run `f` against the stored SSH key with `item_id`, counting it as a use.
+fn with_ssh_key<T>(
+    items: &State<'_, ItemStore>,
+    item_id: &str,
+    f: impl FnOnce(&SshKeyItem) -> Result<T>,
+) -> Result<T> {
+    let mut store = items.0.lock().unwrap();
+    match store
+        .iter_mut()
+        .find(|i| i.id == item_id && i.trashed_at.is_none())
+    {
+        Some(VaultItem {
+            payload: ItemPayload::SshKey(key),
+            usage,
+            ..
+        }) => {
+            usage.touch(Utc::now());
+            f(key)
+        }
+        _ => Err(VaultError::KeyNotFound(item_id.to_string())),
+    }
+}
+
+/// Generate an SSH keypair (`ed25519`, `ecdsa` or `rsa`) inside the vault and
+/// store it encrypted in the item store. Only the public view is returned.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn ssh_generate_key(
+    app: AppHandle,
+    algorithm: String,
+    comment: String,
+    label: Option<String>,
+    rsa_bits: Option<usize>,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<VaultItemPublic> {
+    enforce_policy(&vault, PolicyGate::KeyGeneration)?;
+    let alg = SshKeyAlgorithm::parse(&algorithm)
+        .ok_or_else(|| ssh::SshError::UnsupportedAlgorithm(algorithm.clone()))?;
+    let kp = ssh::generate(alg, &comment, rsa_bits)?;
+
+    let item = VaultItem::new(
+        label,
+        ItemPayload::SshKey(SshKeyItem {
+            algorithm: alg,
+            comment,
+            public_key_openssh: kp.public_key_openssh,
+            fingerprint: kp.fingerprint,
+            private_key_openssh: kp.private_key_openssh.to_string(),
+        }),
+    );
+
+    let public = item.to_public();
+    push_item(&app, &vault, &items, &session, item)?;
+    Ok(public)
+}
+
+#[tauri::command]
+pub fn ssh_list_keys(items: State<'_, ItemStore>) -> Result<Vec<VaultItemPublic>> {
+    let store = items.0.lock().unwrap();
+    Ok(store
+        .iter()
+        .filter(|i| i.trashed_at.is_none() && matches!(i.payload, ItemPayload::SshKey(_)))
+        .map(|i| i.to_public())
+        .collect())
+}
+
+/// The single-line OpenSSH public key, ready for `authorized_keys`.
+#[tauri::command]
+pub fn ssh_export_public_key(item_id: String, items: State<'_, ItemStore>) -> Result<String> {
+    with_ssh_key(&items, &item_id, |k| Ok(k.public_key_openssh.clone()))
+}
+
+/// Export the OpenSSH private key. This is the one path where SSH secret
+/// material crosses IPC, so the vault password is re-verified first. With a
+/// non-empty `passphrase` the exported key is passphrase-protected the same way
+/// `ssh-keygen` does it. `consent_token` comes from `request_consent`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn ssh_export_private_key(
+    app: AppHandle,
+    item_id: String,
+    password: String,
+    passphrase: Option<String>,
+    consent_token: String,
+    state: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<String> {
+    enforce(&state, &limiter, SensitiveOp::Export)?;
+    let plaintext = passphrase.as_deref().is_none_or(str::is_empty);
+    if plaintext {
+        enforce_policy(&state, PolicyGate::PlaintextExport)?;
+        enforce_item_export(&items, std::slice::from_ref(&item_id))?;
+    } else if let Some(passphrase) = &passphrase {
+        enforce_password_strength(&state.0.lock().unwrap(), passphrase)?;
+    }
+    verify_password(&app, &state, &password)?;
+    require_consent(
+        &app,
+        &consent_token,
+        &ConsentOperation::SshPrivateKeyExport {
+            item_id: item_id.clone(),
+        },
+    )?;
+    let mark = export_watermark(&app)?;
+    let pem = with_ssh_key(&items, &item_id, |k| {
+        let pem = ssh::export_private(
+            &k.private_key_openssh,
+            passphrase.as_deref(),
+            Some(&mark.summary()),
+        )?;
+        Ok(pem.to_string())
+    })?;
+    tracing::info!(
+        target: "audit",
+        item_id = %item_id,
+        encrypted = !plaintext,
+        profile = %mark.profile,
+        instance_id = %mark.instance_id,
+        "ssh private key exported"
+    );
+    Ok(pem)
+}
+
+/// Agent-style signing: sign `data_hex` with a stored SSH key and return the
+/// hex of the SSH wire-encoded signature blob. The private key is never
+/// exported, so the vault can stand in for `ssh-agent` for challenge signing.
+#[tauri::command]
+pub fn ssh_sign_challenge(
+    app: AppHandle,
+    item_id: String,
+    data_hex: String,
+    items: State<'_, ItemStore>,
+    vault: State<'_, VaultMutex>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<String> {
+    enforce(&vault, &limiter, SensitiveOp::Decrypt)?;
+    let decrypt = note_decrypt(&app, &vault, &item_id);
+    let data = hex::decode(&data_hex).map_err(|e| VaultError::Storage(e.to_string()))?;
+    let signature = with_ssh_key(&items, &item_id, |k| {
+        Ok(hex::encode(ssh::sign_challenge(
+            &k.private_key_openssh,
+            &data,
+        )?))
+    })?;
+    decrypt.finish(&app)?;
+    Ok(signature)
+}
@@ -0,0 +1,174 @@
+use crate::commands::instance::instance_key;
+use crate::commands::items::{save_items, ItemStore};
+use crate::commands::keys::{
+    atomic_write, keys_file_path, save_keys, KeyStore, MasterSeed, SessionKey,
+};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::vault::VaultMutex;
+use crate::crypto::instance::PURPOSE_SYNC_PACKAGE;
+use crate::crypto::sync;
+use crate::error::{Result, VaultError};
+use crate::models::rate_limit::SensitiveOp;
+use crate::models::sync::{
+    ConflictPolicy, SyncExportSummary, SyncImportReport, SyncPackage, SyncState,
+};
+use chrono::Utc;
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
+
+/// Replication bookkeeping lives in plaintext next to `vault.json`. It is
+/// deliberately left out of drive backups: a restored installation starts a
+/// fresh device id instead of reusing sequence numbers its peer has seen.
+pub const SYNC_FILE: &str = "sync_state.json";
+
+fn load_state(app: &AppHandle) -> Result<SyncState> {
+    let path = keys_file_path(app, SYNC_FILE)?;
+    if !path.exists() {
+        return Ok(SyncState::new());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+fn save_state(app: &AppHandle, state: &SyncState) -> Result<()> {
+    let path = keys_file_path(app, SYNC_FILE)?;
+    atomic_write(&path, &serde_json::to_vec_pretty(state)?)
+}
+
+/// This installation's device id, saved on first use so it stays stable
+/// before the first sync.
+pub(crate) fn device_id(app: &AppHandle) -> Result<String> {
+    if keys_file_path(app, SYNC_FILE)?.exists() {
+        return Ok(load_state(app)?.device_id);
+    }
+    let state = SyncState::new();
+    save_state(app, &state)?;
+    Ok(state.device_id)
+}
+
+fn seed_copy(master_seed: &State<'_, MasterSeed>) -> Result<Zeroizing<[u8; 64]>> {
+    let guard = master_seed.0.lock().unwrap();
+    Ok(guard.as_ref().ok_or(VaultError::NotInitialized)?.clone())
+}
+
+/// This installation's device id, export counter and last sync times.
+#[tauri::command]
+pub fn sync_status(app: AppHandle) -> Result<SyncState> {
+    load_state(&app)
+}
+
+/// Write a sync package to `path` with every key and item changed since the
+/// last export (or all of them when `full`). Packages are encrypted and
+/// signed with keys derived from the master seed, so only another
+/// installation of the same vault can apply them.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn sync_export_package(
+    app: AppHandle,
+    path: String,
+    full: bool,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    master_seed: State<'_, MasterSeed>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<SyncExportSummary> {
+    enforce(&vault, &limiter, SensitiveOp::Export)?;
+    let seed = seed_copy(&master_seed)?;
+    let mut state = load_state(&app)?;
+    let delta = {
+        let keys = keystore.0.lock().unwrap();
+        let items = items.0.lock().unwrap();
+        sync::build_delta(
+            &sync::fingerprint_key(&seed),
+            &mut state.known,
+            &keys,
+            &items,
+            full,
+        )?
+    };
+    let now = Utc::now();
+    state.sequence += 1;
+    state.last_export_at = Some(now);
+    let mut pkg = sync::seal_package(&seed, &state.device_id, state.sequence, now, full, &delta)?;
+    let instance = instance_key(&app, &seed)?;
+    pkg.instance_signature =
+        Some(instance.sign(PURPOSE_SYNC_PACKAGE, &sync::package_message(&pkg))?);
+    atomic_write(std::path::Path::new(&path), &serde_json::to_vec(&pkg)?)?;
+    save_state(&app, &state)?;
+    Ok(SyncExportSummary {
+        sequence: state.sequence,
+        full,
+        keys: delta.keys.len(),
+        items: delta.items.len(),
+        deleted: delta.deleted_keys.len() + delta.deleted_items.len(),
+    })
+}
+
+/// Apply a sync package exported by another installation of this vault.
+/// Packages are applied in order: one from this installation, or one not
+/// newer than the last applied from its sender, is rejected. Entries changed
+/// on both sides are settled by `policy` and listed in the report.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn sync_import_package(
+    app: AppHandle,
+    path: String,
+    policy: ConflictPolicy,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<SyncImportReport> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let seed = seed_copy(&master_seed)?;
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    let pkg: SyncPackage = serde_json::from_slice(&data)?;
+
+    let mut state = load_state(&app)?;
+    let mut report = SyncImportReport {
+        device_id: pkg.device_id.clone(),
+        sequence: pkg.sequence,
+        skipped_packages: sync::check_sequence(&state, &pkg)?,
+        ..Default::default()
+    };
+    let delta = sync::open_package(&seed, &pkg)?;
+    report.instance_fingerprint = sync::check_instance(&state, &pkg)?;
+
+    let (keys_file, items_file) = {
+        let v = vault.0.lock().unwrap();
+        (v.keys_file.clone(), v.items_file.clone())
+    };
+    let mut key_store = keystore.0.lock().unwrap();
+    let mut item_store = items.0.lock().unwrap();
+    // Merge into copies so a failed write leaves the session untouched.
+    let mut keys = key_store.clone();
+    let mut vault_items = item_store.clone();
+    sync::apply_delta(
+        &sync::fingerprint_key(&seed),
+        &mut state.known,
+        &mut keys,
+        &mut vault_items,
+        delta,
+        policy,
+        &mut report,
+    )?;
+    save_keys(&app, &keys_file, &session_key, &keys)?;
+    save_items(&app, &items_file, &session_key, &vault_items)?;
+    // Recorded last: after a crash the package is simply applied again.
+    state.imported.insert(pkg.device_id.clone(), pkg.sequence);
+    if let Some(fingerprint) = &report.instance_fingerprint {
+        state
+            .instance_keys
+            .insert(pkg.device_id.clone(), fingerprint.clone());
+    }
+    state.last_import_at = Some(Utc::now());
+    save_state(&app, &state)?;
+    *key_store = keys;
+    *item_store = vault_items;
+    Ok(report)
+}
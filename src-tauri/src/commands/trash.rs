@@ -0,0 +1,356 @@
+use crate::commands::consent::require_consent;
+use crate::commands::integrity::cascade_deletes;
+use crate::commands::items::{save_items, ItemStore};
+use crate::commands::keys::{save_keys, KeyStore, SessionKey};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::undo::stash_purge;
+use crate::commands::vault::{persist_vault, rewrite_stores, VaultMutex};
+use crate::error::{Result, VaultError};
+use crate::models::consent::ConsentOperation;
+use crate::models::item::VaultItem;
+use crate::models::key::KeyEntry;
+use crate::models::rate_limit::SensitiveOp;
+use crate::models::trash::{purge_after, validate_retention_days, TrashKind, TrashedEntry};
+use crate::models::undo::UndoKind;
+use chrono::{DateTime, Duration, Utc};
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Manager, State};
+
+/// How often the background purge applies the retention setting.
+pub const TRASH_PURGE_INTERVAL_SECS: u64 = 60 * 60;
+
+fn entry(
+    kind: TrashKind,
+    id: &str,
+    label: &Option<String>,
+    trashed_at: DateTime<Utc>,
+    retention_days: Option<u32>,
+) -> TrashedEntry {
+    TrashedEntry {
+        kind,
+        id: id.to_string(),
+        label: label.clone(),
+        trashed_at,
+        purge_after: purge_after(trashed_at, retention_days),
+    }
+}
+
+fn key_entry(key: &KeyEntry, retention_days: Option<u32>) -> Option<TrashedEntry> {
+    let trashed_at = key.metadata.trashed_at?;
+    Some(entry(
+        TrashKind::Key,
+        &key.id,
+        &key.metadata.label,
+        trashed_at,
+        retention_days,
+    ))
+}
+
+fn item_entry(item: &VaultItem, retention_days: Option<u32>) -> Option<TrashedEntry> {
+    let trashed_at = item.trashed_at?;
+    Some(entry(
+        TrashKind::Item,
+        &item.id,
+        &item.label,
+        trashed_at,
+        retention_days,
+    ))
+}
+
+/// Everything in the trash, oldest first. Pure (no I/O) to keep it
+/// unit-testable.
+pub fn collect_trash(
+    keys: &[KeyEntry],
+    items: &[VaultItem],
+    retention_days: Option<u32>,
+) -> Vec<TrashedEntry> {
+    let mut trash: Vec<TrashedEntry> = keys
+        .iter()
+        .filter_map(|k| key_entry(k, retention_days))
+        .chain(items.iter().filter_map(|i| item_entry(i, retention_days)))
+        .collect();
+    trash.sort_by_key(|e| e.trashed_at);
+    trash
+}
+
+/// Remove entries trashed at or before `cutoff` and report them. Live
+/// entries are never touched. Pure (no I/O) to keep it unit-testable.
+pub fn take_purgeable(
+    keys: &mut Vec<KeyEntry>,
+    items: &mut Vec<VaultItem>,
+    cutoff: DateTime<Utc>,
+    retention_days: Option<u32>,
+) -> Vec<TrashedEntry> {
+    let purgeable = |t: Option<DateTime<Utc>>| t.is_some_and(|t| t <= cutoff);
+    let mut purged: Vec<TrashedEntry> = keys
+        .iter()
+        .filter(|k| purgeable(k.metadata.trashed_at))
+        .filter_map(|k| key_entry(k, retention_days))
+        .chain(
+            items
+                .iter()
+                .filter(|i| purgeable(i.trashed_at))
+                .filter_map(|i| item_entry(i, retention_days)),
+        )
+        .collect();
+    keys.retain(|k| !purgeable(k.metadata.trashed_at));
+    items.retain(|i| !purgeable(i.trashed_at));
+    purged.sort_by_key(|e| e.trashed_at);
+    purged
+}
+
+/// Destroy trash older than `cutoff` and write every destroyed entry to the
+/// `audit` tracing target. The stores are rewritten to new generation files
+/// and the old ones wiped, so the destroyed secrets do not linger on disk;
+/// they are only kept, encrypted in memory, for the undo window.
+fn purge_before(
+    app: &AppHandle,
+    vault: &State<'_, VaultMutex>,
+    keystore: &State<'_, KeyStore>,
+    items: &State<'_, ItemStore>,
+    session: &State<'_, SessionKey>,
+    cutoff: DateTime<Utc>,
+) -> Result<Vec<TrashedEntry>> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let mut vault = vault.0.lock().unwrap();
+    let mut keys = keystore.0.lock().unwrap();
+    let mut store = items.0.lock().unwrap();
+    let (mut next_keys, mut next_items) = (keys.clone(), store.clone());
+    let purged = take_purgeable(
+        &mut next_keys,
+        &mut next_items,
+        cutoff,
+        vault.trash_retention_days,
+    );
+    if purged.is_empty() {
+        return Ok(purged);
+    }
+    rewrite_stores(app, &mut vault, &session_key, &next_keys, &next_items)?;
+    let removed_keys = keys
+        .iter()
+        .filter(|k| !next_keys.iter().any(|n| n.id == k.id))
+        .cloned()
+        .collect();
+    let removed_items = store
+        .iter()
+        .filter(|i| !next_items.iter().any(|n| n.id == i.id))
+        .cloned()
+        .collect();
+    *keys = next_keys;
+    *store = next_items;
+    stash_purge(
+        app,
+        &session_key,
+        UndoKind::PurgeTrash,
+        vault.undo_window_secs,
+        &purged,
+        removed_keys,
+        removed_items,
+    );
+    for entry in &purged {
+        tracing::warn!(
+            target: "audit",
+            kind = ?entry.kind,
+            id = %entry.id,
+            label = entry.label.as_deref().unwrap_or(""),
+            trashed_at = %entry.trashed_at,
+            "trashed entry purged"
+        );
+    }
+    Ok(purged)
+}
+
+/// Move a key or item to the trash. Keys generated as part of a keyset are
+/// refused; roll the keyset back instead.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn trash_entry(
+    app: AppHandle,
+    kind: TrashKind,
+    id: String,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<TrashedEntry> {
+    enforce(&vault, &limiter, SensitiveOp::Delete)?;
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let (keys_file, items_file, retention_days) = {
+        let v = vault.0.lock().unwrap();
+        (
+            v.keys_file.clone(),
+            v.items_file.clone(),
+            v.trash_retention_days,
+        )
+    };
+    let now = Utc::now();
+
+    match kind {
+        TrashKind::Key => {
+            let mut store = keystore.0.lock().unwrap();
+            let key = store
+                .iter_mut()
+                .find(|k| k.id == id && k.metadata.trashed_at.is_none())
+                .ok_or_else(|| VaultError::KeyNotFound(id.clone()))?;
+            if let Some(keyset_id) = &key.metadata.keyset_id {
+                return Err(VaultError::Storage(format!(
+                    "key {id} belongs to keyset {keyset_id}; roll back the keyset instead"
+                )));
+            }
+            key.metadata.trashed_at = Some(now);
+            let trashed = entry(kind, &id, &key.metadata.label, now, retention_days);
+            save_keys(&app, &keys_file, &session_key, &store)?;
+            Ok(trashed)
+        }
+        TrashKind::Item => {
+            let mut store = items.0.lock().unwrap();
+            let item = store
+                .iter_mut()
+                .find(|i| i.id == id && i.trashed_at.is_none())
+                .ok_or_else(|| VaultError::KeyNotFound(id.clone()))?;
+            item.trashed_at = Some(now);
+            let trashed = entry(kind, &id, &item.label, now, retention_days);
+            save_items(&app, &items_file, &session_key, &store)?;
+            Ok(trashed)
+        }
+    }
+}
+
+/// Take a key or item back out of the trash.
+#[tauri::command]
+pub fn restore_entry(
+    app: AppHandle,
+    kind: TrashKind,
+    id: String,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let (keys_file, items_file) = {
+        let v = vault.0.lock().unwrap();
+        (v.keys_file.clone(), v.items_file.clone())
+    };
+
+    match kind {
+        TrashKind::Key => {
+            let mut store = keystore.0.lock().unwrap();
+            let key = store
+                .iter_mut()
+                .find(|k| k.id == id && k.metadata.trashed_at.is_some())
+                .ok_or(VaultError::KeyNotFound(id))?;
+            key.metadata.trashed_at = None;
+            save_keys(&app, &keys_file, &session_key, &store)
+        }
+        TrashKind::Item => {
+            let mut store = items.0.lock().unwrap();
+            let item = store
+                .iter_mut()
+                .find(|i| i.id == id && i.trashed_at.is_some())
+                .ok_or(VaultError::KeyNotFound(id))?;
+            item.trashed_at = None;
+            save_items(&app, &items_file, &session_key, &store)
+        }
+    }
+}
+
+#[tauri::command]
+pub fn list_trash(
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+) -> Result<Vec<TrashedEntry>> {
+    let retention_days = vault.0.lock().unwrap().trash_retention_days;
+    let keys = keystore.0.lock().unwrap();
+    let items = items.0.lock().unwrap();
+    Ok(collect_trash(&keys, &items, retention_days))
+}
+
+/// Permanently destroy keys and items that have been in the trash for at
+/// least `older_than_days` days (`0` empties the trash). Returns what was
+/// destroyed. `consent_token` comes from `request_consent`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn purge_trash(
+    app: AppHandle,
+    older_than_days: u32,
+    consent_token: String,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<Vec<TrashedEntry>> {
+    enforce(&vault, &limiter, SensitiveOp::Delete)?;
+    require_consent(
+        &app,
+        &consent_token,
+        &ConsentOperation::PurgeTrash { older_than_days },
+    )?;
+    let cutoff = Utc::now() - Duration::days(older_than_days.into());
+    let purged = purge_before(&app, &vault, &keystore, &items, &session, cutoff)?;
+    if !purged.is_empty() && vault.0.lock().unwrap().undo_window_secs == 0 {
+        cascade_deletes(&app);
+    }
+    Ok(purged)
+}
+
+/// Set (or with `None`, turn off) how many days trash is kept before the
+/// background purge destroys it. Requires an unlocked vault.
+#[tauri::command]
+pub fn set_trash_retention(
+    app: AppHandle,
+    days: Option<u32>,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<Option<u32>> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    if let Some(days) = days {
+        validate_retention_days(days).map_err(VaultError::InvalidMetadata)?;
+    }
+    let mut vault = vault.0.lock().unwrap();
+    let mut next = vault.clone();
+    next.trash_retention_days = days;
+    persist_vault(&app, &next)?;
+    *vault = next;
+    Ok(days)
+}
+
+/// Periodically purge trash past the configured retention, then remove rows
+/// left pointing at entries that no longer exist. Only runs while the vault
+/// is unlocked, since the stores cannot be saved otherwise.
+pub fn spawn_trash_purge(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(StdDuration::from_secs(TRASH_PURGE_INTERVAL_SECS));
+        if app.state::<SessionKey>().0.lock().unwrap().is_none() {
+            continue;
+        }
+        let vault = app.state::<VaultMutex>();
+        let retention_days = vault.0.lock().unwrap().trash_retention_days;
+        if let Some(days) = retention_days {
+            let cutoff = Utc::now() - Duration::days(days.into());
+            let _ = purge_before(
+                &app,
+                &vault,
+                &app.state::<KeyStore>(),
+                &app.state::<ItemStore>(),
+                &app.state::<SessionKey>(),
+                cutoff,
+            );
+        }
+        cascade_deletes(&app);
+    });
+}
@@ -0,0 +1,225 @@
+use crate::commands::keys::{atomic_write, keys_file_path, secrets_hex_for, KeyStore, SessionKey};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::notifications::note_decrypt;
+use crate::commands::spending::enforce_spending;
+use crate::commands::tx_review::confirm_reviewed;
+use crate::commands::vault::VaultMutex;
+use crate::crypto::mldsa87::SecretKey;
+use crate::crypto::treasury::{self, TreasuryError};
+use crate::error::{Result, VaultError};
+use crate::models::key::KeyType;
+use crate::models::rate_limit::SensitiveOp;
+use crate::models::treasury::{
+    TreasuryPolicy, TreasuryProposal, TreasuryProposalStatus, TreasurySignatureBundle,
+    TreasurySigner, TreasuryState,
+};
+use chrono::Utc;
+use tauri::{AppHandle, State};
+
+/// Treasury policies and proposals live in plaintext metadata next to
+/// `vault.json`: they hold only key ids, public keys, payloads and
+/// signatures. Signing still needs the unlocked keystore.
+pub const TREASURY_FILE: &str = "treasury.json";
+
+pub(crate) fn load_treasury(app: &AppHandle) -> Result<TreasuryState> {
+    let path = keys_file_path(app, TREASURY_FILE)?;
+    if !path.exists() {
+        return Ok(TreasuryState::default());
+    }
+    let data = std::fs::read(&path).map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+pub(crate) fn save_treasury(app: &AppHandle, state: &TreasuryState) -> Result<()> {
+    let path = keys_file_path(app, TREASURY_FILE)?;
+    atomic_write(&path, &serde_json::to_vec_pretty(state)?)
+}
+
+fn find_policy<'a>(state: &'a TreasuryState, id: &str) -> Result<&'a TreasuryPolicy> {
+    state
+        .policies
+        .iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| VaultError::KeyNotFound(id.to_string()))
+}
+
+fn find_proposal(state: &TreasuryState, id: &str) -> Result<usize> {
+    state
+        .proposals
+        .iter()
+        .position(|p| p.id == id)
+        .ok_or_else(|| VaultError::KeyNotFound(id.to_string()))
+}
+
+/// Create an M-of-N policy over stored treasury keys.
+#[tauri::command]
+pub fn treasury_create_policy(
+    app: AppHandle,
+    name: String,
+    threshold: usize,
+    signer_key_ids: Vec<String>,
+    keystore: State<'_, KeyStore>,
+) -> Result<TreasuryPolicy> {
+    let signers = {
+        let store = keystore.0.lock().unwrap();
+        signer_key_ids
+            .iter()
+            .map(|id| {
+                let key = store
+                    .iter()
+                    .find(|k| &k.id == id && k.metadata.trashed_at.is_none())
+                    .ok_or_else(|| VaultError::KeyNotFound(id.clone()))?;
+                if key.metadata.key_type != KeyType::Treasury {
+                    return Err(TreasuryError::InvalidPolicy(format!(
+                        "key {id} is not a treasury key"
+                    ))
+                    .into());
+                }
+                Ok(TreasurySigner {
+                    key_id: key.id.clone(),
+                    public_key_hex: key.public_key_hex.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+    treasury::validate_policy(threshold, &signers)?;
+
+    let policy = TreasuryPolicy {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        threshold,
+        signers,
+        created_at: Utc::now(),
+        orphaned_signers: Vec::new(),
+    };
+    let mut state = load_treasury(&app)?;
+    state.policies.push(policy.clone());
+    save_treasury(&app, &state)?;
+    Ok(policy)
+}
+
+#[tauri::command]
+pub fn treasury_list_policies(app: AppHandle) -> Result<Vec<TreasuryPolicy>> {
+    Ok(load_treasury(&app)?.policies)
+}
+
+/// Open a proposal to sign `payload_hex` under a policy.
+#[tauri::command]
+pub fn treasury_create_proposal(
+    app: AppHandle,
+    policy_id: String,
+    description: String,
+    payload_hex: String,
+) -> Result<TreasuryProposalStatus> {
+    hex::decode(&payload_hex).map_err(|e| TreasuryError::InvalidPayload(e.to_string()))?;
+    let mut state = load_treasury(&app)?;
+    let policy = find_policy(&state, &policy_id)?.clone();
+    let proposal = TreasuryProposal {
+        id: uuid::Uuid::new_v4().to_string(),
+        policy_id,
+        description,
+        payload_hex,
+        shares: Vec::new(),
+        created_at: Utc::now(),
+    };
+    let status = treasury::status(&policy, &proposal);
+    state.proposals.push(proposal);
+    save_treasury(&app, &state)?;
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn treasury_list_proposals(app: AppHandle) -> Result<Vec<TreasuryProposalStatus>> {
+    let state = load_treasury(&app)?;
+    state
+        .proposals
+        .iter()
+        .map(|p| Ok(treasury::status(find_policy(&state, &p.policy_id)?, p)))
+        .collect()
+}
+
+/// Add partial signatures to a proposal from the given stored signer keys.
+/// Keys that already signed are skipped, so the call is safe to repeat. The
+/// rest are decrypted together, with one off-hours notice and one audit
+/// entry for the batch. `summary_hash_hex` is the hash `review_transaction`
+/// returned for the proposal's payload.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn sign_treasury_proposal(
+    app: AppHandle,
+    proposal_id: String,
+    key_ids: Vec<String>,
+    summary_hash_hex: String,
+    keystore: State<'_, KeyStore>,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<TreasuryProposalStatus> {
+    let mut state = load_treasury(&app)?;
+    let idx = find_proposal(&state, &proposal_id)?;
+    let policy = find_policy(&state, &state.proposals[idx].policy_id)?.clone();
+    let mut proposal = state.proposals[idx].clone();
+
+    // Keys that have not signed yet, each once. Their secrets are resolved
+    // as one batch, so a missing key fails the call before anything signs.
+    let mut pending: Vec<String> = Vec::new();
+    for key_id in key_ids {
+        if !proposal.shares.iter().any(|s| s.key_id == key_id) && !pending.contains(&key_id) {
+            pending.push(key_id);
+        }
+    }
+    if !pending.is_empty() {
+        for _ in &pending {
+            enforce(&vault, &limiter, SensitiveOp::Decrypt)?;
+        }
+        let payload = hex::decode(&proposal.payload_hex)
+            .map_err(|e| VaultError::InvalidMetadata(e.to_string()))?;
+        let summary = confirm_reviewed(&app, &session, None, &payload, &summary_hash_hex)?;
+        for key_id in &pending {
+            enforce_spending(&app, &session, key_id, &summary)?;
+        }
+        let decrypt = note_decrypt(&app, &vault, &pending.join(", "));
+        let secrets = secrets_hex_for(&keystore, &pending)?;
+        let mut shares = Vec::with_capacity(pending.len());
+        for (key_id, secret_hex) in pending.iter().zip(&secrets) {
+            let sk = SecretKey::from_hex(secret_hex)?;
+            shares.push(treasury::sign_share(
+                &policy,
+                &proposal,
+                key_id,
+                sk,
+                Utc::now(),
+            )?);
+        }
+        decrypt.finish(&app)?;
+        for share in shares {
+            treasury::add_share(&policy, &mut proposal, share)?;
+        }
+    }
+
+    let status = treasury::status(&policy, &proposal);
+    state.proposals[idx] = proposal;
+    save_treasury(&app, &state)?;
+    Ok(status)
+}
+
+/// Combine a proposal's shares into the M-of-N signature bundle for on-chain
+/// submission. Fails until the threshold is met.
+#[tauri::command]
+pub fn treasury_combine_signatures(
+    app: AppHandle,
+    proposal_id: String,
+) -> Result<TreasurySignatureBundle> {
+    let state = load_treasury(&app)?;
+    let proposal = &state.proposals[find_proposal(&state, &proposal_id)?];
+    let policy = find_policy(&state, &proposal.policy_id)?;
+    Ok(treasury::combine(policy, proposal)?)
+}
+
+/// Verify a signature bundle against the stored policy it names.
+#[tauri::command]
+pub fn treasury_verify_bundle(app: AppHandle, bundle: TreasurySignatureBundle) -> Result<bool> {
+    let state = load_treasury(&app)?;
+    let policy = find_policy(&state, &bundle.policy_id)?;
+    Ok(treasury::verify_bundle(policy, &bundle)?)
+}
@@ -0,0 +1,111 @@
+use crate::commands::keys::{load_sealed, save_sealed, session_key, SessionKey};
+use crate::decode::{self, ethereum};
+use crate::error::{Result, VaultError};
+use crate::models::signing_request::SigningPayloadKind;
+use crate::models::tx_review::{AbiEntry, AbiRegistry, TxSummary};
+use tauri::{AppHandle, State};
+
+/// Local 4-byte selector registry, encrypted with the session key next to
+/// `vault.json`.
+pub const ABI_REGISTRY_FILE: &str = "abi_registry.json";
+
+fn load_registry(app: &AppHandle, key: &[u8; 32]) -> Result<AbiRegistry> {
+    load_sealed(app, ABI_REGISTRY_FILE, key)
+}
+
+fn save_registry(app: &AppHandle, key: &[u8; 32], registry: &AbiRegistry) -> Result<()> {
+    save_sealed(app, ABI_REGISTRY_FILE, key, registry)
+}
+
+/// Gate for signing commands: decode `payload` again and refuse to go on
+/// unless its summary hashes to what the reviewer was shown.
+pub(crate) fn confirm_reviewed(
+    app: &AppHandle,
+    session: &State<'_, SessionKey>,
+    kind: Option<SigningPayloadKind>,
+    payload: &[u8],
+    summary_hash_hex: &str,
+) -> Result<TxSummary> {
+    let registry = load_registry(app, &session_key(session)?)?;
+    let summary = decode::confirm(kind, payload, &registry, summary_hash_hex)?;
+    tracing::info!(
+        target: "audit",
+        kind = summary.kind.as_str(),
+        payload_sha256 = %summary.payload_sha256_hex,
+        summary_hash = %summary.summary_hash_hex,
+        "reviewed transaction confirmed for signing"
+    );
+    Ok(summary)
+}
+
+/// Decode a payload for review before signing. Pass the returned
+/// `summary_hash_hex` to the signing command to confirm it. `kind` is
+/// guessed when omitted. Requires an unlocked vault.
+#[tauri::command]
+pub fn review_transaction(
+    app: AppHandle,
+    payload_hex: String,
+    kind: Option<SigningPayloadKind>,
+    session: State<'_, SessionKey>,
+) -> Result<TxSummary> {
+    let payload =
+        hex::decode(payload_hex.trim()).map_err(|e| VaultError::InvalidMetadata(e.to_string()))?;
+    let registry = load_registry(&app, &session_key(&session)?)?;
+    Ok(decode::summarize(kind, &payload, &registry)?)
+}
+
+/// Selectors added on this machine. Built-in ones are not listed. Requires
+/// an unlocked vault.
+#[tauri::command]
+pub fn abi_registry_list(
+    app: AppHandle,
+    session: State<'_, SessionKey>,
+) -> Result<Vec<AbiEntry>> {
+    Ok(load_registry(&app, &session_key(&session)?)?.entries)
+}
+
+/// Teach the decoder a selector, replacing any earlier entry for it. The
+/// selector is not checked against the signature, so copy both from a
+/// source you trust. Requires an unlocked vault.
+#[tauri::command]
+pub fn abi_registry_add(
+    app: AppHandle,
+    selector: String,
+    signature: String,
+    session: State<'_, SessionKey>,
+) -> Result<AbiEntry> {
+    let entry = ethereum::registry_entry(&selector, &signature)?;
+    let key = session_key(&session)?;
+    let mut registry = load_registry(&app, &key)?;
+    registry.entries.retain(|e| e.selector != entry.selector);
+    registry.entries.push(entry.clone());
+    save_registry(&app, &key, &registry)?;
+    tracing::info!(
+        target: "audit",
+        selector = %entry.selector,
+        signature = %entry.signature,
+        "ABI registry entry added"
+    );
+    Ok(entry)
+}
+
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn abi_registry_remove(
+    app: AppHandle,
+    selector: String,
+    session: State<'_, SessionKey>,
+) -> Result<()> {
+    let selector = format!(
+        "0x{}",
+        selector.trim().trim_start_matches("0x").to_ascii_lowercase()
+    );
+    let key = session_key(&session)?;
+    let mut registry = load_registry(&app, &key)?;
+    let before = registry.entries.len();
+    registry.entries.retain(|e| e.selector != selector);
+    if registry.entries.len() == before {
+        return Err(VaultError::KeyNotFound(selector));
+    }
+    save_registry(&app, &key, &registry)
+}
@@ -0,0 +1,189 @@
+use crate::commands::integrity::cascade_deletes;
+use crate::commands::items::ItemStore;
+use crate::commands::keys::{KeyStore, SessionKey};
+use crate::commands::vault::{persist_vault, rewrite_stores, VaultMutex};
+use crate::crypto::encryption;
+use crate::error::{Result, VaultError};
+use crate::models::item::VaultItem;
+use crate::models::key::KeyEntry;
+use crate::models::trash::TrashedEntry;
+use crate::models::undo::{validate_undo_window, UndoBuffer, UndoKind, UndoableOperation};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Manager, State};
+use zeroize::Zeroizing;
+
+/// How often the maintenance job destroys rows whose undo window closed.
+pub const UNDO_SWEEP_INTERVAL_SECS: u64 = 15;
+
+/// Rows removed by recent purges, until their undo window closes.
+#[derive(Default)]
+pub struct UndoLog(pub Mutex<UndoBuffer>);
+
+/// What a purge removed, as sealed into the undo buffer.
+#[derive(Serialize, Deserialize)]
+struct PurgedRows {
+    keys: Vec<KeyEntry>,
+    items: Vec<VaultItem>,
+}
+
+/// Keep the rows a purge or rollback removed, encrypted under the session
+/// key, for the vault's undo window. Best effort: the removal is already
+/// committed, so a failure here only costs the chance to undo it.
+pub(crate) fn stash_purge(
+    app: &AppHandle,
+    key: &[u8; 32],
+    kind: UndoKind,
+    window_secs: u64,
+    entries: &[TrashedEntry],
+    keys: Vec<KeyEntry>,
+    items: Vec<VaultItem>,
+) {
+    if window_secs == 0 {
+        return;
+    }
+    let sealed = serde_json::to_vec(&PurgedRows { keys, items })
+        .map(Zeroizing::new)
+        .map_err(|e| e.to_string())
+        .and_then(|json| encryption::encrypt_vault(key, &json).map_err(|e| e.to_string()));
+    match sealed {
+        Ok(sealed) => {
+            let op = app.state::<UndoLog>().0.lock().unwrap().push(
+                kind,
+                entries.to_vec(),
+                sealed,
+                window_secs,
+                Utc::now(),
+            );
+            tracing::info!(
+                target: "audit",
+                op_id = %op.op_id,
+                kind = ?op.kind,
+                expires_at = %op.expires_at,
+                "removal can be undone"
+            );
+        }
+        Err(e) => tracing::warn!("could not keep the purged entries for undo: {e}"),
+    }
+}
+
+/// Operations that can still be undone, most recent first.
+#[tauri::command]
+pub fn list_undoable_operations(undo: State<'_, UndoLog>) -> Result<Vec<UndoableOperation>> {
+    Ok(undo.0.lock().unwrap().list(Utc::now()))
+}
+
+/// Put back what `op_id` removed, or the most recent operation's when
+/// `op_id` is omitted. Purged keys and items return to the trash, with
+/// their retention restarted so the background purge does not take them
+/// again straight away. Requires an unlocked vault.
+#[tauri::command]
+pub fn undo_last_operation(
+    app: AppHandle,
+    op_id: Option<String>,
+    vault: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+    undo: State<'_, UndoLog>,
+) -> Result<UndoableOperation> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let now = Utc::now();
+    let (operation, sealed) = {
+        let buffer = undo.0.lock().unwrap();
+        let found = match &op_id {
+            Some(op_id) => buffer.get(op_id, now),
+            None => buffer
+                .list(now)
+                .first()
+                .and_then(|op| buffer.get(&op.op_id, now)),
+        };
+        found.ok_or_else(|| {
+            VaultError::InvalidMetadata(match &op_id {
+                Some(op_id) => format!("operation {op_id} can no longer be undone"),
+                None => "there is no operation to undo".to_string(),
+            })
+        })?
+    };
+    let json = Zeroizing::new(
+        encryption::decrypt_vault(&session_key, &sealed)
+            .map_err(|e| VaultError::Storage(e.to_string()))?,
+    );
+    let rows: PurgedRows = serde_json::from_slice(&json)?;
+
+    let mut vault = vault.0.lock().unwrap();
+    let mut keys = keystore.0.lock().unwrap();
+    let mut store = items.0.lock().unwrap();
+    let (mut next_keys, mut next_items) = (keys.clone(), store.clone());
+    for mut key in rows.keys {
+        if !next_keys.iter().any(|k| k.id == key.id) {
+            key.metadata.trashed_at = Some(now);
+            next_keys.push(key);
+        }
+    }
+    for mut item in rows.items {
+        if !next_items.iter().any(|i| i.id == item.id) {
+            item.trashed_at = Some(now);
+            next_items.push(item);
+        }
+    }
+    rewrite_stores(&app, &mut vault, &session_key, &next_keys, &next_items)?;
+    *keys = next_keys;
+    *store = next_items;
+    undo.0.lock().unwrap().remove(&operation.op_id);
+    tracing::warn!(
+        target: "audit",
+        op_id = %operation.op_id,
+        kind = ?operation.kind,
+        entries = operation.entries.len(),
+        "destructive operation undone"
+    );
+    Ok(operation)
+}
+
+/// Set how many seconds a purge can be undone for; `0` turns undo off.
+/// Requires an unlocked vault.
+#[tauri::command]
+pub fn set_undo_window(
+    app: AppHandle,
+    secs: u64,
+    vault: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<u64> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    validate_undo_window(secs).map_err(VaultError::InvalidMetadata)?;
+    let mut vault = vault.0.lock().unwrap();
+    let mut next = vault.clone();
+    next.undo_window_secs = secs;
+    persist_vault(&app, &next)?;
+    *vault = next;
+    Ok(secs)
+}
+
+/// Periodically destroy the rows of operations whose undo window closed,
+/// so nothing is kept past it even if the vault stays unlocked, along with
+/// the rows elsewhere that pointed at them.
+pub fn spawn_undo_expiry(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(StdDuration::from_secs(UNDO_SWEEP_INTERVAL_SECS));
+        let expired = app.state::<UndoLog>().0.lock().unwrap().expire(Utc::now());
+        if !expired.is_empty() {
+            cascade_deletes(&app);
+        }
+        for op in expired {
+            tracing::info!(
+                target: "audit",
+                op_id = %op.op_id,
+                kind = ?op.kind,
+                "undo window closed"
+            );
+        }
+    });
+}
@@ -1,13 +1,33 @@
+use crate::commands::agent::{stop_agent, AgentHandle};
+use crate::commands::consent::{require_consent, ConsentTokens};
+use crate::commands::instance::create_instance_key;
+use crate::commands::items::{load_items, save_items, ItemStore};
+use crate::commands::key_drive::KeyDriveSession;
 use crate::commands::keys::{
-    atomic_write, keys_file_path, load_keys, save_keys, KeyStore, MasterSeed, SessionKey,
+    atomic_write, data_dir, keys_file_path, load_keys, save_keys, secure_remove, KeyDetailCache,
+    KeyStore, MasterSeed, SessionKey,
 };
-use crate::crypto::{encryption, kdf, mnemonic};
+use crate::commands::lease::{acquire_write_lease, holds_write_lease, release_write_lease};
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::notes::NoteCache;
+use crate::commands::notifications::notify;
+use crate::commands::pairing::{stop_pairing, BrowserPairing};
+use crate::commands::password_policy::enforce_password_strength;
+use crate::commands::undo::UndoLog;
+use crate::crypto::{data_key, encryption, kdf, mnemonic, recovery, slip39};
 use crate::error::{Result, VaultError};
-use crate::models::vault::VaultState;
+use crate::i18n::tr;
+use crate::models::consent::ConsentOperation;
+use crate::models::item::VaultItem;
+use crate::models::key::KeyEntry;
+use crate::models::notification::{SecurityEvent, FAILED_UNLOCK_STREAK};
+use crate::models::rate_limit::SensitiveOp;
+use crate::models::vault::{KdfProfile, MasterPasswordProgress, MasterPasswordStage, VaultState};
 use chrono::Utc;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 use zeroize::Zeroizing;
 
 pub struct VaultMutex(pub Mutex<VaultState>);
@@ -75,16 +95,53 @@ impl Default for UnlockState {
     }
 }
 
+/// Argon2id keys derived this session, so confirming the password for one
+/// sensitive operation after another does not re-run the KDF each time.
+/// Cleared on lock, re-key and security policy changes.
+#[derive(Default)]
+pub struct KdfCache(pub Mutex<kdf::DerivedKeyCache>);
+
+/// Bumped by every lock, so a decrypt that began before the lock can tell
+/// its session is gone.
+#[derive(Default)]
+pub struct LockEpoch(pub AtomicU64);
+
+/// A decrypt that began under the current session. Its result is only
+/// returned if [`InFlightDecrypt::finish`] succeeds.
+#[must_use = "call finish() before returning the decrypted result"]
+pub(crate) struct InFlightDecrypt(u64);
+
+impl InFlightDecrypt {
+    pub(crate) fn begin(app: &AppHandle) -> Self {
+        Self(app.state::<LockEpoch>().0.load(Ordering::SeqCst))
+    }
+
+    /// Fails if the vault was locked since [`InFlightDecrypt::begin`], so the
+    /// caller drops what it decrypted instead of handing it out.
+    pub(crate) fn finish(self, app: &AppHandle) -> Result<()> {
+        if app.state::<LockEpoch>().0.load(Ordering::SeqCst) != self.0 {
+            tracing::warn!(target: "audit", "decrypt cancelled: the vault was locked");
+            return Err(VaultError::LockedDuringOperation);
+        }
+        Ok(())
+    }
+}
+
+/// File name of the plaintext vault metadata in the data directory.
+pub const VAULT_FILE: &str = "vault.json";
+/// Emitted at each stage of [`change_master_password`].
+pub const MASTER_PASSWORD_PROGRESS_EVENT: &str = "master_password_progress";
+
 /// Resolve the on-disk path where the vault metadata (salt + verifier) is stored.
 /// Uses the shared, permission-hardened (`0700` on Unix) data directory.
 fn vault_file_path(app: &AppHandle) -> Result<PathBuf> {
-    Ok(crate::commands::keys::data_dir(app)?.join("vault.json"))
+    Ok(crate::commands::keys::data_dir(app)?.join(VAULT_FILE))
 }
 
 /// Persist the current vault metadata to disk via an atomic write. This is the
 /// single commit point that binds the active keystore file to the current
 /// salt/verifier, so a crash never leaves the two out of sync.
-fn persist_vault(app: &AppHandle, vault: &VaultState) -> Result<()> {
+pub(crate) fn persist_vault(app: &AppHandle, vault: &VaultState) -> Result<()> {
     let path = vault_file_path(app)?;
     let data = serde_json::to_string_pretty(vault)?;
     atomic_write(&path, data.as_bytes())
@@ -92,7 +149,7 @@ fn persist_vault(app: &AppHandle, vault: &VaultState) -> Result<()> {
 
 /// Load vault metadata from disk into the provided state if it exists and the
 /// in-memory state has not been initialized yet (e.g. after an app restart).
-fn load_vault_if_needed(app: &AppHandle, vault: &mut VaultState) {
+pub(crate) fn load_vault_if_needed(app: &AppHandle, vault: &mut VaultState) {
     if vault.initialized {
         return;
     }
@@ -111,10 +168,24 @@ fn load_vault_if_needed(app: &AppHandle, vault: &mut VaultState) {
 /// the YubiKey HMAC-SHA1 response when the vault has a YubiKey enrolled. This is
 /// the single place that knows how a vault's key material is derived, so unlock,
 /// change-password and (dis)enrollment all stay consistent.
-fn derive_vault_enc_key(
+pub(crate) fn derive_vault_enc_key(
     vault: &VaultState,
     password: &str,
 ) -> Result<Zeroizing<[u8; kdf::MASTER_KEY_SIZE]>> {
+    let (salt, response) = kdf_inputs(vault)?;
+    let master = Zeroizing::new(kdf::derive_master_key_with_factor_params(
+        password.as_bytes(),
+        response.as_deref(),
+        &salt,
+        vault.kdf_params(),
+    )?);
+    Ok(vault_enc_key(&master))
+}
+
+/// The salt and, with a YubiKey enrolled, the hardware response the vault's
+/// key is derived from. Asks the YubiKey every time, so a cached key never
+/// stands in for the second factor.
+fn kdf_inputs(vault: &VaultState) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
     let salt = hex::decode(&vault.salt_hex).map_err(|e| VaultError::Storage(e.to_string()))?;
     let response = if vault.yubikey_enabled {
         let challenge = hex::decode(&vault.yubikey_challenge_hex)
@@ -126,16 +197,11 @@ fn derive_vault_enc_key(
     } else {
         None
     };
-    let master = Zeroizing::new(kdf::derive_master_key_with_factor_params(
-        password.as_bytes(),
-        response.as_deref(),
-        &salt,
-        vault.kdf_params(),
-    )?);
-    Ok(Zeroizing::new(kdf::derive_encryption_key(
-        &master,
-        "vault_encryption",
-    )))
+    Ok((salt, response))
+}
+
+fn vault_enc_key(master: &[u8; kdf::MASTER_KEY_SIZE]) -> Zeroizing<[u8; kdf::MASTER_KEY_SIZE]> {
+    Zeroizing::new(kdf::derive_encryption_key(master, "vault_encryption"))
 }
 
 /// Encrypt the BIP39 master seed under the vault encryption key, returning the
@@ -170,7 +236,7 @@ fn decrypt_master_seed(enc_key: &[u8; 32], stored: &str) -> Result<Option<Zeroiz
 
 /// Decrypt the stored verifier with `enc_key` and confirm it matches the known
 /// plaintext. Returns `Ok(())` on success, [`VaultError::InvalidPassword`] otherwise.
-fn verify_enc_key(vault: &VaultState, enc_key: &[u8; 32]) -> Result<()> {
+pub(crate) fn verify_enc_key(vault: &VaultState, enc_key: &[u8; 32]) -> Result<()> {
     let parts: Vec<&str> = vault.verifier_hash_hex.split(':').collect();
     if parts.len() != 2 {
         return Err(VaultError::InvalidPassword);
@@ -184,55 +250,210 @@ fn verify_enc_key(vault: &VaultState, enc_key: &[u8; 32]) -> Result<()> {
     }
 }
 
-/// Re-encrypt the keystore and verifier under `new_enc`, writing to a fresh
-/// generation file and committing the (already-mutated) `vault` metadata in a
-/// single atomic step. The old keystore is left intact until the commit, then
-/// cleaned up. Used by change-password and YubiKey (dis)enrollment.
+/// The key a session works with: the vault's data key, unwrapped with
+/// `password_key`, or `password_key` itself for a vault from before data
+/// keys.
+pub(crate) fn session_key_for(
+    vault: &VaultState,
+    password_key: &[u8; 32],
+) -> Result<Zeroizing<[u8; 32]>> {
+    match &vault.data_key {
+        Some(wrapped) => Ok(data_key::unwrap(password_key, wrapped)?),
+        None => Ok(Zeroizing::new(*password_key)),
+    }
+}
+
+/// Confirm a session key obtained without the password, such as from a key
+/// drive, opens this vault.
+pub(crate) fn verify_session_key(vault: &VaultState, key: &[u8; 32]) -> Result<()> {
+    match &vault.data_key {
+        Some(wrapped) => data_key::check(key, wrapped).map_err(|_| VaultError::InvalidPassword),
+        None => verify_enc_key(vault, key),
+    }
+}
+
+/// Move a vault from before data keys onto one. Its password key becomes
+/// its data key, so nothing already encrypted has to be rewritten; from
+/// then on a password change only re-wraps it.
+fn adopt_data_key(app: &AppHandle, vault: &mut VaultState, password_key: &[u8; 32]) -> Result<()> {
+    let mut next = vault.clone();
+    next.data_key = Some(data_key::wrap(password_key, password_key, Utc::now())?);
+    persist_vault(app, &next)?;
+    *vault = next;
+    tracing::info!(target: "audit", "vault moved onto a data key");
+    Ok(())
+}
+
+/// Re-check the vault password (and YubiKey, if enrolled) for an already
+/// unlocked session before a sensitive operation such as exporting secret
+/// material. Nothing is mutated; a wrong password reports
+/// [`VaultError::InvalidPassword`].
+pub(crate) fn verify_password(
+    app: &AppHandle,
+    state: &State<'_, VaultMutex>,
+    password: &str,
+) -> Result<()> {
+    let mut vault = state.0.lock().unwrap();
+    load_vault_if_needed(app, &mut vault);
+    if !vault.initialized {
+        return Err(VaultError::NotInitialized);
+    }
+    let (salt, response) = kdf_inputs(&vault)?;
+    let params = vault.kdf_params();
+    let cache = app.state::<KdfCache>();
+    let cached = {
+        let cache = cache.0.lock().unwrap();
+        cache.get(password.as_bytes(), response.as_deref(), &salt, params)
+    };
+    if let Some(master) = cached {
+        return verify_enc_key(&vault, &vault_enc_key(&master));
+    }
+    let master = Zeroizing::new(kdf::derive_master_key_with_factor_params(
+        password.as_bytes(),
+        response.as_deref(),
+        &salt,
+        params,
+    )?);
+    verify_enc_key(&vault, &vault_enc_key(&master))?;
+    // Only an unlocked session keeps the key; it goes when the session does.
+    if app.state::<SessionKey>().0.lock().unwrap().is_some() {
+        cache.0.lock().unwrap().insert(
+            password.as_bytes(),
+            response.as_deref(),
+            &salt,
+            params,
+            master,
+        );
+    }
+    Ok(())
+}
+
+/// Re-wrap the data key and the verifier under `new_enc` and commit the
+/// (already-mutated) `vault` metadata in a single `vault.json` write. The
+/// stores are left as they are: they are sealed under the data key, which
+/// does not change, so an unlocked session carries on with the same key. A
+/// vault from before data keys adopts `old_enc` as its data key here. Used by
+/// change-password, KDF migration and YubiKey (dis)enrollment.
 ///
 /// Caller must set `vault.salt_hex` (and any YubiKey fields) before calling.
 fn rekey_vault(
     app: &AppHandle,
     vault: &mut VaultState,
     old_enc: &[u8; 32],
-    new_enc: Zeroizing<[u8; 32]>,
-    keystore: &State<'_, KeyStore>,
-    session: &State<'_, SessionKey>,
+    new_enc: &[u8; 32],
 ) -> Result<()> {
-    // Read the keystore using the old key (disk is authoritative).
-    let old_keys_file = vault.keys_file.clone();
-    let entries = load_keys(app, &old_keys_file, old_enc)?;
+    let key_drive_dropped = stage_rekey(vault, old_enc, new_enc)?;
+    // COMMIT: atomically write vault.json with the new verifier and wrapping.
+    persist_vault(app, vault)?;
+    finish_rekey(app, key_drive_dropped);
+    Ok(())
+}
 
-    // Write the re-encrypted keystore to a NEW generation file; the live file is
-    // untouched so the vault stays readable with the old key until we commit.
-    let new_keys_file = format!("keys-{}.enc", uuid::Uuid::new_v4());
-    save_keys(app, &new_keys_file, &new_enc, &entries)?;
+/// The in-memory half of [`rekey_vault`]: re-wrap the data key and the
+/// verifier of `vault` under `new_enc` without writing anything. Returns
+/// whether key drive unlock was switched off.
+fn stage_rekey(vault: &mut VaultState, old_enc: &[u8; 32], new_enc: &[u8; 32]) -> Result<bool> {
+    let data_key = session_key_for(vault, old_enc)?;
+    rewrap_data_key(vault, &data_key, new_enc)
+}
 
-    // Re-wrap the HD master seed under the new key (the seed itself is constant,
-    // only its encryption changes), so HD-derived keys remain stable across a
-    // password / YubiKey change. No-op for legacy vaults with no stored seed.
-    if let Some(seed) = decrypt_master_seed(old_enc, &vault.master_seed_enc_hex)? {
-        vault.master_seed_enc_hex = encrypt_master_seed(&new_enc, &seed)?;
-    }
+/// Wrap `data_key` and a new verifier under `new_enc`, in memory. Returns
+/// whether key drive unlock was switched off, as for [`stage_rekey`].
+/// Account recovery calls it directly: it has the data key from a recovery
+/// code but not the old password.
+pub(crate) fn rewrap_data_key(
+    vault: &mut VaultState,
+    data_key: &[u8; 32],
+    new_enc: &[u8; 32],
+) -> Result<bool> {
+    vault.data_key = Some(data_key::wrap(new_enc, data_key, Utc::now())?);
 
-    // COMMIT: atomically write vault.json pointing at the new verifier/keystore.
     let verifier = b"ZAP_VAULT_VERIFIER";
-    let new_ct = encryption::encrypt_vault(&new_enc, verifier)
+    let new_ct = encryption::encrypt_vault(new_enc, verifier)
         .map_err(|e| VaultError::Storage(e.to_string()))?;
     vault.verifier_hash_hex = hex::encode(new_ct.nonce) + ":" + &hex::encode(new_ct.ciphertext);
-    vault.keys_file = new_keys_file;
-    persist_vault(app, vault)?;
+    // The key drive holds the data key and still opens the vault, but would
+    // bypass a YubiKey that was just enrolled.
+    Ok(vault.yubikey_enabled && vault.key_drive.take().is_some())
+}
 
-    // Best-effort cleanup of the now-orphaned old keystore file.
-    if old_keys_file != vault.keys_file {
-        if let Ok(old_path) = keys_file_path(app, &old_keys_file) {
-            let _ = std::fs::remove_file(old_path);
+/// What follows a committed re-key: keys cached under the old salt go.
+pub(crate) fn finish_rekey(app: &AppHandle, key_drive_dropped: bool) {
+    app.state::<KdfCache>().0.lock().unwrap().clear();
+    if key_drive_dropped {
+        tracing::warn!(target: "audit", "key drive unlock switched off: a YubiKey was enrolled");
+    }
+}
+
+/// Write `entries` and `vault_items` to fresh generation files under the
+/// current key, commit `vault.json`, then wipe the previous files. Used after
+/// permanent deletions so the destroyed secrets do not survive in the old
+/// ciphertext. Returns the names of the wiped files.
+pub(crate) fn rewrite_stores(
+    app: &AppHandle,
+    vault: &mut VaultState,
+    key: &[u8; 32],
+    entries: &[KeyEntry],
+    vault_items: &[VaultItem],
+) -> Result<Vec<String>> {
+    let mut next = vault.clone();
+    next.keys_file = format!("keys-{}.enc", uuid::Uuid::new_v4());
+    save_keys(app, &next.keys_file, key, entries)?;
+    next.items_file = format!("items-{}.enc", uuid::Uuid::new_v4());
+    save_items(app, &next.items_file, key, vault_items)?;
+    persist_vault(app, &next)?;
+    let retired = [vault.keys_file.clone(), vault.items_file.clone()];
+    *vault = next;
+
+    let mut wiped = Vec::new();
+    for name in retired {
+        let path = keys_file_path(app, &name)?;
+        if path.exists() {
+            secure_remove(&path)?;
+            wiped.push(name);
         }
     }
+    Ok(wiped)
+}
 
-    // Refresh the in-memory session.
-    *keystore.0.lock().unwrap() = entries;
-    *session.0.lock().unwrap() = Some(new_enc);
-    Ok(())
+/// Files wiped by [`compact_storage`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CompactionReport {
+    pub wiped: Vec<String>,
+}
+
+/// Rewrite the keystore and item store to new generation files, wiping the
+/// old ones, and wipe any store generation or temp file that `vault.json` no
+/// longer points at (left behind by a crash or an older build that unlinked
+/// without overwriting).
+#[tauri::command]
+pub fn compact_storage(
+    app: AppHandle,
+    state: State<'_, VaultMutex>,
+    keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<CompactionReport> {
+    let session_key = {
+        let guard = session.0.lock().unwrap();
+        guard.as_ref().ok_or(VaultError::NotInitialized)?.clone()
+    };
+    let mut vault = state.0.lock().unwrap();
+    let entries = keystore.0.lock().unwrap();
+    let vault_items = items.0.lock().unwrap();
+    let mut wiped = rewrite_stores(&app, &mut vault, &session_key, &entries, &vault_items)?;
+
+    let dir = data_dir(&app)?;
+    let listing = std::fs::read_dir(&dir).map_err(|e| VaultError::Storage(e.to_string()))?;
+    for dir_entry in listing.flatten() {
+        let name = dir_entry.file_name().to_string_lossy().into_owned();
+        if vault.is_retired_store_file(&name) {
+            secure_remove(&dir_entry.path())?;
+            wiped.push(name);
+        }
+    }
+    tracing::info!(target: "audit", wiped = wiped.len(), "vault storage compacted");
+    Ok(CompactionReport { wiped })
 }
 
 /// Returns whether a vault has already been created on this machine.
@@ -270,20 +491,27 @@ pub fn yubikey_status(app: AppHandle, state: State<'_, VaultMutex>) -> Result<Yu
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct CreateVaultResult {
     pub mnemonic: String,
+    pub language: mnemonic::MnemonicLanguage,
+    /// One-time codes that can reset a forgotten password. Shown once, like
+    /// the mnemonic; only their hashes are kept.
+    pub recovery_codes: Vec<String>,
 }
 
 /// Initialize a fresh vault around an existing 64-byte BIP39 master seed: derive
 /// key material with the high Argon2 profile, store the encrypted verifier +
 /// master seed + KDF params, persist `vault.json`, and open the session. Shared
 /// by `create_vault` (new random mnemonic) and `restore_from_mnemonic`.
+/// With `recovery_codes`, also issues escrowed recovery codes and returns
+/// them.
 fn init_vault_with_seed(
     app: &AppHandle,
     password: &str,
     seed: &[u8; mnemonic::SEED_SIZE],
+    recovery_codes: bool,
     vault: &mut VaultState,
     session: &State<'_, SessionKey>,
     master_seed: &State<'_, MasterSeed>,
-) -> Result<()> {
+) -> Result<Vec<String>> {
     let params = kdf::KdfParams::high();
     let salt = kdf::generate_salt();
     let master_key = Zeroizing::new(kdf::derive_master_key_with_params(
@@ -303,21 +531,37 @@ fn init_vault_with_seed(
     vault.argon2_memory_kib = params.memory_kib;
     vault.argon2_iterations = params.iterations;
     vault.argon2_parallelism = params.parallelism;
-    vault.master_seed_enc_hex = encrypt_master_seed(&enc_key, seed)?;
+    let data_key = data_key::generate_data_key();
+    vault.data_key = Some(data_key::wrap(&enc_key, &data_key, Utc::now())?);
+    vault.master_seed_enc_hex = encrypt_master_seed(&data_key, seed)?;
+    let mut codes = Vec::new();
+    if recovery_codes {
+        let (set, plain) = recovery::issue(Some(&data_key), Utc::now())?;
+        vault.recovery_codes = Some(set);
+        codes = plain.iter().map(|c| c.to_string()).collect();
+    }
     vault.initialized = true;
 
-    persist_vault(app, vault)?;
+    acquire_write_lease(app)?;
+    if let Err(e) = persist_vault(app, vault) {
+        release_write_lease(app);
+        return Err(e);
+    }
 
     // Open the freshly created vault for this session.
     *master_seed.0.lock().unwrap() = Some(Zeroizing::new(*seed));
-    *session.0.lock().unwrap() = Some(enc_key);
-    Ok(())
+    *session.0.lock().unwrap() = Some(data_key);
+    if let Err(e) = create_instance_key(app, seed) {
+        tracing::warn!("instance identity will be created on first use: {e}");
+    }
+    Ok(codes)
 }
 
 #[tauri::command]
 pub fn create_vault(
     app: AppHandle,
     password: String,
+    language: Option<mnemonic::MnemonicLanguage>,
     state: State<'_, VaultMutex>,
     session: State<'_, SessionKey>,
     master_seed: State<'_, MasterSeed>,
@@ -328,26 +572,55 @@ pub fn create_vault(
     if vault.initialized {
         return Err(VaultError::AlreadyUnlocked);
     }
+    enforce_password_strength(&vault, &password)?;
 
     // Generate a fresh 24-word BIP39 mnemonic and its standard 64-byte seed.
-    let phrase = mnemonic::generate_mnemonic();
+    let language = language.unwrap_or_default();
+    let phrase = mnemonic::generate_mnemonic_in(language);
     let seed =
         mnemonic::mnemonic_to_seed(&phrase).map_err(|e| VaultError::Storage(e.to_string()))?;
 
-    init_vault_with_seed(&app, &password, &seed, &mut vault, &session, &master_seed)?;
+    let recovery_codes = init_vault_with_seed(
+        &app,
+        &password,
+        &seed,
+        true,
+        &mut vault,
+        &session,
+        &master_seed,
+    )?;
+
+    Ok(CreateVaultResult {
+        mnemonic: phrase,
+        language,
+        recovery_codes,
+    })
+}
 
-    Ok(CreateVaultResult { mnemonic: phrase })
+/// Check a recovery phrase as it is typed, before `restore_from_mnemonic`:
+/// reports the wordlist it matched, the position of the wrong word and
+/// near-miss replacements. `language` pins the wordlist; otherwise it is
+/// detected from the words.
+#[tauri::command]
+pub fn check_recovery_phrase(
+    phrase: String,
+    language: Option<mnemonic::MnemonicLanguage>,
+) -> mnemonic::PhraseCheck {
+    mnemonic::check_phrase(&phrase, language)
 }
 
 /// Restore a vault from an existing BIP39 mnemonic (recovery). Refuses to run if
 /// a vault already exists on disk — the user must wipe it first (see the reset
 /// instructions). Re-establishes the same HD master seed; the user then
 /// regenerates their keys at the same paths to recover identical keys.
+/// The phrase may be in any BIP39 language; `language` pins the wordlist
+/// instead of detecting it.
 #[tauri::command]
 pub fn restore_from_mnemonic(
     app: AppHandle,
     mnemonic_phrase: String,
     password: String,
+    language: Option<mnemonic::MnemonicLanguage>,
     state: State<'_, VaultMutex>,
     session: State<'_, SessionKey>,
     master_seed: State<'_, MasterSeed>,
@@ -357,24 +630,139 @@ pub fn restore_from_mnemonic(
     if vault.initialized {
         return Err(VaultError::AlreadyUnlocked);
     }
+    enforce_password_strength(&vault, &password)?;
 
     let phrase = mnemonic_phrase.trim();
-    mnemonic::validate_mnemonic(phrase)
-        .map_err(|e| VaultError::Storage(format!("invalid recovery phrase: {e}")))?;
+    match language {
+        Some(language) => mnemonic::validate_mnemonic_in(phrase, language),
+        None => mnemonic::validate_mnemonic(phrase),
+    }
+    .map_err(|e| VaultError::Storage(format!("invalid recovery phrase: {e}")))?;
     let seed =
         mnemonic::mnemonic_to_seed(phrase).map_err(|e| VaultError::Storage(e.to_string()))?;
 
-    init_vault_with_seed(&app, &password, &seed, &mut vault, &session, &master_seed)?;
+    init_vault_with_seed(
+        &app,
+        &password,
+        &seed,
+        false,
+        &mut vault,
+        &session,
+        &master_seed,
+    )?;
 
     Ok("Vault restored from recovery phrase".to_string())
 }
 
+/// Split the master seed into SLIP-39 shares: any `group_threshold` of the
+/// `groups`, each with its member threshold of shares, restore the vault
+/// through `restore_from_slip39`. With a non-empty `passphrase`, restoring
+/// also needs it. Requires the vault password, a `consent_token` from
+/// `request_consent` and an unlocked vault.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_slip39_shares(
+    app: AppHandle,
+    password: String,
+    group_threshold: u8,
+    groups: Vec<slip39::Slip39Group>,
+    passphrase: Option<String>,
+    consent_token: String,
+    state: State<'_, VaultMutex>,
+    master_seed: State<'_, MasterSeed>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<Vec<Vec<String>>> {
+    enforce(&state, &limiter, SensitiveOp::Export)?;
+    let passphrase = Zeroizing::new(passphrase.unwrap_or_default());
+    if !passphrase.is_empty() {
+        enforce_password_strength(&state.0.lock().unwrap(), &passphrase)?;
+    }
+    verify_password(&app, &state, &password)?;
+    require_consent(
+        &app,
+        &consent_token,
+        &ConsentOperation::Slip39Shares {
+            group_threshold,
+            groups: groups.clone(),
+        },
+    )?;
+    let seed = master_seed
+        .0
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or(VaultError::NotInitialized)?;
+    let shares = slip39::generate_mnemonics(
+        group_threshold,
+        &groups,
+        seed.as_slice(),
+        &passphrase,
+        slip39::DEFAULT_ITERATION_EXPONENT,
+    )?;
+    tracing::warn!(
+        target: "audit",
+        group_threshold,
+        groups = groups.len(),
+        passphrase = !passphrase.is_empty(),
+        "master seed split into SLIP-39 shares"
+    );
+    Ok(shares
+        .into_iter()
+        .map(|group| group.iter().map(|m| m.to_string()).collect())
+        .collect())
+}
+
+/// Restore a vault from SLIP-39 shares made by `generate_slip39_shares`, in
+/// any order, with the passphrase they were made with. Refuses to run if a
+/// vault already exists on disk, like `restore_from_mnemonic`.
+#[tauri::command]
+pub fn restore_from_slip39(
+    app: AppHandle,
+    shares: Vec<String>,
+    password: String,
+    passphrase: Option<String>,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    master_seed: State<'_, MasterSeed>,
+) -> Result<String> {
+    let mut vault = state.0.lock().unwrap();
+    load_vault_if_needed(&app, &mut vault);
+    if vault.initialized {
+        return Err(VaultError::AlreadyUnlocked);
+    }
+    enforce_password_strength(&vault, &password)?;
+
+    let secret = slip39::combine_mnemonics(&shares, passphrase.as_deref().unwrap_or_default())?;
+    let seed: Zeroizing<[u8; mnemonic::SEED_SIZE]> =
+        Zeroizing::new(secret.as_slice().try_into().map_err(|_| {
+            VaultError::InvalidMetadata(format!(
+                "the shares hold a {}-byte secret, not a {}-byte vault seed",
+                secret.len(),
+                mnemonic::SEED_SIZE
+            ))
+        })?);
+
+    init_vault_with_seed(
+        &app,
+        &password,
+        &seed,
+        false,
+        &mut vault,
+        &session,
+        &master_seed,
+    )?;
+
+    Ok("Vault restored from SLIP-39 shares".to_string())
+}
+
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn unlock_vault(
     app: AppHandle,
     password: String,
     state: State<'_, VaultMutex>,
     keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
     session: State<'_, SessionKey>,
     master_seed: State<'_, MasterSeed>,
     throttle: State<'_, UnlockState>,
@@ -396,34 +784,97 @@ pub fn unlock_vault(
     match verify_enc_key(&vault, &enc_key) {
         Ok(()) => {
             // Password (and YubiKey, if enrolled) correct: clear the throttle,
-            // load the keystore + HD master seed, then open the session.
+            // then open the session.
             throttle.0.lock().unwrap().record_success();
-            let entries = load_keys(&app, &vault.keys_file, &enc_key)?;
-            let seed = decrypt_master_seed(&enc_key, &vault.master_seed_enc_hex)?;
-            *keystore.0.lock().unwrap() = entries;
-            *master_seed.0.lock().unwrap() = seed;
-            *session.0.lock().unwrap() = Some(enc_key);
+            let session_key = session_key_for(&vault, &enc_key)?;
+            open_session(
+                &app,
+                &mut vault,
+                session_key,
+                &keystore,
+                &items,
+                &session,
+                &master_seed,
+            )?;
             Ok(true)
         }
         Err(_) => {
-            throttle.0.lock().unwrap().record_failure(now);
+            record_unlock_failure(&app, &vault, &throttle, now);
             Err(VaultError::InvalidPassword)
         }
     }
 }
 
+/// Load the keystore, item store and HD master seed with a verified
+/// `enc_key`, the vault's data key, and make it the session key. A vault
+/// from before data keys adopts one here. Shared by every unlock path.
+pub(crate) fn open_session(
+    app: &AppHandle,
+    vault: &mut VaultState,
+    enc_key: Zeroizing<[u8; 32]>,
+    keystore: &State<'_, KeyStore>,
+    items: &State<'_, ItemStore>,
+    session: &State<'_, SessionKey>,
+    master_seed: &State<'_, MasterSeed>,
+) -> Result<()> {
+    let entries = load_keys(app, &vault.keys_file, &enc_key)?;
+    let vault_items = load_items(app, &vault.items_file, &enc_key)?;
+    let seed = decrypt_master_seed(&enc_key, &vault.master_seed_enc_hex)?;
+    acquire_write_lease(app)?;
+    if vault.data_key.is_none() {
+        // Best effort: the vault opens either way and tries again next time.
+        if let Err(e) = adopt_data_key(app, vault, &enc_key) {
+            tracing::warn!("vault stays on its password key for now: {e}");
+        }
+    }
+    *keystore.0.lock().unwrap() = entries;
+    *items.0.lock().unwrap() = vault_items;
+    *master_seed.0.lock().unwrap() = seed;
+    *session.0.lock().unwrap() = Some(enc_key);
+    Ok(())
+}
+
+/// Count a failed unlock against the throttle and notify once failures
+/// reach a streak.
+pub(crate) fn record_unlock_failure(
+    app: &AppHandle,
+    vault: &VaultState,
+    throttle: &State<'_, UnlockState>,
+    now: u64,
+) {
+    let failures = {
+        let mut throttle = throttle.0.lock().unwrap();
+        throttle.record_failure(now);
+        throttle.failures
+    };
+    if failures >= FAILED_UNLOCK_STREAK {
+        notify(
+            app,
+            &vault.notifications,
+            vault.locale(),
+            SecurityEvent::FailedUnlockStreak,
+            &tr(
+                vault.locale(),
+                "failed-unlock-streak-body",
+                &[("count", failures.into())],
+            ),
+        );
+    }
+}
+
 /// Re-key the vault under a new password. Verifies the old password, then
-/// re-wraps both the vault verifier and the encrypted keystore with a key
-/// derived from the new password (and a fresh salt).
+/// re-wraps both the vault verifier and the data key with a key derived from
+/// the new password (and a fresh salt).
 #[tauri::command]
 pub fn change_password(
     app: AppHandle,
     old_password: String,
     new_password: String,
     state: State<'_, VaultMutex>,
-    keystore: State<'_, KeyStore>,
-    session: State<'_, SessionKey>,
+    throttle: State<'_, UnlockState>,
 ) -> Result<String> {
+    let now = Utc::now().timestamp() as u64;
+    throttle.0.lock().unwrap().check(now)?;
     let mut vault = state.0.lock().unwrap();
     load_vault_if_needed(&app, &mut vault);
     if !vault.initialized {
@@ -431,8 +882,14 @@ pub fn change_password(
     }
 
     // 1. Verify the old password (folding in the YubiKey response if enrolled).
+    //    A wrong one counts towards the unlock throttle.
     let old_enc = derive_vault_enc_key(&vault, &old_password)?;
-    verify_enc_key(&vault, &old_enc)?;
+    if let Err(e) = verify_enc_key(&vault, &old_enc) {
+        record_unlock_failure(&app, &vault, &throttle, now);
+        return Err(e);
+    }
+    throttle.0.lock().unwrap().record_success();
+    enforce_password_strength(&vault, &new_password)?;
 
     // 2. Derive new key material from a fresh salt. The YubiKey factor (if any)
     //    is unchanged, so the same challenge/slot still apply to the new salt.
@@ -440,23 +897,222 @@ pub fn change_password(
     vault.salt_hex = hex::encode(new_salt);
     let new_enc = derive_vault_enc_key(&vault, &new_password)?;
 
-    // 3. Re-encrypt the keystore + verifier and atomically commit.
-    rekey_vault(&app, &mut vault, &old_enc, new_enc, &keystore, &session)?;
+    // 3. Re-wrap the data key + verifier and atomically commit.
+    rekey_vault(&app, &mut vault, &old_enc, &new_enc)?;
 
     Ok("Password changed successfully".to_string())
 }
 
+/// Outcome of [`change_master_password`]: what the verification pass opened
+/// under the new password. Counts only; no secret material.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MasterPasswordChange {
+    pub keys: usize,
+    pub items: usize,
+    pub master_seed: bool,
+    /// Key drive unlock was switched off because a YubiKey is enrolled.
+    pub key_drive_dropped: bool,
+}
+
+/// Read `vault.json` as it is on disk, bypassing the in-memory state.
+fn read_vault_file(app: &AppHandle) -> Result<VaultState> {
+    let data = std::fs::read_to_string(vault_file_path(app)?)
+        .map_err(|e| VaultError::Storage(e.to_string()))?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+/// Open everything a re-keyed `vault` protects the way the next unlock
+/// will: the verifier and the data key with `new_enc`, then the keystore,
+/// item store and master seed with that data key, which must be
+/// `data_key`. Returns the counts for [`MasterPasswordChange`].
+pub(crate) fn verify_rekeyed(
+    app: &AppHandle,
+    vault: &VaultState,
+    new_enc: &[u8; 32],
+    data_key: &[u8; 32],
+) -> Result<(usize, usize, bool)> {
+    verify_enc_key(vault, new_enc)?;
+    let unwrapped = session_key_for(vault, new_enc)?;
+    if *unwrapped != *data_key {
+        return Err(VaultError::Storage(
+            "the re-wrapped data key does not match".into(),
+        ));
+    }
+    Ok((
+        load_keys(app, &vault.keys_file, &unwrapped)?.len(),
+        load_items(app, &vault.items_file, &unwrapped)?.len(),
+        decrypt_master_seed(&unwrapped, &vault.master_seed_enc_hex)?.is_some(),
+    ))
+}
+
+/// Change the master password as one checked transaction, emitting
+/// [`MASTER_PASSWORD_PROGRESS_EVENT`] at each stage. The old password
+/// unwraps the data key, which every key, item and the master seed are
+/// sealed under, so nothing below it needs its own password and nothing is
+/// re-encrypted: the data key is re-wrapped under the new password with a
+/// fresh salt. The staged metadata is verified before it is written, and
+/// `vault.json` is read back and verified again after. If the write or the
+/// second check fails the previous metadata is written back and the stage
+/// is reported as `rolled_back`. A wrong old password counts towards the
+/// unlock throttle. Requires an unlocked vault.
+#[tauri::command]
+pub fn change_master_password(
+    app: AppHandle,
+    old_password: String,
+    new_password: String,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+    throttle: State<'_, UnlockState>,
+) -> Result<MasterPasswordChange> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    let now = Utc::now().timestamp() as u64;
+    throttle.0.lock().unwrap().check(now)?;
+    let progress = |stage: MasterPasswordStage| {
+        let _ = app.emit(
+            MASTER_PASSWORD_PROGRESS_EVENT,
+            MasterPasswordProgress::from(stage),
+        );
+    };
+    let mut vault = state.0.lock().unwrap();
+
+    progress(MasterPasswordStage::VerifyingOldPassword);
+    let old_enc = derive_vault_enc_key(&vault, &old_password)?;
+    if let Err(e) = verify_enc_key(&vault, &old_enc) {
+        record_unlock_failure(&app, &vault, &throttle, now);
+        return Err(e);
+    }
+    throttle.0.lock().unwrap().record_success();
+    enforce_password_strength(&vault, &new_password)?;
+    let data_key = session_key_for(&vault, &old_enc)?;
+
+    progress(MasterPasswordStage::DerivingNewKey);
+    let mut next = vault.clone();
+    next.salt_hex = hex::encode(kdf::generate_salt());
+    let new_enc = derive_vault_enc_key(&next, &new_password)?;
+
+    progress(MasterPasswordStage::Rewrapping);
+    let key_drive_dropped = stage_rekey(&mut next, &old_enc, &new_enc)?;
+
+    progress(MasterPasswordStage::Verifying);
+    let (keys, items, master_seed) = verify_rekeyed(&app, &next, &new_enc, &data_key)?;
+
+    progress(MasterPasswordStage::Committing);
+    let committed = persist_vault(&app, &next)
+        .and_then(|()| read_vault_file(&app))
+        .and_then(|on_disk| verify_rekeyed(&app, &on_disk, &new_enc, &data_key));
+    if let Err(e) = committed {
+        let restored = persist_vault(&app, &vault);
+        progress(MasterPasswordStage::RolledBack);
+        tracing::warn!(
+            target: "audit",
+            error = %e,
+            restored = restored.is_ok(),
+            "master password change rolled back"
+        );
+        return Err(match restored {
+            Ok(()) => VaultError::Storage(format!("password change rolled back: {e}")),
+            Err(r) => VaultError::Storage(format!(
+                "password change failed ({e}) and the previous vault.json could not be restored: {r}"
+            )),
+        });
+    }
+    *vault = next;
+    finish_rekey(&app, key_drive_dropped);
+
+    progress(MasterPasswordStage::Complete);
+    tracing::info!(target: "audit", keys, items, "master password changed");
+    Ok(MasterPasswordChange {
+        keys,
+        items,
+        master_seed,
+        key_drive_dropped,
+    })
+}
+
+/// Outcome of [`migrate_encryption`]. Counts only; no secret material.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EncryptionMigration {
+    pub dry_run: bool,
+    /// Whether the vault was re-keyed (or, on a dry run, would be).
+    pub upgraded: bool,
+    pub from: KdfProfile,
+    pub to: KdfProfile,
+    /// Records decrypted with the data key before it is re-wrapped.
+    pub keys: usize,
+    pub items: usize,
+    pub master_seed: bool,
+}
+
+/// Re-key a vault still on an older KDF profile onto the current one, keeping
+/// the same password (and YubiKey, if enrolled). Every record already uses
+/// the AES-256-GCM envelope; only the key derivation ages, so that is what
+/// migrates. The keystore, item store, master seed and verifier are decrypted
+/// first — a dry run stops there, proving the migration would succeed — then
+/// the data key and verifier are re-wrapped and committed in one
+/// `vault.json` write, like a password change. Requires an unlocked vault.
+#[tauri::command]
+pub fn migrate_encryption(
+    app: AppHandle,
+    password: String,
+    dry_run: bool,
+    state: State<'_, VaultMutex>,
+    session: State<'_, SessionKey>,
+) -> Result<EncryptionMigration> {
+    if session.0.lock().unwrap().is_none() {
+        return Err(VaultError::NotInitialized);
+    }
+    let mut vault = state.0.lock().unwrap();
+    let old_enc = derive_vault_enc_key(&vault, &password)?;
+    verify_enc_key(&vault, &old_enc)?;
+    let data_key = session_key_for(&vault, &old_enc)?;
+
+    let report = EncryptionMigration {
+        dry_run,
+        upgraded: vault.needs_kdf_upgrade(),
+        from: vault.kdf_profile(),
+        to: KdfProfile::current(),
+        keys: load_keys(&app, &vault.keys_file, &data_key)?.len(),
+        items: load_items(&app, &vault.items_file, &data_key)?.len(),
+        master_seed: decrypt_master_seed(&data_key, &vault.master_seed_enc_hex)?.is_some(),
+    };
+    if dry_run || !report.upgraded {
+        return Ok(report);
+    }
+
+    // Stage on a copy so a failed re-key leaves the live metadata untouched.
+    let mut next = vault.clone();
+    next.salt_hex = hex::encode(kdf::generate_salt());
+    next.kdf_version = report.to.version;
+    next.argon2_memory_kib = report.to.memory_kib;
+    next.argon2_iterations = report.to.iterations;
+    next.argon2_parallelism = report.to.parallelism;
+    let new_enc = derive_vault_enc_key(&next, &password)?;
+    rekey_vault(&app, &mut next, &old_enc, &new_enc)?;
+    *vault = next;
+    tracing::info!(
+        target: "audit",
+        from_memory_kib = report.from.memory_kib,
+        to_memory_kib = report.to.memory_kib,
+        keys = report.keys,
+        items = report.items,
+        "vault re-keyed onto the current KDF profile"
+    );
+    Ok(report)
+}
+
 /// Enroll a YubiKey as a second factor. Verifies the current (password-only)
 /// vault, then re-keys it so the master key derivation also requires the
-/// YubiKey's HMAC-SHA1 response to a freshly generated challenge.
+/// YubiKey's HMAC-SHA1 response to a freshly generated challenge. Recovery
+/// codes lose their escrow copy, since it opens the data key without the
+/// YubiKey; codes with escrow have to be issued again on purpose.
 #[tauri::command]
 pub fn enroll_yubikey(
     app: AppHandle,
     password: String,
     slot: u8,
     state: State<'_, VaultMutex>,
-    keystore: State<'_, KeyStore>,
-    session: State<'_, SessionKey>,
 ) -> Result<String> {
     let mut vault = state.0.lock().unwrap();
     load_vault_if_needed(&app, &mut vault);
@@ -482,10 +1138,20 @@ pub fn enroll_yubikey(
     vault.yubikey_enabled = true;
     vault.yubikey_slot = slot;
     vault.yubikey_challenge_hex = hex::encode(challenge);
+    let escrow_dropped = vault
+        .recovery_codes
+        .as_mut()
+        .is_some_and(|codes| codes.drop_escrow());
     let new_enc = derive_vault_enc_key(&vault, &password)?;
 
-    // 4. Re-encrypt + atomically commit.
-    rekey_vault(&app, &mut vault, &old_enc, new_enc, &keystore, &session)?;
+    // 4. Re-wrap + atomically commit.
+    rekey_vault(&app, &mut vault, &old_enc, &new_enc)?;
+    if escrow_dropped {
+        tracing::warn!(
+            target: "audit",
+            "recovery code escrow removed on YubiKey enrollment"
+        );
+    }
 
     Ok("YubiKey enrolled successfully".to_string())
 }
@@ -497,8 +1163,6 @@ pub fn disable_yubikey(
     app: AppHandle,
     password: String,
     state: State<'_, VaultMutex>,
-    keystore: State<'_, KeyStore>,
-    session: State<'_, SessionKey>,
 ) -> Result<String> {
     let mut vault = state.0.lock().unwrap();
     load_vault_if_needed(&app, &mut vault);
@@ -520,8 +1184,8 @@ pub fn disable_yubikey(
     vault.yubikey_challenge_hex = String::new();
     let new_enc = derive_vault_enc_key(&vault, &password)?;
 
-    // 3. Re-encrypt + atomically commit.
-    rekey_vault(&app, &mut vault, &old_enc, new_enc, &keystore, &session)?;
+    // 3. Re-wrap + atomically commit.
+    rekey_vault(&app, &mut vault, &old_enc, &new_enc)?;
 
     Ok("YubiKey disabled successfully".to_string())
 }
@@ -633,18 +1297,43 @@ pub fn yk_erase_slot(app: AppHandle, slot: u8, state: State<'_, VaultMutex>) ->
 
 #[tauri::command]
 pub fn lock_vault(
+    app: AppHandle,
     state: State<'_, VaultMutex>,
     keystore: State<'_, KeyStore>,
+    items: State<'_, ItemStore>,
     session: State<'_, SessionKey>,
     master_seed: State<'_, MasterSeed>,
+    limiter: State<'_, RateLimiter>,
 ) -> Result<()> {
     let vault = state.0.lock().unwrap();
     if !vault.initialized {
         return Err(VaultError::NotInitialized);
     }
-    // Drop the session key, HD master seed, and decrypted keys from memory.
-    *session.0.lock().unwrap() = None;
+    app.state::<LockEpoch>().0.fetch_add(1, Ordering::SeqCst);
+    limiter.0.lock().unwrap().clear_reauth();
+    *app.state::<KeyDriveSession>().0.lock().unwrap() = None;
+    stop_agent(&app.state::<AgentHandle>());
+    stop_pairing(&app.state::<BrowserPairing>());
+    // Usage counters are only tracked in memory while unlocked; write them back
+    // before the session key goes away. The vault is locked even if this fails.
+    // Not after losing the write lease: the files belong to its new holder.
+    let key = session.0.lock().unwrap().take();
+    let flushed = match key {
+        Some(key) if holds_write_lease(&app) => {
+            save_keys(&app, &vault.keys_file, &key, &keystore.0.lock().unwrap())
+                .and_then(|_| save_items(&app, &vault.items_file, &key, &items.0.lock().unwrap()))
+        }
+        _ => Ok(()),
+    };
+    release_write_lease(&app);
+    // Drop the session key, HD master seed, and decrypted keys/items from memory.
     *master_seed.0.lock().unwrap() = None;
     keystore.0.lock().unwrap().clear();
-    Ok(())
+    items.0.lock().unwrap().clear();
+    app.state::<NoteCache>().0.lock().unwrap().clear();
+    app.state::<KeyDetailCache>().0.lock().unwrap().clear();
+    app.state::<KdfCache>().0.lock().unwrap().clear();
+    app.state::<ConsentTokens>().0.lock().unwrap().clear();
+    app.state::<UndoLog>().0.lock().unwrap().clear();
+    flushed
 }
@@ -0,0 +1,175 @@
+use crate::commands::consent::require_consent;
+use crate::commands::items::{push_item, ItemStore};
+use crate::commands::keys::SessionKey;
+use crate::commands::limits::{enforce, RateLimiter};
+use crate::commands::policy::{enforce_item_export, enforce_policy, export_watermark};
+use crate::commands::vault::{verify_password, VaultMutex};
+use crate::crypto::wireguard;
+use crate::error::{Result, VaultError};
+use crate::models::consent::ConsentOperation;
+use crate::models::item::{ItemPayload, VaultItem, VaultItemPublic, WireGuardItem, WireGuardPeer};
+use crate::models::policy::PolicyGate;
+use crate::models::rate_limit::SensitiveOp;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+/// Remote peer as entered in the UI. Pre-shared keys are never typed in; they
+/// are generated server-side when `generate_preshared_key` is set.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WireGuardPeerRequest {
+    pub public_key: String,
+    pub endpoint: Option<String>,
+    pub allowed_ips: Vec<String>,
+    pub persistent_keepalive: Option<u16>,
+    #[serde(default)]
+    pub generate_preshared_key: bool,
+}
+
+/// Interface settings for a new tunnel. The interface keypair is generated in
+/// Rust, so no private key is ever supplied over IPC.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WireGuardConfigRequest {
+    pub addresses: Vec<String>,
+    #[serde(default)]
+    pub dns: Vec<String>,
+    pub listen_port: Option<u16>,
+    pub mtu: Option<u16>,
+    pub peers: Vec<WireGuardPeerRequest>,
+}
+
+/// Run `f` against the stored WireGuard config with `item_id`, counting it as a use.
+fn with_wireguard<T>(
+    items: &State<'_, ItemStore>,
+    item_id: &str,
+    f: impl FnOnce(&WireGuardItem) -> Result<T>,
+) -> Result<T> {
+    let mut store = items.0.lock().unwrap();
+    match store
+        .iter_mut()
+        .find(|i| i.id == item_id && i.trashed_at.is_none())
+    {
+        Some(VaultItem {
+            payload: ItemPayload::WireGuard(wg),
+            usage,
+            ..
+        }) => {
+            usage.touch(Utc::now());
+            f(wg)
+        }
+        _ => Err(VaultError::KeyNotFound(item_id.to_string())),
+    }
+}
+
+/// Create a WireGuard tunnel config with a freshly generated interface keypair
+/// and store it encrypted in the item store. Only the public view is returned;
+/// its `public_key` is what gets registered on the VPN server.
+#[tauri::command]
+pub fn wireguard_create_config(
+    app: AppHandle,
+    label: Option<String>,
+    config: WireGuardConfigRequest,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<VaultItemPublic> {
+    enforce_policy(&vault, PolicyGate::KeyGeneration)?;
+    let mut peers = Vec::with_capacity(config.peers.len());
+    for p in config.peers {
+        wireguard::validate_key(&p.public_key)?;
+        peers.push(WireGuardPeer {
+            public_key: p.public_key,
+            preshared_key: p
+                .generate_preshared_key
+                .then(|| wireguard::generate_preshared_key().to_string()),
+            endpoint: p.endpoint,
+            allowed_ips: p.allowed_ips,
+            persistent_keepalive: p.persistent_keepalive,
+        });
+    }
+
+    let kp = wireguard::generate_keypair();
+    let item = VaultItem::new(
+        label,
+        ItemPayload::WireGuard(WireGuardItem {
+            private_key: kp.private_key.to_string(),
+            public_key: kp.public_key,
+            addresses: config.addresses,
+            dns: config.dns,
+            listen_port: config.listen_port,
+            mtu: config.mtu,
+            peers,
+        }),
+    );
+
+    let public = item.to_public();
+    push_item(&app, &vault, &items, &session, item)?;
+    Ok(public)
+}
+
+/// Import an existing `wg-quick` config (e.g. from a VPN provider) into the vault.
+#[tauri::command]
+pub fn wireguard_import_config(
+    app: AppHandle,
+    label: Option<String>,
+    config_text: String,
+    vault: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    session: State<'_, SessionKey>,
+) -> Result<VaultItemPublic> {
+    let wg = wireguard::parse_config(&config_text)?;
+    let item = VaultItem::new(label, ItemPayload::WireGuard(wg));
+    let public = item.to_public();
+    push_item(&app, &vault, &items, &session, item)?;
+    Ok(public)
+}
+
+#[tauri::command]
+pub fn wireguard_list_configs(items: State<'_, ItemStore>) -> Result<Vec<VaultItemPublic>> {
+    let store = items.0.lock().unwrap();
+    Ok(store
+        .iter()
+        .filter(|i| i.trashed_at.is_none() && matches!(i.payload, ItemPayload::WireGuard(_)))
+        .map(|i| i.to_public())
+        .collect())
+}
+
+/// Export the full `wg-quick` config, including the interface private key.
+/// The frontend renders this text as the provisioning QR that the WireGuard
+/// mobile apps scan. Since it carries secret material the vault password is
+/// re-verified first and a consent token is required, as for SSH private
+/// key export.
+#[tauri::command]
+pub fn wireguard_export_config(
+    app: AppHandle,
+    item_id: String,
+    password: String,
+    consent_token: String,
+    state: State<'_, VaultMutex>,
+    items: State<'_, ItemStore>,
+    limiter: State<'_, RateLimiter>,
+) -> Result<String> {
+    enforce(&state, &limiter, SensitiveOp::Export)?;
+    enforce_policy(&state, PolicyGate::PlaintextExport)?;
+    enforce_item_export(&items, std::slice::from_ref(&item_id))?;
+    verify_password(&app, &state, &password)?;
+    require_consent(
+        &app,
+        &consent_token,
+        &ConsentOperation::WireguardConfigExport {
+            item_id: item_id.clone(),
+        },
+    )?;
+    let mark = export_watermark(&app)?;
+    let config = with_wireguard(&items, &item_id, |wg| {
+        Ok(mark.comment_line() + &wireguard::render_config(wg))
+    })?;
+    tracing::info!(
+        target: "audit",
+        item_id = %item_id,
+        profile = %mark.profile,
+        instance_id = %mark.instance_id,
+        "wireguard config exported"
+    );
+    Ok(config)
+}
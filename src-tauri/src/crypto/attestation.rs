@@ -0,0 +1,238 @@
+use crate::crypto::instance::{self, InstanceError, PURPOSE_KEY_ATTESTATION};
+use crate::crypto::mldsa87::{self, CryptoError, PublicKey, SecretKey, Signature};
+use crate::models::attestation::{KeyAttestation, SignedKeyAttestation};
+use crate::models::backup::BackupFile;
+use thiserror::Error;
+use zeroize::Zeroize;
+
+/// Current key attestation document version.
+pub const ATTESTATION_VERSION: u32 = 1;
+
+/// BLAKE3 `derive_key` context for the vault identity key. Outside the HD tree
+/// so no `generate_key` path can collide with it.
+const IDENTITY_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 vault identity key v1";
+/// Signature domain, so an attestation signature cannot be passed off as a
+/// signature over anything else.
+const ATTESTATION_DOMAIN: &[u8] = b"ZAP_KEY_ATTESTATION_V1";
+/// Signature domain for backup manifest entries.
+const BACKUP_FILE_DOMAIN: &[u8] = b"ZAP_BACKUP_FILE_V1";
+
+#[derive(Debug, Error)]
+pub enum AttestationError {
+    #[error("malformed attestation: {0}")]
+    Malformed(String),
+    #[error("unsupported attestation version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("attestation signature is invalid")]
+    BadSignature,
+    #[error("signature error: {0}")]
+    Crypto(#[from] CryptoError),
+    #[error(transparent)]
+    Instance(#[from] InstanceError),
+}
+
+/// The vault's long-term signing identity, derived deterministically from the
+/// HD master seed so it survives re-keying and is restored with the mnemonic.
+pub fn vault_identity(master_seed: &[u8; 64]) -> (PublicKey, SecretKey) {
+    let mut seed = blake3::derive_key(IDENTITY_KEY_CONTEXT, master_seed);
+    let kp = mldsa87::from_seed(&seed);
+    seed.zeroize();
+    kp
+}
+
+fn signed_message(document: &str) -> Vec<u8> {
+    let mut m = ATTESTATION_DOMAIN.to_vec();
+    m.extend_from_slice(document.as_bytes());
+    m
+}
+
+/// Serialize and sign `attestation` with the vault identity and, when given,
+/// the issuing installation's identity.
+pub fn sign_attestation(
+    identity: &SecretKey,
+    instance: Option<&instance::InstanceKey>,
+    attestation: &KeyAttestation,
+) -> Result<SignedKeyAttestation, AttestationError> {
+    let document = serde_json::to_string_pretty(attestation)
+        .map_err(|e| AttestationError::Malformed(e.to_string()))?;
+    let sig = mldsa87::sign(identity, &signed_message(&document))?;
+    let instance_signature = instance
+        .map(|key| key.sign(PURPOSE_KEY_ATTESTATION, document.as_bytes()))
+        .transpose()?;
+    Ok(SignedKeyAttestation {
+        document,
+        signature_hex: sig.to_hex(),
+        instance_signature,
+    })
+}
+
+/// Verify a signed attestation against the public key it embeds, and its
+/// instance signature if it has one, and return the parsed document. Callers
+/// should also check `vault_public_key_hex` against the vault identity they
+/// expect.
+pub fn verify_attestation(
+    signed: &SignedKeyAttestation,
+) -> Result<KeyAttestation, AttestationError> {
+    let attestation: KeyAttestation = serde_json::from_str(&signed.document)
+        .map_err(|e| AttestationError::Malformed(e.to_string()))?;
+    if attestation.version != ATTESTATION_VERSION {
+        return Err(AttestationError::UnsupportedVersion(attestation.version));
+    }
+    let pk = PublicKey::from_hex(&attestation.vault_public_key_hex)?;
+    let sig =
+        Signature::from_hex(&signed.signature_hex).map_err(|_| AttestationError::BadSignature)?;
+    if !mldsa87::verify(&pk, &signed_message(&signed.document), &sig)? {
+        return Err(AttestationError::BadSignature);
+    }
+    if let Some(instance_sig) = &signed.instance_signature {
+        instance::verify_instance(
+            instance_sig,
+            PURPOSE_KEY_ATTESTATION,
+            signed.document.as_bytes(),
+        )?;
+    }
+    Ok(attestation)
+}
+
+/// What a backup file signature covers: the backup id and the entry's name,
+/// size and hash, each length-prefixed. The signature field itself is not
+/// included.
+fn backup_file_message(backup_id: &str, file: &BackupFile) -> Vec<u8> {
+    let mut m = BACKUP_FILE_DOMAIN.to_vec();
+    let size = file.size.to_string();
+    for part in [backup_id, &file.name, &size, &file.blake3_hex] {
+        m.extend_from_slice(&(part.len() as u32).to_be_bytes());
+        m.extend_from_slice(part.as_bytes());
+    }
+    m
+}
+
+/// Sign one backup manifest entry with the vault identity.
+pub fn sign_backup_file(
+    identity: &SecretKey,
+    backup_id: &str,
+    file: &BackupFile,
+) -> Result<String, AttestationError> {
+    Ok(mldsa87::sign(identity, &backup_file_message(backup_id, file))?.to_hex())
+}
+
+/// Whether `file.signature_hex` is a valid signature by `signer_public_hex`.
+/// An entry without a signature does not verify.
+pub fn verify_backup_file(signer_public_hex: &str, backup_id: &str, file: &BackupFile) -> bool {
+    let (Ok(pk), Some(Ok(sig))) = (
+        PublicKey::from_hex(signer_public_hex),
+        file.signature_hex.as_deref().map(Signature::from_hex),
+    ) else {
+        return false;
+    };
+    mldsa87::verify(&pk, &backup_file_message(backup_id, file), &sig).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::attestation::{AttestedKey, EntropySource};
+    use crate::models::key::KeyType;
+    use chrono::Utc;
+
+    fn attestation(vault_pk: &PublicKey) -> KeyAttestation {
+        KeyAttestation {
+            version: ATTESTATION_VERSION,
+            keyset_id: "keyset-1".to_string(),
+            keyset_name: "Genesis".to_string(),
+            generated_at: Utc::now(),
+            entropy_source: EntropySource::VaultMasterSeed,
+            keys: vec![AttestedKey {
+                key_id: "k1".to_string(),
+                role: KeyType::Validator,
+                derivation_path: "m/44'/9999'/1'/0'/0'".to_string(),
+                public_key_hex: "ab".to_string(),
+                address: "zap1".to_string(),
+                created_at: Utc::now(),
+            }],
+            issued_at: Utc::now(),
+            vault_public_key_hex: vault_pk.to_hex(),
+        }
+    }
+
+    #[test]
+    fn identity_is_deterministic_and_distinct_from_owner_key() {
+        let seed = [3u8; 64];
+        assert_eq!(
+            vault_identity(&seed).0.to_hex(),
+            vault_identity(&seed).0.to_hex()
+        );
+        assert_ne!(
+            vault_identity(&seed).0.to_hex(),
+            crate::crypto::emergency::owner_keypair(&seed).0.to_hex()
+        );
+    }
+
+    #[test]
+    fn sign_and_verify_round_trip() {
+        let (pk, sk) = vault_identity(&[5u8; 64]);
+        let doc = attestation(&pk);
+        let signed = sign_attestation(&sk, None, &doc).unwrap();
+        assert_eq!(verify_attestation(&signed).unwrap(), doc);
+    }
+
+    #[test]
+    fn instance_signature_is_checked_when_present() {
+        let (pk, sk) = vault_identity(&[5u8; 64]);
+        let (key, _) = instance::generate_instance(&[5u8; 64], "device-1", Utc::now()).unwrap();
+        let mut signed = sign_attestation(&sk, Some(&key), &attestation(&pk)).unwrap();
+        assert!(signed
+            .instance_signature
+            .as_ref()
+            .unwrap()
+            .is_from(&key.identity));
+        verify_attestation(&signed).unwrap();
+
+        // A signature over another document is rejected.
+        let mut renamed = attestation(&pk);
+        renamed.keyset_name = "Renamed".to_string();
+        let other = sign_attestation(&sk, Some(&key), &renamed).unwrap();
+        signed.instance_signature = other.instance_signature;
+        assert!(matches!(
+            verify_attestation(&signed),
+            Err(AttestationError::Instance(_))
+        ));
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let (pk, sk) = vault_identity(&[5u8; 64]);
+        let mut signed = sign_attestation(&sk, None, &attestation(&pk)).unwrap();
+        signed.document = signed.document.replace("Validator", "Treasury");
+        assert!(matches!(
+            verify_attestation(&signed),
+            Err(AttestationError::BadSignature)
+        ));
+
+        // Re-signing under another key but keeping the embedded vault key fails.
+        let (_, other_sk) = vault_identity(&[6u8; 64]);
+        let forged = sign_attestation(&other_sk, None, &attestation(&pk)).unwrap();
+        assert!(verify_attestation(&forged).is_err());
+    }
+
+    #[test]
+    fn backup_file_signatures_bind_entry_and_backup() {
+        let (pk, sk) = vault_identity(&[5u8; 64]);
+        let mut file = BackupFile {
+            name: "keys.enc".to_string(),
+            size: 300,
+            blake3_hex: "ab".repeat(32),
+            signature_hex: None,
+        };
+        assert!(!verify_backup_file(&pk.to_hex(), "b1", &file));
+        file.signature_hex = Some(sign_backup_file(&sk, "b1", &file).unwrap());
+        assert!(verify_backup_file(&pk.to_hex(), "b1", &file));
+        assert!(!verify_backup_file(&pk.to_hex(), "b2", &file));
+
+        let mut altered = file.clone();
+        altered.size = 301;
+        assert!(!verify_backup_file(&pk.to_hex(), "b1", &altered));
+        let (other, _) = vault_identity(&[6u8; 64]);
+        assert!(!verify_backup_file(&other.to_hex(), "b1", &file));
+    }
+}
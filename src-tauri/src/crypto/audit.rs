@@ -0,0 +1,57 @@
+use crate::crypto::hybrid_signing::{HybridSigner, HybridSigningError};
+use crate::crypto::mnemonic::{self, MnemonicError, MnemonicLanguage};
+use crate::crypto::{address, hd_derivation, mldsa87};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error(transparent)]
+    Mnemonic(#[from] MnemonicError),
+    #[error(transparent)]
+    Hybrid(#[from] HybridSigningError),
+}
+
+/// Every value derived from one entropy, passphrase and path, hex encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HdVector {
+    pub mnemonic: String,
+    /// The 64-byte BIP39 seed, i.e. the vault's master seed.
+    pub seed_hex: String,
+    pub path: String,
+    /// The 32-byte ML-DSA-87 keygen seed for `path`.
+    pub key_seed_hex: String,
+    pub public_key_hex: String,
+    pub address: String,
+    /// The Ed25519 half of the hybrid identity for the same key.
+    pub hybrid_ed25519_public_hex: String,
+}
+
+/// Derive the key at `zap_path(purpose, account, index)` from `entropy`, as
+/// `create_vault` followed by `generate_key` would, reporting every
+/// intermediate value. Paths have the BIP44 shape, but the per-path seed is
+/// ZAP's BLAKE3 derivation rather than BIP32 or SLIP-10, so only the BIP39
+/// step can be checked against external reference vectors.
+pub fn hd_vector(
+    entropy: &[u8],
+    passphrase: &str,
+    purpose: u32,
+    account: u32,
+    index: u32,
+) -> Result<HdVector, AuditError> {
+    let phrase = mnemonic::mnemonic_from_entropy(entropy, MnemonicLanguage::English)?;
+    let seed = mnemonic::mnemonic_to_seed_with_passphrase(&phrase, passphrase)?;
+    let path = hd_derivation::zap_path(purpose, account, index);
+    let key_seed = hd_derivation::derive_seed_from_master(&seed, &path);
+    let (pk, sk) = mldsa87::from_seed(&key_seed);
+    let hybrid = HybridSigner::from_secret(&sk)?;
+    Ok(HdVector {
+        mnemonic: phrase,
+        seed_hex: hex::encode(seed),
+        path: path.to_string(),
+        key_seed_hex: hex::encode(key_seed),
+        public_key_hex: pk.to_hex(),
+        address: address::derive_address(pk.as_bytes()),
+        hybrid_ed25519_public_hex: hex::encode(hybrid.secondary_public_key()),
+    })
+}
@@ -0,0 +1,292 @@
+use crate::crypto::encryption::{self, EncryptionError};
+use crate::crypto::mldsa87::{self, CryptoError, PublicKey, SecretKey, Signature};
+use crate::models::capsule::{CapsuleAttestor, TimeAttestation, TimeCapsule};
+use crate::models::item::VaultItem;
+use chrono::{DateTime, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// Current time capsule format version.
+pub const CAPSULE_VERSION: u32 = 1;
+
+/// BLAKE3 `derive_key` contexts. Outside the HD tree so no `generate_key`
+/// path can collide with them.
+const CAPSULE_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 time capsule key v1";
+const PAYLOAD_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 time capsule payload key v1";
+/// Signature domain for time attestations.
+const TIME_ATTESTATION_DOMAIN: &[u8] = b"ZAP_TIME_ATTESTATION_V1";
+
+#[derive(Debug, Error)]
+pub enum CapsuleError {
+    #[error("capsule opens in {0} seconds")]
+    NotYetReleased(u64),
+    #[error("this capsule needs a time attestation from its attestor")]
+    AttestationRequired,
+    #[error("time attestation predates the release date")]
+    AttestationTooEarly,
+    #[error("time attestation is not signed by the capsule's attestor")]
+    BadAttestation,
+    #[error("capsule is damaged or its terms were altered")]
+    Tampered,
+    #[error("unsupported time capsule version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("malformed capsule: {0}")]
+    Malformed(String),
+    #[error("encryption error: {0}")]
+    Encryption(#[from] EncryptionError),
+    #[error("signature error: {0}")]
+    Crypto(#[from] CryptoError),
+}
+
+fn capsule_key(master_seed: &[u8; 64]) -> Zeroizing<[u8; 32]> {
+    Zeroizing::new(blake3::derive_key(CAPSULE_KEY_CONTEXT, master_seed))
+}
+
+/// Key sealing the secondary key, bound to the capsule's terms.
+fn stage_key(
+    capsule_key: &[u8; 32],
+    id: &str,
+    release_at: DateTime<Utc>,
+    attestor: Option<&CapsuleAttestor>,
+) -> Zeroizing<[u8; 32]> {
+    let mut hasher = blake3::Hasher::new_keyed(capsule_key);
+    for field in [
+        id.as_bytes(),
+        &release_at.timestamp().to_le_bytes(),
+        attestor.map_or(&[][..], |a| a.sign_public_hex.as_bytes()),
+    ] {
+        hasher.update(&(field.len() as u32).to_le_bytes());
+        hasher.update(field);
+    }
+    Zeroizing::new(*hasher.finalize().as_bytes())
+}
+
+/// Key sealing the item: needs both the vault's capsule key and the
+/// secondary key.
+fn payload_key(capsule_key: &[u8; 32], secondary: &[u8]) -> Zeroizing<[u8; 32]> {
+    let mut hasher = blake3::Hasher::new_derive_key(PAYLOAD_KEY_CONTEXT);
+    hasher.update(capsule_key);
+    hasher.update(secondary);
+    Zeroizing::new(*hasher.finalize().as_bytes())
+}
+
+/// Seal `item` until `release_at`, optionally also gated on a time
+/// attestation from `attestor`.
+pub fn seal_capsule(
+    master_seed: &[u8; 64],
+    item: &VaultItem,
+    release_at: DateTime<Utc>,
+    attestor: Option<CapsuleAttestor>,
+    now: DateTime<Utc>,
+) -> Result<TimeCapsule, CapsuleError> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let key = capsule_key(master_seed);
+    let mut secondary = Zeroizing::new([0u8; 32]);
+    OsRng.fill_bytes(secondary.as_mut());
+    let sealed_secondary = encryption::encrypt_aead(
+        &stage_key(&key, &id, release_at, attestor.as_ref()),
+        secondary.as_ref(),
+    )?;
+    let plaintext = Zeroizing::new(
+        serde_json::to_vec(item).map_err(|e| CapsuleError::Malformed(e.to_string()))?,
+    );
+    let payload = encryption::encrypt_aead(&payload_key(&key, secondary.as_ref()), &plaintext)?;
+    Ok(TimeCapsule {
+        version: CAPSULE_VERSION,
+        id,
+        item_id: item.id.clone(),
+        label: item.label.clone(),
+        created_at: now,
+        release_at,
+        attestor,
+        sealed_secondary,
+        payload,
+    })
+}
+
+fn attestation_message(time: u64) -> Vec<u8> {
+    let mut m = TIME_ATTESTATION_DOMAIN.to_vec();
+    m.extend_from_slice(&time.to_le_bytes());
+    m
+}
+
+/// Attest that `time` has been reached, signed with a vault identity.
+pub fn sign_time_attestation(
+    identity: &SecretKey,
+    identity_public: &PublicKey,
+    time: u64,
+) -> Result<TimeAttestation, CapsuleError> {
+    Ok(TimeAttestation {
+        time,
+        signer_public_hex: identity_public.to_hex(),
+        signature_hex: mldsa87::sign(identity, &attestation_message(time))?.to_hex(),
+    })
+}
+
+/// Whether `capsule` may open at `now`: the local clock must have passed the
+/// release date and, if the capsule names an attestor, `attestation` must be
+/// theirs and not earlier than the release date.
+pub fn check_release(
+    capsule: &TimeCapsule,
+    now: DateTime<Utc>,
+    attestation: Option<&TimeAttestation>,
+) -> Result<(), CapsuleError> {
+    if now < capsule.release_at {
+        return Err(CapsuleError::NotYetReleased(
+            (capsule.release_at - now).num_seconds().max(1) as u64,
+        ));
+    }
+    let Some(attestor) = &capsule.attestor else {
+        return Ok(());
+    };
+    let attestation = attestation.ok_or(CapsuleError::AttestationRequired)?;
+    if !attestation
+        .signer_public_hex
+        .eq_ignore_ascii_case(&attestor.sign_public_hex)
+    {
+        return Err(CapsuleError::BadAttestation);
+    }
+    let pk = PublicKey::from_hex(&attestor.sign_public_hex)?;
+    let sig = Signature::from_hex(&attestation.signature_hex)
+        .map_err(|_| CapsuleError::BadAttestation)?;
+    if !mldsa87::verify(&pk, &attestation_message(attestation.time), &sig)? {
+        return Err(CapsuleError::BadAttestation);
+    }
+    if (attestation.time as i64) < capsule.release_at.timestamp() {
+        return Err(CapsuleError::AttestationTooEarly);
+    }
+    Ok(())
+}
+
+/// Check the release conditions, then unseal the secondary key and the item.
+pub fn open_capsule(
+    master_seed: &[u8; 64],
+    capsule: &TimeCapsule,
+    now: DateTime<Utc>,
+    attestation: Option<&TimeAttestation>,
+) -> Result<VaultItem, CapsuleError> {
+    if capsule.version != CAPSULE_VERSION {
+        return Err(CapsuleError::UnsupportedVersion(capsule.version));
+    }
+    check_release(capsule, now, attestation)?;
+    let key = capsule_key(master_seed);
+    let stage = stage_key(
+        &key,
+        &capsule.id,
+        capsule.release_at,
+        capsule.attestor.as_ref(),
+    );
+    let secondary = Zeroizing::new(
+        encryption::decrypt_aead(&stage, &capsule.sealed_secondary)
+            .map_err(|_| CapsuleError::Tampered)?,
+    );
+    let plaintext = Zeroizing::new(
+        encryption::decrypt_aead(&payload_key(&key, &secondary), &capsule.payload)
+            .map_err(|_| CapsuleError::Tampered)?,
+    );
+    let item: VaultItem =
+        serde_json::from_slice(&plaintext).map_err(|e| CapsuleError::Malformed(e.to_string()))?;
+    if item.id != capsule.item_id {
+        return Err(CapsuleError::Tampered);
+    }
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::attestation;
+    use crate::models::item::{ItemPayload, WireGuardItem};
+    use chrono::Duration;
+
+    const SEED: [u8; 64] = [31u8; 64];
+
+    fn item() -> VaultItem {
+        VaultItem::new(
+            Some("letter".to_string()),
+            ItemPayload::WireGuard(WireGuardItem {
+                private_key: "secret".to_string(),
+                public_key: "public".to_string(),
+                addresses: Vec::new(),
+                dns: Vec::new(),
+                listen_port: None,
+                mtu: None,
+                peers: Vec::new(),
+            }),
+        )
+    }
+
+    fn attestor(seed: &[u8; 64]) -> CapsuleAttestor {
+        CapsuleAttestor {
+            contact_id: "notary".to_string(),
+            name: "Notary".to_string(),
+            sign_public_hex: attestation::vault_identity(seed).0.to_hex(),
+        }
+    }
+
+    fn attest(seed: &[u8; 64], time: DateTime<Utc>) -> TimeAttestation {
+        let (pk, sk) = attestation::vault_identity(seed);
+        sign_time_attestation(&sk, &pk, time.timestamp() as u64).unwrap()
+    }
+
+    #[test]
+    fn opens_only_after_release() {
+        let now = Utc::now();
+        let release = now + Duration::days(1);
+        let original = item();
+        let capsule = seal_capsule(&SEED, &original, release, None, now).unwrap();
+        assert!(matches!(
+            open_capsule(&SEED, &capsule, now, None),
+            Err(CapsuleError::NotYetReleased(_))
+        ));
+        let opened = open_capsule(&SEED, &capsule, release, None).unwrap();
+        assert_eq!(opened.id, original.id);
+        assert!(matches!(
+            open_capsule(&[32u8; 64], &capsule, release, None),
+            Err(CapsuleError::Tampered)
+        ));
+    }
+
+    #[test]
+    fn editing_the_release_date_breaks_the_capsule() {
+        let now = Utc::now();
+        let mut capsule =
+            seal_capsule(&SEED, &item(), now + Duration::days(365), None, now).unwrap();
+        capsule.release_at = now - Duration::days(1);
+        assert!(matches!(
+            open_capsule(&SEED, &capsule, now, None),
+            Err(CapsuleError::Tampered)
+        ));
+    }
+
+    #[test]
+    fn attestor_gate() {
+        let notary = [33u8; 64];
+        let now = Utc::now();
+        let release = now + Duration::days(1);
+        let capsule = seal_capsule(&SEED, &item(), release, Some(attestor(&notary)), now).unwrap();
+        let later = release + Duration::hours(1);
+
+        assert!(matches!(
+            open_capsule(&SEED, &capsule, later, None),
+            Err(CapsuleError::AttestationRequired)
+        ));
+        assert!(matches!(
+            open_capsule(&SEED, &capsule, later, Some(&attest(&notary, now))),
+            Err(CapsuleError::AttestationTooEarly)
+        ));
+        assert!(matches!(
+            open_capsule(&SEED, &capsule, later, Some(&attest(&[34u8; 64], later))),
+            Err(CapsuleError::BadAttestation)
+        ));
+        let mut forged = attest(&notary, now);
+        forged.time = later.timestamp() as u64;
+        assert!(matches!(
+            open_capsule(&SEED, &capsule, later, Some(&forged)),
+            Err(CapsuleError::BadAttestation)
+        ));
+        assert!(open_capsule(&SEED, &capsule, later, Some(&attest(&notary, later))).is_ok());
+    }
+}
@@ -0,0 +1,383 @@
+use crate::crypto::mldsa87::{self, CryptoError, PublicKey, SecretKey, Signature};
+use crate::models::ceremony::{
+    Ceremony, CeremonyOperatorInput, CeremonyPhase, EntropyContribution, EntropyReveal,
+    GenesisManifest, OperatorAttestation,
+};
+use bip39::{Language, Mnemonic};
+use chrono::{DateTime, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// Current attestation bundle format version.
+pub const BUNDLE_VERSION: u32 = 1;
+pub const MIN_OPERATORS: usize = 2;
+pub const MAX_OPERATORS: usize = 32;
+/// Each operator must contribute at least 256 bits of entropy.
+pub const MIN_ENTROPY_BYTES: usize = 32;
+pub const MAX_ENTROPY_BYTES: usize = 1024;
+
+/// BLAKE3 `derive_key` contexts; one per use so a commitment can never be
+/// mistaken for combined entropy.
+const COMMITMENT_CONTEXT: &str = "ZAP Quantum Vault 2026 genesis ceremony commitment v1";
+const COMBINE_CONTEXT: &str = "ZAP Quantum Vault 2026 genesis ceremony entropy v1";
+
+#[derive(Debug, Error)]
+pub enum CeremonyError {
+    #[error("invalid operator: {0}")]
+    InvalidOperator(String),
+    #[error("a ceremony needs between {MIN_OPERATORS} and {MAX_OPERATORS} operators")]
+    OperatorCount,
+    #[error("unknown operator: {0}")]
+    UnknownOperator(String),
+    #[error("ceremony is in the {0:?} phase")]
+    WrongPhase(CeremonyPhase),
+    #[error("operator {0} has already committed")]
+    AlreadyCommitted(String),
+    #[error("operator {0} has not committed yet")]
+    NotCommitted(String),
+    #[error("invalid commitment: {0}")]
+    InvalidCommitment(String),
+    #[error("invalid entropy from operator {0}")]
+    InvalidEntropy(String),
+    #[error("no entropy revealed by operator {0}")]
+    MissingReveal(String),
+    #[error("entropy from operator {0} does not match their commitment")]
+    CommitmentMismatch(String),
+    #[error("operator {0} has already attested")]
+    AlreadyAttested(String),
+    #[error("attestation signature from operator {0} is invalid")]
+    BadAttestation(String),
+    #[error("signature error: {0}")]
+    Crypto(#[from] CryptoError),
+}
+
+fn push_field(m: &mut Vec<u8>, field: &[u8]) {
+    m.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    m.extend_from_slice(field);
+}
+
+/// Check an operator's name and ML-DSA-87 public key.
+pub fn validate_operator(operator: &CeremonyOperatorInput) -> Result<(), CeremonyError> {
+    if operator.name.trim().is_empty() {
+        return Err(CeremonyError::InvalidOperator(
+            "name must not be empty".to_string(),
+        ));
+    }
+    PublicKey::from_hex(&operator.public_key_hex)
+        .map_err(|e| CeremonyError::InvalidOperator(format!("{}: {e}", operator.name)))?;
+    Ok(())
+}
+
+/// Commitment to `entropy`, bound to the ceremony and operator so it cannot
+/// be replayed into another ceremony or by another operator.
+pub fn commitment(ceremony_id: &str, operator_id: &str, entropy: &[u8]) -> [u8; 32] {
+    let mut m = Vec::new();
+    push_field(&mut m, ceremony_id.as_bytes());
+    push_field(&mut m, operator_id.as_bytes());
+    push_field(&mut m, entropy);
+    let out = blake3::derive_key(COMMITMENT_CONTEXT, &m);
+    zeroize::Zeroize::zeroize(&mut m);
+    out
+}
+
+/// Operator-side helper: draw fresh entropy and commit to it.
+pub fn contribute(ceremony_id: &str, operator_id: &str) -> EntropyContribution {
+    let mut entropy = Zeroizing::new([0u8; MIN_ENTROPY_BYTES]);
+    OsRng.fill_bytes(entropy.as_mut());
+    EntropyContribution {
+        entropy_hex: hex::encode(entropy.as_ref()),
+        commitment_hex: hex::encode(commitment(ceremony_id, operator_id, entropy.as_ref())),
+    }
+}
+
+/// Record `operator_id`'s entropy commitment.
+pub fn record_commitment(
+    ceremony: &mut Ceremony,
+    operator_id: &str,
+    commitment_hex: &str,
+    now: DateTime<Utc>,
+) -> Result<(), CeremonyError> {
+    if ceremony.phase != CeremonyPhase::Commit {
+        return Err(CeremonyError::WrongPhase(ceremony.phase));
+    }
+    let bytes =
+        hex::decode(commitment_hex).map_err(|e| CeremonyError::InvalidCommitment(e.to_string()))?;
+    if bytes.len() != 32 {
+        return Err(CeremonyError::InvalidCommitment(format!(
+            "expected 32 bytes, got {}",
+            bytes.len()
+        )));
+    }
+    let operator = ceremony
+        .operators
+        .iter_mut()
+        .find(|o| o.id == operator_id)
+        .ok_or_else(|| CeremonyError::UnknownOperator(operator_id.to_string()))?;
+    if operator.commitment_hex.is_some() {
+        return Err(CeremonyError::AlreadyCommitted(operator_id.to_string()));
+    }
+    operator.commitment_hex = Some(hex::encode(bytes));
+    operator.committed_at = Some(now);
+    Ok(())
+}
+
+/// Check every operator's revealed entropy against their commitment and fold
+/// it, in operator order, into the 256-bit ceremony entropy. Any single honest
+/// operator's entropy makes the result unpredictable to the others.
+pub fn combine_entropy(
+    ceremony: &Ceremony,
+    reveals: &[EntropyReveal],
+) -> Result<Zeroizing<[u8; 32]>, CeremonyError> {
+    let mut m = Zeroizing::new(Vec::new());
+    push_field(&mut m, ceremony.id.as_bytes());
+    for operator in &ceremony.operators {
+        let committed = operator
+            .commitment_hex
+            .as_deref()
+            .ok_or_else(|| CeremonyError::NotCommitted(operator.id.clone()))?;
+        let reveal = reveals
+            .iter()
+            .find(|r| r.operator_id == operator.id)
+            .ok_or_else(|| CeremonyError::MissingReveal(operator.id.clone()))?;
+        let entropy = Zeroizing::new(
+            hex::decode(&reveal.entropy_hex)
+                .map_err(|_| CeremonyError::InvalidEntropy(operator.id.clone()))?,
+        );
+        if !(MIN_ENTROPY_BYTES..=MAX_ENTROPY_BYTES).contains(&entropy.len()) {
+            return Err(CeremonyError::InvalidEntropy(operator.id.clone()));
+        }
+        if hex::encode(commitment(&ceremony.id, &operator.id, &entropy)) != committed {
+            return Err(CeremonyError::CommitmentMismatch(operator.id.clone()));
+        }
+        push_field(&mut m, &entropy);
+    }
+    Ok(Zeroizing::new(blake3::derive_key(COMBINE_CONTEXT, &m)))
+}
+
+/// The 24-word backup mnemonic for the combined ceremony entropy.
+pub fn backup_mnemonic(entropy: &[u8; 32]) -> Zeroizing<String> {
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, entropy)
+        .expect("32 bytes is valid BIP39 entropy");
+    Zeroizing::new(mnemonic.to_string())
+}
+
+/// Canonical encoding of the manifest signed by each operator.
+pub fn manifest_message(manifest: &GenesisManifest) -> Vec<u8> {
+    let mut m = Vec::new();
+    m.extend_from_slice(b"ZAP_GENESIS_MANIFEST_V1");
+    push_field(&mut m, manifest.ceremony_id.as_bytes());
+    push_field(&mut m, manifest.name.as_bytes());
+    m.extend_from_slice(&manifest.account.to_le_bytes());
+    m.extend_from_slice(&manifest.generated_at.timestamp().to_le_bytes());
+    m.extend_from_slice(&(manifest.operators.len() as u32).to_le_bytes());
+    for op in &manifest.operators {
+        push_field(&mut m, op.id.as_bytes());
+        push_field(&mut m, op.name.as_bytes());
+        push_field(&mut m, op.public_key_hex.as_bytes());
+        push_field(&mut m, op.commitment_hex.as_bytes());
+    }
+    m.extend_from_slice(&(manifest.keys.len() as u32).to_le_bytes());
+    for key in &manifest.keys {
+        push_field(&mut m, key.key_type.as_str().as_bytes());
+        push_field(&mut m, key.derivation_path.as_bytes());
+        push_field(&mut m, key.public_key_hex.as_bytes());
+        push_field(&mut m, key.address.as_bytes());
+    }
+    m
+}
+
+/// Operator-side helper: sign the manifest.
+pub fn sign_manifest(
+    sign_secret: &SecretKey,
+    manifest: &GenesisManifest,
+) -> Result<Signature, CeremonyError> {
+    Ok(mldsa87::sign(sign_secret, &manifest_message(manifest))?)
+}
+
+/// Verify `operator_id`'s signature over the manifest.
+pub fn verify_attestation(
+    manifest: &GenesisManifest,
+    attestation: &OperatorAttestation,
+) -> Result<(), CeremonyError> {
+    let operator = manifest
+        .operators
+        .iter()
+        .find(|o| o.id == attestation.operator_id)
+        .ok_or_else(|| CeremonyError::UnknownOperator(attestation.operator_id.clone()))?;
+    let pk = PublicKey::from_hex(&operator.public_key_hex)?;
+    let sig = Signature::from_hex(&attestation.signature_hex)
+        .map_err(|_| CeremonyError::BadAttestation(operator.id.clone()))?;
+    if !mldsa87::verify(&pk, &manifest_message(manifest), &sig)? {
+        return Err(CeremonyError::BadAttestation(operator.id.clone()));
+    }
+    Ok(())
+}
+
+/// Verify and record an operator's attestation; the ceremony completes once
+/// every operator has signed.
+pub fn record_attestation(
+    ceremony: &mut Ceremony,
+    operator_id: &str,
+    signature_hex: &str,
+    now: DateTime<Utc>,
+) -> Result<(), CeremonyError> {
+    let manifest = match (&ceremony.phase, &ceremony.manifest) {
+        (CeremonyPhase::Attesting, Some(manifest)) => manifest,
+        _ => return Err(CeremonyError::WrongPhase(ceremony.phase)),
+    };
+    if ceremony
+        .attestations
+        .iter()
+        .any(|a| a.operator_id == operator_id)
+    {
+        return Err(CeremonyError::AlreadyAttested(operator_id.to_string()));
+    }
+    let attestation = OperatorAttestation {
+        operator_id: operator_id.to_string(),
+        signature_hex: signature_hex.to_string(),
+        signed_at: now,
+    };
+    verify_attestation(manifest, &attestation)?;
+    ceremony.attestations.push(attestation);
+    if ceremony.attestations.len() == ceremony.operators.len() {
+        ceremony.phase = CeremonyPhase::Complete;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ceremony::{CeremonyOperator, ManifestKey, ManifestOperator};
+    use crate::models::key::KeyType;
+
+    fn ceremony(operators: &[(&str, &PublicKey)]) -> Ceremony {
+        Ceremony {
+            id: "ceremony-1".to_string(),
+            name: "Mainnet genesis".to_string(),
+            account: 0,
+            members: Vec::new(),
+            operators: operators
+                .iter()
+                .map(|(id, pk)| CeremonyOperator {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    public_key_hex: pk.to_hex(),
+                    commitment_hex: None,
+                    committed_at: None,
+                })
+                .collect(),
+            phase: CeremonyPhase::Commit,
+            keyset_id: None,
+            manifest: None,
+            attestations: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn manifest_for(c: &Ceremony) -> GenesisManifest {
+        GenesisManifest {
+            ceremony_id: c.id.clone(),
+            name: c.name.clone(),
+            account: c.account,
+            operators: c
+                .operators
+                .iter()
+                .map(|o| ManifestOperator {
+                    id: o.id.clone(),
+                    name: o.name.clone(),
+                    public_key_hex: o.public_key_hex.clone(),
+                    commitment_hex: o.commitment_hex.clone().unwrap_or_default(),
+                })
+                .collect(),
+            keys: vec![ManifestKey {
+                key_type: KeyType::Genesis,
+                derivation_path: "m/44'/9999'/0'/0'/0'".to_string(),
+                public_key_hex: "ab".to_string(),
+                address: "zap1".to_string(),
+            }],
+            generated_at: Utc::now(),
+        }
+    }
+
+    fn reveal(operator_id: &str, c: &EntropyContribution) -> EntropyReveal {
+        EntropyReveal {
+            operator_id: operator_id.to_string(),
+            entropy_hex: c.entropy_hex.clone(),
+        }
+    }
+
+    #[test]
+    fn reveals_must_match_commitments() {
+        let (pa, _) = mldsa87::generate();
+        let (pb, _) = mldsa87::generate();
+        let mut c = ceremony(&[("a", &pa), ("b", &pb)]);
+        let ca = contribute(&c.id, "a");
+        let cb = contribute(&c.id, "b");
+        record_commitment(&mut c, "a", &ca.commitment_hex, Utc::now()).unwrap();
+        assert!(matches!(
+            combine_entropy(&c, &[reveal("a", &ca)]),
+            Err(CeremonyError::NotCommitted(_))
+        ));
+        record_commitment(&mut c, "b", &cb.commitment_hex, Utc::now()).unwrap();
+        assert!(matches!(
+            record_commitment(&mut c, "b", &cb.commitment_hex, Utc::now()),
+            Err(CeremonyError::AlreadyCommitted(_))
+        ));
+
+        let combined = combine_entropy(&c, &[reveal("b", &cb), reveal("a", &ca)]).unwrap();
+        let again = combine_entropy(&c, &[reveal("a", &ca), reveal("b", &cb)]).unwrap();
+        assert_eq!(*combined, *again);
+        assert_eq!(backup_mnemonic(&combined).split_whitespace().count(), 24);
+
+        // Swapping in different entropy after committing is caught.
+        let other = contribute(&c.id, "b");
+        assert!(matches!(
+            combine_entropy(&c, &[reveal("a", &ca), reveal("b", &other)]),
+            Err(CeremonyError::CommitmentMismatch(id)) if id == "b"
+        ));
+    }
+
+    #[test]
+    fn attestations_complete_the_ceremony() {
+        let (pa, sa) = mldsa87::generate();
+        let (pb, sb) = mldsa87::generate();
+        let mut c = ceremony(&[("a", &pa), ("b", &pb)]);
+        let manifest = manifest_for(&c);
+        let sig_a = sign_manifest(&sa, &manifest).unwrap().to_hex();
+        assert!(matches!(
+            record_attestation(&mut c, "a", &sig_a, Utc::now()),
+            Err(CeremonyError::WrongPhase(CeremonyPhase::Commit))
+        ));
+
+        c.manifest = Some(manifest.clone());
+        c.phase = CeremonyPhase::Attesting;
+        // Operator b's signature does not verify under a's key.
+        let sig_b = sign_manifest(&sb, &manifest).unwrap().to_hex();
+        assert!(matches!(
+            record_attestation(&mut c, "a", &sig_b, Utc::now()),
+            Err(CeremonyError::BadAttestation(_))
+        ));
+        record_attestation(&mut c, "a", &sig_a, Utc::now()).unwrap();
+        assert_eq!(c.phase, CeremonyPhase::Attesting);
+        record_attestation(&mut c, "b", &sig_b, Utc::now()).unwrap();
+        assert_eq!(c.phase, CeremonyPhase::Complete);
+    }
+
+    #[test]
+    fn tampered_manifest_fails_verification() {
+        let (pa, sa) = mldsa87::generate();
+        let c = ceremony(&[("a", &pa)]);
+        let mut manifest = manifest_for(&c);
+        let attestation = OperatorAttestation {
+            operator_id: "a".to_string(),
+            signature_hex: sign_manifest(&sa, &manifest).unwrap().to_hex(),
+            signed_at: Utc::now(),
+        };
+        verify_attestation(&manifest, &attestation).unwrap();
+        manifest.keys[0].public_key_hex = "cd".to_string();
+        assert!(verify_attestation(&manifest, &attestation).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::crypto::attestation;
+use crate::crypto::fingerprint::KeyFingerprint;
+use crate::crypto::mldsa87::{self, CryptoError, PublicKey, Signature};
+use crate::crypto::mlkem1024::{self, KemKeyPair};
+use crate::models::contact::{validate_contact_name, ContactCard};
+use thiserror::Error;
+use x25519_dalek::StaticSecret;
+use zeroize::Zeroizing;
+
+/// Current contact card format version.
+pub const CONTACT_CARD_VERSION: u32 = 1;
+
+/// Signature domain, so a card signature cannot be passed off as a signature
+/// over anything else.
+const CONTACT_CARD_DOMAIN: &[u8] = b"ZAP_CONTACT_CARD_V1";
+/// BLAKE3 `derive_key` contexts for the card's encryption keys. Outside the
+/// HD tree so no `generate_key` path can collide with them.
+const X25519_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 contact X25519 key v1";
+const KEM_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 contact ML-KEM key v1";
+
+#[derive(Debug, Error)]
+pub enum ContactError {
+    #[error("malformed contact card: {0}")]
+    Malformed(String),
+    #[error("unsupported contact card version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("contact card signature is invalid")]
+    BadSignature,
+    #[error("signature error: {0}")]
+    Crypto(#[from] CryptoError),
+}
+
+fn decode_key(field: &str, value: &str, len: usize) -> Result<Vec<u8>, ContactError> {
+    let bytes = hex::decode(value).map_err(|e| ContactError::Malformed(format!("{field}: {e}")))?;
+    if bytes.len() != len {
+        return Err(ContactError::Malformed(format!(
+            "{field}: expected {len} bytes, got {}",
+            bytes.len()
+        )));
+    }
+    Ok(bytes)
+}
+
+fn push_field(m: &mut Vec<u8>, field: &[u8]) {
+    m.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    m.extend_from_slice(field);
+}
+
+/// The X25519 secret published in this vault's card.
+pub fn x25519_identity(master_seed: &[u8; 64]) -> StaticSecret {
+    StaticSecret::from(blake3::derive_key(X25519_KEY_CONTEXT, master_seed))
+}
+
+/// The ML-KEM-1024 keypair published in this vault's card.
+pub fn kem_identity(master_seed: &[u8; 64]) -> KemKeyPair {
+    let mut seed = Zeroizing::new([0u8; mlkem1024::DECAPSULATION_SEED_SIZE]);
+    let mut hasher = blake3::Hasher::new_derive_key(KEM_KEY_CONTEXT);
+    hasher.update(master_seed);
+    hasher.finalize_xof().fill(seed.as_mut());
+    KemKeyPair::from_seed(&seed)
+}
+
+/// The bytes a card's signature covers: every field but the signature, with
+/// keys as raw bytes so hex casing cannot change the message.
+fn card_message(card: &ContactCard) -> Result<Vec<u8>, ContactError> {
+    let mut m = CONTACT_CARD_DOMAIN.to_vec();
+    m.extend_from_slice(&card.version.to_le_bytes());
+    push_field(&mut m, card.name.as_bytes());
+    for key in identity_keys(card)? {
+        push_field(&mut m, &key);
+    }
+    m.extend_from_slice(&card.issued_at.to_le_bytes());
+    Ok(m)
+}
+
+fn identity_keys(card: &ContactCard) -> Result<[Vec<u8>; 3], ContactError> {
+    Ok([
+        decode_key(
+            "sign_public_hex",
+            &card.sign_public_hex,
+            mldsa87::PUBLIC_KEY_SIZE,
+        )?,
+        decode_key("x25519_public_hex", &card.x25519_public_hex, 32)?,
+        decode_key(
+            "kem_public_hex",
+            &card.kem_public_hex,
+            mlkem1024::ENCAPSULATION_KEY_SIZE,
+        )?,
+    ])
+}
+
+/// Build and sign this vault's contact card.
+pub fn issue_card(
+    master_seed: &[u8; 64],
+    name: &str,
+    issued_at: u64,
+) -> Result<ContactCard, ContactError> {
+    validate_contact_name(name).map_err(ContactError::Malformed)?;
+    let (sign_pk, sign_sk) = attestation::vault_identity(master_seed);
+    let x25519 = x25519_dalek::PublicKey::from(&x25519_identity(master_seed));
+    let mut card = ContactCard {
+        version: CONTACT_CARD_VERSION,
+        name: name.trim().to_string(),
+        sign_public_hex: sign_pk.to_hex(),
+        x25519_public_hex: hex::encode(x25519.as_bytes()),
+        kem_public_hex: hex::encode(&kem_identity(master_seed).encapsulation_key),
+        issued_at,
+        signature_hex: String::new(),
+    };
+    card.signature_hex = mldsa87::sign(&sign_sk, &card_message(&card)?)?.to_hex();
+    Ok(card)
+}
+
+/// Check a card's format and its self-signature. This proves the card is
+/// intact, not who issued it; compare [`fingerprint`] out of band for that.
+pub fn verify_card(card: &ContactCard) -> Result<(), ContactError> {
+    if card.version != CONTACT_CARD_VERSION {
+        return Err(ContactError::UnsupportedVersion(card.version));
+    }
+    validate_contact_name(&card.name).map_err(ContactError::Malformed)?;
+    let message = card_message(card)?;
+    let pk = PublicKey::from_hex(&card.sign_public_hex)?;
+    let sig = Signature::from_hex(&card.signature_hex).map_err(|_| ContactError::BadSignature)?;
+    if !mldsa87::verify(&pk, &message, &sig)? {
+        return Err(ContactError::BadSignature);
+    }
+    Ok(())
+}
+
+/// Fingerprint over the card's three public keys. The name and issue time
+/// are left out, so re-issuing a card under a new name keeps it.
+pub fn fingerprint(card: &ContactCard) -> Result<KeyFingerprint, ContactError> {
+    let [sign, x25519, kem] = identity_keys(card)?;
+    Ok(fingerprint_of(&sign, &x25519, &kem))
+}
+
+/// The fingerprint this vault's own card carries, without issuing one.
+pub fn own_fingerprint(master_seed: &[u8; 64]) -> KeyFingerprint {
+    let sign = attestation::vault_identity(master_seed).0;
+    let x25519 = x25519_dalek::PublicKey::from(&x25519_identity(master_seed));
+    let kem = kem_identity(master_seed);
+    fingerprint_of(sign.as_bytes(), x25519.as_bytes(), &kem.encapsulation_key)
+}
+
+fn fingerprint_of(sign: &[u8], x25519: &[u8], kem: &[u8]) -> KeyFingerprint {
+    let mut m = Vec::new();
+    for key in [sign, x25519, kem] {
+        push_field(&mut m, key);
+    }
+    KeyFingerprint::of(&m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issued_card_verifies_and_is_deterministic() {
+        let seed = [7u8; 64];
+        let card = issue_card(&seed, " Alice ", 1_700_000_000).unwrap();
+        assert_eq!(card.name, "Alice");
+        verify_card(&card).unwrap();
+
+        let again = issue_card(&seed, "Alice (laptop)", 1_800_000_000).unwrap();
+        assert_eq!(again.x25519_public_hex, card.x25519_public_hex);
+        assert_eq!(again.kem_public_hex, card.kem_public_hex);
+        assert_eq!(fingerprint(&again).unwrap(), fingerprint(&card).unwrap());
+
+        assert_eq!(own_fingerprint(&seed), fingerprint(&card).unwrap());
+
+        let other = issue_card(&[8u8; 64], "Alice", 1_700_000_000).unwrap();
+        assert_ne!(fingerprint(&other).unwrap(), fingerprint(&card).unwrap());
+    }
+
+    #[test]
+    fn tampered_card_is_rejected() {
+        let card = issue_card(&[7u8; 64], "Alice", 1).unwrap();
+
+        let mut renamed = card.clone();
+        renamed.name = "Mallory".to_string();
+        assert!(matches!(
+            verify_card(&renamed),
+            Err(ContactError::BadSignature)
+        ));
+
+        let other = issue_card(&[8u8; 64], "Mallory", 1).unwrap();
+        let mut swapped = card.clone();
+        swapped.x25519_public_hex = other.x25519_public_hex;
+        assert!(matches!(
+            verify_card(&swapped),
+            Err(ContactError::BadSignature)
+        ));
+
+        let mut truncated = card.clone();
+        truncated.kem_public_hex.truncate(10);
+        assert!(matches!(
+            verify_card(&truncated),
+            Err(ContactError::Malformed(_))
+        ));
+
+        let mut future = card;
+        future.version = 2;
+        assert!(matches!(
+            verify_card(&future),
+            Err(ContactError::UnsupportedVersion(2))
+        ));
+    }
+
+    #[test]
+    fn identity_keys_agree_with_the_card() {
+        let seed = [9u8; 64];
+        let card = issue_card(&seed, "Bob", 1).unwrap();
+        let kem = kem_identity(&seed);
+        let (ct, shared) =
+            mlkem1024::encapsulate_to(&hex::decode(&card.kem_public_hex).unwrap()).unwrap();
+        assert_eq!(kem.decapsulate(&ct).unwrap(), shared);
+        let x = x25519_dalek::PublicKey::from(&x25519_identity(&seed));
+        assert_eq!(hex::encode(x.as_bytes()), card.x25519_public_hex);
+    }
+}
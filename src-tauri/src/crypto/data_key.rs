@@ -0,0 +1,102 @@
+//! The vault's data key: a random key that the keystore, item store,
+//! master seed and every other session-encrypted store are sealed under.
+//!
+//! The key derived from the password (and YubiKey) only wraps it, so a
+//! password change re-wraps 32 bytes instead of re-encrypting every store,
+//! and the data key can later be wrapped for more than one unlock method.
+
+use crate::crypto::encryption::{self, EncryptionError};
+use crate::crypto::kdf;
+use crate::models::vault::WrappedDataKey;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+const DATA_KEY_WRAP_DOMAIN: &str = "vault_data_key_wrap";
+const DATA_KEY_VERIFIER: &[u8] = b"ZAP_DATA_KEY_VERIFIER";
+
+#[derive(Debug, Error)]
+pub enum DataKeyError {
+    #[error("the data key does not open with this key")]
+    WrongKey,
+    #[error("malformed data key record")]
+    Malformed,
+    #[error(transparent)]
+    Encryption(#[from] EncryptionError),
+}
+
+fn wrapping_key(password_key: &[u8; 32]) -> Zeroizing<[u8; 32]> {
+    Zeroizing::new(kdf::derive_encryption_key(
+        password_key,
+        DATA_KEY_WRAP_DOMAIN,
+    ))
+}
+
+pub fn generate_data_key() -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    rand::thread_rng().fill_bytes(key.as_mut());
+    key
+}
+
+/// Wrap `data_key` under `password_key`.
+pub fn wrap(
+    password_key: &[u8; 32],
+    data_key: &[u8; 32],
+    now: DateTime<Utc>,
+) -> Result<WrappedDataKey, DataKeyError> {
+    Ok(WrappedDataKey {
+        wrapped: encryption::encrypt_vault(&wrapping_key(password_key), data_key)?,
+        verifier: encryption::encrypt_vault(data_key, DATA_KEY_VERIFIER)?,
+        created_at: now,
+    })
+}
+
+/// Unwrap the data key with `password_key`.
+pub fn unwrap(
+    password_key: &[u8; 32],
+    wrapped: &WrappedDataKey,
+) -> Result<Zeroizing<[u8; 32]>, DataKeyError> {
+    let plain = Zeroizing::new(
+        encryption::decrypt_vault(&wrapping_key(password_key), &wrapped.wrapped)
+            .map_err(|_| DataKeyError::WrongKey)?,
+    );
+    let key: [u8; 32] = plain
+        .as_slice()
+        .try_into()
+        .map_err(|_| DataKeyError::Malformed)?;
+    let key = Zeroizing::new(key);
+    check(&key, wrapped)?;
+    Ok(key)
+}
+
+/// Confirm `data_key` is the key `wrapped` holds.
+pub fn check(data_key: &[u8; 32], wrapped: &WrappedDataKey) -> Result<(), DataKeyError> {
+    match encryption::decrypt_vault(data_key, &wrapped.verifier) {
+        Ok(plain) if plain == DATA_KEY_VERIFIER => Ok(()),
+        _ => Err(DataKeyError::WrongKey),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrapping_keeps_the_data_key() {
+        let data_key = generate_data_key();
+        let (old, new) = ([1u8; 32], [2u8; 32]);
+        let wrapped = wrap(&old, &data_key, Utc::now()).unwrap();
+        assert_eq!(*unwrap(&old, &wrapped).unwrap(), *data_key);
+        assert!(matches!(
+            unwrap(&new, &wrapped),
+            Err(DataKeyError::WrongKey)
+        ));
+
+        let rewrapped = wrap(&new, &unwrap(&old, &wrapped).unwrap(), Utc::now()).unwrap();
+        assert_eq!(*unwrap(&new, &rewrapped).unwrap(), *data_key);
+        assert!(unwrap(&old, &rewrapped).is_err());
+        assert!(check(&data_key, &rewrapped).is_ok());
+        assert!(check(&old, &rewrapped).is_err());
+    }
+}
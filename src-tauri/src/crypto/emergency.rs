@@ -0,0 +1,517 @@
+use crate::crypto::encryption::{self, Ciphertext, EncryptionError};
+use crate::crypto::mldsa87::{self, CryptoError, PublicKey, SecretKey, Signature};
+use crate::crypto::mlkem1024::{self, KemCiphertext, KemError, KemKeyPair};
+use crate::models::emergency::{
+    AccessRequest, EmergencyContact, EmergencyContactIdentity, EmergencyGrant, EmergencyStatus,
+    EscrowPackage, VetoRecord,
+};
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Current escrow package format version.
+pub const ESCROW_VERSION: u32 = 1;
+/// Shortest waiting period a grant may be created with (1 hour).
+pub const MIN_WAITING_PERIOD_SECS: u64 = 60 * 60;
+/// Longest waiting period a grant may be created with (90 days).
+pub const MAX_WAITING_PERIOD_SECS: u64 = 90 * 24 * 60 * 60;
+/// Tolerate this much clock skew between the contact's and the vault's clocks.
+pub const MAX_REQUEST_SKEW_SECS: u64 = 5 * 60;
+
+/// Domain label for the escrow payload AEAD key derived from the KEM secret.
+const ESCROW_AEAD_DOMAIN: &str = "ZAP_EMERGENCY_ESCROW_V1";
+/// BLAKE3 `derive_key` context for the owner signing key. Deliberately outside
+/// the HD tree so no `generate_key` path can ever collide with it.
+const OWNER_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 emergency-access owner key v1";
+
+#[derive(Debug, Error)]
+pub enum EmergencyError {
+    #[error("invalid contact key: {0}")]
+    InvalidContactKey(String),
+    #[error("waiting period must be between {MIN_WAITING_PERIOD_SECS} and {MAX_WAITING_PERIOD_SECS} seconds, got {0}")]
+    InvalidWaitingPeriod(u64),
+    #[error("{0} signature is invalid")]
+    BadSignature(&'static str),
+    #[error("access request timestamp is outside the allowed clock skew")]
+    RequestClockSkew,
+    #[error("an access request is already pending")]
+    AlreadyRequested,
+    #[error("no access request is pending")]
+    NoPendingRequest,
+    #[error("waiting period has not elapsed; {0} seconds remaining")]
+    StillWaiting(u64),
+    #[error("unsupported escrow package version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("the grant's package is not sealed under the data key yet")]
+    NotSealed,
+    #[error("malformed escrow package: {0}")]
+    Malformed(String),
+    #[error("KEM error: {0}")]
+    Kem(#[from] KemError),
+    #[error("encryption error: {0}")]
+    Encryption(#[from] EncryptionError),
+    #[error("signature error: {0}")]
+    Crypto(#[from] CryptoError),
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, EmergencyError> {
+    hex::decode(value).map_err(|e| EmergencyError::InvalidContactKey(format!("{field}: {e}")))
+}
+
+fn push_field(m: &mut Vec<u8>, field: &[u8]) {
+    m.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    m.extend_from_slice(field);
+}
+
+/// Generate a fresh recovery-contact identity (ML-KEM-1024 + ML-DSA-87). Meant
+/// to run on the contact's own install; they hand over only `contact`.
+pub fn generate_contact_identity(name: &str) -> EmergencyContactIdentity {
+    let kem = KemKeyPair::generate();
+    let (sign_pk, sign_sk) = mldsa87::generate();
+    EmergencyContactIdentity {
+        contact: EmergencyContact {
+            name: name.to_string(),
+            kem_public_hex: hex::encode(&kem.encapsulation_key),
+            sign_public_hex: sign_pk.to_hex(),
+        },
+        kem_seed_hex: hex::encode(&kem.decapsulation_seed),
+        sign_secret_hex: sign_sk.to_hex(),
+    }
+}
+
+/// Check that a contact's public keys decode to the expected sizes.
+pub fn validate_contact(contact: &EmergencyContact) -> Result<(), EmergencyError> {
+    let kem = decode_hex("kem_public_hex", &contact.kem_public_hex)?;
+    if kem.len() != mlkem1024::ENCAPSULATION_KEY_SIZE {
+        return Err(EmergencyError::InvalidContactKey(format!(
+            "ML-KEM key must be {} bytes, got {}",
+            mlkem1024::ENCAPSULATION_KEY_SIZE,
+            kem.len()
+        )));
+    }
+    PublicKey::from_hex(&contact.sign_public_hex)
+        .map_err(|e| EmergencyError::InvalidContactKey(e.to_string()))?;
+    Ok(())
+}
+
+/// Reject waiting periods outside `[MIN_WAITING_PERIOD_SECS, MAX_WAITING_PERIOD_SECS]`.
+pub fn validate_waiting_period(secs: u64) -> Result<(), EmergencyError> {
+    if !(MIN_WAITING_PERIOD_SECS..=MAX_WAITING_PERIOD_SECS).contains(&secs) {
+        return Err(EmergencyError::InvalidWaitingPeriod(secs));
+    }
+    Ok(())
+}
+
+/// The owner's grant-signing keypair, derived deterministically from the HD
+/// master seed so it survives password / YubiKey re-keying without storage.
+pub fn owner_keypair(master_seed: &[u8; 64]) -> (PublicKey, SecretKey) {
+    let mut seed = blake3::derive_key(OWNER_KEY_CONTEXT, master_seed);
+    let kp = mldsa87::from_seed(&seed);
+    seed.zeroize();
+    kp
+}
+
+/// Seal `plaintext` to the contact's ML-KEM key.
+pub fn seal_escrow(
+    contact: &EmergencyContact,
+    plaintext: &[u8],
+) -> Result<EscrowPackage, EmergencyError> {
+    let ek = decode_hex("kem_public_hex", &contact.kem_public_hex)?;
+    let (kem_ct, mut shared) = mlkem1024::encapsulate_to(&ek)?;
+    let key = Zeroizing::new(encryption::derive_aead_key(&shared, ESCROW_AEAD_DOMAIN));
+    shared.zeroize();
+    let payload = encryption::encrypt_aead(&key, plaintext)?;
+    Ok(EscrowPackage {
+        version: ESCROW_VERSION,
+        kem_ciphertext_hex: hex::encode(&kem_ct.ciphertext),
+        payload,
+    })
+}
+
+/// Open a released package with the contact's ML-KEM decapsulation seed.
+pub fn open_escrow(
+    kem_seed: &[u8],
+    package: &EscrowPackage,
+) -> Result<Zeroizing<Vec<u8>>, EmergencyError> {
+    if package.version != ESCROW_VERSION {
+        return Err(EmergencyError::UnsupportedVersion(package.version));
+    }
+    let ct = KemCiphertext {
+        ciphertext: decode_hex("kem_ciphertext_hex", &package.kem_ciphertext_hex)?,
+        encapsulated_key: Vec::new(),
+    };
+    let mut shared = mlkem1024::decapsulate_with_seed(kem_seed, &ct)?;
+    let key = Zeroizing::new(encryption::derive_aead_key(&shared, ESCROW_AEAD_DOMAIN));
+    shared.zeroize();
+    Ok(Zeroizing::new(encryption::decrypt_aead(
+        &key,
+        &package.payload,
+    )?))
+}
+
+/// Canonical encoding of the grant terms signed by the owner: id, contact keys,
+/// waiting period, creation time and the sealed package. Tampering with any of
+/// them (e.g. shortening the waiting period on disk) invalidates the grant.
+pub fn grant_message(grant: &EmergencyGrant) -> Vec<u8> {
+    let mut m = Vec::new();
+    // Version 2 signs the package as sealed under the data key, so the
+    // signature can still be checked while the vault is locked.
+    m.extend_from_slice(match grant.sealed_package {
+        Some(_) => b"ZAP_EMERGENCY_GRANT_V2",
+        None => b"ZAP_EMERGENCY_GRANT_V1",
+    });
+    push_field(&mut m, grant.id.as_bytes());
+    push_field(&mut m, grant.contact.kem_public_hex.as_bytes());
+    push_field(&mut m, grant.contact.sign_public_hex.as_bytes());
+    m.extend_from_slice(&grant.waiting_period_secs.to_le_bytes());
+    m.extend_from_slice(&grant.created_at.to_le_bytes());
+    match (&grant.sealed_package, &grant.package) {
+        (Some(sealed), _) => {
+            push_field(&mut m, &sealed.nonce);
+            push_field(&mut m, &sealed.ciphertext);
+        }
+        (None, Some(package)) => {
+            m.extend_from_slice(&package.version.to_le_bytes());
+            push_field(&mut m, package.kem_ciphertext_hex.as_bytes());
+            push_field(&mut m, &package.payload.nonce);
+            push_field(&mut m, &package.payload.ciphertext);
+        }
+        (None, None) => {}
+    }
+    m
+}
+
+/// Encrypt a contact's package under the vault's data key for storage in a
+/// grant.
+pub fn seal_package(
+    data_key: &[u8; 32],
+    package: &EscrowPackage,
+) -> Result<Ciphertext, EmergencyError> {
+    let json = serde_json::to_vec(package).map_err(|e| EmergencyError::Malformed(e.to_string()))?;
+    Ok(encryption::encrypt_vault(data_key, &json)?)
+}
+
+/// Decrypt a grant's package with the vault's data key, for release.
+pub fn unseal_package(
+    data_key: &[u8; 32],
+    grant: &EmergencyGrant,
+) -> Result<EscrowPackage, EmergencyError> {
+    let sealed = grant
+        .sealed_package
+        .as_ref()
+        .ok_or(EmergencyError::NotSealed)?;
+    let json = encryption::decrypt_vault(data_key, sealed)?;
+    serde_json::from_slice(&json).map_err(|e| EmergencyError::Malformed(e.to_string()))
+}
+
+/// Message the contact signs to request access at `requested_at`.
+pub fn request_message(grant_id: &str, requested_at: u64) -> Vec<u8> {
+    let mut m = Vec::new();
+    m.extend_from_slice(b"ZAP_EMERGENCY_REQUEST_V1");
+    push_field(&mut m, grant_id.as_bytes());
+    m.extend_from_slice(&requested_at.to_le_bytes());
+    m
+}
+
+/// Message the owner signs to veto the request made at `requested_at`.
+pub fn veto_message(grant_id: &str, requested_at: u64, vetoed_at: u64) -> Vec<u8> {
+    let mut m = Vec::new();
+    m.extend_from_slice(b"ZAP_EMERGENCY_VETO_V1");
+    push_field(&mut m, grant_id.as_bytes());
+    m.extend_from_slice(&requested_at.to_le_bytes());
+    m.extend_from_slice(&vetoed_at.to_le_bytes());
+    m
+}
+
+fn check_signature(
+    what: &'static str,
+    public_hex: &str,
+    message: &[u8],
+    signature_hex: &str,
+) -> Result<(), EmergencyError> {
+    let pk = PublicKey::from_hex(public_hex)?;
+    let sig = Signature::from_hex(signature_hex)?;
+    if !mldsa87::verify(&pk, message, &sig)? {
+        return Err(EmergencyError::BadSignature(what));
+    }
+    Ok(())
+}
+
+/// Verify the owner's signature over the grant terms.
+pub fn verify_grant(grant: &EmergencyGrant) -> Result<(), EmergencyError> {
+    check_signature(
+        "grant",
+        &grant.owner_public_hex,
+        &grant_message(grant),
+        &grant.grant_signature_hex,
+    )
+}
+
+/// Contact-side helper: sign an access request for `grant_id` at `now`.
+pub fn sign_request(
+    sign_secret: &SecretKey,
+    grant_id: &str,
+    now: u64,
+) -> Result<AccessRequest, EmergencyError> {
+    let sig = mldsa87::sign(sign_secret, &request_message(grant_id, now))?;
+    Ok(AccessRequest {
+        requested_at: now,
+        signature_hex: sig.to_hex(),
+    })
+}
+
+/// Lifecycle state of `grant` at reference time `now` (unix seconds).
+pub fn status(grant: &EmergencyGrant, now: u64) -> EmergencyStatus {
+    match &grant.request {
+        None => EmergencyStatus::Armed,
+        Some(req) => {
+            let releasable_at = req.requested_at.saturating_add(grant.waiting_period_secs);
+            if now >= releasable_at {
+                EmergencyStatus::Releasable {
+                    requested_at: req.requested_at,
+                }
+            } else {
+                EmergencyStatus::Waiting {
+                    requested_at: req.requested_at,
+                    releasable_at,
+                }
+            }
+        }
+    }
+}
+
+/// Validate and record a contact's access request, starting the waiting
+/// period. The request must be signed by the grant's contact and its timestamp
+/// must be within `MAX_REQUEST_SKEW_SECS` of `now`, so a contact cannot
+/// backdate a request to skip the waiting period.
+pub fn record_request(
+    grant: &mut EmergencyGrant,
+    request: AccessRequest,
+    now: u64,
+) -> Result<(), EmergencyError> {
+    verify_grant(grant)?;
+    if grant.request.is_some() {
+        return Err(EmergencyError::AlreadyRequested);
+    }
+    if request.requested_at.abs_diff(now) > MAX_REQUEST_SKEW_SECS {
+        return Err(EmergencyError::RequestClockSkew);
+    }
+    check_signature(
+        "access request",
+        &grant.contact.sign_public_hex,
+        &request_message(&grant.id, request.requested_at),
+        &request.signature_hex,
+    )?;
+    grant.request = Some(request);
+    Ok(())
+}
+
+/// Owner veto: cancel the pending request and append a signed veto record.
+/// The grant stays armed, so the contact may request again later.
+pub fn apply_veto(
+    grant: &mut EmergencyGrant,
+    owner_secret: &SecretKey,
+    now: u64,
+) -> Result<(), EmergencyError> {
+    let requested_at = grant
+        .request
+        .as_ref()
+        .ok_or(EmergencyError::NoPendingRequest)?
+        .requested_at;
+    let sig = mldsa87::sign(owner_secret, &veto_message(&grant.id, requested_at, now))?;
+    grant.vetoes.push(VetoRecord {
+        requested_at,
+        vetoed_at: now,
+        signature_hex: sig.to_hex(),
+    });
+    grant.request = None;
+    Ok(())
+}
+
+/// Check that the grant and its request verify and the waiting period has
+/// fully elapsed at `now`, so the package may be released.
+pub fn check_releasable(grant: &EmergencyGrant, now: u64) -> Result<(), EmergencyError> {
+    verify_grant(grant)?;
+    let req = grant
+        .request
+        .as_ref()
+        .ok_or(EmergencyError::NoPendingRequest)?;
+    check_signature(
+        "access request",
+        &grant.contact.sign_public_hex,
+        &request_message(&grant.id, req.requested_at),
+        &req.signature_hex,
+    )?;
+    match status(grant, now) {
+        EmergencyStatus::Releasable { .. } => Ok(()),
+        EmergencyStatus::Waiting { releasable_at, .. } => {
+            Err(EmergencyError::StillWaiting(releasable_at - now))
+        }
+        EmergencyStatus::Armed => Err(EmergencyError::NoPendingRequest),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T0: u64 = 1_800_000_000;
+    const DAY: u64 = 24 * 60 * 60;
+    const DATA_KEY: [u8; 32] = [9u8; 32];
+
+    fn grant_for(identity: &EmergencyContactIdentity) -> (EmergencyGrant, SecretKey) {
+        let (owner_pk, owner_sk) = owner_keypair(&[7u8; 64]);
+        let mut grant = EmergencyGrant {
+            id: "grant-1".to_string(),
+            contact: identity.contact.clone(),
+            waiting_period_secs: 7 * DAY,
+            created_at: T0,
+            owner_public_hex: owner_pk.to_hex(),
+            grant_signature_hex: String::new(),
+            sealed_package: Some(
+                seal_package(
+                    &DATA_KEY,
+                    &seal_escrow(&identity.contact, b"escrowed seed").unwrap(),
+                )
+                .unwrap(),
+            ),
+            package: None,
+            request: None,
+            vetoes: Vec::new(),
+        };
+        grant.grant_signature_hex = mldsa87::sign(&owner_sk, &grant_message(&grant))
+            .unwrap()
+            .to_hex();
+        (grant, owner_sk)
+    }
+
+    fn contact_secret(identity: &EmergencyContactIdentity) -> SecretKey {
+        SecretKey::from_hex(&identity.sign_secret_hex).unwrap()
+    }
+
+    #[test]
+    fn test_seal_and_open_escrow() {
+        let id = generate_contact_identity("alice");
+        let pkg = seal_escrow(&id.contact, b"secret").unwrap();
+        let seed = hex::decode(&id.kem_seed_hex).unwrap();
+        assert_eq!(open_escrow(&seed, &pkg).unwrap().as_slice(), b"secret");
+
+        let other = generate_contact_identity("mallory");
+        let other_seed = hex::decode(&other.kem_seed_hex).unwrap();
+        assert!(open_escrow(&other_seed, &pkg).is_err());
+    }
+
+    #[test]
+    fn test_owner_keypair_is_deterministic() {
+        assert_eq!(
+            owner_keypair(&[1u8; 64]).0.to_hex(),
+            owner_keypair(&[1u8; 64]).0.to_hex()
+        );
+        assert_ne!(
+            owner_keypair(&[1u8; 64]).0.to_hex(),
+            owner_keypair(&[2u8; 64]).0.to_hex()
+        );
+    }
+
+    #[test]
+    fn test_waiting_period_bounds() {
+        assert!(validate_waiting_period(MIN_WAITING_PERIOD_SECS).is_ok());
+        assert!(validate_waiting_period(MIN_WAITING_PERIOD_SECS - 1).is_err());
+        assert!(validate_waiting_period(MAX_WAITING_PERIOD_SECS + 1).is_err());
+    }
+
+    #[test]
+    fn test_full_lifecycle_request_wait_release() {
+        let id = generate_contact_identity("alice");
+        let (mut grant, _) = grant_for(&id);
+        assert_eq!(status(&grant, T0), EmergencyStatus::Armed);
+
+        let req = sign_request(&contact_secret(&id), &grant.id, T0 + DAY).unwrap();
+        record_request(&mut grant, req, T0 + DAY).unwrap();
+
+        assert!(matches!(
+            check_releasable(&grant, T0 + 2 * DAY),
+            Err(EmergencyError::StillWaiting(s)) if s == 6 * DAY
+        ));
+        check_releasable(&grant, T0 + 8 * DAY).unwrap();
+
+        let package = unseal_package(&DATA_KEY, &grant).unwrap();
+        let seed = hex::decode(&id.kem_seed_hex).unwrap();
+        assert_eq!(
+            open_escrow(&seed, &package).unwrap().as_slice(),
+            b"escrowed seed"
+        );
+        assert!(unseal_package(&[1u8; 32], &grant).is_err());
+    }
+
+    #[test]
+    fn test_legacy_grant_still_verifies() {
+        let id = generate_contact_identity("alice");
+        let (mut grant, owner_sk) = grant_for(&id);
+        grant.sealed_package = None;
+        grant.package = Some(seal_escrow(&id.contact, b"escrowed seed").unwrap());
+        assert!(verify_grant(&grant).is_err());
+        grant.grant_signature_hex = mldsa87::sign(&owner_sk, &grant_message(&grant))
+            .unwrap()
+            .to_hex();
+        verify_grant(&grant).unwrap();
+        assert!(matches!(
+            unseal_package(&DATA_KEY, &grant),
+            Err(EmergencyError::NotSealed)
+        ));
+    }
+
+    #[test]
+    fn test_veto_cancels_pending_request() {
+        let id = generate_contact_identity("alice");
+        let (mut grant, owner_sk) = grant_for(&id);
+        let req = sign_request(&contact_secret(&id), &grant.id, T0).unwrap();
+        record_request(&mut grant, req, T0).unwrap();
+
+        apply_veto(&mut grant, &owner_sk, T0 + DAY).unwrap();
+        assert_eq!(status(&grant, T0 + 30 * DAY), EmergencyStatus::Armed);
+        assert!(check_releasable(&grant, T0 + 30 * DAY).is_err());
+        assert_eq!(grant.vetoes.len(), 1);
+        check_signature(
+            "veto",
+            &grant.owner_public_hex,
+            &veto_message(&grant.id, T0, T0 + DAY),
+            &grant.vetoes[0].signature_hex,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_request_from_wrong_key_rejected() {
+        let id = generate_contact_identity("alice");
+        let mallory = generate_contact_identity("mallory");
+        let (mut grant, _) = grant_for(&id);
+        let req = sign_request(&contact_secret(&mallory), &grant.id, T0).unwrap();
+        assert!(matches!(
+            record_request(&mut grant, req, T0),
+            Err(EmergencyError::BadSignature(_))
+        ));
+    }
+
+    #[test]
+    fn test_backdated_request_rejected() {
+        let id = generate_contact_identity("alice");
+        let (mut grant, _) = grant_for(&id);
+        let req = sign_request(&contact_secret(&id), &grant.id, T0 - 30 * DAY).unwrap();
+        assert!(matches!(
+            record_request(&mut grant, req, T0),
+            Err(EmergencyError::RequestClockSkew)
+        ));
+    }
+
+    #[test]
+    fn test_tampered_waiting_period_invalidates_grant() {
+        let id = generate_contact_identity("alice");
+        let (mut grant, _) = grant_for(&id);
+        grant.waiting_period_secs = MIN_WAITING_PERIOD_SECS;
+        let req = sign_request(&contact_secret(&id), &grant.id, T0).unwrap();
+        assert!(matches!(
+            record_request(&mut grant, req, T0),
+            Err(EmergencyError::BadSignature("grant"))
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+//! Short, human-comparable fingerprints of public keys.
+//!
+//! A fingerprint is the first 8 bytes of SHA-256 over the raw public key,
+//! shown as hex and as a pair of BIP39 English words. The identicon is drawn
+//! from the rest of the same digest, so two keys that look alike in one form
+//! still differ in the other.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Bytes of the digest shown as the fingerprint.
+pub const FINGERPRINT_BYTES: usize = 8;
+
+/// Cells per side of the identicon grid.
+pub const IDENTICON_SIZE: usize = 5;
+
+/// A 5×5 grid mirrored left to right, plus a fill colour.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Identicon {
+    /// `#rrggbb`.
+    pub color: String,
+    /// Row-major; `true` cells are filled.
+    pub cells: Vec<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyFingerprint {
+    /// `a1b2:c3d4:e5f6:0718`.
+    pub hex: String,
+    /// Two BIP39 English words, e.g. `orbit-velvet`.
+    pub words: String,
+    pub identicon: Identicon,
+}
+
+impl KeyFingerprint {
+    /// Fingerprint of a raw public key.
+    pub fn of(public_key: &[u8]) -> Self {
+        let digest: [u8; 32] = Sha256::digest(public_key).into();
+        KeyFingerprint {
+            hex: grouped_hex(&digest[..FINGERPRINT_BYTES]),
+            words: word_pair(&digest),
+            identicon: identicon(&digest),
+        }
+    }
+
+    /// Fingerprint of a hex-encoded public key as stored in the keystore.
+    /// Undecodable input is fingerprinted as text, so every entry gets one.
+    pub fn of_hex(public_key_hex: &str) -> Self {
+        match hex::decode(public_key_hex) {
+            Ok(bytes) => Self::of(&bytes),
+            Err(_) => Self::of(public_key_hex.as_bytes()),
+        }
+    }
+
+    /// Whether `claimed` matches this fingerprint, in either its hex or its
+    /// word form. Case, spaces, colons and dashes are ignored, so a value
+    /// read aloud or retyped from paper still compares.
+    pub fn matches(&self, claimed: &str) -> bool {
+        let canon = |s: &str| -> String {
+            s.chars()
+                .filter(|c| !matches!(c, ':' | '-' | ' '))
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+        let claimed = canon(claimed);
+        !claimed.is_empty() && (claimed == canon(&self.hex) || claimed == canon(&self.words))
+    }
+}
+
+fn grouped_hex(bytes: &[u8]) -> String {
+    bytes
+        .chunks(2)
+        .map(hex::encode)
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Two 11-bit indices into the BIP39 English list from the first 22 bits.
+fn word_pair(digest: &[u8; 32]) -> String {
+    let bits = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+    let list = bip39::Language::English.word_list();
+    let first = list[(bits >> 21) as usize];
+    let second = list[((bits >> 10) & 0x7ff) as usize];
+    format!("{first}-{second}")
+}
+
+fn identicon(digest: &[u8; 32]) -> Identicon {
+    let color = format!(
+        "#{:02x}{:02x}{:02x}",
+        digest[FINGERPRINT_BYTES],
+        digest[FINGERPRINT_BYTES + 1],
+        digest[FINGERPRINT_BYTES + 2]
+    );
+    let half = IDENTICON_SIZE.div_ceil(2);
+    let pattern = &digest[FINGERPRINT_BYTES + 3..];
+    let mut cells = vec![false; IDENTICON_SIZE * IDENTICON_SIZE];
+    for row in 0..IDENTICON_SIZE {
+        for col in 0..half {
+            let filled = pattern[row * half + col] & 1 == 1;
+            cells[row * IDENTICON_SIZE + col] = filled;
+            cells[row * IDENTICON_SIZE + IDENTICON_SIZE - 1 - col] = filled;
+        }
+    }
+    Identicon { color, cells }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_is_sha256_prefix() {
+        // SHA-256("abc") = ba7816bf8f01cfea414140de5dae2223...
+        let fp = KeyFingerprint::of(b"abc");
+        assert_eq!(fp.hex, "ba78:16bf:8f01:cfea");
+        assert_eq!(fp, KeyFingerprint::of_hex(&hex::encode(b"abc")));
+        assert_ne!(fp, KeyFingerprint::of(b"abd"));
+    }
+
+    #[test]
+    fn word_pair_uses_the_top_22_bits() {
+        // 0xba78_16bf: first 11 bits 0x5d3 = 1491, next 11 bits 0x605 = 1541.
+        let list = bip39::Language::English.word_list();
+        let fp = KeyFingerprint::of(b"abc");
+        assert_eq!(fp.words, format!("{}-{}", list[1491], list[1541]));
+    }
+
+    #[test]
+    fn identicon_is_mirrored() {
+        let icon = KeyFingerprint::of(b"some public key").identicon;
+        assert_eq!(icon.cells.len(), IDENTICON_SIZE * IDENTICON_SIZE);
+        assert_eq!(icon.color.len(), 7);
+        for row in icon.cells.chunks(IDENTICON_SIZE) {
+            let mut reversed = row.to_vec();
+            reversed.reverse();
+            assert_eq!(row, reversed.as_slice());
+        }
+    }
+
+    #[test]
+    fn matches_ignores_formatting() {
+        let fp = KeyFingerprint::of(b"abc");
+        assert!(fp.matches("BA7816BF8F01CFEA"));
+        assert!(fp.matches("ba78 16bf 8f01 cfea"));
+        assert!(fp.matches(&fp.words.replace('-', " ").to_uppercase()));
+        assert!(!fp.matches("ba78:16bf:8f01:cfeb"));
+        assert!(!fp.matches(""));
+    }
+}
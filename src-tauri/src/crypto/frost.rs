@@ -0,0 +1,904 @@
+use crate::crypto::encryption::{self, EncryptionError};
+use crate::crypto::kdf::{self, KdfError, KdfParams};
+use crate::models::frost::{
+    FrostCommitment, FrostDkgRound1, FrostDkgRound2, FrostDkgSecret, FrostGroup, FrostKeyShare,
+    FrostNonces, FrostShareExport, FrostSignature, FrostSignatureShare, FrostSigningPackage,
+    FROST_SHARE_EXPORT_FORMAT,
+};
+use crate::models::mobile::BundleKdf;
+use chrono::{DateTime, Utc};
+use k256::elliptic_curve::ops::Reduce;
+use k256::elliptic_curve::point::AffineCoordinates;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::elliptic_curve::{Field, PrimeField};
+use k256::{FieldBytes, ProjectivePoint, Scalar, U256};
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// Current share export format version.
+pub const FROST_SHARE_EXPORT_VERSION: u32 = 1;
+/// Largest group supported. Every participant handles one round-2 package
+/// per peer, so this is about usability rather than the math.
+pub const MAX_FROST_PARTICIPANTS: u16 = 32;
+/// Shortest share export password accepted.
+pub const MIN_SHARE_PASSWORD_CHARS: usize = 12;
+
+const DKG_PROOF_TAG: &[u8] = b"ZAP/frost-secp256k1/dkg-proof";
+const BINDING_TAG: &[u8] = b"ZAP/frost-secp256k1/binding";
+const CHALLENGE_TAG: &[u8] = b"BIP0340/challenge";
+const TAP_TWEAK_TAG: &[u8] = b"TapTweak";
+/// BLAKE3 `derive_key` context for the key a round-2 share is encrypted under.
+const SHARE_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 FROST DKG share key v1";
+const EXPORT_KEY_DOMAIN: &str = "frost_share_export";
+
+#[derive(Debug, Error)]
+pub enum FrostError {
+    #[error("malformed FROST data: {0}")]
+    Malformed(String),
+    #[error("invalid threshold {threshold} of {participants}")]
+    InvalidThreshold { threshold: u16, participants: u16 },
+    #[error("round-1 package from participant {0} is invalid")]
+    BadRound1(u16),
+    #[error("secret share from participant {0} does not match its commitments")]
+    BadShare(u16),
+    #[error("signature share from participant {0} is invalid")]
+    BadSignatureShare(u16),
+    #[error("signing package needs between {threshold} and {participants} commitments")]
+    WrongSignerCount { threshold: u16, participants: u16 },
+    #[error("aggregated signature does not verify")]
+    BadSignature,
+    #[error("share password must be at least {MIN_SHARE_PASSWORD_CHARS} characters")]
+    WeakPassword,
+    #[error("wrong share password")]
+    WrongPassword,
+    #[error("unsupported share export version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("key derivation error: {0}")]
+    Kdf(#[from] KdfError),
+    #[error("encryption error: {0}")]
+    Encryption(#[from] EncryptionError),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, FrostError> {
+    hex::decode(value).map_err(|e| FrostError::Malformed(format!("{field}: {e}")))
+}
+
+fn scalar_hex(s: &Scalar) -> String {
+    hex::encode(s.to_bytes())
+}
+
+fn scalar_from_hex(field: &str, value: &str) -> Result<Scalar, FrostError> {
+    let bytes = Zeroizing::new(decode_hex(field, value)?);
+    if bytes.len() != 32 {
+        return Err(FrostError::Malformed(field.to_string()));
+    }
+    Option::<Scalar>::from(Scalar::from_repr(FieldBytes::clone_from_slice(&bytes)))
+        .ok_or_else(|| FrostError::Malformed(field.to_string()))
+}
+
+fn point_hex(p: &ProjectivePoint) -> String {
+    hex::encode(p.to_affine().to_encoded_point(true).as_bytes())
+}
+
+fn point_from_hex(field: &str, value: &str) -> Result<ProjectivePoint, FrostError> {
+    k256::PublicKey::from_sec1_bytes(&decode_hex(field, value)?)
+        .map(|p| p.to_projective())
+        .map_err(|_| FrostError::Malformed(field.to_string()))
+}
+
+fn x_only(p: &ProjectivePoint) -> FieldBytes {
+    p.to_affine().x()
+}
+
+fn has_odd_y(p: &ProjectivePoint) -> bool {
+    p.to_affine().y_is_odd().into()
+}
+
+/// BIP-340 tagged hash.
+pub(crate) fn tagged_hash(tag: &[u8], parts: &[&[u8]]) -> FieldBytes {
+    let tag_hash = Sha256::digest(tag);
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize()
+}
+
+fn hash_to_scalar(tag: &[u8], parts: &[&[u8]]) -> Scalar {
+    <Scalar as Reduce<U256>>::reduce_bytes(&tagged_hash(tag, parts))
+}
+
+/// `f(x)` for the polynomial with `coefficients`, constant term first.
+fn evaluate(coefficients: &[Scalar], x: u16) -> Scalar {
+    let x = Scalar::from(u64::from(x));
+    coefficients
+        .iter()
+        .rev()
+        .fold(Scalar::ZERO, |acc, c| acc * x + c)
+}
+
+/// `f(x)·G` from the commitments to `f`'s coefficients.
+fn evaluate_commitments(commitments: &[ProjectivePoint], x: u16) -> ProjectivePoint {
+    let x = Scalar::from(u64::from(x));
+    commitments
+        .iter()
+        .rev()
+        .fold(ProjectivePoint::IDENTITY, |acc, c| acc * x + c)
+}
+
+/// Lagrange coefficient at zero for `index` over `signers`.
+fn lagrange(index: u16, signers: &[u16]) -> Scalar {
+    let xi = Scalar::from(u64::from(index));
+    let (num, den) = signers.iter().filter(|&&j| j != index).fold(
+        (Scalar::ONE, Scalar::ONE),
+        |(num, den), &j| {
+            let xj = Scalar::from(u64::from(j));
+            (num * xj, den * (xj - xi))
+        },
+    );
+    // Signer indices are distinct and non-zero, so `den` is never zero.
+    num * Option::<Scalar>::from(den.invert()).unwrap_or(Scalar::ZERO)
+}
+
+fn check_parameters(threshold: u16, participants: u16) -> Result<(), FrostError> {
+    if threshold < 2 || threshold > participants || participants > MAX_FROST_PARTICIPANTS {
+        return Err(FrostError::InvalidThreshold {
+            threshold,
+            participants,
+        });
+    }
+    Ok(())
+}
+
+fn proof_challenge(
+    group_id: &str,
+    index: u16,
+    constant: &ProjectivePoint,
+    r: &ProjectivePoint,
+) -> Scalar {
+    hash_to_scalar(
+        DKG_PROOF_TAG,
+        &[
+            &(group_id.len() as u32).to_le_bytes(),
+            group_id.as_bytes(),
+            &index.to_le_bytes(),
+            constant.to_affine().to_encoded_point(true).as_bytes(),
+            r.to_affine().to_encoded_point(true).as_bytes(),
+        ],
+    )
+}
+
+/// Key a round-2 share from `from` to `to` is encrypted under: an ECDH
+/// between the two round-1 encryption keys, bound to the group and direction.
+fn share_key(
+    own_secret: &Scalar,
+    peer_public: &ProjectivePoint,
+    group_id: &str,
+    from: u16,
+    to: u16,
+) -> Zeroizing<[u8; 32]> {
+    let mut shared = Zeroizing::new([0u8; 32]);
+    shared.copy_from_slice(&x_only(&(peer_public * own_secret)));
+    let mut hasher = blake3::Hasher::new_derive_key(SHARE_KEY_CONTEXT);
+    hasher.update(&shared[..]);
+    hasher.update(&(group_id.len() as u32).to_le_bytes());
+    hasher.update(group_id.as_bytes());
+    hasher.update(&from.to_le_bytes());
+    hasher.update(&to.to_le_bytes());
+    Zeroizing::new(*hasher.finalize().as_bytes())
+}
+
+/// Start key generation as participant `index` (1-based) of a
+/// `threshold`-of-`participants` group. Broadcast the returned package to
+/// every other participant; keep the secret.
+pub fn dkg_begin(
+    group_id: &str,
+    threshold: u16,
+    participants: u16,
+    index: u16,
+) -> Result<FrostDkgSecret, FrostError> {
+    check_parameters(threshold, participants)?;
+    if index == 0 || index > participants {
+        return Err(FrostError::Malformed(format!("participant index {index}")));
+    }
+    let coefficients: Vec<Scalar> = (0..threshold).map(|_| Scalar::random(&mut OsRng)).collect();
+    let commitments: Vec<ProjectivePoint> = coefficients
+        .iter()
+        .map(|c| ProjectivePoint::GENERATOR * c)
+        .collect();
+    let k = Scalar::random(&mut OsRng);
+    let r = ProjectivePoint::GENERATOR * k;
+    let z = k + coefficients[0] * proof_challenge(group_id, index, &commitments[0], &r);
+    let encryption_secret = Scalar::random(&mut OsRng);
+
+    Ok(FrostDkgSecret {
+        package: FrostDkgRound1 {
+            group_id: group_id.to_string(),
+            threshold,
+            participants,
+            index,
+            commitments: commitments.iter().map(point_hex).collect(),
+            proof_r_hex: point_hex(&r),
+            proof_z_hex: scalar_hex(&z),
+            encryption_key_hex: point_hex(&(ProjectivePoint::GENERATOR * encryption_secret)),
+        },
+        coefficients: coefficients.iter().map(scalar_hex).collect(),
+        encryption_secret_hex: scalar_hex(&encryption_secret),
+        round1: Vec::new(),
+    })
+}
+
+/// Check one round-1 package against the parameters of `own`.
+fn verify_round1(
+    own: &FrostDkgRound1,
+    package: &FrostDkgRound1,
+) -> Result<Vec<ProjectivePoint>, FrostError> {
+    let bad = || FrostError::BadRound1(package.index);
+    if package.group_id != own.group_id
+        || package.threshold != own.threshold
+        || package.participants != own.participants
+        || package.index == 0
+        || package.index > own.participants
+        || package.commitments.len() != usize::from(own.threshold)
+    {
+        return Err(bad());
+    }
+    let commitments = package
+        .commitments
+        .iter()
+        .map(|c| point_from_hex("commitments", c))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| bad())?;
+    point_from_hex("encryption_key_hex", &package.encryption_key_hex).map_err(|_| bad())?;
+    let r = point_from_hex("proof_r_hex", &package.proof_r_hex).map_err(|_| bad())?;
+    let z = scalar_from_hex("proof_z_hex", &package.proof_z_hex).map_err(|_| bad())?;
+    let c = proof_challenge(&package.group_id, package.index, &commitments[0], &r);
+    if ProjectivePoint::GENERATOR * z != r + commitments[0] * c {
+        return Err(bad());
+    }
+    Ok(commitments)
+}
+
+/// Second round: with every participant's round-1 package (own included or
+/// not), verify them all and encrypt a secret share to each peer. Send each
+/// returned package to its `to` participant.
+pub fn dkg_round2(
+    secret: &mut FrostDkgSecret,
+    round1: &[FrostDkgRound1],
+) -> Result<Vec<FrostDkgRound2>, FrostError> {
+    let own = secret.package.clone();
+    let mut all: Vec<FrostDkgRound1> = round1
+        .iter()
+        .filter(|p| p.index != own.index)
+        .cloned()
+        .collect();
+    all.push(own.clone());
+    all.sort_by_key(|p| p.index);
+    all.dedup_by_key(|p| p.index);
+    if all.len() != usize::from(own.participants) {
+        return Err(FrostError::Malformed(format!(
+            "expected {} round-1 packages, got {}",
+            own.participants,
+            all.len()
+        )));
+    }
+    for package in &all {
+        verify_round1(&own, package)?;
+    }
+
+    let coefficients = secret
+        .coefficients
+        .iter()
+        .map(|c| scalar_from_hex("coefficients", c))
+        .collect::<Result<Vec<_>, _>>()?;
+    let encryption_secret =
+        scalar_from_hex("encryption_secret_hex", &secret.encryption_secret_hex)?;
+    let mut out = Vec::with_capacity(all.len() - 1);
+    for peer in all.iter().filter(|p| p.index != own.index) {
+        let peer_key = point_from_hex("encryption_key_hex", &peer.encryption_key_hex)?;
+        let key = share_key(
+            &encryption_secret,
+            &peer_key,
+            &own.group_id,
+            own.index,
+            peer.index,
+        );
+        let mut share = Zeroizing::new([0u8; 32]);
+        share.copy_from_slice(&evaluate(&coefficients, peer.index).to_bytes());
+        out.push(FrostDkgRound2 {
+            group_id: own.group_id.clone(),
+            from: own.index,
+            to: peer.index,
+            share: encryption::encrypt_aead(&key, &share[..])?,
+        });
+    }
+    secret.round1 = all;
+    Ok(out)
+}
+
+/// Finish key generation with the round-2 packages addressed to this
+/// participant: check every share against its sender's commitments and
+/// combine them into this participant's key share.
+pub fn dkg_finish(
+    secret: &FrostDkgSecret,
+    round2: &[FrostDkgRound2],
+) -> Result<FrostKeyShare, FrostError> {
+    let own = &secret.package;
+    if secret.round1.len() != usize::from(own.participants) {
+        return Err(FrostError::Malformed("round 2 has not run".to_string()));
+    }
+    let commitments = secret
+        .round1
+        .iter()
+        .map(|p| verify_round1(own, p))
+        .collect::<Result<Vec<_>, _>>()?;
+    let coefficients = secret
+        .coefficients
+        .iter()
+        .map(|c| scalar_from_hex("coefficients", c))
+        .collect::<Result<Vec<_>, _>>()?;
+    let encryption_secret =
+        scalar_from_hex("encryption_secret_hex", &secret.encryption_secret_hex)?;
+
+    let mut signing_share = evaluate(&coefficients, own.index);
+    for peer in secret.round1.iter().filter(|p| p.index != own.index) {
+        let package = round2
+            .iter()
+            .find(|p| p.group_id == own.group_id && p.from == peer.index && p.to == own.index)
+            .ok_or_else(|| {
+                FrostError::Malformed(format!("missing share from participant {}", peer.index))
+            })?;
+        let peer_key = point_from_hex("encryption_key_hex", &peer.encryption_key_hex)?;
+        let key = share_key(
+            &encryption_secret,
+            &peer_key,
+            &own.group_id,
+            peer.index,
+            own.index,
+        );
+        let bytes = Zeroizing::new(
+            encryption::decrypt_aead(&key, &package.share)
+                .map_err(|_| FrostError::BadShare(peer.index))?,
+        );
+        if bytes.len() != 32 {
+            return Err(FrostError::BadShare(peer.index));
+        }
+        let share = Option::<Scalar>::from(Scalar::from_repr(FieldBytes::clone_from_slice(&bytes)))
+            .ok_or(FrostError::BadShare(peer.index))?;
+        let expected = evaluate_commitments(&commitments[usize::from(peer.index) - 1], own.index);
+        if ProjectivePoint::GENERATOR * share != expected {
+            return Err(FrostError::BadShare(peer.index));
+        }
+        signing_share += share;
+    }
+
+    let group_public = commitments
+        .iter()
+        .fold(ProjectivePoint::IDENTITY, |acc, c| acc + c[0]);
+    let verifying_shares = (1..=own.participants)
+        .map(|m| {
+            point_hex(
+                &commitments
+                    .iter()
+                    .fold(ProjectivePoint::IDENTITY, |acc, c| {
+                        acc + evaluate_commitments(c, m)
+                    }),
+            )
+        })
+        .collect();
+    let share = FrostKeyShare {
+        group: FrostGroup {
+            group_id: own.group_id.clone(),
+            threshold: own.threshold,
+            participants: own.participants,
+            group_public_hex: point_hex(&group_public),
+            verifying_shares,
+            taproot_output_key_hex: hex::encode(
+                tweak(&group_public, None)?.output_key.to_affine().x(),
+            ),
+        },
+        index: own.index,
+        secret_share_hex: scalar_hex(&signing_share),
+    };
+    if point_hex(&(ProjectivePoint::GENERATOR * signing_share))
+        != share.group.verifying_shares[usize::from(own.index) - 1]
+    {
+        return Err(FrostError::BadShare(own.index));
+    }
+    Ok(share)
+}
+
+/// The BIP-341 tweak of a group key.
+struct Tweak {
+    /// Output key with even y.
+    output_key: ProjectivePoint,
+    /// Sign applied to every secret share so the shares sum to the secret of
+    /// the even-y output key (minus the tweak).
+    share_sign: Scalar,
+    /// Tweak term the coordinator adds, already sign-adjusted.
+    tweak: Scalar,
+}
+
+fn tweak(group_public: &ProjectivePoint, merkle_root: Option<&[u8]>) -> Result<Tweak, FrostError> {
+    let negate_internal = has_odd_y(group_public);
+    let internal = if negate_internal {
+        -group_public
+    } else {
+        *group_public
+    };
+    let t = Option::<Scalar>::from(Scalar::from_repr(tagged_hash(
+        TAP_TWEAK_TAG,
+        &[&x_only(&internal), merkle_root.unwrap_or_default()],
+    )))
+    .ok_or_else(|| FrostError::Malformed("taproot tweak out of range".to_string()))?;
+    let output = internal + ProjectivePoint::GENERATOR * t;
+    let negate_output = has_odd_y(&output);
+    let sign = |negate: bool| if negate { -Scalar::ONE } else { Scalar::ONE };
+    Ok(Tweak {
+        output_key: if negate_output { -output } else { output },
+        share_sign: sign(negate_internal) * sign(negate_output),
+        tweak: sign(negate_output) * t,
+    })
+}
+
+/// The x-only taproot output key of `group` for a script tree with
+/// `merkle_root_hex`, or the key-path-only key when `None`.
+pub fn taproot_output_key(
+    group: &FrostGroup,
+    merkle_root_hex: Option<&str>,
+) -> Result<String, FrostError> {
+    let root = merkle_root_hex
+        .map(|r| decode_hex("merkle_root_hex", r))
+        .transpose()?;
+    let group_public = point_from_hex("group_public_hex", &group.group_public_hex)?;
+    Ok(hex::encode(
+        tweak(&group_public, root.as_deref())?
+            .output_key
+            .to_affine()
+            .x(),
+    ))
+}
+
+/// Fresh signing nonces for one session with `share`. Send the commitment to
+/// the coordinator; keep the nonces and use them for exactly one signature.
+pub fn commit(share: &FrostKeyShare) -> FrostNonces {
+    let hiding = Scalar::random(&mut OsRng);
+    let binding = Scalar::random(&mut OsRng);
+    FrostNonces {
+        commitment: FrostCommitment {
+            index: share.index,
+            hiding_hex: point_hex(&(ProjectivePoint::GENERATOR * hiding)),
+            binding_hex: point_hex(&(ProjectivePoint::GENERATOR * binding)),
+        },
+        hiding_secret_hex: scalar_hex(&hiding),
+        binding_secret_hex: scalar_hex(&binding),
+    }
+}
+
+/// Everything about a signing package every signer and the coordinator
+/// derive identically.
+struct Session {
+    tweak: Tweak,
+    message: Vec<u8>,
+    signers: Vec<u16>,
+    /// `(index, D + ρ·E)` per signer, before any negation for R's parity.
+    nonce_points: Vec<(u16, ProjectivePoint)>,
+    binding: Vec<(u16, Scalar)>,
+    /// Whether R had odd y and every nonce is negated.
+    negate_nonces: bool,
+    group_commitment: ProjectivePoint,
+    challenge: Scalar,
+}
+
+fn session(group: &FrostGroup, package: &FrostSigningPackage) -> Result<Session, FrostError> {
+    if package.group_id != group.group_id {
+        return Err(FrostError::Malformed(
+            "signing package is for another group".to_string(),
+        ));
+    }
+    let message = decode_hex("message_hex", &package.message_hex)?;
+    if message.len() != 32 {
+        return Err(FrostError::Malformed(
+            "message_hex must be a 32-byte sighash".to_string(),
+        ));
+    }
+    let mut commitments = package.commitments.clone();
+    commitments.sort_by_key(|c| c.index);
+    commitments.dedup_by_key(|c| c.index);
+    if commitments.len() != package.commitments.len()
+        || commitments.len() < usize::from(group.threshold)
+        || commitments.len() > usize::from(group.participants)
+        || commitments
+            .iter()
+            .any(|c| c.index == 0 || c.index > group.participants)
+    {
+        return Err(FrostError::WrongSignerCount {
+            threshold: group.threshold,
+            participants: group.participants,
+        });
+    }
+    let root = package
+        .merkle_root_hex
+        .as_deref()
+        .map(|r| decode_hex("merkle_root_hex", r))
+        .transpose()?;
+    let group_public = point_from_hex("group_public_hex", &group.group_public_hex)?;
+    let tweak = tweak(&group_public, root.as_deref())?;
+    let output_x = x_only(&tweak.output_key);
+
+    let mut encoded = Vec::with_capacity(commitments.len() * 68);
+    let mut points = Vec::with_capacity(commitments.len());
+    for c in &commitments {
+        let hiding = point_from_hex("hiding_hex", &c.hiding_hex)?;
+        let binding = point_from_hex("binding_hex", &c.binding_hex)?;
+        encoded.extend_from_slice(&c.index.to_le_bytes());
+        encoded.extend_from_slice(hiding.to_affine().to_encoded_point(true).as_bytes());
+        encoded.extend_from_slice(binding.to_affine().to_encoded_point(true).as_bytes());
+        points.push((c.index, hiding, binding));
+    }
+    let binding: Vec<(u16, Scalar)> = points
+        .iter()
+        .map(|(i, _, _)| {
+            (
+                *i,
+                hash_to_scalar(
+                    BINDING_TAG,
+                    &[&output_x, &message, &encoded, &i.to_le_bytes()],
+                ),
+            )
+        })
+        .collect();
+    let nonce_points: Vec<(u16, ProjectivePoint)> = points
+        .iter()
+        .zip(&binding)
+        .map(|((i, d, e), (_, rho))| (*i, *d + e * rho))
+        .collect();
+    let r = nonce_points
+        .iter()
+        .fold(ProjectivePoint::IDENTITY, |acc, (_, p)| acc + p);
+    let negate_nonces = has_odd_y(&r);
+    let group_commitment = if negate_nonces { -r } else { r };
+    let challenge = hash_to_scalar(
+        CHALLENGE_TAG,
+        &[&x_only(&group_commitment), &output_x, &message],
+    );
+    Ok(Session {
+        tweak,
+        message,
+        signers: commitments.iter().map(|c| c.index).collect(),
+        nonce_points,
+        binding,
+        negate_nonces,
+        group_commitment,
+        challenge,
+    })
+}
+
+/// This signer's partial signature over `package`, using the `nonces` it
+/// committed to for it. The caller must discard the nonces afterwards.
+pub fn sign(
+    share: &FrostKeyShare,
+    nonces: &FrostNonces,
+    package: &FrostSigningPackage,
+) -> Result<FrostSignatureShare, FrostError> {
+    let session = session(&share.group, package)?;
+    if !package.commitments.contains(&nonces.commitment) {
+        return Err(FrostError::Malformed(
+            "signing package does not carry this signer's commitment".to_string(),
+        ));
+    }
+    let rho = session
+        .binding
+        .iter()
+        .find(|(i, _)| *i == share.index)
+        .map(|(_, rho)| *rho)
+        .ok_or_else(|| FrostError::Malformed("signer is not in the package".to_string()))?;
+    let hiding = scalar_from_hex("hiding_secret_hex", &nonces.hiding_secret_hex)?;
+    let binding = scalar_from_hex("binding_secret_hex", &nonces.binding_secret_hex)?;
+    let secret = scalar_from_hex("secret_share_hex", &share.secret_share_hex)?;
+    let mut k = hiding + binding * rho;
+    if session.negate_nonces {
+        k = -k;
+    }
+    let z = k + session.challenge
+        * lagrange(share.index, &session.signers)
+        * session.tweak.share_sign
+        * secret;
+    Ok(FrostSignatureShare {
+        index: share.index,
+        z_hex: scalar_hex(&z),
+    })
+}
+
+/// Coordinator side: check every partial signature against its signer's
+/// public share, aggregate them into a BIP-340 signature for the tweaked
+/// output key, and verify the result.
+pub fn aggregate(
+    group: &FrostGroup,
+    package: &FrostSigningPackage,
+    shares: &[FrostSignatureShare],
+) -> Result<FrostSignature, FrostError> {
+    let session = session(group, package)?;
+    let mut z = session.challenge * session.tweak.tweak;
+    for (index, nonce_point) in &session.nonce_points {
+        let share = shares
+            .iter()
+            .find(|s| s.index == *index)
+            .ok_or_else(|| FrostError::Malformed(format!("missing signature share {index}")))?;
+        let zi = scalar_from_hex("z_hex", &share.z_hex)
+            .map_err(|_| FrostError::BadSignatureShare(*index))?;
+        let verifying = group
+            .verifying_shares
+            .get(usize::from(*index) - 1)
+            .ok_or(FrostError::BadSignatureShare(*index))?;
+        let verifying = point_from_hex("verifying_shares", verifying)?;
+        let r = if session.negate_nonces {
+            -nonce_point
+        } else {
+            *nonce_point
+        };
+        let expected = r + verifying
+            * (session.challenge * lagrange(*index, &session.signers) * session.tweak.share_sign);
+        if ProjectivePoint::GENERATOR * zi != expected {
+            return Err(FrostError::BadSignatureShare(*index));
+        }
+        z += zi;
+    }
+
+    let mut signature = x_only(&session.group_commitment).to_vec();
+    signature.extend_from_slice(&z.to_bytes());
+    let output_key = x_only(&session.tweak.output_key);
+    verify(&output_key, &session.message, &signature)?;
+    Ok(FrostSignature {
+        group_id: group.group_id.clone(),
+        message_hex: package.message_hex.clone(),
+        output_key_hex: hex::encode(output_key),
+        signature_hex: hex::encode(signature),
+    })
+}
+
+/// Plain BIP-340 verification of `signature` under x-only `output_key`.
+pub fn verify(output_key: &[u8], message: &[u8], signature: &[u8]) -> Result<(), FrostError> {
+    let key = k256::schnorr::VerifyingKey::from_bytes(output_key)
+        .map_err(|_| FrostError::Malformed("output_key_hex".to_string()))?;
+    let sig =
+        k256::schnorr::Signature::try_from(signature).map_err(|_| FrostError::BadSignature)?;
+    key.verify_raw(message, &sig)
+        .map_err(|_| FrostError::BadSignature)
+}
+
+fn export_key(
+    password: &str,
+    salt: &[u8],
+    params: KdfParams,
+) -> Result<Zeroizing<[u8; 32]>, FrostError> {
+    let master = Zeroizing::new(kdf::derive_master_key_with_params(
+        password.as_bytes(),
+        salt,
+        params,
+    )?);
+    Ok(Zeroizing::new(kdf::derive_encryption_key(
+        &master,
+        EXPORT_KEY_DOMAIN,
+    )))
+}
+
+/// Seal `share` under `password` for import into another profile or
+/// installation.
+pub fn export_share(
+    share: &FrostKeyShare,
+    password: &str,
+    exported_at: DateTime<Utc>,
+) -> Result<FrostShareExport, FrostError> {
+    if password.chars().count() < MIN_SHARE_PASSWORD_CHARS {
+        return Err(FrostError::WeakPassword);
+    }
+    let params = KdfParams::high();
+    let salt = kdf::generate_salt();
+    let key = export_key(password, &salt, params)?;
+    let json = Zeroizing::new(serde_json::to_vec(share)?);
+    Ok(FrostShareExport {
+        format: FROST_SHARE_EXPORT_FORMAT.to_string(),
+        version: FROST_SHARE_EXPORT_VERSION,
+        exported_at,
+        group_id: share.group.group_id.clone(),
+        index: share.index,
+        salt_hex: hex::encode(salt),
+        kdf: BundleKdf {
+            memory_kib: params.memory_kib,
+            iterations: params.iterations,
+            parallelism: params.parallelism,
+        },
+        payload: encryption::encrypt_aead(&key, &json)?,
+    })
+}
+
+/// Open a share export and check the share is consistent with its group.
+pub fn import_share(
+    export: &FrostShareExport,
+    password: &str,
+) -> Result<FrostKeyShare, FrostError> {
+    if export.format != FROST_SHARE_EXPORT_FORMAT {
+        return Err(FrostError::Malformed(format!("format {}", export.format)));
+    }
+    if export.version != FROST_SHARE_EXPORT_VERSION {
+        return Err(FrostError::UnsupportedVersion(export.version));
+    }
+    let salt = decode_hex("salt_hex", &export.salt_hex)?;
+    let params = KdfParams {
+        memory_kib: export.kdf.memory_kib,
+        iterations: export.kdf.iterations,
+        parallelism: export.kdf.parallelism,
+    };
+    let key = export_key(password, &salt, params)?;
+    let json = Zeroizing::new(
+        encryption::decrypt_aead(&key, &export.payload).map_err(|_| FrostError::WrongPassword)?,
+    );
+    let share: FrostKeyShare = serde_json::from_slice(&json)?;
+    if share.group.group_id != export.group_id || share.index != export.index {
+        return Err(FrostError::Malformed(
+            "export header does not match its share".to_string(),
+        ));
+    }
+    let secret = scalar_from_hex("secret_share_hex", &share.secret_share_hex)?;
+    let expected = share
+        .group
+        .verifying_shares
+        .get(usize::from(share.index).wrapping_sub(1))
+        .ok_or_else(|| FrostError::Malformed("share index".to_string()))?;
+    if point_hex(&(ProjectivePoint::GENERATOR * secret)) != *expected {
+        return Err(FrostError::BadShare(share.index));
+    }
+    Ok(share)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: [u8; 32] = [7u8; 32];
+
+    fn generate(threshold: u16, participants: u16) -> Vec<FrostKeyShare> {
+        let mut secrets: Vec<FrostDkgSecret> = (1..=participants)
+            .map(|i| dkg_begin("treasury", threshold, participants, i).unwrap())
+            .collect();
+        let round1: Vec<FrostDkgRound1> = secrets.iter().map(|s| s.package.clone()).collect();
+        let round2: Vec<FrostDkgRound2> = secrets
+            .iter_mut()
+            .flat_map(|s| dkg_round2(s, &round1).unwrap())
+            .collect();
+        secrets
+            .iter()
+            .map(|s| dkg_finish(s, &round2).unwrap())
+            .collect()
+    }
+
+    fn sign_with(
+        shares: &[&FrostKeyShare],
+        merkle_root_hex: Option<String>,
+    ) -> Result<FrostSignature, FrostError> {
+        let nonces: Vec<FrostNonces> = shares.iter().map(|s| commit(s)).collect();
+        let package = FrostSigningPackage {
+            group_id: shares[0].group.group_id.clone(),
+            message_hex: hex::encode(MESSAGE),
+            commitments: nonces.iter().map(|n| n.commitment.clone()).collect(),
+            merkle_root_hex,
+        };
+        let partials: Vec<FrostSignatureShare> = shares
+            .iter()
+            .zip(&nonces)
+            .map(|(s, n)| sign(s, n, &package).unwrap())
+            .collect();
+        aggregate(&shares[0].group, &package, &partials)
+    }
+
+    #[test]
+    fn every_participant_agrees_on_the_group() {
+        let shares = generate(2, 3);
+        assert!(shares.iter().all(|s| s.group == shares[0].group));
+        assert_eq!(shares[0].group.verifying_shares.len(), 3);
+    }
+
+    #[test]
+    fn any_threshold_subset_signs_for_the_output_key() {
+        for _ in 0..4 {
+            let shares = generate(2, 3);
+            for subset in [[0, 1], [1, 2], [0, 2]] {
+                let sig = sign_with(&[&shares[subset[0]], &shares[subset[1]]], None).unwrap();
+                assert_eq!(sig.output_key_hex, shares[0].group.taproot_output_key_hex);
+                verify(
+                    &hex::decode(&sig.output_key_hex).unwrap(),
+                    &MESSAGE,
+                    &hex::decode(&sig.signature_hex).unwrap(),
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn script_tree_root_changes_the_output_key() {
+        let shares = generate(2, 2);
+        let root = hex::encode([9u8; 32]);
+        let sig = sign_with(&[&shares[0], &shares[1]], Some(root.clone())).unwrap();
+        assert_eq!(
+            sig.output_key_hex,
+            taproot_output_key(&shares[0].group, Some(&root)).unwrap()
+        );
+        assert_ne!(sig.output_key_hex, shares[0].group.taproot_output_key_hex);
+    }
+
+    #[test]
+    fn too_few_signers_or_a_bad_share_is_rejected() {
+        let shares = generate(3, 4);
+        let nonces: Vec<FrostNonces> = shares[..2].iter().map(commit).collect();
+        let short = FrostSigningPackage {
+            group_id: "treasury".to_string(),
+            message_hex: hex::encode(MESSAGE),
+            commitments: nonces.iter().map(|n| n.commitment.clone()).collect(),
+            merkle_root_hex: None,
+        };
+        assert!(matches!(
+            sign(&shares[0], &nonces[0], &short),
+            Err(FrostError::WrongSignerCount { .. })
+        ));
+
+        let nonces: Vec<FrostNonces> = shares[..3].iter().map(commit).collect();
+        let package = FrostSigningPackage {
+            group_id: "treasury".to_string(),
+            message_hex: hex::encode(MESSAGE),
+            commitments: nonces.iter().map(|n| n.commitment.clone()).collect(),
+            merkle_root_hex: None,
+        };
+        let mut partials: Vec<FrostSignatureShare> = shares[..3]
+            .iter()
+            .zip(&nonces)
+            .map(|(s, n)| sign(s, n, &package).unwrap())
+            .collect();
+        partials[1].z_hex = scalar_hex(&Scalar::ONE);
+        assert!(matches!(
+            aggregate(&shares[0].group, &package, &partials),
+            Err(FrostError::BadSignatureShare(2))
+        ));
+    }
+
+    #[test]
+    fn tampered_round2_share_is_caught() {
+        let mut secrets: Vec<FrostDkgSecret> = (1..=3)
+            .map(|i| dkg_begin("treasury", 2, 3, i).unwrap())
+            .collect();
+        let round1: Vec<FrostDkgRound1> = secrets.iter().map(|s| s.package.clone()).collect();
+        let mut round2: Vec<FrostDkgRound2> = secrets
+            .iter_mut()
+            .flat_map(|s| dkg_round2(s, &round1).unwrap())
+            .collect();
+        let forged = round2
+            .iter()
+            .position(|p| p.from == 2 && p.to == 1)
+            .unwrap();
+        round2[forged].share.ciphertext[0] ^= 1;
+        assert!(matches!(
+            dkg_finish(&secrets[0], &round2),
+            Err(FrostError::BadShare(2))
+        ));
+    }
+
+    #[test]
+    fn share_export_round_trips() {
+        let shares = generate(2, 2);
+        let export = export_share(&shares[1], "correct horse battery", Utc::now()).unwrap();
+        assert!(matches!(
+            import_share(&export, "wrong horse battery"),
+            Err(FrostError::WrongPassword)
+        ));
+        let imported = import_share(&export, "correct horse battery").unwrap();
+        assert_eq!(imported.secret_share_hex, shares[1].secret_share_hex);
+        assert_eq!(imported.group, shares[1].group);
+    }
+}
@@ -147,6 +147,19 @@ pub fn zap_path(purpose: u32, account: u32, index: u32) -> KeyPath {
     }
 }
 
+/// Placeholder for the index in a path template, e.g. `m/44'/9999'/0'/0'/{i}'`.
+pub const INDEX_PLACEHOLDER: &str = "{i}";
+
+/// Fill the single `{i}` in `template` with `index` and parse the result.
+pub fn expand_template(template: &str, index: u32) -> Result<KeyPath, DerivationError> {
+    if template.matches(INDEX_PLACEHOLDER).count() != 1 {
+        return Err(DerivationError::InvalidPathComponent(format!(
+            "path template must contain {INDEX_PLACEHOLDER} exactly once"
+        )));
+    }
+    KeyPath::parse(&template.replace(INDEX_PLACEHOLDER, &index.to_string()))
+}
+
 pub fn derive_seed_from_master(master_seed: &[u8; 64], path: &KeyPath) -> [u8; 32] {
     let mut hasher = blake3::Hasher::new();
     hasher.update(b"ZAP_HD_derive");
@@ -162,6 +175,21 @@ pub fn derive_seed_from_master(master_seed: &[u8; 64], path: &KeyPath) -> [u8; 3
 mod tests {
     use super::*;
 
+    #[test]
+    fn templates_expand_to_zap_paths() {
+        let path = expand_template("m/44'/9999'/0'/0'/{i}'", 7).unwrap();
+        assert_eq!(path, zap_path(0, 0, 7));
+        assert_eq!(
+            expand_template("m/44'/9999'/0'/0/{i}", 3)
+                .unwrap()
+                .to_string(),
+            "m/44'/9999'/0'/0/3"
+        );
+        assert!(expand_template("m/44'/9999'/0'/0'/1'", 0).is_err());
+        assert!(expand_template("m/44'/{i}'/{i}'", 0).is_err());
+        assert!(expand_template("m/44'/9999'/x/{i}", 0).is_err());
+    }
+
     #[test]
     fn test_parse_and_format_roundtrip() {
         let path_str = "m/44'/9999'/0'/0/1";
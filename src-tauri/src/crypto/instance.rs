@@ -0,0 +1,186 @@
+//! Per-installation signing identity.
+//!
+//! A random ML-DSA-87 seed, with the Ed25519 key derived from it as in
+//! [`HybridSigner`], wrapped under a key derived from the master seed. It
+//! never leaves the installation: it is not synced and not backed up, so a
+//! restored or newly synced installation gets an identity of its own.
+
+use crate::crypto::encryption::{self, EncryptionError};
+use crate::crypto::hybrid_signing::{HybridSignature, HybridSigner, HybridSigningError};
+use crate::crypto::mldsa87::{self, SecretKey};
+use crate::models::instance::{InstanceIdentity, InstanceIdentityFile, InstanceSignature};
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// BLAKE3 `derive_key` context for the key wrapping the instance seed.
+const WRAP_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 instance identity store v1";
+/// Signature domain for everything an instance signs.
+const INSTANCE_SIGNATURE_DOMAIN: &[u8] = b"ZAP_INSTANCE_SIGNATURE_V1";
+/// [`InstanceSignature::purpose`] values.
+pub const PURPOSE_BACKUP: &str = "backup";
+pub const PURPOSE_SYNC_PACKAGE: &str = "sync_package";
+pub const PURPOSE_KEY_ATTESTATION: &str = "key_attestation";
+pub const PURPOSE_PUBLIC_BUNDLE: &str = "public_bundle";
+
+#[derive(Debug, Error)]
+pub enum InstanceError {
+    #[error("malformed instance identity: {0}")]
+    Malformed(String),
+    #[error("instance identity does not belong to this vault")]
+    ForeignVault,
+    #[error("instance signature is invalid: {0}")]
+    BadSignature(String),
+    #[error("instance signature is for {found}, expected {expected}")]
+    WrongPurpose { expected: String, found: String },
+    #[error("instance identity encryption error: {0}")]
+    Encryption(#[from] EncryptionError),
+    #[error("signing error: {0}")]
+    Signing(#[from] HybridSigningError),
+}
+
+/// An opened instance identity, ready to sign.
+pub struct InstanceKey {
+    pub identity: InstanceIdentity,
+    signer: HybridSigner,
+}
+
+fn wrap_key(master_seed: &[u8; 64]) -> Zeroizing<[u8; 32]> {
+    Zeroizing::new(blake3::derive_key(WRAP_KEY_CONTEXT, master_seed))
+}
+
+fn identity_of(instance_id: &str, signer: &HybridSigner, at: DateTime<Utc>) -> InstanceIdentity {
+    InstanceIdentity {
+        instance_id: instance_id.to_string(),
+        mldsa_public_hex: signer.primary_public_key().to_hex(),
+        ed25519_public_hex: hex::encode(signer.secondary_public_key()),
+        created_at: at,
+    }
+}
+
+/// Generate a fresh identity for `instance_id` and its wrapped file.
+pub fn generate_instance(
+    master_seed: &[u8; 64],
+    instance_id: &str,
+    now: DateTime<Utc>,
+) -> Result<(InstanceKey, InstanceIdentityFile), InstanceError> {
+    let (_, secret) = mldsa87::generate();
+    let signer = HybridSigner::from_secret(&secret)?;
+    let identity = identity_of(instance_id, &signer, now);
+    let file = InstanceIdentityFile {
+        identity: identity.clone(),
+        wrapped_secret: encryption::encrypt_aead(&wrap_key(master_seed), &secret.0)?,
+    };
+    Ok((InstanceKey { identity, signer }, file))
+}
+
+/// Unwrap a stored identity and check it matches its recorded public keys.
+pub fn open_instance(
+    master_seed: &[u8; 64],
+    file: &InstanceIdentityFile,
+) -> Result<InstanceKey, InstanceError> {
+    let seed = Zeroizing::new(
+        encryption::decrypt_aead(&wrap_key(master_seed), &file.wrapped_secret)
+            .map_err(|_| InstanceError::ForeignVault)?,
+    );
+    if seed.len() != mldsa87::SEED_SIZE {
+        return Err(InstanceError::Malformed("wrong seed length".to_string()));
+    }
+    let signer = HybridSigner::from_secret(&SecretKey(seed.to_vec()))?;
+    let identity = identity_of(
+        &file.identity.instance_id,
+        &signer,
+        file.identity.created_at,
+    );
+    if identity != file.identity {
+        return Err(InstanceError::Malformed(
+            "public keys do not match the stored seed".to_string(),
+        ));
+    }
+    Ok(InstanceKey { identity, signer })
+}
+
+/// Domain, purpose and instance id, each length-prefixed, then the payload.
+fn signed_message(purpose: &str, instance_id: &str, payload: &[u8]) -> Vec<u8> {
+    let mut m = INSTANCE_SIGNATURE_DOMAIN.to_vec();
+    for part in [purpose.as_bytes(), instance_id.as_bytes()] {
+        m.extend_from_slice(&(part.len() as u32).to_be_bytes());
+        m.extend_from_slice(part);
+    }
+    m.extend_from_slice(payload);
+    m
+}
+
+impl InstanceKey {
+    /// Sign `payload` for `purpose`.
+    pub fn sign(&self, purpose: &str, payload: &[u8]) -> Result<InstanceSignature, InstanceError> {
+        let id = &self.identity.instance_id;
+        let sig = self.signer.sign(&signed_message(purpose, id, payload))?;
+        Ok(InstanceSignature {
+            instance_id: id.clone(),
+            purpose: purpose.to_string(),
+            mldsa_public_hex: self.identity.mldsa_public_hex.clone(),
+            ed25519_public_hex: self.identity.ed25519_public_hex.clone(),
+            mldsa_signature_hex: hex::encode(&sig.primary),
+            ed25519_signature_hex: hex::encode(&sig.secondary),
+        })
+    }
+}
+
+/// Check `sig` over `payload` against the keys it names. Callers decide
+/// whether to trust those keys, e.g. by comparing
+/// [`InstanceSignature::key_fingerprint`] with a pinned one.
+pub fn verify_instance(
+    sig: &InstanceSignature,
+    purpose: &str,
+    payload: &[u8],
+) -> Result<(), InstanceError> {
+    if sig.purpose != purpose {
+        return Err(InstanceError::WrongPurpose {
+            expected: purpose.to_string(),
+            found: sig.purpose.clone(),
+        });
+    }
+    let decode = |h: &str| hex::decode(h).map_err(|e| InstanceError::Malformed(e.to_string()));
+    let hybrid = HybridSignature {
+        primary: decode(&sig.mldsa_signature_hex)?,
+        secondary: decode(&sig.ed25519_signature_hex)?,
+        primary_public_key: decode(&sig.mldsa_public_hex)?,
+        secondary_public_key: decode(&sig.ed25519_public_hex)?
+            .try_into()
+            .map_err(|_| InstanceError::Malformed("Ed25519 key must be 32 bytes".to_string()))?,
+        algorithm: String::new(),
+    };
+    HybridSigner::verify(&signed_message(purpose, &sig.instance_id, payload), &hybrid)
+        .map_err(|e| InstanceError::BadSignature(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_round_trips_and_signs() {
+        let seed = [3u8; 64];
+        let (key, file) = generate_instance(&seed, "device-1", Utc::now()).unwrap();
+        let reopened = open_instance(&seed, &file).unwrap();
+        assert_eq!(reopened.identity, key.identity);
+        assert!(matches!(
+            open_instance(&[4u8; 64], &file),
+            Err(InstanceError::ForeignVault)
+        ));
+
+        let sig = reopened.sign("backup", b"manifest").unwrap();
+        assert!(sig.is_from(&key.identity));
+        assert_eq!(sig.key_fingerprint(), key.identity.key_fingerprint());
+        verify_instance(&sig, "backup", b"manifest").unwrap();
+        assert!(verify_instance(&sig, "backup", b"other").is_err());
+        assert!(matches!(
+            verify_instance(&sig, "sync_package", b"manifest"),
+            Err(InstanceError::WrongPurpose { .. })
+        ));
+        let mut moved = sig.clone();
+        moved.instance_id = "device-2".to_string();
+        assert!(verify_instance(&moved, "backup", b"manifest").is_err());
+    }
+}
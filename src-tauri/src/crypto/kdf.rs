@@ -1,5 +1,6 @@
 use argon2::{Algorithm, Argon2, Params, Version};
 use thiserror::Error;
+use zeroize::Zeroizing;
 
 pub const ARGON2_MEMORY_KIB: u32 = 65536;
 pub const ARGON2_ITERATIONS: u32 = 3;
@@ -159,6 +160,99 @@ pub fn generate_salt() -> [u8; SALT_SIZE] {
     salt
 }
 
+/// Most keys a [`DerivedKeyCache`] holds; the oldest is dropped past this.
+pub const DERIVED_KEY_CACHE_SIZE: usize = 8;
+
+/// Argon2id outputs kept for an unlocked session, so confirming the password
+/// again does not re-run the KDF. Entries are found by a BLAKE3 tag over the
+/// password, second-factor response, salt and parameters, keyed with a
+/// random secret of this cache: the password is never stored, and a tag
+/// cannot be brute-forced without the secret, which never leaves memory.
+/// Keys and the secret are wiped when the cache is cleared or dropped.
+pub struct DerivedKeyCache {
+    secret: Zeroizing<[u8; 32]>,
+    entries: Vec<([u8; 32], Zeroizing<[u8; MASTER_KEY_SIZE]>)>,
+}
+
+impl Default for DerivedKeyCache {
+    fn default() -> Self {
+        use rand::RngCore;
+        let mut secret = Zeroizing::new([0u8; 32]);
+        rand::thread_rng().fill_bytes(secret.as_mut());
+        DerivedKeyCache {
+            secret,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl DerivedKeyCache {
+    fn tag(
+        &self,
+        password: &[u8],
+        hardware_response: Option<&[u8]>,
+        salt: &[u8],
+        p: KdfParams,
+    ) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new_keyed(&self.secret);
+        for field in [password, hardware_response.unwrap_or_default(), salt] {
+            hasher.update(&(field.len() as u64).to_le_bytes());
+            hasher.update(field);
+        }
+        hasher.update(&[u8::from(hardware_response.is_some())]);
+        for n in [p.memory_kib, p.iterations, p.parallelism] {
+            hasher.update(&n.to_le_bytes());
+        }
+        *hasher.finalize().as_bytes()
+    }
+
+    /// The key [`derive_master_key_with_factor_params`] gave for these
+    /// inputs, if it was cached.
+    pub fn get(
+        &self,
+        password: &[u8],
+        hardware_response: Option<&[u8]>,
+        salt: &[u8],
+        p: KdfParams,
+    ) -> Option<Zeroizing<[u8; MASTER_KEY_SIZE]>> {
+        let tag = self.tag(password, hardware_response, salt, p);
+        self.entries
+            .iter()
+            .find(|(t, _)| *t == tag)
+            .map(|(_, key)| key.clone())
+    }
+
+    /// Cache `key` for these inputs. Only cache a key once it is known to be
+    /// right, so wrong guesses cannot push good keys out.
+    pub fn insert(
+        &mut self,
+        password: &[u8],
+        hardware_response: Option<&[u8]>,
+        salt: &[u8],
+        p: KdfParams,
+        key: Zeroizing<[u8; MASTER_KEY_SIZE]>,
+    ) {
+        let tag = self.tag(password, hardware_response, salt, p);
+        self.entries.retain(|(t, _)| *t != tag);
+        if self.entries.len() >= DERIVED_KEY_CACHE_SIZE {
+            self.entries.remove(0);
+        }
+        self.entries.push((tag, key));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -260,4 +354,32 @@ mod tests {
         let y = derive_master_key_with_factor(b"a", Some(b"bc"), &salt).unwrap();
         assert_ne!(x, y);
     }
+
+    #[test]
+    fn test_derived_key_cache_matches_every_input() {
+        let salt = [7u8; SALT_SIZE];
+        let p = KdfParams::legacy();
+        let key = Zeroizing::new(derive_master_key_with_params(b"pw", &salt, p).unwrap());
+        let mut cache = DerivedKeyCache::default();
+        assert!(cache.get(b"pw", None, &salt, p).is_none());
+        cache.insert(b"pw", None, &salt, p, key.clone());
+        assert_eq!(cache.get(b"pw", None, &salt, p).as_deref(), Some(&*key));
+
+        assert!(cache.get(b"pw2", None, &salt, p).is_none());
+        assert!(cache.get(b"pw", Some(b""), &salt, p).is_none());
+        assert!(cache.get(b"pw", None, &[8u8; SALT_SIZE], p).is_none());
+        assert!(cache.get(b"pw", None, &salt, KdfParams::high()).is_none());
+        // Another cache's secret gives other tags.
+        assert!(DerivedKeyCache::default()
+            .get(b"pw", None, &salt, p)
+            .is_none());
+
+        for i in 0..DERIVED_KEY_CACHE_SIZE as u8 {
+            cache.insert(&[i], None, &salt, p, Zeroizing::new([i; 32]));
+        }
+        assert_eq!(cache.len(), DERIVED_KEY_CACHE_SIZE);
+        assert!(cache.get(b"pw", None, &salt, p).is_none());
+        cache.clear();
+        assert!(cache.is_empty());
+    }
 }
@@ -0,0 +1,188 @@
+//! Key-drive unlock: the vault encryption key, wrapped under a short PIN,
+//! kept on a trusted encrypted drive.
+//!
+//! The wrapping key is Argon2id over the PIN with a random binding secret
+//! from `vault.json` folded in, the same way a YubiKey response is. The
+//! wrapped key lives only on the drive, so neither the drive nor the
+//! machine alone can unlock the vault, and an attacker holding both still
+//! has to run Argon2id once per PIN guess.
+
+use crate::crypto::encryption::{self, EncryptionError};
+use crate::crypto::kdf::{self, KdfError, KdfParams};
+use crate::models::drive::DriveInfo;
+use crate::models::key_drive::{
+    KeyDriveConfig, KeyDriveFile, KEY_DRIVE_FORMAT, KEY_DRIVE_VERSION, MIN_KEY_DRIVE_PIN_CHARS,
+};
+use crate::models::mobile::BundleKdf;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+const KEY_DRIVE_KEY_DOMAIN: &str = "key_drive_unlock";
+
+#[derive(Debug, Error)]
+pub enum KeyDriveError {
+    #[error("PIN must be at least {MIN_KEY_DRIVE_PIN_CHARS} characters")]
+    WeakPin,
+    #[error("malformed key drive file: {0}")]
+    Malformed(String),
+    #[error("the unlock file on the drive belongs to another enrollment")]
+    WrongFile,
+    #[error("wrong PIN")]
+    WrongPin,
+    #[error(transparent)]
+    Kdf(#[from] KdfError),
+    #[error(transparent)]
+    Encryption(#[from] EncryptionError),
+}
+
+fn wrapping_key(pin: &str, config: &KeyDriveConfig) -> Result<Zeroizing<[u8; 32]>, KeyDriveError> {
+    let bad = |what: &str| KeyDriveError::Malformed(format!("invalid {what} in vault.json"));
+    let salt = hex::decode(&config.salt_hex).map_err(|_| bad("salt"))?;
+    let binding = Zeroizing::new(hex::decode(&config.binding_hex).map_err(|_| bad("binding"))?);
+    let params = KdfParams {
+        memory_kib: config.kdf.memory_kib,
+        iterations: config.kdf.iterations,
+        parallelism: config.kdf.parallelism,
+    };
+    let master = Zeroizing::new(kdf::derive_master_key_with_factor_params(
+        pin.as_bytes(),
+        Some(&binding),
+        &salt,
+        params,
+    )?);
+    Ok(Zeroizing::new(kdf::derive_encryption_key(
+        &master,
+        KEY_DRIVE_KEY_DOMAIN,
+    )))
+}
+
+/// Wrap `vault_key` for `drive` under `pin`. Returns the config to keep in
+/// `vault.json` and the file to write to the drive.
+pub fn enroll(
+    vault_key: &[u8; 32],
+    pin: &str,
+    drive: &DriveInfo,
+    params: KdfParams,
+    now: DateTime<Utc>,
+) -> Result<(KeyDriveConfig, KeyDriveFile), KeyDriveError> {
+    if pin.chars().count() < MIN_KEY_DRIVE_PIN_CHARS {
+        return Err(KeyDriveError::WeakPin);
+    }
+    let mut binding = Zeroizing::new([0u8; 32]);
+    rand::thread_rng().fill_bytes(binding.as_mut());
+    let config = KeyDriveConfig {
+        id: uuid::Uuid::new_v4().to_string(),
+        drive_id: drive.id.clone(),
+        label: drive.label.clone(),
+        salt_hex: hex::encode(kdf::generate_salt()),
+        kdf: BundleKdf {
+            memory_kib: params.memory_kib,
+            iterations: params.iterations,
+            parallelism: params.parallelism,
+        },
+        binding_hex: hex::encode(binding.as_ref()),
+        enrolled_at: now,
+        failures: 0,
+    };
+    let key = wrapping_key(pin, &config)?;
+    let file = KeyDriveFile {
+        format: KEY_DRIVE_FORMAT.to_string(),
+        version: KEY_DRIVE_VERSION,
+        id: config.id.clone(),
+        wrapped: encryption::encrypt_aead(&key, vault_key)?,
+    };
+    Ok((config, file))
+}
+
+/// Recover the vault key from the drive's file `data` with `pin`.
+pub fn unwrap(
+    config: &KeyDriveConfig,
+    data: &[u8],
+    pin: &str,
+) -> Result<Zeroizing<[u8; 32]>, KeyDriveError> {
+    let file: KeyDriveFile =
+        serde_json::from_slice(data).map_err(|e| KeyDriveError::Malformed(e.to_string()))?;
+    if file.format != KEY_DRIVE_FORMAT || file.version != KEY_DRIVE_VERSION {
+        return Err(KeyDriveError::Malformed(format!(
+            "{} version {}",
+            file.format, file.version
+        )));
+    }
+    if file.id != config.id {
+        return Err(KeyDriveError::WrongFile);
+    }
+    let key = wrapping_key(pin, config)?;
+    let plain = Zeroizing::new(
+        encryption::decrypt_aead(&key, &file.wrapped).map_err(|_| KeyDriveError::WrongPin)?,
+    );
+    let vault_key: [u8; 32] = plain
+        .as_slice()
+        .try_into()
+        .map_err(|_| KeyDriveError::Malformed("wrapped key has the wrong length".to_string()))?;
+    Ok(Zeroizing::new(vault_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::drive::{DriveEncryption, DriveStatus};
+
+    fn drive() -> DriveInfo {
+        DriveInfo {
+            id: "uuid-1".to_string(),
+            device: "/dev/mapper/luks-1".to_string(),
+            label: Some("KEY".to_string()),
+            fs_type: Some("ext4".to_string()),
+            size_bytes: 1 << 30,
+            available_bytes: Some(1 << 29),
+            mount_point: Some("/media/key".to_string()),
+            encrypted: true,
+            encryption: Some(DriveEncryption::Luks),
+            luks_uuid: Some("luks-1".to_string()),
+            status: DriveStatus::Ready,
+        }
+    }
+
+    fn fast() -> KdfParams {
+        KdfParams {
+            memory_kib: 1024,
+            iterations: 1,
+            parallelism: 1,
+        }
+    }
+
+    #[test]
+    fn test_pin_unwraps_only_with_its_binding() {
+        let vault_key = [7u8; 32];
+        let (config, file) = enroll(&vault_key, "4821", &drive(), fast(), Utc::now()).unwrap();
+        let data = serde_json::to_vec(&file).unwrap();
+        assert_eq!(*unwrap(&config, &data, "4821").unwrap(), vault_key);
+        assert!(matches!(
+            unwrap(&config, &data, "4822"),
+            Err(KeyDriveError::WrongPin)
+        ));
+
+        let mut other = config.clone();
+        other.binding_hex = hex::encode([1u8; 32]);
+        assert!(matches!(
+            unwrap(&other, &data, "4821"),
+            Err(KeyDriveError::WrongPin)
+        ));
+        other = config;
+        other.id = "another".to_string();
+        assert!(matches!(
+            unwrap(&other, &data, "4821"),
+            Err(KeyDriveError::WrongFile)
+        ));
+    }
+
+    #[test]
+    fn test_short_pin_is_refused() {
+        assert!(matches!(
+            enroll(&[0u8; 32], "123", &drive(), fast(), Utc::now()),
+            Err(KeyDriveError::WeakPin)
+        ));
+    }
+}
@@ -0,0 +1,308 @@
+use crate::crypto::attestation;
+use crate::crypto::contact::{self, ContactError};
+use crate::crypto::encryption::{self, EncryptionError};
+use crate::crypto::mldsa87::{self, CryptoError, PublicKey, SecretKey, Signature};
+use crate::crypto::mlkem1024::{self, KemCiphertext, KemError};
+use crate::models::contact::ContactCard;
+use crate::models::key::KeyEntry;
+use crate::models::key_escrow::{validate_conditions, EscrowPolicy, KeyEscrowPackage};
+use rand::rngs::OsRng;
+use thiserror::Error;
+use x25519_dalek::{PublicKey as X25519Public, StaticSecret};
+use zeroize::Zeroizing;
+
+/// Current key escrow package and policy format version.
+pub const KEY_ESCROW_VERSION: u32 = 1;
+
+/// Signature domain for the sender's signature over a package.
+const KEY_ESCROW_DOMAIN: &[u8] = b"ZAP_KEY_ESCROW_V1";
+/// BLAKE3 `derive_key` context combining the two shared secrets.
+const PAYLOAD_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 key escrow payload key v1";
+
+#[derive(Debug, Error)]
+pub enum KeyEscrowError {
+    #[error("malformed escrow package: {0}")]
+    Malformed(String),
+    #[error("unsupported escrow package version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("escrow package signature is invalid")]
+    BadSignature,
+    #[error("escrow package is addressed to another vault")]
+    WrongRecipient,
+    #[error("escrowed key does not match its policy")]
+    KeyMismatch,
+    #[error("contact card error: {0}")]
+    Contact(#[from] ContactError),
+    #[error("KEM error: {0}")]
+    Kem(#[from] KemError),
+    #[error("encryption error: {0}")]
+    Encryption(#[from] EncryptionError),
+    #[error("signature error: {0}")]
+    Crypto(#[from] CryptoError),
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, KeyEscrowError> {
+    hex::decode(value).map_err(|e| KeyEscrowError::Malformed(format!("{field}: {e}")))
+}
+
+fn push_field(m: &mut Vec<u8>, field: &[u8]) {
+    m.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    m.extend_from_slice(field);
+}
+
+/// AEAD key from both shared secrets, bound to the ciphertexts that produced
+/// them.
+fn payload_key(
+    x25519_shared: &[u8],
+    kem_shared: &[u8],
+    ephemeral: &[u8],
+    kem_ciphertext: &[u8],
+) -> Zeroizing<[u8; 32]> {
+    let mut hasher = blake3::Hasher::new_derive_key(PAYLOAD_KEY_CONTEXT);
+    for field in [x25519_shared, kem_shared, ephemeral, kem_ciphertext] {
+        hasher.update(&(field.len() as u32).to_le_bytes());
+        hasher.update(field);
+    }
+    Zeroizing::new(*hasher.finalize().as_bytes())
+}
+
+/// The bytes the sender signs: the policy document and everything sealed
+/// under it, so no part can be swapped between packages.
+fn package_message(package: &KeyEscrowPackage) -> Result<Vec<u8>, KeyEscrowError> {
+    let mut m = KEY_ESCROW_DOMAIN.to_vec();
+    m.extend_from_slice(&package.version.to_le_bytes());
+    push_field(&mut m, package.sender.sign_public_hex.as_bytes());
+    push_field(&mut m, package.policy_document.as_bytes());
+    push_field(
+        &mut m,
+        &decode_hex("x25519_ephemeral_hex", &package.x25519_ephemeral_hex)?,
+    );
+    push_field(
+        &mut m,
+        &decode_hex("kem_ciphertext_hex", &package.kem_ciphertext_hex)?,
+    );
+    push_field(&mut m, &package.payload.nonce);
+    push_field(&mut m, &package.payload.ciphertext);
+    Ok(m)
+}
+
+/// Seal `entry`, secret included, to `recipient` under `conditions`, signed
+/// with the vault identity behind `sender`.
+pub fn seal_key_escrow(
+    master_seed: &[u8; 64],
+    sender: &ContactCard,
+    recipient: &ContactCard,
+    entry: &KeyEntry,
+    conditions: &str,
+    now: u64,
+) -> Result<KeyEscrowPackage, KeyEscrowError> {
+    validate_conditions(conditions).map_err(KeyEscrowError::Malformed)?;
+    contact::verify_card(recipient)?;
+    let policy = EscrowPolicy {
+        version: KEY_ESCROW_VERSION,
+        package_id: uuid::Uuid::new_v4().to_string(),
+        key_id: entry.id.clone(),
+        key_type: entry.metadata.key_type.clone(),
+        address: entry.metadata.address.clone(),
+        public_key_hex: entry.public_key_hex.clone(),
+        conditions: conditions.to_string(),
+        sender_fingerprint: contact::fingerprint(sender)?.hex,
+        recipient_fingerprint: contact::fingerprint(recipient)?.hex,
+        created_at: now,
+    };
+    let policy_document = serde_json::to_string_pretty(&policy)
+        .map_err(|e| KeyEscrowError::Malformed(e.to_string()))?;
+
+    let recipient_x25519: [u8; 32] = decode_hex("x25519_public_hex", &recipient.x25519_public_hex)?
+        .try_into()
+        .map_err(|_| KeyEscrowError::Malformed("x25519_public_hex".to_string()))?;
+    let ephemeral = StaticSecret::random_from_rng(OsRng);
+    let ephemeral_public = X25519Public::from(&ephemeral);
+    let x25519_shared = ephemeral.diffie_hellman(&X25519Public::from(recipient_x25519));
+    let (kem_ct, kem_shared) =
+        mlkem1024::encapsulate_to(&decode_hex("kem_public_hex", &recipient.kem_public_hex)?)?;
+    let kem_shared = Zeroizing::new(kem_shared);
+    let key = payload_key(
+        x25519_shared.as_bytes(),
+        kem_shared.as_ref(),
+        ephemeral_public.as_bytes(),
+        &kem_ct.ciphertext,
+    );
+    let plaintext = Zeroizing::new(
+        serde_json::to_vec(entry).map_err(|e| KeyEscrowError::Malformed(e.to_string()))?,
+    );
+    let payload = encryption::encrypt_aead(&key, &plaintext)?;
+
+    let mut package = KeyEscrowPackage {
+        version: KEY_ESCROW_VERSION,
+        sender: sender.clone(),
+        policy_document,
+        x25519_ephemeral_hex: hex::encode(ephemeral_public.as_bytes()),
+        kem_ciphertext_hex: hex::encode(&kem_ct.ciphertext),
+        payload,
+        signature_hex: String::new(),
+        watermark: None,
+    };
+    let (_, identity) = attestation::vault_identity(master_seed);
+    package.signature_hex = mldsa87::sign(&identity, &package_message(&package)?)?.to_hex();
+    Ok(package)
+}
+
+/// Check a package's sender card and signature and return its policy. Needs
+/// no secrets, so the terms can be shown before anything is decrypted.
+pub fn verify_key_escrow(package: &KeyEscrowPackage) -> Result<EscrowPolicy, KeyEscrowError> {
+    if package.version != KEY_ESCROW_VERSION {
+        return Err(KeyEscrowError::UnsupportedVersion(package.version));
+    }
+    contact::verify_card(&package.sender)?;
+    let pk = PublicKey::from_hex(&package.sender.sign_public_hex)?;
+    let sig =
+        Signature::from_hex(&package.signature_hex).map_err(|_| KeyEscrowError::BadSignature)?;
+    if !mldsa87::verify(&pk, &package_message(package)?, &sig)? {
+        return Err(KeyEscrowError::BadSignature);
+    }
+    let policy: EscrowPolicy = serde_json::from_str(&package.policy_document)
+        .map_err(|e| KeyEscrowError::Malformed(e.to_string()))?;
+    if policy.version != KEY_ESCROW_VERSION {
+        return Err(KeyEscrowError::UnsupportedVersion(policy.version));
+    }
+    if policy.sender_fingerprint != contact::fingerprint(&package.sender)?.hex {
+        return Err(KeyEscrowError::BadSignature);
+    }
+    Ok(policy)
+}
+
+/// Verify a package addressed to this vault and decrypt the key it carries.
+pub fn open_key_escrow(
+    master_seed: &[u8; 64],
+    package: &KeyEscrowPackage,
+) -> Result<(EscrowPolicy, KeyEntry), KeyEscrowError> {
+    let policy = verify_key_escrow(package)?;
+    if policy.recipient_fingerprint != contact::own_fingerprint(master_seed).hex {
+        return Err(KeyEscrowError::WrongRecipient);
+    }
+    let ephemeral: [u8; 32] = decode_hex("x25519_ephemeral_hex", &package.x25519_ephemeral_hex)?
+        .try_into()
+        .map_err(|_| KeyEscrowError::Malformed("x25519_ephemeral_hex".to_string()))?;
+    let x25519_shared =
+        contact::x25519_identity(master_seed).diffie_hellman(&X25519Public::from(ephemeral));
+    let kem_ct = KemCiphertext {
+        ciphertext: decode_hex("kem_ciphertext_hex", &package.kem_ciphertext_hex)?,
+        encapsulated_key: Vec::new(),
+    };
+    let kem_shared = Zeroizing::new(contact::kem_identity(master_seed).decapsulate(&kem_ct)?);
+    let key = payload_key(
+        x25519_shared.as_bytes(),
+        kem_shared.as_ref(),
+        &ephemeral,
+        &kem_ct.ciphertext,
+    );
+    let plaintext = Zeroizing::new(encryption::decrypt_aead(&key, &package.payload)?);
+    let entry: KeyEntry =
+        serde_json::from_slice(&plaintext).map_err(|e| KeyEscrowError::Malformed(e.to_string()))?;
+    if entry.id != policy.key_id || entry.public_key_hex != policy.public_key_hex {
+        return Err(KeyEscrowError::KeyMismatch);
+    }
+    // The secret must be the one behind the advertised public key.
+    let pk = PublicKey::from_hex(&entry.public_key_hex)?;
+    let sk = SecretKey::from_hex(&entry.encrypted_secret_hex)?;
+    let probe = mldsa87::sign(&sk, KEY_ESCROW_DOMAIN)?;
+    if !mldsa87::verify(&pk, KEY_ESCROW_DOMAIN, &probe)? {
+        return Err(KeyEscrowError::KeyMismatch);
+    }
+    Ok((policy, entry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{address, mldsa87};
+    use crate::models::key::KeyType;
+
+    const ALICE: [u8; 64] = [21u8; 64];
+    const BOB: [u8; 64] = [22u8; 64];
+
+    fn key() -> KeyEntry {
+        let (pk, sk) = mldsa87::generate();
+        KeyEntry::new(
+            KeyType::Treasury,
+            44,
+            0,
+            3,
+            &pk.to_hex(),
+            &sk.to_hex(),
+            &address::derive_address(pk.as_bytes()),
+            "m/44'/9999'/0'/0'/3'",
+        )
+    }
+
+    fn package(entry: &KeyEntry) -> KeyEscrowPackage {
+        let alice = contact::issue_card(&ALICE, "Alice", 1).unwrap();
+        let bob = contact::issue_card(&BOB, "Bob", 1).unwrap();
+        seal_key_escrow(
+            &ALICE,
+            &alice,
+            &bob,
+            entry,
+            "Release if Alice is unreachable",
+            10,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn recipient_opens_the_key() {
+        let entry = key();
+        let sealed = package(&entry);
+        let policy = verify_key_escrow(&sealed).unwrap();
+        assert_eq!(policy.key_id, entry.id);
+        assert_eq!(policy.conditions, "Release if Alice is unreachable");
+
+        let (opened_policy, opened) = open_key_escrow(&BOB, &sealed).unwrap();
+        assert_eq!(opened_policy, policy);
+        assert_eq!(opened.encrypted_secret_hex, entry.encrypted_secret_hex);
+        assert_eq!(
+            opened.metadata.derivation_path,
+            entry.metadata.derivation_path
+        );
+    }
+
+    #[test]
+    fn only_the_recipient_can_open() {
+        let sealed = package(&key());
+        assert!(matches!(
+            open_key_escrow(&ALICE, &sealed),
+            Err(KeyEscrowError::WrongRecipient)
+        ));
+        assert!(matches!(
+            open_key_escrow(&[23u8; 64], &sealed),
+            Err(KeyEscrowError::WrongRecipient)
+        ));
+    }
+
+    #[test]
+    fn tampering_breaks_the_signature() {
+        let sealed = package(&key());
+
+        let mut looser = sealed.clone();
+        looser.policy_document = looser.policy_document.replace("unreachable", "on holiday");
+        assert!(matches!(
+            verify_key_escrow(&looser),
+            Err(KeyEscrowError::BadSignature)
+        ));
+
+        let mut swapped = sealed.clone();
+        swapped.payload = package(&key()).payload;
+        assert!(matches!(
+            open_key_escrow(&BOB, &swapped),
+            Err(KeyEscrowError::BadSignature)
+        ));
+
+        let mut future = sealed;
+        future.version = 2;
+        assert!(matches!(
+            verify_key_escrow(&future),
+            Err(KeyEscrowError::UnsupportedVersion(2))
+        ));
+    }
+}
@@ -49,74 +49,96 @@ impl KemKeyPair {
         }
     }
 
-    pub fn encapsulate(&self) -> Result<(KemCiphertext, [u8; 32]), KemError> {
-        if self.encapsulation_key.len() != ENCAPSULATION_KEY_SIZE {
-            return Err(KemError::InvalidKeySize {
-                expected: ENCAPSULATION_KEY_SIZE,
-                got: self.encapsulation_key.len(),
-            });
+    /// Rebuild a keypair from its 64-byte decapsulation seed, e.g. one
+    /// derived deterministically from the vault master seed.
+    pub fn from_seed(seed: &[u8; DECAPSULATION_SEED_SIZE]) -> Self {
+        let dk = DecapsulationKey::<MlKem1024>::from_seed(Array::from(*seed));
+        Self {
+            encapsulation_key: dk.encapsulation_key().to_bytes().to_vec(),
+            decapsulation_seed: seed.to_vec(),
         }
+    }
 
-        let ek_arr: [u8; ENCAPSULATION_KEY_SIZE] =
-            self.encapsulation_key.as_slice().try_into().map_err(|_| {
-                KemError::KeyDecodeError("slice to array conversion failed".to_string())
-            })?;
+    pub fn encapsulate(&self) -> Result<(KemCiphertext, [u8; 32]), KemError> {
+        encapsulate_to(&self.encapsulation_key)
+    }
 
-        let ek = EncapsulationKey::<MlKem1024>::new(&Array::from(ek_arr))
-            .map_err(|e| KemError::KeyDecodeError(e.to_string()))?;
+    pub fn decapsulate(&self, ct: &KemCiphertext) -> Result<[u8; 32], KemError> {
+        decapsulate_with_seed(&self.decapsulation_seed, ct)
+    }
+}
 
-        let (ct, shared) = ek.encapsulate();
+/// Encapsulate to a recipient's encapsulation key alone (no keypair needed),
+/// e.g. when sealing data to someone else's published ML-KEM key.
+pub fn encapsulate_to(encapsulation_key: &[u8]) -> Result<(KemCiphertext, [u8; 32]), KemError> {
+    if encapsulation_key.len() != ENCAPSULATION_KEY_SIZE {
+        return Err(KemError::InvalidKeySize {
+            expected: ENCAPSULATION_KEY_SIZE,
+            got: encapsulation_key.len(),
+        });
+    }
 
-        let ct_vec = ct.to_vec();
-        let shared_vec = shared.to_vec();
+    let ek_arr: [u8; ENCAPSULATION_KEY_SIZE] = encapsulation_key
+        .try_into()
+        .map_err(|_| KemError::KeyDecodeError("slice to array conversion failed".to_string()))?;
 
-        let mut shared_arr = [0u8; 32];
-        shared_arr.copy_from_slice(&shared_vec[..32]);
+    let ek = EncapsulationKey::<MlKem1024>::new(&Array::from(ek_arr))
+        .map_err(|e| KemError::KeyDecodeError(e.to_string()))?;
 
-        Ok((
-            KemCiphertext {
-                ciphertext: ct_vec,
-                encapsulated_key: self.encapsulation_key.clone(),
-            },
-            shared_arr,
-        ))
-    }
+    let (ct, shared) = ek.encapsulate();
 
-    pub fn decapsulate(&self, ct: &KemCiphertext) -> Result<[u8; 32], KemError> {
-        if self.decapsulation_seed.len() != DECAPSULATION_SEED_SIZE {
-            return Err(KemError::InvalidKeySize {
-                expected: DECAPSULATION_SEED_SIZE,
-                got: self.decapsulation_seed.len(),
-            });
-        }
-        if ct.ciphertext.len() != CIPHERTEXT_SIZE {
-            return Err(KemError::InvalidCiphertextSize {
-                expected: CIPHERTEXT_SIZE,
-                got: ct.ciphertext.len(),
-            });
-        }
+    let ct_vec = ct.to_vec();
+    let shared_vec = shared.to_vec();
+
+    let mut shared_arr = [0u8; 32];
+    shared_arr.copy_from_slice(&shared_vec[..32]);
 
-        let seed_arr: [u8; DECAPSULATION_SEED_SIZE] = self
-            .decapsulation_seed
-            .as_slice()
-            .try_into()
-            .map_err(|_| KemError::KeyDecodeError("seed conversion failed".to_string()))?;
+    Ok((
+        KemCiphertext {
+            ciphertext: ct_vec,
+            encapsulated_key: encapsulation_key.to_vec(),
+        },
+        shared_arr,
+    ))
+}
 
-        let dk = DecapsulationKey::<MlKem1024>::from_seed(Array::from(seed_arr));
+/// Decapsulate using only the 64-byte decapsulation seed.
+pub fn decapsulate_with_seed(
+    decapsulation_seed: &[u8],
+    ct: &KemCiphertext,
+) -> Result<[u8; 32], KemError> {
+    if decapsulation_seed.len() != DECAPSULATION_SEED_SIZE {
+        return Err(KemError::InvalidKeySize {
+            expected: DECAPSULATION_SEED_SIZE,
+            got: decapsulation_seed.len(),
+        });
+    }
+    if ct.ciphertext.len() != CIPHERTEXT_SIZE {
+        return Err(KemError::InvalidCiphertextSize {
+            expected: CIPHERTEXT_SIZE,
+            got: ct.ciphertext.len(),
+        });
+    }
 
-        let ct_arr: [u8; CIPHERTEXT_SIZE] =
-            ct.ciphertext.as_slice().try_into().map_err(|_| {
-                KemError::KeyDecodeError("ciphertext conversion failed".to_string())
-            })?;
+    let seed_arr: [u8; DECAPSULATION_SEED_SIZE] = decapsulation_seed
+        .try_into()
+        .map_err(|_| KemError::KeyDecodeError("seed conversion failed".to_string()))?;
 
-        let shared = dk.decapsulate(&Array::from(ct_arr));
-        let shared_vec = shared.to_vec();
+    let dk = DecapsulationKey::<MlKem1024>::from_seed(Array::from(seed_arr));
 
-        let mut shared_arr = [0u8; 32];
-        shared_arr.copy_from_slice(&shared_vec[..32]);
+    let ct_arr: [u8; CIPHERTEXT_SIZE] = ct
+        .ciphertext
+        .as_slice()
+        .try_into()
+        .map_err(|_| KemError::KeyDecodeError("ciphertext conversion failed".to_string()))?;
 
-        Ok(shared_arr)
-    }
+    let shared = dk.decapsulate(&Array::from(ct_arr));
+    let shared_vec = shared.to_vec();
+
+    let mut shared_arr = [0u8; 32];
+    shared_arr.copy_from_slice(&shared_vec[..32]);
+
+    Ok(shared_arr)
 }
 
 #[cfg(test)]
@@ -148,6 +170,16 @@ mod tests {
         assert_eq!(shared_send, shared_recv);
     }
 
+    #[test]
+    fn test_from_seed_matches_generated() {
+        let kp = KemKeyPair::generate();
+        let seed: [u8; DECAPSULATION_SEED_SIZE] = kp.decapsulation_seed.clone().try_into().unwrap();
+        let rebuilt = KemKeyPair::from_seed(&seed);
+        assert_eq!(rebuilt.encapsulation_key, kp.encapsulation_key);
+        let (ct, shared) = encapsulate_to(&kp.encapsulation_key).unwrap();
+        assert_eq!(rebuilt.decapsulate(&ct).unwrap(), shared);
+    }
+
     #[test]
     fn test_two_keypairs_different_keys() {
         let kp1 = KemKeyPair::generate();
@@ -1,29 +1,326 @@
-use bip39::{Language, Mnemonic};
+use bip39::{Error as Bip39Error, Language, Mnemonic};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use thiserror::Error;
 
 pub const MNEMONIC_WORD_COUNT: usize = 24;
 pub const SEED_SIZE: usize = 64;
 
+/// How many near-miss words a [`PhraseCheck`] suggests for a mistyped word.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// The standard BIP39 wordlists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MnemonicLanguage {
+    #[default]
+    English,
+    ChineseSimplified,
+    ChineseTraditional,
+    Czech,
+    French,
+    Italian,
+    Japanese,
+    Korean,
+    Portuguese,
+    Spanish,
+}
+
+impl MnemonicLanguage {
+    pub const ALL: [MnemonicLanguage; 10] = [
+        MnemonicLanguage::English,
+        MnemonicLanguage::ChineseSimplified,
+        MnemonicLanguage::ChineseTraditional,
+        MnemonicLanguage::Czech,
+        MnemonicLanguage::French,
+        MnemonicLanguage::Italian,
+        MnemonicLanguage::Japanese,
+        MnemonicLanguage::Korean,
+        MnemonicLanguage::Portuguese,
+        MnemonicLanguage::Spanish,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MnemonicLanguage::English => "english",
+            MnemonicLanguage::ChineseSimplified => "chinese_simplified",
+            MnemonicLanguage::ChineseTraditional => "chinese_traditional",
+            MnemonicLanguage::Czech => "czech",
+            MnemonicLanguage::French => "french",
+            MnemonicLanguage::Italian => "italian",
+            MnemonicLanguage::Japanese => "japanese",
+            MnemonicLanguage::Korean => "korean",
+            MnemonicLanguage::Portuguese => "portuguese",
+            MnemonicLanguage::Spanish => "spanish",
+        }
+    }
+
+    fn wordlist(self) -> Language {
+        match self {
+            MnemonicLanguage::English => Language::English,
+            MnemonicLanguage::ChineseSimplified => Language::SimplifiedChinese,
+            MnemonicLanguage::ChineseTraditional => Language::TraditionalChinese,
+            MnemonicLanguage::Czech => Language::Czech,
+            MnemonicLanguage::French => Language::French,
+            MnemonicLanguage::Italian => Language::Italian,
+            MnemonicLanguage::Japanese => Language::Japanese,
+            MnemonicLanguage::Korean => Language::Korean,
+            MnemonicLanguage::Portuguese => Language::Portuguese,
+            MnemonicLanguage::Spanish => Language::Spanish,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum MnemonicError {
     #[error("invalid mnemonic: {0}")]
     InvalidMnemonic(String),
+    #[error("invalid mnemonic: word {position} (\"{word}\") is not in the {} wordlist", language.as_str())]
+    UnknownWord {
+        /// 1-based position of the word in the phrase.
+        position: usize,
+        word: String,
+        language: MnemonicLanguage,
+    },
+    #[error("invalid mnemonic: expected 12, 15, 18, 21 or 24 words, got {0}")]
+    BadWordCount(usize),
+    #[error("invalid mnemonic: checksum mismatch, a word is wrong or out of order")]
+    InvalidChecksum,
     #[error("invalid seed size: expected {expected}, got {got}")]
     InvalidSeedSize { expected: usize, got: usize },
 }
 
+/// Result of checking a phrase as typed, for pointing the user at the
+/// mistake rather than just rejecting it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PhraseCheck {
+    pub valid: bool,
+    /// The wordlist the phrase was checked against; `None` if no word
+    /// matched any list.
+    pub language: Option<MnemonicLanguage>,
+    pub word_count: usize,
+    /// 1-based position of the word that is wrong, when it can be pinned
+    /// down: a word missing from the wordlist, or the only word whose
+    /// near-miss replacement fixes the checksum.
+    pub wrong_position: Option<usize>,
+    /// Wordlist words close to the one at `wrong_position`.
+    pub suggestions: Vec<String>,
+    /// Why the phrase is invalid.
+    pub error: Option<String>,
+}
+
 pub fn generate_mnemonic() -> String {
-    let mnemonic = Mnemonic::generate_in(Language::English, MNEMONIC_WORD_COUNT)
+    generate_mnemonic_in(MnemonicLanguage::English)
+}
+
+/// Generate a 24-word mnemonic from the `language` wordlist. Japanese
+/// phrases are joined with the ideographic space, as BIP39 specifies.
+pub fn generate_mnemonic_in(language: MnemonicLanguage) -> String {
+    let mnemonic = Mnemonic::generate_in(language.wordlist(), MNEMONIC_WORD_COUNT)
         .expect("24-word mnemonic generation should not fail");
-    mnemonic.to_string()
+    let words: Vec<&str> = mnemonic.words().collect();
+    match language {
+        MnemonicLanguage::Japanese => words.join("\u{3000}"),
+        _ => words.join(" "),
+    }
 }
 
-pub fn validate_mnemonic(words: &str) -> Result<(), MnemonicError> {
-    Mnemonic::parse_in(Language::English, words)
-        .map(|_| ())
+/// The mnemonic encoding caller-supplied entropy (16 to 32 bytes), for
+/// reproducing BIP39 reference vectors. Audit builds only.
+#[cfg(feature = "audit-vectors")]
+pub fn mnemonic_from_entropy(
+    entropy: &[u8],
+    language: MnemonicLanguage,
+) -> Result<String, MnemonicError> {
+    Mnemonic::from_entropy_in(language.wordlist(), entropy)
+        .map(|m| m.to_string())
         .map_err(|e| MnemonicError::InvalidMnemonic(e.to_string()))
 }
 
+/// NFKD-normalize `words`, as BIP39 requires before any wordlist lookup.
+fn normalize(words: &str) -> Cow<'_, str> {
+    let mut cow = Cow::Borrowed(words);
+    Mnemonic::normalize_utf8_cow(&mut cow);
+    cow
+}
+
+/// Wordlists holding any of `words`, most hits first, so a typo in the
+/// first word does not throw detection off. Ties keep the order of
+/// [`MnemonicLanguage::ALL`], English first.
+fn candidate_languages(words: &[&str]) -> Vec<MnemonicLanguage> {
+    let mut hits: Vec<(MnemonicLanguage, usize)> = MnemonicLanguage::ALL
+        .iter()
+        .map(|&l| {
+            let list = l.wordlist();
+            (
+                l,
+                words.iter().filter(|w| list.find_word(w).is_some()).count(),
+            )
+        })
+        .filter(|&(_, n)| n > 0)
+        .collect();
+    hits.sort_by_key(|&(_, n)| std::cmp::Reverse(n));
+    hits.into_iter().map(|(l, _)| l).collect()
+}
+
+fn map_error(e: Bip39Error, words: &[&str], language: MnemonicLanguage) -> MnemonicError {
+    match e {
+        Bip39Error::UnknownWord(i) => MnemonicError::UnknownWord {
+            position: i + 1,
+            word: words.get(i).copied().unwrap_or_default().to_string(),
+            language,
+        },
+        Bip39Error::BadWordCount(n) => MnemonicError::BadWordCount(n),
+        Bip39Error::InvalidChecksum => MnemonicError::InvalidChecksum,
+        other => MnemonicError::InvalidMnemonic(other.to_string()),
+    }
+}
+
+/// Parse `words` in `language`, or when `language` is `None` in the first
+/// wordlist that accepts it; some words appear in more than one list. A
+/// failure is reported against the list holding the most of the words,
+/// English if none holds any.
+fn parse(
+    words: &str,
+    language: Option<MnemonicLanguage>,
+) -> Result<(Mnemonic, MnemonicLanguage), MnemonicError> {
+    let normalized = normalize(words);
+    let split: Vec<&str> = normalized.split_whitespace().collect();
+    let joined = split.join(" ");
+    let candidates = match language {
+        Some(language) => vec![language],
+        None => match candidate_languages(&split) {
+            found if found.is_empty() => vec![MnemonicLanguage::English],
+            found => found,
+        },
+    };
+    let mut first_err = None;
+    for language in &candidates {
+        match Mnemonic::parse_in_normalized(language.wordlist(), &joined) {
+            Ok(mnemonic) => return Ok((mnemonic, *language)),
+            Err(e) => {
+                first_err.get_or_insert_with(|| map_error(e, &split, *language));
+            }
+        }
+    }
+    Err(first_err.expect("at least one wordlist is tried"))
+}
+
+/// Validate a phrase in any standard BIP39 language.
+pub fn validate_mnemonic(words: &str) -> Result<(), MnemonicError> {
+    parse(words, None).map(|_| ())
+}
+
+/// Validate a phrase against one wordlist.
+pub fn validate_mnemonic_in(words: &str, language: MnemonicLanguage) -> Result<(), MnemonicError> {
+    parse(words, Some(language)).map(|_| ())
+}
+
+/// Levenshtein distance over chars, for near-miss suggestions.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// Wordlist words within two edits of `word`, closest first.
+fn near_words(language: MnemonicLanguage, word: &str) -> Vec<&'static str> {
+    let mut near: Vec<(usize, &'static str)> = language
+        .wordlist()
+        .word_list()
+        .iter()
+        .map(|w| (edit_distance(word, w), *w))
+        .filter(|&(d, w)| d <= 2 && w != word)
+        .collect();
+    near.sort();
+    near.into_iter().map(|(_, w)| w).collect()
+}
+
+/// For a phrase whose words are all valid but whose checksum fails, find
+/// the positions where swapping in a near-miss word fixes the checksum.
+fn checksum_fixes(words: &[&str], language: MnemonicLanguage) -> Vec<(usize, Vec<&'static str>)> {
+    let mut fixes = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        let mut candidate: Vec<&str> = words.to_vec();
+        let fixed: Vec<&'static str> = near_words(language, word)
+            .into_iter()
+            .filter(|near| {
+                candidate[i] = near;
+                Mnemonic::parse_in_normalized(language.wordlist(), &candidate.join(" ")).is_ok()
+            })
+            .collect();
+        if !fixed.is_empty() {
+            fixes.push((i, fixed));
+        }
+    }
+    fixes
+}
+
+/// Check a phrase as the user typed it and say which word is wrong. Never
+/// fails: every problem is reported in the returned [`PhraseCheck`].
+pub fn check_phrase(words: &str, language: Option<MnemonicLanguage>) -> PhraseCheck {
+    let normalized = normalize(words);
+    let split: Vec<&str> = normalized.split_whitespace().collect();
+    let detected = match language {
+        Some(language) => Some(language),
+        None => parse(&normalized, None)
+            .map(|(_, l)| l)
+            .ok()
+            .or_else(|| candidate_languages(&split).first().copied()),
+    };
+    let mut check = PhraseCheck {
+        valid: false,
+        language: detected,
+        word_count: split.len(),
+        wrong_position: None,
+        suggestions: Vec::new(),
+        error: None,
+    };
+    let Some(language) = detected else {
+        check.error = Some("no word is in a BIP39 wordlist".to_string());
+        return check;
+    };
+    let err = match parse(&normalized, Some(language)) {
+        Ok(_) => {
+            check.valid = true;
+            return check;
+        }
+        Err(e) => e,
+    };
+    match &err {
+        MnemonicError::UnknownWord { position, word, .. } => {
+            check.wrong_position = Some(*position);
+            check.suggestions = near_words(language, word)
+                .into_iter()
+                .take(MAX_SUGGESTIONS)
+                .map(str::to_string)
+                .collect();
+        }
+        MnemonicError::InvalidChecksum => {
+            let fixes = checksum_fixes(&split, language);
+            if let [(i, fixed)] = fixes.as_slice() {
+                check.wrong_position = Some(i + 1);
+                check.suggestions = fixed
+                    .iter()
+                    .take(MAX_SUGGESTIONS)
+                    .map(|w| w.to_string())
+                    .collect();
+            }
+        }
+        _ => {}
+    }
+    check.error = Some(err.to_string());
+    check
+}
+
 /// Derive the 64-byte BIP39 seed using the **standard empty passphrase**, so the
 /// result is interoperable with any other BIP39 wallet.
 ///
@@ -41,12 +338,14 @@ pub fn mnemonic_to_seed(words: &str) -> Result<[u8; SEED_SIZE], MnemonicError> {
 /// The passphrase is a secret that is intentionally **not** stored anywhere:
 /// per BIP39 design, losing it makes the derived keys unrecoverable, and a
 /// different passphrase silently derives a different (valid) wallet.
+///
+/// The phrase may be in any standard BIP39 language; the seed depends only
+/// on the normalized words, so the language need not be stored.
 pub fn mnemonic_to_seed_with_passphrase(
     words: &str,
     passphrase: &str,
 ) -> Result<[u8; SEED_SIZE], MnemonicError> {
-    let mnemonic = Mnemonic::parse_in(Language::English, words)
-        .map_err(|e| MnemonicError::InvalidMnemonic(e.to_string()))?;
+    let (mnemonic, _) = parse(words, None)?;
 
     Ok(mnemonic.to_seed(passphrase))
 }
@@ -129,4 +428,65 @@ abandon abandon abandon abandon abandon art";
 0af7a4d73245cafa9c3cca8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8";
         assert_eq!(hex::encode(seed), expected);
     }
+
+    #[test]
+    fn test_every_language_round_trips() {
+        for language in MnemonicLanguage::ALL {
+            let phrase = generate_mnemonic_in(language);
+            assert!(validate_mnemonic_in(&phrase, language).is_ok());
+            assert!(mnemonic_to_seed(&phrase).is_ok());
+            let check = check_phrase(&phrase, Some(language));
+            assert!(check.valid);
+            assert_eq!(check.word_count, MNEMONIC_WORD_COUNT);
+        }
+    }
+
+    #[test]
+    fn test_composed_japanese_is_normalized() {
+        // BIP39 Japanese vector for all-zero entropy, typed with precomposed
+        // kana (ぞ) and ideographic spaces; the wordlist is stored NFKD.
+        let phrase = ["あいこくしん"; 11].join("\u{3000}") + "\u{3000}あおぞら";
+        assert!(validate_mnemonic(&phrase).is_ok());
+        let check = check_phrase(&phrase, None);
+        assert!(check.valid);
+        assert_eq!(check.language, Some(MnemonicLanguage::Japanese));
+    }
+
+    #[test]
+    fn test_unknown_word_position_and_suggestions() {
+        let phrase = "abandon abandon abandon abandon abandn abandon \
+abandon abandon abandon abandon abandon about";
+        match validate_mnemonic(phrase) {
+            Err(MnemonicError::UnknownWord { position, word, .. }) => {
+                assert_eq!(position, 5);
+                assert_eq!(word, "abandn");
+            }
+            other => panic!("expected an unknown word, got {other:?}"),
+        }
+        let check = check_phrase(phrase, None);
+        assert!(!check.valid);
+        assert_eq!(check.language, Some(MnemonicLanguage::English));
+        assert_eq!(check.wrong_position, Some(5));
+        assert_eq!(
+            check.suggestions.first().map(String::as_str),
+            Some("abandon")
+        );
+    }
+
+    #[test]
+    fn test_checksum_mismatch_points_at_mistyped_word() {
+        let phrase = "abandon abandon abandon abandon abandon abandon \
+abandon abandon abandon abandon abandon above";
+        assert!(matches!(
+            validate_mnemonic(phrase),
+            Err(MnemonicError::InvalidChecksum)
+        ));
+        let check = check_phrase(phrase, None);
+        assert_eq!(check.wrong_position, Some(12));
+        assert!(check.suggestions.iter().any(|w| w == "about"));
+        assert!(matches!(
+            validate_mnemonic("abandon abandon abandon"),
+            Err(MnemonicError::BadWordCount(3))
+        ));
+    }
 }
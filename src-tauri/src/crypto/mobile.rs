@@ -0,0 +1,143 @@
+use crate::crypto::encryption::{self, EncryptionError};
+use crate::crypto::kdf::{self, KdfError, KdfParams};
+use crate::crypto::mldsa87::{self, CryptoError, PublicKey, SecretKey, Signature};
+use crate::models::mobile::{BundleKdf, MobileBundle, MobileBundleContents};
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// Current mobile bundle format version.
+pub const MOBILE_BUNDLE_VERSION: u32 = 1;
+/// Shortest bundle password accepted. The bundle leaves the air-gapped
+/// machine, so its password is all that stands between it and an attacker.
+pub const MIN_BUNDLE_PASSWORD_CHARS: usize = 12;
+
+/// BLAKE3 `derive_key` context for the bundle-signing key, derived from the
+/// HD master seed so it needs no storing.
+const SIGNING_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 mobile bundle signing key v1";
+const PAYLOAD_KEY_DOMAIN: &str = "mobile_bundle_payload";
+
+#[derive(Debug, Error)]
+pub enum MobileBundleError {
+    #[error("bundle password must be at least {MIN_BUNDLE_PASSWORD_CHARS} characters")]
+    WeakPassword,
+    #[error("unsupported mobile bundle version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("mobile bundle signature is invalid")]
+    BadSignature,
+    #[error("wrong bundle password")]
+    WrongPassword,
+    #[error("key derivation error: {0}")]
+    Kdf(#[from] KdfError),
+    #[error("encryption error: {0}")]
+    Encryption(#[from] EncryptionError),
+    #[error("signature error: {0}")]
+    Crypto(#[from] CryptoError),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// The bundle-signing keypair of the vault holding `master_seed`.
+pub fn signing_keypair(master_seed: &[u8; 64]) -> (PublicKey, SecretKey) {
+    let seed = Zeroizing::new(blake3::derive_key(SIGNING_KEY_CONTEXT, master_seed));
+    mldsa87::from_seed(&seed)
+}
+
+fn push_field(m: &mut Vec<u8>, field: &[u8]) {
+    m.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    m.extend_from_slice(field);
+}
+
+/// Canonical encoding of everything in a bundle except the signature.
+pub fn bundle_message(bundle: &MobileBundle) -> Vec<u8> {
+    let mut m = Vec::with_capacity(96 + bundle.payload.ciphertext.len());
+    m.extend_from_slice(b"ZAP_MOBILE_BUNDLE_V1");
+    m.extend_from_slice(&bundle.version.to_le_bytes());
+    m.extend_from_slice(&bundle.created_at.timestamp_millis().to_le_bytes());
+    push_field(&mut m, bundle.salt_hex.as_bytes());
+    m.extend_from_slice(&bundle.kdf.memory_kib.to_le_bytes());
+    m.extend_from_slice(&bundle.kdf.iterations.to_le_bytes());
+    m.extend_from_slice(&bundle.kdf.parallelism.to_le_bytes());
+    push_field(&mut m, &bundle.payload.nonce);
+    push_field(&mut m, &bundle.payload.ciphertext);
+    push_field(&mut m, bundle.public_key_hex.as_bytes());
+    m
+}
+
+fn payload_key(
+    password: &str,
+    salt: &[u8],
+    params: KdfParams,
+) -> Result<Zeroizing<[u8; 32]>, MobileBundleError> {
+    let master = Zeroizing::new(kdf::derive_master_key_with_params(
+        password.as_bytes(),
+        salt,
+        params,
+    )?);
+    Ok(Zeroizing::new(kdf::derive_encryption_key(
+        &master,
+        PAYLOAD_KEY_DOMAIN,
+    )))
+}
+
+/// Encrypt `contents` under `password` and sign the bundle with the vault's
+/// bundle key. Uses the interactive Argon2 profile so phones can open it.
+pub fn seal_bundle(
+    master_seed: &[u8; 64],
+    password: &str,
+    contents: &MobileBundleContents,
+    created_at: DateTime<Utc>,
+) -> Result<MobileBundle, MobileBundleError> {
+    if password.chars().count() < MIN_BUNDLE_PASSWORD_CHARS {
+        return Err(MobileBundleError::WeakPassword);
+    }
+    let params = KdfParams::legacy();
+    let salt = kdf::generate_salt();
+    let key = payload_key(password, &salt, params)?;
+    let json = Zeroizing::new(serde_json::to_vec(contents)?);
+    let (pk, sk) = signing_keypair(master_seed);
+    let mut bundle = MobileBundle {
+        version: MOBILE_BUNDLE_VERSION,
+        created_at,
+        salt_hex: hex::encode(salt),
+        kdf: BundleKdf {
+            memory_kib: params.memory_kib,
+            iterations: params.iterations,
+            parallelism: params.parallelism,
+        },
+        payload: encryption::encrypt_aead(&key, &json)?,
+        public_key_hex: pk.to_hex(),
+        signature_hex: String::new(),
+    };
+    bundle.signature_hex = mldsa87::sign(&sk, &bundle_message(&bundle))?.to_hex();
+    Ok(bundle)
+}
+
+/// Check the bundle's signature against the key it carries and decrypt it.
+/// Callers that have seen a bundle from this vault before should also
+/// compare `public_key_hex` with the key they pinned.
+pub fn open_bundle(
+    password: &str,
+    bundle: &MobileBundle,
+) -> Result<MobileBundleContents, MobileBundleError> {
+    if bundle.version != MOBILE_BUNDLE_VERSION {
+        return Err(MobileBundleError::UnsupportedVersion(bundle.version));
+    }
+    let pk = PublicKey::from_hex(&bundle.public_key_hex)?;
+    let sig = Signature::from_hex(&bundle.signature_hex)?;
+    if !mldsa87::verify(&pk, &bundle_message(bundle), &sig)? {
+        return Err(MobileBundleError::BadSignature);
+    }
+    let salt = hex::decode(&bundle.salt_hex).map_err(|_| MobileBundleError::BadSignature)?;
+    let params = KdfParams {
+        memory_kib: bundle.kdf.memory_kib,
+        iterations: bundle.kdf.iterations,
+        parallelism: bundle.kdf.parallelism,
+    };
+    let key = payload_key(password, &salt, params)?;
+    let json = Zeroizing::new(
+        encryption::decrypt_aead(&key, &bundle.payload)
+            .map_err(|_| MobileBundleError::WrongPassword)?,
+    );
+    Ok(serde_json::from_slice(&json)?)
+}
@@ -1,15 +1,46 @@
 pub mod address;
+pub mod attestation;
+#[cfg(feature = "audit-vectors")]
+pub mod audit;
+// Caller-supplied entropy has no place in a shipped binary.
+#[cfg(all(feature = "audit-vectors", not(debug_assertions)))]
+compile_error!("the `audit-vectors` feature must not be enabled in a release build");
+pub mod capsule;
+pub mod ceremony;
+pub mod contact;
+pub mod data_key;
+pub mod emergency;
 pub mod encryption;
+pub mod fingerprint;
+pub mod frost;
 pub mod hash;
 pub mod hd_derivation;
 pub mod hybrid_signing;
+pub mod instance;
 pub mod kdf;
+pub mod key_drive;
+pub mod key_escrow;
 pub mod mldsa87;
 pub mod mlkem1024;
 pub mod mnemonic;
+pub mod mobile;
+pub mod musig2;
+pub mod pairing;
+pub mod passkey;
+pub mod password_strength;
+pub mod price;
 pub mod proof_batch;
+pub mod public_bundle;
+pub mod recovery;
+pub mod signing_request;
+pub mod slip39;
+pub mod spending;
+pub mod ssh;
+pub mod sync;
 pub mod threshold;
+pub mod treasury;
 pub mod vrf;
+pub mod wireguard;
 
 pub use address::derive_address;
 pub use encryption::{decrypt_aead, decrypt_vault, encrypt_aead, encrypt_vault};
@@ -23,7 +54,9 @@ pub use mldsa87::{
 };
 pub use mlkem1024::{KemCiphertext, KemError, KemKeyPair};
 pub use mnemonic::{
-    generate_mnemonic, mnemonic_to_seed, mnemonic_to_seed_with_passphrase, validate_mnemonic,
+    check_phrase, generate_mnemonic, generate_mnemonic_in, mnemonic_to_seed,
+    mnemonic_to_seed_with_passphrase, validate_mnemonic, validate_mnemonic_in, MnemonicLanguage,
+    PhraseCheck,
 };
 pub use proof_batch::{AggregationError, BatchedProof, ProofBatcher};
 pub use threshold::{ThresholdError, ThresholdShare, ThresholdSignature, ThresholdSigner};
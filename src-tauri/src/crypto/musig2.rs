@@ -0,0 +1,514 @@
+use crate::crypto::frost::{self, tagged_hash, FrostError};
+use crate::models::musig2::{
+    MuSig2PartialSignature, MuSig2PublicNonce, MuSig2Session, MuSig2SessionInfo, MuSig2Signature,
+};
+use chrono::{DateTime, Utc};
+use k256::elliptic_curve::ops::Reduce;
+use k256::elliptic_curve::point::AffineCoordinates;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::elliptic_curve::{Field, PrimeField};
+use k256::{FieldBytes, ProjectivePoint, Scalar, U256};
+use rand::rngs::OsRng;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// Largest number of co-signers in one session.
+pub const MAX_MUSIG2_SIGNERS: usize = 16;
+
+/// BLAKE3 `derive_key` context for this vault's MuSig2 key, derived from the
+/// HD master seed so it needs no storing.
+const SIGNING_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 MuSig2 signing key v1";
+const KEY_AGG_LIST_TAG: &[u8] = b"KeyAgg list";
+const KEY_AGG_COEFFICIENT_TAG: &[u8] = b"KeyAgg coefficient";
+const NONCE_COEFFICIENT_TAG: &[u8] = b"MuSig/noncecoef";
+const CHALLENGE_TAG: &[u8] = b"BIP0340/challenge";
+const TAP_TWEAK_TAG: &[u8] = b"TapTweak";
+
+#[derive(Debug, Error)]
+pub enum MuSig2Error {
+    #[error("malformed MuSig2 data: {0}")]
+    Malformed(String),
+    #[error("a session needs between 2 and {MAX_MUSIG2_SIGNERS} distinct signer keys")]
+    WrongSignerCount,
+    #[error("this vault's key is not one of the session's signers")]
+    NotASigner,
+    #[error("this session has already been signed; start a new one")]
+    NonceUsed,
+    #[error("partial signature from {0} is invalid")]
+    BadPartialSignature(String),
+    #[error("final signature does not verify")]
+    BadSignature,
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, MuSig2Error> {
+    hex::decode(value).map_err(|e| MuSig2Error::Malformed(format!("{field}: {e}")))
+}
+
+fn scalar_from_hex(field: &str, value: &str) -> Result<Scalar, MuSig2Error> {
+    let bytes = Zeroizing::new(decode_hex(field, value)?);
+    if bytes.len() != 32 {
+        return Err(MuSig2Error::Malformed(field.to_string()));
+    }
+    Option::<Scalar>::from(Scalar::from_repr(FieldBytes::clone_from_slice(&bytes)))
+        .ok_or_else(|| MuSig2Error::Malformed(field.to_string()))
+}
+
+fn point_bytes(p: &ProjectivePoint) -> Vec<u8> {
+    p.to_affine().to_encoded_point(true).as_bytes().to_vec()
+}
+
+fn point_from_bytes(field: &str, bytes: &[u8]) -> Result<ProjectivePoint, MuSig2Error> {
+    k256::PublicKey::from_sec1_bytes(bytes)
+        .map(|p| p.to_projective())
+        .map_err(|_| MuSig2Error::Malformed(field.to_string()))
+}
+
+/// BIP-327 `cbytes_ext`: the point, or 33 zero bytes for infinity.
+fn point_bytes_ext(p: &ProjectivePoint) -> Vec<u8> {
+    if *p == ProjectivePoint::IDENTITY {
+        vec![0u8; 33]
+    } else {
+        point_bytes(p)
+    }
+}
+
+fn x_only(p: &ProjectivePoint) -> FieldBytes {
+    p.to_affine().x()
+}
+
+fn has_odd_y(p: &ProjectivePoint) -> bool {
+    p.to_affine().y_is_odd().into()
+}
+
+fn sign_of(negate: bool) -> Scalar {
+    if negate {
+        -Scalar::ONE
+    } else {
+        Scalar::ONE
+    }
+}
+
+fn hash_to_scalar(tag: &[u8], parts: &[&[u8]]) -> Scalar {
+    <Scalar as Reduce<U256>>::reduce_bytes(&tagged_hash(tag, parts))
+}
+
+/// This vault's MuSig2 secret key.
+fn secret_key(master_seed: &[u8; 64]) -> Scalar {
+    let seed = Zeroizing::new(blake3::derive_key(SIGNING_KEY_CONTEXT, master_seed));
+    <Scalar as Reduce<U256>>::reduce_bytes(&FieldBytes::clone_from_slice(seed.as_ref()))
+}
+
+/// This vault's individual MuSig2 public key, compressed SEC1 hex. Share it
+/// with the co-signers so each can compute the aggregate key.
+pub fn public_key_hex(master_seed: &[u8; 64]) -> String {
+    hex::encode(point_bytes(
+        &(ProjectivePoint::GENERATOR * secret_key(master_seed)),
+    ))
+}
+
+/// Parse, normalize and sort signer keys as BIP-327 `KeySort`, rejecting
+/// duplicates so every partial signature maps to one signer.
+fn sorted_keys(public_keys: &[String]) -> Result<Vec<Vec<u8>>, MuSig2Error> {
+    let mut keys = public_keys
+        .iter()
+        .map(|k| {
+            let bytes = decode_hex("public_keys", k)?;
+            Ok(point_bytes(&point_from_bytes("public_keys", &bytes)?))
+        })
+        .collect::<Result<Vec<_>, MuSig2Error>>()?;
+    keys.sort();
+    keys.dedup();
+    if keys.len() != public_keys.len() || keys.len() < 2 || keys.len() > MAX_MUSIG2_SIGNERS {
+        return Err(MuSig2Error::WrongSignerCount);
+    }
+    Ok(keys)
+}
+
+/// BIP-327 key aggregation followed by the BIP-341 x-only tweak.
+struct KeyAgg {
+    keys: Vec<Vec<u8>>,
+    coefficients: Vec<Scalar>,
+    points: Vec<ProjectivePoint>,
+    /// Tweaked aggregate key, before the even-y adjustment of signing.
+    output: ProjectivePoint,
+    gacc: Scalar,
+    tacc: Scalar,
+}
+
+impl KeyAgg {
+    fn new(keys: Vec<Vec<u8>>, merkle_root: Option<&[u8]>) -> Result<Self, MuSig2Error> {
+        let list: Vec<u8> = keys.concat();
+        let l = tagged_hash(KEY_AGG_LIST_TAG, &[&list]);
+        let second = keys.iter().find(|k| **k != keys[0]).cloned();
+        let coefficients: Vec<Scalar> = keys
+            .iter()
+            .map(|k| {
+                if Some(k) == second.as_ref() {
+                    Scalar::ONE
+                } else {
+                    hash_to_scalar(KEY_AGG_COEFFICIENT_TAG, &[&l, k])
+                }
+            })
+            .collect();
+        let points = keys
+            .iter()
+            .map(|k| point_from_bytes("public_keys", k))
+            .collect::<Result<Vec<_>, _>>()?;
+        let aggregate = points
+            .iter()
+            .zip(&coefficients)
+            .fold(ProjectivePoint::IDENTITY, |acc, (p, a)| acc + p * a);
+
+        let t = Option::<Scalar>::from(Scalar::from_repr(tagged_hash(
+            TAP_TWEAK_TAG,
+            &[&x_only(&aggregate), merkle_root.unwrap_or_default()],
+        )))
+        .ok_or_else(|| MuSig2Error::Malformed("taproot tweak out of range".to_string()))?;
+        let g = sign_of(has_odd_y(&aggregate));
+        Ok(Self {
+            keys,
+            coefficients,
+            points,
+            output: aggregate * g + ProjectivePoint::GENERATOR * t,
+            gacc: g,
+            tacc: t,
+        })
+    }
+
+    fn position(&self, key: &[u8]) -> Option<usize> {
+        self.keys.iter().position(|k| k == key)
+    }
+
+    fn output_key(&self) -> FieldBytes {
+        x_only(&self.output)
+    }
+}
+
+fn merkle_root(merkle_root_hex: Option<&str>) -> Result<Option<Vec<u8>>, MuSig2Error> {
+    merkle_root_hex
+        .map(|r| decode_hex("merkle_root_hex", r))
+        .transpose()
+}
+
+/// The x-only taproot output key the signers' keys aggregate to, with the
+/// key-path-only (BIP-86) tweak when `merkle_root_hex` is `None`.
+pub fn output_key_hex(
+    public_keys: &[String],
+    merkle_root_hex: Option<&str>,
+) -> Result<String, MuSig2Error> {
+    let root = merkle_root(merkle_root_hex)?;
+    let agg = KeyAgg::new(sorted_keys(public_keys)?, root.as_deref())?;
+    Ok(hex::encode(agg.output_key()))
+}
+
+/// Open a session to co-sign `message_hex` under the aggregate of
+/// `public_keys`, one of which must be this vault's. Generates this signer's
+/// nonces; share the public nonce with the other signers.
+pub fn new_session(
+    master_seed: &[u8; 64],
+    public_keys: &[String],
+    message_hex: &str,
+    merkle_root_hex: Option<&str>,
+    created_at: DateTime<Utc>,
+) -> Result<MuSig2Session, MuSig2Error> {
+    if decode_hex("message_hex", message_hex)?.len() != 32 {
+        return Err(MuSig2Error::Malformed(
+            "message_hex must be a 32-byte sighash".to_string(),
+        ));
+    }
+    let keys = sorted_keys(public_keys)?;
+    KeyAgg::new(keys.clone(), merkle_root(merkle_root_hex)?.as_deref())?;
+    let own = public_key_hex(master_seed);
+    if !keys.iter().any(|k| hex::encode(k) == own) {
+        return Err(MuSig2Error::NotASigner);
+    }
+    let k1 = Scalar::random(&mut OsRng);
+    let k2 = Scalar::random(&mut OsRng);
+    let mut public_nonce = point_bytes(&(ProjectivePoint::GENERATOR * k1));
+    public_nonce.extend_from_slice(&point_bytes(&(ProjectivePoint::GENERATOR * k2)));
+    let mut secret_nonce = Zeroizing::new(k1.to_bytes().to_vec());
+    secret_nonce.extend_from_slice(&k2.to_bytes());
+    Ok(MuSig2Session {
+        session_id: uuid::Uuid::new_v4().to_string(),
+        public_key_hex: own,
+        public_keys: keys.iter().map(hex::encode).collect(),
+        message_hex: message_hex.to_lowercase(),
+        merkle_root_hex: merkle_root_hex.map(str::to_lowercase),
+        public_nonce_hex: hex::encode(public_nonce),
+        secret_nonce_hex: Some(hex::encode(&*secret_nonce)),
+        public_nonces: Vec::new(),
+        created_at,
+    })
+}
+
+/// The public side of `session`, to show and share.
+pub fn session_info(session: &MuSig2Session) -> Result<MuSig2SessionInfo, MuSig2Error> {
+    Ok(MuSig2SessionInfo {
+        session_id: session.session_id.clone(),
+        public_key_hex: session.public_key_hex.clone(),
+        public_keys: session.public_keys.clone(),
+        message_hex: session.message_hex.clone(),
+        merkle_root_hex: session.merkle_root_hex.clone(),
+        output_key_hex: output_key_hex(&session.public_keys, session.merkle_root_hex.as_deref())?,
+        public_nonce_hex: session.public_nonce_hex.clone(),
+        signed: session.secret_nonce_hex.is_none(),
+    })
+}
+
+/// Nonce aggregation and the values every signer derives from it.
+struct SigningContext {
+    agg: KeyAgg,
+    message: Vec<u8>,
+    /// `(R1, R2)` per signer, in key order.
+    nonces: Vec<(ProjectivePoint, ProjectivePoint)>,
+    b: Scalar,
+    r: ProjectivePoint,
+    e: Scalar,
+}
+
+impl SigningContext {
+    fn new(
+        session: &MuSig2Session,
+        public_nonces: &[MuSig2PublicNonce],
+    ) -> Result<Self, MuSig2Error> {
+        let agg = KeyAgg::new(
+            sorted_keys(&session.public_keys)?,
+            merkle_root(session.merkle_root_hex.as_deref())?.as_deref(),
+        )?;
+        let message = decode_hex("message_hex", &session.message_hex)?;
+        if public_nonces.len() != agg.keys.len() {
+            return Err(MuSig2Error::Malformed(format!(
+                "expected {} public nonces, got {}",
+                agg.keys.len(),
+                public_nonces.len()
+            )));
+        }
+        let mut nonces = vec![None; agg.keys.len()];
+        for n in public_nonces {
+            let key = point_bytes(&point_from_bytes(
+                "public_key_hex",
+                &decode_hex("public_key_hex", &n.public_key_hex)?,
+            )?);
+            let pos = agg.position(&key).ok_or_else(|| {
+                MuSig2Error::Malformed(format!("nonce from unknown signer {}", n.public_key_hex))
+            })?;
+            let bytes = decode_hex("nonce_hex", &n.nonce_hex)?;
+            if bytes.len() != 66 || nonces[pos].is_some() {
+                return Err(MuSig2Error::Malformed("nonce_hex".to_string()));
+            }
+            nonces[pos] = Some((
+                point_from_bytes("nonce_hex", &bytes[..33])?,
+                point_from_bytes("nonce_hex", &bytes[33..])?,
+            ));
+        }
+        let nonces: Vec<(ProjectivePoint, ProjectivePoint)> =
+            nonces.into_iter().flatten().collect();
+
+        let (r1, r2) = nonces.iter().fold(
+            (ProjectivePoint::IDENTITY, ProjectivePoint::IDENTITY),
+            |(a, b), (r1, r2)| (a + r1, b + r2),
+        );
+        let mut aggnonce = point_bytes_ext(&r1);
+        aggnonce.extend_from_slice(&point_bytes_ext(&r2));
+        let output_x = agg.output_key();
+        let b = hash_to_scalar(NONCE_COEFFICIENT_TAG, &[&aggnonce, &output_x, &message]);
+        let mut r = r1 + r2 * b;
+        if r == ProjectivePoint::IDENTITY {
+            r = ProjectivePoint::GENERATOR;
+        }
+        let e = hash_to_scalar(CHALLENGE_TAG, &[&x_only(&r), &output_x, &message]);
+        Ok(Self {
+            agg,
+            message,
+            nonces,
+            b,
+            r,
+            e,
+        })
+    }
+
+    /// Sign applied to every signer's secret: the even-y adjustment of the
+    /// output key times the accumulated tweak sign.
+    fn key_sign(&self) -> Scalar {
+        sign_of(has_odd_y(&self.agg.output)) * self.agg.gacc
+    }
+}
+
+/// Produce this vault's partial signature for `session` once every signer's
+/// public nonce is known. The secret nonce is cleared from `session` first;
+/// persist the session before releasing the result.
+pub fn sign(
+    master_seed: &[u8; 64],
+    session: &mut MuSig2Session,
+    public_nonces: &[MuSig2PublicNonce],
+) -> Result<MuSig2PartialSignature, MuSig2Error> {
+    let secret_nonce = Zeroizing::new(
+        session
+            .secret_nonce_hex
+            .take()
+            .ok_or(MuSig2Error::NonceUsed)?,
+    );
+    let own = public_nonces
+        .iter()
+        .find(|n| n.public_key_hex.to_lowercase() == session.public_key_hex)
+        .ok_or(MuSig2Error::NotASigner)?;
+    if own.nonce_hex.to_lowercase() != session.public_nonce_hex {
+        return Err(MuSig2Error::Malformed(
+            "this signer's nonce in the list is not the session's".to_string(),
+        ));
+    }
+    let ctx = SigningContext::new(session, public_nonces)?;
+    let k1 = scalar_from_hex("secret_nonce_hex", &secret_nonce[..64])?;
+    let k2 = scalar_from_hex("secret_nonce_hex", &secret_nonce[64..])?;
+    let nonce_sign = sign_of(has_odd_y(&ctx.r));
+    let sk = secret_key(master_seed);
+    let own_key = point_bytes(&(ProjectivePoint::GENERATOR * sk));
+    let pos = ctx.agg.position(&own_key).ok_or(MuSig2Error::NotASigner)?;
+    let s =
+        nonce_sign * (k1 + ctx.b * k2) + ctx.e * ctx.agg.coefficients[pos] * ctx.key_sign() * sk;
+    session.public_nonces = public_nonces.to_vec();
+    Ok(MuSig2PartialSignature {
+        public_key_hex: session.public_key_hex.clone(),
+        s_hex: hex::encode(s.to_bytes()),
+    })
+}
+
+/// Verify every signer's partial signature and combine them into the final
+/// BIP-340 signature for the output key. Runs on any signer that has signed.
+pub fn finalize(
+    session: &MuSig2Session,
+    partials: &[MuSig2PartialSignature],
+) -> Result<MuSig2Signature, MuSig2Error> {
+    if session.public_nonces.is_empty() {
+        return Err(MuSig2Error::Malformed(
+            "sign the session before finalizing it".to_string(),
+        ));
+    }
+    let ctx = SigningContext::new(session, &session.public_nonces)?;
+    let nonce_sign = sign_of(has_odd_y(&ctx.r));
+    let mut s = ctx.e * sign_of(has_odd_y(&ctx.agg.output)) * ctx.agg.tacc;
+    for (pos, key) in ctx.agg.keys.iter().enumerate() {
+        let key_hex = hex::encode(key);
+        let partial = partials
+            .iter()
+            .find(|p| p.public_key_hex.to_lowercase() == key_hex)
+            .ok_or_else(|| {
+                MuSig2Error::Malformed(format!("missing partial signature from {key_hex}"))
+            })?;
+        let si = scalar_from_hex("s_hex", &partial.s_hex)
+            .map_err(|_| MuSig2Error::BadPartialSignature(key_hex.clone()))?;
+        let (r1, r2) = ctx.nonces[pos];
+        let expected = (r1 + r2 * ctx.b) * nonce_sign
+            + ctx.agg.points[pos] * (ctx.e * ctx.agg.coefficients[pos] * ctx.key_sign());
+        if ProjectivePoint::GENERATOR * si != expected {
+            return Err(MuSig2Error::BadPartialSignature(key_hex));
+        }
+        s += si;
+    }
+    let mut signature = x_only(&ctx.r).to_vec();
+    signature.extend_from_slice(&s.to_bytes());
+    let output_key = ctx.agg.output_key();
+    frost::verify(&output_key, &ctx.message, &signature).map_err(|e| match e {
+        FrostError::BadSignature => MuSig2Error::BadSignature,
+        other => MuSig2Error::Malformed(other.to_string()),
+    })?;
+    Ok(MuSig2Signature {
+        message_hex: session.message_hex.clone(),
+        output_key_hex: hex::encode(output_key),
+        signature_hex: hex::encode(signature),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: [u8; 64] = [31u8; 64];
+    const BOB: [u8; 64] = [32u8; 64];
+    const MESSAGE: [u8; 32] = [5u8; 32];
+
+    fn sessions(merkle_root_hex: Option<&str>) -> (MuSig2Session, MuSig2Session) {
+        let keys = vec![public_key_hex(&ALICE), public_key_hex(&BOB)];
+        let message = hex::encode(MESSAGE);
+        (
+            new_session(&ALICE, &keys, &message, merkle_root_hex, Utc::now()).unwrap(),
+            new_session(&BOB, &keys, &message, merkle_root_hex, Utc::now()).unwrap(),
+        )
+    }
+
+    fn nonces(sessions: &[&MuSig2Session]) -> Vec<MuSig2PublicNonce> {
+        sessions
+            .iter()
+            .map(|s| MuSig2PublicNonce {
+                public_key_hex: s.public_key_hex.clone(),
+                nonce_hex: s.public_nonce_hex.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn two_signers_produce_a_valid_taproot_signature() {
+        for root in [None, Some(hex::encode([3u8; 32]))] {
+            let (mut alice, mut bob) = sessions(root.as_deref());
+            let all = nonces(&[&alice, &bob]);
+            let partials = vec![
+                sign(&ALICE, &mut alice, &all).unwrap(),
+                sign(&BOB, &mut bob, &all).unwrap(),
+            ];
+            let sig = finalize(&alice, &partials).unwrap();
+            assert_eq!(sig, finalize(&bob, &partials).unwrap());
+            assert_eq!(
+                sig.output_key_hex,
+                output_key_hex(&alice.public_keys, root.as_deref()).unwrap()
+            );
+            frost::verify(
+                &hex::decode(&sig.output_key_hex).unwrap(),
+                &MESSAGE,
+                &hex::decode(&sig.signature_hex).unwrap(),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn key_order_does_not_change_the_output_key() {
+        let forward = vec![public_key_hex(&ALICE), public_key_hex(&BOB)];
+        let reverse = vec![public_key_hex(&BOB), public_key_hex(&ALICE)];
+        assert_eq!(
+            output_key_hex(&forward, None).unwrap(),
+            output_key_hex(&reverse, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn a_session_signs_only_once() {
+        let (mut alice, bob) = sessions(None);
+        let all = nonces(&[&alice, &bob]);
+        sign(&ALICE, &mut alice, &all).unwrap();
+        assert!(matches!(
+            sign(&ALICE, &mut alice, &all),
+            Err(MuSig2Error::NonceUsed)
+        ));
+    }
+
+    #[test]
+    fn outsiders_and_bad_partials_are_rejected() {
+        let keys = vec![public_key_hex(&ALICE), public_key_hex(&BOB)];
+        assert!(matches!(
+            new_session(&[33u8; 64], &keys, &hex::encode(MESSAGE), None, Utc::now()),
+            Err(MuSig2Error::NotASigner)
+        ));
+
+        let (mut alice, mut bob) = sessions(None);
+        let all = nonces(&[&alice, &bob]);
+        let mut partials = vec![
+            sign(&ALICE, &mut alice, &all).unwrap(),
+            sign(&BOB, &mut bob, &all).unwrap(),
+        ];
+        partials[1].s_hex = hex::encode(Scalar::ONE.to_bytes());
+        assert!(matches!(
+            finalize(&alice, &partials),
+            Err(MuSig2Error::BadPartialSignature(k)) if k == bob.public_key_hex
+        ));
+    }
+}
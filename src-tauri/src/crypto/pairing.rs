@@ -0,0 +1,189 @@
+//! Key exchange and message sealing for paired browser extensions.
+//!
+//! Pairing is an ephemeral X25519 exchange. Both sides derive the pairing
+//! key and a six-digit code from the shared secret and both public keys; the
+//! user compares the codes, which defeats a relay that swapped the keys.
+
+use crate::crypto::encryption::{self, Ciphertext, EncryptionError};
+use rand::rngs::OsRng;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroizing;
+
+/// BLAKE3 `derive_key` contexts.
+const PAIRING_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 browser pairing key v1";
+const PAIRING_CODE_CONTEXT: &str = "ZAP Quantum Vault 2026 browser pairing code v1";
+const WRAP_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 browser pairing store v1";
+
+#[derive(Debug, Error)]
+pub enum PairingError {
+    #[error("malformed pairing message: {0}")]
+    Malformed(String),
+    #[error("pairing encryption error: {0}")]
+    Encryption(#[from] EncryptionError),
+}
+
+/// The app's half of a new pairing.
+pub struct PairingOffer {
+    pub app_public_hex: String,
+    pub key: Zeroizing<[u8; 32]>,
+    pub code: String,
+}
+
+fn decode_public(hex_key: &str) -> Result<PublicKey, PairingError> {
+    let bytes: [u8; 32] = hex::decode(hex_key)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| PairingError::Malformed("public key must be 32 bytes of hex".into()))?;
+    Ok(PublicKey::from(bytes))
+}
+
+/// Derive the pairing key and code from the X25519 shared secret. Both
+/// public keys are bound in, extension first.
+pub fn derive_pairing(
+    shared: &[u8; 32],
+    extension_public: &[u8; 32],
+    app_public: &[u8; 32],
+) -> (Zeroizing<[u8; 32]>, String) {
+    let transcript = |context: &str| {
+        let mut hasher = blake3::Hasher::new_derive_key(context);
+        hasher.update(shared);
+        hasher.update(extension_public);
+        hasher.update(app_public);
+        *hasher.finalize().as_bytes()
+    };
+    let key = Zeroizing::new(transcript(PAIRING_KEY_CONTEXT));
+    let code_bytes = transcript(PAIRING_CODE_CONTEXT);
+    let code = u32::from_le_bytes(code_bytes[..4].try_into().unwrap()) % 1_000_000;
+    (key, format!("{code:06}"))
+}
+
+/// Answer an extension's pairing request with a fresh ephemeral key.
+pub fn accept_pairing(extension_public_hex: &str) -> Result<PairingOffer, PairingError> {
+    let extension_public = decode_public(extension_public_hex)?;
+    let secret = StaticSecret::random_from_rng(OsRng);
+    let app_public = PublicKey::from(&secret);
+    let shared = Zeroizing::new(secret.diffie_hellman(&extension_public).to_bytes());
+    if shared.iter().all(|b| *b == 0) {
+        return Err(PairingError::Malformed("low-order public key".into()));
+    }
+    let (key, code) = derive_pairing(&shared, extension_public.as_bytes(), app_public.as_bytes());
+    Ok(PairingOffer {
+        app_public_hex: hex::encode(app_public.as_bytes()),
+        key,
+        code,
+    })
+}
+
+fn wrap_key(master_seed: &[u8; 64]) -> Zeroizing<[u8; 32]> {
+    Zeroizing::new(blake3::derive_key(WRAP_KEY_CONTEXT, master_seed))
+}
+
+/// Encrypt a pairing key for `browser_pairing.json`.
+pub fn wrap_pairing_key(
+    master_seed: &[u8; 64],
+    key: &[u8; 32],
+) -> Result<Ciphertext, PairingError> {
+    Ok(encryption::encrypt_aead(&wrap_key(master_seed), key)?)
+}
+
+pub fn unwrap_pairing_key(
+    master_seed: &[u8; 64],
+    wrapped: &Ciphertext,
+) -> Result<Zeroizing<[u8; 32]>, PairingError> {
+    let bytes = Zeroizing::new(encryption::decrypt_aead(&wrap_key(master_seed), wrapped)?);
+    let key: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| PairingError::Malformed("wrapped key has the wrong length".into()))?;
+    Ok(Zeroizing::new(key))
+}
+
+/// Serialize and encrypt `message` under the pairing key, as
+/// `(nonce_hex, ciphertext_hex)`.
+pub fn seal<T: Serialize>(key: &[u8; 32], message: &T) -> Result<(String, String), PairingError> {
+    let json = Zeroizing::new(
+        serde_json::to_vec(message).map_err(|e| PairingError::Malformed(e.to_string()))?,
+    );
+    let ct = encryption::encrypt_aead(key, &json)?;
+    Ok((hex::encode(ct.nonce), hex::encode(ct.ciphertext)))
+}
+
+/// Decrypt and parse a sealed message. Fails on any tampering.
+pub fn open<T: DeserializeOwned>(
+    key: &[u8; 32],
+    nonce_hex: &str,
+    ciphertext_hex: &str,
+) -> Result<T, PairingError> {
+    let decode = |v: &str| hex::decode(v).map_err(|e| PairingError::Malformed(e.to_string()));
+    let ct = Ciphertext {
+        nonce: decode(nonce_hex)?,
+        ciphertext: decode(ciphertext_hex)?,
+    };
+    let json = Zeroizing::new(encryption::decrypt_aead(key, &ct)?);
+    serde_json::from_slice(&json).map_err(|e| PairingError::Malformed(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::pairing::{FillAction, FillRequest};
+
+    /// The extension's side of the exchange, as the browser would run it.
+    fn extension_side(
+        secret: &StaticSecret,
+        app_public_hex: &str,
+    ) -> (Zeroizing<[u8; 32]>, String) {
+        let app_public = decode_public(app_public_hex).unwrap();
+        let shared = secret.diffie_hellman(&app_public).to_bytes();
+        derive_pairing(
+            &shared,
+            PublicKey::from(secret).as_bytes(),
+            app_public.as_bytes(),
+        )
+    }
+
+    #[test]
+    fn both_sides_derive_the_same_key_and_code() {
+        let ext = StaticSecret::random_from_rng(OsRng);
+        let offer = accept_pairing(&hex::encode(PublicKey::from(&ext).as_bytes())).unwrap();
+        let (key, code) = extension_side(&ext, &offer.app_public_hex);
+        assert_eq!(*key, *offer.key);
+        assert_eq!(code, offer.code);
+        assert_eq!(code.len(), 6);
+
+        // A relay that substitutes its own key ends up with a different code.
+        let mitm = StaticSecret::random_from_rng(OsRng);
+        let relayed = accept_pairing(&hex::encode(PublicKey::from(&mitm).as_bytes())).unwrap();
+        assert_ne!(extension_side(&ext, &relayed.app_public_hex).0, offer.key);
+    }
+
+    #[test]
+    fn sealed_messages_round_trip_and_reject_tampering() {
+        let key = [5u8; 32];
+        let request = FillRequest {
+            seq: 3,
+            origin: "https://example.com".into(),
+            action: FillAction::ApiToken,
+        };
+        let (nonce, mut ct) = seal(&key, &request).unwrap();
+        let back: FillRequest = open(&key, &nonce, &ct).unwrap();
+        assert_eq!(back.seq, 3);
+        assert!(open::<FillRequest>(&[6u8; 32], &nonce, &ct).is_err());
+        ct.replace_range(0..2, if ct.starts_with("00") { "01" } else { "00" });
+        assert!(open::<FillRequest>(&key, &nonce, &ct).is_err());
+    }
+
+    #[test]
+    fn pairing_keys_are_wrapped_under_the_seed() {
+        let wrapped = wrap_pairing_key(&[1u8; 64], &[9u8; 32]).unwrap();
+        assert_eq!(
+            *unwrap_pairing_key(&[1u8; 64], &wrapped).unwrap(),
+            [9u8; 32]
+        );
+        assert!(unwrap_pairing_key(&[2u8; 64], &wrapped).is_err());
+        assert!(accept_pairing(&hex::encode([0u8; 32])).is_err());
+    }
+}
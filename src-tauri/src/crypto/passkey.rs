@@ -0,0 +1,367 @@
+//! A software FIDO2 authenticator for passkey items: key generation, COSE
+//! public keys and WebAuthn assertion signing.
+
+use crate::models::item::{PasskeyAlgorithm, PasskeyItem, PasskeySource};
+use crate::models::passkey::{ExportedPasskey, PasskeyAssertion};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use p256::ecdsa::signature::Signer;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// Length of vault-generated credential ids.
+pub const CREDENTIAL_ID_BYTES: usize = 32;
+/// Longest credential id WebAuthn allows.
+pub const MAX_CREDENTIAL_ID_BYTES: usize = 1023;
+/// Longest user handle WebAuthn allows.
+pub const MAX_USER_HANDLE_BYTES: usize = 64;
+
+/// Authenticator data flags: user present, user verified (the vault is
+/// unlocked), backup eligible and backed up (the item store is backed up
+/// with the vault).
+const FLAGS: u8 = 0x01 | 0x04 | 0x08 | 0x10;
+
+#[derive(Debug, Error)]
+pub enum PasskeyError {
+    #[error("invalid passkey field {0}: {1}")]
+    InvalidField(&'static str, String),
+    #[error("invalid passkey private key")]
+    InvalidPrivateKey,
+    #[error("passkey sign counter is exhausted")]
+    CounterExhausted,
+}
+
+fn decode(field: &'static str, value: &str) -> Result<Vec<u8>, PasskeyError> {
+    URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(|e| PasskeyError::InvalidField(field, e.to_string()))
+}
+
+fn private_bytes(private_key: &str) -> Result<Zeroizing<[u8; 32]>, PasskeyError> {
+    let bytes = Zeroizing::new(
+        URL_SAFE_NO_PAD
+            .decode(private_key)
+            .map_err(|_| PasskeyError::InvalidPrivateKey)?,
+    );
+    let arr: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| PasskeyError::InvalidPrivateKey)?;
+    Ok(Zeroizing::new(arr))
+}
+
+/// COSE_Key for the public half of `private_key`, base64url encoded.
+pub fn public_key_cose(
+    algorithm: PasskeyAlgorithm,
+    private_key: &str,
+) -> Result<String, PasskeyError> {
+    let secret = private_bytes(private_key)?;
+    let mut cose = Vec::with_capacity(77);
+    match algorithm {
+        PasskeyAlgorithm::Es256 => {
+            let sk = p256::ecdsa::SigningKey::from_slice(secret.as_ref())
+                .map_err(|_| PasskeyError::InvalidPrivateKey)?;
+            let point = sk.verifying_key().to_encoded_point(false);
+            let (Some(x), Some(y)) = (point.x(), point.y()) else {
+                return Err(PasskeyError::InvalidPrivateKey);
+            };
+            // {1: 2 (EC2), 3: -7 (ES256), -1: 1 (P-256), -2: x, -3: y}
+            cose.extend_from_slice(&[0xa5, 0x01, 0x02, 0x03, 0x26, 0x20, 0x01]);
+            cose.extend_from_slice(&[0x21, 0x58, 0x20]);
+            cose.extend_from_slice(x);
+            cose.extend_from_slice(&[0x22, 0x58, 0x20]);
+            cose.extend_from_slice(y);
+        }
+        PasskeyAlgorithm::EdDsa => {
+            let sk = ed25519_dalek::SigningKey::from_bytes(&secret);
+            // {1: 1 (OKP), 3: -8 (EdDSA), -1: 6 (Ed25519), -2: x}
+            cose.extend_from_slice(&[0xa4, 0x01, 0x01, 0x03, 0x27, 0x20, 0x06]);
+            cose.extend_from_slice(&[0x21, 0x58, 0x20]);
+            cose.extend_from_slice(sk.verifying_key().as_bytes());
+        }
+    }
+    Ok(URL_SAFE_NO_PAD.encode(cose))
+}
+
+fn validate_identity(
+    rp_id: &str,
+    user_handle: &str,
+    credential_id: &str,
+) -> Result<(), PasskeyError> {
+    if rp_id.is_empty() || rp_id.contains(['/', ':', ' ']) {
+        return Err(PasskeyError::InvalidField(
+            "rp_id",
+            "must be a bare domain".to_string(),
+        ));
+    }
+    let handle = decode("user_handle", user_handle)?;
+    if handle.is_empty() || handle.len() > MAX_USER_HANDLE_BYTES {
+        return Err(PasskeyError::InvalidField(
+            "user_handle",
+            format!("must be 1 to {MAX_USER_HANDLE_BYTES} bytes"),
+        ));
+    }
+    let id = decode("credential_id", credential_id)?;
+    if id.is_empty() || id.len() > MAX_CREDENTIAL_ID_BYTES {
+        return Err(PasskeyError::InvalidField(
+            "credential_id",
+            format!("must be 1 to {MAX_CREDENTIAL_ID_BYTES} bytes"),
+        ));
+    }
+    Ok(())
+}
+
+/// Generate a new discoverable credential for `rp_id` and `user_handle`.
+pub fn generate(
+    algorithm: PasskeyAlgorithm,
+    rp_id: &str,
+    rp_name: Option<String>,
+    user_handle: &str,
+    user_name: &str,
+    user_display_name: Option<String>,
+) -> Result<PasskeyItem, PasskeyError> {
+    let mut secret = Zeroizing::new([0u8; 32]);
+    match algorithm {
+        PasskeyAlgorithm::Es256 => {
+            let sk = p256::ecdsa::SigningKey::random(&mut OsRng);
+            secret.copy_from_slice(&sk.to_bytes());
+        }
+        PasskeyAlgorithm::EdDsa => OsRng.fill_bytes(secret.as_mut()),
+    }
+    let mut credential_id = [0u8; CREDENTIAL_ID_BYTES];
+    OsRng.fill_bytes(&mut credential_id);
+    let private_key = URL_SAFE_NO_PAD.encode(secret.as_ref());
+    let item = PasskeyItem {
+        rp_id: rp_id.to_string(),
+        rp_name,
+        user_handle: user_handle.to_string(),
+        user_name: user_name.to_string(),
+        user_display_name,
+        credential_id: URL_SAFE_NO_PAD.encode(credential_id),
+        algorithm,
+        public_key_cose: public_key_cose(algorithm, &private_key)?,
+        private_key,
+        sign_count: 0,
+        discoverable: true,
+        source: PasskeySource::VaultGenerated,
+    };
+    validate_identity(&item.rp_id, &item.user_handle, &item.credential_id)?;
+    Ok(item)
+}
+
+/// Turn one credential from an export document into an item, deriving its
+/// public key from the private key.
+pub fn from_export(
+    exported: &ExportedPasskey,
+    provider: Option<String>,
+) -> Result<PasskeyItem, PasskeyError> {
+    validate_identity(
+        &exported.rp_id,
+        &exported.user_handle,
+        &exported.credential_id,
+    )?;
+    Ok(PasskeyItem {
+        rp_id: exported.rp_id.clone(),
+        rp_name: exported.rp_name.clone(),
+        user_handle: exported.user_handle.clone(),
+        user_name: exported.user_name.clone(),
+        user_display_name: exported.user_display_name.clone(),
+        credential_id: exported.credential_id.clone(),
+        algorithm: exported.algorithm,
+        public_key_cose: public_key_cose(exported.algorithm, &exported.private_key)?,
+        private_key: exported.private_key.clone(),
+        sign_count: exported.sign_count,
+        discoverable: exported.discoverable,
+        source: PasskeySource::Imported { provider },
+    })
+}
+
+pub fn to_export(item: &PasskeyItem) -> ExportedPasskey {
+    ExportedPasskey {
+        rp_id: item.rp_id.clone(),
+        rp_name: item.rp_name.clone(),
+        user_handle: item.user_handle.clone(),
+        user_name: item.user_name.clone(),
+        user_display_name: item.user_display_name.clone(),
+        credential_id: item.credential_id.clone(),
+        algorithm: item.algorithm,
+        private_key: item.private_key.clone(),
+        sign_count: item.sign_count,
+        discoverable: item.discoverable,
+    }
+}
+
+/// `SHA-256(rp_id) || flags || sign_count`, the authenticator data of an
+/// assertion.
+pub fn authenticator_data(rp_id: &str, sign_count: u32) -> Vec<u8> {
+    let mut data = Sha256::digest(rp_id.as_bytes()).to_vec();
+    data.push(FLAGS);
+    data.extend_from_slice(&sign_count.to_be_bytes());
+    data
+}
+
+/// Bump the sign counter and sign `authenticator_data || client_data_hash`.
+/// The caller must persist the item afterwards so the counter never goes
+/// back.
+pub fn sign_assertion(
+    item: &mut PasskeyItem,
+    client_data_hash: &[u8; 32],
+) -> Result<PasskeyAssertion, PasskeyError> {
+    let count = item
+        .sign_count
+        .checked_add(1)
+        .ok_or(PasskeyError::CounterExhausted)?;
+    let auth_data = authenticator_data(&item.rp_id, count);
+    let mut message = auth_data.clone();
+    message.extend_from_slice(client_data_hash);
+    let secret = private_bytes(&item.private_key)?;
+    let signature = match item.algorithm {
+        PasskeyAlgorithm::Es256 => {
+            let sk = p256::ecdsa::SigningKey::from_slice(secret.as_ref())
+                .map_err(|_| PasskeyError::InvalidPrivateKey)?;
+            let sig: p256::ecdsa::Signature = sk.sign(&message);
+            sig.to_der().as_bytes().to_vec()
+        }
+        PasskeyAlgorithm::EdDsa => {
+            use ed25519_dalek::Signer as _;
+            ed25519_dalek::SigningKey::from_bytes(&secret)
+                .sign(&message)
+                .to_bytes()
+                .to_vec()
+        }
+    };
+    item.sign_count = count;
+    Ok(PasskeyAssertion {
+        credential_id: item.credential_id.clone(),
+        authenticator_data: URL_SAFE_NO_PAD.encode(auth_data),
+        signature: URL_SAFE_NO_PAD.encode(signature),
+        user_handle: item.user_handle.clone(),
+        sign_count: count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::signature::Verifier;
+
+    fn handle() -> String {
+        URL_SAFE_NO_PAD.encode(b"user-42")
+    }
+
+    #[test]
+    fn es256_assertion_verifies_and_counts() {
+        let mut item = generate(
+            PasskeyAlgorithm::Es256,
+            "example.com",
+            None,
+            &handle(),
+            "alice",
+            None,
+        )
+        .unwrap();
+        let hash = [9u8; 32];
+        let first = sign_assertion(&mut item, &hash).unwrap();
+        let second = sign_assertion(&mut item, &hash).unwrap();
+        assert_eq!((first.sign_count, second.sign_count), (1, 2));
+
+        let auth_data = URL_SAFE_NO_PAD.decode(&second.authenticator_data).unwrap();
+        assert_eq!(&auth_data[..32], Sha256::digest(b"example.com").as_slice());
+        assert_eq!(auth_data[32], FLAGS);
+        assert_eq!(&auth_data[33..], &2u32.to_be_bytes());
+
+        let cose = URL_SAFE_NO_PAD.decode(&item.public_key_cose).unwrap();
+        assert_eq!(cose.len(), 77);
+        let mut sec1 = vec![0x04];
+        sec1.extend_from_slice(&cose[10..42]);
+        sec1.extend_from_slice(&cose[45..77]);
+        let vk = p256::ecdsa::VerifyingKey::from_sec1_bytes(&sec1).unwrap();
+        let sig =
+            p256::ecdsa::Signature::from_der(&URL_SAFE_NO_PAD.decode(&second.signature).unwrap())
+                .unwrap();
+        let mut message = auth_data;
+        message.extend_from_slice(&hash);
+        assert!(vk.verify(&message, &sig).is_ok());
+    }
+
+    #[test]
+    fn eddsa_assertion_verifies() {
+        let mut item = generate(
+            PasskeyAlgorithm::EdDsa,
+            "example.com",
+            None,
+            &handle(),
+            "alice",
+            None,
+        )
+        .unwrap();
+        let assertion = sign_assertion(&mut item, &[1u8; 32]).unwrap();
+        let cose = URL_SAFE_NO_PAD.decode(&item.public_key_cose).unwrap();
+        let vk = ed25519_dalek::VerifyingKey::from_bytes(cose[10..42].try_into().unwrap()).unwrap();
+        let sig_bytes: [u8; 64] = URL_SAFE_NO_PAD
+            .decode(&assertion.signature)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let mut message = URL_SAFE_NO_PAD
+            .decode(&assertion.authenticator_data)
+            .unwrap();
+        message.extend_from_slice(&[1u8; 32]);
+        assert!(vk
+            .verify_strict(&message, &ed25519_dalek::Signature::from_bytes(&sig_bytes))
+            .is_ok());
+    }
+
+    #[test]
+    fn export_round_trip_keeps_the_key() {
+        let item = generate(
+            PasskeyAlgorithm::Es256,
+            "example.com",
+            Some("Example".to_string()),
+            &handle(),
+            "alice",
+            None,
+        )
+        .unwrap();
+        let json = serde_json::to_string(&to_export(&item)).unwrap();
+        assert!(json.contains("\"rpId\""));
+        let back: ExportedPasskey = serde_json::from_str(&json).unwrap();
+        let imported = from_export(&back, Some("other".to_string())).unwrap();
+        assert_eq!(imported.public_key_cose, item.public_key_cose);
+        assert_eq!(imported.credential_id, item.credential_id);
+        assert_eq!(
+            imported.source,
+            PasskeySource::Imported {
+                provider: Some("other".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_bad_fields() {
+        let mut exported = to_export(
+            &generate(
+                PasskeyAlgorithm::EdDsa,
+                "example.com",
+                None,
+                &handle(),
+                "a",
+                None,
+            )
+            .unwrap(),
+        );
+        exported.rp_id = "https://example.com".to_string();
+        assert!(from_export(&exported, None).is_err());
+        exported.rp_id = "example.com".to_string();
+        exported.private_key = URL_SAFE_NO_PAD.encode([1u8; 16]);
+        assert!(matches!(
+            from_export(&exported, None),
+            Err(PasskeyError::InvalidPrivateKey)
+        ));
+        exported.private_key = URL_SAFE_NO_PAD.encode([1u8; 32]);
+        exported.user_handle = String::new();
+        assert!(from_export(&exported, None).is_err());
+    }
+}
@@ -0,0 +1,651 @@
+//! Password strength estimation in the style of zxcvbn.
+//!
+//! A password is split into the cheapest-to-guess run of recognisable
+//! patterns (common passwords, words tied to this app, sequences, repeats,
+//! keyboard rows, years) and brute-forced characters. The estimated number
+//! of guesses is the product over that run, and the score is 0 to 4 by
+//! order of magnitude. Used wherever the user picks a new password.
+
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::OnceLock;
+
+/// Ranked common passwords, most common first, one per line in lower case,
+/// gzip-compressed. Unpacked on first use. See `docs/PASSWORD_POLICY.md`
+/// for how it is built.
+const COMMON_PASSWORDS_GZ: &[u8] = include_bytes!("common_passwords.txt.gz");
+
+/// Words tied to this app, guessed as early as the most common passwords.
+pub const APP_WORDS: &[&str] = &["zap", "vault", "quantum", "zapvault", "quantumvault"];
+
+pub const MAX_SCORE: u8 = 4;
+/// Score a new password needs unless the vault sets another floor.
+pub const DEFAULT_MIN_SCORE: u8 = 3;
+/// log10 of the guesses below which a password scores 0, 1, 2 and 3.
+const SCORE_THRESHOLDS: [f64; 4] = [3.0, 6.0, 8.0, 10.0];
+/// log10 of the guesses for a character no pattern explains. zxcvbn uses
+/// 10 per character.
+const BRUTEFORCE_LOG10_PER_CHAR: f64 = 1.0;
+/// Floor for a matched pattern (about 50 guesses), so even the most common
+/// password is not free.
+const MIN_PATTERN_LOG10: f64 = 1.7;
+/// Passwords shorter than this are told to grow.
+const SUGGESTED_MIN_CHARS: usize = 12;
+/// Longest word looked up in the common password list.
+const MAX_WORD_CHARS: usize = 32;
+/// Only this many leading characters are analysed; each character past them
+/// costs what an analysed one did on average.
+const MAX_ANALYSED_CHARS: usize = 128;
+const KEYBOARD_ROWS: &[&str] = &[
+    "1234567890",
+    "qwertyuiop",
+    "asdfghjkl",
+    "zxcvbnm",
+    "qwertzuiop",
+    "azertyuiop",
+    "qsdfghjklm",
+    "wxcvbn",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternKind {
+    /// A word from the common password list, possibly capitalised,
+    /// reversed or with l33t substitutions.
+    Dictionary,
+    /// A word tied to this app or to the caller's context.
+    Context,
+    Sequence,
+    Repeat,
+    Keyboard,
+    Year,
+    Bruteforce,
+}
+
+/// One piece of the cheapest split. Positions are in characters; the text
+/// itself is never reported.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PasswordPattern {
+    pub kind: PatternKind,
+    pub start: usize,
+    pub len: usize,
+    pub guesses_log10: f64,
+}
+
+/// Structured result of a strength check, for live feedback while typing
+/// and for the error when a password is refused.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PasswordFeedback {
+    /// 0 (guessed at once) to 4 (infeasible to guess).
+    pub score: u8,
+    pub min_score: u8,
+    pub acceptable: bool,
+    /// log10 of the estimated number of guesses.
+    pub guesses_log10: f64,
+    pub warning: Option<String>,
+    pub suggestions: Vec<String>,
+    pub patterns: Vec<PasswordPattern>,
+}
+
+impl PasswordFeedback {
+    /// One line for an error message.
+    pub fn summary(&self) -> String {
+        let mut parts: Vec<&str> = self.warning.iter().map(String::as_str).collect();
+        parts.extend(self.suggestions.iter().map(String::as_str));
+        format!(
+            "password scores {} of {MAX_SCORE}, needs {}. {}",
+            self.score,
+            self.min_score,
+            parts.join(" ")
+        )
+        .trim_end()
+        .to_string()
+    }
+}
+
+/// A candidate pattern over `start..end`.
+struct Found {
+    start: usize,
+    end: usize,
+    kind: PatternKind,
+    log10: f64,
+    rank: Option<usize>,
+    capitalized: bool,
+    leet: bool,
+    reversed: bool,
+}
+
+impl Found {
+    fn new(start: usize, end: usize, kind: PatternKind, log10: f64) -> Self {
+        Found {
+            start,
+            end,
+            kind,
+            log10,
+            rank: None,
+            capitalized: false,
+            leet: false,
+            reversed: false,
+        }
+    }
+}
+
+/// The common password list, unpacked. The list is embedded at build time,
+/// so failing to unpack it is a build defect.
+fn common_passwords() -> &'static str {
+    static LIST: OnceLock<String> = OnceLock::new();
+    LIST.get_or_init(|| {
+        let mut list = String::new();
+        GzDecoder::new(COMMON_PASSWORDS_GZ)
+            .read_to_string(&mut list)
+            .expect("embedded common password list is valid gzip");
+        list
+    })
+}
+
+fn ranked() -> &'static HashMap<&'static str, usize> {
+    static RANKED: OnceLock<HashMap<&'static str, usize>> = OnceLock::new();
+    RANKED.get_or_init(|| {
+        let mut map = HashMap::new();
+        let words = common_passwords()
+            .lines()
+            .map(str::trim)
+            .filter(|w| !w.is_empty());
+        for (i, word) in words.enumerate() {
+            map.entry(word).or_insert(i + 1);
+        }
+        map
+    })
+}
+
+fn unleet(c: char) -> char {
+    match c {
+        '4' | '@' => 'a',
+        '8' => 'b',
+        '(' => 'c',
+        '3' => 'e',
+        '6' | '9' => 'g',
+        '1' | '!' | '|' => 'i',
+        '0' => 'o',
+        '$' | '5' => 's',
+        '7' | '+' => 't',
+        '2' => 'z',
+        _ => c,
+    }
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// log10 of the extra guesses capital letters in `token` cost. A leading,
+/// trailing or all-caps pattern only doubles them; anything else counts
+/// every way to place that many capitals.
+fn caps_log10(token: &[char]) -> f64 {
+    let upper = token.iter().filter(|c| c.is_uppercase()).count();
+    let lower = token.iter().filter(|c| c.is_lowercase()).count();
+    if upper == 0 {
+        return 0.0;
+    }
+    let edge = upper == 1
+        && (token.first().is_some_and(|c| c.is_uppercase())
+            || token.last().is_some_and(|c| c.is_uppercase()));
+    if lower == 0 || edge {
+        return 2f64.log10();
+    }
+    (1..=upper.min(lower))
+        .map(|k| binomial(upper + lower, k))
+        .sum::<f64>()
+        .log10()
+}
+
+fn dictionary_matches(chars: &[char], lower: &[char], context: &[String], out: &mut Vec<Found>) {
+    let n = lower.len();
+    let unleeted: Vec<char> = lower.iter().map(|&c| unleet(c)).collect();
+    let doubled = 2f64.log10();
+    for i in 0..n {
+        for j in (i + 3)..=n.min(i + MAX_WORD_CHARS) {
+            let caps = caps_log10(&chars[i..j]);
+            let plain: String = lower[i..j].iter().collect();
+            let reversed: String = lower[i..j].iter().rev().collect();
+            let leet: String = unleeted[i..j].iter().collect();
+            let mut candidates: Vec<Found> = Vec::new();
+            let mut add = |kind, log10: f64, rank: Option<usize>, leet: bool, reversed: bool| {
+                candidates.push(Found {
+                    rank,
+                    capitalized: caps > 0.0,
+                    leet,
+                    reversed,
+                    ..Found::new(i, j, kind, log10 + caps)
+                });
+            };
+            if context.contains(&plain) {
+                add(PatternKind::Context, 0.0, None, false, false);
+            }
+            if let Some(&rank) = ranked().get(plain.as_str()) {
+                add(
+                    PatternKind::Dictionary,
+                    (rank as f64).log10(),
+                    Some(rank),
+                    false,
+                    false,
+                );
+            }
+            if reversed != plain {
+                if let Some(&rank) = ranked().get(reversed.as_str()) {
+                    add(
+                        PatternKind::Dictionary,
+                        (rank as f64).log10() + doubled,
+                        Some(rank),
+                        false,
+                        true,
+                    );
+                }
+            }
+            if leet != plain {
+                if context.contains(&leet) {
+                    add(PatternKind::Context, doubled, None, true, false);
+                }
+                if let Some(&rank) = ranked().get(leet.as_str()) {
+                    add(
+                        PatternKind::Dictionary,
+                        (rank as f64).log10() + doubled,
+                        Some(rank),
+                        true,
+                        false,
+                    );
+                }
+            }
+            if let Some(best) = candidates
+                .into_iter()
+                .min_by(|a, b| a.log10.total_cmp(&b.log10))
+            {
+                out.push(best);
+            }
+        }
+    }
+}
+
+fn same_class(a: char, b: char) -> bool {
+    (a.is_ascii_digit() && b.is_ascii_digit()) || (a.is_ascii_lowercase() && b.is_ascii_lowercase())
+}
+
+/// Runs like `abcd`, `9876` or `xyz` of three or more characters.
+fn sequence_matches(lower: &[char], out: &mut Vec<Found>) {
+    let n = lower.len();
+    let mut i = 0;
+    while i + 1 < n {
+        let delta = lower[i + 1] as i64 - lower[i] as i64;
+        let mut j = i + 1;
+        if delta.abs() == 1 && same_class(lower[i], lower[i + 1]) {
+            while j + 1 < n
+                && lower[j + 1] as i64 - lower[j] as i64 == delta
+                && same_class(lower[j], lower[j + 1])
+            {
+                j += 1;
+            }
+        }
+        let len = j - i + 1;
+        if len >= 3 {
+            let first = lower[i];
+            let base: f64 = if matches!(first, 'a' | 'z' | '0' | '1' | '9') {
+                4.0
+            } else if first.is_ascii_digit() {
+                10.0
+            } else {
+                26.0
+            };
+            let descending = if delta < 0 { 2.0 } else { 1.0 };
+            out.push(Found::new(
+                i,
+                j + 1,
+                PatternKind::Sequence,
+                (base * len as f64 * descending).log10(),
+            ));
+        }
+        i = j;
+    }
+}
+
+/// A chunk repeated back to back, like `aaa` or `abcabc`. The chunk is
+/// itself estimated, so `passwordpassword` costs little more than
+/// `password`.
+fn repeat_matches(
+    lower: &[char],
+    context: &[String],
+    cache: &mut HashMap<String, f64>,
+    out: &mut Vec<Found>,
+) {
+    let n = lower.len();
+    for i in 0..n {
+        for unit in 1..=(n - i) / 2 {
+            // Only the first occurrence starts a repeat.
+            if i >= unit && lower[i - unit..i] == lower[i..i + unit] {
+                continue;
+            }
+            let chunk = &lower[i..i + unit];
+            let mut count = 1;
+            while i + (count + 1) * unit <= n
+                && lower[i + count * unit..i + (count + 1) * unit] == *chunk
+            {
+                count += 1;
+            }
+            if count < 2 || count * unit < 3 {
+                continue;
+            }
+            let chunk: String = chunk.iter().collect();
+            let base = match cache.get(&chunk) {
+                Some(&log10) => log10,
+                None => {
+                    let log10 = analyse(&chunk, context, cache).0;
+                    cache.insert(chunk, log10);
+                    log10
+                }
+            };
+            out.push(Found::new(
+                i,
+                i + count * unit,
+                PatternKind::Repeat,
+                base + (count as f64).log10(),
+            ));
+        }
+    }
+}
+
+/// Four or more adjacent keys along one keyboard row, either way.
+fn keyboard_matches(lower: &[char], out: &mut Vec<Found>) {
+    let n = lower.len();
+    for row in KEYBOARD_ROWS {
+        let forward: Vec<char> = row.chars().collect();
+        let backward: Vec<char> = row.chars().rev().collect();
+        for i in 0..n {
+            for j in (i + 4)..=n {
+                let token = &lower[i..j];
+                let len = token.len() as f64;
+                if forward.windows(token.len()).any(|w| w == token) {
+                    out.push(Found::new(
+                        i,
+                        j,
+                        PatternKind::Keyboard,
+                        (40.0 * len).log10(),
+                    ));
+                } else if backward.windows(token.len()).any(|w| w == token) {
+                    out.push(Found::new(
+                        i,
+                        j,
+                        PatternKind::Keyboard,
+                        (80.0 * len).log10(),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Years 1900 to 2099.
+fn year_matches(lower: &[char], out: &mut Vec<Found>) {
+    for (i, w) in lower.windows(4).enumerate() {
+        if w.iter().all(|c| c.is_ascii_digit()) && matches!((w[0], w[1]), ('1', '9') | ('2', '0')) {
+            out.push(Found::new(i, i + 4, PatternKind::Year, 200f64.log10()));
+        }
+    }
+}
+
+/// The cheapest split of `password` and its cost, as log10 guesses.
+fn analyse(
+    password: &str,
+    context: &[String],
+    cache: &mut HashMap<String, f64>,
+) -> (f64, Vec<Found>) {
+    let all: Vec<char> = password.chars().collect();
+    let chars = &all[..all.len().min(MAX_ANALYSED_CHARS)];
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let n = chars.len();
+
+    let mut found = Vec::new();
+    dictionary_matches(chars, &lower, context, &mut found);
+    sequence_matches(&lower, &mut found);
+    repeat_matches(&lower, context, cache, &mut found);
+    keyboard_matches(&lower, &mut found);
+    year_matches(&lower, &mut found);
+
+    // best[k]: cheapest cover of the first k characters; via[k]: the
+    // pattern ending at k that achieves it, or None for one brute-forced
+    // character.
+    let mut best = vec![0.0; n + 1];
+    let mut via: Vec<Option<usize>> = vec![None; n + 1];
+    for k in 1..=n {
+        best[k] = best[k - 1] + BRUTEFORCE_LOG10_PER_CHAR;
+        for (idx, f) in found.iter().enumerate().filter(|(_, f)| f.end == k) {
+            let cost = best[f.start] + f.log10.max(MIN_PATTERN_LOG10);
+            if cost < best[k] {
+                best[k] = cost;
+                via[k] = Some(idx);
+            }
+        }
+    }
+
+    let mut chosen = Vec::new();
+    let mut k = n;
+    while k > 0 {
+        match via[k] {
+            Some(idx) => {
+                chosen.push(idx);
+                k = found[idx].start;
+            }
+            None => k -= 1,
+        }
+    }
+    chosen.reverse();
+    let mut slots: Vec<Option<Found>> = found.into_iter().map(Some).collect();
+    let picked = chosen.into_iter().filter_map(|i| slots[i].take()).collect();
+    let tail = match n {
+        0 => 0.0,
+        _ => (all.len() - n) as f64 * best[n] / n as f64,
+    };
+    (best[n] + tail, picked)
+}
+
+/// The picked patterns with the gaps between them as brute force.
+fn patterns_of(picked: &[Found], len: usize) -> Vec<PasswordPattern> {
+    let mut out = Vec::new();
+    let mut at = 0;
+    let brute = |start: usize, end: usize| PasswordPattern {
+        kind: PatternKind::Bruteforce,
+        start,
+        len: end - start,
+        guesses_log10: (end - start) as f64 * BRUTEFORCE_LOG10_PER_CHAR,
+    };
+    for f in picked {
+        if f.start > at {
+            out.push(brute(at, f.start));
+        }
+        out.push(PasswordPattern {
+            kind: f.kind,
+            start: f.start,
+            len: f.end - f.start,
+            guesses_log10: f.log10.max(MIN_PATTERN_LOG10),
+        });
+        at = f.end;
+    }
+    if len > at {
+        out.push(brute(at, len));
+    }
+    out
+}
+
+pub fn score_of(guesses_log10: f64) -> u8 {
+    SCORE_THRESHOLDS
+        .iter()
+        .position(|&t| guesses_log10 < t)
+        .map_or(MAX_SCORE, |s| s as u8)
+}
+
+fn warning_for(picked: &[Found], len: usize) -> Option<String> {
+    let main = picked.iter().max_by_key(|f| f.end - f.start);
+    let whole = main.is_some_and(|f| f.end - f.start == len);
+    let text = match main.map(|f| (f.kind, f.rank)) {
+        Some((PatternKind::Dictionary, Some(rank))) if whole && rank <= 10 => {
+            "This is a top-10 common password."
+        }
+        Some((PatternKind::Dictionary, Some(rank))) if whole && rank <= 100 => {
+            "This is a top-100 common password."
+        }
+        Some((PatternKind::Dictionary, _)) if whole => "This is a very common password.",
+        Some((PatternKind::Dictionary, _)) => "Words from common password lists are easy to guess.",
+        Some((PatternKind::Context, _)) => "Words like the app's name are easy to guess.",
+        Some((PatternKind::Sequence, _)) => "Sequences like abc or 6543 are easy to guess.",
+        Some((PatternKind::Repeat, _)) => "Repeats like \"aaa\" or \"abcabc\" are easy to guess.",
+        Some((PatternKind::Keyboard, _)) => "Straight rows of keys are easy to guess.",
+        Some((PatternKind::Year, _)) => "Recent years are easy to guess.",
+        Some((PatternKind::Bruteforce, _)) | None if len < SUGGESTED_MIN_CHARS => {
+            "Short passwords are easy to guess."
+        }
+        _ => return None,
+    };
+    Some(text.to_string())
+}
+
+fn suggestions_for(picked: &[Found], len: usize) -> Vec<String> {
+    let mut out = vec!["Add another word or two. Uncommon words are better."];
+    if len < SUGGESTED_MIN_CHARS {
+        out.push(
+            "Use at least 12 characters. A few unrelated words are easier to remember than symbols.",
+        );
+    }
+    if picked.iter().any(|f| f.capitalized) {
+        out.push("Capitalizing the first or every letter doesn't help much.");
+    }
+    if picked.iter().any(|f| f.leet) {
+        out.push("Predictable substitutions like '@' instead of 'a' don't help much.");
+    }
+    if picked.iter().any(|f| f.reversed) {
+        out.push("Reversed words aren't much harder to guess.");
+    }
+    if picked.iter().any(|f| f.kind == PatternKind::Year) {
+        out.push("Avoid years that are associated with you.");
+    }
+    if picked.iter().any(|f| {
+        matches!(
+            f.kind,
+            PatternKind::Sequence | PatternKind::Repeat | PatternKind::Keyboard
+        )
+    }) {
+        out.push("Avoid sequences, repeated characters and keyboard rows.");
+    }
+    out.into_iter().map(str::to_string).collect()
+}
+
+/// Estimate `password`'s strength against `min_score`. `context` holds
+/// extra words an attacker would try first, such as a profile name; the
+/// app's own words are always included.
+pub fn check(password: &str, context: &[&str], min_score: u8) -> PasswordFeedback {
+    let context: Vec<String> = APP_WORDS
+        .iter()
+        .chain(context)
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| w.chars().count() >= 3)
+        .collect();
+    let (guesses_log10, picked) = analyse(password, &context, &mut HashMap::new());
+    let len = password.chars().count();
+    let score = score_of(guesses_log10);
+    let acceptable = score >= min_score && len > 0;
+    // Like zxcvbn, stay quiet about passwords that are already strong.
+    let quiet = acceptable && score >= 3;
+    PasswordFeedback {
+        score,
+        min_score,
+        acceptable,
+        guesses_log10,
+        warning: if len == 0 {
+            Some("Enter a password.".to_string())
+        } else if quiet {
+            None
+        } else {
+            warning_for(&picked, len)
+        },
+        suggestions: if quiet {
+            Vec::new()
+        } else {
+            suggestions_for(&picked, len)
+        },
+        patterns: patterns_of(&picked, len.min(MAX_ANALYSED_CHARS)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(feedback: &PasswordFeedback) -> Vec<PatternKind> {
+        feedback.patterns.iter().map(|p| p.kind).collect()
+    }
+
+    #[test]
+    fn common_passwords_and_their_variants_score_zero() {
+        for pw in ["password", "123456", "P@ssw0rd", "drowssap", "Qwerty123"] {
+            let f = check(pw, &[], 3);
+            assert_eq!(f.score, 0, "{pw}");
+            assert!(!f.acceptable);
+            assert!(f.warning.is_some());
+        }
+        assert_eq!(
+            check("password", &[], 3).warning.as_deref(),
+            Some("This is a top-10 common password.")
+        );
+        assert!(check("P@ssw0rd", &[], 3)
+            .suggestions
+            .iter()
+            .any(|s| s.contains("substitutions")));
+    }
+
+    #[test]
+    fn the_deepest_listed_password_is_found_at_its_rank() {
+        let words: Vec<&str> = common_passwords()
+            .lines()
+            .map(str::trim)
+            .filter(|w| !w.is_empty())
+            .collect();
+        let last = *words.last().unwrap();
+        let rank = ranked()[last];
+        assert!(rank > words.len() / 2, "{last} is listed earlier too");
+        let f = check(last, &[], DEFAULT_MIN_SCORE);
+        assert!(!f.acceptable, "{last}");
+        assert_eq!(kinds(&f), [PatternKind::Dictionary]);
+        assert!(f.guesses_log10 >= (rank as f64).log10());
+    }
+
+    #[test]
+    fn patterns_are_found_and_cost_little() {
+        assert_eq!(kinds(&check("abcdefgh", &[], 0)), [PatternKind::Sequence]);
+        assert_eq!(kinds(&check("zzzzzzzzzz", &[], 0)), [PatternKind::Repeat]);
+        assert_eq!(kinds(&check("wertyu", &[], 0)), [PatternKind::Keyboard]);
+        assert!(check("summer2024", &[], 0).score <= 1);
+        assert!(kinds(&check("summer2024", &[], 0)).contains(&PatternKind::Year));
+        assert!(check("passwordpassword", &[], 0).score <= 1);
+        assert!(check(&"a".repeat(200), &[], 0).score <= 1);
+        assert!(check("ZapVault", &[], 0).score <= 1);
+        assert!(
+            check("acme-holdings", &["Acme"], 0).guesses_log10
+                < check("acme-holdings", &[], 0).guesses_log10
+        );
+    }
+
+    #[test]
+    fn unpredictable_passwords_pass_quietly() {
+        for pw in ["kT9#mQ2$vL8@", "marble kettle ozone fidget", "Wq7!zR3^pL0&"] {
+            let f = check(pw, &[], 3);
+            assert_eq!(f.score, MAX_SCORE, "{pw}");
+            assert!(f.acceptable);
+            assert!(f.warning.is_none());
+            assert!(f.suggestions.is_empty());
+        }
+        assert!(!check("", &[], 0).acceptable);
+        assert!(check("abc", &[], 0).acceptable);
+    }
+}
@@ -0,0 +1,416 @@
+//! Signed asset price snapshots, so amounts can be shown in fiat without
+//! network access.
+//!
+//! A publisher signs a [`PriceSnapshot`] with ML-DSA-87 and ships it as JSON
+//! or CSV. The signature covers the canonical form of the snapshot, not the
+//! file, so either encoding of the same snapshot verifies. A vault only
+//! imports snapshots from publishers it has been told to trust, and never
+//! one older than the snapshot it already holds.
+
+use crate::crypto::fingerprint::KeyFingerprint;
+use crate::crypto::mldsa87::{self, CryptoError, PublicKey, SecretKey, Signature};
+use crate::i18n::tr;
+use crate::models::price::{
+    AssetPrice, PriceSigner, PriceSnapshot, PRICE_SNAPSHOT_FORMAT, PRICE_SNAPSHOT_VERSION,
+    PRICE_STALE_HOURS, PRICE_VERY_STALE_DAYS,
+};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use thiserror::Error;
+
+/// Most digits a price may have after the decimal point.
+pub const MAX_PRICE_FRACTION_DIGITS: u32 = 18;
+/// Most base-unit decimals an asset may declare (wei is 18).
+pub const MAX_ASSET_DECIMALS: u8 = 24;
+
+/// Domain separator for snapshot signatures.
+const SNAPSHOT_DOMAIN: &[u8] = b"ZAP-price-snapshot-v1";
+/// How far ahead of this machine's clock a snapshot may be dated.
+const MAX_CLOCK_SKEW_SECS: i64 = 5 * 60;
+const CSV_HEADER: &str = "asset,price,decimals";
+
+#[derive(Debug, Error)]
+pub enum PriceError {
+    #[error("malformed price snapshot: {0}")]
+    Malformed(String),
+    #[error("unsupported price snapshot version {0}")]
+    UnsupportedVersion(u32),
+    #[error("price snapshot is signed by an untrusted key ({0})")]
+    UntrustedSigner(String),
+    #[error("price snapshot signature does not verify")]
+    BadSignature,
+    #[error("price snapshot is dated in the future")]
+    FromFuture,
+    #[error("price snapshot from {imported} is not newer than the stored one from {current}")]
+    NotNewer {
+        imported: DateTime<Utc>,
+        current: DateTime<Utc>,
+    },
+    #[error(transparent)]
+    Crypto(#[from] CryptoError),
+}
+
+fn malformed(reason: impl Into<String>) -> PriceError {
+    PriceError::Malformed(reason.into())
+}
+
+/// Uppercase ticker of 1–32 letters, digits, `.`, `-` or `_`.
+pub fn normalize_asset(asset: &str) -> Result<String, PriceError> {
+    let asset = asset.trim().to_ascii_uppercase();
+    if asset.is_empty()
+        || asset.len() > 32
+        || !asset
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+    {
+        return Err(malformed(format!("invalid asset {asset:?}")));
+    }
+    Ok(asset)
+}
+
+/// A non-negative decimal as its digits without the point, and how many of
+/// them are fractional: `67000.12` → (6700012, 2).
+fn parse_decimal(price: &str) -> Result<(u128, u32), PriceError> {
+    let bad = || malformed(format!("invalid price {price:?}"));
+    let (whole, fraction) = price.split_once('.').unwrap_or((price, ""));
+    if whole.is_empty()
+        || !whole.bytes().all(|b| b.is_ascii_digit())
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+        || (price.contains('.') && fraction.is_empty())
+        || fraction.len() as u32 > MAX_PRICE_FRACTION_DIGITS
+    {
+        return Err(bad());
+    }
+    let digits: u128 = format!("{whole}{fraction}").parse().map_err(|_| bad())?;
+    Ok((digits, fraction.len() as u32))
+}
+
+/// Check a snapshot's fields, normalising tickers and sorting prices so the
+/// signed form does not depend on the order a file lists them in.
+fn validate(snapshot: &mut PriceSnapshot) -> Result<(), PriceError> {
+    if snapshot.format != PRICE_SNAPSHOT_FORMAT {
+        return Err(malformed(format!("format {}", snapshot.format)));
+    }
+    if snapshot.version != PRICE_SNAPSHOT_VERSION {
+        return Err(PriceError::UnsupportedVersion(snapshot.version));
+    }
+    snapshot.currency = snapshot.currency.trim().to_ascii_uppercase();
+    if snapshot.currency.len() != 3 || !snapshot.currency.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(malformed(format!(
+            "invalid currency {:?}",
+            snapshot.currency
+        )));
+    }
+    if snapshot.prices.is_empty() {
+        return Err(malformed("no prices"));
+    }
+    for price in snapshot.prices.iter_mut() {
+        price.asset = normalize_asset(&price.asset)?;
+        price.price = price.price.trim().to_string();
+        parse_decimal(&price.price)?;
+        if price.decimals > MAX_ASSET_DECIMALS {
+            return Err(malformed(format!("{} has too many decimals", price.asset)));
+        }
+    }
+    snapshot.prices.sort_by(|a, b| a.asset.cmp(&b.asset));
+    if snapshot.prices.windows(2).any(|w| w[0].asset == w[1].asset) {
+        return Err(malformed("an asset is listed twice"));
+    }
+    Ok(())
+}
+
+fn signing_message(snapshot: &PriceSnapshot) -> Vec<u8> {
+    let body = serde_json::to_vec(&(
+        &snapshot.format,
+        snapshot.version,
+        snapshot
+            .created_at
+            .to_rfc3339_opts(SecondsFormat::Secs, true),
+        &snapshot.currency,
+        &snapshot.prices,
+    ))
+    .expect("snapshot serializes");
+    [SNAPSHOT_DOMAIN, body.as_slice()].concat()
+}
+
+/// Build and sign a snapshot, as a publisher does.
+pub fn sign_snapshot(
+    secret: &SecretKey,
+    public_key_hex: &str,
+    created_at: DateTime<Utc>,
+    currency: &str,
+    prices: Vec<AssetPrice>,
+) -> Result<PriceSnapshot, PriceError> {
+    let mut snapshot = PriceSnapshot {
+        format: PRICE_SNAPSHOT_FORMAT.to_string(),
+        version: PRICE_SNAPSHOT_VERSION,
+        created_at,
+        currency: currency.to_string(),
+        prices,
+        public_key_hex: public_key_hex.to_string(),
+        signature_hex: String::new(),
+    };
+    validate(&mut snapshot)?;
+    snapshot.signature_hex = mldsa87::sign(secret, &signing_message(&snapshot))?.to_hex();
+    Ok(snapshot)
+}
+
+fn parse_csv(text: &str) -> Result<PriceSnapshot, PriceError> {
+    let mut headers = Vec::new();
+    let mut prices = Vec::new();
+    let mut seen_columns = false;
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some(comment) = line.strip_prefix('#') {
+            if let Some((key, value)) = comment.split_once(':') {
+                headers.push((key.trim().to_string(), value.trim().to_string()));
+            }
+            continue;
+        }
+        if !seen_columns {
+            if line.replace(' ', "") != CSV_HEADER {
+                return Err(malformed(format!("expected the header row {CSV_HEADER}")));
+            }
+            seen_columns = true;
+            continue;
+        }
+        let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [asset, price, decimals] = cells.as_slice() else {
+            return Err(malformed(format!("row {line:?} needs three columns")));
+        };
+        prices.push(AssetPrice {
+            asset: asset.to_string(),
+            price: price.to_string(),
+            decimals: decimals
+                .parse()
+                .map_err(|_| malformed(format!("invalid decimals in row {line:?}")))?,
+        });
+    }
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.clone())
+            .ok_or_else(|| malformed(format!("missing # {name}: line")))
+    };
+    Ok(PriceSnapshot {
+        format: header("format")?,
+        version: header("version")?
+            .parse()
+            .map_err(|_| malformed("invalid version"))?,
+        created_at: DateTime::parse_from_rfc3339(&header("created_at")?)
+            .map_err(|e| malformed(format!("invalid created_at: {e}")))?
+            .with_timezone(&Utc),
+        currency: header("currency")?,
+        prices,
+        public_key_hex: header("public_key")?,
+        signature_hex: header("signature")?,
+    })
+}
+
+/// A snapshot as CSV, with its metadata in `# key: value` lines.
+pub fn to_csv(snapshot: &PriceSnapshot) -> String {
+    let mut out = format!(
+        "# format: {}\n# version: {}\n# created_at: {}\n# currency: {}\n# public_key: {}\n# signature: {}\n{CSV_HEADER}\n",
+        snapshot.format,
+        snapshot.version,
+        snapshot.created_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        snapshot.currency,
+        snapshot.public_key_hex,
+        snapshot.signature_hex,
+    );
+    for p in &snapshot.prices {
+        out.push_str(&format!("{},{},{}\n", p.asset, p.price, p.decimals));
+    }
+    out
+}
+
+/// Read a JSON or CSV snapshot file. The signature is not checked here.
+pub fn parse(data: &[u8]) -> Result<PriceSnapshot, PriceError> {
+    let text = std::str::from_utf8(data).map_err(|_| malformed("not UTF-8"))?;
+    let mut snapshot = if text.trim_start().starts_with('{') {
+        serde_json::from_str(text).map_err(|e| malformed(e.to_string()))?
+    } else {
+        parse_csv(text)?
+    };
+    validate(&mut snapshot)?;
+    Ok(snapshot)
+}
+
+/// The trusted publisher that signed `snapshot`.
+pub fn verify<'a>(
+    snapshot: &PriceSnapshot,
+    signers: &'a [PriceSigner],
+) -> Result<&'a PriceSigner, PriceError> {
+    let public_key_hex = snapshot.public_key_hex.trim().to_ascii_lowercase();
+    let signer = signers
+        .iter()
+        .find(|s| s.public_key_hex == public_key_hex)
+        .ok_or_else(|| {
+            let pk = hex::decode(&public_key_hex).unwrap_or_default();
+            PriceError::UntrustedSigner(KeyFingerprint::of(&pk).hex)
+        })?;
+    let pk = PublicKey::from_hex(&public_key_hex)?;
+    let sig = Signature::from_hex(&snapshot.signature_hex).map_err(|_| PriceError::BadSignature)?;
+    if !mldsa87::verify(&pk, &signing_message(snapshot), &sig)? {
+        return Err(PriceError::BadSignature);
+    }
+    Ok(signer)
+}
+
+/// Refuse a snapshot dated ahead of this machine's clock, or no newer than
+/// the one already stored, so an old snapshot cannot be replayed over a
+/// fresh one.
+pub fn check_newer(
+    snapshot: &PriceSnapshot,
+    current: Option<&PriceSnapshot>,
+    now: DateTime<Utc>,
+) -> Result<(), PriceError> {
+    if snapshot.created_at > now + Duration::seconds(MAX_CLOCK_SKEW_SECS) {
+        return Err(PriceError::FromFuture);
+    }
+    if let Some(current) = current {
+        if snapshot.created_at <= current.created_at {
+            return Err(PriceError::NotNewer {
+                imported: snapshot.created_at,
+                current: current.created_at,
+            });
+        }
+    }
+    Ok(())
+}
+
+pub fn age_warnings(created_at: DateTime<Utc>, now: DateTime<Utc>, locale: &str) -> Vec<String> {
+    let age = now - created_at;
+    if age > Duration::days(PRICE_VERY_STALE_DAYS) {
+        vec![tr(
+            locale,
+            "warning-prices-very-stale",
+            &[("days", age.num_days().into())],
+        )]
+    } else if age > Duration::hours(PRICE_STALE_HOURS) {
+        vec![tr(
+            locale,
+            "warning-prices-stale",
+            &[("hours", age.num_hours().into())],
+        )]
+    } else {
+        Vec::new()
+    }
+}
+
+/// `amount` base units of `price.asset` in hundredths of the currency,
+/// rounded down. `None` if the arithmetic overflows.
+pub fn fiat_cents(amount: u128, price: &AssetPrice) -> Option<u128> {
+    let (mantissa, scale) = parse_decimal(&price.price).ok()?;
+    let product = amount.checked_mul(mantissa)?;
+    let shift = price.decimals as u32 + scale;
+    if shift >= 2 {
+        Some(product / 10u128.checked_pow(shift - 2)?)
+    } else {
+        product.checked_mul(10u128.pow(2 - shift))
+    }
+}
+
+/// `123456` → `1234.56`.
+pub fn format_cents(cents: u128) -> String {
+    format!("{}.{:02}", cents / 100, cents % 100)
+}
+
+/// `amount` base units of `price.asset`, rounded down to two decimals.
+/// `None` if the arithmetic overflows.
+pub fn fiat_value(amount: u128, price: &AssetPrice) -> Option<String> {
+    fiat_cents(amount, price).map(format_cents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices() -> Vec<AssetPrice> {
+        vec![
+            AssetPrice {
+                asset: "eth".to_string(),
+                price: "3500.5".to_string(),
+                decimals: 18,
+            },
+            AssetPrice {
+                asset: "BTC".to_string(),
+                price: "67000.12".to_string(),
+                decimals: 8,
+            },
+        ]
+    }
+
+    fn signer(public_key_hex: &str) -> PriceSigner {
+        PriceSigner {
+            name: "desk".to_string(),
+            public_key_hex: public_key_hex.to_string(),
+            fingerprint: KeyFingerprint::of(&hex::decode(public_key_hex).unwrap()),
+            added_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_json_and_csv_verify_alike() {
+        let (pk, sk) = mldsa87::generate();
+        let snapshot = sign_snapshot(&sk, &pk.to_hex(), Utc::now(), "usd", prices()).unwrap();
+        assert_eq!(snapshot.prices[0].asset, "BTC");
+        let signers = vec![signer(&pk.to_hex())];
+
+        let from_json = parse(&serde_json::to_vec(&snapshot).unwrap()).unwrap();
+        assert_eq!(verify(&from_json, &signers).unwrap().name, "desk");
+        let from_csv = parse(to_csv(&snapshot).as_bytes()).unwrap();
+        assert_eq!(verify(&from_csv, &signers).unwrap().name, "desk");
+    }
+
+    #[test]
+    fn test_tampered_or_untrusted_snapshot_is_refused() {
+        let (pk, sk) = mldsa87::generate();
+        let snapshot = sign_snapshot(&sk, &pk.to_hex(), Utc::now(), "USD", prices()).unwrap();
+        let csv = to_csv(&snapshot).replace("67000.12", "6700.12");
+        assert!(matches!(
+            verify(&parse(csv.as_bytes()).unwrap(), &[signer(&pk.to_hex())]),
+            Err(PriceError::BadSignature)
+        ));
+        let (other, _) = mldsa87::generate();
+        assert!(matches!(
+            verify(&snapshot, &[signer(&other.to_hex())]),
+            Err(PriceError::UntrustedSigner(_))
+        ));
+    }
+
+    #[test]
+    fn test_only_newer_snapshots_are_accepted() {
+        let (pk, sk) = mldsa87::generate();
+        let now = Utc::now();
+        let old =
+            sign_snapshot(&sk, &pk.to_hex(), now - Duration::hours(2), "USD", prices()).unwrap();
+        let new =
+            sign_snapshot(&sk, &pk.to_hex(), now - Duration::hours(1), "USD", prices()).unwrap();
+        check_newer(&new, Some(&old), now).unwrap();
+        assert!(check_newer(&old, Some(&new), now).is_err());
+        let ahead =
+            sign_snapshot(&sk, &pk.to_hex(), now + Duration::hours(1), "USD", prices()).unwrap();
+        assert!(matches!(
+            check_newer(&ahead, None, now),
+            Err(PriceError::FromFuture)
+        ));
+    }
+
+    #[test]
+    fn test_fiat_value_and_age() {
+        let btc = &prices()[1];
+        assert_eq!(fiat_value(150_000_000, btc).as_deref(), Some("100500.18"));
+        assert_eq!(fiat_value(1, btc).as_deref(), Some("0.00"));
+        let eth = &prices()[0];
+        assert_eq!(
+            fiat_value(2_000_000_000_000_000_000, eth).as_deref(),
+            Some("7001.00")
+        );
+        let now = Utc::now();
+        assert!(age_warnings(now - Duration::hours(1), now, "en-US").is_empty());
+        assert!(age_warnings(now - Duration::hours(30), now, "en-US")[0].contains("30 hours"));
+        assert!(age_warnings(now - Duration::days(9), now, "en-US")[0].contains("9 days"));
+        assert!(parse_decimal("1.").is_err());
+        assert!(parse_decimal("-1").is_err());
+    }
+}
@@ -0,0 +1,133 @@
+//! Signed public bundles: a vault's public keys and addresses, for watch-only
+//! use on another vault instance. Signed like a key attestation, by the vault
+//! identity and the exporting installation.
+
+use crate::crypto::instance::{self, InstanceError, InstanceKey, PURPOSE_PUBLIC_BUNDLE};
+use crate::crypto::mldsa87::{self, CryptoError, PublicKey, SecretKey, Signature};
+use crate::models::public_bundle::{PublicBundle, SignedPublicBundle};
+use thiserror::Error;
+
+/// Current public bundle document version.
+pub const PUBLIC_BUNDLE_VERSION: u32 = 1;
+
+/// Signature domain, so a bundle signature cannot be passed off as a
+/// signature over anything else.
+const PUBLIC_BUNDLE_DOMAIN: &[u8] = b"ZAP_PUBLIC_BUNDLE_V1";
+
+#[derive(Debug, Error)]
+pub enum PublicBundleError {
+    #[error("malformed public bundle: {0}")]
+    Malformed(String),
+    #[error("unsupported public bundle version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("public bundle signature is invalid")]
+    BadSignature,
+    #[error("signature error: {0}")]
+    Crypto(#[from] CryptoError),
+    #[error(transparent)]
+    Instance(#[from] InstanceError),
+}
+
+fn signed_message(document: &str) -> Vec<u8> {
+    let mut m = PUBLIC_BUNDLE_DOMAIN.to_vec();
+    m.extend_from_slice(document.as_bytes());
+    m
+}
+
+/// Serialize and sign `bundle` with the vault identity and, when given, the
+/// exporting installation's identity.
+pub fn sign_bundle(
+    identity: &SecretKey,
+    instance: Option<&InstanceKey>,
+    bundle: &PublicBundle,
+) -> Result<SignedPublicBundle, PublicBundleError> {
+    let document = serde_json::to_string_pretty(bundle)
+        .map_err(|e| PublicBundleError::Malformed(e.to_string()))?;
+    let sig = mldsa87::sign(identity, &signed_message(&document))?;
+    let instance_signature = instance
+        .map(|key| key.sign(PURPOSE_PUBLIC_BUNDLE, document.as_bytes()))
+        .transpose()?;
+    Ok(SignedPublicBundle {
+        document,
+        signature_hex: sig.to_hex(),
+        instance_signature,
+    })
+}
+
+/// Verify a signed bundle against the vault key it embeds, and its instance
+/// signature if it has one, and return the parsed bundle. This proves the
+/// bundle is intact, not whose it is; compare the fingerprint of
+/// `vault_public_key_hex` out of band for that.
+pub fn verify_bundle(signed: &SignedPublicBundle) -> Result<PublicBundle, PublicBundleError> {
+    let bundle: PublicBundle = serde_json::from_str(&signed.document)
+        .map_err(|e| PublicBundleError::Malformed(e.to_string()))?;
+    if bundle.version != PUBLIC_BUNDLE_VERSION {
+        return Err(PublicBundleError::UnsupportedVersion(bundle.version));
+    }
+    let pk = PublicKey::from_hex(&bundle.vault_public_key_hex)?;
+    let sig =
+        Signature::from_hex(&signed.signature_hex).map_err(|_| PublicBundleError::BadSignature)?;
+    if !mldsa87::verify(&pk, &signed_message(&signed.document), &sig)? {
+        return Err(PublicBundleError::BadSignature);
+    }
+    if let Some(instance_sig) = &signed.instance_signature {
+        instance::verify_instance(
+            instance_sig,
+            PURPOSE_PUBLIC_BUNDLE,
+            signed.document.as_bytes(),
+        )?;
+    }
+    Ok(bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::attestation::vault_identity;
+    use crate::models::key::KeyType;
+    use crate::models::public_bundle::PublicBundleKey;
+    use chrono::Utc;
+
+    fn bundle(vault_pk: &PublicKey) -> PublicBundle {
+        PublicBundle {
+            version: PUBLIC_BUNDLE_VERSION,
+            bundle_id: "bundle-1".to_string(),
+            source_name: "Treasury".to_string(),
+            exported_at: Utc::now(),
+            vault_public_key_hex: vault_pk.to_hex(),
+            keys: vec![PublicBundleKey {
+                key_id: "k1".to_string(),
+                label: Some("Cold".to_string()),
+                key_type: KeyType::Treasury,
+                network: "zap".to_string(),
+                address: "zap1".to_string(),
+                public_key_hex: "ab".to_string(),
+                derivation_path: "m/44'/9999'/3'/0'/0'".to_string(),
+                account: 3,
+                index: 0,
+                tags: Vec::new(),
+                created_at: Utc::now(),
+            }],
+        }
+    }
+
+    #[test]
+    fn signed_bundle_round_trips_and_detects_tampering() {
+        let (pk, sk) = vault_identity(&[5u8; 64]);
+        let (key, _) = instance::generate_instance(&[5u8; 64], "device-1", Utc::now()).unwrap();
+        let doc = bundle(&pk);
+        let signed = sign_bundle(&sk, Some(&key), &doc).unwrap();
+        assert_eq!(verify_bundle(&signed).unwrap(), doc);
+
+        let mut tampered = signed.clone();
+        tampered.document = tampered.document.replace("zap1", "zap9");
+        assert!(matches!(
+            verify_bundle(&tampered),
+            Err(PublicBundleError::BadSignature)
+        ));
+
+        // Re-signing under another key but keeping the embedded vault key fails.
+        let (_, other_sk) = vault_identity(&[6u8; 64]);
+        assert!(verify_bundle(&sign_bundle(&other_sk, None, &doc).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! Recovery codes: one-time codes that reset a forgotten vault password.
+//!
+//! A code is 100 random bits, so unlike a chosen password it needs no
+//! Argon2 stretching. The code key is BLAKE3 over the set's salt and the
+//! normalised code. A hash of that key identifies the code in `vault.json`.
+//!
+//! With escrow, a random recovery key seals the data key, and each code key
+//! seals the recovery key. A password change only re-wraps the data key, so
+//! the codes stay valid. Sets issued before data keys sealed the password
+//! key, which such a vault adopts as its data key, so they still open it.
+
+use crate::crypto::encryption::{self, Ciphertext, EncryptionError};
+use crate::crypto::kdf;
+use crate::models::recovery::{RecoveryCode, RecoveryCodeSet, RecoveryEscrow, RECOVERY_CODE_COUNT};
+use chrono::{DateTime, Utc};
+use rand::{Rng, RngCore};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+const CODE_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 recovery code key v1";
+const CODE_HASH_DOMAIN: &str = "recovery_code_hash";
+/// Crockford base32: no I, L, O or U, so codes read back without mix-ups.
+const CODE_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const CODE_GROUPS: usize = 4;
+const CODE_GROUP_CHARS: usize = 5;
+
+#[derive(Debug, Error)]
+pub enum RecoveryError {
+    #[error("recovery code is not valid or was already used")]
+    UnknownCode,
+    #[error("malformed recovery code record")]
+    Malformed,
+    #[error(transparent)]
+    Encryption(#[from] EncryptionError),
+}
+
+/// Upper case without separators, with the letters Crockford base32 reads
+/// as digits mapped to them.
+fn normalize(code: &str) -> String {
+    code.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        })
+        .collect()
+}
+
+fn code_key(salt: &[u8], code: &str) -> Zeroizing<[u8; 32]> {
+    let mut hasher = blake3::Hasher::new_derive_key(CODE_KEY_CONTEXT);
+    hasher.update(salt);
+    hasher.update(normalize(code).as_bytes());
+    Zeroizing::new(*hasher.finalize().as_bytes())
+}
+
+fn code_hash(key: &[u8; 32]) -> String {
+    hex::encode(kdf::derive_encryption_key(key, CODE_HASH_DOMAIN))
+}
+
+/// Open a 32-byte key sealed with [`encryption::encrypt_vault`].
+fn unseal(key: &[u8; 32], sealed: &Ciphertext) -> Result<Zeroizing<[u8; 32]>, RecoveryError> {
+    let plain = Zeroizing::new(encryption::decrypt_vault(key, sealed)?);
+    let key: [u8; 32] = plain
+        .as_slice()
+        .try_into()
+        .map_err(|_| RecoveryError::Malformed)?;
+    Ok(Zeroizing::new(key))
+}
+
+/// A fresh code, such as `7K2QM-0XH4D-RW9TB-3NFCA`.
+fn generate_code() -> Zeroizing<String> {
+    let mut rng = rand::thread_rng();
+    let groups: Vec<String> = (0..CODE_GROUPS)
+        .map(|_| {
+            (0..CODE_GROUP_CHARS)
+                .map(|_| CODE_ALPHABET[rng.gen_range(0..CODE_ALPHABET.len())] as char)
+                .collect()
+        })
+        .collect();
+    Zeroizing::new(groups.join("-"))
+}
+
+/// Issue a set of [`RECOVERY_CODE_COUNT`] codes. With `data_key`, the
+/// codes also carry an escrow copy of it. Returns the set to keep in
+/// `vault.json` and the codes to show the user once.
+pub fn issue(
+    data_key: Option<&[u8; 32]>,
+    now: DateTime<Utc>,
+) -> Result<(RecoveryCodeSet, Vec<Zeroizing<String>>), RecoveryError> {
+    let recovery_key = data_key.map(|_| {
+        let mut key = Zeroizing::new([0u8; 32]);
+        rand::thread_rng().fill_bytes(key.as_mut());
+        key
+    });
+    let salt = kdf::generate_salt();
+    let mut codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+    let mut plain = Vec::with_capacity(RECOVERY_CODE_COUNT);
+    for _ in 0..RECOVERY_CODE_COUNT {
+        let code = generate_code();
+        let key = code_key(&salt, &code);
+        codes.push(RecoveryCode {
+            id: uuid::Uuid::new_v4().to_string(),
+            hash_hex: code_hash(&key),
+            escrow: recovery_key
+                .as_ref()
+                .map(|rk| encryption::encrypt_vault(&key, rk.as_ref()))
+                .transpose()?,
+            used_at: None,
+        });
+        plain.push(code);
+    }
+    let escrow = match (data_key, &recovery_key) {
+        (Some(dk), Some(rk)) => Some(RecoveryEscrow {
+            data_key: encryption::encrypt_vault(rk, dk)?,
+        }),
+        _ => None,
+    };
+    let set = RecoveryCodeSet {
+        salt_hex: hex::encode(salt),
+        created_at: now,
+        codes,
+        escrow,
+    };
+    Ok((set, plain))
+}
+
+/// A code [`open`] matched.
+pub struct OpenedCode {
+    pub id: String,
+    /// The data key from the set's escrow copy, if it has one.
+    pub data_key: Option<Zeroizing<[u8; 32]>>,
+}
+
+/// Find the unused code `code` in `set` and open the escrow copy of the
+/// data key through it, if the set has one. Separators and case do not
+/// matter.
+pub fn open(set: &RecoveryCodeSet, code: &str) -> Result<OpenedCode, RecoveryError> {
+    let salt = hex::decode(&set.salt_hex).map_err(|_| RecoveryError::Malformed)?;
+    let key = code_key(&salt, code);
+    let found = set
+        .find_unused(&code_hash(&key))
+        .ok_or(RecoveryError::UnknownCode)?;
+    let data_key = match (&found.escrow, &set.escrow) {
+        (Some(sealed), Some(escrow)) => {
+            let recovery_key = unseal(&key, sealed)?;
+            Some(unseal(&recovery_key, &escrow.data_key)?)
+        }
+        _ => None,
+    };
+    Ok(OpenedCode {
+        id: found.id.clone(),
+        data_key,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_key() -> [u8; 32] {
+        let mut key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        key
+    }
+
+    #[test]
+    fn escrowed_code_opens_the_data_key_once() {
+        let dk = random_key();
+        let (mut set, codes) = issue(Some(&dk), Utc::now()).unwrap();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert!(set.escrowed());
+
+        // Read back in lower case without dashes, with an O for a 0.
+        let typed = codes[3].replace('-', "").to_lowercase().replace('0', "o");
+        let opened = open(&set, &typed).unwrap();
+        assert_eq!(*opened.data_key.unwrap(), dk);
+
+        assert!(set.consume(&opened.id, Utc::now()));
+        assert!(matches!(
+            open(&set, &codes[3]),
+            Err(RecoveryError::UnknownCode)
+        ));
+        assert!(open(&set, "AAAAA-AAAAA-AAAAA-AAAAA").is_err());
+    }
+
+    #[test]
+    fn codes_without_escrow_hold_no_key() {
+        let (set, codes) = issue(None, Utc::now()).unwrap();
+        assert!(!set.escrowed());
+        assert!(open(&set, &codes[0]).unwrap().data_key.is_none());
+    }
+}
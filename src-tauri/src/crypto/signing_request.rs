@@ -0,0 +1,392 @@
+use crate::crypto::attestation;
+use crate::crypto::contact::{self, ContactError};
+use crate::crypto::encryption::{self, EncryptionError};
+use crate::crypto::mldsa87::{self, CryptoError, PublicKey, Signature};
+use crate::crypto::mlkem1024::{self, KemCiphertext, KemError};
+use crate::models::contact::ContactCard;
+use crate::models::signing_request::{
+    EnvelopeKind, SigningEnvelope, SigningRequestBody, SigningResponseBody, MAX_REQUEST_TTL_SECS,
+    MIN_REQUEST_TTL_SECS,
+};
+use rand::rngs::OsRng;
+use thiserror::Error;
+use x25519_dalek::{PublicKey as X25519Public, StaticSecret};
+use zeroize::Zeroizing;
+
+/// Current signing envelope format version.
+pub const SIGNING_ENVELOPE_VERSION: u32 = 1;
+/// Tolerate this much clock skew between the two machines (seconds).
+pub const MAX_CLOCK_SKEW_SECS: u64 = 5 * 60;
+
+/// Signature domain for the sender's signature over an envelope.
+const SIGNING_ENVELOPE_DOMAIN: &[u8] = b"ZAP_SIGNING_ENVELOPE_V1";
+/// BLAKE3 `derive_key` context combining the two shared secrets.
+const PAYLOAD_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 signing envelope payload key v1";
+
+#[derive(Debug, Error)]
+pub enum SigningEnvelopeError {
+    #[error("malformed signing envelope: {0}")]
+    Malformed(String),
+    #[error("unsupported signing envelope version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("signing envelope signature is invalid")]
+    BadSignature,
+    #[error("signing envelope is addressed to another vault")]
+    WrongRecipient,
+    #[error("signing envelope has expired")]
+    Expired,
+    #[error("expected a signing {0}")]
+    WrongKind(&'static str),
+    #[error("contact card error: {0}")]
+    Contact(#[from] ContactError),
+    #[error("KEM error: {0}")]
+    Kem(#[from] KemError),
+    #[error("encryption error: {0}")]
+    Encryption(#[from] EncryptionError),
+    #[error("signature error: {0}")]
+    Crypto(#[from] CryptoError),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, SigningEnvelopeError> {
+    hex::decode(value).map_err(|e| SigningEnvelopeError::Malformed(format!("{field}: {e}")))
+}
+
+fn push_field(m: &mut Vec<u8>, field: &[u8]) {
+    m.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    m.extend_from_slice(field);
+}
+
+/// AEAD key from both shared secrets, bound to the ciphertexts that produced
+/// them.
+fn payload_key(
+    x25519_shared: &[u8],
+    kem_shared: &[u8],
+    ephemeral: &[u8],
+    kem_ciphertext: &[u8],
+) -> Zeroizing<[u8; 32]> {
+    let mut hasher = blake3::Hasher::new_derive_key(PAYLOAD_KEY_CONTEXT);
+    for field in [x25519_shared, kem_shared, ephemeral, kem_ciphertext] {
+        hasher.update(&(field.len() as u32).to_le_bytes());
+        hasher.update(field);
+    }
+    Zeroizing::new(*hasher.finalize().as_bytes())
+}
+
+/// The bytes the sender signs: every envelope field, so none can be swapped
+/// or replayed into another envelope.
+fn envelope_message(envelope: &SigningEnvelope) -> Result<Vec<u8>, SigningEnvelopeError> {
+    let mut m = SIGNING_ENVELOPE_DOMAIN.to_vec();
+    m.extend_from_slice(&envelope.version.to_le_bytes());
+    m.push(envelope.kind.tag());
+    push_field(&mut m, envelope.envelope_id.as_bytes());
+    push_field(
+        &mut m,
+        envelope
+            .in_reply_to
+            .as_deref()
+            .unwrap_or_default()
+            .as_bytes(),
+    );
+    push_field(&mut m, envelope.sender.sign_public_hex.as_bytes());
+    push_field(&mut m, envelope.recipient_fingerprint.as_bytes());
+    m.extend_from_slice(&envelope.created_at.to_le_bytes());
+    m.extend_from_slice(&envelope.expires_at.to_le_bytes());
+    push_field(
+        &mut m,
+        &decode_hex("x25519_ephemeral_hex", &envelope.x25519_ephemeral_hex)?,
+    );
+    push_field(
+        &mut m,
+        &decode_hex("kem_ciphertext_hex", &envelope.kem_ciphertext_hex)?,
+    );
+    push_field(&mut m, &envelope.payload.nonce);
+    push_field(&mut m, &envelope.payload.ciphertext);
+    Ok(m)
+}
+
+/// Seal `body` to `recipient`, signed with the vault identity behind
+/// `sender`, valid for `ttl_secs` from `now`.
+#[allow(clippy::too_many_arguments)]
+fn seal(
+    master_seed: &[u8; 64],
+    sender: &ContactCard,
+    recipient: &ContactCard,
+    kind: EnvelopeKind,
+    in_reply_to: Option<String>,
+    body: &[u8],
+    now: u64,
+    ttl_secs: u64,
+) -> Result<SigningEnvelope, SigningEnvelopeError> {
+    contact::verify_card(recipient)?;
+    let recipient_x25519: [u8; 32] = decode_hex("x25519_public_hex", &recipient.x25519_public_hex)?
+        .try_into()
+        .map_err(|_| SigningEnvelopeError::Malformed("x25519_public_hex".to_string()))?;
+    let ephemeral = StaticSecret::random_from_rng(OsRng);
+    let ephemeral_public = X25519Public::from(&ephemeral);
+    let x25519_shared = ephemeral.diffie_hellman(&X25519Public::from(recipient_x25519));
+    let (kem_ct, kem_shared) =
+        mlkem1024::encapsulate_to(&decode_hex("kem_public_hex", &recipient.kem_public_hex)?)?;
+    let kem_shared = Zeroizing::new(kem_shared);
+    let key = payload_key(
+        x25519_shared.as_bytes(),
+        kem_shared.as_ref(),
+        ephemeral_public.as_bytes(),
+        &kem_ct.ciphertext,
+    );
+
+    let mut envelope = SigningEnvelope {
+        version: SIGNING_ENVELOPE_VERSION,
+        kind,
+        envelope_id: uuid::Uuid::new_v4().to_string(),
+        in_reply_to,
+        sender: sender.clone(),
+        recipient_fingerprint: contact::fingerprint(recipient)?.hex,
+        created_at: now,
+        expires_at: now + ttl_secs,
+        x25519_ephemeral_hex: hex::encode(ephemeral_public.as_bytes()),
+        kem_ciphertext_hex: hex::encode(&kem_ct.ciphertext),
+        payload: encryption::encrypt_aead(&key, body)?,
+        signature_hex: String::new(),
+    };
+    let (_, identity) = attestation::vault_identity(master_seed);
+    envelope.signature_hex = mldsa87::sign(&identity, &envelope_message(&envelope)?)?.to_hex();
+    Ok(envelope)
+}
+
+/// Check an envelope's version, sender card, signature and lifetime. Needs
+/// no secrets.
+pub fn verify_envelope(envelope: &SigningEnvelope, now: u64) -> Result<(), SigningEnvelopeError> {
+    if envelope.version != SIGNING_ENVELOPE_VERSION {
+        return Err(SigningEnvelopeError::UnsupportedVersion(envelope.version));
+    }
+    contact::verify_card(&envelope.sender)?;
+    let pk = PublicKey::from_hex(&envelope.sender.sign_public_hex)?;
+    let sig = Signature::from_hex(&envelope.signature_hex)
+        .map_err(|_| SigningEnvelopeError::BadSignature)?;
+    if !mldsa87::verify(&pk, &envelope_message(envelope)?, &sig)? {
+        return Err(SigningEnvelopeError::BadSignature);
+    }
+    if envelope.expires_at <= envelope.created_at
+        || envelope.expires_at - envelope.created_at > MAX_REQUEST_TTL_SECS
+        || envelope.created_at > now + MAX_CLOCK_SKEW_SECS
+    {
+        return Err(SigningEnvelopeError::Malformed(
+            "envelope lifetime is out of range".to_string(),
+        ));
+    }
+    if now > envelope.expires_at {
+        return Err(SigningEnvelopeError::Expired);
+    }
+    Ok(())
+}
+
+/// Verify an envelope addressed to this vault and decrypt its body.
+fn open(
+    master_seed: &[u8; 64],
+    envelope: &SigningEnvelope,
+    now: u64,
+) -> Result<Zeroizing<Vec<u8>>, SigningEnvelopeError> {
+    verify_envelope(envelope, now)?;
+    if envelope.recipient_fingerprint != contact::own_fingerprint(master_seed).hex {
+        return Err(SigningEnvelopeError::WrongRecipient);
+    }
+    let ephemeral: [u8; 32] = decode_hex("x25519_ephemeral_hex", &envelope.x25519_ephemeral_hex)?
+        .try_into()
+        .map_err(|_| SigningEnvelopeError::Malformed("x25519_ephemeral_hex".to_string()))?;
+    let x25519_shared =
+        contact::x25519_identity(master_seed).diffie_hellman(&X25519Public::from(ephemeral));
+    let kem_ct = KemCiphertext {
+        ciphertext: decode_hex("kem_ciphertext_hex", &envelope.kem_ciphertext_hex)?,
+        encapsulated_key: Vec::new(),
+    };
+    let kem_shared = Zeroizing::new(contact::kem_identity(master_seed).decapsulate(&kem_ct)?);
+    let key = payload_key(
+        x25519_shared.as_bytes(),
+        kem_shared.as_ref(),
+        &ephemeral,
+        &kem_ct.ciphertext,
+    );
+    Ok(Zeroizing::new(encryption::decrypt_aead(
+        &key,
+        &envelope.payload,
+    )?))
+}
+
+/// Seal a signing request to `recipient`.
+pub fn seal_request(
+    master_seed: &[u8; 64],
+    sender: &ContactCard,
+    recipient: &ContactCard,
+    body: &SigningRequestBody,
+    now: u64,
+    ttl_secs: u64,
+) -> Result<SigningEnvelope, SigningEnvelopeError> {
+    if !(MIN_REQUEST_TTL_SECS..=MAX_REQUEST_TTL_SECS).contains(&ttl_secs) {
+        return Err(SigningEnvelopeError::Malformed(format!(
+            "request lifetime must be between {MIN_REQUEST_TTL_SECS} and {MAX_REQUEST_TTL_SECS} seconds"
+        )));
+    }
+    decode_hex("payload_hex", &body.payload_hex)?;
+    let json = Zeroizing::new(serde_json::to_vec(body)?);
+    seal(
+        master_seed,
+        sender,
+        recipient,
+        EnvelopeKind::Request,
+        None,
+        &json,
+        now,
+        ttl_secs,
+    )
+}
+
+/// Open a signing request addressed to this vault.
+pub fn open_request(
+    master_seed: &[u8; 64],
+    envelope: &SigningEnvelope,
+    now: u64,
+) -> Result<SigningRequestBody, SigningEnvelopeError> {
+    if envelope.kind != EnvelopeKind::Request || envelope.in_reply_to.is_some() {
+        return Err(SigningEnvelopeError::WrongKind("request"));
+    }
+    let json = open(master_seed, envelope, now)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// Seal the response to request `request_id` back to `requester`. It
+/// expires with the request, so an answer can never outlive the question.
+pub fn seal_response(
+    master_seed: &[u8; 64],
+    sender: &ContactCard,
+    requester: &ContactCard,
+    request_id: &str,
+    request_expires_at: u64,
+    body: &SigningResponseBody,
+    now: u64,
+) -> Result<SigningEnvelope, SigningEnvelopeError> {
+    if now >= request_expires_at {
+        return Err(SigningEnvelopeError::Expired);
+    }
+    let json = Zeroizing::new(serde_json::to_vec(body)?);
+    seal(
+        master_seed,
+        sender,
+        requester,
+        EnvelopeKind::Response,
+        Some(request_id.to_string()),
+        &json,
+        now,
+        request_expires_at - now,
+    )
+}
+
+/// Open a response addressed to this vault. The caller must check that
+/// `in_reply_to` names a request it sent to this sender and has not seen
+/// answered.
+pub fn open_response(
+    master_seed: &[u8; 64],
+    envelope: &SigningEnvelope,
+    now: u64,
+) -> Result<SigningResponseBody, SigningEnvelopeError> {
+    if envelope.kind != EnvelopeKind::Response || envelope.in_reply_to.is_none() {
+        return Err(SigningEnvelopeError::WrongKind("response"));
+    }
+    let json = open(master_seed, envelope, now)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::signing_request::SigningPayloadKind;
+
+    const ONLINE: [u8; 64] = [41u8; 64];
+    const OFFLINE: [u8; 64] = [42u8; 64];
+    const NOW: u64 = 1_700_000_000;
+
+    fn cards() -> (ContactCard, ContactCard) {
+        (
+            contact::issue_card(&ONLINE, "Online", NOW).unwrap(),
+            contact::issue_card(&OFFLINE, "Offline", NOW).unwrap(),
+        )
+    }
+
+    fn body() -> SigningRequestBody {
+        SigningRequestBody {
+            kind: SigningPayloadKind::Psbt,
+            payload_hex: hex::encode(b"psbt\xff"),
+            description: "Pay the auditor".to_string(),
+            key_id: None,
+        }
+    }
+
+    #[test]
+    fn request_and_response_round_trip() {
+        let (online, offline) = cards();
+        let request = seal_request(&ONLINE, &online, &offline, &body(), NOW, 3600).unwrap();
+        assert_eq!(open_request(&OFFLINE, &request, NOW + 10).unwrap(), body());
+
+        let answer = SigningResponseBody {
+            approved: true,
+            reason: None,
+            public_key_hex: Some("ab".to_string()),
+            result_hex: Some("cd".to_string()),
+        };
+        let response = seal_response(
+            &OFFLINE,
+            &offline,
+            &request.sender,
+            &request.envelope_id,
+            request.expires_at,
+            &answer,
+            NOW + 20,
+        )
+        .unwrap();
+        assert_eq!(
+            response.in_reply_to.as_deref(),
+            Some(request.envelope_id.as_str())
+        );
+        assert_eq!(response.expires_at, request.expires_at);
+        assert_eq!(open_response(&ONLINE, &response, NOW + 30).unwrap(), answer);
+    }
+
+    #[test]
+    fn only_the_recipient_opens_and_only_in_time() {
+        let (online, offline) = cards();
+        let request = seal_request(&ONLINE, &online, &offline, &body(), NOW, 3600).unwrap();
+        assert!(matches!(
+            open_request(&ONLINE, &request, NOW),
+            Err(SigningEnvelopeError::WrongRecipient)
+        ));
+        assert!(matches!(
+            open_request(&OFFLINE, &request, NOW + 3601),
+            Err(SigningEnvelopeError::Expired)
+        ));
+        assert!(matches!(
+            open_response(&OFFLINE, &request, NOW),
+            Err(SigningEnvelopeError::WrongKind("response"))
+        ));
+    }
+
+    #[test]
+    fn tampering_breaks_the_signature() {
+        let (online, offline) = cards();
+        let request = seal_request(&ONLINE, &online, &offline, &body(), NOW, 3600).unwrap();
+
+        let mut longer = request.clone();
+        longer.expires_at += 86_400;
+        assert!(matches!(
+            open_request(&OFFLINE, &longer, NOW),
+            Err(SigningEnvelopeError::BadSignature)
+        ));
+
+        let mut renamed = request;
+        renamed.envelope_id = uuid::Uuid::new_v4().to_string();
+        assert!(matches!(
+            open_request(&OFFLINE, &renamed, NOW),
+            Err(SigningEnvelopeError::BadSignature)
+        ));
+    }
+}
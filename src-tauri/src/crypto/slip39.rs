@@ -0,0 +1,788 @@
+//! SLIP-39 Shamir backups of the master seed.
+//!
+//! Follows SatoshiLabs' SLIP-39. The master secret is encrypted with a
+//! four-round Feistel network keyed by PBKDF2-HMAC-SHA256 over the
+//! passphrase, then split twice with Shamir's scheme over GF(256): into
+//! groups, and each group share into member shares. A share is a mnemonic
+//! from the 1024-word SLIP-39 list, ending in an RS1024 checksum, so any
+//! SLIP-39 implementation reads shares made here and the other way round.
+//!
+//! New shares are extendable, as the current specification makes them:
+//! the backup identifier is not part of the Feistel salt.
+
+use hmac::{Hmac, Mac};
+use pbkdf2::pbkdf2_hmac;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// The SLIP-39 wordlist, sorted, one word per line. Every word is told
+/// apart by its first four letters.
+const WORDLIST: &str = include_str!("slip39_wordlist.txt");
+
+const RADIX_BITS: usize = 10;
+const ID_BITS: usize = 15;
+const ITERATION_EXP_BITS: usize = 4;
+/// Identifier, extendable flag and iteration exponent; then the group and
+/// member fields, four bits each.
+const METADATA_WORDS: usize = 4;
+const CHECKSUM_WORDS: usize = 3;
+const CUSTOMIZATION: &[u8] = b"shamir";
+const CUSTOMIZATION_EXTENDABLE: &[u8] = b"shamir_extendable";
+const MIN_SECRET_BYTES: usize = 16;
+const MIN_MNEMONIC_WORDS: usize =
+    METADATA_WORDS + (MIN_SECRET_BYTES * 8).div_ceil(RADIX_BITS) + CHECKSUM_WORDS;
+const MAX_SHARES: usize = 16;
+const BASE_ITERATIONS: u32 = 10_000;
+const ROUNDS: u8 = 4;
+const SECRET_INDEX: u8 = 255;
+const DIGEST_INDEX: u8 = 254;
+const DIGEST_BYTES: usize = 4;
+
+/// Iteration exponent of new backups: 20,000 PBKDF2 iterations in all, the
+/// reference implementation's default.
+pub const DEFAULT_ITERATION_EXPONENT: u8 = 1;
+
+#[derive(Debug, Error)]
+pub enum Slip39Error {
+    #[error("{0:?} is not a SLIP-39 word")]
+    UnknownWord(String),
+    #[error("share checksum does not match; a word is wrong")]
+    InvalidChecksum,
+    #[error("malformed share: {0}")]
+    Malformed(String),
+    #[error("shares belong to different backups")]
+    Mismatch,
+    #[error("not enough shares: {0}")]
+    NotEnoughShares(String),
+    #[error("shares do not combine; one of them is wrong")]
+    DigestMismatch,
+    #[error("invalid sharing scheme: {0}")]
+    InvalidScheme(String),
+    #[error("the passphrase may only hold printable ASCII")]
+    InvalidPassphrase,
+}
+
+/// One group of a backup: `member_threshold` of its `member_count` shares
+/// recover the group's share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Slip39Group {
+    pub member_threshold: u8,
+    pub member_count: u8,
+}
+
+fn words() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| WORDLIST.lines().map(str::trim).collect())
+}
+
+fn word_index(word: &str) -> Result<u16, Slip39Error> {
+    let word = word.to_lowercase();
+    words()
+        .binary_search(&word.as_str())
+        .map(|i| i as u16)
+        .map_err(|_| Slip39Error::UnknownWord(word))
+}
+
+/// The RS1024 checksum state after `values`.
+fn polymod(values: impl IntoIterator<Item = u32>) -> u32 {
+    const GEN: [u32; 10] = [
+        0x00E0_E040,
+        0x01C1_C080,
+        0x0383_8100,
+        0x0707_0200,
+        0x0E0E_0009,
+        0x1C0C_2412,
+        0x3808_6C24,
+        0x3090_FC48,
+        0x21B1_F890,
+        0x03F3_F120,
+    ];
+    let mut chk = 1u32;
+    for v in values {
+        let b = chk >> 20;
+        chk = ((chk & 0xF_FFFF) << 10) ^ v;
+        for (i, g) in GEN.iter().enumerate() {
+            if (b >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn customization(extendable: bool) -> &'static [u8] {
+    if extendable {
+        CUSTOMIZATION_EXTENDABLE
+    } else {
+        CUSTOMIZATION
+    }
+}
+
+fn checksum(data: &[u16], extendable: bool) -> [u16; CHECKSUM_WORDS] {
+    let values = customization(extendable)
+        .iter()
+        .map(|&b| u32::from(b))
+        .chain(data.iter().map(|&w| u32::from(w)))
+        .chain([0; CHECKSUM_WORDS]);
+    let chk = polymod(values) ^ 1;
+    std::array::from_fn(|i| ((chk >> (RADIX_BITS * (CHECKSUM_WORDS - 1 - i))) & 1023) as u16)
+}
+
+fn checksum_valid(data: &[u16], extendable: bool) -> bool {
+    let values = customization(extendable)
+        .iter()
+        .map(|&b| u32::from(b))
+        .chain(data.iter().map(|&w| u32::from(w)));
+    polymod(values) == 1
+}
+
+/// Exponent and logarithm tables of GF(256) modulo x^8 + x^4 + x^3 + x + 1,
+/// the field SLIP-39 shares Shamir's scheme over.
+const fn gf_tables() -> ([u8; 255], [u8; 256]) {
+    let mut exp = [0u8; 255];
+    let mut log = [0u8; 256];
+    let mut poly: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = poly as u8;
+        log[poly as usize] = i as u8;
+        // Multiply by the generator x + 1.
+        poly ^= poly << 1;
+        if poly & 0x100 != 0 {
+            poly ^= 0x11B;
+        }
+        i += 1;
+    }
+    (exp, log)
+}
+
+const GF: ([u8; 255], [u8; 256]) = gf_tables();
+
+fn gf_log(x: u8) -> usize {
+    GF.1[x as usize] as usize
+}
+
+/// The value at `x` of the polynomial through `shares`, whose x coordinates
+/// must be distinct and whose values must be as long as each other.
+fn interpolate(shares: &[(u8, &[u8])], x: u8) -> Result<Zeroizing<Vec<u8>>, Slip39Error> {
+    if let Some((_, value)) = shares.iter().find(|(xi, _)| *xi == x) {
+        return Ok(Zeroizing::new(value.to_vec()));
+    }
+    let len = shares[0].1.len();
+    if shares.iter().any(|(_, v)| v.len() != len) {
+        return Err(Slip39Error::Malformed(
+            "shares are of different lengths".to_string(),
+        ));
+    }
+    let log_prod: usize = shares.iter().map(|(xi, _)| gf_log(xi ^ x)).sum();
+    let mut result = Zeroizing::new(vec![0u8; len]);
+    for (i, (xi, value)) in shares.iter().enumerate() {
+        let others: usize = shares
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, (xj, _))| gf_log(xi ^ xj))
+            .sum();
+        let log_basis = (log_prod + 255 * shares.len() - gf_log(xi ^ x) - others) % 255;
+        for (out, &v) in result.iter_mut().zip(value.iter()) {
+            if v != 0 {
+                *out ^= GF.0[(gf_log(v) + log_basis) % 255];
+            }
+        }
+    }
+    Ok(result)
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+/// A share's x coordinate and value.
+type SecretShare = (u8, Zeroizing<Vec<u8>>);
+
+/// Split `secret` so that any `threshold` of `count` shares recover it. The
+/// shares are numbered from 0.
+fn split_secret(threshold: u8, count: u8, secret: &[u8]) -> Result<Vec<SecretShare>, Slip39Error> {
+    if threshold == 0 || threshold > count || usize::from(count) > MAX_SHARES {
+        return Err(Slip39Error::InvalidScheme(format!(
+            "{threshold} of {count} shares"
+        )));
+    }
+    if threshold == 1 {
+        return Ok((0..count)
+            .map(|i| (i, Zeroizing::new(secret.to_vec())))
+            .collect());
+    }
+    let mut rng = rand::thread_rng();
+    let mut random_part = Zeroizing::new(vec![0u8; secret.len() - DIGEST_BYTES]);
+    rng.fill_bytes(&mut random_part);
+    let mut digest = Zeroizing::new(hmac_sha256(&random_part, secret)[..DIGEST_BYTES].to_vec());
+    digest.extend_from_slice(&random_part);
+
+    let mut shares: Vec<SecretShare> = (0..threshold - 2)
+        .map(|i| {
+            let mut value = Zeroizing::new(vec![0u8; secret.len()]);
+            rng.fill_bytes(&mut value);
+            (i, value)
+        })
+        .collect();
+    let base: Vec<(u8, &[u8])> = shares
+        .iter()
+        .map(|(x, v)| (*x, v.as_slice()))
+        .chain([(DIGEST_INDEX, digest.as_slice()), (SECRET_INDEX, secret)])
+        .collect();
+    let rest = (threshold - 2..count)
+        .map(|i| Ok((i, interpolate(&base, i)?)))
+        .collect::<Result<Vec<_>, Slip39Error>>()?;
+    shares.extend(rest);
+    Ok(shares)
+}
+
+fn recover_secret(
+    threshold: u8,
+    shares: &[(u8, &[u8])],
+) -> Result<Zeroizing<Vec<u8>>, Slip39Error> {
+    if threshold == 1 {
+        return Ok(Zeroizing::new(shares[0].1.to_vec()));
+    }
+    let secret = interpolate(shares, SECRET_INDEX)?;
+    let digest = interpolate(shares, DIGEST_INDEX)?;
+    if digest.len() < DIGEST_BYTES
+        || hmac_sha256(&digest[DIGEST_BYTES..], &secret)[..DIGEST_BYTES] != digest[..DIGEST_BYTES]
+    {
+        return Err(Slip39Error::DigestMismatch);
+    }
+    Ok(secret)
+}
+
+fn feistel_salt(identifier: u16, extendable: bool) -> Vec<u8> {
+    if extendable {
+        Vec::new()
+    } else {
+        [CUSTOMIZATION, &identifier.to_be_bytes()].concat()
+    }
+}
+
+fn feistel(
+    input: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    salt: &[u8],
+    rounds: impl Iterator<Item = u8>,
+) -> Zeroizing<Vec<u8>> {
+    let half = input.len() / 2;
+    let mut left = Zeroizing::new(input[..half].to_vec());
+    let mut right = Zeroizing::new(input[half..].to_vec());
+    let iterations = (BASE_ITERATIONS / u32::from(ROUNDS)) << iteration_exponent;
+    for round in rounds {
+        let password = Zeroizing::new([&[round], passphrase].concat());
+        let mut f = Zeroizing::new(vec![0u8; right.len()]);
+        pbkdf2_hmac::<Sha256>(&password, &[salt, &right].concat(), iterations, &mut f);
+        let mixed = Zeroizing::new(left.iter().zip(f.iter()).map(|(l, f)| l ^ f).collect());
+        left = std::mem::replace(&mut right, mixed);
+    }
+    let mut out = right;
+    out.extend_from_slice(&left);
+    out
+}
+
+fn encrypt(
+    secret: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+) -> Zeroizing<Vec<u8>> {
+    let salt = feistel_salt(identifier, extendable);
+    feistel(secret, passphrase, iteration_exponent, &salt, 0..ROUNDS)
+}
+
+fn decrypt(
+    encrypted: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+) -> Zeroizing<Vec<u8>> {
+    let salt = feistel_salt(identifier, extendable);
+    feistel(
+        encrypted,
+        passphrase,
+        iteration_exponent,
+        &salt,
+        (0..ROUNDS).rev(),
+    )
+}
+
+struct Share {
+    identifier: u16,
+    extendable: bool,
+    iteration_exponent: u8,
+    group_index: u8,
+    group_threshold: u8,
+    group_count: u8,
+    member_index: u8,
+    member_threshold: u8,
+    value: Zeroizing<Vec<u8>>,
+}
+
+impl Share {
+    /// Fields every share of one backup has in common.
+    fn backup(&self) -> (u16, bool, u8, u8, u8, usize) {
+        (
+            self.identifier,
+            self.extendable,
+            self.iteration_exponent,
+            self.group_threshold,
+            self.group_count,
+            self.value.len(),
+        )
+    }
+
+    fn to_mnemonic(&self) -> Zeroizing<String> {
+        let mut bits = BitWriter::default();
+        bits.push(u32::from(self.identifier), ID_BITS);
+        bits.push(u32::from(self.extendable), 1);
+        bits.push(u32::from(self.iteration_exponent), ITERATION_EXP_BITS);
+        for field in [
+            self.group_index,
+            self.group_threshold - 1,
+            self.group_count - 1,
+            self.member_index,
+            self.member_threshold - 1,
+        ] {
+            bits.push(u32::from(field), 4);
+        }
+        let value_words = (self.value.len() * 8).div_ceil(RADIX_BITS);
+        bits.push(0, value_words * RADIX_BITS - self.value.len() * 8);
+        for &b in self.value.iter() {
+            bits.push(u32::from(b), 8);
+        }
+        let mut data = bits.words;
+        data.extend(checksum(&data, self.extendable));
+        let list = words();
+        Zeroizing::new(
+            data.iter()
+                .map(|&w| list[usize::from(w)])
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+
+    fn from_mnemonic(mnemonic: &str) -> Result<Self, Slip39Error> {
+        let data = Zeroizing::new(
+            mnemonic
+                .split_whitespace()
+                .map(word_index)
+                .collect::<Result<Vec<u16>, _>>()?,
+        );
+        if data.len() < MIN_MNEMONIC_WORDS {
+            return Err(Slip39Error::Malformed(format!(
+                "a share has at least {MIN_MNEMONIC_WORDS} words, this one {}",
+                data.len()
+            )));
+        }
+        let padding = (RADIX_BITS * (data.len() - METADATA_WORDS - CHECKSUM_WORDS)) % 16;
+        if padding > 8 {
+            return Err(Slip39Error::Malformed(format!(
+                "{} words is not a share length",
+                data.len()
+            )));
+        }
+        let extendable = (data[1] >> 4) & 1 == 1;
+        if !checksum_valid(&data, extendable) {
+            return Err(Slip39Error::InvalidChecksum);
+        }
+
+        let mut bits = BitReader::new(&data[..data.len() - CHECKSUM_WORDS]);
+        let identifier = bits.take(ID_BITS) as u16;
+        bits.take(1);
+        let iteration_exponent = bits.take(ITERATION_EXP_BITS) as u8;
+        let mut field = || bits.take(4) as u8;
+        let (group_index, group_threshold, group_count) = (field(), field() + 1, field() + 1);
+        let (member_index, member_threshold) = (field(), field() + 1);
+        if group_threshold > group_count {
+            return Err(Slip39Error::Malformed(format!(
+                "group threshold {group_threshold} exceeds the {group_count} groups"
+            )));
+        }
+        if bits.take(padding) != 0 {
+            return Err(Slip39Error::Malformed("padding is not zero".to_string()));
+        }
+        let value = Zeroizing::new(
+            (0..bits.remaining() / 8)
+                .map(|_| bits.take(8) as u8)
+                .collect::<Vec<u8>>(),
+        );
+        Ok(Share {
+            identifier,
+            extendable,
+            iteration_exponent,
+            group_index,
+            group_threshold,
+            group_count,
+            member_index,
+            member_threshold,
+            value,
+        })
+    }
+}
+
+/// Packs fields into 10-bit words, most significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    words: Vec<u16>,
+    acc: u32,
+    len: usize,
+}
+
+impl BitWriter {
+    fn push(&mut self, value: u32, bits: usize) {
+        for i in (0..bits).rev() {
+            self.acc = (self.acc << 1) | ((value >> i) & 1);
+            self.len += 1;
+            if self.len == RADIX_BITS {
+                self.words.push(self.acc as u16);
+                self.acc = 0;
+                self.len = 0;
+            }
+        }
+    }
+}
+
+struct BitReader<'a> {
+    words: &'a [u16],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(words: &'a [u16]) -> Self {
+        Self { words, pos: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.words.len() * RADIX_BITS - self.pos
+    }
+
+    fn take(&mut self, bits: usize) -> u32 {
+        let mut value = 0u32;
+        for _ in 0..bits {
+            let word = self.words[self.pos / RADIX_BITS];
+            let bit = (word >> (RADIX_BITS - 1 - self.pos % RADIX_BITS)) & 1;
+            value = (value << 1) | u32::from(bit);
+            self.pos += 1;
+        }
+        value
+    }
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), Slip39Error> {
+    if passphrase.bytes().all(|b| (32..=126).contains(&b)) {
+        Ok(())
+    } else {
+        Err(Slip39Error::InvalidPassphrase)
+    }
+}
+
+/// Split `master_secret` into SLIP-39 shares. Any `group_threshold` of the
+/// `groups`, each with its member threshold of shares, recover it. Returns
+/// each group's mnemonics in order.
+pub fn generate_mnemonics(
+    group_threshold: u8,
+    groups: &[Slip39Group],
+    master_secret: &[u8],
+    passphrase: &str,
+    iteration_exponent: u8,
+) -> Result<Vec<Vec<Zeroizing<String>>>, Slip39Error> {
+    if master_secret.len() < MIN_SECRET_BYTES || master_secret.len() % 2 != 0 {
+        return Err(Slip39Error::InvalidScheme(format!(
+            "a {}-byte master secret; it needs at least {MIN_SECRET_BYTES} bytes, an even number",
+            master_secret.len()
+        )));
+    }
+    check_passphrase(passphrase)?;
+    if usize::from(iteration_exponent) >= 1 << ITERATION_EXP_BITS {
+        return Err(Slip39Error::InvalidScheme(format!(
+            "iteration exponent {iteration_exponent}"
+        )));
+    }
+    if groups.len() > MAX_SHARES {
+        return Err(Slip39Error::InvalidScheme(format!(
+            "{} groups; at most {MAX_SHARES}",
+            groups.len()
+        )));
+    }
+    if let Some(g) = groups
+        .iter()
+        .find(|g| g.member_threshold == 1 && g.member_count > 1)
+    {
+        return Err(Slip39Error::InvalidScheme(format!(
+            "1 of {} members; use a 1-of-1 group instead",
+            g.member_count
+        )));
+    }
+
+    let identifier = rand::thread_rng().gen_range(0..1u16 << ID_BITS);
+    let extendable = true;
+    let encrypted = encrypt(
+        master_secret,
+        passphrase.as_bytes(),
+        iteration_exponent,
+        identifier,
+        extendable,
+    );
+    let group_shares = split_secret(group_threshold, groups.len() as u8, &encrypted)?;
+    groups
+        .iter()
+        .zip(group_shares)
+        .map(|(group, (group_index, group_secret))| {
+            let members = split_secret(group.member_threshold, group.member_count, &group_secret)?;
+            Ok(members
+                .into_iter()
+                .map(|(member_index, value)| {
+                    Share {
+                        identifier,
+                        extendable,
+                        iteration_exponent,
+                        group_index,
+                        group_threshold,
+                        group_count: groups.len() as u8,
+                        member_index,
+                        member_threshold: group.member_threshold,
+                        value,
+                    }
+                    .to_mnemonic()
+                })
+                .collect())
+        })
+        .collect()
+}
+
+/// Recover the master secret from enough `mnemonics` of one backup, in any
+/// order. A wrong passphrase is not detected: it yields another secret.
+pub fn combine_mnemonics(
+    mnemonics: &[String],
+    passphrase: &str,
+) -> Result<Zeroizing<Vec<u8>>, Slip39Error> {
+    check_passphrase(passphrase)?;
+    let shares = mnemonics
+        .iter()
+        .filter(|m| !m.trim().is_empty())
+        .map(|m| Share::from_mnemonic(m))
+        .collect::<Result<Vec<_>, _>>()?;
+    let first = shares
+        .first()
+        .ok_or_else(|| Slip39Error::NotEnoughShares("no shares given".to_string()))?;
+    if shares.iter().any(|s| s.backup() != first.backup()) {
+        return Err(Slip39Error::Mismatch);
+    }
+
+    let mut groups: BTreeMap<u8, Vec<&Share>> = BTreeMap::new();
+    for share in &shares {
+        groups.entry(share.group_index).or_default().push(share);
+    }
+    let mut group_shares = Vec::new();
+    for (index, members) in &groups {
+        let threshold = members[0].member_threshold;
+        if members.iter().any(|m| m.member_threshold != threshold) {
+            return Err(Slip39Error::Mismatch);
+        }
+        let mut points: Vec<(u8, &[u8])> = Vec::new();
+        for m in members {
+            match points.iter().find(|(x, _)| *x == m.member_index) {
+                Some((_, v)) if *v != m.value.as_slice() => return Err(Slip39Error::Mismatch),
+                Some(_) => {}
+                None => points.push((m.member_index, m.value.as_slice())),
+            }
+        }
+        if points.len() >= usize::from(threshold) {
+            points.truncate(threshold.into());
+            group_shares.push((*index, recover_secret(threshold, &points)?));
+        }
+    }
+    if group_shares.len() < usize::from(first.group_threshold) {
+        return Err(Slip39Error::NotEnoughShares(format!(
+            "{} of the {} groups needed are complete",
+            group_shares.len(),
+            first.group_threshold
+        )));
+    }
+    let points: Vec<(u8, &[u8])> = group_shares
+        .iter()
+        .take(first.group_threshold.into())
+        .map(|(x, v)| (*x, v.as_slice()))
+        .collect();
+    let encrypted = recover_secret(first.group_threshold, &points)?;
+    Ok(decrypt(
+        &encrypted,
+        passphrase.as_bytes(),
+        first.iteration_exponent,
+        first.identifier,
+        first.extendable,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(mnemonics: &[&str]) -> Vec<String> {
+        mnemonics.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn wordlist_is_sorted_with_unique_prefixes() {
+        let list = words();
+        assert_eq!(list.len(), 1024);
+        assert!(list.windows(2).all(|w| w[0] < w[1]));
+        assert!(list.iter().all(|w| (4..=8).contains(&w.len())));
+        let prefixes: std::collections::HashSet<&str> = list.iter().map(|w| &w[..4]).collect();
+        assert_eq!(prefixes.len(), 1024);
+        assert_eq!((list[0], list[1023]), ("academic", "zero"));
+    }
+
+    #[test]
+    fn official_non_extendable_vectors_recover() {
+        // Non-extendable shares from the SLIP-39 test vectors, passphrase
+        // "TREZOR".
+        let single = strings(&[
+            "duckling enlarge academic academic agency result length solution fridge kidney \
+             coal piece deal husband erode duke ajar critical decision keyboard",
+        ]);
+        assert_eq!(
+            hex::encode(combine_mnemonics(&single, "TREZOR").unwrap()),
+            "bb54aac4b89dc868ba37d9cc21b2cece"
+        );
+        let two_of_three = strings(&[
+            "shadow pistol academic always adequate wildlife fancy gross oasis cylinder \
+             mustang wrist rescue view short owner flip making coding armed",
+            "shadow pistol academic acid actress prayer class unknown daughter sweater \
+             depict flip twice unkind craft early superior advocate guest smoking",
+        ]);
+        assert_eq!(
+            hex::encode(combine_mnemonics(&two_of_three, "TREZOR").unwrap()),
+            "b43ceb7e57a0ea8766221624d01b0864"
+        );
+    }
+
+    #[test]
+    fn official_extendable_vectors_recover() {
+        // Extendable shares, as `generate_mnemonics` makes them, from the
+        // SLIP-39 test vectors, passphrase "TREZOR".
+        let single = strings(&[
+            "testify swimming academic academic column loyalty smear include exotic bedroom \
+             exotic wrist lobe cover grief golden smart junior estimate learn",
+        ]);
+        assert_eq!(
+            hex::encode(combine_mnemonics(&single, "TREZOR").unwrap()),
+            "1679b4516e0ee5954351d288a838f45e"
+        );
+        let two_groups = strings(&[
+            "enemy favorite academic acid cowboy phrase havoc level response walnut budget \
+             painting inside trash adjust froth kitchen learn tidy punish",
+            "enemy favorite academic always academic sniff script carpet romp kind promise \
+             scatter center unfair training emphasis evening belong fake enforce",
+        ]);
+        assert_eq!(
+            hex::encode(combine_mnemonics(&two_groups, "TREZOR").unwrap()),
+            "48b1a4b80b8c209ad42c33672bdaa428"
+        );
+        let long = strings(&[
+            "impulse calcium academic academic alcohol sugar lyrics pajamas column facility \
+             finance tension extend space birthday rainbow swimming purple syndrome facility \
+             trial warn duration snapshot shadow hormone rhyme public spine counter easy hawk \
+             album",
+        ]);
+        assert_eq!(
+            hex::encode(combine_mnemonics(&long, "TREZOR").unwrap()),
+            "8340611602fe91af634a5f4608377b5235fa2d757c51d720c0c7656249a3035f"
+        );
+    }
+
+    #[test]
+    fn a_wrong_word_fails_the_checksum() {
+        let share = strings(&[
+            "duckling enlarge academic academic agency result length solution fridge kidney \
+             coal piece deal husband erode duke ajar critical decision kidney",
+        ]);
+        assert!(matches!(
+            combine_mnemonics(&share, "TREZOR"),
+            Err(Slip39Error::InvalidChecksum)
+        ));
+    }
+
+    #[test]
+    fn group_shares_round_trip_and_need_their_thresholds() {
+        let seed: Vec<u8> = (0..64).collect();
+        let groups = [
+            Slip39Group {
+                member_threshold: 1,
+                member_count: 1,
+            },
+            Slip39Group {
+                member_threshold: 2,
+                member_count: 3,
+            },
+            Slip39Group {
+                member_threshold: 3,
+                member_count: 5,
+            },
+        ];
+        let shares = generate_mnemonics(2, &groups, &seed, "", 0).unwrap();
+        assert_eq!(shares.iter().map(Vec::len).collect::<Vec<_>>(), [1, 3, 5]);
+        let pick = |picks: &[(usize, usize)]| -> Vec<String> {
+            picks
+                .iter()
+                .map(|&(g, m)| shares[g][m].as_str().to_string())
+                .collect()
+        };
+
+        let recovered = combine_mnemonics(&pick(&[(1, 2), (2, 4), (1, 0), (2, 0), (2, 3)]), "");
+        assert_eq!(recovered.unwrap().as_slice(), seed.as_slice());
+        let recovered = combine_mnemonics(&pick(&[(0, 0), (1, 1), (1, 2)]), "");
+        assert_eq!(recovered.unwrap().as_slice(), seed.as_slice());
+
+        assert!(matches!(
+            combine_mnemonics(&pick(&[(0, 0), (1, 1), (2, 1), (2, 2)]), ""),
+            Err(Slip39Error::NotEnoughShares(_))
+        ));
+    }
+
+    #[test]
+    fn a_passphrase_changes_the_secret_and_bad_schemes_are_refused() {
+        let seed = [7u8; 32];
+        let group = [Slip39Group {
+            member_threshold: 2,
+            member_count: 2,
+        }];
+        let shares: Vec<String> = generate_mnemonics(1, &group, &seed, "correct horse", 0)
+            .unwrap()
+            .remove(0)
+            .iter()
+            .map(|m| m.as_str().to_string())
+            .collect();
+        assert_eq!(
+            combine_mnemonics(&shares, "correct horse")
+                .unwrap()
+                .as_slice(),
+            seed
+        );
+        assert_ne!(combine_mnemonics(&shares, "").unwrap().as_slice(), seed);
+
+        let one_of_two = [Slip39Group {
+            member_threshold: 1,
+            member_count: 2,
+        }];
+        assert!(generate_mnemonics(1, &one_of_two, &seed, "", 0).is_err());
+        assert!(generate_mnemonics(2, &group, &seed, "", 0).is_err());
+        assert!(generate_mnemonics(1, &group, &seed[..15], "", 0).is_err());
+        assert!(generate_mnemonics(1, &group, &seed, "pässword", 0).is_err());
+    }
+}
@@ -0,0 +1,402 @@
+//! Per-key spending limits and second-approver co-approval.
+//!
+//! A reviewed transaction's [`TxSummary`] is reduced to an [`Outflow`]: how
+//! much it moves and where to. [`evaluate`] checks that against the key's
+//! [`SpendingPolicy`] and lists every reason it goes over; a signature with
+//! any reason needs an [`Approver`] to co-approve it first.
+
+use crate::crypto::encryption::{self, EncryptionError};
+use crate::crypto::kdf::{self, KdfError, KdfParams};
+use crate::crypto::price;
+use crate::models::mobile::BundleKdf;
+use crate::models::spending::{Approver, SpendRecord, SpendingPolicy};
+use crate::models::tx_review::{TxDetail, TxSummary};
+use chrono::{DateTime, Duration, Utc};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+pub const MIN_APPROVER_PASSPHRASE_CHARS: usize = 12;
+
+/// Domain for the key an approver's passphrase derives.
+const APPROVER_KEY_DOMAIN: &str = "ZAP-spending-approver-v1";
+/// Plaintext of an approver's verifier.
+const APPROVER_VERIFIER: &[u8] = b"ZAP_SPENDING_APPROVER";
+
+/// Contract calls whose whole effect on the key's funds shows in `value`.
+/// Any other call (a token transfer, an approval, an unknown selector)
+/// cannot be measured.
+const MEASURABLE_CALLS: &[&str] = &["deposit()", "withdraw(uint256)"];
+/// Cosmos message fields that carry an amount.
+const COSMOS_AMOUNT_FIELDS: &[&str] = &["amount", "token", "funds"];
+/// Cosmos message fields that name where value goes.
+const COSMOS_DESTINATION_FIELDS: &[&str] =
+    &["to_address", "receiver", "validator_address", "contract"];
+
+#[derive(Debug, Error)]
+pub enum SpendingError {
+    #[error("invalid amount: {0}")]
+    InvalidAmount(String),
+    #[error("invalid asset: {0}")]
+    InvalidAsset(String),
+    #[error("approver passphrase must be at least {MIN_APPROVER_PASSPHRASE_CHARS} characters")]
+    WeakPassphrase,
+    #[error("wrong approver passphrase")]
+    WrongPassphrase,
+    #[error("signature needs a second approver ({approval_id}): {reasons}")]
+    ApprovalRequired {
+        approval_id: String,
+        reasons: String,
+    },
+    #[error("approval {0} is not waiting for approval")]
+    NotPending(String),
+    #[error(transparent)]
+    Kdf(#[from] KdfError),
+    #[error(transparent)]
+    Encryption(#[from] EncryptionError),
+}
+
+/// What a transaction moves and where to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outflow {
+    /// In the transaction's base unit; `None` when it cannot be measured.
+    pub amount: Option<u128>,
+    /// Lowercase, as [`normalize_destination`] leaves them.
+    pub destinations: Vec<String>,
+}
+
+pub fn parse_amount(amount: &str) -> Result<u128, SpendingError> {
+    let amount = amount.trim();
+    if amount.is_empty() || !amount.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(SpendingError::InvalidAmount(amount.to_string()));
+    }
+    amount
+        .parse()
+        .map_err(|_| SpendingError::InvalidAmount(amount.to_string()))
+}
+
+pub fn normalize_destination(destination: &str) -> String {
+    destination.trim().to_ascii_lowercase()
+}
+
+/// Check a policy before it is stored, normalising its destinations.
+pub fn validate_policy(policy: &mut SpendingPolicy) -> Result<(), SpendingError> {
+    for amount in [&policy.max_per_tx, &policy.max_per_day]
+        .into_iter()
+        .flatten()
+    {
+        parse_amount(amount)?;
+    }
+    if let Some(asset) = &policy.asset {
+        policy.asset = Some(
+            price::normalize_asset(asset)
+                .map_err(|_| SpendingError::InvalidAsset(asset.clone()))?,
+        );
+    }
+    policy.allowed_destinations = policy
+        .allowed_destinations
+        .iter()
+        .map(|d| normalize_destination(d))
+        .filter(|d| !d.is_empty())
+        .collect();
+    policy.allowed_destinations.sort();
+    policy.allowed_destinations.dedup();
+    Ok(())
+}
+
+/// `12345uatom` → (12345, `uatom`).
+fn cosmos_coin(coin: &str) -> Option<(u128, &str)> {
+    let split = coin.find(|c: char| !c.is_ascii_digit())?;
+    let amount = coin[..split].parse().ok()?;
+    Some((amount, &coin[split..]))
+}
+
+pub fn outflow(summary: &TxSummary) -> Outflow {
+    let (amount, destinations) = match &summary.detail {
+        TxDetail::Psbt(psbt) => {
+            let paying = psbt.outputs.iter().filter(|o| o.script_type != "op_return");
+            let amount = paying.clone().map(|o| o.amount_sats as u128).sum();
+            (Some(amount), paying.map(|o| o.script_hex.clone()).collect())
+        }
+        TxDetail::EthereumTx(tx) => {
+            let mut destinations: Vec<String> = tx.to.iter().cloned().collect();
+            let mut measurable = true;
+            if let Some(call) = &tx.call {
+                measurable &= call
+                    .signature
+                    .as_deref()
+                    .is_some_and(|s| MEASURABLE_CALLS.contains(&s));
+                destinations.extend(
+                    call.args
+                        .iter()
+                        .filter(|a| a.name == "address")
+                        .map(|a| a.value.clone()),
+                );
+            }
+            let amount = if measurable {
+                tx.value_wei.parse().ok()
+            } else {
+                None
+            };
+            (amount, destinations)
+        }
+        TxDetail::CosmosSignDoc(doc) => {
+            let mut total = Some(0u128);
+            let mut denom: Option<&str> = None;
+            let mut destinations = Vec::new();
+            for message in &doc.messages {
+                if message.fields.is_empty() {
+                    total = None;
+                }
+                for field in &message.fields {
+                    let name = field.name.as_str();
+                    if COSMOS_DESTINATION_FIELDS.contains(&name) {
+                        destinations.push(field.value.clone());
+                    }
+                    if !COSMOS_AMOUNT_FIELDS.contains(&name) || field.value.is_empty() {
+                        continue;
+                    }
+                    for coin in field.value.split(", ") {
+                        total = match (total, cosmos_coin(coin)) {
+                            (Some(t), Some((amount, d))) if denom.is_none_or(|x| x == d) => {
+                                denom = Some(d);
+                                t.checked_add(amount)
+                            }
+                            _ => None,
+                        };
+                    }
+                }
+            }
+            (total, destinations)
+        }
+        TxDetail::Raw(_) => (None, Vec::new()),
+    };
+    Outflow {
+        amount,
+        destinations: destinations
+            .iter()
+            .map(|d| normalize_destination(d))
+            .collect(),
+    }
+}
+
+/// Sum of a key's spends recorded after `since`.
+pub fn spent_since(history: &[SpendRecord], key_id: &str, since: DateTime<Utc>) -> u128 {
+    history
+        .iter()
+        .filter(|r| r.key_id == key_id && r.signed_at > since)
+        .filter_map(|r| parse_amount(&r.amount).ok())
+        .fold(0u128, u128::saturating_add)
+}
+
+/// Every way `outflow` goes over `policy`, given what the key has spent in
+/// the last 24 hours. Empty means the signature is allowed on its own.
+pub fn evaluate(
+    policy: &SpendingPolicy,
+    outflow: &Outflow,
+    history: &[SpendRecord],
+    now: DateTime<Utc>,
+) -> Result<Vec<String>, SpendingError> {
+    let mut reasons = Vec::new();
+    let limited = policy.max_per_tx.is_some() || policy.max_per_day.is_some();
+    match outflow.amount {
+        None if limited => {
+            reasons.push("the amount this transaction moves cannot be measured".to_string())
+        }
+        None => {}
+        Some(amount) => {
+            if let Some(max) = &policy.max_per_tx {
+                if amount > parse_amount(max)? {
+                    reasons.push(format!(
+                        "{amount} is over the per-transaction limit of {max}"
+                    ));
+                }
+            }
+            if let Some(max) = &policy.max_per_day {
+                let spent = spent_since(history, &policy.key_id, now - Duration::hours(24));
+                if spent.saturating_add(amount) > parse_amount(max)? {
+                    reasons.push(format!(
+                        "{amount} on top of {spent} already spent today is over the daily limit of {max}"
+                    ));
+                }
+            }
+        }
+    }
+    if !policy.allowed_destinations.is_empty() {
+        if outflow.destinations.is_empty() {
+            reasons.push("this transaction has no destination to check".to_string());
+        }
+        for destination in &outflow.destinations {
+            if !policy.allowed_destinations.contains(destination) {
+                reasons.push(format!("{destination} is not an allowed destination"));
+            }
+        }
+    }
+    Ok(reasons)
+}
+
+fn approver_key(
+    passphrase: &str,
+    salt: &[u8],
+    params: KdfParams,
+) -> Result<Zeroizing<[u8; 32]>, SpendingError> {
+    let master = Zeroizing::new(kdf::derive_master_key_with_params(
+        passphrase.as_bytes(),
+        salt,
+        params,
+    )?);
+    Ok(Zeroizing::new(kdf::derive_encryption_key(
+        &master,
+        APPROVER_KEY_DOMAIN,
+    )))
+}
+
+pub fn enroll_approver(
+    name: &str,
+    passphrase: &str,
+    now: DateTime<Utc>,
+) -> Result<Approver, SpendingError> {
+    if passphrase.chars().count() < MIN_APPROVER_PASSPHRASE_CHARS {
+        return Err(SpendingError::WeakPassphrase);
+    }
+    let params = KdfParams::high();
+    let salt = kdf::generate_salt();
+    let key = approver_key(passphrase, &salt, params)?;
+    Ok(Approver {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.trim().to_string(),
+        salt_hex: hex::encode(salt),
+        kdf: BundleKdf {
+            memory_kib: params.memory_kib,
+            iterations: params.iterations,
+            parallelism: params.parallelism,
+        },
+        verifier: encryption::encrypt_aead(&key, APPROVER_VERIFIER)?,
+        created_at: now,
+    })
+}
+
+pub fn verify_approver(approver: &Approver, passphrase: &str) -> Result<(), SpendingError> {
+    let salt = hex::decode(&approver.salt_hex).map_err(|_| SpendingError::WrongPassphrase)?;
+    let params = KdfParams {
+        memory_kib: approver.kdf.memory_kib,
+        iterations: approver.kdf.iterations,
+        parallelism: approver.kdf.parallelism,
+    };
+    let key = approver_key(passphrase, &salt, params)?;
+    match encryption::decrypt_aead(&key, &approver.verifier) {
+        Ok(plain) if plain == APPROVER_VERIFIER => Ok(()),
+        _ => Err(SpendingError::WrongPassphrase),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::signing_request::SigningPayloadKind;
+    use crate::models::tx_review::{PsbtOutput, PsbtSummary};
+
+    fn psbt_summary(outputs: &[(u64, &str)]) -> TxSummary {
+        TxSummary {
+            kind: SigningPayloadKind::Psbt,
+            payload_sha256_hex: String::new(),
+            detail: TxDetail::Psbt(PsbtSummary {
+                tx_version: 2,
+                lock_time: 0,
+                inputs: Vec::new(),
+                outputs: outputs
+                    .iter()
+                    .map(|(amount, script)| PsbtOutput {
+                        amount_sats: *amount,
+                        script_type: "p2wpkh".to_string(),
+                        script_hex: script.to_string(),
+                    })
+                    .collect(),
+                total_in_sats: None,
+                total_out_sats: outputs.iter().map(|(a, _)| a).sum(),
+                fee_sats: None,
+            }),
+            warnings: Vec::new(),
+            summary_hash_hex: String::new(),
+        }
+    }
+
+    fn policy(
+        max_per_tx: Option<&str>,
+        max_per_day: Option<&str>,
+        allowed: &[&str],
+    ) -> SpendingPolicy {
+        let mut policy = SpendingPolicy {
+            key_id: "k".to_string(),
+            max_per_tx: max_per_tx.map(str::to_string),
+            max_per_day: max_per_day.map(str::to_string),
+            allowed_destinations: allowed.iter().map(|s| s.to_string()).collect(),
+            asset: None,
+            updated_at: Utc::now(),
+        };
+        validate_policy(&mut policy).unwrap();
+        policy
+    }
+
+    #[test]
+    fn test_within_limits_needs_no_approval() {
+        let out = outflow(&psbt_summary(&[(600, "00AA"), (400, "00bb")]));
+        assert_eq!(out.amount, Some(1_000));
+        assert_eq!(out.destinations, vec!["00aa", "00bb"]);
+        let p = policy(Some("1000"), Some("5000"), &["00aa", "00BB"]);
+        assert!(evaluate(&p, &out, &[], Utc::now()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_limits_and_destinations_are_enforced() {
+        let now = Utc::now();
+        let out = outflow(&psbt_summary(&[(900, "00aa"), (100, "00cc")]));
+        let p = policy(Some("999"), Some("1500"), &["00aa"]);
+        let history = vec![
+            SpendRecord {
+                key_id: "k".to_string(),
+                amount: "600".to_string(),
+                signed_at: now - Duration::hours(2),
+            },
+            SpendRecord {
+                key_id: "k".to_string(),
+                amount: "5000".to_string(),
+                signed_at: now - Duration::hours(30),
+            },
+        ];
+        let reasons = evaluate(&p, &out, &history, now).unwrap();
+        assert_eq!(reasons.len(), 3);
+        assert!(reasons[0].contains("per-transaction"));
+        assert!(reasons[1].contains("600 already spent"));
+        assert!(reasons[2].contains("00cc"));
+    }
+
+    #[test]
+    fn test_unmeasurable_amount_needs_approval() {
+        let raw = TxSummary {
+            detail: TxDetail::Raw(crate::models::tx_review::RawSummary {
+                len: 32,
+                text: None,
+            }),
+            ..psbt_summary(&[])
+        };
+        let out = outflow(&raw);
+        assert_eq!(out.amount, None);
+        assert_eq!(
+            evaluate(&policy(Some("1"), None, &[]), &out, &[], Utc::now())
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(evaluate(&policy(None, None, &[]), &out, &[], Utc::now())
+            .unwrap()
+            .is_empty());
+        assert!(parse_amount("-1").is_err());
+        assert!(parse_amount("1e9").is_err());
+    }
+
+    #[test]
+    fn test_cosmos_coin_parsing() {
+        assert_eq!(cosmos_coin("12345uatom"), Some((12345, "uatom")));
+        assert_eq!(cosmos_coin("uatom"), None);
+    }
+}
@@ -0,0 +1,658 @@
+use crate::crypto::encryption::{self, EncryptionError};
+use crate::crypto::instance::{verify_instance, PURPOSE_SYNC_PACKAGE};
+use crate::crypto::mldsa87::{self, CryptoError, PublicKey, SecretKey, Signature};
+use crate::models::item::VaultItem;
+use crate::models::key::KeyEntry;
+use crate::models::sync::{
+    ConflictPolicy, SyncConflict, SyncDelta, SyncEntryKind, SyncImportReport, SyncPackage,
+    SyncState,
+};
+use crate::models::usage::UsageStats;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// Current sync package format version.
+pub const SYNC_PACKAGE_VERSION: u32 = 1;
+
+/// BLAKE3 `derive_key` contexts for the sync keys. All three come from the HD
+/// master seed, so only installations of the same vault can read, sign or
+/// fingerprint packages, and none of them needs storing.
+const SIGNING_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 sync package signing key v1";
+const PAYLOAD_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 sync package payload key v1";
+const FINGERPRINT_KEY_CONTEXT: &str = "ZAP Quantum Vault 2026 sync entry fingerprint key v1";
+
+#[derive(Debug, Error)]
+pub enum SyncError {
+    #[error("unsupported sync package version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("sync package was exported from a different vault")]
+    ForeignVault,
+    #[error("sync package signature is invalid")]
+    BadSignature,
+    #[error("sync package was exported by this installation")]
+    OwnPackage,
+    #[error("sync package {sequence} is not newer than the last one applied ({last})")]
+    Stale { sequence: u64, last: u64 },
+    #[error("sync package instance signature is invalid: {0}")]
+    BadInstanceSignature(String),
+    #[error("installation {0} signed earlier packages with a different instance key")]
+    InstanceKeyChanged(String),
+    #[error("encryption error: {0}")]
+    Encryption(#[from] EncryptionError),
+    #[error("signature error: {0}")]
+    Crypto(#[from] CryptoError),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+fn seed_key(context: &str, master_seed: &[u8; 64]) -> Zeroizing<[u8; 32]> {
+    Zeroizing::new(blake3::derive_key(context, master_seed))
+}
+
+/// The package-signing keypair shared by every installation of the vault.
+pub fn signing_keypair(master_seed: &[u8; 64]) -> (PublicKey, SecretKey) {
+    mldsa87::from_seed(&seed_key(SIGNING_KEY_CONTEXT, master_seed))
+}
+
+/// Key for the keyed entry fingerprints kept in [`SyncState::known`].
+pub fn fingerprint_key(master_seed: &[u8; 64]) -> Zeroizing<[u8; 32]> {
+    seed_key(FINGERPRINT_KEY_CONTEXT, master_seed)
+}
+
+fn push_field(m: &mut Vec<u8>, field: &[u8]) {
+    m.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    m.extend_from_slice(field);
+}
+
+/// Canonical encoding of everything in a package except the signature.
+pub fn package_message(pkg: &SyncPackage) -> Vec<u8> {
+    let mut m = Vec::with_capacity(64 + pkg.payload.ciphertext.len());
+    m.extend_from_slice(b"ZAP_SYNC_PACKAGE_V1");
+    m.extend_from_slice(&pkg.version.to_le_bytes());
+    push_field(&mut m, pkg.device_id.as_bytes());
+    m.extend_from_slice(&pkg.sequence.to_le_bytes());
+    m.extend_from_slice(&pkg.created_at.timestamp_millis().to_le_bytes());
+    m.push(pkg.full as u8);
+    push_field(&mut m, &pkg.payload.nonce);
+    push_field(&mut m, &pkg.payload.ciphertext);
+    m
+}
+
+/// Encrypt `delta` and sign the package.
+pub fn seal_package(
+    master_seed: &[u8; 64],
+    device_id: &str,
+    sequence: u64,
+    created_at: DateTime<Utc>,
+    full: bool,
+    delta: &SyncDelta,
+) -> Result<SyncPackage, SyncError> {
+    let json = Zeroizing::new(serde_json::to_vec(delta)?);
+    let key = seed_key(PAYLOAD_KEY_CONTEXT, master_seed);
+    let (pk, sk) = signing_keypair(master_seed);
+    let mut pkg = SyncPackage {
+        version: SYNC_PACKAGE_VERSION,
+        device_id: device_id.to_string(),
+        sequence,
+        created_at,
+        full,
+        payload: encryption::encrypt_aead(&key, &json)?,
+        public_key_hex: pk.to_hex(),
+        signature_hex: String::new(),
+        instance_signature: None,
+    };
+    pkg.signature_hex = mldsa87::sign(&sk, &package_message(&pkg))?.to_hex();
+    Ok(pkg)
+}
+
+/// Check that `pkg` came from this vault and is intact, and decrypt it.
+pub fn open_package(master_seed: &[u8; 64], pkg: &SyncPackage) -> Result<SyncDelta, SyncError> {
+    if pkg.version != SYNC_PACKAGE_VERSION {
+        return Err(SyncError::UnsupportedVersion(pkg.version));
+    }
+    let (pk, _) = signing_keypair(master_seed);
+    if pkg.public_key_hex != pk.to_hex() {
+        return Err(SyncError::ForeignVault);
+    }
+    let sig = Signature::from_hex(&pkg.signature_hex)?;
+    if !mldsa87::verify(&pk, &package_message(pkg), &sig)? {
+        return Err(SyncError::BadSignature);
+    }
+    let key = seed_key(PAYLOAD_KEY_CONTEXT, master_seed);
+    let json = Zeroizing::new(encryption::decrypt_aead(&key, &pkg.payload)?);
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// Check the package's instance signature against the key pinned for its
+/// sender. Once a sender has signed a package, unsigned ones from it are
+/// refused. Returns the fingerprint to pin, `None` for an unsigned package.
+pub fn check_instance(state: &SyncState, pkg: &SyncPackage) -> Result<Option<String>, SyncError> {
+    let pinned = state.instance_keys.get(&pkg.device_id);
+    let Some(sig) = &pkg.instance_signature else {
+        return match pinned {
+            Some(_) => Err(SyncError::BadInstanceSignature(
+                "the sender signs its packages but this one is unsigned".to_string(),
+            )),
+            None => Ok(None),
+        };
+    };
+    if sig.instance_id != pkg.device_id {
+        return Err(SyncError::BadInstanceSignature(format!(
+            "signed by {}, exported by {}",
+            sig.instance_id, pkg.device_id
+        )));
+    }
+    verify_instance(sig, PURPOSE_SYNC_PACKAGE, &package_message(pkg))
+        .map_err(|e| SyncError::BadInstanceSignature(e.to_string()))?;
+    let fingerprint = sig.key_fingerprint();
+    match pinned {
+        Some(p) if *p != fingerprint => Err(SyncError::InstanceKeyChanged(pkg.device_id.clone())),
+        _ => Ok(Some(fingerprint)),
+    }
+}
+
+/// Reject packages from this installation and packages not newer than the
+/// last one applied from their sender. Returns how many of the sender's
+/// packages were skipped in between (always 0 for a full package).
+pub fn check_sequence(state: &SyncState, pkg: &SyncPackage) -> Result<u64, SyncError> {
+    if pkg.device_id == state.device_id {
+        return Err(SyncError::OwnPackage);
+    }
+    let last = state.imported.get(&pkg.device_id).copied().unwrap_or(0);
+    if pkg.sequence <= last {
+        return Err(SyncError::Stale {
+            sequence: pkg.sequence,
+            last,
+        });
+    }
+    Ok(if pkg.full { 0 } else { pkg.sequence - last - 1 })
+}
+
+/// A keystore or item store entry as seen by replication.
+trait Replicated: Clone + Serialize {
+    const KIND: SyncEntryKind;
+    fn id(&self) -> &str;
+    fn label(&self) -> Option<String>;
+    /// Per-installation counters, excluded from fingerprints and never
+    /// overwritten by an import.
+    fn usage_mut(&mut self) -> &mut UsageStats;
+    /// Whether `other` holds the same secret under a different id.
+    fn duplicates(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+impl Replicated for KeyEntry {
+    const KIND: SyncEntryKind = SyncEntryKind::Key;
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn label(&self) -> Option<String> {
+        self.metadata.label.clone()
+    }
+    fn usage_mut(&mut self) -> &mut UsageStats {
+        &mut self.metadata.usage
+    }
+    // Both installations derive HD keys from the same seed, so the same path
+    // generated on each side yields one key under two ids.
+    fn duplicates(&self, other: &Self) -> bool {
+        self.public_key_hex == other.public_key_hex
+    }
+}
+
+impl Replicated for VaultItem {
+    const KIND: SyncEntryKind = SyncEntryKind::Item;
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn label(&self) -> Option<String> {
+        self.label.clone()
+    }
+    fn usage_mut(&mut self) -> &mut UsageStats {
+        &mut self.usage
+    }
+}
+
+fn prefix(kind: SyncEntryKind) -> &'static str {
+    match kind {
+        SyncEntryKind::Key => "key:",
+        SyncEntryKind::Item => "item:",
+    }
+}
+
+fn known_key<T: Replicated>(id: &str) -> String {
+    format!("{}{id}", prefix(T::KIND))
+}
+
+fn fingerprint<T: Replicated>(fp_key: &[u8; 32], entry: &T) -> Result<String, SyncError> {
+    let mut entry = entry.clone();
+    *entry.usage_mut() = UsageStats::default();
+    let json = Zeroizing::new(serde_json::to_vec(&entry)?);
+    Ok(blake3::keyed_hash(fp_key, &json).to_hex().to_string())
+}
+
+/// Collect the entries that changed since the last sync (all of them when
+/// `full`) and the ids deleted since then, marking them as known.
+pub fn build_delta(
+    fp_key: &[u8; 32],
+    known: &mut BTreeMap<String, String>,
+    keys: &[KeyEntry],
+    items: &[VaultItem],
+    full: bool,
+) -> Result<SyncDelta, SyncError> {
+    let mut delta = SyncDelta::default();
+    collect(
+        fp_key,
+        known,
+        keys,
+        full,
+        &mut delta.keys,
+        &mut delta.deleted_keys,
+    )?;
+    collect(
+        fp_key,
+        known,
+        items,
+        full,
+        &mut delta.items,
+        &mut delta.deleted_items,
+    )?;
+    Ok(delta)
+}
+
+fn collect<T: Replicated>(
+    fp_key: &[u8; 32],
+    known: &mut BTreeMap<String, String>,
+    entries: &[T],
+    full: bool,
+    changed: &mut Vec<T>,
+    deleted: &mut Vec<String>,
+) -> Result<(), SyncError> {
+    for entry in entries {
+        let k = known_key::<T>(entry.id());
+        let fp = fingerprint(fp_key, entry)?;
+        if full || known.get(&k) != Some(&fp) {
+            changed.push(entry.clone());
+            known.insert(k, fp);
+        }
+    }
+    let present: HashSet<&str> = entries.iter().map(|e| e.id()).collect();
+    let gone: Vec<String> = known
+        .keys()
+        .filter_map(|k| k.strip_prefix(prefix(T::KIND)))
+        .filter(|id| !present.contains(id))
+        .map(String::from)
+        .collect();
+    for id in &gone {
+        known.remove(&known_key::<T>(id));
+    }
+    deleted.extend(gone);
+    Ok(())
+}
+
+/// Merge a decrypted delta into the local stores. An entry changed on only
+/// one side since the last sync takes that side's version; one changed on
+/// both is a conflict settled by `policy` and reported. Local usage counters
+/// are kept.
+pub fn apply_delta(
+    fp_key: &[u8; 32],
+    known: &mut BTreeMap<String, String>,
+    keys: &mut Vec<KeyEntry>,
+    items: &mut Vec<VaultItem>,
+    delta: SyncDelta,
+    policy: ConflictPolicy,
+    report: &mut SyncImportReport,
+) -> Result<(), SyncError> {
+    let SyncDelta {
+        keys: incoming_keys,
+        items: incoming_items,
+        deleted_keys,
+        deleted_items,
+    } = delta;
+    merge(
+        fp_key,
+        known,
+        keys,
+        incoming_keys,
+        deleted_keys,
+        policy,
+        report,
+    )?;
+    merge(
+        fp_key,
+        known,
+        items,
+        incoming_items,
+        deleted_items,
+        policy,
+        report,
+    )
+}
+
+fn merge<T: Replicated>(
+    fp_key: &[u8; 32],
+    known: &mut BTreeMap<String, String>,
+    local: &mut Vec<T>,
+    incoming: Vec<T>,
+    deleted: Vec<String>,
+    policy: ConflictPolicy,
+    report: &mut SyncImportReport,
+) -> Result<(), SyncError> {
+    for mut entry in incoming {
+        let k = known_key::<T>(entry.id());
+        let fp = fingerprint(fp_key, &entry)?;
+        match local.iter().position(|e| e.id() == entry.id()) {
+            None if local.iter().any(|e| e.duplicates(&entry)) => {
+                report.unchanged += 1;
+                continue;
+            }
+            None => {
+                local.push(entry);
+                report.added += 1;
+            }
+            Some(i) => {
+                let local_fp = fingerprint(fp_key, &local[i])?;
+                let edited = known.get(&k) != Some(&local_fp);
+                if local_fp == fp {
+                    report.unchanged += 1;
+                } else {
+                    if edited {
+                        report.conflicts.push(SyncConflict {
+                            kind: T::KIND,
+                            id: entry.id().to_string(),
+                            label: local[i].label(),
+                            deleted_remotely: false,
+                            resolution: policy,
+                        });
+                    }
+                    if !edited || policy == ConflictPolicy::TakeIncoming {
+                        *entry.usage_mut() = local[i].usage_mut().clone();
+                        local[i] = entry;
+                        report.updated += 1;
+                    }
+                }
+            }
+        }
+        // Even a conflict kept locally is recorded as agreed on the incoming
+        // version, so the next export sends the local one back and both sides
+        // converge.
+        known.insert(k, fp);
+    }
+
+    for id in deleted {
+        let k = known_key::<T>(&id);
+        if let Some(i) = local.iter().position(|e| e.id() == id) {
+            let edited = known.get(&k) != Some(&fingerprint(fp_key, &local[i])?);
+            if edited {
+                report.conflicts.push(SyncConflict {
+                    kind: T::KIND,
+                    id: id.clone(),
+                    label: local[i].label(),
+                    deleted_remotely: true,
+                    resolution: policy,
+                });
+            }
+            if !edited || policy == ConflictPolicy::TakeIncoming {
+                local.remove(i);
+                report.deleted += 1;
+            }
+        }
+        known.remove(&k);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::item::{ItemPayload, SshKeyAlgorithm, SshKeyItem};
+    use crate::models::key::KeyType;
+
+    const SEED: [u8; 64] = [7u8; 64];
+
+    fn key(index: u32, pk: &str) -> KeyEntry {
+        KeyEntry::new(KeyType::User, 44, 0, index, pk, "secret", "zap1q", "m/44'")
+    }
+
+    fn item(label: &str) -> VaultItem {
+        VaultItem::new(
+            Some(label.to_string()),
+            ItemPayload::SshKey(SshKeyItem {
+                algorithm: SshKeyAlgorithm::Ed25519,
+                comment: label.to_string(),
+                public_key_openssh: "ssh-ed25519 AAAA".to_string(),
+                fingerprint: "SHA256:x".to_string(),
+                private_key_openssh: "private".to_string(),
+            }),
+        )
+    }
+
+    /// One installation's stores and sync state.
+    struct Install {
+        state: SyncState,
+        keys: Vec<KeyEntry>,
+        items: Vec<VaultItem>,
+    }
+
+    impl Install {
+        fn new() -> Self {
+            Self {
+                state: SyncState::new(),
+                keys: Vec::new(),
+                items: Vec::new(),
+            }
+        }
+
+        fn export(&mut self, full: bool) -> SyncPackage {
+            let fp = fingerprint_key(&SEED);
+            let delta =
+                build_delta(&fp, &mut self.state.known, &self.keys, &self.items, full).unwrap();
+            self.state.sequence += 1;
+            seal_package(
+                &SEED,
+                &self.state.device_id,
+                self.state.sequence,
+                Utc::now(),
+                full,
+                &delta,
+            )
+            .unwrap()
+        }
+
+        fn import(&mut self, pkg: &SyncPackage, policy: ConflictPolicy) -> SyncImportReport {
+            let mut report = SyncImportReport {
+                skipped_packages: check_sequence(&self.state, pkg).unwrap(),
+                ..Default::default()
+            };
+            let delta = open_package(&SEED, pkg).unwrap();
+            let fp = fingerprint_key(&SEED);
+            apply_delta(
+                &fp,
+                &mut self.state.known,
+                &mut self.keys,
+                &mut self.items,
+                delta,
+                policy,
+                &mut report,
+            )
+            .unwrap();
+            self.state
+                .imported
+                .insert(pkg.device_id.clone(), pkg.sequence);
+            report
+        }
+    }
+
+    #[test]
+    fn package_round_trips_and_rejects_tampering() {
+        let delta = SyncDelta {
+            items: vec![item("laptop")],
+            ..Default::default()
+        };
+        let pkg = seal_package(&SEED, "a", 1, Utc::now(), false, &delta).unwrap();
+        assert_eq!(open_package(&SEED, &pkg).unwrap().items.len(), 1);
+
+        let mut tampered = pkg.clone();
+        tampered.sequence = 9;
+        assert!(matches!(
+            open_package(&SEED, &tampered),
+            Err(SyncError::BadSignature)
+        ));
+        assert!(matches!(
+            open_package(&[8u8; 64], &pkg),
+            Err(SyncError::ForeignVault)
+        ));
+    }
+
+    #[test]
+    fn sequence_checks_reject_own_and_replayed_packages() {
+        let mut state = SyncState::new();
+        let mut pkg =
+            seal_package(&SEED, "b", 3, Utc::now(), false, &SyncDelta::default()).unwrap();
+        assert_eq!(check_sequence(&state, &pkg).unwrap(), 2);
+        state.imported.insert("b".to_string(), 3);
+        assert!(matches!(
+            check_sequence(&state, &pkg),
+            Err(SyncError::Stale {
+                sequence: 3,
+                last: 3
+            })
+        ));
+        pkg.device_id = state.device_id.clone();
+        assert!(matches!(
+            check_sequence(&state, &pkg),
+            Err(SyncError::OwnPackage)
+        ));
+    }
+
+    #[test]
+    fn instance_signatures_are_checked_and_pinned() {
+        let mut state = SyncState::new();
+        let mut pkg =
+            seal_package(&SEED, "b", 1, Utc::now(), false, &SyncDelta::default()).unwrap();
+        assert_eq!(check_instance(&state, &pkg).unwrap(), None);
+
+        let (key, _) = crate::crypto::instance::generate_instance(&SEED, "b", Utc::now()).unwrap();
+        let sign = |pkg: &SyncPackage| key.sign(PURPOSE_SYNC_PACKAGE, &package_message(pkg));
+        pkg.instance_signature = Some(sign(&pkg).unwrap());
+        let fingerprint = check_instance(&state, &pkg).unwrap().unwrap();
+        assert_eq!(fingerprint, key.identity.key_fingerprint());
+        state.instance_keys.insert("b".to_string(), fingerprint);
+
+        // Once pinned, unsigned packages and other keys are refused.
+        let signature = pkg.instance_signature.take();
+        assert!(matches!(
+            check_instance(&state, &pkg),
+            Err(SyncError::BadInstanceSignature(_))
+        ));
+        let (other, _) =
+            crate::crypto::instance::generate_instance(&SEED, "b", Utc::now()).unwrap();
+        pkg.instance_signature = Some(
+            other
+                .sign(PURPOSE_SYNC_PACKAGE, &package_message(&pkg))
+                .unwrap(),
+        );
+        assert!(matches!(
+            check_instance(&state, &pkg),
+            Err(SyncError::InstanceKeyChanged(_))
+        ));
+
+        // The signature covers the package and names its sender.
+        pkg.instance_signature = signature;
+        pkg.sequence = 2;
+        assert!(check_instance(&state, &pkg).is_err());
+        pkg.sequence = 1;
+        pkg.device_id = "c".to_string();
+        assert!(check_instance(&SyncState::new(), &pkg).is_err());
+    }
+
+    #[test]
+    fn deltas_carry_only_changes_since_the_last_sync() {
+        let mut a = Install::new();
+        a.keys.push(key(0, "aa"));
+        a.items.push(item("laptop"));
+        let first = open_package(&SEED, &a.export(false)).unwrap();
+        assert_eq!((first.keys.len(), first.items.len()), (1, 1));
+
+        // Usage counters are local and do not count as a change.
+        a.keys[0].metadata.usage.touch(Utc::now());
+        let idle = open_package(&SEED, &a.export(false)).unwrap();
+        assert!(idle.keys.is_empty() && idle.items.is_empty());
+
+        a.keys[0].metadata.label = Some("cold".to_string());
+        let gone = a.items.remove(0);
+        let next = open_package(&SEED, &a.export(false)).unwrap();
+        assert_eq!(next.keys.len(), 1);
+        assert_eq!(next.deleted_items, vec![gone.id.clone()]);
+
+        let full = open_package(&SEED, &a.export(true)).unwrap();
+        assert_eq!(full.keys.len(), 1);
+    }
+
+    #[test]
+    fn two_installations_converge() {
+        let mut a = Install::new();
+        let mut b = Install::new();
+        a.keys.push(key(0, "aa"));
+        a.items.push(item("laptop"));
+        let report = b.import(&a.export(false), ConflictPolicy::KeepLocal);
+        assert_eq!(report.added, 2);
+
+        // An edit on one side is taken without a conflict.
+        b.items[0].label = Some("office laptop".to_string());
+        let report = a.import(&b.export(false), ConflictPolicy::KeepLocal);
+        assert_eq!(report.updated, 1);
+        assert!(report.conflicts.is_empty());
+        assert_eq!(a.items[0].label.as_deref(), Some("office laptop"));
+
+        // Both sides edit the same key: the importer keeps its own version,
+        // reports the conflict, and the next round trip settles on it.
+        a.keys[0].metadata.label = Some("from a".to_string());
+        b.keys[0].metadata.label = Some("from b".to_string());
+        let report = b.import(&a.export(false), ConflictPolicy::KeepLocal);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(b.keys[0].metadata.label.as_deref(), Some("from b"));
+        let report = a.import(&b.export(false), ConflictPolicy::KeepLocal);
+        assert!(report.conflicts.is_empty());
+        assert_eq!(a.keys[0].metadata.label.as_deref(), Some("from b"));
+
+        // Deletions propagate.
+        b.items.clear();
+        let report = a.import(&b.export(false), ConflictPolicy::KeepLocal);
+        assert_eq!(report.deleted, 1);
+        assert!(a.items.is_empty());
+    }
+
+    #[test]
+    fn deleting_an_entry_edited_locally_is_a_conflict() {
+        let mut a = Install::new();
+        let mut b = Install::new();
+        a.items.push(item("laptop"));
+        b.import(&a.export(false), ConflictPolicy::KeepLocal);
+
+        a.items.clear();
+        b.items[0].label = Some("renamed".to_string());
+        let report = b.import(&a.export(false), ConflictPolicy::KeepLocal);
+        assert_eq!(report.deleted, 0);
+        assert!(report.conflicts[0].deleted_remotely);
+        assert_eq!(b.items.len(), 1);
+
+        // The kept item flows back to `a` on the next export.
+        let report = a.import(&b.export(false), ConflictPolicy::KeepLocal);
+        assert_eq!(report.added, 1);
+    }
+
+    #[test]
+    fn same_key_derived_on_both_sides_is_not_duplicated() {
+        let mut a = Install::new();
+        let mut b = Install::new();
+        a.keys.push(key(0, "aa"));
+        b.keys.push(key(0, "aa"));
+        let report = b.import(&a.export(false), ConflictPolicy::KeepLocal);
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(b.keys.len(), 1);
+    }
+}
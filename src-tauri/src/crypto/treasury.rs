@@ -0,0 +1,299 @@
+use crate::crypto::mldsa87::{CryptoError, PublicKey, SecretKey};
+use crate::crypto::threshold::{ThresholdError, ThresholdShare, ThresholdSigner};
+use crate::models::treasury::{
+    TreasuryPolicy, TreasuryProposal, TreasuryProposalStatus, TreasuryShare,
+    TreasurySignatureBundle, TreasurySigner,
+};
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+/// Upper bound on N for a treasury policy.
+pub const MAX_TREASURY_SIGNERS: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum TreasuryError {
+    #[error("invalid policy: {0}")]
+    InvalidPolicy(String),
+    #[error("invalid payload: {0}")]
+    InvalidPayload(String),
+    #[error("key {0} is not a signer of this policy")]
+    NotASigner(String),
+    #[error("key {0} has already signed this proposal")]
+    AlreadySigned(String),
+    #[error("share from key {0} does not verify")]
+    BadShare(String),
+    #[error("bundle does not match policy {0}")]
+    BundleMismatch(String),
+    #[error("threshold error: {0}")]
+    Threshold(#[from] ThresholdError),
+    #[error("signature error: {0}")]
+    Crypto(#[from] CryptoError),
+}
+
+fn push_field(m: &mut Vec<u8>, field: &[u8]) {
+    m.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    m.extend_from_slice(field);
+}
+
+/// Check an M-of-N policy: 1 <= M <= N <= [`MAX_TREASURY_SIGNERS`], with
+/// distinct, well-formed signer keys.
+pub fn validate_policy(threshold: usize, signers: &[TreasurySigner]) -> Result<(), TreasuryError> {
+    if signers.is_empty() || signers.len() > MAX_TREASURY_SIGNERS {
+        return Err(TreasuryError::InvalidPolicy(format!(
+            "a policy needs between 1 and {MAX_TREASURY_SIGNERS} signers"
+        )));
+    }
+    if threshold == 0 || threshold > signers.len() {
+        return Err(TreasuryError::InvalidPolicy(format!(
+            "threshold must be between 1 and {}",
+            signers.len()
+        )));
+    }
+    for (i, signer) in signers.iter().enumerate() {
+        PublicKey::from_hex(&signer.public_key_hex)?;
+        if signers[..i]
+            .iter()
+            .any(|s| s.key_id == signer.key_id || s.public_key_hex == signer.public_key_hex)
+        {
+            return Err(TreasuryError::InvalidPolicy(format!(
+                "signer {} is listed twice",
+                signer.key_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The bytes every share of `proposal_id` signs: the policy terms (id,
+/// threshold and signer set) and the payload, so a share cannot be replayed
+/// under a different policy or for a different payload.
+pub fn proposal_message(policy: &TreasuryPolicy, proposal_id: &str, payload: &[u8]) -> Vec<u8> {
+    let mut m = Vec::new();
+    m.extend_from_slice(b"ZAP_TREASURY_PROPOSAL_V1");
+    push_field(&mut m, policy.id.as_bytes());
+    m.extend_from_slice(&(policy.threshold as u32).to_le_bytes());
+    m.extend_from_slice(&(policy.signers.len() as u32).to_le_bytes());
+    for signer in &policy.signers {
+        push_field(&mut m, signer.public_key_hex.as_bytes());
+    }
+    push_field(&mut m, proposal_id.as_bytes());
+    push_field(&mut m, payload);
+    m
+}
+
+fn payload_bytes(proposal: &TreasuryProposal) -> Result<Vec<u8>, TreasuryError> {
+    hex::decode(&proposal.payload_hex).map_err(|e| TreasuryError::InvalidPayload(e.to_string()))
+}
+
+fn signer<'a>(
+    policy: &'a TreasuryPolicy,
+    key_id: &str,
+) -> Result<&'a TreasurySigner, TreasuryError> {
+    policy
+        .signers
+        .iter()
+        .find(|s| s.key_id == key_id)
+        .ok_or_else(|| TreasuryError::NotASigner(key_id.to_string()))
+}
+
+/// Sign `proposal` with one signer's secret key.
+pub fn sign_share(
+    policy: &TreasuryPolicy,
+    proposal: &TreasuryProposal,
+    key_id: &str,
+    secret: SecretKey,
+    now: DateTime<Utc>,
+) -> Result<TreasuryShare, TreasuryError> {
+    let expected = signer(policy, key_id)?;
+    let threshold_signer = ThresholdSigner::new(secret, policy.threshold)?;
+    if threshold_signer.public_key().to_hex() != expected.public_key_hex {
+        return Err(TreasuryError::BadShare(key_id.to_string()));
+    }
+    let message = proposal_message(policy, &proposal.id, &payload_bytes(proposal)?);
+    let share = threshold_signer.create_share(&message)?;
+    Ok(TreasuryShare {
+        key_id: key_id.to_string(),
+        signature_hex: hex::encode(share.signature),
+        signed_at: now,
+    })
+}
+
+fn to_threshold_share(
+    policy: &TreasuryPolicy,
+    share: &TreasuryShare,
+) -> Result<ThresholdShare, TreasuryError> {
+    let pk = PublicKey::from_hex(&signer(policy, &share.key_id)?.public_key_hex)?;
+    Ok(ThresholdShare {
+        signer_public_key: pk.0,
+        signature: hex::decode(&share.signature_hex)
+            .map_err(|_| TreasuryError::BadShare(share.key_id.clone()))?,
+    })
+}
+
+/// Verify `share` and add it to the proposal.
+pub fn add_share(
+    policy: &TreasuryPolicy,
+    proposal: &mut TreasuryProposal,
+    share: TreasuryShare,
+) -> Result<(), TreasuryError> {
+    if proposal.shares.iter().any(|s| s.key_id == share.key_id) {
+        return Err(TreasuryError::AlreadySigned(share.key_id));
+    }
+    let message = proposal_message(policy, &proposal.id, &payload_bytes(proposal)?);
+    if !ThresholdSigner::verify_share(&to_threshold_share(policy, &share)?, &message)? {
+        return Err(TreasuryError::BadShare(share.key_id));
+    }
+    proposal.shares.push(share);
+    Ok(())
+}
+
+pub fn status(policy: &TreasuryPolicy, proposal: &TreasuryProposal) -> TreasuryProposalStatus {
+    TreasuryProposalStatus {
+        proposal: proposal.clone(),
+        threshold: policy.threshold,
+        signer_count: policy.signers.len(),
+        ready: proposal.shares.len() >= policy.threshold,
+    }
+}
+
+/// Combine the proposal's shares into a threshold signature bundle. Fails
+/// until at least M valid shares are in.
+pub fn combine(
+    policy: &TreasuryPolicy,
+    proposal: &TreasuryProposal,
+) -> Result<TreasurySignatureBundle, TreasuryError> {
+    let message = proposal_message(policy, &proposal.id, &payload_bytes(proposal)?);
+    let shares = proposal
+        .shares
+        .iter()
+        .map(|s| to_threshold_share(policy, s))
+        .collect::<Result<Vec<_>, _>>()?;
+    let signature = ThresholdSigner::aggregate(&message, shares, policy.threshold)?;
+    Ok(TreasurySignatureBundle {
+        policy_id: policy.id.clone(),
+        proposal_id: proposal.id.clone(),
+        threshold: policy.threshold,
+        signers: policy.signers.clone(),
+        payload_hex: proposal.payload_hex.clone(),
+        message_hex: hex::encode(&message),
+        signature,
+    })
+}
+
+/// Verify a bundle against `policy`: the terms match, every share comes from
+/// a policy signer, and at least M distinct shares verify over the message.
+pub fn verify_bundle(
+    policy: &TreasuryPolicy,
+    bundle: &TreasurySignatureBundle,
+) -> Result<bool, TreasuryError> {
+    if bundle.policy_id != policy.id
+        || bundle.threshold != policy.threshold
+        || bundle.signers != policy.signers
+        || bundle.signature.threshold != policy.threshold
+    {
+        return Err(TreasuryError::BundleMismatch(policy.id.clone()));
+    }
+    let payload = hex::decode(&bundle.payload_hex)
+        .map_err(|e| TreasuryError::InvalidPayload(e.to_string()))?;
+    let message = proposal_message(policy, &bundle.proposal_id, &payload);
+    if hex::encode(&message) != bundle.message_hex {
+        return Err(TreasuryError::BundleMismatch(policy.id.clone()));
+    }
+    for share in &bundle.signature.shares {
+        let pk = PublicKey(share.signer_public_key.clone()).to_hex();
+        if !policy.signers.iter().any(|s| s.public_key_hex == pk) {
+            return Ok(false);
+        }
+    }
+    Ok(ThresholdSigner::verify(&bundle.signature, &message)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::mldsa87;
+
+    fn policy(threshold: usize, n: usize) -> (TreasuryPolicy, Vec<SecretKey>) {
+        let keys: Vec<_> = (0..n).map(|_| mldsa87::generate()).collect();
+        let policy = TreasuryPolicy {
+            id: "policy-1".to_string(),
+            name: "Treasury".to_string(),
+            threshold,
+            signers: keys
+                .iter()
+                .enumerate()
+                .map(|(i, (pk, _))| TreasurySigner {
+                    key_id: format!("k{i}"),
+                    public_key_hex: pk.to_hex(),
+                })
+                .collect(),
+            created_at: Utc::now(),
+            orphaned_signers: Vec::new(),
+        };
+        (policy, keys.into_iter().map(|(_, sk)| sk).collect())
+    }
+
+    fn proposal() -> TreasuryProposal {
+        TreasuryProposal {
+            id: "proposal-1".to_string(),
+            policy_id: "policy-1".to_string(),
+            description: "Fund grants".to_string(),
+            payload_hex: hex::encode(b"transfer 100 ZAP"),
+            shares: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn validate_policy_bounds() {
+        let (p, _) = policy(2, 3);
+        assert!(validate_policy(2, &p.signers).is_ok());
+        assert!(validate_policy(0, &p.signers).is_err());
+        assert!(validate_policy(4, &p.signers).is_err());
+        let dup = vec![p.signers[0].clone(), p.signers[0].clone()];
+        assert!(validate_policy(1, &dup).is_err());
+    }
+
+    #[test]
+    fn two_of_three_combines_and_verifies() {
+        let (p, sks) = policy(2, 3);
+        let mut prop = proposal();
+        let share = sign_share(&p, &prop, "k0", sks[0].clone(), Utc::now()).unwrap();
+        add_share(&p, &mut prop, share.clone()).unwrap();
+        assert!(matches!(
+            add_share(&p, &mut prop, share),
+            Err(TreasuryError::AlreadySigned(_))
+        ));
+        assert!(!status(&p, &prop).ready);
+        assert!(combine(&p, &prop).is_err());
+
+        let share = sign_share(&p, &prop, "k2", sks[2].clone(), Utc::now()).unwrap();
+        add_share(&p, &mut prop, share).unwrap();
+        assert!(status(&p, &prop).ready);
+        let bundle = combine(&p, &prop).unwrap();
+        assert!(verify_bundle(&p, &bundle).unwrap());
+
+        // A bundle checked against a different policy is rejected.
+        let (other, _) = policy(2, 3);
+        assert!(verify_bundle(&other, &bundle).is_err());
+    }
+
+    #[test]
+    fn shares_are_bound_to_signer_and_payload() {
+        let (p, sks) = policy(1, 2);
+        let mut prop = proposal();
+        // k0's id with k1's key is refused before signing.
+        assert!(sign_share(&p, &prop, "k0", sks[1].clone(), Utc::now()).is_err());
+        assert!(matches!(
+            sign_share(&p, &prop, "nobody", sks[0].clone(), Utc::now()),
+            Err(TreasuryError::NotASigner(_))
+        ));
+
+        let share = sign_share(&p, &prop, "k0", sks[0].clone(), Utc::now()).unwrap();
+        prop.payload_hex = hex::encode(b"transfer 9999 ZAP");
+        assert!(matches!(
+            add_share(&p, &mut prop, share),
+            Err(TreasuryError::BadShare(_))
+        ));
+    }
+}
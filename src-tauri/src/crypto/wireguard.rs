@@ -0,0 +1,326 @@
+use crate::models::item::{WireGuardItem, WireGuardPeer};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::fmt::Write;
+use thiserror::Error;
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroizing;
+
+/// WireGuard keys (private, public and pre-shared) are all 32 bytes.
+pub const KEY_SIZE: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum WireGuardError {
+    #[error("invalid WireGuard key: {0}")]
+    InvalidKey(String),
+    #[error("invalid WireGuard config: {0}")]
+    InvalidConfig(String),
+}
+
+/// A freshly generated WireGuard interface keypair, base64 encoded as `wg genkey`
+/// / `wg pubkey` print them.
+pub struct WireGuardKeypair {
+    pub private_key: Zeroizing<String>,
+    pub public_key: String,
+}
+
+fn decode_key(b64: &str) -> Result<Zeroizing<[u8; KEY_SIZE]>, WireGuardError> {
+    let bytes = Zeroizing::new(
+        STANDARD
+            .decode(b64.trim())
+            .map_err(|e| WireGuardError::InvalidKey(e.to_string()))?,
+    );
+    let arr: [u8; KEY_SIZE] = bytes.as_slice().try_into().map_err(|_| {
+        WireGuardError::InvalidKey(format!("expected {KEY_SIZE} bytes, got {}", bytes.len()))
+    })?;
+    Ok(Zeroizing::new(arr))
+}
+
+/// Check that `b64` is a well-formed 32-byte WireGuard key.
+pub fn validate_key(b64: &str) -> Result<(), WireGuardError> {
+    decode_key(b64).map(|_| ())
+}
+
+/// Generate a new Curve25519 interface keypair.
+pub fn generate_keypair() -> WireGuardKeypair {
+    keypair_from_secret(StaticSecret::random_from_rng(OsRng))
+}
+
+/// The keypair for a caller-chosen private key, for reproducing RFC 7748
+/// vectors. Audit builds only.
+#[cfg(feature = "audit-vectors")]
+pub fn keypair_from_entropy(entropy: &[u8; KEY_SIZE]) -> WireGuardKeypair {
+    keypair_from_secret(StaticSecret::from(*entropy))
+}
+
+fn keypair_from_secret(secret: StaticSecret) -> WireGuardKeypair {
+    let public = PublicKey::from(&secret);
+    WireGuardKeypair {
+        private_key: Zeroizing::new(STANDARD.encode(secret.to_bytes())),
+        public_key: STANDARD.encode(public.as_bytes()),
+    }
+}
+
+/// Derive the base64 public key for a base64 private key (`wg pubkey`).
+pub fn public_key_from_private(private_b64: &str) -> Result<String, WireGuardError> {
+    let secret = StaticSecret::from(*decode_key(private_b64)?);
+    Ok(STANDARD.encode(PublicKey::from(&secret).as_bytes()))
+}
+
+/// Generate a random pre-shared key (`wg genpsk`).
+pub fn generate_preshared_key() -> Zeroizing<String> {
+    let mut psk = Zeroizing::new([0u8; KEY_SIZE]);
+    OsRng.fill_bytes(psk.as_mut());
+    Zeroizing::new(STANDARD.encode(psk.as_ref()))
+}
+
+/// Render a stored config in `wg-quick` format. This text is exactly what the
+/// WireGuard mobile apps accept via "scan from QR code", so it doubles as the
+/// provisioning QR payload.
+pub fn render_config(wg: &WireGuardItem) -> Zeroizing<String> {
+    let mut out = Zeroizing::new(String::new());
+    // Writing to a String is infallible.
+    let _ = writeln!(out, "[Interface]");
+    let _ = writeln!(out, "PrivateKey = {}", wg.private_key);
+    if !wg.addresses.is_empty() {
+        let _ = writeln!(out, "Address = {}", wg.addresses.join(", "));
+    }
+    if !wg.dns.is_empty() {
+        let _ = writeln!(out, "DNS = {}", wg.dns.join(", "));
+    }
+    if let Some(port) = wg.listen_port {
+        let _ = writeln!(out, "ListenPort = {port}");
+    }
+    if let Some(mtu) = wg.mtu {
+        let _ = writeln!(out, "MTU = {mtu}");
+    }
+    for peer in &wg.peers {
+        let _ = writeln!(out, "\n[Peer]");
+        let _ = writeln!(out, "PublicKey = {}", peer.public_key);
+        if let Some(psk) = &peer.preshared_key {
+            let _ = writeln!(out, "PresharedKey = {psk}");
+        }
+        if !peer.allowed_ips.is_empty() {
+            let _ = writeln!(out, "AllowedIPs = {}", peer.allowed_ips.join(", "));
+        }
+        if let Some(endpoint) = &peer.endpoint {
+            let _ = writeln!(out, "Endpoint = {endpoint}");
+        }
+        if let Some(keepalive) = peer.persistent_keepalive {
+            let _ = writeln!(out, "PersistentKeepalive = {keepalive}");
+        }
+    }
+    out
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_u16(key: &str, value: &str) -> Result<u16, WireGuardError> {
+    value
+        .parse()
+        .map_err(|_| WireGuardError::InvalidConfig(format!("{key} must be a number: {value}")))
+}
+
+/// Parse an existing `wg-quick` config (e.g. one handed out by a VPN provider)
+/// so it can be imported. Keys are validated; unknown directives such as
+/// `PostUp` are rejected rather than silently dropped.
+pub fn parse_config(text: &str) -> Result<WireGuardItem, WireGuardError> {
+    enum Section {
+        None,
+        Interface,
+        Peer,
+    }
+
+    let mut section = Section::None;
+    let mut private_key: Option<String> = None;
+    let mut addresses = Vec::new();
+    let mut dns = Vec::new();
+    let mut listen_port = None;
+    let mut mtu = None;
+    let mut peers: Vec<WireGuardPeer> = Vec::new();
+
+    for raw in text.lines() {
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.eq_ignore_ascii_case("[Interface]") {
+            section = Section::Interface;
+            continue;
+        }
+        if line.eq_ignore_ascii_case("[Peer]") {
+            section = Section::Peer;
+            peers.push(WireGuardPeer {
+                public_key: String::new(),
+                preshared_key: None,
+                endpoint: None,
+                allowed_ips: Vec::new(),
+                persistent_keepalive: None,
+            });
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .map(|(k, v)| (k.trim(), v.trim()))
+            .ok_or_else(|| WireGuardError::InvalidConfig(format!("malformed line: {line}")))?;
+
+        match section {
+            Section::None => {
+                return Err(WireGuardError::InvalidConfig(format!(
+                    "{key} outside of a section"
+                )))
+            }
+            Section::Interface => match key.to_ascii_lowercase().as_str() {
+                "privatekey" => private_key = Some(value.to_string()),
+                "address" => addresses.extend(split_list(value)),
+                "dns" => dns.extend(split_list(value)),
+                "listenport" => listen_port = Some(parse_u16(key, value)?),
+                "mtu" => mtu = Some(parse_u16(key, value)?),
+                _ => {
+                    return Err(WireGuardError::InvalidConfig(format!(
+                        "unsupported [Interface] key: {key}"
+                    )))
+                }
+            },
+            Section::Peer => {
+                // A [Peer] header always pushed an entry above.
+                let peer = peers.last_mut().expect("peer section has an entry");
+                match key.to_ascii_lowercase().as_str() {
+                    "publickey" => peer.public_key = value.to_string(),
+                    "presharedkey" => peer.preshared_key = Some(value.to_string()),
+                    "endpoint" => peer.endpoint = Some(value.to_string()),
+                    "allowedips" => peer.allowed_ips.extend(split_list(value)),
+                    "persistentkeepalive" => {
+                        peer.persistent_keepalive = Some(parse_u16(key, value)?)
+                    }
+                    _ => {
+                        return Err(WireGuardError::InvalidConfig(format!(
+                            "unsupported [Peer] key: {key}"
+                        )))
+                    }
+                }
+            }
+        }
+    }
+
+    let private_key = private_key
+        .ok_or_else(|| WireGuardError::InvalidConfig("missing PrivateKey".to_string()))?;
+    let public_key = public_key_from_private(&private_key)?;
+    for peer in &peers {
+        validate_key(&peer.public_key)?;
+        if let Some(psk) = &peer.preshared_key {
+            validate_key(psk)?;
+        }
+    }
+
+    Ok(WireGuardItem {
+        private_key,
+        public_key,
+        addresses,
+        dns,
+        listen_port,
+        mtu,
+        peers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(kp: &WireGuardKeypair, peer_pub: &str) -> WireGuardItem {
+        WireGuardItem {
+            private_key: kp.private_key.to_string(),
+            public_key: kp.public_key.clone(),
+            addresses: vec!["10.8.0.2/32".to_string(), "fd00::2/128".to_string()],
+            dns: vec!["1.1.1.1".to_string()],
+            listen_port: None,
+            mtu: Some(1420),
+            peers: vec![WireGuardPeer {
+                public_key: peer_pub.to_string(),
+                preshared_key: Some(generate_preshared_key().to_string()),
+                endpoint: Some("vpn.example.com:51820".to_string()),
+                allowed_ips: vec!["0.0.0.0/0".to_string(), "::/0".to_string()],
+                persistent_keepalive: Some(25),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_generate_keypair_public_matches_private() {
+        let kp = generate_keypair();
+        assert_eq!(STANDARD.decode(&kp.public_key).unwrap().len(), KEY_SIZE);
+        assert_eq!(
+            public_key_from_private(&kp.private_key).unwrap(),
+            kp.public_key
+        );
+    }
+
+    #[test]
+    fn test_keypairs_are_unique() {
+        assert_ne!(generate_keypair().public_key, generate_keypair().public_key);
+    }
+
+    #[test]
+    fn test_validate_key_rejects_wrong_length() {
+        assert!(validate_key(&STANDARD.encode([0u8; 16])).is_err());
+        assert!(validate_key("not base64!").is_err());
+        assert!(validate_key(&generate_keypair().public_key).is_ok());
+    }
+
+    #[test]
+    fn test_render_then_parse_roundtrip() {
+        let kp = generate_keypair();
+        let peer = generate_keypair();
+        let item = sample(&kp, &peer.public_key);
+        let text = render_config(&item);
+        assert!(text.starts_with("[Interface]\nPrivateKey = "));
+        assert!(text.contains("AllowedIPs = 0.0.0.0/0, ::/0"));
+
+        let parsed = parse_config(&text).unwrap();
+        assert_eq!(parsed.public_key, kp.public_key);
+        assert_eq!(parsed.addresses, item.addresses);
+        assert_eq!(parsed.mtu, Some(1420));
+        assert_eq!(parsed.peers.len(), 1);
+        assert_eq!(parsed.peers[0].public_key, peer.public_key);
+        assert_eq!(parsed.peers[0].preshared_key, item.peers[0].preshared_key);
+        assert_eq!(parsed.peers[0].persistent_keepalive, Some(25));
+    }
+
+    #[test]
+    fn test_parse_rejects_missing_private_key_and_unknown_directives() {
+        let peer = generate_keypair().public_key;
+        assert!(parse_config(&format!("[Peer]\nPublicKey = {peer}\n")).is_err());
+
+        let kp = generate_keypair();
+        let text = format!(
+            "[Interface]\nPrivateKey = {}\nPostUp = iptables -A FORWARD\n",
+            kp.private_key.as_str()
+        );
+        assert!(matches!(
+            parse_config(&text),
+            Err(WireGuardError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_peer_key() {
+        let kp = generate_keypair();
+        let text = format!(
+            "[Interface]\nPrivateKey = {}\n\n[Peer]\nPublicKey = AAAA\n",
+            kp.private_key.as_str()
+        );
+        assert!(matches!(
+            parse_config(&text),
+            Err(WireGuardError::InvalidKey(_))
+        ));
+    }
+}
@@ -0,0 +1,448 @@
+//! BIP-174 PSBT (version 0) decoding: the unsigned transaction plus the
+//! amounts each input's UTXO record carries, which is what the fee needs.
+
+use super::{malformed, DecodeError};
+use crate::models::tx_review::{PsbtInput, PsbtOutput, PsbtSummary};
+use sha2::{Digest, Sha256};
+
+pub const MAGIC: &[u8] = b"psbt\xff";
+
+const GLOBAL_UNSIGNED_TX: u8 = 0x00;
+const GLOBAL_VERSION: u8 = 0xfb;
+const IN_NON_WITNESS_UTXO: u8 = 0x00;
+const IN_WITNESS_UTXO: u8 = 0x01;
+
+/// Outputs paying more than this share of the amount sent as fee are flagged.
+const HIGH_FEE_PERCENT: u64 = 10;
+
+fn err(reason: impl Into<String>) -> DecodeError {
+    malformed("PSBT", reason)
+}
+
+/// A raw key and value from a PSBT map.
+type MapEntry<'a> = (&'a [u8], &'a [u8]);
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Reader { buf, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos == self.buf.len()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.buf.len())
+            .ok_or_else(|| err("unexpected end of data"))?;
+        let out = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, DecodeError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn compact_size(&mut self) -> Result<u64, DecodeError> {
+        Ok(match self.u8()? {
+            0xfd => u16::from_le_bytes(self.take(2)?.try_into().unwrap()) as u64,
+            0xfe => self.u32()? as u64,
+            0xff => self.u64()?,
+            n => n as u64,
+        })
+    }
+
+    fn var_bytes(&mut self) -> Result<&'a [u8], DecodeError> {
+        let len = self.compact_size()?;
+        self.take(usize::try_from(len).map_err(|_| err("length overflows"))?)
+    }
+
+    /// A count of items each at least `min_item` bytes long, bounded by what
+    /// is left so a hostile count cannot force a huge allocation.
+    fn count(&mut self, min_item: usize) -> Result<usize, DecodeError> {
+        let n = self.compact_size()?;
+        let left = (self.buf.len() - self.pos) as u64;
+        if n.saturating_mul(min_item as u64) > left {
+            return Err(err("item count exceeds the data"));
+        }
+        Ok(n as usize)
+    }
+
+    /// One key-value map, up to its `0x00` terminator.
+    fn map(&mut self) -> Result<Vec<MapEntry<'a>>, DecodeError> {
+        let mut entries = Vec::new();
+        loop {
+            let key = self.var_bytes()?;
+            if key.is_empty() {
+                return Ok(entries);
+            }
+            let value = self.var_bytes()?;
+            if entries.iter().any(|(k, _)| *k == key) {
+                return Err(err("duplicate key"));
+            }
+            entries.push((key, value));
+        }
+    }
+}
+
+struct TxIn<'a> {
+    txid: &'a [u8],
+    vout: u32,
+    script_sig: &'a [u8],
+    sequence: u32,
+}
+
+struct TxOut<'a> {
+    value: u64,
+    script: &'a [u8],
+}
+
+struct Tx<'a> {
+    version: i32,
+    inputs: Vec<TxIn<'a>>,
+    outputs: Vec<TxOut<'a>>,
+    has_witness: bool,
+    lock_time: u32,
+}
+
+fn read_tx_out<'a>(r: &mut Reader<'a>) -> Result<TxOut<'a>, DecodeError> {
+    Ok(TxOut {
+        value: r.u64()?,
+        script: r.var_bytes()?,
+    })
+}
+
+fn parse_tx(bytes: &[u8]) -> Result<Tx<'_>, DecodeError> {
+    let mut r = Reader::new(bytes);
+    let version = r.u32()? as i32;
+    let has_witness = bytes.get(4..6) == Some(&[0x00, 0x01]);
+    if has_witness {
+        r.take(2)?;
+    }
+    let mut inputs = Vec::new();
+    for _ in 0..r.count(41)? {
+        inputs.push(TxIn {
+            txid: r.take(32)?,
+            vout: r.u32()?,
+            script_sig: r.var_bytes()?,
+            sequence: r.u32()?,
+        });
+    }
+    let mut outputs = Vec::new();
+    for _ in 0..r.count(9)? {
+        outputs.push(read_tx_out(&mut r)?);
+    }
+    if has_witness {
+        for _ in 0..inputs.len() {
+            for _ in 0..r.count(1)? {
+                r.var_bytes()?;
+            }
+        }
+    }
+    let lock_time = r.u32()?;
+    if !r.is_empty() {
+        return Err(err("trailing bytes after transaction"));
+    }
+    Ok(Tx {
+        version,
+        inputs,
+        outputs,
+        has_witness,
+        lock_time,
+    })
+}
+
+/// Transaction id: double SHA-256 of the serialization without witnesses.
+fn txid(tx: &Tx<'_>) -> [u8; 32] {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&tx.version.to_le_bytes());
+    push_compact_size(&mut buf, tx.inputs.len() as u64);
+    for input in &tx.inputs {
+        buf.extend_from_slice(input.txid);
+        buf.extend_from_slice(&input.vout.to_le_bytes());
+        push_compact_size(&mut buf, input.script_sig.len() as u64);
+        buf.extend_from_slice(input.script_sig);
+        buf.extend_from_slice(&input.sequence.to_le_bytes());
+    }
+    push_compact_size(&mut buf, tx.outputs.len() as u64);
+    for output in &tx.outputs {
+        buf.extend_from_slice(&output.value.to_le_bytes());
+        push_compact_size(&mut buf, output.script.len() as u64);
+        buf.extend_from_slice(output.script);
+    }
+    buf.extend_from_slice(&tx.lock_time.to_le_bytes());
+    Sha256::digest(Sha256::digest(&buf)).into()
+}
+
+fn push_compact_size(buf: &mut Vec<u8>, n: u64) {
+    match n {
+        0..=0xfc => buf.push(n as u8),
+        0xfd..=0xffff => {
+            buf.push(0xfd);
+            buf.extend_from_slice(&(n as u16).to_le_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            buf.push(0xfe);
+            buf.extend_from_slice(&(n as u32).to_le_bytes());
+        }
+        _ => {
+            buf.push(0xff);
+            buf.extend_from_slice(&n.to_le_bytes());
+        }
+    }
+}
+
+/// Txids are shown byte-reversed, as block explorers do.
+fn display_txid(txid: &[u8]) -> String {
+    let mut reversed = txid.to_vec();
+    reversed.reverse();
+    hex::encode(reversed)
+}
+
+pub fn script_type(script: &[u8]) -> &'static str {
+    match script {
+        [0x51, 0x20, rest @ ..] if rest.len() == 32 => "p2tr",
+        [0x00, 0x14, rest @ ..] if rest.len() == 20 => "p2wpkh",
+        [0x00, 0x20, rest @ ..] if rest.len() == 32 => "p2wsh",
+        [0x76, 0xa9, 0x14, rest @ .., 0x88, 0xac] if rest.len() == 20 => "p2pkh",
+        [0xa9, 0x14, rest @ .., 0x87] if rest.len() == 20 => "p2sh",
+        [0x6a, ..] => "op_return",
+        _ => "unknown",
+    }
+}
+
+/// Amount and script of the output an input spends, from whichever UTXO
+/// record it carries. A full previous transaction must hash to the txid the
+/// input names, or its amount could be anything.
+fn spent_output(
+    index: usize,
+    input: &TxIn<'_>,
+    map: &[MapEntry<'_>],
+) -> Result<Option<(u64, &'static str)>, DecodeError> {
+    let get = |t: u8| map.iter().find(|(k, _)| *k == [t]).map(|(_, v)| *v);
+    if let Some(prev) = get(IN_NON_WITNESS_UTXO) {
+        let prev = parse_tx(prev)?;
+        if txid(&prev) != input.txid {
+            return Err(err(format!(
+                "input {index}: previous transaction does not match its txid"
+            )));
+        }
+        let out = prev
+            .outputs
+            .get(input.vout as usize)
+            .ok_or_else(|| err(format!("input {index}: vout is out of range")))?;
+        return Ok(Some((out.value, script_type(out.script))));
+    }
+    if let Some(utxo) = get(IN_WITNESS_UTXO) {
+        let mut r = Reader::new(utxo);
+        let out = read_tx_out(&mut r)?;
+        if !r.is_empty() {
+            return Err(err(format!(
+                "input {index}: trailing bytes in witness UTXO"
+            )));
+        }
+        return Ok(Some((out.value, script_type(out.script))));
+    }
+    Ok(None)
+}
+
+pub fn decode(payload: &[u8]) -> Result<(PsbtSummary, Vec<String>), DecodeError> {
+    let body = payload
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| err("missing PSBT magic"))?;
+    let mut r = Reader::new(body);
+    let global = r.map()?;
+    if let Some((_, version)) = global.iter().find(|(k, _)| *k == [GLOBAL_VERSION]) {
+        if *version != [0, 0, 0, 0] {
+            return Err(err("only PSBT version 0 is supported"));
+        }
+    }
+    let unsigned = global
+        .iter()
+        .find(|(k, _)| *k == [GLOBAL_UNSIGNED_TX])
+        .map(|(_, v)| *v)
+        .ok_or_else(|| err("missing unsigned transaction"))?;
+    let tx = parse_tx(unsigned)?;
+    if tx.has_witness || tx.inputs.iter().any(|i| !i.script_sig.is_empty()) {
+        return Err(err("unsigned transaction carries signatures"));
+    }
+
+    let mut warnings = Vec::new();
+    let mut inputs = Vec::with_capacity(tx.inputs.len());
+    let mut total_in = Some(0u64);
+    for (index, input) in tx.inputs.iter().enumerate() {
+        let map = r.map()?;
+        let spent = spent_output(index, input, &map)?;
+        match spent {
+            Some((amount, _)) => {
+                total_in = total_in
+                    .map(|t| {
+                        t.checked_add(amount)
+                            .ok_or_else(|| err("input total overflows"))
+                    })
+                    .transpose()?;
+            }
+            None => {
+                total_in = None;
+                warnings.push(format!(
+                    "input {index} has no UTXO record; the fee cannot be computed"
+                ));
+            }
+        }
+        inputs.push(PsbtInput {
+            txid: display_txid(input.txid),
+            vout: input.vout,
+            sequence: input.sequence,
+            amount_sats: spent.map(|(amount, _)| amount),
+            script_type: spent.map(|(_, ty)| ty.to_string()),
+        });
+    }
+    let mut outputs = Vec::with_capacity(tx.outputs.len());
+    let mut total_out = 0u64;
+    for output in &tx.outputs {
+        r.map()?;
+        total_out = total_out
+            .checked_add(output.value)
+            .ok_or_else(|| err("output total overflows"))?;
+        outputs.push(PsbtOutput {
+            amount_sats: output.value,
+            script_type: script_type(output.script).to_string(),
+            script_hex: hex::encode(output.script),
+        });
+    }
+    if !r.is_empty() {
+        return Err(err("trailing bytes after the output maps"));
+    }
+
+    let fee = match total_in {
+        Some(total_in) => Some(
+            total_in
+                .checked_sub(total_out)
+                .ok_or_else(|| err("outputs spend more than the inputs hold"))?,
+        ),
+        None => None,
+    };
+    if let Some(fee) = fee {
+        if fee.saturating_mul(100) > total_out.saturating_mul(HIGH_FEE_PERCENT) {
+            warnings.push(format!(
+                "fee of {fee} sats is more than {HIGH_FEE_PERCENT}% of the amount sent"
+            ));
+        }
+    }
+    if outputs.iter().any(|o| o.script_type == "unknown") {
+        warnings.push("an output pays to a non-standard script".to_string());
+    }
+    Ok((
+        PsbtSummary {
+            tx_version: tx.version,
+            lock_time: tx.lock_time,
+            inputs,
+            outputs,
+            total_in_sats: total_in,
+            total_out_sats: total_out,
+            fee_sats: fee,
+        },
+        warnings,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p2wpkh(byte: u8) -> Vec<u8> {
+        let mut script = vec![0x00, 0x14];
+        script.extend_from_slice(&[byte; 20]);
+        script
+    }
+
+    fn unsigned_tx(outputs: &[(u64, Vec<u8>)]) -> Vec<u8> {
+        let mut tx = 2u32.to_le_bytes().to_vec();
+        tx.push(1);
+        tx.extend_from_slice(&[0x11; 32]);
+        tx.extend_from_slice(&0u32.to_le_bytes());
+        tx.push(0);
+        tx.extend_from_slice(&0xffff_fffdu32.to_le_bytes());
+        tx.push(outputs.len() as u8);
+        for (value, script) in outputs {
+            tx.extend_from_slice(&value.to_le_bytes());
+            tx.push(script.len() as u8);
+            tx.extend_from_slice(script);
+        }
+        tx.extend_from_slice(&0u32.to_le_bytes());
+        tx
+    }
+
+    fn psbt(outputs: &[(u64, Vec<u8>)], utxo_value: Option<u64>) -> Vec<u8> {
+        let tx = unsigned_tx(outputs);
+        let mut out = MAGIC.to_vec();
+        out.extend_from_slice(&[1, GLOBAL_UNSIGNED_TX]);
+        push_compact_size(&mut out, tx.len() as u64);
+        out.extend_from_slice(&tx);
+        out.push(0);
+        if let Some(value) = utxo_value {
+            let mut utxo = value.to_le_bytes().to_vec();
+            let script = p2wpkh(0x22);
+            utxo.push(script.len() as u8);
+            utxo.extend_from_slice(&script);
+            out.extend_from_slice(&[1, IN_WITNESS_UTXO]);
+            out.push(utxo.len() as u8);
+            out.extend_from_slice(&utxo);
+        }
+        out.push(0);
+        out.resize(out.len() + outputs.len(), 0);
+        out
+    }
+
+    #[test]
+    fn test_decodes_amounts_and_fee() {
+        let payload = psbt(
+            &[(70_000, p2wpkh(0x33)), (29_000, p2wpkh(0x44))],
+            Some(100_000),
+        );
+        let (summary, warnings) = decode(&payload).unwrap();
+        assert_eq!(summary.tx_version, 2);
+        assert_eq!(summary.inputs[0].txid, hex::encode([0x11; 32]));
+        assert_eq!(summary.inputs[0].amount_sats, Some(100_000));
+        assert_eq!(summary.inputs[0].script_type.as_deref(), Some("p2wpkh"));
+        assert_eq!(summary.outputs[1].script_type, "p2wpkh");
+        assert_eq!(summary.total_out_sats, 99_000);
+        assert_eq!(summary.fee_sats, Some(1_000));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_flags_missing_utxo_and_high_fee() {
+        let (summary, warnings) = decode(&psbt(&[(1_000, p2wpkh(0x33))], None)).unwrap();
+        assert_eq!(summary.fee_sats, None);
+        assert!(warnings[0].contains("no UTXO record"));
+
+        let (_, warnings) = decode(&psbt(&[(1_000, p2wpkh(0x33))], Some(50_000))).unwrap();
+        assert!(warnings[0].contains("more than 10%"));
+    }
+
+    #[test]
+    fn test_rejects_overspend_and_truncation() {
+        let payload = psbt(&[(200_000, p2wpkh(0x33))], Some(100_000));
+        assert!(decode(&payload).is_err());
+        let payload = psbt(&[(1_000, p2wpkh(0x33))], Some(2_000));
+        assert!(decode(&payload[..payload.len() - 1]).is_err());
+        assert!(decode(b"psbt").is_err());
+    }
+}
@@ -0,0 +1,354 @@
+//! Cosmos SDK `SignDoc` (SIGN_MODE_DIRECT) decoding. The body's messages
+//! are listed by type URL; a handful of common ones have their fields
+//! decoded, the rest are shown as bytes with a warning.
+
+use super::{malformed, DecodeError};
+use crate::models::tx_review::{CosmosCoin, CosmosMessage, CosmosSignDocSummary, DecodedField};
+
+fn err(reason: impl Into<String>) -> DecodeError {
+    malformed("Cosmos SignDoc", reason)
+}
+
+enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+/// Every `(field number, value)` of one protobuf message, in wire order.
+fn fields(buf: &[u8]) -> Result<Vec<(u64, Value<'_>)>, DecodeError> {
+    let mut out = Vec::new();
+    let mut pos = 0;
+    let varint = |pos: &mut usize| -> Result<u64, DecodeError> {
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = *buf.get(*pos).ok_or_else(|| err("unexpected end of data"))?;
+            *pos += 1;
+            n |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(err("varint is too long"))
+    };
+    while pos < buf.len() {
+        let key = varint(&mut pos)?;
+        let skip = |pos: &mut usize, n: usize| -> Result<(), DecodeError> {
+            *pos = pos
+                .checked_add(n)
+                .filter(|end| *end <= buf.len())
+                .ok_or_else(|| err("unexpected end of data"))?;
+            Ok(())
+        };
+        let value = match key & 7 {
+            0 => Value::Varint(varint(&mut pos)?),
+            1 => {
+                skip(&mut pos, 8)?;
+                Value::Fixed
+            }
+            2 => {
+                let len =
+                    usize::try_from(varint(&mut pos)?).map_err(|_| err("length overflows"))?;
+                let start = pos;
+                skip(&mut pos, len)?;
+                Value::Bytes(&buf[start..pos])
+            }
+            5 => {
+                skip(&mut pos, 4)?;
+                Value::Fixed
+            }
+            _ => return Err(err("unsupported wire type")),
+        };
+        out.push((key >> 3, value));
+    }
+    Ok(out)
+}
+
+fn bytes_of<'a>(fields: &[(u64, Value<'a>)], number: u64) -> Vec<&'a [u8]> {
+    fields
+        .iter()
+        .filter_map(|(n, v)| match v {
+            Value::Bytes(b) if *n == number => Some(*b),
+            _ => None,
+        })
+        .collect()
+}
+
+fn single_bytes<'a>(fields: &[(u64, Value<'a>)], number: u64) -> &'a [u8] {
+    bytes_of(fields, number).last().copied().unwrap_or_default()
+}
+
+fn string_of(fields: &[(u64, Value<'_>)], number: u64) -> Result<String, DecodeError> {
+    std::str::from_utf8(single_bytes(fields, number))
+        .map(str::to_string)
+        .map_err(|_| err(format!("field {number} is not UTF-8")))
+}
+
+fn varint_of(fields: &[(u64, Value<'_>)], number: u64) -> u64 {
+    fields
+        .iter()
+        .filter_map(|(n, v)| match v {
+            Value::Varint(x) if *n == number => Some(*x),
+            _ => None,
+        })
+        .next_back()
+        .unwrap_or_default()
+}
+
+fn coin(buf: &[u8]) -> Result<CosmosCoin, DecodeError> {
+    let f = fields(buf)?;
+    Ok(CosmosCoin {
+        denom: string_of(&f, 1)?,
+        amount: string_of(&f, 2)?,
+    })
+}
+
+fn coins(fields: &[(u64, Value<'_>)], number: u64) -> Result<Vec<CosmosCoin>, DecodeError> {
+    bytes_of(fields, number).into_iter().map(coin).collect()
+}
+
+fn show_coins(coins: &[CosmosCoin]) -> String {
+    coins
+        .iter()
+        .map(|c| format!("{}{}", c.amount, c.denom))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Text,
+    Coins,
+}
+
+/// Field number, name and shape of each field shown for a message.
+type Layout = &'static [(u64, &'static str, Field)];
+
+/// Messages whose fields are decoded, by type URL.
+const KNOWN_MESSAGES: &[(&str, Layout)] = &[
+    (
+        "/cosmos.bank.v1beta1.MsgSend",
+        &[
+            (1, "from_address", Field::Text),
+            (2, "to_address", Field::Text),
+            (3, "amount", Field::Coins),
+        ],
+    ),
+    (
+        "/cosmos.staking.v1beta1.MsgDelegate",
+        &[
+            (1, "delegator_address", Field::Text),
+            (2, "validator_address", Field::Text),
+            (3, "amount", Field::Coins),
+        ],
+    ),
+    (
+        "/cosmos.staking.v1beta1.MsgUndelegate",
+        &[
+            (1, "delegator_address", Field::Text),
+            (2, "validator_address", Field::Text),
+            (3, "amount", Field::Coins),
+        ],
+    ),
+    (
+        "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward",
+        &[
+            (1, "delegator_address", Field::Text),
+            (2, "validator_address", Field::Text),
+        ],
+    ),
+    (
+        "/ibc.applications.transfer.v1.MsgTransfer",
+        &[
+            (1, "source_port", Field::Text),
+            (2, "source_channel", Field::Text),
+            (3, "token", Field::Coins),
+            (4, "sender", Field::Text),
+            (5, "receiver", Field::Text),
+        ],
+    ),
+    (
+        "/cosmwasm.wasm.v1.MsgExecuteContract",
+        &[
+            (1, "sender", Field::Text),
+            (2, "contract", Field::Text),
+            (3, "msg", Field::Text),
+            (5, "funds", Field::Coins),
+        ],
+    ),
+];
+
+/// Messages that hand authority over the account to someone else.
+const DELEGATING_MESSAGES: &[&str] = &[
+    "/cosmos.authz.v1beta1.MsgGrant",
+    "/cosmos.feegrant.v1beta1.MsgGrantAllowance",
+];
+
+fn message(any: &[u8], warnings: &mut Vec<String>) -> Result<CosmosMessage, DecodeError> {
+    let f = fields(any)?;
+    let type_url = string_of(&f, 1)?;
+    let value = single_bytes(&f, 2);
+    let mut decoded = Vec::new();
+    match KNOWN_MESSAGES.iter().find(|(url, _)| *url == type_url) {
+        Some((_, layout)) => {
+            let mf = fields(value)?;
+            for (number, name, shape) in layout.iter() {
+                let value = match shape {
+                    Field::Text => string_of(&mf, *number)?,
+                    Field::Coins => show_coins(&coins(&mf, *number)?),
+                };
+                decoded.push(DecodedField {
+                    name: name.to_string(),
+                    value,
+                });
+            }
+        }
+        None => warnings.push(format!("message {type_url} is not decoded")),
+    }
+    if DELEGATING_MESSAGES.contains(&type_url.as_str()) {
+        warnings.push(format!("{type_url} gives another account authority"));
+    }
+    Ok(CosmosMessage {
+        type_url,
+        fields: decoded,
+        value_hex: hex::encode(value),
+    })
+}
+
+pub fn decode(payload: &[u8]) -> Result<(CosmosSignDocSummary, Vec<String>), DecodeError> {
+    let doc = fields(payload)?;
+    let body = fields(single_bytes(&doc, 1))?;
+    let auth_info = fields(single_bytes(&doc, 2))?;
+    let chain_id = string_of(&doc, 3)?;
+    let mut warnings = Vec::new();
+    if chain_id.is_empty() {
+        return Err(err("missing chain id"));
+    }
+
+    let messages = bytes_of(&body, 1)
+        .into_iter()
+        .map(|any| message(any, &mut warnings))
+        .collect::<Result<Vec<_>, _>>()?;
+    if messages.is_empty() {
+        return Err(err("no messages"));
+    }
+    if body.iter().any(|(n, _)| *n == 1023 || *n == 2047) {
+        warnings.push("transaction carries extension options".to_string());
+    }
+
+    let sequence = bytes_of(&auth_info, 1)
+        .first()
+        .map(|signer| fields(signer).map(|f| varint_of(&f, 3)))
+        .transpose()?;
+    let fee = fields(single_bytes(&auth_info, 2))?;
+    let granter = string_of(&fee, 4)?;
+    if !granter.is_empty() {
+        warnings.push(format!("fee is paid by {granter}"));
+    }
+
+    Ok((
+        CosmosSignDocSummary {
+            chain_id,
+            account_number: varint_of(&doc, 4),
+            sequence,
+            messages,
+            memo: string_of(&body, 2)?,
+            fee: coins(&fee, 1)?,
+            gas_limit: varint_of(&fee, 2),
+        },
+        warnings,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(buf: &mut Vec<u8>, number: u64, wire: u64) {
+        varint(buf, (number << 3) | wire);
+    }
+
+    fn varint(buf: &mut Vec<u8>, mut n: u64) {
+        while n >= 0x80 {
+            buf.push((n as u8) | 0x80);
+            n >>= 7;
+        }
+        buf.push(n as u8);
+    }
+
+    fn bytes_field(buf: &mut Vec<u8>, number: u64, value: &[u8]) {
+        tag(buf, number, 2);
+        varint(buf, value.len() as u64);
+        buf.extend_from_slice(value);
+    }
+
+    fn varint_field(buf: &mut Vec<u8>, number: u64, value: u64) {
+        tag(buf, number, 0);
+        varint(buf, value);
+    }
+
+    fn coin_bytes(denom: &str, amount: &str) -> Vec<u8> {
+        let mut c = Vec::new();
+        bytes_field(&mut c, 1, denom.as_bytes());
+        bytes_field(&mut c, 2, amount.as_bytes());
+        c
+    }
+
+    fn sign_doc(type_url: &str, msg: &[u8]) -> Vec<u8> {
+        let mut any = Vec::new();
+        bytes_field(&mut any, 1, type_url.as_bytes());
+        bytes_field(&mut any, 2, msg);
+        let mut body = Vec::new();
+        bytes_field(&mut body, 1, &any);
+        bytes_field(&mut body, 2, b"rent");
+
+        let mut signer = Vec::new();
+        varint_field(&mut signer, 3, 12);
+        let mut fee = Vec::new();
+        bytes_field(&mut fee, 1, &coin_bytes("uatom", "5000"));
+        varint_field(&mut fee, 2, 200_000);
+        let mut auth = Vec::new();
+        bytes_field(&mut auth, 1, &signer);
+        bytes_field(&mut auth, 2, &fee);
+
+        let mut doc = Vec::new();
+        bytes_field(&mut doc, 1, &body);
+        bytes_field(&mut doc, 2, &auth);
+        bytes_field(&mut doc, 3, b"cosmoshub-4");
+        varint_field(&mut doc, 4, 42);
+        doc
+    }
+
+    #[test]
+    fn test_decodes_msg_send() {
+        let mut msg = Vec::new();
+        bytes_field(&mut msg, 1, b"cosmos1from");
+        bytes_field(&mut msg, 2, b"cosmos1to");
+        bytes_field(&mut msg, 3, &coin_bytes("uatom", "1000000"));
+        let (summary, warnings) = decode(&sign_doc("/cosmos.bank.v1beta1.MsgSend", &msg)).unwrap();
+        assert_eq!(summary.chain_id, "cosmoshub-4");
+        assert_eq!(summary.account_number, 42);
+        assert_eq!(summary.sequence, Some(12));
+        assert_eq!(summary.memo, "rent");
+        assert_eq!(summary.gas_limit, 200_000);
+        assert_eq!(summary.fee[0].amount, "5000");
+        let fields = &summary.messages[0].fields;
+        assert_eq!(fields[1].value, "cosmos1to");
+        assert_eq!(fields[2].value, "1000000uatom");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_flags_unknown_and_delegating_messages() {
+        let (summary, warnings) = decode(&sign_doc("/cosmos.authz.v1beta1.MsgGrant", b"")).unwrap();
+        assert!(summary.messages[0].fields.is_empty());
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[1].contains("gives another account authority"));
+    }
+
+    #[test]
+    fn test_rejects_truncated_doc() {
+        let doc = sign_doc("/cosmos.bank.v1beta1.MsgSend", b"");
+        assert!(decode(&doc[..doc.len() - 3]).is_err());
+    }
+}
@@ -0,0 +1,565 @@
+//! Unsigned Ethereum transactions (legacy, EIP-2930 and EIP-1559) and their
+//! calldata. Selectors are looked up in a short built-in list and then in the
+//! local [`AbiRegistry`]; arguments of static types, `bytes` and `string` are
+//! decoded, anything else is shown as its raw head word.
+
+use super::{malformed, DecodeError};
+use crate::models::tx_review::{
+    AbiEntry, AbiRegistry, DecodedField, EthereumCall, EthereumTxSummary,
+};
+
+/// Selectors that are always known. The local registry cannot redefine them.
+pub const BUILTIN_SELECTORS: &[(&str, &str)] = &[
+    ("0xa9059cbb", "transfer(address,uint256)"),
+    ("0x095ea7b3", "approve(address,uint256)"),
+    ("0x23b872dd", "transferFrom(address,address,uint256)"),
+    ("0x42842e0e", "safeTransferFrom(address,address,uint256)"),
+    ("0xa22cb465", "setApprovalForAll(address,bool)"),
+    ("0xd0e30db0", "deposit()"),
+    ("0x2e1a7d4d", "withdraw(uint256)"),
+];
+
+/// RLP nesting deeper than this is refused; an access list needs three.
+const MAX_RLP_DEPTH: usize = 8;
+
+fn err(reason: impl Into<String>) -> DecodeError {
+    malformed("Ethereum transaction", reason)
+}
+
+enum Rlp<'a> {
+    Bytes(&'a [u8]),
+    List(Vec<Rlp<'a>>),
+}
+
+fn be_len(bytes: &[u8]) -> Result<usize, DecodeError> {
+    if bytes.len() > 4 || bytes.first() == Some(&0) {
+        return Err(err("non-canonical RLP length"));
+    }
+    Ok(bytes.iter().fold(0usize, |n, b| (n << 8) | *b as usize))
+}
+
+fn split(buf: &[u8], at: usize) -> Result<(&[u8], &[u8]), DecodeError> {
+    if at > buf.len() {
+        return Err(err("unexpected end of data"));
+    }
+    Ok(buf.split_at(at))
+}
+
+/// One RLP item from the front of `buf`, and what follows it.
+fn rlp_item(buf: &[u8], depth: usize) -> Result<(Rlp<'_>, &[u8]), DecodeError> {
+    if depth > MAX_RLP_DEPTH {
+        return Err(err("RLP nested too deeply"));
+    }
+    let (&prefix, rest) = buf
+        .split_first()
+        .ok_or_else(|| err("unexpected end of data"))?;
+    match prefix {
+        0x00..=0x7f => Ok((Rlp::Bytes(&buf[..1]), rest)),
+        0x80..=0xb7 => {
+            let (bytes, rest) = split(rest, (prefix - 0x80) as usize)?;
+            if bytes.len() == 1 && bytes[0] < 0x80 {
+                return Err(err("non-canonical RLP byte"));
+            }
+            Ok((Rlp::Bytes(bytes), rest))
+        }
+        0xb8..=0xbf => {
+            let (len, rest) = split(rest, (prefix - 0xb7) as usize)?;
+            let (bytes, rest) = split(rest, be_len(len)?)?;
+            Ok((Rlp::Bytes(bytes), rest))
+        }
+        0xc0..=0xf7 => {
+            let (payload, rest) = split(rest, (prefix - 0xc0) as usize)?;
+            Ok((Rlp::List(rlp_list(payload, depth)?), rest))
+        }
+        0xf8..=0xff => {
+            let (len, rest) = split(rest, (prefix - 0xf7) as usize)?;
+            let (payload, rest) = split(rest, be_len(len)?)?;
+            Ok((Rlp::List(rlp_list(payload, depth)?), rest))
+        }
+    }
+}
+
+fn rlp_list(mut payload: &[u8], depth: usize) -> Result<Vec<Rlp<'_>>, DecodeError> {
+    let mut items = Vec::new();
+    while !payload.is_empty() {
+        let (item, rest) = rlp_item(payload, depth + 1)?;
+        items.push(item);
+        payload = rest;
+    }
+    Ok(items)
+}
+
+fn bytes<'a>(item: &Rlp<'a>, field: &str) -> Result<&'a [u8], DecodeError> {
+    match item {
+        Rlp::Bytes(b) => Ok(b),
+        Rlp::List(_) => Err(err(format!("{field} must be a byte string"))),
+    }
+}
+
+fn uint<'a>(item: &Rlp<'a>, field: &str) -> Result<&'a [u8], DecodeError> {
+    let b = bytes(item, field)?;
+    if b.len() > 32 {
+        return Err(err(format!("{field} is wider than 256 bits")));
+    }
+    if b.first() == Some(&0) {
+        return Err(err(format!("{field} has leading zeros")));
+    }
+    Ok(b)
+}
+
+fn u64_field(item: &Rlp<'_>, field: &str) -> Result<u64, DecodeError> {
+    let b = uint(item, field)?;
+    if b.len() > 8 {
+        return Err(err(format!("{field} does not fit in 64 bits")));
+    }
+    Ok(b.iter().fold(0u64, |n, x| (n << 8) | *x as u64))
+}
+
+fn u128_of(be: &[u8]) -> Option<u128> {
+    let be = &be[be.iter().take_while(|b| **b == 0).count()..];
+    (be.len() <= 16).then(|| be.iter().fold(0u128, |n, x| (n << 8) | *x as u128))
+}
+
+/// Big-endian unsigned integer of any width as decimal.
+pub(crate) fn decimal(be: &[u8]) -> String {
+    let mut digits = vec![0u8];
+    for &byte in be {
+        let mut carry = byte as u32;
+        for d in digits.iter_mut() {
+            let v = *d as u32 * 256 + carry;
+            *d = (v % 10) as u8;
+            carry = v / 10;
+        }
+        while carry > 0 {
+            digits.push((carry % 10) as u8);
+            carry /= 10;
+        }
+    }
+    digits.iter().rev().map(|d| (b'0' + d) as char).collect()
+}
+
+fn address(item: &Rlp<'_>) -> Result<Option<String>, DecodeError> {
+    match bytes(item, "to")? {
+        [] => Ok(None),
+        b if b.len() == 20 => Ok(Some(format!("0x{}", hex::encode(b)))),
+        _ => Err(err("to must be empty or 20 bytes")),
+    }
+}
+
+/// Split `name(type,type)` into its name and argument types.
+pub fn parse_signature(signature: &str) -> Result<(String, Vec<String>), DecodeError> {
+    let bad = || DecodeError::Registry(format!("not a function signature: {signature}"));
+    let (name, rest) = signature.split_once('(').ok_or_else(bad)?;
+    let inner = rest.strip_suffix(')').ok_or_else(bad)?;
+    if name.is_empty()
+        || name.starts_with(|c: char| c.is_ascii_digit())
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+        || inner.chars().any(char::is_whitespace)
+    {
+        return Err(bad());
+    }
+    let mut types = Vec::new();
+    let (mut depth, mut start) = (0i32, 0usize);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                types.push(inner[start..i].to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        if depth < 0 {
+            return Err(bad());
+        }
+    }
+    if depth != 0 {
+        return Err(bad());
+    }
+    if !inner.is_empty() {
+        types.push(inner[start..].to_string());
+    }
+    if types.iter().any(String::is_empty) {
+        return Err(bad());
+    }
+    Ok((name.to_string(), types))
+}
+
+/// Check and normalise a registry entry.
+pub fn registry_entry(selector: &str, signature: &str) -> Result<AbiEntry, DecodeError> {
+    let raw = selector
+        .trim()
+        .trim_start_matches("0x")
+        .to_ascii_lowercase();
+    let bytes = hex::decode(&raw).map_err(|e| DecodeError::Registry(e.to_string()))?;
+    if bytes.len() != 4 {
+        return Err(DecodeError::Registry(
+            "a selector is four bytes".to_string(),
+        ));
+    }
+    let selector = format!("0x{raw}");
+    if BUILTIN_SELECTORS.iter().any(|(s, _)| *s == selector) {
+        return Err(DecodeError::Registry(format!(
+            "{selector} is built in and cannot be redefined"
+        )));
+    }
+    parse_signature(signature)?;
+    Ok(AbiEntry {
+        selector,
+        signature: signature.to_string(),
+    })
+}
+
+pub fn lookup(selector: &str, registry: &AbiRegistry) -> Option<String> {
+    BUILTIN_SELECTORS
+        .iter()
+        .find(|(s, _)| *s == selector)
+        .map(|(_, sig)| sig.to_string())
+        .or_else(|| {
+            registry
+                .entries
+                .iter()
+                .find(|e| e.selector == selector)
+                .map(|e| e.signature.clone())
+        })
+}
+
+fn word(args: &[u8], index: usize) -> Option<&[u8]> {
+    args.get(index.checked_mul(32)?..index.checked_mul(32)?.checked_add(32)?)
+}
+
+fn word_usize(word: &[u8]) -> Option<usize> {
+    u128_of(word).and_then(|n| usize::try_from(n).ok())
+}
+
+/// The tail a `bytes` or `string` head word points at.
+fn dynamic<'a>(args: &'a [u8], head: &[u8]) -> Option<&'a [u8]> {
+    let offset = word_usize(head)?;
+    let len = word_usize(args.get(offset..offset.checked_add(32)?)?)?;
+    let start = offset + 32;
+    args.get(start..start.checked_add(len)?)
+}
+
+fn render(ty: &str, head: &[u8], args: &[u8]) -> String {
+    let unsupported = || format!("0x{} (not decoded)", hex::encode(head));
+    match ty {
+        "address" if head[..12].iter().all(|b| *b == 0) => {
+            format!("0x{}", hex::encode(&head[12..]))
+        }
+        "bool" if head[..31].iter().all(|b| *b == 0) && head[31] <= 1 => {
+            (head[31] == 1).to_string()
+        }
+        "bytes" => dynamic(args, head)
+            .map(|b| format!("0x{}", hex::encode(b)))
+            .unwrap_or_else(unsupported),
+        "string" => dynamic(args, head)
+            .and_then(|b| std::str::from_utf8(b).ok())
+            .map(|s| format!("{s:?}"))
+            .unwrap_or_else(unsupported),
+        t if t.starts_with("uint") => decimal(head),
+        t if t.starts_with("int") => {
+            if head[0] & 0x80 == 0 {
+                decimal(head)
+            } else {
+                let mut neg: Vec<u8> = head.iter().map(|b| !b).collect();
+                for b in neg.iter_mut().rev() {
+                    let (v, overflow) = b.overflowing_add(1);
+                    *b = v;
+                    if !overflow {
+                        break;
+                    }
+                }
+                format!("-{}", decimal(&neg))
+            }
+        }
+        t if t.starts_with("bytes") => match t[5..].parse::<usize>() {
+            Ok(n @ 1..=32) => format!("0x{}", hex::encode(&head[..n])),
+            _ => unsupported(),
+        },
+        _ => unsupported(),
+    }
+}
+
+fn decode_call(data: &[u8], registry: &AbiRegistry, warnings: &mut Vec<String>) -> EthereumCall {
+    let selector = format!("0x{}", hex::encode(&data[..4]));
+    let args = &data[4..];
+    let signature = lookup(&selector, registry);
+    let mut fields = Vec::new();
+    match signature.as_deref().map(parse_signature) {
+        Some(Ok((name, types))) => {
+            for (i, ty) in types.iter().enumerate() {
+                let Some(head) = word(args, i) else {
+                    warnings.push("calldata is shorter than the function signature".to_string());
+                    break;
+                };
+                fields.push(DecodedField {
+                    name: ty.clone(),
+                    value: render(ty, head, args),
+                });
+            }
+            let max = decimal(&[0xff; 32]);
+            match (name.as_str(), fields.as_slice()) {
+                ("approve", [_, amount]) if amount.value == max => {
+                    warnings.push("unlimited token approval".to_string())
+                }
+                ("setApprovalForAll", [_, approved]) if approved.value == "true" => {
+                    warnings.push("grants the operator every token in the collection".to_string())
+                }
+                _ => {}
+            }
+        }
+        _ => warnings.push(format!(
+            "unknown function selector {selector}; the call cannot be shown"
+        )),
+    }
+    EthereumCall {
+        selector,
+        signature,
+        args: fields,
+        data_len: data.len(),
+    }
+}
+
+pub fn decode(
+    payload: &[u8],
+    registry: &AbiRegistry,
+) -> Result<(EthereumTxSummary, Vec<String>), DecodeError> {
+    let (tx_type, body) = match payload.first() {
+        Some(t @ (1 | 2)) => (*t, &payload[1..]),
+        Some(b) if *b >= 0xc0 => (0, payload),
+        _ => return Err(err("not an RLP transaction")),
+    };
+    let (item, rest) = rlp_item(body, 0)?;
+    if !rest.is_empty() {
+        return Err(err("trailing bytes after transaction"));
+    }
+    let Rlp::List(f) = item else {
+        return Err(err("transaction must be an RLP list"));
+    };
+
+    let mut warnings = Vec::new();
+    // Field positions of nonce, the fee fields, gas limit, to, value and data.
+    let (chain_id, nonce, fees, gas, to, value, data) = match (tx_type, f.len()) {
+        (0, 6 | 9) => {
+            let chain_id = if f.len() == 6 {
+                warnings.push("no chain id; the signature is valid on every chain".to_string());
+                None
+            } else if bytes(&f[7], "r")?.is_empty() && bytes(&f[8], "s")?.is_empty() {
+                Some(u64_field(&f[6], "chain id")?)
+            } else {
+                warnings.push("transaction is already signed".to_string());
+                u64_field(&f[6], "v")?.checked_sub(35).map(|v| v / 2)
+            };
+            (chain_id, &f[0], &f[1..2], &f[2], &f[3], &f[4], &f[5])
+        }
+        (1, 8 | 11) => (
+            Some(u64_field(&f[0], "chain id")?),
+            &f[1],
+            &f[2..3],
+            &f[3],
+            &f[4],
+            &f[5],
+            &f[6],
+        ),
+        (2, 9 | 12) => (
+            Some(u64_field(&f[0], "chain id")?),
+            &f[1],
+            &f[2..4],
+            &f[4],
+            &f[5],
+            &f[6],
+            &f[7],
+        ),
+        _ => return Err(err("wrong number of fields for the transaction type")),
+    };
+    if matches!((tx_type, f.len()), (1, 11) | (2, 12)) {
+        warnings.push("transaction is already signed".to_string());
+    }
+
+    let gas_limit = u64_field(gas, "gas limit")?;
+    let fee_cap = uint(fees.last().unwrap(), "gas price")?;
+    let max_gas_cost_wei = u128_of(fee_cap)
+        .and_then(|fee| fee.checked_mul(gas_limit as u128))
+        .map(|n| n.to_string());
+    let (gas_price_wei, max_fee_per_gas_wei, max_priority_fee_per_gas_wei) = if tx_type == 2 {
+        (
+            None,
+            Some(decimal(fee_cap)),
+            Some(decimal(uint(&fees[0], "priority fee")?)),
+        )
+    } else {
+        (Some(decimal(fee_cap)), None, None)
+    };
+
+    let to = address(to)?;
+    let data = bytes(data, "data")?;
+    let call = match (&to, data.len()) {
+        (None, _) => {
+            warnings.push("deploys a new contract".to_string());
+            None
+        }
+        (Some(_), 0) => None,
+        (Some(_), 1..=3) => {
+            warnings.push("calldata is shorter than a function selector".to_string());
+            None
+        }
+        (Some(_), _) => Some(decode_call(data, registry, &mut warnings)),
+    };
+
+    Ok((
+        EthereumTxSummary {
+            tx_type,
+            chain_id,
+            nonce: u64_field(nonce, "nonce")?,
+            to,
+            value_wei: decimal(uint(value, "value")?),
+            gas_limit,
+            gas_price_wei,
+            max_fee_per_gas_wei,
+            max_priority_fee_per_gas_wei,
+            max_gas_cost_wei,
+            call,
+        },
+        warnings,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rlp_bytes(b: &[u8]) -> Vec<u8> {
+        match b {
+            [x] if *x < 0x80 => vec![*x],
+            _ if b.len() < 56 => [&[0x80 + b.len() as u8][..], b].concat(),
+            _ => [&[0xb8, b.len() as u8][..], b].concat(),
+        }
+    }
+
+    fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+        let payload = items.concat();
+        if payload.len() < 56 {
+            [&[0xc0 + payload.len() as u8][..], &payload].concat()
+        } else {
+            [&[0xf8, payload.len() as u8][..], &payload].concat()
+        }
+    }
+
+    fn uint_bytes(n: u64) -> Vec<u8> {
+        let be = n.to_be_bytes();
+        be[be.iter().take_while(|b| **b == 0).count()..].to_vec()
+    }
+
+    fn eip1559(to: &[u8], value: u64, data: &[u8]) -> Vec<u8> {
+        let fields = vec![
+            rlp_bytes(&uint_bytes(1)),
+            rlp_bytes(&uint_bytes(7)),
+            rlp_bytes(&uint_bytes(2_000_000_000)),
+            rlp_bytes(&uint_bytes(30_000_000_000)),
+            rlp_bytes(&uint_bytes(60_000)),
+            rlp_bytes(to),
+            rlp_bytes(&uint_bytes(value)),
+            rlp_bytes(data),
+            rlp_list(&[]),
+        ];
+        [vec![2], rlp_list(&fields)].concat()
+    }
+
+    fn erc20_call(selector: &str, to: [u8; 20], amount: [u8; 32]) -> Vec<u8> {
+        let mut data = hex::decode(&selector[2..]).unwrap();
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(&to);
+        data.extend_from_slice(&amount);
+        data
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(decimal(&[]), "0");
+        assert_eq!(decimal(&[0x01, 0x00]), "256");
+        assert_eq!(
+            decimal(&[0xff; 32]),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+    }
+
+    #[test]
+    fn test_decodes_erc20_transfer() {
+        let mut amount = [0u8; 32];
+        amount[31] = 0xe8;
+        amount[30] = 0x03;
+        let data = erc20_call("0xa9059cbb", [0xbb; 20], amount);
+        let payload = eip1559(&[0xaa; 20], 0, &data);
+        let (summary, warnings) = decode(&payload, &AbiRegistry::default()).unwrap();
+        assert_eq!(summary.tx_type, 2);
+        assert_eq!(summary.chain_id, Some(1));
+        assert_eq!(summary.nonce, 7);
+        assert_eq!(summary.to, Some(format!("0x{}", hex::encode([0xaa; 20]))));
+        assert_eq!(summary.max_fee_per_gas_wei.as_deref(), Some("30000000000"));
+        assert_eq!(
+            summary.max_gas_cost_wei.as_deref(),
+            Some("1800000000000000")
+        );
+        let call = summary.call.unwrap();
+        assert_eq!(call.signature.as_deref(), Some("transfer(address,uint256)"));
+        assert_eq!(call.args[0].value, format!("0x{}", hex::encode([0xbb; 20])));
+        assert_eq!(call.args[1].value, "1000");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_flags_unlimited_approval_and_unknown_selector() {
+        let data = erc20_call("0x095ea7b3", [0xbb; 20], [0xff; 32]);
+        let (_, warnings) =
+            decode(&eip1559(&[0xaa; 20], 0, &data), &AbiRegistry::default()).unwrap();
+        assert_eq!(warnings, vec!["unlimited token approval".to_string()]);
+
+        let data = erc20_call("0x12345678", [0xbb; 20], [0; 32]);
+        let payload = eip1559(&[0xaa; 20], 0, &data);
+        let (summary, warnings) = decode(&payload, &AbiRegistry::default()).unwrap();
+        assert!(summary.call.unwrap().signature.is_none());
+        assert!(warnings[0].contains("unknown function selector"));
+
+        let registry = AbiRegistry {
+            entries: vec![registry_entry("12345678", "mint(address,uint256)").unwrap()],
+        };
+        let (summary, warnings) = decode(&payload, &registry).unwrap();
+        assert_eq!(
+            summary.call.unwrap().signature.as_deref(),
+            Some("mint(address,uint256)")
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_registry_entries_are_checked() {
+        assert!(registry_entry("0xa9059cbb", "steal(address)").is_err());
+        assert!(registry_entry("0x1234", "f()").is_err());
+        assert!(registry_entry("0x12345678", "f(uint256").is_err());
+        assert!(registry_entry("0x12345678", "f(uint256,,bool)").is_err());
+        assert_eq!(
+            parse_signature("swap((address,uint256),bytes)").unwrap().1,
+            vec!["(address,uint256)".to_string(), "bytes".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_legacy_without_chain_id_is_flagged() {
+        let fields = vec![
+            rlp_bytes(&uint_bytes(0)),
+            rlp_bytes(&uint_bytes(1_000_000_000)),
+            rlp_bytes(&uint_bytes(21_000)),
+            rlp_bytes(&[0xaa; 20]),
+            rlp_bytes(&uint_bytes(5)),
+            rlp_bytes(&[]),
+        ];
+        let (summary, warnings) = decode(&rlp_list(&fields), &AbiRegistry::default()).unwrap();
+        assert_eq!(summary.tx_type, 0);
+        assert_eq!(summary.chain_id, None);
+        assert_eq!(summary.value_wei, "5");
+        assert!(warnings[0].starts_with("no chain id"));
+    }
+}
@@ -0,0 +1,212 @@
+//! Human-readable summaries of the payloads the vault is asked to sign.
+//!
+//! [`summarize`] turns a Bitcoin PSBT ([`bitcoin`]), an Ethereum transaction
+//! ([`ethereum`]) or a Cosmos `SignDoc` ([`cosmos`]) into a [`TxSummary`] the
+//! UI shows before anything is signed. Anything else is summarised as raw
+//! bytes. The summary hash covers the payload digest and every decoded
+//! field, and signing commands only go ahead when [`confirm`] recomputes the
+//! hash the reviewer was shown, so what was approved is what gets signed.
+
+pub mod bitcoin;
+pub mod cosmos;
+pub mod ethereum;
+
+use crate::models::signing_request::SigningPayloadKind;
+use crate::models::tx_review::{AbiRegistry, RawSummary, TxDetail, TxSummary};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DecodeError {
+    #[error("malformed {kind}: {reason}")]
+    Malformed { kind: &'static str, reason: String },
+    #[error("invalid ABI registry entry: {0}")]
+    Registry(String),
+    #[error("transaction summary does not match the payload; review it again before signing")]
+    SummaryMismatch,
+}
+
+pub(crate) fn malformed(kind: &'static str, reason: impl Into<String>) -> DecodeError {
+    DecodeError::Malformed {
+        kind,
+        reason: reason.into(),
+    }
+}
+
+/// Domain separator for summary hashes.
+const SUMMARY_DOMAIN: &[u8] = b"ZAP-tx-summary-v1";
+
+/// Guess the payload kind from its first bytes. The formats do not overlap:
+/// a PSBT starts with its magic, an Ethereum transaction with a type byte or
+/// an RLP list, and a `SignDoc` with the tag of its `body_bytes` field.
+pub fn detect(payload: &[u8]) -> SigningPayloadKind {
+    match payload.first() {
+        _ if payload.starts_with(bitcoin::MAGIC) => SigningPayloadKind::Psbt,
+        Some(1) | Some(2) => SigningPayloadKind::EthereumTx,
+        Some(b) if *b >= 0xc0 => SigningPayloadKind::EthereumTx,
+        Some(0x0a) => SigningPayloadKind::CosmosSignDoc,
+        _ => SigningPayloadKind::Raw,
+    }
+}
+
+/// Decode `payload` for review. With an explicit `kind` a payload that does
+/// not decode is an error; when the kind is guessed it falls back to a raw
+/// summary with a warning.
+pub fn summarize(
+    kind: Option<SigningPayloadKind>,
+    payload: &[u8],
+    registry: &AbiRegistry,
+) -> Result<TxSummary, DecodeError> {
+    let (kind, detail, warnings) = match kind {
+        Some(kind) => {
+            let (detail, warnings) = decode_as(kind, payload, registry)?;
+            (kind, detail, warnings)
+        }
+        None => match decode_as(detect(payload), payload, registry) {
+            Ok((detail, warnings)) => (detect(payload), detail, warnings),
+            Err(e) => {
+                let (detail, mut warnings) = raw(payload);
+                warnings.insert(0, format!("payload did not decode as expected: {e}"));
+                (SigningPayloadKind::Raw, detail, warnings)
+            }
+        },
+    };
+    let payload_sha256_hex = hex::encode(Sha256::digest(payload));
+    let summary_hash_hex = summary_hash(kind, &payload_sha256_hex, &detail, &warnings);
+    Ok(TxSummary {
+        kind,
+        payload_sha256_hex,
+        detail,
+        warnings,
+        summary_hash_hex,
+    })
+}
+
+/// Re-derive the summary of `payload` and check it is the one the reviewer
+/// approved.
+pub fn confirm(
+    kind: Option<SigningPayloadKind>,
+    payload: &[u8],
+    registry: &AbiRegistry,
+    summary_hash_hex: &str,
+) -> Result<TxSummary, DecodeError> {
+    let summary = summarize(kind, payload, registry)?;
+    if !summary
+        .summary_hash_hex
+        .eq_ignore_ascii_case(summary_hash_hex.trim())
+    {
+        return Err(DecodeError::SummaryMismatch);
+    }
+    Ok(summary)
+}
+
+fn decode_as(
+    kind: SigningPayloadKind,
+    payload: &[u8],
+    registry: &AbiRegistry,
+) -> Result<(TxDetail, Vec<String>), DecodeError> {
+    Ok(match kind {
+        SigningPayloadKind::Psbt => {
+            let (summary, warnings) = bitcoin::decode(payload)?;
+            (TxDetail::Psbt(summary), warnings)
+        }
+        SigningPayloadKind::EthereumTx => {
+            let (summary, warnings) = ethereum::decode(payload, registry)?;
+            (TxDetail::EthereumTx(summary), warnings)
+        }
+        SigningPayloadKind::CosmosSignDoc => {
+            let (summary, warnings) = cosmos::decode(payload)?;
+            (TxDetail::CosmosSignDoc(summary), warnings)
+        }
+        SigningPayloadKind::Raw => raw(payload),
+    })
+}
+
+fn raw(payload: &[u8]) -> (TxDetail, Vec<String>) {
+    let text = std::str::from_utf8(payload)
+        .ok()
+        .filter(|s| !s.is_empty() && s.chars().all(|c| !c.is_control() || c.is_whitespace()))
+        .map(str::to_string);
+    let mut warnings = Vec::new();
+    if text.is_none() {
+        warnings.push(if payload.len() == 32 {
+            "payload is a 32-byte digest; the vault cannot show what it commits to".to_string()
+        } else {
+            "payload is not a recognised transaction; check the bytes with the requester"
+                .to_string()
+        });
+    }
+    (
+        TxDetail::Raw(RawSummary {
+            len: payload.len(),
+            text,
+        }),
+        warnings,
+    )
+}
+
+fn summary_hash(
+    kind: SigningPayloadKind,
+    payload_sha256_hex: &str,
+    detail: &TxDetail,
+    warnings: &[String],
+) -> String {
+    let body = serde_json::to_vec(&(kind, payload_sha256_hex, detail, warnings))
+        .expect("summary serializes");
+    let mut hasher = Sha256::new();
+    hasher.update(SUMMARY_DOMAIN);
+    hasher.update(&body);
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_is_summarised_as_raw() {
+        let summary = summarize(None, b"hello vault", &AbiRegistry::default()).unwrap();
+        assert_eq!(summary.kind, SigningPayloadKind::Raw);
+        assert_eq!(
+            summary.detail,
+            TxDetail::Raw(RawSummary {
+                len: 11,
+                text: Some("hello vault".to_string()),
+            })
+        );
+        assert!(summary.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_confirm_accepts_only_the_reviewed_summary() {
+        let registry = AbiRegistry::default();
+        let summary = summarize(None, b"pay bob 5", &registry).unwrap();
+        confirm(None, b"pay bob 5", &registry, &summary.summary_hash_hex).unwrap();
+        confirm(
+            None,
+            b"pay bob 5",
+            &registry,
+            &summary.summary_hash_hex.to_uppercase(),
+        )
+        .unwrap();
+        assert!(matches!(
+            confirm(None, b"pay eve 5", &registry, &summary.summary_hash_hex),
+            Err(DecodeError::SummaryMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_guessed_kind_falls_back_to_raw() {
+        let payload = [0x02, 0x01, 0x02, 0x03];
+        assert_eq!(detect(&payload), SigningPayloadKind::EthereumTx);
+        let summary = summarize(None, &payload, &AbiRegistry::default()).unwrap();
+        assert_eq!(summary.kind, SigningPayloadKind::Raw);
+        assert!(summary.warnings[0].starts_with("payload did not decode"));
+        assert!(summarize(
+            Some(SigningPayloadKind::EthereumTx),
+            &payload,
+            &AbiRegistry::default()
+        )
+        .is_err());
+    }
+}
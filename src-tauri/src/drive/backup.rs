@@ -0,0 +1,1279 @@
+use super::{check_relative_path, DriveBackend, DriveError};
+use crate::crypto::attestation;
+use crate::crypto::encryption::{self, Ciphertext};
+use crate::crypto::instance::{verify_instance, InstanceKey, PURPOSE_BACKUP};
+use crate::crypto::kdf::{self, KdfParams};
+use crate::crypto::mldsa87::{PublicKey, SecretKey, PUBLIC_KEY_SIZE, SIGNATURE_SIZE};
+use crate::models::backup::{
+    BackupEstimate, BackupFile, BackupManifest, BackupMode, BackupProgress, BackupVerification,
+    EstimatedFile, FileCheck, FileCheckStatus, FileEncryption, ProgressStage,
+};
+use crate::models::drive::DriveInfo;
+use crate::models::forecast::BackupGrowth;
+use crate::models::instance::InstanceSignature;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::time::Instant;
+use zeroize::Zeroizing;
+
+/// Directory on the drive that holds one sub-directory per backup. Each
+/// backup directory holds only its manifest.
+pub const BACKUP_ROOT: &str = "zap-vault-backups";
+/// Content-addressed store shared by every backup on the drive: each file is
+/// stored once, under its BLAKE3 hash, however many backups reference it.
+pub const OBJECT_ROOT: &str = "zap-vault-objects";
+pub const MANIFEST_FILE: &str = "manifest.json";
+/// Encrypted list of the backups on a drive, at the drive root, so listing
+/// does not read every manifest. A cache only: a missing, damaged or foreign
+/// index is rebuilt from the manifests.
+pub const INDEX_FILE: &str = "zap-vault-index.enc";
+pub const BACKUP_FORMAT_VERSION: u32 = 2;
+/// Threads used to hash and sign a backup's files. Bounded so a large backup
+/// does not take every core from the UI.
+pub const MAX_BACKUP_WORKERS: usize = 4;
+/// Version 1 backups kept a copy of every file inside the backup directory.
+/// They are still listed, verified and restored.
+const INLINE_FORMAT_VERSION: u32 = 1;
+/// Version of [`BackupMode::FileEncrypted`] manifests. Laid out like version
+/// 2, but numbered apart so releases that cannot decrypt refuse them instead
+/// of restoring ciphertext.
+pub const FILE_ENCRYPTED_FORMAT_VERSION: u32 = 3;
+/// Domain separating the file-encrypted backup key from other keys derived
+/// from the vault password.
+const FILE_KEY_DOMAIN: &str = "backup_file_encryption";
+/// Allocation unit assumed when sizing a backup. USB media formatted as
+/// FAT32, exFAT or ext4 typically uses 4 KiB clusters; smaller real clusters
+/// only make the estimate conservative.
+pub const BLOCK_SIZE: u64 = 4096;
+
+/// A vault data-directory file as copied into (or out of) a backup. The
+/// keystore and item store are already encrypted; the rest is the plaintext
+/// metadata that lives next to them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultFile {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+fn backup_dir(backup_id: &str) -> String {
+    format!("{BACKUP_ROOT}/{backup_id}")
+}
+
+/// Backup ids and vault file names are single path components.
+fn check_name(name: &str) -> Result<(), DriveError> {
+    check_relative_path(name)?;
+    if name.contains('/') {
+        return Err(DriveError::InvalidPath(name.to_string()));
+    }
+    Ok(())
+}
+
+pub(super) fn hash_hex(data: &[u8]) -> String {
+    blake3::hash(data).to_hex().to_string()
+}
+
+/// Where the object with this BLAKE3 hash lives, fanned out by its first
+/// byte to keep directories small.
+pub fn object_path(blake3_hex: &str) -> Result<String, DriveError> {
+    let valid = blake3_hex.len() == 64
+        && blake3_hex
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+    if !valid {
+        return Err(DriveError::Malformed(format!(
+            "bad object hash {blake3_hex}"
+        )));
+    }
+    Ok(format!("{OBJECT_ROOT}/{}/{blake3_hex}", &blake3_hex[..2]))
+}
+
+/// Drive path holding the contents of `entry` in `manifest`.
+pub(super) fn stored_path(
+    manifest: &BackupManifest,
+    entry: &BackupFile,
+) -> Result<String, DriveError> {
+    check_name(&entry.name)?;
+    if manifest.version == INLINE_FORMAT_VERSION {
+        Ok(format!("{}/{}", backup_dir(&manifest.id), entry.name))
+    } else {
+        object_path(&entry.blake3_hex)
+    }
+}
+
+/// Whether an intact copy of the object is already on the drive. A damaged
+/// copy counts as absent so the next backup rewrites it.
+fn object_intact(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    blake3_hex: &str,
+) -> Result<bool, DriveError> {
+    let path = object_path(blake3_hex)?;
+    if !backend.exists(drive_id, &path)? {
+        return Ok(false);
+    }
+    match backend.read_file(drive_id, &path) {
+        Ok(data) => Ok(hash_hex(&data) == blake3_hex),
+        Err(DriveError::FileNotFound(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+pub(super) fn on_disk(size: u64) -> u64 {
+    size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE
+}
+
+fn new_backup_id(now: DateTime<Utc>) -> String {
+    // Timestamp first so ids sort chronologically on the drive.
+    format!(
+        "{}-{}",
+        now.format("%Y%m%dT%H%M%SZ"),
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    )
+}
+
+/// Size a backup of `files` on `drive` and check it against the drive's free
+/// space. Contents already stored on the drive (or repeated within `files`)
+/// cost nothing.
+pub fn estimate_backup(
+    backend: &dyn DriveBackend,
+    drive: &DriveInfo,
+    files: &[VaultFile],
+) -> Result<BackupEstimate, DriveError> {
+    let hashes = parallel_map(files, |f| hash_hex(&f.data));
+    estimate_hashed(backend, drive, files, &hashes)
+}
+
+/// [`estimate_backup`] for files already hashed, `hashes[i]` being the hash
+/// of `files[i]`.
+fn estimate_hashed(
+    backend: &dyn DriveBackend,
+    drive: &DriveInfo,
+    files: &[VaultFile],
+    hashes: &[String],
+) -> Result<BackupEstimate, DriveError> {
+    // Sized as if signed, so the estimate holds either way.
+    let manifest = BackupManifest {
+        version: BACKUP_FORMAT_VERSION,
+        id: new_backup_id(Utc::now()),
+        created_at: Utc::now(),
+        mode: BackupMode::Container,
+        file_encryption: None,
+        files: files
+            .iter()
+            .zip(hashes)
+            .map(|(f, hash)| BackupFile {
+                name: f.name.clone(),
+                size: f.data.len() as u64,
+                blake3_hex: hash.clone(),
+                signature_hex: Some("0".repeat(SIGNATURE_SIZE * 2)),
+            })
+            .collect(),
+        signer_public_hex: Some("0".repeat(PUBLIC_KEY_SIZE * 2)),
+        instance_signature: Some(InstanceSignature {
+            instance_id: uuid::Uuid::nil().to_string(),
+            purpose: PURPOSE_BACKUP.to_string(),
+            mldsa_public_hex: "0".repeat(PUBLIC_KEY_SIZE * 2),
+            ed25519_public_hex: "0".repeat(64),
+            mldsa_signature_hex: "0".repeat(SIGNATURE_SIZE * 2),
+            ed25519_signature_hex: "0".repeat(128),
+        }),
+    };
+    let manifest_bytes = serde_json::to_vec_pretty(&manifest)
+        .map(|m| m.len() as u64)
+        .unwrap_or(BLOCK_SIZE);
+
+    let mut seen = HashSet::new();
+    let mut estimated = Vec::with_capacity(manifest.files.len());
+    let mut new_bytes = 0;
+    let mut deduplicated_bytes = 0;
+    for f in &manifest.files {
+        let stored = !seen.insert(f.blake3_hex.as_str())
+            || backend.exists(&drive.id, &object_path(&f.blake3_hex)?)?;
+        if stored {
+            deduplicated_bytes += f.size;
+        } else {
+            new_bytes += on_disk(f.size);
+        }
+        estimated.push(EstimatedFile {
+            name: f.name.clone(),
+            size: f.size,
+            stored,
+        });
+    }
+    let required_bytes = new_bytes + on_disk(manifest_bytes);
+    Ok(BackupEstimate {
+        drive_id: drive.id.clone(),
+        files: estimated,
+        payload_bytes: manifest.total_bytes(),
+        deduplicated_bytes,
+        required_bytes,
+        available_bytes: drive.available_bytes,
+        fits: drive
+            .available_bytes
+            .is_none_or(|free| free >= required_bytes),
+        capacity_warning: None,
+    })
+}
+
+/// Sends [`BackupProgress`] updates to the command layer, which forwards them
+/// to the UI. A closed or absent channel is ignored: progress is best-effort
+/// and never fails the operation.
+pub struct ProgressReporter<'a> {
+    tx: Option<&'a Sender<BackupProgress>>,
+    total_bytes: u64,
+    bytes_written: u64,
+    started: Instant,
+}
+
+impl<'a> ProgressReporter<'a> {
+    pub fn new(tx: Option<&'a Sender<BackupProgress>>, total_bytes: u64) -> Self {
+        Self {
+            tx,
+            total_bytes,
+            bytes_written: 0,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn advance(&mut self, bytes: u64) {
+        self.bytes_written = (self.bytes_written + bytes).min(self.total_bytes);
+    }
+
+    pub fn report(&self, stage: ProgressStage, current_item: Option<&str>) {
+        let Some(tx) = self.tx else { return };
+        let percent = match (stage, self.total_bytes) {
+            (ProgressStage::Complete, _) => 100,
+            (_, 0) => 0,
+            _ => (self.bytes_written * 100 / self.total_bytes) as u8,
+        };
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let bytes_per_sec = if elapsed > 0.0 {
+            (self.bytes_written as f64 / elapsed) as u64
+        } else {
+            0
+        };
+        let _ = tx.send(BackupProgress {
+            stage,
+            percent,
+            current_item: current_item.map(str::to_string),
+            bytes_written: self.bytes_written,
+            total_bytes: self.total_bytes,
+            bytes_per_sec,
+        });
+    }
+}
+
+/// `f` over every item on up to [`MAX_BACKUP_WORKERS`] threads, results in
+/// input order whatever order the workers finish in.
+fn parallel_map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(MAX_BACKUP_WORKERS)
+        .min(items.len());
+    if workers <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let next = AtomicUsize::new(0);
+    let mut done: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut out = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(i) else { break };
+                        out.push((i, f(item)));
+                    }
+                    out
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("backup worker panicked"))
+            .collect()
+    });
+    done.sort_by_key(|(i, _)| *i);
+    done.into_iter().map(|(_, r)| r).collect()
+}
+
+/// Copy `files` into a new backup on `drive_id`. Only contents not already in
+/// the drive's object store are written. Fails before writing anything if the
+/// drive reports too little free space. Objects are written first and the
+/// manifest last, so a backup interrupted by an unplug never shows up in
+/// [`list_backups`].
+pub fn create_backup(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    files: &[VaultFile],
+    now: DateTime<Utc>,
+    progress: Option<&Sender<BackupProgress>>,
+) -> Result<BackupManifest, DriveError> {
+    create_signed_backup(backend, drive_id, files, now, None, None, progress)
+}
+
+/// [`create_backup`], signing every manifest entry with the vault identity
+/// and the whole manifest with the installation's identity, when given.
+pub fn create_signed_backup(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    files: &[VaultFile],
+    now: DateTime<Utc>,
+    identity: Option<(&PublicKey, &SecretKey)>,
+    instance: Option<&InstanceKey>,
+    progress: Option<&Sender<BackupProgress>>,
+) -> Result<BackupManifest, DriveError> {
+    write_backup(
+        backend, drive_id, files, now, identity, instance, None, progress,
+    )
+}
+
+/// Derive the key of a file-encrypted backup from the vault password.
+pub fn file_key(
+    password: &str,
+    encryption: &FileEncryption,
+) -> Result<Zeroizing<[u8; 32]>, DriveError> {
+    let salt = hex::decode(&encryption.salt_hex)
+        .map_err(|e| DriveError::Malformed(format!("file encryption salt: {e}")))?;
+    let params = KdfParams {
+        memory_kib: encryption.kdf.memory_kib,
+        iterations: encryption.kdf.iterations,
+        parallelism: encryption.kdf.parallelism,
+    };
+    let master = Zeroizing::new(
+        kdf::derive_master_key_with_params(password.as_bytes(), &salt, params)
+            .map_err(|e| DriveError::Malformed(e.to_string()))?,
+    );
+    Ok(Zeroizing::new(kdf::derive_encryption_key(
+        &master,
+        FILE_KEY_DOMAIN,
+    )))
+}
+
+/// [`create_signed_backup`] for a drive whose filesystem is not encrypted:
+/// every file is encrypted under `key` (from [`file_key`] with
+/// `encryption`) before it is hashed, signed and stored, and the manifest is
+/// marked [`BackupMode::FileEncrypted`]. Encrypted objects never match
+/// earlier ones, so nothing is shared with other backups.
+#[allow(clippy::too_many_arguments)]
+pub fn create_file_encrypted_backup(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    files: &[VaultFile],
+    now: DateTime<Utc>,
+    key: &[u8; 32],
+    encryption: FileEncryption,
+    identity: Option<(&PublicKey, &SecretKey)>,
+    instance: Option<&InstanceKey>,
+    progress: Option<&Sender<BackupProgress>>,
+) -> Result<BackupManifest, DriveError> {
+    let sealed = parallel_map(files, |f| {
+        encryption::encrypt_aead(key, &f.data).map(|ct| VaultFile {
+            name: f.name.clone(),
+            data: [ct.nonce, ct.ciphertext].concat(),
+        })
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| DriveError::Malformed(e.to_string()))?;
+    write_backup(
+        backend,
+        drive_id,
+        &sealed,
+        now,
+        identity,
+        instance,
+        Some(encryption),
+        progress,
+    )
+}
+
+/// Decrypt the files of a file-encrypted backup, as read by [`read_backup`]
+/// or [`read_backup_checked`]. Files of other backups are returned as they
+/// are. Fails on the first file that does not decrypt, which is nearly
+/// always a wrong password.
+pub fn decrypt_files(
+    manifest: &BackupManifest,
+    password: Option<&str>,
+    files: Vec<VaultFile>,
+) -> Result<Vec<VaultFile>, DriveError> {
+    let Some(encryption) = &manifest.file_encryption else {
+        return Ok(files);
+    };
+    let password = password.ok_or_else(|| DriveError::PasswordRequired(manifest.id.clone()))?;
+    let key = file_key(password, encryption)?;
+    files
+        .into_iter()
+        .map(|f| {
+            if f.data.len() < encryption::XCHACHA_NONCE_SIZE {
+                return Err(DriveError::Decrypt(f.name));
+            }
+            let (nonce, ciphertext) = f.data.split_at(encryption::XCHACHA_NONCE_SIZE);
+            let sealed = Ciphertext {
+                nonce: nonce.to_vec(),
+                ciphertext: ciphertext.to_vec(),
+            };
+            match encryption::decrypt_aead(&key, &sealed) {
+                Ok(data) => Ok(VaultFile { name: f.name, data }),
+                Err(_) => Err(DriveError::Decrypt(f.name)),
+            }
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_backup(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    files: &[VaultFile],
+    now: DateTime<Utc>,
+    identity: Option<(&PublicKey, &SecretKey)>,
+    instance: Option<&InstanceKey>,
+    file_encryption: Option<FileEncryption>,
+    progress: Option<&Sender<BackupProgress>>,
+) -> Result<BackupManifest, DriveError> {
+    let total = files.iter().map(|f| f.data.len() as u64).sum();
+    let mut progress = ProgressReporter::new(progress, total);
+    progress.report(ProgressStage::Preparing, None);
+    let drive = backend.drive(drive_id)?;
+    if !drive.is_writable() {
+        return Err(DriveError::NotReady(drive_id.to_string()));
+    }
+    if files.is_empty() {
+        return Err(DriveError::Malformed("nothing to back up".to_string()));
+    }
+    for (i, f) in files.iter().enumerate() {
+        check_name(&f.name)?;
+        if files[..i].iter().any(|g| g.name == f.name) {
+            return Err(DriveError::Malformed(format!("duplicate file {}", f.name)));
+        }
+    }
+
+    let hashes = parallel_map(files, |f| hash_hex(&f.data));
+    let estimate = estimate_hashed(backend, &drive, files, &hashes)?;
+    if !estimate.fits {
+        return Err(DriveError::InsufficientSpace {
+            required: estimate.required_bytes,
+            available: estimate.available_bytes.unwrap_or(0),
+        });
+    }
+
+    // Hashing and signing run on the worker pool; the drive is written from
+    // this thread, in order, since removable media gains nothing from
+    // concurrent writes.
+    let id = new_backup_id(now);
+    let unsigned: Vec<BackupFile> = files
+        .iter()
+        .zip(hashes)
+        .map(|(f, hash)| BackupFile {
+            name: f.name.clone(),
+            size: f.data.len() as u64,
+            blake3_hex: hash,
+            signature_hex: None,
+        })
+        .collect();
+    let entries = match identity {
+        Some((_, sk)) => parallel_map(&unsigned, |entry| {
+            attestation::sign_backup_file(sk, &id, entry).map(|sig| BackupFile {
+                signature_hex: Some(sig),
+                ..entry.clone()
+            })
+        })
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| DriveError::Malformed(e.to_string()))?,
+        None => unsigned,
+    };
+    for (f, entry) in files.iter().zip(&entries) {
+        progress.report(ProgressStage::Copying, Some(&f.name));
+        if !object_intact(backend, drive_id, &entry.blake3_hex)? {
+            backend.write_file(drive_id, &object_path(&entry.blake3_hex)?, &f.data)?;
+        }
+        progress.advance(f.data.len() as u64);
+    }
+    let (version, mode) = match file_encryption {
+        Some(_) => (FILE_ENCRYPTED_FORMAT_VERSION, BackupMode::FileEncrypted),
+        None => (BACKUP_FORMAT_VERSION, BackupMode::Container),
+    };
+    let mut manifest = BackupManifest {
+        version,
+        id,
+        created_at: now,
+        mode,
+        file_encryption,
+        files: entries,
+        signer_public_hex: identity.map(|(pk, _)| pk.to_hex()),
+        instance_signature: None,
+    };
+    if let Some(key) = instance {
+        manifest.instance_signature = Some(
+            key.sign(PURPOSE_BACKUP, &manifest_message(&manifest))
+                .map_err(|e| DriveError::Malformed(e.to_string()))?,
+        );
+    }
+    let json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| DriveError::Malformed(e.to_string()))?;
+    progress.report(ProgressStage::Finalizing, Some(MANIFEST_FILE));
+    backend.write_file(
+        drive_id,
+        &format!("{}/{MANIFEST_FILE}", backup_dir(&manifest.id)),
+        &json,
+    )?;
+    progress.report(ProgressStage::Complete, None);
+    Ok(manifest)
+}
+
+fn push_field(m: &mut Vec<u8>, part: &[u8]) {
+    m.extend_from_slice(&(part.len() as u32).to_be_bytes());
+    m.extend_from_slice(part);
+}
+
+/// What a manifest's instance signature covers: every field but the
+/// signature itself, each length-prefixed. The file encryption parameters
+/// are only added for file-encrypted backups, so older signatures still
+/// verify.
+pub fn manifest_message(manifest: &BackupManifest) -> Vec<u8> {
+    let mut m = Vec::new();
+    push_field(&mut m, &manifest.version.to_be_bytes());
+    push_field(&mut m, manifest.id.as_bytes());
+    push_field(&mut m, manifest.created_at.to_rfc3339().as_bytes());
+    if let Some(enc) = &manifest.file_encryption {
+        push_field(&mut m, enc.salt_hex.as_bytes());
+        for param in [
+            enc.kdf.version,
+            enc.kdf.memory_kib,
+            enc.kdf.iterations,
+            enc.kdf.parallelism,
+        ] {
+            push_field(&mut m, &param.to_be_bytes());
+        }
+    }
+    push_field(
+        &mut m,
+        manifest
+            .signer_public_hex
+            .as_deref()
+            .unwrap_or("")
+            .as_bytes(),
+    );
+    for f in &manifest.files {
+        push_field(&mut m, f.name.as_bytes());
+        push_field(&mut m, &f.size.to_be_bytes());
+        push_field(&mut m, f.blake3_hex.as_bytes());
+        push_field(&mut m, f.signature_hex.as_deref().unwrap_or("").as_bytes());
+    }
+    m
+}
+
+/// Whether the manifest's instance signature verifies, `None` without one.
+pub fn instance_signature_ok(manifest: &BackupManifest) -> Option<bool> {
+    manifest
+        .instance_signature
+        .as_ref()
+        .map(|sig| verify_instance(sig, PURPOSE_BACKUP, &manifest_message(manifest)).is_ok())
+}
+
+pub fn load_manifest(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    backup_id: &str,
+) -> Result<BackupManifest, DriveError> {
+    check_name(backup_id)?;
+    let path = format!("{}/{MANIFEST_FILE}", backup_dir(backup_id));
+    let data = backend.read_file(drive_id, &path)?;
+    let manifest: BackupManifest =
+        serde_json::from_slice(&data).map_err(|e| DriveError::Malformed(e.to_string()))?;
+    if ![
+        INLINE_FORMAT_VERSION,
+        BACKUP_FORMAT_VERSION,
+        FILE_ENCRYPTED_FORMAT_VERSION,
+    ]
+    .contains(&manifest.version)
+    {
+        return Err(DriveError::Malformed(format!(
+            "unsupported backup version {}",
+            manifest.version
+        )));
+    }
+    let file_encrypted = manifest.version == FILE_ENCRYPTED_FORMAT_VERSION;
+    if file_encrypted != (manifest.mode == BackupMode::FileEncrypted)
+        || file_encrypted != manifest.file_encryption.is_some()
+    {
+        return Err(DriveError::Malformed(format!(
+            "backup {backup_id} is not consistently marked file-encrypted"
+        )));
+    }
+    if manifest.id != backup_id {
+        return Err(DriveError::Malformed(format!(
+            "manifest id {} does not match directory {backup_id}",
+            manifest.id
+        )));
+    }
+    Ok(manifest)
+}
+
+/// A manifest as listed: signatures are dropped to keep listings and the
+/// index small. [`verify_backup`] checks them.
+fn listed(mut manifest: BackupManifest) -> BackupManifest {
+    for f in &mut manifest.files {
+        f.signature_hex = None;
+    }
+    manifest.instance_signature = None;
+    manifest
+}
+
+/// Complete backups on the drive, newest first. Directories without a
+/// readable manifest (interrupted or damaged) are skipped here; use
+/// [`verify_backup`] to inspect a specific one.
+pub fn list_backups(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+) -> Result<Vec<BackupManifest>, DriveError> {
+    let mut out: Vec<BackupManifest> = backend
+        .list_dir(drive_id, BACKUP_ROOT)?
+        .iter()
+        .filter_map(|id| load_manifest(backend, drive_id, id).ok())
+        .map(listed)
+        .collect();
+    out.sort_by_key(|m| std::cmp::Reverse(m.created_at));
+    Ok(out)
+}
+
+fn read_index(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    key: &[u8; 32],
+) -> Option<Vec<BackupManifest>> {
+    let data = backend.read_file(drive_id, INDEX_FILE).ok()?;
+    let sealed: Ciphertext = serde_json::from_slice(&data).ok()?;
+    let plain = encryption::decrypt_vault(key, &sealed).ok()?;
+    serde_json::from_slice(&plain).ok()
+}
+
+fn write_index(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    key: &[u8; 32],
+    manifests: &[BackupManifest],
+) -> Result<(), DriveError> {
+    let plain = serde_json::to_vec(manifests).map_err(|e| DriveError::Malformed(e.to_string()))?;
+    let sealed =
+        encryption::encrypt_vault(key, &plain).map_err(|e| DriveError::Malformed(e.to_string()))?;
+    let data = serde_json::to_vec(&sealed).map_err(|e| DriveError::Malformed(e.to_string()))?;
+    backend.write_file(drive_id, INDEX_FILE, &data)
+}
+
+/// [`list_backups`] through the drive's index, sealed under `key`. Only the
+/// backup directory listing is read, plus the manifests of backups the index
+/// does not know yet; entries whose directory is gone are dropped. When
+/// anything changed the index is rewritten, best-effort, so a read-only
+/// drive still lists.
+pub fn list_backups_indexed(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    key: &[u8; 32],
+) -> Result<Vec<BackupManifest>, DriveError> {
+    let ids = backend.list_dir(drive_id, BACKUP_ROOT)?;
+    let cached = read_index(backend, drive_id, key);
+    let mut changed = cached.is_none();
+    let mut index = cached.unwrap_or_default();
+    let before = index.len();
+    index.retain(|m| ids.contains(&m.id));
+    changed |= index.len() != before;
+    for id in &ids {
+        if index.iter().any(|m| &m.id == id) {
+            continue;
+        }
+        if let Ok(manifest) = load_manifest(backend, drive_id, id) {
+            index.push(listed(manifest));
+            changed = true;
+        }
+    }
+    index.sort_by_key(|m| std::cmp::Reverse(m.created_at));
+    if changed {
+        if let Err(e) = write_index(backend, drive_id, key, &index) {
+            tracing::warn!("could not update the backup index on {drive_id}: {e}");
+        }
+    }
+    Ok(index)
+}
+
+/// Add a just-written backup to the drive's index. An index that cannot be
+/// read is left for the next [`list_backups_indexed`] to rebuild.
+pub fn add_to_index(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    key: &[u8; 32],
+    manifest: &BackupManifest,
+) -> Result<(), DriveError> {
+    let Some(mut index) = read_index(backend, drive_id, key) else {
+        return Ok(());
+    };
+    index.retain(|m| m.id != manifest.id);
+    index.push(listed(manifest.clone()));
+    index.sort_by_key(|m| std::cmp::Reverse(m.created_at));
+    write_index(backend, drive_id, key, &index)
+}
+
+/// Space each backup on the drive added, oldest first. Objects count
+/// towards the first backup that referenced them; version 1 backups count
+/// all their files. Directories without a readable manifest are skipped.
+pub fn backup_growth(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+) -> Result<Vec<BackupGrowth>, DriveError> {
+    let mut manifests = Vec::new();
+    for id in backend.list_dir(drive_id, BACKUP_ROOT)? {
+        let Ok(manifest) = load_manifest(backend, drive_id, &id) else {
+            continue;
+        };
+        // Signed manifests run to several blocks, so the stored size counts.
+        let path = format!("{}/{MANIFEST_FILE}", backup_dir(&id));
+        let size = backend.read_file(drive_id, &path)?.len() as u64;
+        manifests.push((manifest, size));
+    }
+    manifests.sort_by_key(|(m, _)| m.created_at);
+
+    let mut stored = HashSet::new();
+    Ok(manifests
+        .into_iter()
+        .map(|(manifest, manifest_bytes)| {
+            let files: u64 = manifest
+                .files
+                .iter()
+                .filter(|f| {
+                    manifest.version == INLINE_FORMAT_VERSION || stored.insert(f.blake3_hex.clone())
+                })
+                .map(|f| on_disk(f.size))
+                .sum();
+            BackupGrowth {
+                backup_id: manifest.id,
+                created_at: manifest.created_at,
+                added_bytes: files + on_disk(manifest_bytes),
+            }
+        })
+        .collect())
+}
+
+/// Whether a manifest entry carries a valid signature, for signed backups.
+/// Unsigned backups have nothing to check.
+fn signature_ok(manifest: &BackupManifest, entry: &BackupFile) -> bool {
+    manifest
+        .signer_public_hex
+        .as_deref()
+        .is_none_or(|pk| attestation::verify_backup_file(pk, &manifest.id, entry))
+}
+
+/// Re-read every file of a backup and compare it with the manifest.
+pub fn verify_backup(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    backup_id: &str,
+) -> Result<BackupVerification, DriveError> {
+    read_backup_checked(backend, drive_id, backup_id).map(|(_, verification, _)| verification)
+}
+
+/// Read every file of a backup, checking each against the manifest, without
+/// failing on damaged ones. Returns the manifest, the per-file result and the
+/// contents of the files that matched, for read-only inspection.
+pub fn read_backup_checked(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    backup_id: &str,
+) -> Result<(BackupManifest, BackupVerification, Vec<VaultFile>), DriveError> {
+    let manifest = load_manifest(backend, drive_id, backup_id)?;
+    let mut checks = Vec::with_capacity(manifest.files.len());
+    let mut intact = Vec::new();
+    for entry in &manifest.files {
+        let status = match backend.read_file(drive_id, &stored_path(&manifest, entry)?) {
+            Ok(data) if hash_hex(&data) == entry.blake3_hex => {
+                if !signature_ok(&manifest, entry) {
+                    checks.push(FileCheck {
+                        name: entry.name.clone(),
+                        status: FileCheckStatus::BadSignature,
+                    });
+                    continue;
+                }
+                intact.push(VaultFile {
+                    name: entry.name.clone(),
+                    data,
+                });
+                FileCheckStatus::Ok
+            }
+            Ok(_) => FileCheckStatus::Corrupt,
+            Err(DriveError::FileNotFound(_)) => FileCheckStatus::Missing,
+            Err(e) => return Err(e),
+        };
+        checks.push(FileCheck {
+            name: entry.name.clone(),
+            status,
+        });
+    }
+    let instance_signature_ok = instance_signature_ok(&manifest);
+    let verification = BackupVerification {
+        backup_id: manifest.id.clone(),
+        ok: checks.iter().all(|f| f.status == FileCheckStatus::Ok)
+            && instance_signature_ok != Some(false),
+        files: checks,
+        instance_signature_ok,
+    };
+    Ok((manifest, verification, intact))
+}
+
+/// Read a backup's files back, failing if any of them does not match the
+/// manifest. Nothing partial is ever returned. Reports the `Preparing` and
+/// `Copying` stages; installing the files is up to the caller.
+pub fn read_backup(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    backup_id: &str,
+    progress: Option<&Sender<BackupProgress>>,
+) -> Result<Vec<VaultFile>, DriveError> {
+    let manifest = load_manifest(backend, drive_id, backup_id)?;
+    if instance_signature_ok(&manifest) == Some(false) {
+        return Err(DriveError::Integrity(MANIFEST_FILE.to_string()));
+    }
+    let mut progress = ProgressReporter::new(progress, manifest.total_bytes());
+    progress.report(ProgressStage::Preparing, None);
+    let mut files = Vec::with_capacity(manifest.files.len());
+    for entry in &manifest.files {
+        let path = stored_path(&manifest, entry)?;
+        progress.report(ProgressStage::Copying, Some(&entry.name));
+        let data = backend.read_file(drive_id, &path)?;
+        progress.advance(data.len() as u64);
+        if hash_hex(&data) != entry.blake3_hex || !signature_ok(&manifest, entry) {
+            return Err(DriveError::Integrity(entry.name.clone()));
+        }
+        files.push(VaultFile {
+            name: entry.name.clone(),
+            data,
+        });
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drive::mock::MockBackend;
+    use crate::models::drive::DriveStatus;
+
+    fn files() -> Vec<VaultFile> {
+        vec![
+            VaultFile {
+                name: "vault.json".to_string(),
+                data: b"{\"initialized\":true}".to_vec(),
+            },
+            VaultFile {
+                name: "keys.enc".to_string(),
+                data: vec![7u8; 300],
+            },
+        ]
+    }
+
+    fn backend() -> MockBackend {
+        let b = MockBackend::new();
+        b.add_drive(MockBackend::ready_drive("usb", 1 << 20));
+        b
+    }
+
+    #[test]
+    fn create_list_verify_and_read_back() {
+        let b = backend();
+        let m = create_backup(&b, "usb", &files(), Utc::now(), None).unwrap();
+        assert_eq!(m.total_bytes(), 320);
+        assert_eq!(list_backups(&b, "usb").unwrap(), vec![m.clone()]);
+        assert!(verify_backup(&b, "usb", &m.id).unwrap().ok);
+        assert_eq!(read_backup(&b, "usb", &m.id, None).unwrap(), files());
+    }
+
+    #[test]
+    fn signed_entries_detect_manifest_tampering() {
+        let b = backend();
+        let (pk, sk) = attestation::vault_identity(&[9u8; 64]);
+        let m = create_signed_backup(
+            &b,
+            "usb",
+            &files(),
+            Utc::now(),
+            Some((&pk, &sk)),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(m.signer_public_hex, Some(pk.to_hex()));
+        assert!(m.files.iter().all(|f| f.signature_hex.is_some()));
+        assert!(verify_backup(&b, "usb", &m.id).unwrap().ok);
+
+        let mut altered = m.clone();
+        altered.files[0].name = "notes.json".to_string();
+        b.put_raw(
+            "usb",
+            &format!("{}/{MANIFEST_FILE}", backup_dir(&m.id)),
+            serde_json::to_vec(&altered).unwrap(),
+        );
+        let (_, v, intact) = read_backup_checked(&b, "usb", &m.id).unwrap();
+        assert_eq!(v.files[0].status, FileCheckStatus::BadSignature);
+        assert_eq!(v.files[1].status, FileCheckStatus::Ok);
+        assert_eq!(intact.len(), 1);
+        assert!(matches!(
+            read_backup(&b, "usb", &m.id, None),
+            Err(DriveError::Integrity(_))
+        ));
+    }
+
+    #[test]
+    fn instance_signature_covers_the_whole_manifest() {
+        let b = backend();
+        let (key, _) =
+            crate::crypto::instance::generate_instance(&[9u8; 64], "device-1", Utc::now()).unwrap();
+        let m =
+            create_signed_backup(&b, "usb", &files(), Utc::now(), None, Some(&key), None).unwrap();
+        assert!(m
+            .instance_signature
+            .as_ref()
+            .unwrap()
+            .is_from(&key.identity));
+        let v = verify_backup(&b, "usb", &m.id).unwrap();
+        assert!(v.ok);
+        assert_eq!(v.instance_signature_ok, Some(true));
+        assert!(list_backups(&b, "usb").unwrap()[0]
+            .instance_signature
+            .is_none());
+
+        let mut altered = m.clone();
+        altered.created_at -= chrono::Duration::days(1);
+        b.put_raw(
+            "usb",
+            &format!("{}/{MANIFEST_FILE}", backup_dir(&m.id)),
+            serde_json::to_vec(&altered).unwrap(),
+        );
+        let v = verify_backup(&b, "usb", &m.id).unwrap();
+        assert!(!v.ok);
+        assert_eq!(v.instance_signature_ok, Some(false));
+        assert!(read_backup(&b, "usb", &m.id, None).is_err());
+    }
+
+    #[test]
+    fn corruption_and_missing_files_are_reported() {
+        let b = backend();
+        let m = create_backup(&b, "usb", &files(), Utc::now(), None).unwrap();
+        let keys = object_path(&m.files[1].blake3_hex).unwrap();
+        b.put_raw("usb", &keys, vec![8u8; 300]);
+        let v = verify_backup(&b, "usb", &m.id).unwrap();
+        assert!(!v.ok);
+        assert_eq!(v.files[0].status, FileCheckStatus::Ok);
+        assert_eq!(v.files[1].status, FileCheckStatus::Corrupt);
+        assert!(matches!(
+            read_backup(&b, "usb", &m.id, None),
+            Err(DriveError::Integrity(_))
+        ));
+
+        let meta = object_path(&m.files[0].blake3_hex).unwrap();
+        b.remove_file("usb", &meta).unwrap();
+        let v = verify_backup(&b, "usb", &m.id).unwrap();
+        assert_eq!(v.files[0].status, FileCheckStatus::Missing);
+    }
+
+    #[test]
+    fn interrupted_backup_is_not_listed() {
+        let b = backend();
+        b.put_raw("usb", &format!("{BACKUP_ROOT}/partial/keys.enc"), vec![1]);
+        assert!(list_backups(&b, "usb").unwrap().is_empty());
+    }
+
+    #[test]
+    fn refuses_unready_drives_and_bad_names() {
+        let b = backend();
+        b.set_status("usb", DriveStatus::ReadOnly);
+        assert!(matches!(
+            create_backup(&b, "usb", &files(), Utc::now(), None),
+            Err(DriveError::NotReady(_))
+        ));
+        b.set_status("usb", DriveStatus::Ready);
+
+        let mut bad = files();
+        bad[0].name = "../vault.json".to_string();
+        assert!(create_backup(&b, "usb", &bad, Utc::now(), None).is_err());
+        assert!(load_manifest(&b, "usb", "a/b").is_err());
+        assert!(create_backup(&b, "missing", &files(), Utc::now(), None).is_err());
+    }
+
+    #[test]
+    fn progress_is_reported_in_order() {
+        let b = backend();
+        let (tx, rx) = std::sync::mpsc::channel();
+        create_backup(&b, "usb", &files(), Utc::now(), Some(&tx)).unwrap();
+        drop(tx);
+        let events: Vec<BackupProgress> = rx.iter().collect();
+        let stages: Vec<ProgressStage> = events.iter().map(|e| e.stage).collect();
+        assert_eq!(
+            stages,
+            [
+                ProgressStage::Preparing,
+                ProgressStage::Copying,
+                ProgressStage::Copying,
+                ProgressStage::Finalizing,
+                ProgressStage::Complete,
+            ]
+        );
+        assert_eq!(events[2].current_item.as_deref(), Some("keys.enc"));
+        assert_eq!(events[2].bytes_written, 20);
+        assert_eq!(events[3].percent, 100);
+        assert!(events.iter().all(|e| e.total_bytes == 320));
+        assert!(events.windows(2).all(|w| w[0].percent <= w[1].percent));
+    }
+
+    #[test]
+    fn out_of_space_leaves_no_listed_backup() {
+        let b = MockBackend::new();
+        b.add_drive(MockBackend::ready_drive("tiny", 100));
+        assert!(matches!(
+            create_backup(&b, "tiny", &files(), Utc::now(), None),
+            Err(DriveError::InsufficientSpace {
+                required: 49152,
+                available: 100
+            })
+        ));
+        assert!(b.paths("tiny").is_empty());
+        assert!(list_backups(&b, "tiny").unwrap().is_empty());
+    }
+
+    #[test]
+    fn estimate_rounds_to_blocks_and_checks_free_space() {
+        let b = backend();
+        let mut drive = MockBackend::ready_drive("usb", 49152);
+        let e = estimate_backup(&b, &drive, &files()).unwrap();
+        assert_eq!(e.payload_bytes, 320);
+        assert_eq!(e.deduplicated_bytes, 0);
+        // Two objects, plus a manifest sized for two signatures, a signer key
+        // and an instance signature.
+        assert_eq!(e.required_bytes, 12 * BLOCK_SIZE);
+        assert_eq!(e.files[1].size, 300);
+        assert!(e.fits);
+
+        drive.available_bytes = Some(49151);
+        assert!(!estimate_backup(&b, &drive, &files()).unwrap().fits);
+        drive.available_bytes = None;
+        assert!(estimate_backup(&b, &drive, &files()).unwrap().fits);
+
+        // Once stored, a repeat backup only needs room for its manifest.
+        create_backup(&b, "usb", &files(), Utc::now(), None).unwrap();
+        let e = estimate_backup(&b, &b.drive("usb").unwrap(), &files()).unwrap();
+        assert_eq!(e.deduplicated_bytes, 320);
+        assert_eq!(e.required_bytes, 10 * BLOCK_SIZE);
+        assert!(e.files.iter().all(|f| f.stored));
+    }
+
+    #[test]
+    fn repeated_backups_share_objects() {
+        let b = backend();
+        let first = create_backup(&b, "usb", &files(), Utc::now(), None).unwrap();
+        let objects = b.paths("usb").len();
+        let mut changed = files();
+        changed[1].data = vec![9u8; 300];
+        let second = create_backup(&b, "usb", &changed, Utc::now(), None).unwrap();
+        // One new object (the changed keystore) plus the second manifest.
+        assert_eq!(b.paths("usb").len(), objects + 2);
+        assert_eq!(first.files[0].blake3_hex, second.files[0].blake3_hex);
+        assert_eq!(read_backup(&b, "usb", &first.id, None).unwrap(), files());
+        assert_eq!(read_backup(&b, "usb", &second.id, None).unwrap(), changed);
+    }
+
+    #[test]
+    fn growth_counts_each_object_once() {
+        let b = backend();
+        let now = Utc::now();
+        let first = create_backup(&b, "usb", &files(), now, None).unwrap();
+        let mut changed = files();
+        changed[1].data = vec![9u8; 300];
+        let second =
+            create_backup(&b, "usb", &changed, now + chrono::Duration::days(1), None).unwrap();
+        let growth = backup_growth(&b, "usb").unwrap();
+        let ids: Vec<&str> = growth.iter().map(|g| g.backup_id.as_str()).collect();
+        assert_eq!(ids, [first.id.as_str(), second.id.as_str()]);
+        // Two objects and a manifest, then the changed keystore and a manifest.
+        assert_eq!(growth[0].added_bytes, 3 * BLOCK_SIZE);
+        assert_eq!(growth[1].added_bytes, 2 * BLOCK_SIZE);
+    }
+
+    #[test]
+    fn manifest_order_follows_the_input_whatever_the_workers_do() {
+        let many: Vec<VaultFile> = (0..40)
+            .map(|i| VaultFile {
+                name: format!("file{i:02}.json"),
+                data: vec![i as u8; 100 + i * 37],
+            })
+            .collect();
+        assert_eq!(
+            parallel_map(&many, |f| f.name.clone()),
+            many.iter().map(|f| f.name.clone()).collect::<Vec<_>>()
+        );
+        let b = backend();
+        let manifest = create_backup(&b, "usb", &many, Utc::now(), None).unwrap();
+        for (entry, f) in manifest.files.iter().zip(&many) {
+            assert_eq!(entry.name, f.name);
+            assert_eq!(entry.blake3_hex, hash_hex(&f.data));
+        }
+        assert_eq!(read_backup(&b, "usb", &manifest.id, None).unwrap(), many);
+    }
+
+    #[test]
+    fn index_tracks_backups_and_rebuilds_when_unreadable() {
+        let b = backend();
+        let key = [7u8; 32];
+        let first = create_backup(&b, "usb", &files(), Utc::now(), None).unwrap();
+        assert!(b.read_file("usb", INDEX_FILE).is_err());
+        assert_eq!(
+            list_backups_indexed(&b, "usb", &key).unwrap(),
+            vec![first.clone()]
+        );
+        assert!(b.read_file("usb", INDEX_FILE).is_ok());
+
+        let later = Utc::now() + chrono::Duration::seconds(1);
+        let second = create_backup(&b, "usb", &files(), later, None).unwrap();
+        add_to_index(&b, "usb", &key, &second).unwrap();
+        let listed = list_backups_indexed(&b, "usb", &key).unwrap();
+        assert_eq!(listed, vec![second.clone(), first.clone()]);
+        assert_eq!(listed, list_backups(&b, "usb").unwrap());
+
+        // A removed backup drops out; an index under another key is rebuilt.
+        b.remove_file("usb", &format!("{}/{MANIFEST_FILE}", backup_dir(&first.id)))
+            .unwrap();
+        assert_eq!(
+            list_backups_indexed(&b, "usb", &[8u8; 32]).unwrap(),
+            vec![second.clone()]
+        );
+        b.put_raw("usb", INDEX_FILE, b"garbage".to_vec());
+        assert_eq!(list_backups_indexed(&b, "usb", &key).unwrap(), vec![second]);
+    }
+
+    #[test]
+    fn damaged_object_is_detected_in_every_backup_and_rewritten() {
+        let b = backend();
+        let first = create_backup(&b, "usb", &files(), Utc::now(), None).unwrap();
+        let second = create_backup(&b, "usb", &files(), Utc::now(), None).unwrap();
+        let keys = object_path(&first.files[1].blake3_hex).unwrap();
+        b.put_raw("usb", &keys, vec![0u8; 300]);
+        assert!(!verify_backup(&b, "usb", &first.id).unwrap().ok);
+        assert!(!verify_backup(&b, "usb", &second.id).unwrap().ok);
+
+        create_backup(&b, "usb", &files(), Utc::now(), None).unwrap();
+        assert!(verify_backup(&b, "usb", &first.id).unwrap().ok);
+    }
+
+    #[test]
+    fn version_1_inline_backups_still_restore() {
+        let b = backend();
+        let f = files();
+        let manifest = BackupManifest {
+            version: INLINE_FORMAT_VERSION,
+            id: "20250101T000000Z-legacy01".to_string(),
+            created_at: Utc::now(),
+            mode: BackupMode::Container,
+            file_encryption: None,
+            files: f
+                .iter()
+                .map(|f| BackupFile {
+                    name: f.name.clone(),
+                    size: f.data.len() as u64,
+                    blake3_hex: hash_hex(&f.data),
+                    signature_hex: None,
+                })
+                .collect(),
+            signer_public_hex: None,
+            instance_signature: None,
+        };
+        let dir = backup_dir(&manifest.id);
+        for file in &f {
+            b.put_raw("usb", &format!("{dir}/{}", file.name), file.data.clone());
+        }
+        let json = serde_json::to_vec(&manifest).unwrap();
+        b.put_raw("usb", &format!("{dir}/{MANIFEST_FILE}"), json);
+
+        assert_eq!(list_backups(&b, "usb").unwrap(), vec![manifest.clone()]);
+        assert!(verify_backup(&b, "usb", &manifest.id).unwrap().ok);
+        assert_eq!(read_backup(&b, "usb", &manifest.id, None).unwrap(), f);
+    }
+
+    #[test]
+    fn file_encrypted_backups_verify_and_need_the_password() {
+        let b = backend();
+        let (pk, sk) = attestation::vault_identity(&[9u8; 64]);
+        let encryption = FileEncryption {
+            salt_hex: hex::encode([5u8; kdf::SALT_SIZE]),
+            kdf: crate::models::vault::KdfProfile {
+                version: kdf::KDF_VERSION,
+                memory_kib: 64,
+                iterations: 1,
+                parallelism: 1,
+            },
+        };
+        let key = file_key("pw", &encryption).unwrap();
+        let m = create_file_encrypted_backup(
+            &b,
+            "usb",
+            &files(),
+            Utc::now(),
+            &key,
+            encryption,
+            Some((&pk, &sk)),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(m.version, FILE_ENCRYPTED_FORMAT_VERSION);
+        assert_eq!(m.mode, BackupMode::FileEncrypted);
+        let json = b
+            .read_file("usb", &format!("{}/{MANIFEST_FILE}", backup_dir(&m.id)))
+            .unwrap();
+        assert!(String::from_utf8(json)
+            .unwrap()
+            .contains("\"file-encrypted\""));
+        assert!(verify_backup(&b, "usb", &m.id).unwrap().ok);
+
+        // Nothing readable is stored, and the files are not shared with a
+        // plain backup of the same contents.
+        let stored = read_backup(&b, "usb", &m.id, None).unwrap();
+        assert!(stored.iter().zip(files()).all(|(s, f)| s.data != f.data));
+        assert!(!b
+            .exists("usb", &object_path(&hash_hex(&files()[1].data)).unwrap())
+            .unwrap());
+
+        let m = load_manifest(&b, "usb", &m.id).unwrap();
+        assert_eq!(
+            decrypt_files(&m, Some("pw"), stored.clone()).unwrap(),
+            files()
+        );
+        assert!(matches!(
+            decrypt_files(&m, None, stored.clone()),
+            Err(DriveError::PasswordRequired(_))
+        ));
+        assert!(matches!(
+            decrypt_files(&m, Some("wrong"), stored),
+            Err(DriveError::Decrypt(_))
+        ));
+
+        // Dropping the mode would have an older release restore ciphertext.
+        let mut altered = m.clone();
+        altered.mode = BackupMode::Container;
+        b.put_raw(
+            "usb",
+            &format!("{}/{MANIFEST_FILE}", backup_dir(&m.id)),
+            serde_json::to_vec(&altered).unwrap(),
+        );
+        assert!(matches!(
+            load_manifest(&b, "usb", &m.id),
+            Err(DriveError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn object_hashes_cannot_escape_the_store() {
+        assert!(object_path("../../etc/passwd").is_err());
+        assert!(object_path(&"A".repeat(64)).is_err());
+        assert!(object_path(&"a".repeat(64)).is_ok());
+    }
+}
@@ -0,0 +1,306 @@
+//! Backup-set images: the vault's backup files on a drive, packed into one
+//! file so they can be copied onto another drive. They are file-level, not
+//! block-level images of the device: like the backends, this never touches the
+//! block device, so it needs no root and works on whatever filesystem the
+//! desktop mounted. An image is compressed, then encrypted, since the drive
+//! it came from is expected to be encrypted too.
+
+use super::backup::{hash_hex, on_disk, BACKUP_ROOT, INDEX_FILE, MANIFEST_FILE, OBJECT_ROOT};
+use super::{check_relative_path, DriveBackend, DriveError};
+use crate::crypto::encryption::{self, Ciphertext};
+use crate::models::drive_image::{
+    DriveImageEntry, DriveImageHeader, DriveImageSummary, DRIVE_IMAGE_FORMAT, DRIVE_IMAGE_MAGIC,
+    DRIVE_IMAGE_VERSION,
+};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// Directories an image holds, each two levels deep: a backup or object
+/// fan-out directory, then its files. The drive seal, notes, key drive and
+/// portable vault belong to the source drive and are left out.
+const IMAGED_DIRS: [&str; 2] = [BACKUP_ROOT, OBJECT_ROOT];
+
+fn imaged(path: &str) -> bool {
+    path == INDEX_FILE
+        || IMAGED_DIRS.iter().any(|root| {
+            path.strip_prefix(root)
+                .and_then(|rest| rest.strip_prefix('/'))
+                .is_some_and(|rest| rest.matches('/').count() == 1)
+        })
+}
+
+fn image_paths(backend: &dyn DriveBackend, drive_id: &str) -> Result<Vec<String>, DriveError> {
+    let mut paths = Vec::new();
+    for root in IMAGED_DIRS {
+        for sub in backend.list_dir(drive_id, root)? {
+            let dir = format!("{root}/{sub}");
+            for name in backend.list_dir(drive_id, &dir)? {
+                paths.push(format!("{dir}/{name}"));
+            }
+        }
+    }
+    Ok(paths)
+}
+
+fn backups_in(header: &DriveImageHeader) -> usize {
+    header
+        .files
+        .iter()
+        .filter(|f| f.path.starts_with(BACKUP_ROOT) && f.path.ends_with(MANIFEST_FILE))
+        .count()
+}
+
+fn summary(
+    header: &DriveImageHeader,
+    target_drive_id: Option<&str>,
+    image: &[u8],
+) -> DriveImageSummary {
+    DriveImageSummary {
+        source_drive_id: header.source_drive_id.clone(),
+        target_drive_id: target_drive_id.map(str::to_string),
+        created_at: header.created_at,
+        backups: backups_in(header),
+        files: header.files.len(),
+        payload_bytes: header.total_bytes(),
+        image_bytes: image.len() as u64,
+        image_blake3_hex: hash_hex(image),
+    }
+}
+
+/// Read the backups, objects and index on `drive_id` into an image sealed
+/// under `key`. Returns the image file's contents.
+pub fn create_image(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    key: &[u8; 32],
+    now: DateTime<Utc>,
+) -> Result<(DriveImageSummary, Vec<u8>), DriveError> {
+    let drive = backend.drive(drive_id)?;
+    let mut paths = image_paths(backend, drive_id)?;
+    // The index is a cache; it is carried along when there is one.
+    let index = match backend.read_file(drive_id, INDEX_FILE) {
+        Ok(data) => Some(data),
+        Err(DriveError::FileNotFound(_)) => None,
+        Err(e) => return Err(e),
+    };
+    if index.is_some() {
+        paths.push(INDEX_FILE.to_string());
+    }
+
+    let mut files = Vec::with_capacity(paths.len());
+    let mut contents = Vec::new();
+    for path in paths {
+        let data = match (&index, path.as_str()) {
+            (Some(index), INDEX_FILE) => index.clone(),
+            _ => backend.read_file(drive_id, &path)?,
+        };
+        files.push(DriveImageEntry {
+            size: data.len() as u64,
+            blake3_hex: hash_hex(&data),
+            path,
+        });
+        contents.extend_from_slice(&data);
+    }
+    let header = DriveImageHeader {
+        format: DRIVE_IMAGE_FORMAT.to_string(),
+        version: DRIVE_IMAGE_VERSION,
+        created_at: now,
+        source_drive_id: drive_id.to_string(),
+        source_label: drive.label,
+        files,
+    };
+    if backups_in(&header) == 0 {
+        return Err(DriveError::Malformed(format!(
+            "drive {drive_id} holds no backups"
+        )));
+    }
+
+    let io = |e: std::io::Error| DriveError::Io(e.to_string());
+    let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+    serde_json::to_writer(&mut gz, &header).map_err(|e| DriveError::Malformed(e.to_string()))?;
+    gz.write_all(b"\n").map_err(io)?;
+    gz.write_all(&contents).map_err(io)?;
+    let compressed = gz.finish().map_err(io)?;
+    let sealed = encryption::encrypt_aead(key, &compressed)
+        .map_err(|e| DriveError::Malformed(e.to_string()))?;
+    let image = [DRIVE_IMAGE_MAGIC, &sealed.nonce, &sealed.ciphertext].concat();
+    Ok((summary(&header, None, &image), image))
+}
+
+/// Decrypt and unpack an image, checking every file against its hash.
+/// Returns the header and each file's contents, in header order.
+pub fn open_image(
+    key: &[u8; 32],
+    image: &[u8],
+) -> Result<(DriveImageHeader, Vec<Vec<u8>>), DriveError> {
+    let sealed = image
+        .strip_prefix(DRIVE_IMAGE_MAGIC)
+        .filter(|rest| rest.len() > encryption::XCHACHA_NONCE_SIZE)
+        .ok_or_else(|| DriveError::Malformed("not a drive image".to_string()))?;
+    let (nonce, ciphertext) = sealed.split_at(encryption::XCHACHA_NONCE_SIZE);
+    let compressed = encryption::decrypt_aead(
+        key,
+        &Ciphertext {
+            nonce: nonce.to_vec(),
+            ciphertext: ciphertext.to_vec(),
+        },
+    )
+    .map_err(|_| {
+        DriveError::Malformed("drive image is damaged or from another vault".to_string())
+    })?;
+    let mut plain = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut plain)
+        .map_err(|e| DriveError::Malformed(format!("drive image: {e}")))?;
+
+    let split = plain
+        .iter()
+        .position(|b| *b == b'\n')
+        .ok_or_else(|| DriveError::Malformed("drive image has no header".to_string()))?;
+    let header: DriveImageHeader = serde_json::from_slice(&plain[..split])
+        .map_err(|e| DriveError::Malformed(format!("drive image header: {e}")))?;
+    if header.format != DRIVE_IMAGE_FORMAT || header.version != DRIVE_IMAGE_VERSION {
+        return Err(DriveError::Malformed(format!(
+            "unsupported drive image {} v{}",
+            header.format, header.version
+        )));
+    }
+    let mut rest = &plain[split + 1..];
+    let mut files = Vec::with_capacity(header.files.len());
+    for entry in &header.files {
+        check_relative_path(&entry.path)?;
+        if !imaged(&entry.path) {
+            return Err(DriveError::InvalidPath(entry.path.clone()));
+        }
+        let size = usize::try_from(entry.size)
+            .ok()
+            .filter(|size| *size <= rest.len())
+            .ok_or_else(|| DriveError::Integrity(entry.path.clone()))?;
+        let (data, tail) = rest.split_at(size);
+        if hash_hex(data) != entry.blake3_hex {
+            return Err(DriveError::Integrity(entry.path.clone()));
+        }
+        files.push(data.to_vec());
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        return Err(DriveError::Malformed(
+            "drive image has trailing data".to_string(),
+        ));
+    }
+    Ok((header, files))
+}
+
+/// Write an image onto `target_drive_id`, which must be writable and hold no
+/// backups yet. Manifests are written last, so an interrupted write lists
+/// no backup whose files are missing. Every file is read back and checked.
+pub fn write_image(
+    backend: &dyn DriveBackend,
+    target_drive_id: &str,
+    key: &[u8; 32],
+    image: &[u8],
+) -> Result<DriveImageSummary, DriveError> {
+    let (header, contents) = open_image(key, image)?;
+    let drive = backend.drive(target_drive_id)?;
+    if !drive.is_writable() {
+        return Err(DriveError::NotReady(target_drive_id.to_string()));
+    }
+    if !backend.list_dir(target_drive_id, BACKUP_ROOT)?.is_empty() {
+        return Err(DriveError::NotEmpty(target_drive_id.to_string()));
+    }
+    let required: u64 = header.files.iter().map(|f| on_disk(f.size)).sum();
+    if let Some(available) = drive.available_bytes.filter(|free| *free < required) {
+        return Err(DriveError::InsufficientSpace {
+            required,
+            available,
+        });
+    }
+
+    let mut order: Vec<_> = header.files.iter().zip(&contents).collect();
+    order.sort_by_key(|(entry, _)| entry.path.ends_with(MANIFEST_FILE));
+    for (entry, data) in &order {
+        backend.write_file(target_drive_id, &entry.path, data)?;
+    }
+    for (entry, _) in &order {
+        let data = backend.read_file(target_drive_id, &entry.path)?;
+        if hash_hex(&data) != entry.blake3_hex {
+            return Err(DriveError::Integrity(entry.path.clone()));
+        }
+    }
+    Ok(summary(&header, Some(target_drive_id), image))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drive::backup::{create_backup, list_backups, verify_backup, VaultFile};
+    use crate::drive::mock::MockBackend;
+
+    const KEY: [u8; 32] = [4u8; 32];
+
+    fn source() -> MockBackend {
+        let b = MockBackend::new();
+        b.add_drive(MockBackend::ready_drive("usb", 1 << 20));
+        b.add_drive(MockBackend::ready_drive("spare", 1 << 20));
+        for n in 0..2u8 {
+            let files = vec![VaultFile {
+                name: "vault.json".to_string(),
+                data: vec![n; 500],
+            }];
+            create_backup(&b, "usb", &files, Utc::now(), None).unwrap();
+        }
+        b.put_raw("usb", "zap-drive-seal.json", b"{}".to_vec());
+        b.put_raw("usb", INDEX_FILE, b"index".to_vec());
+        b
+    }
+
+    #[test]
+    fn image_clones_backups_onto_another_drive() {
+        let b = source();
+        let (made, image) = create_image(&b, "usb", &KEY, Utc::now()).unwrap();
+        assert_eq!(made.backups, 2);
+        assert_eq!(made.files, 5);
+        assert_eq!(made.image_blake3_hex, hash_hex(&image));
+
+        let written = write_image(&b, "spare", &KEY, &image).unwrap();
+        assert_eq!(written.target_drive_id.as_deref(), Some("spare"));
+        assert_eq!(
+            list_backups(&b, "spare").unwrap(),
+            list_backups(&b, "usb").unwrap()
+        );
+        for m in list_backups(&b, "spare").unwrap() {
+            assert!(verify_backup(&b, "spare", &m.id).unwrap().ok);
+        }
+        assert_eq!(b.read_file("spare", INDEX_FILE).unwrap(), b"index");
+        assert!(matches!(
+            b.read_file("spare", "zap-drive-seal.json"),
+            Err(DriveError::FileNotFound(_))
+        ));
+
+        // Never merged into a drive that already holds backups.
+        assert!(matches!(
+            write_image(&b, "usb", &KEY, &image),
+            Err(DriveError::NotEmpty(_))
+        ));
+    }
+
+    #[test]
+    fn damaged_or_foreign_images_are_refused() {
+        let b = source();
+        let (_, image) = create_image(&b, "usb", &KEY, Utc::now()).unwrap();
+        assert!(matches!(
+            open_image(&[5u8; 32], &image),
+            Err(DriveError::Malformed(_))
+        ));
+        let mut flipped = image.clone();
+        *flipped.last_mut().unwrap() ^= 1;
+        assert!(open_image(&KEY, &flipped).is_err());
+        assert!(open_image(&KEY, b"not an image").is_err());
+        assert!(imaged("zap-vault-objects/ab/abcd"));
+        assert!(!imaged("zap-drive-seal.json"));
+        assert!(!imaged("zap-key-drive/unlock.json"));
+    }
+}
@@ -0,0 +1,300 @@
+use super::DriveError;
+use crate::models::drive::{DriveEncryption, DriveInfo, DriveStatus};
+use serde_json::Value;
+use std::process::Command;
+
+const LSBLK_COLUMNS: &str =
+    "NAME,PATH,TYPE,SIZE,FSTYPE,LABEL,UUID,MOUNTPOINT,FSAVAIL,RM,HOTPLUG,RO,TRAN";
+
+/// Buses whose disks are removable media even when the kernel's `removable`
+/// flag is off, as it is for SD cards and many USB SSDs.
+const REMOVABLE_TRANSPORTS: &[&str] = &["usb", "mmc", "ieee1394", "memstick"];
+/// Buses of internal disks. Their hot-swap bays set `hotplug`, which on a
+/// server would otherwise offer every data disk.
+const FIXED_TRANSPORTS: &[&str] = &["sata", "ata", "sas", "scsi", "fc", "iscsi", "spi"];
+/// Mount points that mean a disk holds the running system, as an SD card
+/// does on a single-board computer.
+const SYSTEM_MOUNTS: &[&str] = &["/", "/boot", "/boot/efi", "/usr", "/var", "/home", "[SWAP]"];
+
+/// Detect removable drives with `lsblk`, which needs no root.
+pub fn list_drives() -> Result<Vec<DriveInfo>, DriveError> {
+    let out = Command::new("lsblk")
+        .args(["-J", "-b", "-o", LSBLK_COLUMNS])
+        .output()
+        .map_err(|e| DriveError::Io(format!("lsblk: {e}")))?;
+    if !out.status.success() {
+        return Err(DriveError::Io(
+            String::from_utf8_lossy(&out.stderr).trim().to_string(),
+        ));
+    }
+    parse_lsblk(&out.stdout)
+}
+
+/// lsblk prints flags as JSON booleans on util-linux >= 2.37 and as "0"/"1"
+/// strings before that, and sizes as numbers or strings depending on flags.
+fn flag(v: &Value, key: &str) -> bool {
+    match &v[key] {
+        Value::Bool(b) => *b,
+        Value::String(s) => s == "1",
+        Value::Number(n) => n.as_u64() == Some(1),
+        _ => false,
+    }
+}
+
+fn number(v: &Value, key: &str) -> Option<u64> {
+    match &v[key] {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn text(v: &Value, key: &str) -> Option<String> {
+    v[key]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Turn `lsblk -J -b -o` output into the removable drives the vault can
+/// offer. Fixed disks are skipped; an unlocked LUKS or BitLocker container
+/// is reported through its mapped (`crypt`) device, a locked one as
+/// [`DriveStatus::Locked`].
+pub fn parse_lsblk(json: &[u8]) -> Result<Vec<DriveInfo>, DriveError> {
+    let root: Value =
+        serde_json::from_slice(json).map_err(|e| DriveError::Io(format!("lsblk output: {e}")))?;
+    let mut drives = Vec::new();
+    for dev in root["blockdevices"].as_array().into_iter().flatten() {
+        if is_removable(dev) && !holds_system(dev) {
+            collect(dev, None, &mut drives);
+        }
+    }
+    Ok(drives)
+}
+
+/// Whether a whole disk is removable media, from its `removable` flag or
+/// its bus. `TRAN` is empty for SD cards on older lsblk, so `mmcblk` disks
+/// are recognized by name. Partition naming (`sdb1`, `mmcblk0p1`,
+/// `nvme1n1p1`) does not matter: devices are addressed by lsblk's `PATH`.
+fn is_removable(dev: &Value) -> bool {
+    let tran = text(dev, "tran");
+    let tran = tran.as_deref();
+    let name = text(dev, "name").unwrap_or_default();
+    flag(dev, "rm")
+        || tran.is_some_and(|t| REMOVABLE_TRANSPORTS.contains(&t))
+        || (name.starts_with("mmcblk") && !name.contains("boot") && !name.ends_with("rpmb"))
+        || (flag(dev, "hotplug") && !tran.is_some_and(|t| FIXED_TRANSPORTS.contains(&t)))
+}
+
+fn holds_system(dev: &Value) -> bool {
+    text(dev, "mountpoint").is_some_and(|m| SYSTEM_MOUNTS.contains(&m.as_str()))
+        || dev["children"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .any(holds_system)
+}
+
+/// The encrypted container a filesystem sits in, and the container's UUID,
+/// empty if lsblk did not report one.
+type Container<'a> = Option<(DriveEncryption, &'a str)>;
+
+fn collect(dev: &Value, container: Container<'_>, out: &mut Vec<DriveInfo>) {
+    let children = dev["children"].as_array();
+    let fs_type = text(dev, "fstype");
+    let encryption = match fs_type.as_deref() {
+        Some("crypto_LUKS") => Some(DriveEncryption::Luks),
+        // blkid's name for BitLocker volumes; `cryptsetup bitlkOpen` maps
+        // them like LUKS.
+        Some("BitLocker") => Some(DriveEncryption::BitLocker),
+        _ => None,
+    };
+    match (encryption, fs_type.as_deref()) {
+        (Some(encryption), _) => {
+            let uuid = text(dev, "uuid");
+            let uuid = uuid.as_deref().unwrap_or_default();
+            let opened = children.map(|c| !c.is_empty()).unwrap_or(false);
+            if opened {
+                for child in children.into_iter().flatten() {
+                    collect(child, Some((encryption, uuid)), out);
+                }
+            } else {
+                out.push(info(
+                    dev,
+                    fs_type,
+                    Some((encryption, uuid)),
+                    DriveStatus::Locked,
+                ));
+            }
+        }
+        (None, Some("swap")) => {}
+        (None, Some(_)) => {
+            let status = match text(dev, "mountpoint") {
+                None => DriveStatus::NotMounted,
+                Some(_) if flag(dev, "ro") => DriveStatus::ReadOnly,
+                Some(_) => DriveStatus::Ready,
+            };
+            out.push(info(dev, fs_type, container, status));
+        }
+        (None, None) => {
+            for child in children.into_iter().flatten() {
+                collect(child, container, out);
+            }
+        }
+    }
+}
+
+/// `container` is `Some` for anything inside (or being) an encrypted
+/// container. Only LUKS header UUIDs are recorded, for the drive seal.
+fn info(
+    dev: &Value,
+    fs_type: Option<String>,
+    container: Container<'_>,
+    status: DriveStatus,
+) -> DriveInfo {
+    let device = text(dev, "path")
+        .or_else(|| text(dev, "name").map(|n| format!("/dev/{n}")))
+        .unwrap_or_default();
+    DriveInfo {
+        id: text(dev, "uuid").unwrap_or_else(|| device.clone()),
+        device,
+        label: text(dev, "label"),
+        fs_type,
+        size_bytes: number(dev, "size").unwrap_or(0),
+        available_bytes: number(dev, "fsavail"),
+        mount_point: text(dev, "mountpoint"),
+        encrypted: container.is_some(),
+        encryption: container.map(|(encryption, _)| encryption),
+        luks_uuid: container
+            .filter(|(encryption, uuid)| *encryption == DriveEncryption::Luks && !uuid.is_empty())
+            .map(|(_, uuid)| uuid.to_string()),
+        status,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LSBLK: &str = r#"{"blockdevices": [
+        {"name":"nvme0n1","path":"/dev/nvme0n1","type":"disk","size":512110190592,
+         "fstype":null,"rm":false,"hotplug":false,"ro":false,
+         "children":[{"name":"nvme0n1p1","path":"/dev/nvme0n1p1","type":"part","size":1000,
+            "fstype":"ext4","uuid":"root-uuid","mountpoint":"/","fsavail":500,"rm":false,"ro":false}]},
+        {"name":"sdb","path":"/dev/sdb","type":"disk","size":"32010928128","fstype":null,
+         "rm":"1","hotplug":"1","ro":"0",
+         "children":[
+            {"name":"sdb1","path":"/dev/sdb1","type":"part","size":"1000","fstype":"vfat",
+             "label":"ZAPBACKUP","uuid":"AB12-CD34","mountpoint":"/media/u/ZAPBACKUP",
+             "fsavail":"800","rm":"1","ro":"0"},
+            {"name":"sdb2","path":"/dev/sdb2","type":"part","size":"2000","fstype":"crypto_LUKS",
+             "uuid":"luks-locked","mountpoint":null,"rm":"1","ro":"0"}]},
+        {"name":"sdc","path":"/dev/sdc","type":"disk","size":64000,"fstype":"crypto_LUKS",
+         "uuid":"luks-outer","rm":true,"hotplug":true,"ro":false,
+         "children":[{"name":"luks-outer","path":"/dev/mapper/luks-outer","type":"crypt",
+            "size":63000,"fstype":"ext4","label":"VAULT","uuid":"inner-uuid",
+            "mountpoint":"/media/u/VAULT","fsavail":60000,"rm":false,"ro":true}]},
+        {"name":"sdd","path":"/dev/sdd","type":"disk","size":1000,"fstype":"exfat",
+         "uuid":"unmounted","mountpoint":null,"rm":true,"hotplug":true,"ro":false},
+        {"name":"sde","path":"/dev/sde","type":"disk","size":8000,"fstype":null,
+         "rm":true,"hotplug":true,"ro":false,
+         "children":[
+            {"name":"sde1","path":"/dev/sde1","type":"part","size":4000,"fstype":"BitLocker",
+             "uuid":"bitlocker-locked","rm":true,"ro":false},
+            {"name":"sde2","path":"/dev/sde2","type":"part","size":4000,"fstype":"BitLocker",
+             "uuid":"bitlocker-open","rm":true,"ro":false,
+             "children":[{"name":"bitlk-e2","path":"/dev/mapper/bitlk-e2","type":"crypt",
+                "size":3900,"fstype":"ntfs","uuid":"ntfs-uuid","mountpoint":"/media/u/WIN",
+                "fsavail":3000,"rm":false,"ro":false}]}]}
+    ]}"#;
+
+    #[test]
+    fn parses_removable_drives_in_both_lsblk_flag_formats() {
+        let drives = parse_lsblk(LSBLK.as_bytes()).unwrap();
+        let ids: Vec<&str> = drives.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "AB12-CD34",
+                "luks-locked",
+                "inner-uuid",
+                "unmounted",
+                "bitlocker-locked",
+                "ntfs-uuid"
+            ]
+        );
+
+        let usb = &drives[0];
+        assert_eq!(usb.status, DriveStatus::Ready);
+        assert_eq!(usb.label.as_deref(), Some("ZAPBACKUP"));
+        assert_eq!(usb.available_bytes, Some(800));
+        assert!(!usb.encrypted);
+        assert_eq!(usb.encryption, None);
+        assert_eq!(usb.luks_uuid, None);
+
+        assert_eq!(drives[1].status, DriveStatus::Locked);
+        assert!(drives[1].encrypted);
+        assert_eq!(drives[1].encryption, Some(DriveEncryption::Luks));
+
+        let vault = &drives[2];
+        assert!(vault.encrypted);
+        assert_eq!(vault.encryption, Some(DriveEncryption::Luks));
+        assert_eq!(vault.luks_uuid.as_deref(), Some("luks-outer"));
+        assert_eq!(vault.device, "/dev/mapper/luks-outer");
+        assert_eq!(vault.status, DriveStatus::ReadOnly);
+
+        assert_eq!(drives[3].status, DriveStatus::NotMounted);
+
+        assert_eq!(drives[4].status, DriveStatus::Locked);
+        assert_eq!(drives[4].encryption, Some(DriveEncryption::BitLocker));
+        assert_eq!(drives[4].luks_uuid, None);
+        let windows = &drives[5];
+        assert_eq!(windows.status, DriveStatus::Ready);
+        assert!(windows.encrypted);
+        assert_eq!(windows.encryption, Some(DriveEncryption::BitLocker));
+        assert_eq!(windows.luks_uuid, None);
+    }
+
+    #[test]
+    fn recognizes_sd_cards_and_skips_server_and_system_disks() {
+        let lsblk = r#"{"blockdevices": [
+            {"name":"mmcblk0","path":"/dev/mmcblk0","type":"disk","size":32000,"tran":null,
+             "rm":false,"hotplug":false,"ro":false,
+             "children":[{"name":"mmcblk0p1","path":"/dev/mmcblk0p1","type":"part","size":32000,
+                "fstype":"exfat","uuid":"sd-card","mountpoint":"/media/u/SD","rm":false,"ro":false}]},
+            {"name":"mmcblk0boot0","path":"/dev/mmcblk0boot0","type":"disk","size":4000,
+             "rm":false,"hotplug":false,"ro":true},
+            {"name":"mmcblk1","path":"/dev/mmcblk1","type":"disk","size":16000,"tran":"mmc",
+             "rm":false,"hotplug":false,"ro":false,
+             "children":[
+                {"name":"mmcblk1p1","path":"/dev/mmcblk1p1","type":"part","size":500,
+                 "fstype":"vfat","uuid":"pi-boot","mountpoint":"/boot","ro":false},
+                {"name":"mmcblk1p2","path":"/dev/mmcblk1p2","type":"part","size":15500,
+                 "fstype":"ext4","uuid":"pi-root","mountpoint":"/","ro":false}]},
+            {"name":"sdq","path":"/dev/sdq","type":"disk","size":4000000,"tran":"sas",
+             "rm":false,"hotplug":true,"ro":false,
+             "children":[{"name":"sdq1","path":"/dev/sdq1","type":"part","size":4000000,
+                "fstype":"xfs","uuid":"server-data","mountpoint":"/srv/data","ro":false}]},
+            {"name":"sdr","path":"/dev/sdr","type":"disk","size":500000,"tran":"usb",
+             "rm":false,"hotplug":false,"ro":false,
+             "children":[{"name":"sdr1","path":"/dev/sdr1","type":"part","size":500000,
+                "fstype":"ntfs","uuid":"usb-ssd","mountpoint":"/media/u/SSD","ro":false}]},
+            {"name":"nvme1n1","path":"/dev/nvme1n1","type":"disk","size":900000,"tran":"nvme",
+             "rm":false,"hotplug":true,"ro":false,
+             "children":[{"name":"nvme1n1p1","path":"/dev/nvme1n1p1","type":"part","size":900000,
+                "fstype":"ext4","uuid":"thunderbolt","mountpoint":null,"ro":false}]}
+        ]}"#;
+        let drives = parse_lsblk(lsblk.as_bytes()).unwrap();
+        let ids: Vec<&str> = drives.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["sd-card", "usb-ssd", "thunderbolt"]);
+        assert_eq!(drives[0].device, "/dev/mmcblk0p1");
+        assert_eq!(drives[0].status, DriveStatus::Ready);
+        assert_eq!(drives[2].device, "/dev/nvme1n1p1");
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(parse_lsblk(b"not json").is_err());
+        assert!(parse_lsblk(b"{}").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+use super::DriveError;
+use crate::models::drive::{DriveEncryption, DriveInfo, DriveStatus};
+use std::collections::HashMap;
+use std::process::Command;
+
+/// Detect external volumes with `diskutil`, which needs no root.
+pub fn list_drives() -> Result<Vec<DriveInfo>, DriveError> {
+    let out = Command::new("diskutil")
+        .args(["info", "-all"])
+        .output()
+        .map_err(|e| DriveError::Io(format!("diskutil: {e}")))?;
+    if !out.status.success() {
+        return Err(DriveError::Io(
+            String::from_utf8_lossy(&out.stderr).trim().to_string(),
+        ));
+    }
+    Ok(parse_diskutil(&String::from_utf8_lossy(&out.stdout)))
+}
+
+fn yes(record: &HashMap<&str, &str>, key: &str) -> bool {
+    record.get(key).is_some_and(|v| v.starts_with("Yes"))
+}
+
+/// `500.1 GB (500107862016 Bytes) (exactly ...)` → `500107862016`.
+fn bytes(record: &HashMap<&str, &str>, key: &str) -> Option<u64> {
+    let value = record.get(key)?;
+    let (_, rest) = value.split_once('(')?;
+    rest.split_once(" Bytes")?.0.trim().parse().ok()
+}
+
+fn text(record: &HashMap<&str, &str>, key: &str) -> Option<String> {
+    record
+        .get(key)
+        .filter(|v| !v.is_empty() && !v.starts_with("Not applicable"))
+        .map(|v| v.to_string())
+}
+
+/// Turn `diskutil info -all` output into the external volumes the vault can
+/// offer. Whole disks, APFS containers and their physical stores have no
+/// filesystem and are skipped. An encrypted APFS volume that has not been
+/// unlocked is [`DriveStatus::Locked`].
+pub fn parse_diskutil(output: &str) -> Vec<DriveInfo> {
+    let mut drives = Vec::new();
+    for block in output.split("**********") {
+        let record: HashMap<&str, &str> = block
+            .lines()
+            .filter_map(|l| l.split_once(':'))
+            .map(|(k, v)| (k.trim(), v.trim()))
+            .collect();
+        let external = record.get("Device Location") == Some(&"External")
+            || record.get("Removable Media") == Some(&"Removable");
+        let Some(fs_type) = text(&record, "Type (Bundle)") else {
+            continue;
+        };
+        if !external {
+            continue;
+        }
+        let device = text(&record, "Device Node").unwrap_or_default();
+        let encryption = (fs_type == "apfs"
+            && (yes(&record, "FileVault") || yes(&record, "Encrypted")))
+        .then_some(DriveEncryption::Apfs);
+        let mount_point = text(&record, "Mount Point").filter(|_| yes(&record, "Mounted"));
+        let status = if yes(&record, "Locked") {
+            DriveStatus::Locked
+        } else if mount_point.is_none() {
+            DriveStatus::NotMounted
+        } else if yes(&record, "Volume Read-Only") || yes(&record, "Media Read-Only") {
+            DriveStatus::ReadOnly
+        } else {
+            DriveStatus::Ready
+        };
+        drives.push(DriveInfo {
+            id: text(&record, "Volume UUID").unwrap_or_else(|| device.clone()),
+            device,
+            label: text(&record, "Volume Name"),
+            fs_type: Some(fs_type),
+            size_bytes: bytes(&record, "Disk Size").unwrap_or(0),
+            available_bytes: bytes(&record, "Volume Free Space")
+                .or_else(|| bytes(&record, "Container Free Space")),
+            mount_point,
+            encrypted: encryption.is_some(),
+            encryption,
+            luks_uuid: None,
+            status,
+        });
+    }
+    drives
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISKUTIL: &str = "   Device Identifier:         disk0s1
+   Device Node:               /dev/disk0s1
+   Whole:                     No
+   Volume Name:               Macintosh HD
+   Mounted:                   Yes
+   Mount Point:               /
+   Type (Bundle):             apfs
+   Device Location:           Internal
+   Removable Media:           Fixed
+   FileVault:                 Yes
+**********
+
+   Device Identifier:         disk4
+   Device Node:               /dev/disk4
+   Whole:                     Yes
+   Device Location:           External
+   Removable Media:           Removable
+**********
+
+   Device Identifier:         disk4s1
+   Device Node:               /dev/disk4s1
+   Whole:                     No
+   Volume Name:               BACKUP
+   Mounted:                   Yes
+   Mount Point:               /Volumes/BACKUP
+   Type (Bundle):             apfs
+   Volume UUID:               6B2C1D0E-0000-4000-8000-000000000001
+   Disk Size:                 64.0 GB (64023257088 Bytes) (exactly 125045424 512-Byte-Units)
+   Container Free Space:      60.0 GB (60000000000 Bytes) (exactly 117187500 512-Byte-Units)
+   Media Read-Only:           No
+   Volume Read-Only:          No
+   Device Location:           External
+   Removable Media:           Removable
+   Encrypted:                 Yes
+   FileVault:                 Yes
+   Locked:                    No
+**********
+
+   Device Identifier:         disk5s1
+   Device Node:               /dev/disk5s1
+   Whole:                     No
+   Volume Name:               SEALED
+   Mounted:                   No
+   Mount Point:               Not applicable (no file system)
+   Type (Bundle):             apfs
+   Volume UUID:               6B2C1D0E-0000-4000-8000-000000000002
+   Device Location:           External
+   Encrypted:                 Yes
+   FileVault:                 Yes
+   Locked:                    Yes
+**********
+
+   Device Identifier:         disk6s1
+   Device Node:               /dev/disk6s1
+   Whole:                     No
+   Volume Name:               STICK
+   Mounted:                   Yes
+   Mount Point:               /Volumes/STICK
+   Type (Bundle):             msdos
+   Volume UUID:               6B2C1D0E-0000-4000-8000-000000000003
+   Disk Size:                 8.0 GB (8004304896 Bytes) (exactly 15633408 512-Byte-Units)
+   Volume Free Space:         7.9 GB (7900000000 Bytes) (exactly 15429688 512-Byte-Units)
+   Media Read-Only:           Yes
+   Device Location:           External
+   Removable Media:           Removable
+";
+
+    #[test]
+    fn parses_external_volumes_and_apfs_encryption() {
+        let drives = parse_diskutil(DISKUTIL);
+        let labels: Vec<&str> = drives.iter().filter_map(|d| d.label.as_deref()).collect();
+        assert_eq!(labels, ["BACKUP", "SEALED", "STICK"]);
+
+        let backup = &drives[0];
+        assert_eq!(backup.id, "6B2C1D0E-0000-4000-8000-000000000001");
+        assert_eq!(backup.status, DriveStatus::Ready);
+        assert_eq!(backup.encryption, Some(DriveEncryption::Apfs));
+        assert!(backup.encrypted);
+        assert_eq!(backup.size_bytes, 64_023_257_088);
+        assert_eq!(backup.available_bytes, Some(60_000_000_000));
+        assert_eq!(backup.mount_point.as_deref(), Some("/Volumes/BACKUP"));
+
+        assert_eq!(drives[1].status, DriveStatus::Locked);
+        assert_eq!(drives[1].mount_point, None);
+        assert!(drives[1].encrypted);
+
+        let stick = &drives[2];
+        assert_eq!(stick.status, DriveStatus::ReadOnly);
+        assert_eq!(stick.encryption, None);
+        assert_eq!(stick.available_bytes, Some(7_900_000_000));
+    }
+}
@@ -0,0 +1,188 @@
+use super::{check_relative_path, DriveBackend, DriveError};
+use crate::models::drive::{DriveInfo, DriveStatus};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+struct MockDrive {
+    info: DriveInfo,
+    files: BTreeMap<String, Vec<u8>>,
+    modified: BTreeMap<String, DateTime<Utc>>,
+}
+
+/// In-memory drives for tests and CI. Free space is tracked from
+/// `available_bytes` so out-of-space paths can be exercised too.
+#[derive(Default)]
+pub struct MockBackend {
+    drives: Mutex<BTreeMap<String, MockDrive>>,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A ready, mounted drive with `capacity` bytes free.
+    pub fn ready_drive(id: &str, capacity: u64) -> DriveInfo {
+        DriveInfo {
+            id: id.to_string(),
+            device: format!("/dev/mock-{id}"),
+            label: Some(id.to_uppercase()),
+            fs_type: Some("ext4".to_string()),
+            size_bytes: capacity,
+            available_bytes: Some(capacity),
+            mount_point: Some(format!("/media/mock/{id}")),
+            encrypted: false,
+            encryption: None,
+            luks_uuid: None,
+            status: DriveStatus::Ready,
+        }
+    }
+
+    pub fn add_drive(&self, info: DriveInfo) {
+        self.drives.lock().unwrap().insert(
+            info.id.clone(),
+            MockDrive {
+                info,
+                files: BTreeMap::new(),
+                modified: BTreeMap::new(),
+            },
+        );
+    }
+
+    /// Simulate unplugging a drive.
+    pub fn remove_drive(&self, drive_id: &str) {
+        self.drives.lock().unwrap().remove(drive_id);
+    }
+
+    pub fn set_status(&self, drive_id: &str, status: DriveStatus) {
+        if let Some(d) = self.drives.lock().unwrap().get_mut(drive_id) {
+            d.info.status = status;
+        }
+    }
+
+    /// Overwrite a file directly, bypassing the checks (e.g. to simulate bit-rot).
+    pub fn put_raw(&self, drive_id: &str, path: &str, data: Vec<u8>) {
+        if let Some(d) = self.drives.lock().unwrap().get_mut(drive_id) {
+            d.files.insert(path.to_string(), data);
+            d.modified.insert(path.to_string(), Utc::now());
+        }
+    }
+
+    /// Backdate or postdate a file, as a copy tool preserving times would.
+    pub fn set_modified(&self, drive_id: &str, path: &str, at: DateTime<Utc>) {
+        if let Some(d) = self.drives.lock().unwrap().get_mut(drive_id) {
+            d.modified.insert(path.to_string(), at);
+        }
+    }
+
+    pub fn set_luks_uuid(&self, drive_id: &str, luks_uuid: Option<&str>) {
+        if let Some(d) = self.drives.lock().unwrap().get_mut(drive_id) {
+            d.info.luks_uuid = luks_uuid.map(str::to_string);
+        }
+    }
+
+    pub fn paths(&self, drive_id: &str) -> Vec<String> {
+        self.drives
+            .lock()
+            .unwrap()
+            .get(drive_id)
+            .map(|d| d.files.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn with_drive<T>(
+        &self,
+        drive_id: &str,
+        path: &str,
+        write: bool,
+        f: impl FnOnce(&mut MockDrive) -> Result<T, DriveError>,
+    ) -> Result<T, DriveError> {
+        check_relative_path(path)?;
+        let mut drives = self.drives.lock().unwrap();
+        let drive = drives
+            .get_mut(drive_id)
+            .ok_or_else(|| DriveError::NotFound(drive_id.to_string()))?;
+        let readable = matches!(
+            drive.info.status,
+            DriveStatus::Ready | DriveStatus::ReadOnly
+        );
+        if !readable || (write && !drive.info.is_writable()) {
+            return Err(DriveError::NotReady(drive_id.to_string()));
+        }
+        f(drive)
+    }
+}
+
+impl DriveBackend for MockBackend {
+    fn list_drives(&self) -> Result<Vec<DriveInfo>, DriveError> {
+        Ok(self
+            .drives
+            .lock()
+            .unwrap()
+            .values()
+            .map(|d| d.info.clone())
+            .collect())
+    }
+
+    fn read_file(&self, drive_id: &str, path: &str) -> Result<Vec<u8>, DriveError> {
+        self.with_drive(drive_id, path, false, |d| {
+            d.files
+                .get(path)
+                .cloned()
+                .ok_or_else(|| DriveError::FileNotFound(path.to_string()))
+        })
+    }
+
+    fn write_file(&self, drive_id: &str, path: &str, data: &[u8]) -> Result<(), DriveError> {
+        self.with_drive(drive_id, path, true, |d| {
+            let old = d.files.get(path).map(|f| f.len() as u64).unwrap_or(0);
+            if let Some(free) = d.info.available_bytes {
+                let free = free + old;
+                if data.len() as u64 > free {
+                    return Err(DriveError::Io("no space left on device".to_string()));
+                }
+                d.info.available_bytes = Some(free - data.len() as u64);
+            }
+            d.files.insert(path.to_string(), data.to_vec());
+            d.modified.insert(path.to_string(), Utc::now());
+            Ok(())
+        })
+    }
+
+    fn list_dir(&self, drive_id: &str, dir: &str) -> Result<Vec<String>, DriveError> {
+        self.with_drive(drive_id, dir, false, |d| {
+            let prefix = format!("{dir}/");
+            let names: BTreeSet<String> = d
+                .files
+                .keys()
+                .filter_map(|p| p.strip_prefix(&prefix))
+                .map(|rest| rest.split('/').next().unwrap_or(rest).to_string())
+                .collect();
+            Ok(names.into_iter().collect())
+        })
+    }
+
+    fn remove_file(&self, drive_id: &str, path: &str) -> Result<(), DriveError> {
+        self.with_drive(drive_id, path, true, |d| {
+            let removed = d
+                .files
+                .remove(path)
+                .ok_or_else(|| DriveError::FileNotFound(path.to_string()))?;
+            d.modified.remove(path);
+            if let Some(free) = d.info.available_bytes.as_mut() {
+                *free += removed.len() as u64;
+            }
+            Ok(())
+        })
+    }
+
+    fn modified(&self, drive_id: &str, path: &str) -> Result<Option<DateTime<Utc>>, DriveError> {
+        self.with_drive(drive_id, path, false, |d| {
+            if !d.files.contains_key(path) {
+                return Err(DriveError::FileNotFound(path.to_string()));
+            }
+            Ok(d.modified.get(path).copied())
+        })
+    }
+}
@@ -0,0 +1,168 @@
+//! Removable-drive access for vault backups.
+//!
+//! Everything that touches a device or a mounted filesystem goes through
+//! [`DriveBackend`], so the backup engine in [`backup`] (and [`scrub`]) runs unchanged against
+//! real drives ([`mounted::MountedBackend`]) and in tests or CI ([`mock::MockBackend`]).
+//! Backends never mount, format or unlock devices and never need root: they
+//! work with whatever the desktop has already mounted.
+//!
+//! Detection is per platform ([`linux`], [`macos`], [`windows`]). The parsers
+//! are built everywhere so their tests run on any host.
+
+pub mod backup;
+pub mod image;
+pub mod linux;
+pub mod macos;
+pub mod mock;
+pub mod mounted;
+pub mod remote;
+pub mod scrub;
+pub mod seal;
+pub mod windows;
+
+use crate::models::drive::DriveInfo;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DriveError {
+    #[error("drive not found: {0}")]
+    NotFound(String),
+    #[error("drive {0} is not ready (not mounted, locked or read-only)")]
+    NotReady(String),
+    #[error("file not found on drive: {0}")]
+    FileNotFound(String),
+    #[error("invalid drive path: {0}")]
+    InvalidPath(String),
+    #[error("drive access is not supported on this platform")]
+    Unsupported,
+    #[error("malformed backup: {0}")]
+    Malformed(String),
+    #[error("not enough space on drive: backup needs {required} bytes, {available} available")]
+    InsufficientSpace { required: u64, available: u64 },
+    #[error("backup integrity check failed: {0}")]
+    Integrity(String),
+    #[error("backup {0} is file-encrypted: enter the vault password it was made with")]
+    PasswordRequired(String),
+    #[error("could not decrypt {0}: wrong password or damaged backup")]
+    Decrypt(String),
+    #[error("backup {0} is not file-encrypted: only file-encrypted backups leave the drive")]
+    NotFileEncrypted(String),
+    #[error("drive {0} already holds vault backups")]
+    NotEmpty(String),
+    #[error("drive I/O error: {0}")]
+    Io(String),
+}
+
+/// Device and file operations on removable drives. Paths are relative to the
+/// drive's mount point and use `/` separators.
+pub trait DriveBackend: Send + Sync {
+    /// Detect removable drives, mounted or not.
+    fn list_drives(&self) -> Result<Vec<DriveInfo>, DriveError>;
+    fn read_file(&self, drive_id: &str, path: &str) -> Result<Vec<u8>, DriveError>;
+    /// Write `data` so that either the old or the new contents are observed
+    /// after a crash or an unplug, creating parent directories as needed.
+    fn write_file(&self, drive_id: &str, path: &str, data: &[u8]) -> Result<(), DriveError>;
+    /// Names of the entries directly under `dir`; empty if it does not exist.
+    fn list_dir(&self, drive_id: &str, dir: &str) -> Result<Vec<String>, DriveError>;
+    fn remove_file(&self, drive_id: &str, path: &str) -> Result<(), DriveError>;
+
+    /// When `path` was last modified, or `None` if the backend cannot tell.
+    fn modified(&self, _drive_id: &str, _path: &str) -> Result<Option<DateTime<Utc>>, DriveError> {
+        Ok(None)
+    }
+
+    fn exists(&self, drive_id: &str, path: &str) -> Result<bool, DriveError> {
+        check_relative_path(path)?;
+        let (dir, name) = path.rsplit_once('/').unwrap_or(("", path));
+        if dir.is_empty() {
+            return Ok(false);
+        }
+        Ok(self.list_dir(drive_id, dir)?.iter().any(|n| n == name))
+    }
+
+    fn drive(&self, drive_id: &str) -> Result<DriveInfo, DriveError> {
+        self.list_drives()?
+            .into_iter()
+            .find(|d| d.id == drive_id)
+            .ok_or_else(|| DriveError::NotFound(drive_id.to_string()))
+    }
+}
+
+/// Reject absolute paths and `..`/`.`/empty components so a backend can never
+/// be steered outside the drive.
+pub fn check_relative_path(path: &str) -> Result<(), DriveError> {
+    let bad = path.is_empty()
+        || path.starts_with('/')
+        || path.contains('\\')
+        || path
+            .split('/')
+            .any(|c| c.is_empty() || c == "." || c == "..");
+    if bad {
+        return Err(DriveError::InvalidPath(path.to_string()));
+    }
+    Ok(())
+}
+
+/// Backend for platforms without drive support: detects nothing.
+pub struct UnsupportedBackend;
+
+impl DriveBackend for UnsupportedBackend {
+    fn list_drives(&self) -> Result<Vec<DriveInfo>, DriveError> {
+        Ok(Vec::new())
+    }
+    fn read_file(&self, _: &str, _: &str) -> Result<Vec<u8>, DriveError> {
+        Err(DriveError::Unsupported)
+    }
+    fn write_file(&self, _: &str, _: &str, _: &[u8]) -> Result<(), DriveError> {
+        Err(DriveError::Unsupported)
+    }
+    fn list_dir(&self, _: &str, _: &str) -> Result<Vec<String>, DriveError> {
+        Err(DriveError::Unsupported)
+    }
+    fn remove_file(&self, _: &str, _: &str) -> Result<(), DriveError> {
+        Err(DriveError::Unsupported)
+    }
+}
+
+/// The backend for the platform the vault is running on.
+pub fn default_backend() -> Box<dyn DriveBackend> {
+    #[cfg(target_os = "linux")]
+    {
+        Box::new(mounted::MountedBackend::new(linux::list_drives))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        Box::new(mounted::MountedBackend::new(macos::list_drives))
+    }
+    #[cfg(target_os = "windows")]
+    {
+        Box::new(mounted::MountedBackend::new(windows::list_drives))
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        Box::new(UnsupportedBackend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_paths_are_confined() {
+        assert!(check_relative_path("zap-vault-backups/abc/manifest.json").is_ok());
+        for bad in [
+            "",
+            "/etc/passwd",
+            "a/../b",
+            "./a",
+            "a//b",
+            "a/",
+            "a\\b",
+            "..",
+        ] {
+            assert!(check_relative_path(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use super::{check_relative_path, DriveBackend, DriveError};
+use crate::models::drive::DriveInfo;
+use chrono::{DateTime, Utc};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Detects drives with a platform tool (`lsblk`, `diskutil`, PowerShell; no
+/// root or administrator needed) and does file I/O under the mount points
+/// the desktop created.
+pub struct MountedBackend {
+    detect: fn() -> Result<Vec<DriveInfo>, DriveError>,
+}
+
+impl MountedBackend {
+    pub fn new(detect: fn() -> Result<Vec<DriveInfo>, DriveError>) -> Self {
+        MountedBackend { detect }
+    }
+
+    fn mounted_path(&self, drive_id: &str, path: &str, write: bool) -> Result<PathBuf, DriveError> {
+        check_relative_path(path)?;
+        let drive = self.drive(drive_id)?;
+        if write && !drive.is_writable() {
+            return Err(DriveError::NotReady(drive_id.to_string()));
+        }
+        let mount = drive
+            .mount_point
+            .ok_or_else(|| DriveError::NotReady(drive_id.to_string()))?;
+        Ok(PathBuf::from(mount).join(path))
+    }
+}
+
+impl DriveBackend for MountedBackend {
+    fn list_drives(&self) -> Result<Vec<DriveInfo>, DriveError> {
+        (self.detect)()
+    }
+
+    fn read_file(&self, drive_id: &str, path: &str) -> Result<Vec<u8>, DriveError> {
+        let full = self.mounted_path(drive_id, path, false)?;
+        std::fs::read(&full).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => DriveError::FileNotFound(path.to_string()),
+            _ => DriveError::Io(e.to_string()),
+        })
+    }
+
+    fn write_file(&self, drive_id: &str, path: &str, data: &[u8]) -> Result<(), DriveError> {
+        let full = self.mounted_path(drive_id, path, true)?;
+        let io = |e: std::io::Error| DriveError::Io(e.to_string());
+        let dir = full.parent().expect("checked relative path has a parent");
+        std::fs::create_dir_all(dir).map_err(io)?;
+        let tmp = full.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+        {
+            let mut f = std::fs::File::create(&tmp).map_err(io)?;
+            f.write_all(data).map_err(io)?;
+            f.sync_all().map_err(io)?;
+        }
+        std::fs::rename(&tmp, &full).map_err(io)?;
+        // Removable media is often yanked right after a write; make the rename
+        // itself durable too. Windows cannot open a directory as a file and
+        // commits the rename with the file.
+        #[cfg(unix)]
+        std::fs::File::open(dir)
+            .and_then(|d| d.sync_all())
+            .map_err(io)?;
+        Ok(())
+    }
+
+    fn list_dir(&self, drive_id: &str, dir: &str) -> Result<Vec<String>, DriveError> {
+        let full = self.mounted_path(drive_id, dir, false)?;
+        let entries = match std::fs::read_dir(&full) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(DriveError::Io(e.to_string())),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| DriveError::Io(e.to_string()))?;
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    fn remove_file(&self, drive_id: &str, path: &str) -> Result<(), DriveError> {
+        let full = self.mounted_path(drive_id, path, true)?;
+        std::fs::remove_file(&full).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => DriveError::FileNotFound(path.to_string()),
+            _ => DriveError::Io(e.to_string()),
+        })
+    }
+
+    fn modified(&self, drive_id: &str, path: &str) -> Result<Option<DateTime<Utc>>, DriveError> {
+        let full = self.mounted_path(drive_id, path, false)?;
+        let meta = std::fs::metadata(&full).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => DriveError::FileNotFound(path.to_string()),
+            _ => DriveError::Io(e.to_string()),
+        })?;
+        Ok(meta.modified().ok().map(DateTime::<Utc>::from))
+    }
+}
@@ -0,0 +1,315 @@
+use super::backup::{self, ProgressReporter, MANIFEST_FILE};
+use super::{check_relative_path, DriveBackend, DriveError};
+use crate::models::backup::{BackupMode, BackupProgress, ProgressStage};
+use crate::models::remote::RemoteCatalogEntry;
+use chrono::{DateTime, Utc};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+
+/// Upload part size. Small enough that an interrupted transfer over a slow
+/// mounted link loses little, large enough to keep the part count low.
+pub const PART_SIZE: usize = 4 * 1024 * 1024;
+pub const REMOTE_CATALOG: &str = "catalog.json";
+
+/// Off-site storage for already-encrypted backup objects, with multi-part
+/// uploads that survive interruption. Keys are relative `/`-separated paths.
+pub trait RemoteStore: Send + Sync {
+    fn exists(&self, key: &str) -> Result<bool, DriveError>;
+    fn get(&self, key: &str) -> Result<Vec<u8>, DriveError>;
+    /// Store a small object in one write.
+    fn put(&self, key: &str, data: &[u8]) -> Result<(), DriveError>;
+    /// Part numbers already received for an unfinished upload of `key`.
+    fn uploaded_parts(&self, key: &str) -> Result<Vec<u32>, DriveError>;
+    fn put_part(&self, key: &str, part: u32, data: &[u8]) -> Result<(), DriveError>;
+    /// Join parts `0..parts` into `key` and discard them.
+    fn complete(&self, key: &str, parts: u32) -> Result<(), DriveError>;
+}
+
+/// A remote mounted into the local filesystem.
+pub struct DirectoryStore {
+    pub root: PathBuf,
+}
+
+impl DirectoryStore {
+    fn path(&self, key: &str) -> Result<PathBuf, DriveError> {
+        check_relative_path(key)?;
+        Ok(self.root.join(key))
+    }
+
+    fn parts_dir(&self, key: &str) -> Result<PathBuf, DriveError> {
+        check_relative_path(key)?;
+        Ok(self.root.join(".parts").join(key.replace('/', "_")))
+    }
+}
+
+fn io(e: std::io::Error) -> DriveError {
+    DriveError::Io(e.to_string())
+}
+
+fn write_synced(path: &std::path::Path, data: &[u8]) -> Result<(), DriveError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(io)?;
+    }
+    let tmp = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    {
+        let mut f = std::fs::File::create(&tmp).map_err(io)?;
+        f.write_all(data).map_err(io)?;
+        f.sync_all().map_err(io)?;
+    }
+    std::fs::rename(&tmp, path).map_err(io)
+}
+
+impl RemoteStore for DirectoryStore {
+    fn exists(&self, key: &str) -> Result<bool, DriveError> {
+        Ok(self.path(key)?.is_file())
+    }
+
+    fn get(&self, key: &str) -> Result<Vec<u8>, DriveError> {
+        std::fs::read(self.path(key)?).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => DriveError::FileNotFound(key.to_string()),
+            _ => io(e),
+        })
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> Result<(), DriveError> {
+        write_synced(&self.path(key)?, data)
+    }
+
+    fn uploaded_parts(&self, key: &str) -> Result<Vec<u32>, DriveError> {
+        let dir = self.parts_dir(key)?;
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io(e)),
+        };
+        let mut parts = Vec::new();
+        for entry in entries {
+            let name = entry.map_err(io)?.file_name();
+            if let Ok(n) = name.to_string_lossy().parse() {
+                parts.push(n);
+            }
+        }
+        parts.sort_unstable();
+        Ok(parts)
+    }
+
+    fn put_part(&self, key: &str, part: u32, data: &[u8]) -> Result<(), DriveError> {
+        write_synced(&self.parts_dir(key)?.join(part.to_string()), data)
+    }
+
+    fn complete(&self, key: &str, parts: u32) -> Result<(), DriveError> {
+        let dir = self.parts_dir(key)?;
+        let mut data = Vec::new();
+        for n in 0..parts {
+            data.extend(std::fs::read(dir.join(n.to_string())).map_err(io)?);
+        }
+        self.put(key, &data)?;
+        std::fs::remove_dir_all(&dir).map_err(io)
+    }
+}
+
+fn object_key(blake3_hex: &str) -> String {
+    format!("objects/{blake3_hex}")
+}
+
+/// Send one object in `PART_SIZE` parts, skipping parts a previous attempt
+/// already delivered. Returns the bytes actually sent.
+fn upload_object(
+    store: &dyn RemoteStore,
+    key: &str,
+    data: &[u8],
+    progress: &mut ProgressReporter,
+) -> Result<u64, DriveError> {
+    let done = store.uploaded_parts(key)?;
+    let chunks: Vec<&[u8]> = if data.is_empty() {
+        vec![data]
+    } else {
+        data.chunks(PART_SIZE).collect()
+    };
+    let mut sent = 0;
+    for (n, chunk) in chunks.iter().enumerate() {
+        let n = n as u32;
+        if !done.contains(&n) {
+            store.put_part(key, n, chunk)?;
+            sent += chunk.len() as u64;
+        }
+        progress.advance(chunk.len() as u64);
+        progress.report(ProgressStage::Copying, Some(key));
+    }
+    store.complete(key, chunks.len() as u32)?;
+    Ok(sent)
+}
+
+/// Upload a drive backup to `store`: its objects (verified while being read
+/// from the drive, and skipped when the remote already has them), then its
+/// manifest, then an entry in the remote `catalog.json`. Re-running after an
+/// interruption resumes where the previous attempt stopped.
+pub fn upload_backup(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    backup_id: &str,
+    store: &dyn RemoteStore,
+    now: DateTime<Utc>,
+    progress: Option<&Sender<BackupProgress>>,
+) -> Result<RemoteCatalogEntry, DriveError> {
+    let manifest = backup::load_manifest(backend, drive_id, backup_id)?;
+    // A container backup's files are only as safe as the drive they sit on.
+    if manifest.mode != BackupMode::FileEncrypted {
+        return Err(DriveError::NotFileEncrypted(backup_id.to_string()));
+    }
+    let files = backup::read_backup(backend, drive_id, backup_id, None)?;
+    let mut progress = ProgressReporter::new(progress, manifest.total_bytes());
+    progress.report(ProgressStage::Preparing, None);
+
+    let mut uploaded_bytes = 0;
+    for (entry, file) in manifest.files.iter().zip(&files) {
+        let key = object_key(&entry.blake3_hex);
+        if store.exists(&key)? {
+            progress.advance(entry.size);
+            continue;
+        }
+        uploaded_bytes += upload_object(store, &key, &file.data, &mut progress)?;
+    }
+
+    progress.report(ProgressStage::Finalizing, Some(REMOTE_CATALOG));
+    let json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| DriveError::Malformed(e.to_string()))?;
+    store.put(&format!("backups/{backup_id}/{MANIFEST_FILE}"), &json)?;
+
+    let mut catalog: Vec<RemoteCatalogEntry> = match store.get(REMOTE_CATALOG) {
+        Ok(data) => {
+            serde_json::from_slice(&data).map_err(|e| DriveError::Malformed(e.to_string()))?
+        }
+        Err(DriveError::FileNotFound(_)) => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    let entry = RemoteCatalogEntry {
+        backup_id: manifest.id.clone(),
+        source_drive_id: drive_id.to_string(),
+        backup_created_at: manifest.created_at,
+        uploaded_at: now,
+        total_bytes: manifest.total_bytes(),
+        uploaded_bytes,
+    };
+    catalog.retain(|e| e.backup_id != entry.backup_id);
+    catalog.push(entry.clone());
+    let json =
+        serde_json::to_vec_pretty(&catalog).map_err(|e| DriveError::Malformed(e.to_string()))?;
+    store.put(REMOTE_CATALOG, &json)?;
+    progress.report(ProgressStage::Complete, None);
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drive::backup::{create_backup, create_file_encrypted_backup, VaultFile};
+    use crate::drive::mock::MockBackend;
+    use crate::models::backup::FileEncryption;
+
+    fn store() -> (DirectoryStore, PathBuf) {
+        let root = std::env::temp_dir().join(format!("zap-remote-{}", uuid::Uuid::new_v4()));
+        (DirectoryStore { root: root.clone() }, root)
+    }
+
+    fn files(keys: Vec<u8>) -> Vec<VaultFile> {
+        vec![
+            VaultFile {
+                name: "vault.json".to_string(),
+                data: b"{}".to_vec(),
+            },
+            VaultFile {
+                name: "keys.enc".to_string(),
+                data: keys,
+            },
+        ]
+    }
+
+    fn drive_with_backup(keys: Vec<u8>) -> (MockBackend, String) {
+        let b = MockBackend::new();
+        b.add_drive(MockBackend::ready_drive("usb", 64 << 20));
+        let encryption = FileEncryption {
+            salt_hex: hex::encode([5u8; 16]),
+            kdf: crate::models::vault::KdfProfile::current(),
+        };
+        let m = create_file_encrypted_backup(
+            &b,
+            "usb",
+            &files(keys),
+            Utc::now(),
+            &[4u8; 32],
+            encryption,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        (b, m.id)
+    }
+
+    #[test]
+    fn upload_writes_objects_manifest_and_catalog() {
+        let (b, id) = drive_with_backup(vec![3u8; PART_SIZE + 10]);
+        let (remote, root) = store();
+        let entry = upload_backup(&b, "usb", &id, &remote, Utc::now(), None).unwrap();
+        let manifest = backup::load_manifest(&b, "usb", &id).unwrap();
+        assert_eq!(entry.uploaded_bytes, manifest.total_bytes());
+
+        for f in &manifest.files {
+            let data = remote.get(&object_key(&f.blake3_hex)).unwrap();
+            assert_eq!(blake3::hash(&data).to_hex().to_string(), f.blake3_hex);
+        }
+        let catalog: Vec<RemoteCatalogEntry> =
+            serde_json::from_slice(&remote.get(REMOTE_CATALOG).unwrap()).unwrap();
+        assert_eq!(catalog, vec![entry]);
+
+        // Uploading again sends nothing and keeps a single catalog entry.
+        let again = upload_backup(&b, "usb", &id, &remote, Utc::now(), None).unwrap();
+        assert_eq!(again.uploaded_bytes, 0);
+        let catalog: Vec<RemoteCatalogEntry> =
+            serde_json::from_slice(&remote.get(REMOTE_CATALOG).unwrap()).unwrap();
+        assert_eq!(catalog.len(), 1);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn interrupted_upload_resumes_from_delivered_parts() {
+        let (b, id) = drive_with_backup(vec![9u8; 2 * PART_SIZE + 1]);
+        let (remote, root) = store();
+        let stored = backup::read_backup(&b, "usb", &id, None).unwrap();
+        let keys = &stored[1].data;
+        let hash = blake3::hash(keys).to_hex().to_string();
+        // A previous attempt delivered the first part before dropping out.
+        remote
+            .put_part(&object_key(&hash), 0, &keys[..PART_SIZE])
+            .unwrap();
+
+        let entry = upload_backup(&b, "usb", &id, &remote, Utc::now(), None).unwrap();
+        let total = backup::load_manifest(&b, "usb", &id).unwrap().total_bytes();
+        assert_eq!(entry.uploaded_bytes, total - PART_SIZE as u64);
+        assert_eq!(&remote.get(&object_key(&hash)).unwrap(), keys);
+        assert!(remote
+            .uploaded_parts(&object_key(&hash))
+            .unwrap()
+            .is_empty());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn container_backups_are_not_uploaded() {
+        let b = MockBackend::new();
+        b.add_drive(MockBackend::ready_drive("usb", 64 << 20));
+        let m = create_backup(&b, "usb", &files(vec![1u8; 32]), Utc::now(), None).unwrap();
+        let (remote, root) = store();
+        assert!(matches!(
+            upload_backup(&b, "usb", &m.id, &remote, Utc::now(), None),
+            Err(DriveError::NotFileEncrypted(_))
+        ));
+        assert!(matches!(
+            remote.get(REMOTE_CATALOG),
+            Err(DriveError::FileNotFound(_))
+        ));
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,222 @@
+use super::backup::{
+    hash_hex, load_manifest, object_path, stored_path, ProgressReporter, BACKUP_ROOT, OBJECT_ROOT,
+};
+use super::{DriveBackend, DriveError};
+use crate::models::backup::{BackupProgress, ProgressStage, ScrubIssue, ScrubReport, ScrubStatus};
+use std::collections::BTreeMap;
+use std::sync::mpsc::Sender;
+
+/// A stored file to check: its expected hash and the backups using it.
+struct Target {
+    blake3_hex: String,
+    size: u64,
+    backup_ids: Vec<String>,
+}
+
+/// Re-read every manifest and every stored file on `drive_id` and check the
+/// contents against their BLAKE3 hashes. Damaged or missing files are
+/// rewritten from an intact copy in the object store of any other attached
+/// drive when one exists (and this drive is writable); everything else is
+/// reported. Unreferenced objects are checked too, since a later backup may
+/// reuse them.
+pub fn scrub_drive(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    progress: Option<&Sender<BackupProgress>>,
+) -> Result<ScrubReport, DriveError> {
+    let drive = backend.drive(drive_id)?;
+    let mut report = ScrubReport {
+        drive_id: drive_id.to_string(),
+        backups_checked: 0,
+        files_checked: 0,
+        bytes_checked: 0,
+        unreadable_manifests: Vec::new(),
+        issues: Vec::new(),
+    };
+
+    let mut targets: BTreeMap<String, Target> = BTreeMap::new();
+    for id in backend.list_dir(drive_id, BACKUP_ROOT)? {
+        let manifest = match load_manifest(backend, drive_id, &id) {
+            Ok(m) => m,
+            // No manifest: an interrupted version 1 backup, not damage.
+            Err(DriveError::FileNotFound(_)) => continue,
+            Err(_) => {
+                report.unreadable_manifests.push(id);
+                continue;
+            }
+        };
+        report.backups_checked += 1;
+        for entry in &manifest.files {
+            let target = targets
+                .entry(stored_path(&manifest, entry)?)
+                .or_insert_with(|| Target {
+                    blake3_hex: entry.blake3_hex.clone(),
+                    size: entry.size,
+                    backup_ids: Vec::new(),
+                });
+            target.backup_ids.push(manifest.id.clone());
+        }
+    }
+    for fan in backend.list_dir(drive_id, OBJECT_ROOT)? {
+        for hash in backend.list_dir(drive_id, &format!("{OBJECT_ROOT}/{fan}"))? {
+            if let Ok(path) = object_path(&hash) {
+                targets.entry(path).or_insert(Target {
+                    blake3_hex: hash,
+                    size: 0,
+                    backup_ids: Vec::new(),
+                });
+            }
+        }
+    }
+
+    let others: Vec<String> = backend
+        .list_drives()?
+        .into_iter()
+        .filter(|d| d.id != drive_id)
+        .map(|d| d.id)
+        .collect();
+    let total = targets.values().map(|t| t.size).sum();
+    let mut progress = ProgressReporter::new(progress, total);
+    progress.report(ProgressStage::Preparing, None);
+
+    for (path, target) in targets {
+        progress.report(ProgressStage::Copying, Some(&path));
+        let status = match backend.read_file(drive_id, &path) {
+            Ok(data) => {
+                report.bytes_checked += data.len() as u64;
+                (hash_hex(&data) != target.blake3_hex).then_some(ScrubStatus::Corrupt)
+            }
+            Err(DriveError::FileNotFound(_)) => Some(ScrubStatus::Missing),
+            Err(e) => return Err(e),
+        };
+        report.files_checked += 1;
+        progress.advance(target.size);
+        let Some(mut status) = status else { continue };
+
+        let mut repaired_from = None;
+        if drive.is_writable() {
+            if let Some((source, data)) = find_mirror(backend, &others, &target.blake3_hex) {
+                backend.write_file(drive_id, &path, &data)?;
+                status = ScrubStatus::Repaired;
+                repaired_from = Some(source);
+            }
+        }
+        report.issues.push(ScrubIssue {
+            path,
+            blake3_hex: target.blake3_hex,
+            status,
+            backup_ids: target.backup_ids,
+            repaired_from,
+        });
+    }
+    progress.report(ProgressStage::Complete, None);
+    Ok(report)
+}
+
+/// An intact copy of the object from another drive's store. Drives that are
+/// unreadable or lack the object are skipped.
+fn find_mirror(
+    backend: &dyn DriveBackend,
+    drives: &[String],
+    blake3_hex: &str,
+) -> Option<(String, Vec<u8>)> {
+    let path = object_path(blake3_hex).ok()?;
+    drives.iter().find_map(|d| {
+        let data = backend.read_file(d, &path).ok()?;
+        (hash_hex(&data) == blake3_hex).then(|| (d.clone(), data))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drive::backup::{create_backup, VaultFile, MANIFEST_FILE};
+    use crate::drive::mock::MockBackend;
+    use crate::models::drive::DriveStatus;
+    use chrono::Utc;
+
+    fn files() -> Vec<VaultFile> {
+        vec![
+            VaultFile {
+                name: "vault.json".to_string(),
+                data: b"{}".to_vec(),
+            },
+            VaultFile {
+                name: "keys.enc".to_string(),
+                data: vec![4u8; 64],
+            },
+        ]
+    }
+
+    fn two_drives() -> MockBackend {
+        let b = MockBackend::new();
+        b.add_drive(MockBackend::ready_drive("primary", 1 << 20));
+        b.add_drive(MockBackend::ready_drive("mirror", 1 << 20));
+        b
+    }
+
+    #[test]
+    fn clean_drive_scrubs_healthy() {
+        let b = two_drives();
+        create_backup(&b, "primary", &files(), Utc::now(), None).unwrap();
+        create_backup(&b, "primary", &files(), Utc::now(), None).unwrap();
+        let report = scrub_drive(&b, "primary", None).unwrap();
+        assert!(report.is_healthy());
+        assert_eq!(report.backups_checked, 2);
+        assert_eq!(report.files_checked, 2);
+        assert_eq!(report.bytes_checked, 66);
+    }
+
+    #[test]
+    fn damage_is_repaired_from_a_mirror() {
+        let b = two_drives();
+        let m = create_backup(&b, "primary", &files(), Utc::now(), None).unwrap();
+        create_backup(&b, "mirror", &files(), Utc::now(), None).unwrap();
+        let keys = object_path(&m.files[1].blake3_hex).unwrap();
+        b.put_raw("primary", &keys, vec![5u8; 64]);
+
+        let report = scrub_drive(&b, "primary", None).unwrap();
+        assert!(report.is_healthy());
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].status, ScrubStatus::Repaired);
+        assert_eq!(report.issues[0].repaired_from.as_deref(), Some("mirror"));
+        assert_eq!(report.issues[0].backup_ids, vec![m.id.clone()]);
+        assert_eq!(b.read_file("primary", &keys).unwrap(), vec![4u8; 64]);
+    }
+
+    #[test]
+    fn unrepairable_damage_is_reported() {
+        let b = two_drives();
+        let m = create_backup(&b, "primary", &files(), Utc::now(), None).unwrap();
+        let meta = object_path(&m.files[0].blake3_hex).unwrap();
+        b.remove_file("primary", &meta).unwrap();
+        let keys = object_path(&m.files[1].blake3_hex).unwrap();
+        b.put_raw("primary", &keys, vec![0u8; 64]);
+        b.put_raw(
+            "primary",
+            &format!("{BACKUP_ROOT}/broken/{MANIFEST_FILE}"),
+            b"not json".to_vec(),
+        );
+
+        let report = scrub_drive(&b, "primary", None).unwrap();
+        assert!(!report.is_healthy());
+        assert_eq!(report.unreadable_manifests, vec!["broken".to_string()]);
+        let statuses: Vec<ScrubStatus> = report.issues.iter().map(|i| i.status).collect();
+        assert!(statuses.contains(&ScrubStatus::Missing));
+        assert!(statuses.contains(&ScrubStatus::Corrupt));
+    }
+
+    #[test]
+    fn read_only_drive_is_checked_but_not_repaired() {
+        let b = two_drives();
+        let m = create_backup(&b, "primary", &files(), Utc::now(), None).unwrap();
+        create_backup(&b, "mirror", &files(), Utc::now(), None).unwrap();
+        let keys = object_path(&m.files[1].blake3_hex).unwrap();
+        b.put_raw("primary", &keys, vec![5u8; 64]);
+        b.set_status("primary", DriveStatus::ReadOnly);
+
+        let report = scrub_drive(&b, "primary", None).unwrap();
+        assert_eq!(report.issues[0].status, ScrubStatus::Corrupt);
+        assert!(report.issues[0].repaired_from.is_none());
+    }
+}
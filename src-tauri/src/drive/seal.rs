@@ -0,0 +1,220 @@
+//! Seals on provisioned drives, for noticing changes made outside the app.
+//!
+//! Provisioning writes a random nonce to [`DRIVE_SEAL_FILE`] and records it
+//! in `vault.json` with the drive's LUKS UUID and the seal's modification
+//! time. Every write the app makes to the drive ends by rolling the nonce.
+//! A check compares the drive with the record: a different LUKS header, a
+//! missing or different nonce, a rewritten seal or backup files newer than
+//! the seal all mean something else wrote to the drive.
+
+use super::backup::{BACKUP_ROOT, MANIFEST_FILE, OBJECT_ROOT};
+use super::{DriveBackend, DriveError};
+use crate::models::drive::DriveInfo;
+use crate::models::drive_seal::{
+    DriveAnomaly, DriveSealFile, ProvisionedDrive, DRIVE_SEAL_FILE, DRIVE_SEAL_FORMAT,
+    DRIVE_SEAL_VERSION, MAX_REPORTED_FILES, SEAL_MTIME_TOLERANCE_SECS,
+};
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+
+/// Write a fresh nonce to the seal. Returns it with the seal's modification
+/// time as the drive reports it.
+fn write_seal(
+    backend: &dyn DriveBackend,
+    drive_id: &str,
+    now: DateTime<Utc>,
+) -> Result<(String, Option<DateTime<Utc>>), DriveError> {
+    let mut nonce = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let seal = DriveSealFile {
+        format: DRIVE_SEAL_FORMAT.to_string(),
+        version: DRIVE_SEAL_VERSION,
+        drive_id: drive_id.to_string(),
+        nonce_hex: hex::encode(nonce),
+        written_at: now,
+    };
+    let data = serde_json::to_vec_pretty(&seal).map_err(|e| DriveError::Io(e.to_string()))?;
+    backend.write_file(drive_id, DRIVE_SEAL_FILE, &data)?;
+    Ok((seal.nonce_hex, backend.modified(drive_id, DRIVE_SEAL_FILE)?))
+}
+
+/// Seal `drive` and return the record to keep in `vault.json`.
+pub fn provision(
+    backend: &dyn DriveBackend,
+    drive: &DriveInfo,
+    now: DateTime<Utc>,
+) -> Result<ProvisionedDrive, DriveError> {
+    let (nonce_hex, seal_modified_at) = write_seal(backend, &drive.id, now)?;
+    Ok(ProvisionedDrive {
+        drive_id: drive.id.clone(),
+        luks_uuid: drive.luks_uuid.clone(),
+        label: drive.label.clone(),
+        provisioned_at: now,
+        nonce_hex,
+        sealed_at: now,
+        seal_modified_at,
+        checked_at: None,
+        untrusted: None,
+    })
+}
+
+/// Roll the nonce after the app wrote to the drive.
+pub fn reseal(
+    backend: &dyn DriveBackend,
+    record: &mut ProvisionedDrive,
+    now: DateTime<Utc>,
+) -> Result<(), DriveError> {
+    let (nonce_hex, seal_modified_at) = write_seal(backend, &record.drive_id, now)?;
+    record.nonce_hex = nonce_hex;
+    record.sealed_at = now;
+    record.seal_modified_at = seal_modified_at;
+    Ok(())
+}
+
+/// Backup manifests and stored objects, the files only the app should write.
+fn sealed_files(backend: &dyn DriveBackend, drive_id: &str) -> Result<Vec<String>, DriveError> {
+    let mut paths = Vec::new();
+    for id in backend.list_dir(drive_id, BACKUP_ROOT)? {
+        paths.push(format!("{BACKUP_ROOT}/{id}/{MANIFEST_FILE}"));
+    }
+    for prefix in backend.list_dir(drive_id, OBJECT_ROOT)? {
+        let dir = format!("{OBJECT_ROOT}/{prefix}");
+        for name in backend.list_dir(drive_id, &dir)? {
+            paths.push(format!("{dir}/{name}"));
+        }
+    }
+    Ok(paths)
+}
+
+/// Compare `drive` with its record. Empty when nothing suggests it was
+/// written outside the app.
+pub fn check(
+    backend: &dyn DriveBackend,
+    drive: &DriveInfo,
+    record: &ProvisionedDrive,
+) -> Result<Vec<DriveAnomaly>, DriveError> {
+    let tolerance = Duration::seconds(SEAL_MTIME_TOLERANCE_SECS);
+    let mut anomalies = Vec::new();
+    if drive.luks_uuid != record.luks_uuid {
+        anomalies.push(DriveAnomaly::LuksHeaderChanged {
+            expected: record.luks_uuid.clone(),
+            found: drive.luks_uuid.clone(),
+        });
+    }
+
+    match backend.read_file(&drive.id, DRIVE_SEAL_FILE) {
+        Ok(data) => {
+            let matches = serde_json::from_slice::<DriveSealFile>(&data).is_ok_and(|seal| {
+                seal.format == DRIVE_SEAL_FORMAT
+                    && seal.drive_id == record.drive_id
+                    && seal.nonce_hex == record.nonce_hex
+            });
+            if !matches {
+                anomalies.push(DriveAnomaly::NonceMismatch);
+            }
+            let modified = backend.modified(&drive.id, DRIVE_SEAL_FILE)?;
+            if let (Some(expected), Some(modified_at)) = (record.seal_modified_at, modified) {
+                if (modified_at - expected).abs() > tolerance {
+                    anomalies.push(DriveAnomaly::SealTouched { modified_at });
+                }
+            }
+        }
+        Err(DriveError::FileNotFound(_)) => anomalies.push(DriveAnomaly::SealMissing),
+        Err(e) => return Err(e),
+    }
+
+    let sealed = record.seal_modified_at.unwrap_or(record.sealed_at) + tolerance;
+    let mut newer = 0;
+    for path in sealed_files(backend, &drive.id)? {
+        match backend.modified(&drive.id, &path) {
+            Ok(Some(modified_at)) if modified_at > sealed => {
+                newer += 1;
+                if newer <= MAX_REPORTED_FILES {
+                    anomalies.push(DriveAnomaly::ModifiedAfterSeal { path, modified_at });
+                }
+            }
+            Ok(_) | Err(DriveError::FileNotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(anomalies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drive::mock::MockBackend;
+    use crate::models::drive::DriveEncryption;
+
+    fn setup() -> (MockBackend, ProvisionedDrive) {
+        let b = MockBackend::new();
+        let mut drive = MockBackend::ready_drive("usb", 1 << 20);
+        drive.encrypted = true;
+        drive.encryption = Some(DriveEncryption::Luks);
+        drive.luks_uuid = Some("luks-usb".to_string());
+        b.add_drive(drive.clone());
+        b.put_raw(
+            "usb",
+            &format!("{BACKUP_ROOT}/b1/{MANIFEST_FILE}"),
+            b"{}".to_vec(),
+        );
+        let record = provision(&b, &drive, Utc::now()).unwrap();
+        (b, record)
+    }
+
+    #[test]
+    fn untouched_drive_checks_clean() {
+        let (b, mut record) = setup();
+        let drive = b.drive("usb").unwrap();
+        assert!(check(&b, &drive, &record).unwrap().is_empty());
+
+        // The app's own writes are followed by a reseal.
+        b.write_file("usb", &format!("{BACKUP_ROOT}/b2/{MANIFEST_FILE}"), b"{}")
+            .unwrap();
+        reseal(&b, &mut record, Utc::now()).unwrap();
+        assert!(check(&b, &drive, &record).unwrap().is_empty());
+    }
+
+    #[test]
+    fn outside_changes_are_flagged() {
+        let (b, record) = setup();
+        let later = Utc::now() + Duration::minutes(5);
+        let manifest = format!("{BACKUP_ROOT}/b1/{MANIFEST_FILE}");
+        b.set_modified("usb", &manifest, later);
+        b.set_modified("usb", DRIVE_SEAL_FILE, later);
+        b.set_luks_uuid("usb", Some("luks-other"));
+        let anomalies = check(&b, &b.drive("usb").unwrap(), &record).unwrap();
+        assert_eq!(
+            anomalies,
+            vec![
+                DriveAnomaly::LuksHeaderChanged {
+                    expected: Some("luks-usb".to_string()),
+                    found: Some("luks-other".to_string()),
+                },
+                DriveAnomaly::SealTouched { modified_at: later },
+                DriveAnomaly::ModifiedAfterSeal {
+                    path: manifest,
+                    modified_at: later,
+                },
+            ]
+        );
+
+        // A drive rolled back to an older copy carries an older nonce.
+        let (b, record) = setup();
+        let mut seal: DriveSealFile =
+            serde_json::from_slice(&b.read_file("usb", DRIVE_SEAL_FILE).unwrap()).unwrap();
+        seal.nonce_hex = hex::encode([0u8; 32]);
+        b.put_raw("usb", DRIVE_SEAL_FILE, serde_json::to_vec(&seal).unwrap());
+        let drive = b.drive("usb").unwrap();
+        assert_eq!(
+            check(&b, &drive, &record).unwrap(),
+            vec![DriveAnomaly::NonceMismatch]
+        );
+
+        b.remove_file("usb", DRIVE_SEAL_FILE).unwrap();
+        assert_eq!(
+            check(&b, &drive, &record).unwrap(),
+            vec![DriveAnomaly::SealMissing]
+        );
+    }
+}
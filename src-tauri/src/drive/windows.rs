@@ -0,0 +1,144 @@
+use super::DriveError;
+use crate::models::drive::{DriveEncryption, DriveInfo, DriveStatus};
+use serde_json::Value;
+use std::process::Command;
+
+/// Lists partitions on USB and SD disks as JSON. BitLocker status comes from
+/// the shell's `System.Volume.BitLockerProtection` property, which, unlike
+/// `manage-bde` and `Get-BitLockerVolume`, needs no administrator.
+const DETECT_SCRIPT: &str = r#"
+$shell = New-Object -ComObject Shell.Application
+@(Get-Disk | Where-Object { $_.BusType -in 'USB', 'SD', 'MMC' } | Get-Partition | ForEach-Object {
+    $volume = $_ | Get-Volume -ErrorAction SilentlyContinue
+    $letter = if ("$($_.DriveLetter)" -match '^[A-Za-z]$') { "$($_.DriveLetter)" } else { $null }
+    $protection = if ($letter) {
+        $shell.NameSpace(17).ParseName("${letter}:").ExtendedProperty('System.Volume.BitLockerProtection')
+    } else { $null }
+    [pscustomobject]@{
+        DriveLetter = $letter
+        Guid = $_.Guid
+        Label = $volume.FileSystemLabel
+        FileSystem = $volume.FileSystem
+        Size = $_.Size
+        SizeRemaining = $volume.SizeRemaining
+        ReadOnly = $_.IsReadOnly
+        BitLockerProtection = $protection
+    }
+}) | ConvertTo-Json -Compress
+"#;
+
+/// Detect partitions on removable disks with PowerShell.
+pub fn list_drives() -> Result<Vec<DriveInfo>, DriveError> {
+    let out = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", DETECT_SCRIPT])
+        .output()
+        .map_err(|e| DriveError::Io(format!("powershell: {e}")))?;
+    if !out.status.success() {
+        return Err(DriveError::Io(
+            String::from_utf8_lossy(&out.stderr).trim().to_string(),
+        ));
+    }
+    parse_volumes(&out.stdout)
+}
+
+fn text(v: &Value, key: &str) -> Option<String> {
+    v[key]
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Turn the detection script's JSON into drives. `System.Volume.BitLockerProtection`
+/// is 1 (on), 3 (encrypting), 4 (decrypting) or 5 (suspended) for an unlocked
+/// BitLocker volume and 6 for a locked one.
+pub fn parse_volumes(json: &[u8]) -> Result<Vec<DriveInfo>, DriveError> {
+    let json = String::from_utf8_lossy(json);
+    if json.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let root: Value =
+        serde_json::from_str(&json).map_err(|e| DriveError::Io(format!("volume list: {e}")))?;
+    // ConvertTo-Json writes a single object for a one-element list.
+    let volumes = match root {
+        Value::Array(volumes) => volumes,
+        other => vec![other],
+    };
+    let mut drives = Vec::new();
+    for v in &volumes {
+        let protection = v["BitLockerProtection"].as_u64();
+        let encryption =
+            matches!(protection, Some(1 | 3 | 4 | 5 | 6)).then_some(DriveEncryption::BitLocker);
+        let fs_type = text(v, "FileSystem");
+        let mount_point = text(v, "DriveLetter").map(|l| format!("{l}:\\"));
+        let status = if protection == Some(6) {
+            DriveStatus::Locked
+        } else if mount_point.is_none() || fs_type.is_none() {
+            DriveStatus::NotMounted
+        } else if v["ReadOnly"].as_bool() == Some(true) {
+            DriveStatus::ReadOnly
+        } else {
+            DriveStatus::Ready
+        };
+        if fs_type.is_none() && encryption.is_none() {
+            // Reserved and unformatted partitions.
+            continue;
+        }
+        let Some(guid) = text(v, "Guid") else {
+            continue;
+        };
+        drives.push(DriveInfo {
+            id: guid.trim_matches(['{', '}']).to_lowercase(),
+            device: mount_point.clone().unwrap_or_else(|| guid.clone()),
+            label: text(v, "Label"),
+            fs_type,
+            size_bytes: v["Size"].as_u64().unwrap_or(0),
+            available_bytes: v["SizeRemaining"].as_u64(),
+            mount_point: mount_point.filter(|_| status != DriveStatus::Locked),
+            encrypted: encryption.is_some(),
+            encryption,
+            luks_uuid: None,
+            status,
+        });
+    }
+    Ok(drives)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VOLUMES: &str = r#"[
+        {"DriveLetter":"E","Guid":"{AAAA-1}","Label":"BACKUP","FileSystem":"NTFS",
+         "Size":64000000000,"SizeRemaining":60000000000,"ReadOnly":false,"BitLockerProtection":1},
+        {"DriveLetter":"F","Guid":"{AAAA-2}","Label":null,"FileSystem":"",
+         "Size":32000000000,"SizeRemaining":null,"ReadOnly":false,"BitLockerProtection":6},
+        {"DriveLetter":"G","Guid":"{AAAA-3}","Label":"STICK","FileSystem":"exFAT",
+         "Size":8000000000,"SizeRemaining":7000000000,"ReadOnly":true,"BitLockerProtection":2},
+        {"DriveLetter":null,"Guid":"{AAAA-4}","Label":null,"FileSystem":null,
+         "Size":16777216,"SizeRemaining":null,"ReadOnly":false,"BitLockerProtection":null}
+    ]"#;
+
+    #[test]
+    fn parses_volumes_and_bitlocker_state() {
+        let drives = parse_volumes(VOLUMES.as_bytes()).unwrap();
+        let ids: Vec<&str> = drives.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["aaaa-1", "aaaa-2", "aaaa-3"]);
+
+        let backup = &drives[0];
+        assert_eq!(backup.status, DriveStatus::Ready);
+        assert_eq!(backup.encryption, Some(DriveEncryption::BitLocker));
+        assert_eq!(backup.mount_point.as_deref(), Some("E:\\"));
+
+        assert_eq!(drives[1].status, DriveStatus::Locked);
+        assert!(drives[1].encrypted);
+        assert_eq!(drives[1].mount_point, None);
+
+        assert_eq!(drives[2].status, DriveStatus::ReadOnly);
+        assert!(!drives[2].encrypted);
+
+        let single = parse_volumes(br#"{"DriveLetter":"E","Guid":"{B}","FileSystem":"FAT32","Size":1,"SizeRemaining":1,"ReadOnly":false}"#).unwrap();
+        assert_eq!(single.len(), 1);
+        assert!(parse_volumes(b"").unwrap().is_empty());
+    }
+}
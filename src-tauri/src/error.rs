@@ -10,28 +10,107 @@ pub enum VaultError {
     Kdf(#[from] crate::crypto::kdf::KdfError),
     #[error("encryption error: {0}")]
     Encryption(#[from] crate::crypto::encryption::EncryptionError),
+    #[error("data key error: {0}")]
+    DataKey(#[from] crate::crypto::data_key::DataKeyError),
+    #[error("derivation error: {0}")]
+    Derivation(#[from] crate::crypto::hd_derivation::DerivationError),
     #[error("mnemonic error: {0}")]
     Mnemonic(#[from] crate::crypto::mnemonic::MnemonicError),
+    #[error("SSH error: {0}")]
+    Ssh(#[from] crate::crypto::ssh::SshError),
+    #[error("browser pairing error: {0}")]
+    Pairing(#[from] crate::crypto::pairing::PairingError),
+    #[error("passkey error: {0}")]
+    Passkey(#[from] crate::crypto::passkey::PasskeyError),
+    #[error("instance identity error: {0}")]
+    Instance(#[from] crate::crypto::instance::InstanceError),
+    #[error("key attestation error: {0}")]
+    Attestation(#[from] crate::crypto::attestation::AttestationError),
+    #[error("time capsule error: {0}")]
+    Capsule(#[from] crate::crypto::capsule::CapsuleError),
+    #[error("contact card error: {0}")]
+    Contact(#[from] crate::crypto::contact::ContactError),
+    #[error("key escrow error: {0}")]
+    KeyEscrow(#[from] crate::crypto::key_escrow::KeyEscrowError),
+    #[error("genesis ceremony error: {0}")]
+    Ceremony(#[from] crate::crypto::ceremony::CeremonyError),
+    #[error("drive error: {0}")]
+    Drive(#[from] crate::drive::DriveError),
+    #[error("emergency access error: {0}")]
+    Emergency(#[from] crate::crypto::emergency::EmergencyError),
+    #[error("recovery code error: {0}")]
+    Recovery(#[from] crate::crypto::recovery::RecoveryError),
+    #[error("sync error: {0}")]
+    Sync(#[from] crate::crypto::sync::SyncError),
+    #[error("mobile bundle error: {0}")]
+    MobileBundle(#[from] crate::crypto::mobile::MobileBundleError),
+    #[error("public bundle error: {0}")]
+    PublicBundle(#[from] crate::crypto::public_bundle::PublicBundleError),
+    #[error("treasury error: {0}")]
+    Treasury(#[from] crate::crypto::treasury::TreasuryError),
+    #[error("FROST error: {0}")]
+    Frost(#[from] crate::crypto::frost::FrostError),
+    #[error("MuSig2 error: {0}")]
+    MuSig2(#[from] crate::crypto::musig2::MuSig2Error),
+    #[error("signing envelope error: {0}")]
+    SigningEnvelope(#[from] crate::crypto::signing_request::SigningEnvelopeError),
+    #[error("price error: {0}")]
+    Price(#[from] crate::crypto::price::PriceError),
+    #[error("spending policy error: {0}")]
+    Spending(#[from] crate::crypto::spending::SpendingError),
+    #[error("transaction review error: {0}")]
+    Decode(#[from] crate::decode::DecodeError),
+    #[error("key drive error: {0}")]
+    KeyDrive(#[from] crate::crypto::key_drive::KeyDriveError),
+    #[error("WireGuard error: {0}")]
+    WireGuard(#[from] crate::crypto::wireguard::WireGuardError),
+    #[error("SLIP-39 error: {0}")]
+    Slip39(#[from] crate::crypto::slip39::Slip39Error),
+    #[error("plugin error: {0}")]
+    Plugin(#[from] crate::plugin::PluginError),
     #[error("vault not initialized")]
     NotInitialized,
     #[error("vault already locked")]
     AlreadyLocked,
     #[error("vault already unlocked")]
     AlreadyUnlocked,
+    #[error("the vault was locked before the operation finished")]
+    LockedDuringOperation,
+    #[error("the vault is open in another instance: {0}")]
+    VaultInUse(String),
     #[error("invalid password")]
     InvalidPassword,
+    #[error("password is too weak: {0}")]
+    WeakPassword(String),
     #[error("too many failed unlock attempts; try again in {0} seconds")]
     TooManyAttempts(u64),
+    #[error(
+        "rate limit exceeded for {operation} operations; try again in {retry_after_secs} seconds"
+    )]
+    RateLimited {
+        operation: &'static str,
+        retry_after_secs: u64,
+    },
+    #[error("denied by vault security policy: {0}")]
+    PolicyDenied(String),
+    #[error("explicit consent required: {0}")]
+    ConsentRequired(String),
     #[error("key not found: {0}")]
     KeyNotFound(String),
     #[error("key already exists: {0}")]
     KeyAlreadyExists(String),
+    #[error("invalid metadata: {0}")]
+    InvalidMetadata(String),
     #[error("serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
     #[error("storage error: {0}")]
     Storage(String),
+    #[error("keyset error: {0}")]
+    Keyset(String),
     #[error("airgap error: {0}")]
     AirGap(String),
+    #[error("hook failed: {0}")]
+    Hook(String),
     #[error("YubiKey not detected; insert your YubiKey and try again")]
     YubiKeyNotFound,
     #[error("YubiKey error: {0}")]
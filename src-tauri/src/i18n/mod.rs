@@ -0,0 +1,178 @@
+//! Translations of the text the backend writes for people: notifications,
+//! drive and forecast warnings, and report text.
+//!
+//! Messages live in Fluent files under `locales/<locale>/vault.ftl`, bundled
+//! into the binary. [`tr`] looks a message up in the vault's locale and
+//! falls back to [`DEFAULT_LOCALE`], so a partial translation still shows
+//! every message. Adding a language is adding its file to [`LOCALES`].
+//! Error messages returned over IPC stay in English; the UI translates
+//! those by error kind.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::FluentValue;
+
+/// The locale every message is written in first.
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// Bundled translations: locale, its name in its own language, and the
+/// Fluent source.
+const LOCALES: &[(&str, &str, &str)] = &[
+    (
+        "en-US",
+        "English",
+        include_str!("../../locales/en-US/vault.ftl"),
+    ),
+    ("de", "Deutsch", include_str!("../../locales/de/vault.ftl")),
+];
+
+/// A locale the vault can write text in, as listed to the UI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocaleInfo {
+    pub id: String,
+    pub name: String,
+}
+
+pub fn supported_locales() -> Vec<LocaleInfo> {
+    LOCALES
+        .iter()
+        .map(|(id, name, _)| LocaleInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+        })
+        .collect()
+}
+
+/// The bundled locale to use for `requested`: an exact match, else one for
+/// the same language (`de-AT` gets `de`, `en` gets `en-US`), else
+/// [`DEFAULT_LOCALE`].
+pub fn resolve(requested: Option<&str>) -> &'static str {
+    let Some(requested) = requested.map(str::trim).filter(|r| !r.is_empty()) else {
+        return DEFAULT_LOCALE;
+    };
+    let language = |id: &str| {
+        id.split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+    LOCALES
+        .iter()
+        .find(|(id, _, _)| id.eq_ignore_ascii_case(requested))
+        .or_else(|| {
+            LOCALES
+                .iter()
+                .find(|(id, _, _)| language(id) == language(requested))
+        })
+        .map_or(DEFAULT_LOCALE, |(id, _, _)| *id)
+}
+
+fn bundle(locale: &str, source: &str) -> FluentBundle<FluentResource> {
+    let langid: LanguageIdentifier = locale.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Notifications and the PDF writer show text as is, where Unicode
+    // isolation marks around arguments would come out as stray characters.
+    bundle.set_use_isolating(false);
+    let resource =
+        FluentResource::try_new(source.to_string()).unwrap_or_else(|(partial, errors)| {
+            tracing::warn!(locale, ?errors, "translation has syntax errors");
+            partial
+        });
+    if let Err(errors) = bundle.add_resource(resource) {
+        tracing::warn!(locale, ?errors, "translation defines a message twice");
+    }
+    bundle
+}
+
+fn bundles() -> &'static [(&'static str, FluentBundle<FluentResource>)] {
+    static BUNDLES: OnceLock<Vec<(&'static str, FluentBundle<FluentResource>)>> = OnceLock::new();
+    BUNDLES.get_or_init(|| {
+        LOCALES
+            .iter()
+            .map(|(id, _, source)| (*id, bundle(id, source)))
+            .collect()
+    })
+}
+
+/// Message `id` in `locale`, with `args` filled in. Falls back to
+/// [`DEFAULT_LOCALE`], and to the id itself if no locale has the message.
+pub fn tr(locale: &str, id: &str, args: &[(&str, FluentValue<'_>)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    for locale in [resolve(Some(locale)), DEFAULT_LOCALE] {
+        let Some((_, bundle)) = bundles().iter().find(|(l, _)| *l == locale) else {
+            continue;
+        };
+        if let Some(pattern) = bundle.get_message(id).and_then(|m| m.value()) {
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+            if !errors.is_empty() {
+                tracing::warn!(locale, id, ?errors, "could not fill in a message");
+            }
+            return text.into_owned();
+        }
+    }
+    tracing::warn!(id, "message missing from every locale");
+    id.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ids of the messages a Fluent source defines.
+    fn message_ids(source: &str) -> Vec<&str> {
+        source
+            .lines()
+            .filter(|l| l.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|l| l.split_once(" =").map(|(id, _)| id.trim()))
+            .collect()
+    }
+
+    #[test]
+    fn translations_parse_and_match_the_default() {
+        let default_ids = message_ids(LOCALES[0].2);
+        assert!(default_ids.len() > 20);
+        for (locale, _, source) in LOCALES {
+            assert!(
+                FluentResource::try_new(source.to_string()).is_ok(),
+                "{locale}"
+            );
+            for id in message_ids(source) {
+                assert!(default_ids.contains(&id), "{locale} defines unknown {id}");
+            }
+        }
+    }
+
+    #[test]
+    fn resolves_and_falls_back() {
+        assert_eq!(resolve(None), "en-US");
+        assert_eq!(resolve(Some("de-AT")), "de");
+        assert_eq!(resolve(Some("EN")), "en-US");
+        assert_eq!(resolve(Some("tlh")), "en-US");
+
+        assert_eq!(
+            tr(
+                "en-US",
+                "failed-unlock-streak-body",
+                &[("count", 4u32.into())]
+            ),
+            "4 failed unlock attempts in a row"
+        );
+        assert_eq!(
+            tr("de", "failed-unlock-streak-body", &[("count", 4u32.into())]),
+            "4 fehlgeschlagene Entsperrversuche in Folge"
+        );
+        assert_eq!(tr("de", "no-such-message", &[]), "no-such-message");
+        assert_eq!(
+            tr("en-US", "forecast-filling-up", &[("days", 1i64.into())]),
+            "the drive is expected to be full in 1 day"
+        );
+    }
+}
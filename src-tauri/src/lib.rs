@@ -1,11 +1,29 @@
 pub mod commands;
 pub mod crypto;
+pub mod decode;
+pub mod drive;
 pub mod error;
+pub mod i18n;
 pub mod models;
+pub mod plugin;
+pub mod report;
 
+use commands::admin_api::AdminApiHandle;
+use commands::agent::AgentHandle;
 use commands::airgap::SeenNonces;
-use commands::keys::{KeyStore, MasterSeed, SessionKey};
-use commands::vault::{UnlockState, VaultMutex};
+use commands::backup::Drives;
+use commands::consent::ConsentTokens;
+use commands::items::ItemStore;
+use commands::key_drive::KeyDriveSession;
+use commands::keys::{KeyDetailCache, KeyStore, MasterSeed, SessionKey};
+use commands::lease::WriteLeaseHolder;
+use commands::limits::RateLimiter;
+use commands::notes::NoteCache;
+use commands::pairing::BrowserPairing;
+use commands::portable::PortableState;
+use commands::profiles::Profiles;
+use commands::undo::UndoLog;
+use commands::vault::{KdfCache, LockEpoch, UnlockState, VaultMutex};
 use std::sync::Mutex;
 use tauri::Manager;
 
@@ -15,6 +33,8 @@ pub fn run() {
 
     tauri::Builder::default()
         .setup(|app| {
+            commands::profiles::init_profiles(app.handle());
+            commands::portable::init_portable(app.handle());
             let salt_path = app
                 .path()
                 .app_local_data_dir()
@@ -22,20 +42,59 @@ pub fn run() {
                 .join("salt.txt");
             app.handle()
                 .plugin(tauri_plugin_stronghold::Builder::with_argon2(&salt_path).build())?;
+            commands::items::spawn_expiry_watch(app.handle().clone());
+            commands::trash::spawn_trash_purge(app.handle().clone());
+            commands::quick_access::spawn_usage_flush(app.handle().clone());
+            commands::undo::spawn_undo_expiry(app.handle().clone());
+            commands::lease::spawn_lease_heartbeat(app.handle().clone());
+            commands::notifications::spawn_drive_watch(app.handle().clone());
+            commands::portable::spawn_portable_watch(app.handle().clone());
+            commands::key_drive::spawn_key_drive_watch(app.handle().clone());
+            commands::selftest::spawn_startup_selftest(app.handle().clone());
+            commands::admin_api::start_admin_api(app.handle());
             Ok(())
         })
+        .plugin(tauri_plugin_notification::init())
         .manage(VaultMutex(Mutex::new(models::vault::VaultState::default())))
         .manage(KeyStore(Mutex::new(Vec::new())))
+        .manage(ItemStore(Mutex::new(Vec::new())))
         .manage(SessionKey(Mutex::new(None)))
         .manage(MasterSeed(Mutex::new(None)))
         .manage(SeenNonces::default())
         .manage(UnlockState::default())
+        .manage(LockEpoch::default())
+        .manage(KdfCache::default())
+        .manage(Drives::default())
+        .manage(RateLimiter::default())
+        .manage(AgentHandle::default())
+        .manage(AdminApiHandle::default())
+        .manage(BrowserPairing::default())
+        .manage(PortableState::default())
+        .manage(KeyDetailCache::default())
+        .manage(KeyDriveSession::default())
+        .manage(NoteCache::default())
+        .manage(ConsentTokens::default())
+        .manage(UndoLog::default())
+        .manage(WriteLeaseHolder::default())
+        .manage(Profiles::default())
         .invoke_handler(tauri::generate_handler![
             commands::vault::vault_status,
+            commands::selftest::run_security_selftest,
             commands::vault::create_vault,
             commands::vault::restore_from_mnemonic,
+            commands::vault::generate_slip39_shares,
+            commands::vault::restore_from_slip39,
+            commands::vault::check_recovery_phrase,
             commands::vault::unlock_vault,
             commands::vault::change_password,
+            commands::vault::change_master_password,
+            commands::recovery::generate_recovery_codes,
+            commands::recovery::recovery_codes_status,
+            commands::recovery::recover_account,
+            commands::password_policy::check_password_strength,
+            commands::password_policy::set_min_password_score,
+            commands::vault::migrate_encryption,
+            commands::vault::compact_storage,
             commands::vault::lock_vault,
             commands::vault::yubikey_status,
             commands::vault::enroll_yubikey,
@@ -47,6 +106,199 @@ pub fn run() {
             commands::keys::generate_key,
             commands::keys::list_keys,
             commands::keys::get_key_detail,
+            commands::keys::update_key_metadata,
+            commands::health::verify_all_keys,
+            commands::keysets::generate_keyset,
+            commands::keysets::list_keysets,
+            commands::keysets::rollback_keyset,
+            commands::keysets::save_keyset_template,
+            commands::keysets::list_keyset_templates,
+            commands::keysets::delete_keyset_template,
+            commands::keysets::create_keysets_from_template,
+            commands::keysets::get_vault_identity,
+            commands::instance::get_instance_identity,
+            commands::instance::verify_instance_signature,
+            commands::instance::verify_backup_instance_signature,
+            commands::keysets::export_zap_key_attestation,
+            commands::keysets::verify_zap_key_attestation,
+            commands::ceremony::ceremony_create,
+            commands::ceremony::ceremony_list,
+            commands::ceremony::ceremony_contribute,
+            commands::ceremony::ceremony_commit,
+            commands::ceremony::ceremony_generate,
+            commands::ceremony::ceremony_sign_manifest,
+            commands::ceremony::ceremony_attest,
+            commands::ceremony::ceremony_export_bundle,
+            commands::ceremony::ceremony_export_worksheet,
+            commands::treasury::treasury_create_policy,
+            commands::treasury::treasury_list_policies,
+            commands::treasury::treasury_create_proposal,
+            commands::treasury::treasury_list_proposals,
+            commands::treasury::sign_treasury_proposal,
+            commands::treasury::treasury_combine_signatures,
+            commands::treasury::treasury_verify_bundle,
+            commands::backup::list_drives,
+            commands::backup::get_drive_details,
+            commands::backup::estimate_backup_size,
+            commands::backup::get_drive_forecast,
+            commands::backup::create_backup,
+            commands::backup::create_file_encrypted_backup,
+            commands::backup::list_backups,
+            commands::notes::attach_note,
+            commands::notes::delete_note,
+            commands::custody::list_drive_locations,
+            commands::custody::drive_check_in,
+            commands::custody::drive_check_out,
+            commands::custody::verify_drive_location,
+            commands::custody::drive_location_report,
+            commands::custody::set_location_window,
+            commands::backup::verify_backup,
+            commands::backup::inspect_backup,
+            commands::backup::restore_backup,
+            commands::backup::restore_backup_files,
+            commands::backup::scrub_drive,
+            commands::portable::portable_vault_status,
+            commands::portable::move_vault_to_drive,
+            commands::portable::move_vault_from_drive,
+            commands::profiles::list_profiles,
+            commands::profiles::create_profile,
+            commands::profiles::switch_profile,
+            commands::remote::set_air_gap_mode,
+            commands::remote::remote_add_target,
+            commands::remote::remote_list_targets,
+            commands::remote::remote_remove_target,
+            commands::remote::remote_upload_backup,
+            commands::limits::get_rate_limits,
+            commands::limits::set_rate_limits,
+            commands::policy::get_security_policy,
+            commands::policy::set_security_policy,
+            commands::policy::reauthenticate,
+            commands::consent::request_consent,
+            commands::notifications::get_notification_settings,
+            commands::notifications::set_notification_settings,
+            commands::sync::sync_status,
+            commands::sync::sync_export_package,
+            commands::sync::sync_import_package,
+            commands::mobile::export_mobile_bundle,
+            commands::public_bundle::export_public_bundle,
+            commands::public_bundle::import_public_bundle,
+            commands::public_bundle::list_watch_only,
+            commands::public_bundle::remove_watch_only,
+            commands::contacts::export_contact_card,
+            commands::contacts::import_contact_card,
+            commands::contacts::list_contacts,
+            commands::contacts::verify_contact_fingerprint,
+            commands::contacts::delete_contact,
+            commands::key_escrow::create_escrow_package,
+            commands::key_escrow::inspect_escrow_package,
+            commands::key_escrow::open_escrow_package,
+            commands::frost::frost_dkg_begin,
+            commands::frost::frost_dkg_round2,
+            commands::frost::frost_dkg_finish,
+            commands::frost::frost_list_shares,
+            commands::frost::frost_commit,
+            commands::frost::frost_sign,
+            commands::frost::frost_aggregate,
+            commands::frost::frost_export_share,
+            commands::frost::frost_import_share,
+            commands::frost::frost_export_worksheet,
+            commands::musig2::musig2_public_key,
+            commands::musig2::musig2_output_key,
+            commands::musig2::musig2_create_session,
+            commands::musig2::musig2_list_sessions,
+            commands::musig2::musig2_sign,
+            commands::musig2::musig2_finalize,
+            commands::musig2::musig2_delete_session,
+            commands::signing_requests::signing_request_export,
+            commands::signing_requests::signing_request_import,
+            commands::signing_requests::signing_request_list,
+            commands::signing_requests::signing_request_respond,
+            commands::signing_requests::signing_response_import,
+            commands::tx_review::review_transaction,
+            commands::tx_review::abi_registry_list,
+            commands::tx_review::abi_registry_add,
+            commands::tx_review::abi_registry_remove,
+            commands::spending::list_spending_policies,
+            commands::spending::set_spending_policy,
+            commands::spending::remove_spending_policy,
+            commands::spending::list_approvers,
+            commands::spending::add_approver,
+            commands::spending::remove_approver,
+            commands::spending::list_spend_approvals,
+            commands::spending::approve_spend,
+            commands::price::list_price_signers,
+            commands::price::trust_price_signer,
+            commands::price::remove_price_signer,
+            commands::price::import_price_snapshot,
+            commands::price::get_price_snapshot,
+            commands::price::price_value,
+            commands::portfolio::list_balances,
+            commands::portfolio::record_balance,
+            commands::portfolio::remove_balance,
+            commands::portfolio::generate_portfolio_report,
+            commands::compliance::export_compliance_report,
+            commands::hooks::list_hooks,
+            commands::hooks::add_hook,
+            commands::hooks::set_hook_enabled,
+            commands::hooks::remove_hook,
+            commands::hooks::test_hook,
+            commands::locale::list_locales,
+            commands::locale::get_locale,
+            commands::locale::set_locale,
+            commands::admin_api::admin_api_enable,
+            commands::admin_api::admin_api_disable,
+            commands::admin_api::admin_api_status,
+            commands::plugins::list_plugins,
+            commands::plugins::trust_plugin_publisher,
+            commands::plugins::distrust_plugin_publisher,
+            commands::plugins::install_plugin,
+            commands::plugins::remove_plugin,
+            commands::plugins::plugin_item_create,
+            commands::plugins::plugin_item_reveal,
+            commands::plugins::run_plugin_exporter,
+            commands::plugins::plugin_derive_address,
+            commands::key_drive::key_drive_status,
+            commands::key_drive::enroll_key_drive,
+            commands::key_drive::disable_key_drive,
+            commands::key_drive::unlock_with_key_drive,
+            commands::drive_trust::get_drive_trust,
+            commands::drive_trust::set_drive_trust,
+            commands::drive_seal::list_provisioned_drives,
+            commands::drive_seal::provision_drive,
+            commands::drive_seal::unprovision_drive,
+            commands::drive_seal::check_provisioned_drive,
+            commands::drive_seal::reverify_drive,
+            commands::drive_image::export_backup_set_image,
+            commands::drive_image::write_backup_set_image,
+            commands::capsule::create_time_capsule,
+            commands::capsule::list_time_capsules,
+            commands::capsule::open_time_capsule,
+            commands::capsule::sign_time_attestation,
+            commands::passkey::passkey_generate,
+            commands::passkey::passkey_import,
+            commands::passkey::passkey_list,
+            commands::passkey::passkey_sign_assertion,
+            commands::passkey::passkey_export,
+            commands::api_tokens::api_token_create,
+            commands::api_tokens::api_token_list,
+            commands::api_tokens::api_token_reveal,
+            commands::api_tokens::render_env_file,
+            commands::agent::agent_start,
+            commands::agent::agent_stop,
+            commands::agent::agent_status,
+            commands::pairing::pairing_enable,
+            commands::pairing::pairing_disable,
+            commands::pairing::pairing_list,
+            commands::pairing::pairing_confirm,
+            commands::pairing::pairing_revoke,
+            commands::pairing::pairing_approve_site,
+            commands::pairing::pairing_remove_site,
+            commands::dashboard::get_dashboard_summary,
+            commands::derivation::preview_derivation,
+            commands::addresses::mark_addresses_used,
+            commands::addresses::get_address_stats,
+            commands::addresses::set_address_gap_limit,
+            commands::addresses::export_addresses,
             commands::signing::sign_message,
             commands::signing::sign_message_with_key,
             commands::signing::sign_message_hybrid_with_key,
@@ -56,6 +308,41 @@ pub fn run() {
             commands::airgap::generate_qr_with_key,
             commands::airgap::parse_qr,
             commands::airgap::verify_qr,
+            commands::ssh::ssh_generate_key,
+            commands::ssh::ssh_list_keys,
+            commands::ssh::ssh_export_public_key,
+            commands::ssh::ssh_export_private_key,
+            commands::ssh::ssh_sign_challenge,
+            commands::wireguard::wireguard_create_config,
+            commands::wireguard::wireguard_import_config,
+            commands::wireguard::wireguard_list_configs,
+            commands::wireguard::wireguard_export_config,
+            commands::emergency::emergency_generate_contact,
+            commands::emergency::emergency_create_grant,
+            commands::emergency::emergency_list_grants,
+            commands::emergency::emergency_sign_request,
+            commands::emergency::emergency_request_access,
+            commands::emergency::emergency_veto_request,
+            commands::emergency::emergency_revoke_grant,
+            commands::emergency::emergency_release_package,
+            commands::emergency::emergency_open_package,
+            commands::quick_access::get_quick_access_items,
+            commands::quick_access::set_key_favorite,
+            commands::quick_access::set_item_favorite,
+            commands::items::update_item_metadata,
+            commands::items::set_item_expiry,
+            commands::items::set_item_export_policy,
+            commands::items::get_expired_items,
+            commands::trash::trash_entry,
+            commands::trash::restore_entry,
+            commands::trash::list_trash,
+            commands::trash::purge_trash,
+            commands::trash::set_trash_retention,
+            commands::undo::list_undoable_operations,
+            commands::undo::undo_last_operation,
+            commands::undo::set_undo_window,
+            commands::integrity::repair_orphans,
+            commands::lease::write_lease_status,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
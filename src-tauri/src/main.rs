@@ -1,5 +1,13 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if zap_quantum_vault_lib::commands::pairing::is_native_host_invocation(&args) {
+        if let Err(e) = zap_quantum_vault_lib::commands::pairing::run_native_host() {
+            eprintln!("native messaging host failed: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
     zap_quantum_vault_lib::run()
 }
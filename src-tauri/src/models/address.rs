@@ -0,0 +1,405 @@
+use crate::models::backup::BackupRecord;
+use crate::models::key::{BackupStatus, KeyDetails, KeyEntry, KeyType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// BIP44's recommended address gap limit: recovery scans stop after this many
+/// consecutive unused indices.
+pub const DEFAULT_ADDRESS_GAP_LIMIT: u32 = 20;
+/// Upper bound on a user-configured gap limit.
+pub const MAX_ADDRESS_GAP_LIMIT: u32 = 1000;
+/// Network name written into address exports. Every key in this vault is a
+/// ZAP chain key.
+pub const ADDRESS_NETWORK: &str = "zap";
+
+/// Address usage for one HD account (`purpose` / `account`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressStats {
+    pub purpose: u32,
+    pub account: u32,
+    pub total: usize,
+    pub used: usize,
+    pub unused: usize,
+    pub highest_index: Option<u32>,
+    pub highest_used_index: Option<u32>,
+    /// Unused addresses after the last used one.
+    pub trailing_unused: usize,
+    /// The highest index that can still be derived without leaving the
+    /// recovery scan window.
+    pub max_derivable_index: u32,
+    pub gap_limit: u32,
+}
+
+/// Keys in one account of the vault's own HD tree (ceremony and escrowed keys
+/// excluded).
+pub fn account_keys(
+    keys: &[KeyEntry],
+    purpose: u32,
+    account: u32,
+) -> impl Iterator<Item = &KeyEntry> {
+    keys.iter().filter(move |k| {
+        k.metadata.ceremony_id.is_none()
+            && k.metadata.escrow_id.is_none()
+            && k.metadata.purpose == purpose
+            && k.metadata.account == account
+    })
+}
+
+/// Highest index a new key in this account may use: at most `gap_limit`
+/// indices past the last used one, so a restore that scans until it sees
+/// `gap_limit` unused addresses in a row still finds every key.
+pub fn max_derivable_index(keys: &[KeyEntry], purpose: u32, account: u32, gap_limit: u32) -> u32 {
+    let first_unscanned = account_keys(keys, purpose, account)
+        .filter(|k| k.metadata.used)
+        .map(|k| k.metadata.index.saturating_add(1))
+        .max()
+        .unwrap_or(0);
+    first_unscanned.saturating_add(gap_limit.saturating_sub(1))
+}
+
+/// Aggregate address usage for one account.
+pub fn address_stats(
+    keys: &[KeyEntry],
+    purpose: u32,
+    account: u32,
+    gap_limit: u32,
+) -> AddressStats {
+    let entries: Vec<&KeyEntry> = account_keys(keys, purpose, account).collect();
+    let used = entries.iter().filter(|k| k.metadata.used).count();
+    let highest_used_index = entries
+        .iter()
+        .filter(|k| k.metadata.used)
+        .map(|k| k.metadata.index)
+        .max();
+    let trailing_unused = entries
+        .iter()
+        .filter(|k| !k.metadata.used && highest_used_index.is_none_or(|u| k.metadata.index > u))
+        .count();
+    AddressStats {
+        purpose,
+        account,
+        total: entries.len(),
+        used,
+        unused: entries.len() - used,
+        highest_index: entries.iter().map(|k| k.metadata.index).max(),
+        highest_used_index,
+        trailing_unused,
+        max_derivable_index: max_derivable_index(keys, purpose, account, gap_limit),
+        gap_limit,
+    }
+}
+
+/// The latest backup in `history` written since `key` was created.
+pub fn last_backup_of(history: &[BackupRecord], key: &KeyEntry) -> Option<BackupRecord> {
+    history
+        .iter()
+        .filter(|b| b.created_at >= key.metadata.created_at)
+        .max_by_key(|b| b.created_at)
+        .cloned()
+}
+
+/// Whether the vault's mnemonic restores `key`, whether a wallet scan over
+/// `keys` with `gap_limit` would find it, and the last backup in `history`
+/// that holds it.
+pub fn backup_status(
+    keys: &[KeyEntry],
+    key: &KeyEntry,
+    gap_limit: u32,
+    history: &[BackupRecord],
+) -> BackupStatus {
+    let m = &key.metadata;
+    // Ceremony and escrowed keys come from another mnemonic, not the vault's.
+    let hd = !m.derivation_path.is_empty() && m.ceremony_id.is_none() && m.escrow_id.is_none();
+    BackupStatus {
+        mnemonic_recoverable: hd,
+        within_scan_window: hd
+            && (m.used || m.index <= max_derivable_index(keys, m.purpose, m.account, gap_limit)),
+        last_backup: last_backup_of(history, key),
+    }
+}
+
+/// Build the full detail view for `key`, with stats over its account in `keys`
+/// and its last backup from `history`.
+pub fn key_details(
+    keys: &[KeyEntry],
+    key: &KeyEntry,
+    gap_limit: u32,
+    history: &[BackupRecord],
+) -> KeyDetails {
+    let m = &key.metadata;
+    KeyDetails {
+        key: key.to_public(),
+        address_stats: address_stats(keys, m.purpose, m.account, gap_limit),
+        backup: backup_status(keys, key, gap_limit, history),
+    }
+}
+
+/// Layout of a file written by `export_addresses`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressExportFormat {
+    /// RFC 4180 CSV with a header row.
+    Csv,
+    /// A JSON array of [`AddressRecord`]s.
+    Json,
+}
+
+/// Which keys an address export includes. Unset fields match everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AddressFilter {
+    /// Frontend key type name, as accepted by [`KeyType::parse`].
+    pub key_type: Option<String>,
+    pub account: Option<u32>,
+    pub used: Option<bool>,
+    pub tag: Option<String>,
+    pub keyset_id: Option<String>,
+    pub include_trashed: bool,
+}
+
+impl AddressFilter {
+    pub fn matches(&self, key: &KeyEntry) -> bool {
+        let m = &key.metadata;
+        (self.include_trashed || m.trashed_at.is_none())
+            && self
+                .key_type
+                .as_deref()
+                .is_none_or(|t| KeyType::parse(t) == m.key_type)
+            && self.account.is_none_or(|a| a == m.account)
+            && self.used.is_none_or(|u| u == m.used)
+            && self.tag.as_ref().is_none_or(|t| m.tags.contains(t))
+            && self
+                .keyset_id
+                .as_ref()
+                .is_none_or(|s| m.keyset_id.as_ref() == Some(s))
+    }
+}
+
+/// One row of an address export. Public data only: no key material beyond
+/// what the address itself reveals.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressRecord {
+    pub key_id: String,
+    pub label: Option<String>,
+    pub address: String,
+    pub network: String,
+    pub key_type: String,
+    pub derivation_path: String,
+    pub account: u32,
+    pub index: u32,
+    pub used: bool,
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AddressRecord {
+    pub fn of(key: &KeyEntry) -> Self {
+        let m = &key.metadata;
+        AddressRecord {
+            key_id: key.id.clone(),
+            label: m.label.clone(),
+            address: m.address.clone(),
+            network: ADDRESS_NETWORK.to_string(),
+            key_type: m.key_type.as_str().to_string(),
+            derivation_path: m.derivation_path.clone(),
+            account: m.account,
+            index: m.index,
+            used: m.used,
+            tags: m.tags.clone(),
+            created_at: m.created_at,
+        }
+    }
+}
+
+/// The keys matching `filter`, ordered by account then index.
+pub fn address_records(keys: &[KeyEntry], filter: &AddressFilter) -> Vec<AddressRecord> {
+    let mut records: Vec<AddressRecord> = keys
+        .iter()
+        .filter(|k| filter.matches(k))
+        .map(AddressRecord::of)
+        .collect();
+    records.sort_by_key(|r| (r.account, r.index, r.created_at));
+    records
+}
+
+/// Quote a CSV field when needed. Text a spreadsheet would evaluate as a
+/// formula (a leading `=`, `+`, `-` or `@`) is prefixed with `'`, since
+/// labels are user input and these files are opened in spreadsheets.
+pub(crate) fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+pub fn render_addresses(
+    records: &[AddressRecord],
+    format: AddressExportFormat,
+) -> Result<String, String> {
+    match format {
+        AddressExportFormat::Json => {
+            serde_json::to_string_pretty(records).map_err(|e| e.to_string())
+        }
+        AddressExportFormat::Csv => {
+            let mut out = String::from(
+                "key_id,label,address,network,key_type,derivation_path,account,index,used,tags,created_at\r\n",
+            );
+            for r in records {
+                let row = [
+                    csv_field(&r.key_id),
+                    csv_field(r.label.as_deref().unwrap_or_default()),
+                    csv_field(&r.address),
+                    csv_field(&r.network),
+                    csv_field(&r.key_type),
+                    csv_field(&r.derivation_path),
+                    r.account.to_string(),
+                    r.index.to_string(),
+                    r.used.to_string(),
+                    csv_field(&r.tags.join(";")),
+                    r.created_at.to_rfc3339(),
+                ];
+                out.push_str(&row.join(","));
+                out.push_str("\r\n");
+            }
+            Ok(out)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::key::KeyType;
+
+    fn key(account: u32, index: u32, used: bool) -> KeyEntry {
+        let mut k = KeyEntry::new(KeyType::User, 0, account, index, "pk", "sk", "zap1", "");
+        k.metadata.used = used;
+        k
+    }
+
+    #[test]
+    fn window_starts_at_zero_for_fresh_account() {
+        assert_eq!(max_derivable_index(&[], 0, 0, 20), 19);
+        assert_eq!(max_derivable_index(&[], 0, 0, 1), 0);
+    }
+
+    #[test]
+    fn window_moves_past_last_used_index() {
+        let keys = vec![
+            key(0, 0, true),
+            key(0, 5, true),
+            key(0, 6, false),
+            key(1, 50, true),
+        ];
+        assert_eq!(max_derivable_index(&keys, 0, 0, 20), 25);
+        assert_eq!(max_derivable_index(&keys, 0, 1, 20), 70);
+    }
+
+    #[test]
+    fn key_details_backup_status() {
+        let mut legacy = key(0, 0, false);
+        legacy.metadata.derivation_path.clear();
+        let mut hd = key(0, 3, false);
+        hd.metadata.derivation_path = "m/44'/9999'/0'/0'/3'".to_string();
+        let mut far = key(0, 40, false);
+        far.metadata.derivation_path = "m/44'/9999'/0'/0'/40'".to_string();
+        let keys = vec![legacy.clone(), hd.clone(), far.clone()];
+
+        let d = key_details(&keys, &legacy, 20, &[]);
+        assert!(!d.backup.mnemonic_recoverable && !d.backup.within_scan_window);
+        assert!(d.backup.last_backup.is_none());
+        assert!(key_details(&keys, &hd, 20, &[]).backup.within_scan_window);
+        assert!(!key_details(&keys, &far, 20, &[]).backup.within_scan_window);
+
+        // Only backups written since the key was created hold it.
+        let created = hd.metadata.created_at;
+        let record = |id: &str, at| BackupRecord {
+            drive_id: "usb".to_string(),
+            backup_id: id.to_string(),
+            created_at: at,
+            verified_at: None,
+        };
+        let history = vec![
+            record("before", created - chrono::Duration::days(1)),
+            record("after", created + chrono::Duration::days(1)),
+            record("later", created + chrono::Duration::days(2)),
+        ];
+        let last = key_details(&keys, &hd, 20, &history).backup.last_backup;
+        assert_eq!(last.unwrap().backup_id, "later");
+        assert!(key_details(&keys, &hd, 20, &history[..1])
+            .backup
+            .last_backup
+            .is_none());
+
+        // The public key fields stay at the top level of the JSON.
+        let json = serde_json::to_value(&d).unwrap();
+        assert_eq!(json["id"], legacy.id.as_str());
+        assert_eq!(json["address_stats"]["total"], 3);
+    }
+
+    #[test]
+    fn stats_count_per_account() {
+        let keys = vec![
+            key(0, 0, true),
+            key(0, 1, false),
+            key(0, 2, true),
+            key(0, 3, false),
+            key(0, 4, false),
+            key(1, 0, true),
+        ];
+        let s = address_stats(&keys, 0, 0, 20);
+        assert_eq!((s.total, s.used, s.unused), (5, 2, 3));
+        assert_eq!(s.highest_index, Some(4));
+        assert_eq!(s.highest_used_index, Some(2));
+        assert_eq!(s.trailing_unused, 2);
+        assert_eq!(s.max_derivable_index, 22);
+    }
+
+    #[test]
+    fn address_export_filters_and_escapes() {
+        let mut labelled = key(0, 1, true);
+        labelled.metadata.label = Some("=HYPERLINK(\"x\"), \"cold\"".to_string());
+        labelled.metadata.tags = vec!["tax".to_string(), "2026".to_string()];
+        let mut trashed = key(0, 2, false);
+        trashed.metadata.trashed_at = Some(Utc::now());
+        let keys = vec![
+            key(1, 0, false),
+            labelled.clone(),
+            key(0, 0, false),
+            trashed,
+        ];
+
+        let all = address_records(&keys, &AddressFilter::default());
+        assert_eq!(
+            all.iter().map(|r| (r.account, r.index)).collect::<Vec<_>>(),
+            [(0, 0), (0, 1), (1, 0)]
+        );
+        let tagged = AddressFilter {
+            tag: Some("tax".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(address_records(&keys, &tagged)[0].key_id, labelled.id);
+        let used = AddressFilter {
+            used: Some(false),
+            include_trashed: true,
+            ..Default::default()
+        };
+        assert_eq!(address_records(&keys, &used).len(), 3);
+
+        let csv = render_addresses(&all[1..2], AddressExportFormat::Csv).unwrap();
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.contains(r#""'=HYPERLINK(""x""), ""cold""""#));
+        assert!(row.contains(",zap,user,,0,1,true,tax;2026,"));
+        assert!(!csv.contains("sk"));
+
+        let json = render_addresses(&all, AddressExportFormat::Json).unwrap();
+        let parsed: Vec<AddressRecord> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, all);
+    }
+}
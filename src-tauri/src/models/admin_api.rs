@@ -0,0 +1,174 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Socket file name inside the local data directory.
+pub const ADMIN_SOCKET_FILE: &str = "admin.sock";
+/// The admin API's settings, next to `profiles.json` since they cover every
+/// profile on the workstation.
+pub const ADMIN_API_FILE: &str = "admin_api.json";
+/// Longest request line the admin API reads before dropping the connection.
+pub const MAX_ADMIN_REQUEST_BYTES: usize = 64 * 1024;
+
+/// Persisted while the admin API is enabled. Only a hash of the token is
+/// kept; the token itself is shown once when the API is enabled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminApiConfig {
+    pub token_blake3_hex: String,
+    /// Refuse operations that change anything.
+    #[serde(default)]
+    pub read_only: bool,
+    pub enabled_at: DateTime<Utc>,
+}
+
+/// An operation on the admin socket. Each mirrors the command of the same
+/// name and answers with what that command returns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum AdminOp {
+    VaultStatus,
+    RunSecuritySelftest,
+    ListProfiles,
+    CreateProfile { name: String },
+    ListApprovers,
+    ListDrives,
+    CreateBackup { drive_id: String },
+    LockVault,
+}
+
+impl AdminOp {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AdminOp::VaultStatus => "vault_status",
+            AdminOp::RunSecuritySelftest => "run_security_selftest",
+            AdminOp::ListProfiles => "list_profiles",
+            AdminOp::CreateProfile { .. } => "create_profile",
+            AdminOp::ListApprovers => "list_approvers",
+            AdminOp::ListDrives => "list_drives",
+            AdminOp::CreateBackup { .. } => "create_backup",
+            AdminOp::LockVault => "lock_vault",
+        }
+    }
+
+    /// Whether the operation changes anything, and so is refused by a
+    /// read-only admin API.
+    pub fn changes_state(&self) -> bool {
+        matches!(
+            self,
+            AdminOp::CreateProfile { .. } | AdminOp::CreateBackup { .. } | AdminOp::LockVault
+        )
+    }
+}
+
+/// One newline-terminated JSON request on the admin socket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminRequest {
+    pub token: String,
+    #[serde(flatten)]
+    pub op: AdminOp,
+}
+
+/// The admin API's newline-terminated JSON reply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdminResponse {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AdminResponse {
+    pub fn value(value: serde_json::Value) -> Self {
+        AdminResponse {
+            ok: true,
+            value: Some(value),
+            error: None,
+        }
+    }
+
+    pub fn error(error: impl Into<String>) -> Self {
+        AdminResponse {
+            ok: false,
+            value: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Returned once by `admin_api_enable`, for the configuration management
+/// tool to store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminApiEnabled {
+    pub socket_path: String,
+    pub token: String,
+    pub read_only: bool,
+}
+
+/// Whether the admin API is enabled and listening, without its token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminApiStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub socket_path: Option<String>,
+    pub read_only: bool,
+    pub enabled_at: Option<DateTime<Utc>>,
+}
+
+/// Check a request's token against the stored hash, and a change against
+/// a read-only API.
+pub fn authorize(request: &AdminRequest, config: &AdminApiConfig) -> Result<(), &'static str> {
+    if blake3::hash(request.token.as_bytes()).to_hex().as_str() != config.token_blake3_hex {
+        return Err("invalid admin token");
+    }
+    if config.read_only && request.op.changes_state() {
+        return Err("the admin API is read-only");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_need_the_token_and_respect_read_only() {
+        let mut config = AdminApiConfig {
+            token_blake3_hex: blake3::hash(b"t").to_hex().to_string(),
+            read_only: true,
+            enabled_at: Utc::now(),
+        };
+        let request = |token: &str, op: AdminOp| AdminRequest {
+            token: token.to_string(),
+            op,
+        };
+        assert!(authorize(&request("t", AdminOp::ListDrives), &config).is_ok());
+        assert_eq!(
+            authorize(&request("x", AdminOp::ListDrives), &config),
+            Err("invalid admin token")
+        );
+        assert_eq!(
+            authorize(&request("t", AdminOp::LockVault), &config),
+            Err("the admin API is read-only")
+        );
+        config.read_only = false;
+        assert!(authorize(&request("t", AdminOp::LockVault), &config).is_ok());
+    }
+
+    #[test]
+    fn wire_format_is_tagged_by_op() {
+        let request: AdminRequest =
+            serde_json::from_str(r#"{"op":"create_backup","token":"t","drive_id":"d1"}"#).unwrap();
+        assert_eq!(
+            request.op,
+            AdminOp::CreateBackup {
+                drive_id: "d1".to_string()
+            }
+        );
+        assert_eq!(request.op.name(), "create_backup");
+        let request: AdminRequest =
+            serde_json::from_str(r#"{"op":"vault_status","token":"t"}"#).unwrap();
+        assert_eq!(request.op, AdminOp::VaultStatus);
+        let reply = serde_json::to_string(&AdminResponse::value(true.into())).unwrap();
+        assert_eq!(reply, r#"{"ok":true,"value":true}"#);
+    }
+}
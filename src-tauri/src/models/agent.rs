@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+
+/// Owner-only directory inside the data directory that holds the socket.
+pub const AGENT_SOCKET_DIR: &str = "agent";
+/// Socket file name inside [`AGENT_SOCKET_DIR`].
+pub const AGENT_SOCKET_FILE: &str = "agent.sock";
+/// Longest request line the agent reads before dropping the connection.
+pub const MAX_AGENT_REQUEST_BYTES: usize = 64 * 1024;
+
+/// What a tool may do with one item through the agent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentGrant {
+    pub item_id: String,
+    /// Return the secret itself (API tokens).
+    #[serde(default)]
+    pub reveal: bool,
+    /// Sign with it without revealing it (SSH keys).
+    #[serde(default)]
+    pub sign: bool,
+}
+
+/// One newline-terminated JSON request on the agent socket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum AgentRequest {
+    GetSecret {
+        token: String,
+        item_id: String,
+    },
+    Sign {
+        token: String,
+        item_id: String,
+        data_hex: String,
+    },
+}
+
+impl AgentRequest {
+    pub fn token(&self) -> &str {
+        match self {
+            AgentRequest::GetSecret { token, .. } | AgentRequest::Sign { token, .. } => token,
+        }
+    }
+
+    pub fn item_id(&self) -> &str {
+        match self {
+            AgentRequest::GetSecret { item_id, .. } | AgentRequest::Sign { item_id, .. } => item_id,
+        }
+    }
+
+    pub fn op(&self) -> &'static str {
+        match self {
+            AgentRequest::GetSecret { .. } => "get_secret",
+            AgentRequest::Sign { .. } => "sign",
+        }
+    }
+}
+
+/// The agent's newline-terminated JSON reply.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentResponse {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AgentResponse {
+    pub fn value(value: String) -> Self {
+        AgentResponse {
+            ok: true,
+            value: Some(value),
+            error: None,
+        }
+    }
+
+    pub fn error(error: impl Into<String>) -> Self {
+        AgentResponse {
+            ok: false,
+            value: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Returned by `agent_start`; the frontend shows both so the user can export
+/// them as `ZAP_AGENT_SOCK` and `ZAP_AGENT_TOKEN`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentInfo {
+    pub socket_path: String,
+    pub token: String,
+    pub grants: Vec<AgentGrant>,
+}
+
+/// Whether the agent is running, without its token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentStatus {
+    pub running: bool,
+    pub socket_path: Option<String>,
+    pub grants: Vec<AgentGrant>,
+}
+
+/// Check a request against the session token and the allow list. The token
+/// comparison goes through BLAKE3 so it takes the same time wherever the
+/// first differing byte is.
+pub fn authorize(
+    request: &AgentRequest,
+    session_token: &str,
+    grants: &[AgentGrant],
+) -> Result<(), &'static str> {
+    if blake3::hash(request.token().as_bytes()) != blake3::hash(session_token.as_bytes()) {
+        return Err("invalid agent token");
+    }
+    let grant = grants
+        .iter()
+        .find(|g| g.item_id == request.item_id())
+        .ok_or("item is not shared with the agent")?;
+    let allowed = match request {
+        AgentRequest::GetSecret { .. } => grant.reveal,
+        AgentRequest::Sign { .. } => grant.sign,
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err("operation is not allowed for this item")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(token: &str, item_id: &str) -> AgentRequest {
+        AgentRequest::GetSecret {
+            token: token.to_string(),
+            item_id: item_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn requests_need_the_token_and_a_matching_grant() {
+        let grants = vec![AgentGrant {
+            item_id: "a".to_string(),
+            reveal: true,
+            sign: false,
+        }];
+        assert!(authorize(&get("t", "a"), "t", &grants).is_ok());
+        assert_eq!(
+            authorize(&get("x", "a"), "t", &grants),
+            Err("invalid agent token")
+        );
+        assert!(authorize(&get("t", "b"), "t", &grants).is_err());
+        let sign = AgentRequest::Sign {
+            token: "t".to_string(),
+            item_id: "a".to_string(),
+            data_hex: "00".to_string(),
+        };
+        assert_eq!(
+            authorize(&sign, "t", &grants),
+            Err("operation is not allowed for this item")
+        );
+    }
+
+    #[test]
+    fn wire_format_is_tagged_by_op() {
+        let req: AgentRequest =
+            serde_json::from_str(r#"{"op":"get_secret","token":"t","item_id":"a"}"#).unwrap();
+        assert_eq!(req.op(), "get_secret");
+        let reply = serde_json::to_string(&AgentResponse::error("no")).unwrap();
+        assert_eq!(reply, r#"{"ok":false,"error":"no"}"#);
+    }
+}
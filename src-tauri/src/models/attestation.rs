@@ -0,0 +1,57 @@
+use crate::models::ceremony::ManifestOperator;
+use crate::models::instance::InstanceSignature;
+use crate::models::key::KeyType;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Where a keyset's key material came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EntropySource {
+    /// Derived from the vault's BIP39 master seed.
+    VaultMasterSeed,
+    /// Derived from a genesis ceremony's combined operator entropy.
+    GenesisCeremony {
+        ceremony_id: String,
+        operators: Vec<ManifestOperator>,
+        /// How many operators have signed the ceremony manifest.
+        attestation_count: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttestedKey {
+    pub key_id: String,
+    pub role: KeyType,
+    pub derivation_path: String,
+    pub public_key_hex: String,
+    pub address: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Public provenance of one keyset: its keys and roles, when it was
+/// generated and from what entropy. Contains no private material.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyAttestation {
+    pub version: u32,
+    pub keyset_id: String,
+    pub keyset_name: String,
+    pub generated_at: DateTime<Utc>,
+    pub entropy_source: EntropySource,
+    pub keys: Vec<AttestedKey>,
+    pub issued_at: DateTime<Utc>,
+    /// ML-DSA-87 public key of the vault identity that signs the document.
+    pub vault_public_key_hex: String,
+}
+
+/// A [`KeyAttestation`] as exported: the exact JSON `document` that was
+/// signed, so verifiers check the signature over those bytes before parsing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedKeyAttestation {
+    pub document: String,
+    pub signature_hex: String,
+    /// The issuing installation's signature over `document`, naming which
+    /// installation of the vault issued it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_signature: Option<InstanceSignature>,
+}
@@ -0,0 +1,270 @@
+use crate::models::instance::InstanceSignature;
+use crate::models::note::Note;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One vault file copied into a backup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupFile {
+    /// File name in the vault data directory (e.g. `vault.json`, `keys.enc`).
+    pub name: String,
+    pub size: u64,
+    /// BLAKE3 of the contents; also the key of the object holding them.
+    pub blake3_hex: String,
+    /// ML-DSA-87 signature by the vault identity over the backup id and this
+    /// entry. Absent for backups written while the vault was locked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_hex: Option<String>,
+}
+
+/// How a backup protects the files it stores.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackupMode {
+    /// Files are stored as they are in the data directory; the drive itself
+    /// is expected to be an encrypted container.
+    #[default]
+    Container,
+    /// Every file is encrypted on its own, for drives whose filesystem is
+    /// not encrypted (exFAT, NTFS, FAT32).
+    FileEncrypted,
+}
+
+/// Key derivation for a file-encrypted backup: Argon2id over the vault
+/// password with a salt of its own, so the backup restores with the password
+/// alone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEncryption {
+    pub salt_hex: String,
+    pub kdf: crate::models::vault::KdfProfile,
+}
+
+/// Written last into a backup directory: a backup without a manifest is an
+/// interrupted write and is ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub version: u32,
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub mode: BackupMode,
+    /// Set for [`BackupMode::FileEncrypted`] backups.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_encryption: Option<FileEncryption>,
+    pub files: Vec<BackupFile>,
+    /// Vault identity public key the file signatures verify against. Compare
+    /// it with `get_vault_identity` to know the backup came from this vault.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer_public_hex: Option<String>,
+    /// Signature of the installation that wrote the backup, over the whole
+    /// manifest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_signature: Option<InstanceSignature>,
+}
+
+impl BackupManifest {
+    pub fn total_bytes(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+}
+
+/// A backup as listed to the UI: its manifest plus the notes attached to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupListing {
+    #[serde(flatten)]
+    pub manifest: BackupManifest,
+    pub notes: Vec<Note>,
+}
+
+/// How many [`BackupRecord`]s a vault keeps.
+pub const MAX_BACKUP_RECORDS: usize = 64;
+
+/// A backup this vault wrote, remembered in `vault.json` so a security policy
+/// can require a verified one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupRecord {
+    pub drive_id: String,
+    pub backup_id: String,
+    pub created_at: DateTime<Utc>,
+    /// When the backup last passed `verify_backup`; cleared if a later
+    /// verification fails.
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileCheckStatus {
+    Ok,
+    Missing,
+    Corrupt,
+    /// The contents match the manifest, but the manifest entry's signature
+    /// does not: the entry was altered after the backup was written.
+    BadSignature,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileCheck {
+    pub name: String,
+    pub status: FileCheckStatus,
+}
+
+/// Result of re-reading a backup from its drive and checking every file
+/// against the manifest hashes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupVerification {
+    pub backup_id: String,
+    pub ok: bool,
+    pub files: Vec<FileCheck>,
+    /// Whether the manifest's instance signature verifies; `None` for
+    /// backups without one. A bad signature also clears `ok`.
+    #[serde(default)]
+    pub instance_signature_ok: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressStage {
+    Preparing,
+    Copying,
+    Finalizing,
+    Complete,
+}
+
+/// Payload of the `backup_progress` / `restore_progress` events.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupProgress {
+    pub stage: ProgressStage,
+    pub percent: u8,
+    /// The file being copied, if any.
+    pub current_item: Option<String>,
+    pub bytes_written: u64,
+    pub total_bytes: u64,
+    /// Average rate since the operation started.
+    pub bytes_per_sec: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EstimatedFile {
+    pub name: String,
+    pub size: u64,
+    /// Identical contents are already stored on the drive and will be shared.
+    pub stored: bool,
+}
+
+/// Expected footprint of a backup on a given drive, computed before anything
+/// is written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupEstimate {
+    pub drive_id: String,
+    pub files: Vec<EstimatedFile>,
+    /// Sum of the file sizes.
+    pub payload_bytes: u64,
+    /// Part of `payload_bytes` already on the drive from earlier backups.
+    pub deduplicated_bytes: u64,
+    /// Space needed on the drive: new objects plus manifest, each rounded up
+    /// to whole filesystem blocks.
+    pub required_bytes: u64,
+    /// Free space reported for the drive; `None` when the filesystem does not
+    /// report it.
+    pub available_bytes: Option<u64>,
+    /// `false` only when free space is known and too small.
+    pub fits: bool,
+    /// Set by `estimate_backup_size` when the drive's forecast says it is
+    /// running out of space.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity_warning: Option<crate::models::forecast::CapacityWarning>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrubStatus {
+    Corrupt,
+    Missing,
+    /// Was corrupt or missing and has been rewritten from an intact copy on
+    /// another attached drive.
+    Repaired,
+}
+
+/// A stored file whose contents no longer match their hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScrubIssue {
+    /// Drive path of the damaged file.
+    pub path: String,
+    pub blake3_hex: String,
+    pub status: ScrubStatus,
+    /// Backups that reference the file; empty for an unreferenced object.
+    pub backup_ids: Vec<String>,
+    /// Drive the intact copy was taken from, for `Repaired`.
+    pub repaired_from: Option<String>,
+}
+
+/// Outcome of re-reading everything the backup engine stored on a drive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScrubReport {
+    pub drive_id: String,
+    pub backups_checked: usize,
+    pub files_checked: usize,
+    pub bytes_checked: u64,
+    /// Backup directories whose manifest could not be read.
+    pub unreadable_manifests: Vec<String>,
+    pub issues: Vec<ScrubIssue>,
+}
+
+impl ScrubReport {
+    /// Nothing is left damaged (repaired files count as healthy).
+    pub fn is_healthy(&self) -> bool {
+        self.unreadable_manifests.is_empty()
+            && self
+                .issues
+                .iter()
+                .all(|i| i.status == ScrubStatus::Repaired)
+    }
+}
+
+/// Public `vault.json` settings of the vault a backup was taken from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectedVault {
+    pub initialized: bool,
+    pub kdf: crate::models::vault::KdfProfile,
+    pub yubikey_enabled: bool,
+    pub has_master_seed: bool,
+    pub air_gap_mode: bool,
+    pub address_gap_limit: u32,
+    pub keysets: Vec<crate::models::keyset::Keyset>,
+    /// Whether the keystore and item store `vault.json` points at are in the
+    /// backup and intact. Their contents stay encrypted.
+    pub keystore_intact: bool,
+    pub items_intact: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignedRecordKind {
+    CeremonyAttestation,
+    EmergencyGrant,
+}
+
+/// One signature found in a backup's plaintext metadata, checked against the
+/// public key recorded next to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureCheck {
+    pub kind: SignedRecordKind,
+    pub record_id: String,
+    /// Operator id or owner public key that signed.
+    pub signer: String,
+    pub valid: bool,
+    pub error: Option<String>,
+}
+
+/// Read-only view of a backup, possibly from another vault instance: nothing
+/// is decrypted, imported or written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInspection {
+    pub manifest: BackupManifest,
+    pub verification: BackupVerification,
+    /// `None` when `vault.json` is missing, damaged or unparsable.
+    pub vault: Option<InspectedVault>,
+    pub signatures: Vec<SignatureCheck>,
+    /// Metadata files that were intact but could not be parsed.
+    pub problems: Vec<String>,
+}
@@ -0,0 +1,124 @@
+use crate::crypto::encryption::Ciphertext;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Furthest ahead a capsule's release date may be set (fifty years).
+pub const MAX_CAPSULE_DAYS: i64 = 50 * 365;
+
+/// The contact whose signed time attestation a capsule needs before it
+/// opens, as it was when the capsule was sealed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapsuleAttestor {
+    pub contact_id: String,
+    pub name: String,
+    pub sign_public_hex: String,
+}
+
+/// A vault item sealed until `release_at`. The item is encrypted under a key
+/// built from the vault's capsule key and a random secondary key. The
+/// secondary key is itself sealed under a key bound to the capsule's terms,
+/// so editing the release date or attestor on disk makes it unopenable.
+/// See `docs/TIME_CAPSULE.md` for what this does and does not protect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeCapsule {
+    pub version: u32,
+    pub id: String,
+    pub item_id: String,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub release_at: DateTime<Utc>,
+    pub attestor: Option<CapsuleAttestor>,
+    pub sealed_secondary: Ciphertext,
+    pub payload: Ciphertext,
+}
+
+/// A statement by a vault identity that `time` (unix seconds) has been
+/// reached on its clock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeAttestation {
+    pub time: u64,
+    pub signer_public_hex: String,
+    pub signature_hex: String,
+}
+
+/// A capsule as listed. Never carries the sealed material.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapsuleStatus {
+    pub id: String,
+    pub item_id: String,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub release_at: DateTime<Utc>,
+    pub attestor: Option<CapsuleAttestor>,
+    /// Zero once the local clock has passed `release_at`.
+    pub seconds_remaining: u64,
+    /// Whether opening also needs a time attestation from `attestor`.
+    pub needs_attestation: bool,
+}
+
+impl TimeCapsule {
+    pub fn status(&self, now: DateTime<Utc>) -> CapsuleStatus {
+        CapsuleStatus {
+            id: self.id.clone(),
+            item_id: self.item_id.clone(),
+            label: self.label.clone(),
+            created_at: self.created_at,
+            release_at: self.release_at,
+            attestor: self.attestor.clone(),
+            seconds_remaining: (self.release_at - now).num_seconds().max(0) as u64,
+            needs_attestation: self.attestor.is_some(),
+        }
+    }
+}
+
+pub fn validate_release_at(release_at: DateTime<Utc>, now: DateTime<Utc>) -> Result<(), String> {
+    if release_at <= now {
+        return Err("release date must be in the future".to_string());
+    }
+    if release_at > now + Duration::days(MAX_CAPSULE_DAYS) {
+        return Err(format!(
+            "release date must be at most {MAX_CAPSULE_DAYS} days ahead"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capsule(release_at: DateTime<Utc>) -> TimeCapsule {
+        let empty = Ciphertext {
+            nonce: Vec::new(),
+            ciphertext: Vec::new(),
+        };
+        TimeCapsule {
+            version: 1,
+            id: "c1".to_string(),
+            item_id: "i1".to_string(),
+            label: None,
+            created_at: release_at - Duration::days(1),
+            release_at,
+            attestor: None,
+            sealed_secondary: empty.clone(),
+            payload: empty,
+        }
+    }
+
+    #[test]
+    fn status_counts_down_to_zero() {
+        let now = Utc::now();
+        let c = capsule(now + Duration::seconds(90));
+        assert_eq!(c.status(now).seconds_remaining, 90);
+        assert_eq!(c.status(now + Duration::days(2)).seconds_remaining, 0);
+        assert!(!c.status(now).needs_attestation);
+    }
+
+    #[test]
+    fn release_date_bounds() {
+        let now = Utc::now();
+        assert!(validate_release_at(now, now).is_err());
+        assert!(validate_release_at(now + Duration::days(30), now).is_ok());
+        assert!(validate_release_at(now + Duration::days(MAX_CAPSULE_DAYS + 1), now).is_err());
+    }
+}
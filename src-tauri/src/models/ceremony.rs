@@ -0,0 +1,136 @@
+use crate::models::key::KeyType;
+use crate::models::keyset::KeysetMemberSpec;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CeremonyPhase {
+    /// Operators are submitting entropy commitments.
+    Commit,
+    /// The genesis keyset exists; operators are signing its manifest.
+    Attesting,
+    /// Every operator has signed the manifest.
+    Complete,
+}
+
+/// An operator as given when the ceremony is set up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CeremonyOperatorInput {
+    pub name: String,
+    /// The operator's ML-DSA-87 public key, used to verify their attestation.
+    pub public_key_hex: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CeremonyOperator {
+    pub id: String,
+    pub name: String,
+    pub public_key_hex: String,
+    /// BLAKE3 commitment to the operator's entropy, submitted before anyone
+    /// reveals, so no operator can pick their entropy after seeing the others'.
+    pub commitment_hex: Option<String>,
+    pub committed_at: Option<DateTime<Utc>>,
+}
+
+/// One key of the genesis keyset as listed in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestKey {
+    pub key_type: KeyType,
+    pub derivation_path: String,
+    pub public_key_hex: String,
+    pub address: String,
+}
+
+/// The public result of a ceremony: the operators and their entropy
+/// commitments, and every key the combined entropy produced. This is what the
+/// operators sign.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisManifest {
+    pub ceremony_id: String,
+    pub name: String,
+    pub account: u32,
+    pub operators: Vec<ManifestOperator>,
+    pub keys: Vec<ManifestKey>,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestOperator {
+    pub id: String,
+    pub name: String,
+    pub public_key_hex: String,
+    pub commitment_hex: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorAttestation {
+    pub operator_id: String,
+    pub signature_hex: String,
+    pub signed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ceremony {
+    pub id: String,
+    pub name: String,
+    pub account: u32,
+    pub members: Vec<KeysetMemberSpec>,
+    pub operators: Vec<CeremonyOperator>,
+    pub phase: CeremonyPhase,
+    pub keyset_id: Option<String>,
+    pub manifest: Option<GenesisManifest>,
+    pub attestations: Vec<OperatorAttestation>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Operator-side output of contributing to a ceremony: the secret entropy,
+/// kept by the operator until the reveal, and its commitment, submitted now.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntropyContribution {
+    pub entropy_hex: String,
+    pub commitment_hex: String,
+}
+
+impl Drop for EntropyContribution {
+    fn drop(&mut self) {
+        self.entropy_hex.zeroize();
+    }
+}
+
+/// An operator revealing the entropy behind their commitment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntropyReveal {
+    pub operator_id: String,
+    pub entropy_hex: String,
+}
+
+impl Drop for EntropyReveal {
+    fn drop(&mut self) {
+        self.entropy_hex.zeroize();
+    }
+}
+
+/// Result of generating the genesis keyset. `backup_mnemonic` encodes the
+/// combined entropy and is the only way to restore the ceremony keys; it is
+/// returned once and never stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CeremonyGenerated {
+    pub ceremony: Ceremony,
+    pub backup_mnemonic: String,
+}
+
+impl Drop for CeremonyGenerated {
+    fn drop(&mut self) {
+        self.backup_mnemonic.zeroize();
+    }
+}
+
+/// The exportable attestation bundle shipped with the genesis config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationBundle {
+    pub version: u32,
+    pub manifest: GenesisManifest,
+    pub attestations: Vec<OperatorAttestation>,
+}
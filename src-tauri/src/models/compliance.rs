@@ -0,0 +1,59 @@
+use crate::models::policy::SecurityPolicy;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Layout of a file written by `export_compliance_report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceReportFormat {
+    /// RFC 4180 CSV with one `metric,value` row per figure.
+    Csv,
+    /// The [`ComplianceReport`] itself.
+    Json,
+}
+
+/// A vault profile's access controls, by count only.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessSummary {
+    /// Profiles with a vault set up.
+    pub profiles: usize,
+    /// Of those, vaults that open with the password alone: no YubiKey.
+    pub profiles_without_2fa: usize,
+    /// Vaults whose key derivation is older than the current profile.
+    pub profiles_with_outdated_kdf: usize,
+}
+
+/// Figures for auditors about the active vault and the other profiles on
+/// this machine. Only counts, dates and settings: no key material,
+/// addresses, labels or names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComplianceReport {
+    pub generated_at: DateTime<Utc>,
+    pub access: AccessSummary,
+    /// Live keys, in total and per key type.
+    pub keys: usize,
+    pub keys_by_type: BTreeMap<String, usize>,
+    /// Keys created before the newest verified backup, so a restore brings
+    /// them back.
+    pub keys_in_verified_backup: usize,
+    /// `keys_in_verified_backup` out of `keys`, rounded down. `None` without
+    /// keys.
+    pub keys_in_verified_backup_percent: Option<u8>,
+    /// Live items with a rotation deadline, and how many are past it.
+    pub items_with_rotation: usize,
+    pub items_past_rotation: usize,
+    pub backups: usize,
+    pub verified_backups: usize,
+    pub backup_drives: usize,
+    pub last_verified_backup_at: Option<DateTime<Utc>>,
+    pub policy: SecurityPolicy,
+    /// Whether a verified backup exists, as `require_verified_backup`
+    /// demands. `None` while that rule is off.
+    pub verified_backup_requirement_met: Option<bool>,
+}
+
+/// `part` out of `total`, rounded down.
+pub fn percent(part: usize, total: usize) -> Option<u8> {
+    (total > 0).then(|| (part * 100 / total) as u8)
+}
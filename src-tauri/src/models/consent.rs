@@ -0,0 +1,219 @@
+use crate::crypto::slip39::Slip39Group;
+use crate::models::agent::AgentGrant;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How long a consent token can be redeemed after it was issued.
+pub const CONSENT_TTL_SECS: i64 = 60;
+
+/// A destructive or exposing operation that needs explicit consent, with
+/// the arguments it will run with. A token only redeems for the exact
+/// operation it was issued for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConsentOperation {
+    /// `purge_trash`.
+    PurgeTrash { older_than_days: u32 },
+    /// `ssh_export_private_key`.
+    SshPrivateKeyExport { item_id: String },
+    /// `wireguard_export_config`.
+    WireguardConfigExport { item_id: String },
+    /// `passkey_export`.
+    PasskeyExport { item_ids: Vec<String> },
+    /// `api_token_reveal`.
+    ApiTokenReveal { item_id: String },
+    /// `render_env_file`.
+    EnvFileRender { item_ids: Vec<String>, path: String },
+    /// `plugin_item_reveal`.
+    PluginItemReveal { item_id: String },
+    /// `run_plugin_exporter`.
+    PluginExport {
+        plugin_id: String,
+        exporter: String,
+        path: String,
+    },
+    /// `generate_slip39_shares`.
+    Slip39Shares {
+        group_threshold: u8,
+        groups: Vec<Slip39Group>,
+    },
+    /// `frost_export_share`.
+    FrostShareExport { group_id: String, path: String },
+    /// `agent_start`.
+    AgentStart { grants: Vec<AgentGrant> },
+    /// `repair_orphans` with `fix`.
+    RepairOrphans,
+    /// `rollback_keyset` of a complete keyset.
+    RollbackKeyset { keyset_id: String },
+    /// `create_escrow_package`.
+    KeyEscrow {
+        key_id: String,
+        recipient_contact_id: String,
+    },
+}
+
+impl ConsentOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsentOperation::PurgeTrash { .. } => "purge_trash",
+            ConsentOperation::SshPrivateKeyExport { .. } => "ssh_private_key_export",
+            ConsentOperation::WireguardConfigExport { .. } => "wireguard_config_export",
+            ConsentOperation::PasskeyExport { .. } => "passkey_export",
+            ConsentOperation::ApiTokenReveal { .. } => "api_token_reveal",
+            ConsentOperation::EnvFileRender { .. } => "env_file_render",
+            ConsentOperation::PluginItemReveal { .. } => "plugin_item_reveal",
+            ConsentOperation::PluginExport { .. } => "plugin_export",
+            ConsentOperation::Slip39Shares { .. } => "slip39_shares",
+            ConsentOperation::FrostShareExport { .. } => "frost_share_export",
+            ConsentOperation::AgentStart { .. } => "agent_start",
+            ConsentOperation::RepairOrphans => "repair_orphans",
+            ConsentOperation::RollbackKeyset { .. } => "rollback_keyset",
+            ConsentOperation::KeyEscrow { .. } => "key_escrow",
+        }
+    }
+}
+
+/// Returned by `request_consent`. The UI shows `description` and passes
+/// `token` to the command it describes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsentToken {
+    pub token: String,
+    pub operation: ConsentOperation,
+    /// What the operation will do, in the vault's locale.
+    pub description: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct PendingConsent {
+    token: String,
+    operation: ConsentOperation,
+    epoch: u64,
+    expires_at: DateTime<Utc>,
+}
+
+/// Consent tokens issued and not yet redeemed. In-memory only; each token
+/// is tied to the lock epoch it was issued in, so none survives a lock.
+/// Pure logic taking `now` explicitly so it is unit-testable.
+#[derive(Debug, Default)]
+pub struct ConsentLedger {
+    pending: Vec<PendingConsent>,
+}
+
+impl ConsentLedger {
+    pub fn issue(
+        &mut self,
+        operation: ConsentOperation,
+        description: String,
+        epoch: u64,
+        now: DateTime<Utc>,
+    ) -> ConsentToken {
+        self.prune(epoch, now);
+        let token = ConsentToken {
+            token: hex::encode(rand::random::<[u8; 16]>()),
+            operation,
+            description,
+            expires_at: now + Duration::seconds(CONSENT_TTL_SECS),
+        };
+        self.pending.push(PendingConsent {
+            token: token.token.clone(),
+            operation: token.operation.clone(),
+            epoch,
+            expires_at: token.expires_at,
+        });
+        token
+    }
+
+    /// Use up `token` for `operation`. A token is spent by any attempt to
+    /// redeem it, so one issued for something else cannot be retried.
+    pub fn redeem(
+        &mut self,
+        token: &str,
+        operation: &ConsentOperation,
+        epoch: u64,
+        now: DateTime<Utc>,
+    ) -> Result<(), String> {
+        self.prune(epoch, now);
+        let idx = self
+            .pending
+            .iter()
+            .position(|p| p.token == token)
+            .ok_or_else(|| "the consent token is unknown, used or expired".to_string())?;
+        let pending = self.pending.swap_remove(idx);
+        if &pending.operation != operation {
+            return Err(format!(
+                "the consent token was issued for {}, not {}",
+                pending.operation.as_str(),
+                operation.as_str()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Drop every token, when the vault locks.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+
+    fn prune(&mut self, epoch: u64, now: DateTime<Utc>) {
+        self.pending
+            .retain(|p| p.epoch == epoch && now < p.expires_at);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn purge() -> ConsentOperation {
+        ConsentOperation::PurgeTrash { older_than_days: 0 }
+    }
+
+    #[test]
+    fn token_redeems_once_for_its_own_operation() {
+        let now = Utc::now();
+        let mut ledger = ConsentLedger::default();
+        let token = ledger.issue(purge(), "purge".to_string(), 1, now);
+        assert_eq!(token.expires_at, now + Duration::seconds(CONSENT_TTL_SECS));
+        assert!(ledger.redeem(&token.token, &purge(), 1, now).is_ok());
+        assert!(ledger.redeem(&token.token, &purge(), 1, now).is_err());
+
+        // A token for another operation is refused and spent.
+        let monthly = ConsentOperation::PurgeTrash {
+            older_than_days: 30,
+        };
+        let token = ledger.issue(monthly.clone(), "purge".to_string(), 1, now);
+        assert!(ledger.redeem(&token.token, &purge(), 1, now).is_err());
+        assert!(ledger.redeem(&token.token, &monthly, 1, now).is_err());
+    }
+
+    #[test]
+    fn file_write_token_is_bound_to_its_path() {
+        let now = Utc::now();
+        let mut ledger = ConsentLedger::default();
+        let render = |path: &str| ConsentOperation::EnvFileRender {
+            item_ids: vec!["a".to_string()],
+            path: path.to_string(),
+        };
+        let token = ledger.issue(render("/p/.env"), "render".to_string(), 1, now);
+        assert!(ledger
+            .redeem(&token.token, &render("/tmp/.env"), 1, now)
+            .is_err());
+        let token = ledger.issue(render("/p/.env"), "render".to_string(), 1, now);
+        assert!(ledger
+            .redeem(&token.token, &render("/p/.env"), 1, now)
+            .is_ok());
+    }
+
+    #[test]
+    fn tokens_expire_and_do_not_survive_a_lock() {
+        let now = Utc::now();
+        let mut ledger = ConsentLedger::default();
+        let token = ledger.issue(purge(), "purge".to_string(), 1, now);
+        let late = now + Duration::seconds(CONSENT_TTL_SECS);
+        assert!(ledger.redeem(&token.token, &purge(), 1, late).is_err());
+
+        let token = ledger.issue(purge(), "purge".to_string(), 1, now);
+        assert!(ledger.redeem(&token.token, &purge(), 2, now).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::crypto::fingerprint::KeyFingerprint;
+use serde::{Deserialize, Serialize};
+
+/// Longest display name a contact card may carry.
+pub const MAX_CONTACT_NAME_CHARS: usize = 64;
+
+/// A vault's public identity as handed to other vault users: its ML-DSA-87
+/// identity key, which signs the card, plus an X25519 and an ML-KEM-1024
+/// key that others can seal data to. Every key is derived from the owner's
+/// master seed, so a card stays valid across re-keying and restores.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContactCard {
+    pub version: u32,
+    pub name: String,
+    pub sign_public_hex: String,
+    pub x25519_public_hex: String,
+    pub kem_public_hex: String,
+    /// Unix seconds.
+    pub issued_at: u64,
+    /// ML-DSA-87 signature by `sign_public_hex` over every field above.
+    pub signature_hex: String,
+}
+
+/// An imported card, kept in plaintext metadata next to `vault.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contact {
+    pub id: String,
+    pub card: ContactCard,
+    /// Unix seconds.
+    pub imported_at: u64,
+    /// Set once the user has compared the fingerprint with the card owner
+    /// over another channel. A card's signature only proves it was not
+    /// altered, not whose it is.
+    pub verified: bool,
+}
+
+/// A contact as listed, with the fingerprint to compare.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactPublic {
+    #[serde(flatten)]
+    pub contact: Contact,
+    pub fingerprint: KeyFingerprint,
+}
+
+/// This vault's own card and the fingerprint to read out to its recipients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnContactCard {
+    pub card: ContactCard,
+    pub fingerprint: KeyFingerprint,
+}
+
+pub fn validate_contact_name(name: &str) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("contact name must not be empty".to_string());
+    }
+    if name.chars().count() > MAX_CONTACT_NAME_CHARS {
+        return Err(format!(
+            "contact name must be at most {MAX_CONTACT_NAME_CHARS} characters"
+        ));
+    }
+    if name.chars().any(char::is_control) {
+        return Err("contact name must not contain control characters".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contact_names_are_bounded() {
+        assert!(validate_contact_name("Alice").is_ok());
+        assert!(validate_contact_name("  ").is_err());
+        assert!(validate_contact_name("bad\nname").is_err());
+        assert!(validate_contact_name(&"x".repeat(MAX_CONTACT_NAME_CHARS)).is_ok());
+        assert!(validate_contact_name(&"x".repeat(MAX_CONTACT_NAME_CHARS + 1)).is_err());
+    }
+}
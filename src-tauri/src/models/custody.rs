@@ -0,0 +1,266 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Default age after which a drive's location counts as unverified.
+pub const DEFAULT_LOCATION_WINDOW_DAYS: u32 = 90;
+pub const MAX_LOCATION_WINDOW_DAYS: u32 = 3650;
+/// How many custody events each drive keeps.
+pub const MAX_CUSTODY_HISTORY: usize = 100;
+
+fn default_window_days() -> u32 {
+    DEFAULT_LOCATION_WINDOW_DAYS
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustodyAction {
+    CheckIn,
+    CheckOut,
+    /// Someone confirmed the drive is still where it was checked in.
+    Verified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustodyEvent {
+    pub action: CustodyAction,
+    pub location: String,
+    pub custodian: String,
+    pub at: DateTime<Utc>,
+}
+
+/// Where a backup drive is kept and who holds it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveLocation {
+    pub drive_id: String,
+    pub location: String,
+    pub custodian: String,
+    /// `false` while the drive is checked out of its location.
+    pub checked_in: bool,
+    /// Last check-in or confirmation; a check-out does not count.
+    pub last_verified_at: DateTime<Utc>,
+    /// Oldest first, capped at [`MAX_CUSTODY_HISTORY`].
+    #[serde(default)]
+    pub history: Vec<CustodyEvent>,
+}
+
+impl DriveLocation {
+    fn record(&mut self, action: CustodyAction, at: DateTime<Utc>) {
+        self.history.push(CustodyEvent {
+            action,
+            location: self.location.clone(),
+            custodian: self.custodian.clone(),
+            at,
+        });
+        if self.history.len() > MAX_CUSTODY_HISTORY {
+            let excess = self.history.len() - MAX_CUSTODY_HISTORY;
+            self.history.drain(..excess);
+        }
+    }
+}
+
+/// A drive whose location has not been verified within the window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaleLocation {
+    pub drive_id: String,
+    pub location: String,
+    pub custodian: String,
+    pub checked_in: bool,
+    pub last_verified_at: DateTime<Utc>,
+    pub days_since_verified: i64,
+}
+
+/// Every tracked drive location, persisted as `drive_locations.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustodyBook {
+    #[serde(default = "default_window_days")]
+    pub window_days: u32,
+    #[serde(default)]
+    pub drives: Vec<DriveLocation>,
+}
+
+impl Default for CustodyBook {
+    fn default() -> Self {
+        CustodyBook {
+            window_days: DEFAULT_LOCATION_WINDOW_DAYS,
+            drives: Vec::new(),
+        }
+    }
+}
+
+fn check_field(name: &str, value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err(format!("{name} must not be empty"));
+    }
+    Ok(())
+}
+
+impl CustodyBook {
+    pub fn validate_window(days: u32) -> Result<(), String> {
+        if !(1..=MAX_LOCATION_WINDOW_DAYS).contains(&days) {
+            return Err(format!(
+                "window must be between 1 and {MAX_LOCATION_WINDOW_DAYS} days"
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, drive_id: &str) -> Option<&DriveLocation> {
+        self.drives.iter().find(|d| d.drive_id == drive_id)
+    }
+
+    fn get_mut(&mut self, drive_id: &str) -> Result<&mut DriveLocation, String> {
+        self.drives
+            .iter_mut()
+            .find(|d| d.drive_id == drive_id)
+            .ok_or_else(|| format!("drive {drive_id} has no recorded location"))
+    }
+
+    /// Put `drive_id` at `location` in `custodian`'s care, which also
+    /// verifies its location. Starts tracking the drive if needed.
+    pub fn check_in(
+        &mut self,
+        drive_id: &str,
+        location: &str,
+        custodian: &str,
+        now: DateTime<Utc>,
+    ) -> Result<&DriveLocation, String> {
+        check_field("drive id", drive_id)?;
+        check_field("location", location)?;
+        check_field("custodian", custodian)?;
+        let index = match self.drives.iter().position(|d| d.drive_id == drive_id) {
+            Some(i) => i,
+            None => {
+                self.drives.push(DriveLocation {
+                    drive_id: drive_id.to_string(),
+                    location: String::new(),
+                    custodian: String::new(),
+                    checked_in: false,
+                    last_verified_at: now,
+                    history: Vec::new(),
+                });
+                self.drives.len() - 1
+            }
+        };
+        let entry = &mut self.drives[index];
+        entry.location = location.trim().to_string();
+        entry.custodian = custodian.trim().to_string();
+        entry.checked_in = true;
+        entry.last_verified_at = now;
+        entry.record(CustodyAction::CheckIn, now);
+        Ok(entry)
+    }
+
+    /// Take `drive_id` out of its location, now held by `custodian`.
+    pub fn check_out(
+        &mut self,
+        drive_id: &str,
+        custodian: &str,
+        now: DateTime<Utc>,
+    ) -> Result<&DriveLocation, String> {
+        check_field("custodian", custodian)?;
+        let entry = self.get_mut(drive_id)?;
+        if !entry.checked_in {
+            return Err(format!("drive {drive_id} is already checked out"));
+        }
+        entry.checked_in = false;
+        entry.custodian = custodian.trim().to_string();
+        entry.record(CustodyAction::CheckOut, now);
+        Ok(entry)
+    }
+
+    /// Confirm a checked-in drive is still at its location.
+    pub fn verify(&mut self, drive_id: &str, now: DateTime<Utc>) -> Result<&DriveLocation, String> {
+        let entry = self.get_mut(drive_id)?;
+        if !entry.checked_in {
+            return Err(format!(
+                "drive {drive_id} is checked out; check it in instead"
+            ));
+        }
+        entry.last_verified_at = now;
+        entry.record(CustodyAction::Verified, now);
+        Ok(entry)
+    }
+
+    /// Drives not verified within `window_days` of `now`, most overdue first.
+    pub fn stale(&self, now: DateTime<Utc>) -> Vec<StaleLocation> {
+        let window = Duration::days(i64::from(self.window_days));
+        let mut stale: Vec<StaleLocation> = self
+            .drives
+            .iter()
+            .filter(|d| now - d.last_verified_at > window)
+            .map(|d| StaleLocation {
+                drive_id: d.drive_id.clone(),
+                location: d.location.clone(),
+                custodian: d.custodian.clone(),
+                checked_in: d.checked_in,
+                last_verified_at: d.last_verified_at,
+                days_since_verified: (now - d.last_verified_at).num_days(),
+            })
+            .collect();
+        stale.sort_by_key(|s| s.last_verified_at);
+        stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_in_out_and_verify() {
+        let t0 = Utc::now();
+        let mut book = CustodyBook::default();
+        book.check_in("usb-1", "Safe #2", "alice", t0).unwrap();
+        assert!(book.check_in("usb-1", " ", "alice", t0).is_err());
+        assert!(book.verify("usb-9", t0).is_err());
+
+        let t1 = t0 + Duration::days(3);
+        let out = book.check_out("usb-1", "bob", t1).unwrap();
+        assert!(!out.checked_in);
+        assert_eq!(out.last_verified_at, t0);
+        assert!(book.check_out("usb-1", "bob", t1).is_err());
+        assert!(book.verify("usb-1", t1).is_err());
+
+        let t2 = t1 + Duration::days(1);
+        book.check_in("usb-1", "Bank vault", "carol", t2).unwrap();
+        book.verify("usb-1", t2).unwrap();
+        let entry = book.get("usb-1").unwrap();
+        assert_eq!(entry.location, "Bank vault");
+        assert_eq!(entry.last_verified_at, t2);
+        let actions: Vec<_> = entry.history.iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            [
+                CustodyAction::CheckIn,
+                CustodyAction::CheckOut,
+                CustodyAction::CheckIn,
+                CustodyAction::Verified
+            ]
+        );
+    }
+
+    #[test]
+    fn stale_report_respects_window() {
+        let t0 = Utc::now();
+        let mut book = CustodyBook {
+            window_days: 30,
+            ..Default::default()
+        };
+        book.check_in("old", "Safe", "alice", t0).unwrap();
+        book.check_in("new", "Safe", "alice", t0 + Duration::days(20))
+            .unwrap();
+        book.check_out("new", "bob", t0 + Duration::days(40))
+            .unwrap();
+
+        let report = book.stale(t0 + Duration::days(45));
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].drive_id, "old");
+        assert_eq!(report[0].days_since_verified, 45);
+
+        let later = book.stale(t0 + Duration::days(60));
+        assert_eq!(later.len(), 2);
+        assert_eq!(later[0].drive_id, "old");
+        assert!(!later[1].checked_in);
+        assert!(CustodyBook::validate_window(0).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use crate::models::backup::BackupRecord;
+use crate::models::health::HealthEntryKind;
+use crate::models::item::VaultItem;
+use crate::models::key::KeyEntry;
+use crate::models::vault::VaultState;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Items expiring within this many days count as upcoming rotations.
+pub const ROTATION_HORIZON_DAYS: i64 = 30;
+/// Entries in [`DashboardSummary::recent_activity`].
+pub const RECENT_ACTIVITY_LEN: usize = 8;
+
+/// The newest backup of this vault on one drive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveBackupSummary {
+    pub drive_id: String,
+    pub backup_id: String,
+    pub created_at: DateTime<Utc>,
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RotationSummary {
+    /// Past their expiry.
+    pub overdue: usize,
+    /// Expiring within [`ROTATION_HORIZON_DAYS`].
+    pub upcoming: usize,
+    pub next_due_at: Option<DateTime<Utc>>,
+}
+
+/// A key or item by its last use.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentUse {
+    pub kind: HealthEntryKind,
+    pub id: String,
+    pub label: Option<String>,
+    pub last_used_at: DateTime<Utc>,
+}
+
+/// Something on the dashboard that needs the user's attention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DashboardHighlight {
+    FailedUnlockAttempts,
+    OverdueRotations,
+    NoVerifiedBackup,
+    UnverifiedBackups,
+    KdfUpgradeAvailable,
+}
+
+/// Everything the dashboard shows, computed in one pass over the unlocked
+/// stores and the vault metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DashboardSummary {
+    /// Live keys per key type.
+    pub keys_by_type: BTreeMap<String, usize>,
+    /// Live items per item kind.
+    pub items_by_kind: BTreeMap<String, usize>,
+    pub trashed_keys: usize,
+    pub trashed_items: usize,
+    /// Newest backup per drive, most recent first.
+    pub last_backup_per_drive: Vec<DriveBackupSummary>,
+    pub rotations: RotationSummary,
+    pub recent_activity: Vec<RecentUse>,
+    pub failed_unlock_attempts: u32,
+    pub highlights: Vec<DashboardHighlight>,
+}
+
+fn last_backups(history: &[BackupRecord]) -> Vec<DriveBackupSummary> {
+    let mut latest: BTreeMap<&str, &BackupRecord> = BTreeMap::new();
+    for record in history {
+        latest
+            .entry(&record.drive_id)
+            .and_modify(|r| {
+                if record.created_at >= r.created_at {
+                    *r = record;
+                }
+            })
+            .or_insert(record);
+    }
+    let mut out: Vec<DriveBackupSummary> = latest
+        .into_values()
+        .map(|r| DriveBackupSummary {
+            drive_id: r.drive_id.clone(),
+            backup_id: r.backup_id.clone(),
+            created_at: r.created_at,
+            verified_at: r.verified_at,
+        })
+        .collect();
+    out.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    out
+}
+
+/// Aggregate the dashboard from the unlocked keystore and item store. Pure
+/// so it can be tested without an app.
+pub fn summarize(
+    keys: &[KeyEntry],
+    items: &[VaultItem],
+    vault: &VaultState,
+    failed_unlock_attempts: u32,
+    now: DateTime<Utc>,
+) -> DashboardSummary {
+    let mut keys_by_type = BTreeMap::new();
+    let mut items_by_kind = BTreeMap::new();
+    let mut recent = Vec::new();
+    let mut rotations = RotationSummary::default();
+    let (mut trashed_keys, mut trashed_items) = (0, 0);
+
+    for key in keys {
+        if key.metadata.trashed_at.is_some() {
+            trashed_keys += 1;
+            continue;
+        }
+        *keys_by_type
+            .entry(key.metadata.key_type.as_str().to_string())
+            .or_insert(0) += 1;
+        if let Some(at) = key.metadata.usage.last_used_at {
+            recent.push(RecentUse {
+                kind: HealthEntryKind::Key,
+                id: key.id.clone(),
+                label: key.metadata.label.clone(),
+                last_used_at: at,
+            });
+        }
+    }
+    let horizon = now + Duration::days(ROTATION_HORIZON_DAYS);
+    for item in items {
+        if item.trashed_at.is_some() {
+            trashed_items += 1;
+            continue;
+        }
+        *items_by_kind
+            .entry(item.payload.kind().to_string())
+            .or_insert(0) += 1;
+        if let Some(at) = item.usage.last_used_at {
+            recent.push(RecentUse {
+                kind: HealthEntryKind::Item,
+                id: item.id.clone(),
+                label: item.label.clone(),
+                last_used_at: at,
+            });
+        }
+        match item.expires_at {
+            Some(_) if item.is_expired(now) => rotations.overdue += 1,
+            Some(due) if due <= horizon => {
+                rotations.upcoming += 1;
+                rotations.next_due_at = Some(rotations.next_due_at.map_or(due, |d| d.min(due)));
+            }
+            _ => {}
+        }
+    }
+    recent.sort_by_key(|r| std::cmp::Reverse(r.last_used_at));
+    recent.truncate(RECENT_ACTIVITY_LEN);
+
+    let mut highlights = Vec::new();
+    if failed_unlock_attempts > 0 {
+        highlights.push(DashboardHighlight::FailedUnlockAttempts);
+    }
+    if rotations.overdue > 0 {
+        highlights.push(DashboardHighlight::OverdueRotations);
+    }
+    if !vault.has_verified_backup() {
+        highlights.push(DashboardHighlight::NoVerifiedBackup);
+    } else if vault.backup_history.iter().any(|b| b.verified_at.is_none()) {
+        highlights.push(DashboardHighlight::UnverifiedBackups);
+    }
+    if vault.needs_kdf_upgrade() {
+        highlights.push(DashboardHighlight::KdfUpgradeAvailable);
+    }
+
+    DashboardSummary {
+        keys_by_type,
+        items_by_kind,
+        trashed_keys,
+        trashed_items,
+        last_backup_per_drive: last_backups(&vault.backup_history),
+        rotations,
+        recent_activity: recent,
+        failed_unlock_attempts,
+        highlights,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::item::{ApiTokenItem, ItemPayload};
+    use crate::models::vault::KdfProfile;
+
+    fn token(expires_in_days: Option<i64>, now: DateTime<Utc>) -> VaultItem {
+        let mut item = VaultItem::new(
+            None,
+            ItemPayload::ApiToken(ApiTokenItem {
+                provider: "github".into(),
+                token: "t".into(),
+                scopes: Vec::new(),
+                environment: None,
+                env_var: "T".into(),
+            }),
+        );
+        item.expires_at = expires_in_days.map(|d| now + Duration::days(d));
+        item
+    }
+
+    fn record(drive: &str, id: &str, at: DateTime<Utc>, verified: bool) -> BackupRecord {
+        BackupRecord {
+            drive_id: drive.into(),
+            backup_id: id.into(),
+            created_at: at,
+            verified_at: verified.then_some(at),
+        }
+    }
+
+    fn current_vault() -> VaultState {
+        let profile = KdfProfile::current();
+        VaultState {
+            kdf_version: profile.version,
+            argon2_memory_kib: profile.memory_kib,
+            argon2_iterations: profile.iterations,
+            argon2_parallelism: profile.parallelism,
+            ..VaultState::default()
+        }
+    }
+
+    #[test]
+    fn counts_rotations_and_activity() {
+        let now = Utc::now();
+        let mut used = token(None, now);
+        used.usage.touch(now - Duration::hours(1));
+        let mut trashed = token(None, now);
+        trashed.trashed_at = Some(now);
+        let items = vec![
+            token(Some(-1), now),
+            token(Some(10), now),
+            token(Some(90), now),
+            used,
+            trashed,
+        ];
+        let summary = summarize(&[], &items, &current_vault(), 0, now);
+        assert_eq!(summary.items_by_kind["api_token"], 4);
+        assert_eq!(summary.trashed_items, 1);
+        assert_eq!(summary.rotations.overdue, 1);
+        assert_eq!(summary.rotations.upcoming, 1);
+        assert_eq!(summary.recent_activity.len(), 1);
+        assert_eq!(
+            summary.highlights,
+            [
+                DashboardHighlight::OverdueRotations,
+                DashboardHighlight::NoVerifiedBackup
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_newest_backup_per_drive() {
+        let now = Utc::now();
+        let mut vault = current_vault();
+        vault.backup_history = vec![
+            record("a", "1", now - Duration::days(3), true),
+            record("b", "2", now - Duration::days(2), false),
+            record("a", "3", now - Duration::days(1), false),
+        ];
+        let summary = summarize(&[], &[], &vault, 2, now);
+        let ids: Vec<&str> = summary
+            .last_backup_per_drive
+            .iter()
+            .map(|b| b.backup_id.as_str())
+            .collect();
+        assert_eq!(ids, ["3", "2"]);
+        assert_eq!(
+            summary.highlights,
+            [
+                DashboardHighlight::FailedUnlockAttempts,
+                DashboardHighlight::UnverifiedBackups
+            ]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+/// How long an entry stays cached without being read.
+pub const DISPLAY_CACHE_TTL_SECS: i64 = 5 * 60;
+/// Most entries kept; past this the least recently read are dropped.
+pub const DISPLAY_CACHE_MAX_ENTRIES: usize = 4096;
+
+#[derive(Debug)]
+struct Entry<V> {
+    epoch: u64,
+    last_used: DateTime<Utc>,
+    value: V,
+}
+
+/// Display data built for the current session, such as decrypted note text
+/// or a key's detail view, so detail views need not decrypt or compute it
+/// again on every call. Each entry is tagged with the lock epoch it was built
+/// in and is never returned in another one; it also expires after
+/// [`DISPLAY_CACHE_TTL_SECS`] unread.
+/// Never put key material or item secrets in it; hold decrypted text in
+/// `Zeroizing` so it is wiped when dropped.
+#[derive(Debug)]
+pub struct DisplayCache<V> {
+    entries: HashMap<String, Entry<V>>,
+}
+
+impl<V> Default for DisplayCache<V> {
+    fn default() -> Self {
+        DisplayCache {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<V: Clone> DisplayCache<V> {
+    /// The value cached under `key` in `epoch`, refreshing its expiry.
+    pub fn get(&mut self, key: &str, epoch: u64, now: DateTime<Utc>) -> Option<V> {
+        self.prune(epoch, now);
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = now;
+        Some(entry.value.clone())
+    }
+
+    pub fn insert(&mut self, key: String, epoch: u64, now: DateTime<Utc>, value: V) {
+        self.prune(epoch, now);
+        if self.entries.len() >= DISPLAY_CACHE_MAX_ENTRIES && !self.entries.contains_key(&key) {
+            if let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone())
+            {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(
+            key,
+            Entry {
+                epoch,
+                last_used: now,
+                value,
+            },
+        );
+    }
+
+    /// Drop `key`, after the data behind it changed or was deleted.
+    pub fn remove(&mut self, key: &str) {
+        self.entries.remove(key);
+    }
+
+    /// Drop everything, when the vault locks.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drop entries from another session and entries unread for too long.
+    fn prune(&mut self, epoch: u64, now: DateTime<Utc>) {
+        let ttl = Duration::seconds(DISPLAY_CACHE_TTL_SECS);
+        self.entries
+            .retain(|_, e| e.epoch == epoch && now - e.last_used < ttl);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_expire_when_unread_and_across_sessions() {
+        let now = Utc::now();
+        let mut cache = DisplayCache::default();
+        cache.insert("a".to_string(), 1, now, "alpha".to_string());
+        cache.insert("b".to_string(), 1, now, "beta".to_string());
+
+        // Reading keeps an entry alive past the first TTL.
+        let later = now + Duration::seconds(DISPLAY_CACHE_TTL_SECS - 1);
+        assert_eq!(cache.get("a", 1, later).as_deref(), Some("alpha"));
+        let much_later = now + Duration::seconds(DISPLAY_CACHE_TTL_SECS + 1);
+        assert_eq!(cache.get("a", 1, much_later).as_deref(), Some("alpha"));
+        assert_eq!(cache.get("b", 1, much_later), None);
+        assert_eq!(cache.len(), 1);
+
+        // Nothing from an earlier session is handed out.
+        assert_eq!(cache.get("a", 2, much_later), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn least_recently_read_is_evicted_when_full() {
+        let now = Utc::now();
+        let mut cache = DisplayCache::default();
+        for i in 0..DISPLAY_CACHE_MAX_ENTRIES {
+            let at = now + Duration::milliseconds(i as i64);
+            cache.insert(i.to_string(), 0, at, i);
+        }
+        let at = now + Duration::seconds(1);
+        assert_eq!(cache.get("0", 0, at), Some(0));
+        cache.insert("new".to_string(), 0, at, usize::MAX);
+        assert_eq!(cache.len(), DISPLAY_CACHE_MAX_ENTRIES);
+        assert_eq!(cache.get("0", 0, at), Some(0));
+        assert_eq!(cache.get("1", 0, at), None);
+        cache.remove("new");
+        assert_eq!(cache.get("new", 0, at), None);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::models::note::Note;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Directory on a drive that holds a portable vault's files.
+pub const PORTABLE_VAULT_DIR: &str = "zap-vault";
+
+/// Whether the vault can use a detected drive as-is. The vault never mounts
+/// or unlocks devices itself; that is left to the desktop (udisks, Finder,
+/// Explorer), so anything short of `Ready` is reported for the user to fix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriveStatus {
+    Ready,
+    /// A filesystem was found but is not mounted.
+    NotMounted,
+    /// An encrypted volume (LUKS, BitLocker, APFS) that has not been
+    /// unlocked.
+    Locked,
+    /// Mounted read-only, or the device is write-protected.
+    ReadOnly,
+}
+
+/// How a drive is encrypted at the volume level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriveEncryption {
+    Luks,
+    /// BitLocker, including BitLocker To Go on removable drives.
+    BitLocker,
+    /// An encrypted APFS volume.
+    Apfs,
+}
+
+/// A removable drive (or partition) as detected by a `DriveBackend`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveInfo {
+    /// Stable identifier: the filesystem UUID when there is one, otherwise the
+    /// device path.
+    pub id: String,
+    pub device: String,
+    pub label: Option<String>,
+    pub fs_type: Option<String>,
+    pub size_bytes: u64,
+    pub available_bytes: Option<u64>,
+    pub mount_point: Option<String>,
+    pub encrypted: bool,
+    /// The volume encryption behind `encrypted`, when it is set.
+    #[serde(default)]
+    pub encryption: Option<DriveEncryption>,
+    /// UUID of the LUKS header the filesystem sits in, for encrypted drives.
+    #[serde(default)]
+    pub luks_uuid: Option<String>,
+    pub status: DriveStatus,
+}
+
+impl DriveInfo {
+    pub fn is_writable(&self) -> bool {
+        self.status == DriveStatus::Ready
+    }
+}
+
+/// A drive with every note attached to it or to its backups.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveDetails {
+    pub drive: DriveInfo,
+    pub notes: Vec<Note>,
+}
+
+/// Set when the vault's files live on a removable drive instead of the local
+/// data directory. Kept in the local data directory, since it is what finds
+/// the drive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortableVault {
+    pub drive_id: String,
+    pub label: Option<String>,
+    pub moved_at: DateTime<Utc>,
+}
+
+/// What `portable_vault_status` returns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortableVaultStatus {
+    pub enabled: bool,
+    pub drive_id: Option<String>,
+    pub label: Option<String>,
+    /// Whether the drive is mounted with the vault directory on it.
+    pub attached: bool,
+    pub path: Option<String>,
+}
+
+/// What detaching a drive does, set per drive in the drive trust settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveTrust {
+    pub drive_id: String,
+    pub label: Option<String>,
+    /// The vault stays unlocked only while this drive is plugged in.
+    #[serde(default)]
+    pub presence_token: bool,
+    /// Lock when the drive is detached while it holds a backup of this vault
+    /// that has not been verified.
+    #[serde(default)]
+    pub lock_on_unsynced_backup: bool,
+}
+
+/// Why a drive change locked the vault. The payload of the auto-lock event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum DriveLockTrigger {
+    PresenceTokenMissing { drive_id: String },
+    UnsyncedBackupDetached { drive_id: String },
+}
+
+impl DriveLockTrigger {
+    pub fn describe(&self) -> String {
+        match self {
+            DriveLockTrigger::PresenceTokenMissing { drive_id } => {
+                format!("presence token drive {drive_id} is not attached")
+            }
+            DriveLockTrigger::UnsyncedBackupDetached { drive_id } => {
+                format!("drive {drive_id} was removed before its backup was verified")
+            }
+        }
+    }
+}
+
+/// The first rule in `trust` broken by the drives now `attached`, given
+/// those attached at the last check. `unsynced` says whether a drive holds
+/// a backup that has not been verified.
+pub fn drive_lock_trigger(
+    trust: &[DriveTrust],
+    attached: &HashSet<String>,
+    previously: &HashSet<String>,
+    unsynced: impl Fn(&str) -> bool,
+) -> Option<DriveLockTrigger> {
+    trust.iter().find_map(|t| {
+        if attached.contains(&t.drive_id) {
+            return None;
+        }
+        if t.presence_token {
+            Some(DriveLockTrigger::PresenceTokenMissing {
+                drive_id: t.drive_id.clone(),
+            })
+        } else if t.lock_on_unsynced_backup
+            && previously.contains(&t.drive_id)
+            && unsynced(&t.drive_id)
+        {
+            Some(DriveLockTrigger::UnsyncedBackupDetached {
+                drive_id: t.drive_id.clone(),
+            })
+        } else {
+            None
+        }
+    })
+}
+
+/// Where a portable vault's files are on `drive`, if it is mounted.
+pub fn portable_root(drive: &DriveInfo) -> Option<PathBuf> {
+    drive
+        .mount_point
+        .as_ref()
+        .map(|m| Path::new(m).join(PORTABLE_VAULT_DIR))
+}
+
+/// A drive can hold the live vault only if it is an unlocked encrypted
+/// container, mounted writable. Plaintext drives are refused: several vault
+/// stores are unencrypted JSON.
+pub fn check_portable_drive(drive: &DriveInfo) -> Result<PathBuf, String> {
+    if !drive.encrypted {
+        return Err(format!("drive {} is not encrypted", drive.id));
+    }
+    match portable_root(drive) {
+        Some(root) if drive.is_writable() => Ok(root),
+        _ => Err(format!(
+            "drive {} must be unlocked and mounted writable",
+            drive.id
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn portable_vault_needs_mounted_encrypted_drive() {
+        let mut drive = DriveInfo {
+            id: "uuid-1".to_string(),
+            device: "/dev/mapper/luks-1".to_string(),
+            label: Some("VAULT".to_string()),
+            fs_type: Some("ext4".to_string()),
+            size_bytes: 1 << 30,
+            available_bytes: Some(1 << 29),
+            mount_point: Some("/media/user/VAULT".to_string()),
+            encrypted: true,
+            encryption: Some(DriveEncryption::Luks),
+            luks_uuid: Some("luks-1".to_string()),
+            status: DriveStatus::Ready,
+        };
+        assert_eq!(
+            check_portable_drive(&drive).unwrap(),
+            Path::new("/media/user/VAULT/zap-vault")
+        );
+
+        drive.status = DriveStatus::ReadOnly;
+        assert!(check_portable_drive(&drive).is_err());
+        drive.status = DriveStatus::Ready;
+        drive.encrypted = false;
+        assert!(check_portable_drive(&drive).is_err());
+        drive.encrypted = true;
+        drive.mount_point = None;
+        assert!(check_portable_drive(&drive).is_err());
+        assert!(portable_root(&drive).is_none());
+    }
+
+    fn trust(drive_id: &str, presence_token: bool, lock_on_unsynced_backup: bool) -> DriveTrust {
+        DriveTrust {
+            drive_id: drive_id.to_string(),
+            label: None,
+            presence_token,
+            lock_on_unsynced_backup,
+        }
+    }
+
+    fn ids(ids: &[&str]) -> HashSet<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn drive_lock_trigger_follows_trust_settings() {
+        let rules = [trust("token", true, false), trust("backup", false, true)];
+        let unsynced = |id: &str| id == "backup";
+        let all = ids(&["token", "backup"]);
+        assert_eq!(drive_lock_trigger(&rules, &all, &all, unsynced), None);
+
+        // A presence token locks whenever it is missing, not only on removal.
+        assert_eq!(
+            drive_lock_trigger(&rules, &ids(&["backup"]), &ids(&["backup"]), unsynced),
+            Some(DriveLockTrigger::PresenceTokenMissing {
+                drive_id: "token".to_string()
+            })
+        );
+
+        // A backup drive locks only when it goes away holding an unverified backup.
+        let token = ids(&["token"]);
+        assert_eq!(
+            drive_lock_trigger(&rules, &token, &all, unsynced),
+            Some(DriveLockTrigger::UnsyncedBackupDetached {
+                drive_id: "backup".to_string()
+            })
+        );
+        assert_eq!(drive_lock_trigger(&rules, &token, &token, unsynced), None);
+        assert_eq!(drive_lock_trigger(&rules, &token, &all, |_| false), None);
+    }
+}
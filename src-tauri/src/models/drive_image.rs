@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// `format` of a [`DriveImageHeader`].
+pub const DRIVE_IMAGE_FORMAT: &str = "zap-drive-image";
+pub const DRIVE_IMAGE_VERSION: u32 = 1;
+/// First bytes of an image file, before the nonce and the ciphertext.
+pub const DRIVE_IMAGE_MAGIC: &[u8] = b"ZAPDRVIMG1\n";
+
+/// One file copied from the drive into the image.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveImageEntry {
+    /// Path relative to the drive's mount point.
+    pub path: String,
+    pub size: u64,
+    pub blake3_hex: String,
+}
+
+/// First line of a decrypted, decompressed image. The file contents follow
+/// in the same order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveImageHeader {
+    pub format: String,
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub source_drive_id: String,
+    pub source_label: Option<String>,
+    pub files: Vec<DriveImageEntry>,
+}
+
+impl DriveImageHeader {
+    pub fn total_bytes(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+}
+
+/// Returned by `export_backup_set_image` and `write_backup_set_image`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveImageSummary {
+    pub source_drive_id: String,
+    /// The drive the image was written to, for `write_backup_set_image`.
+    pub target_drive_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub backups: usize,
+    pub files: usize,
+    pub payload_bytes: u64,
+    /// Size of the image file, compressed and encrypted.
+    pub image_bytes: u64,
+    /// BLAKE3 of the image file, to record next to it.
+    pub image_blake3_hex: String,
+}
@@ -0,0 +1,118 @@
+use crate::i18n::tr;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// File at the root of a provisioned drive holding its rolling nonce.
+pub const DRIVE_SEAL_FILE: &str = "zap-drive-seal.json";
+/// `format` of a [`DriveSealFile`].
+pub const DRIVE_SEAL_FORMAT: &str = "zap-drive-seal";
+pub const DRIVE_SEAL_VERSION: u32 = 1;
+/// Slack allowed when comparing modification times, for filesystems that
+/// store them at a coarse resolution.
+pub const SEAL_MTIME_TOLERANCE_SECS: i64 = 2;
+/// Files newer than the seal reported per check. More are summarised by
+/// the first few.
+pub const MAX_REPORTED_FILES: usize = 10;
+
+/// The seal file written to a provisioned drive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveSealFile {
+    pub format: String,
+    pub version: u32,
+    pub drive_id: String,
+    pub nonce_hex: String,
+    pub written_at: DateTime<Utc>,
+}
+
+/// Something that says the drive was written outside the app.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DriveAnomaly {
+    /// The filesystem now sits in a different LUKS container: the drive was
+    /// re-encrypted, or its contents copied to another drive.
+    LuksHeaderChanged {
+        expected: Option<String>,
+        found: Option<String>,
+    },
+    SealMissing,
+    /// The seal holds another nonce: the drive was rolled back to an older
+    /// copy, or written by another copy of the vault.
+    NonceMismatch,
+    /// The seal has the right nonce but was rewritten since the app wrote it.
+    SealTouched {
+        modified_at: DateTime<Utc>,
+    },
+    /// A backup file changed after the app last wrote to the drive.
+    ModifiedAfterSeal {
+        path: String,
+        modified_at: DateTime<Utc>,
+    },
+}
+
+impl DriveAnomaly {
+    pub fn describe(&self, locale: &str) -> String {
+        let or_none =
+            |v: &Option<String>| v.clone().unwrap_or_else(|| tr(locale, "anomaly-none", &[]));
+        match self {
+            DriveAnomaly::LuksHeaderChanged { expected, found } => tr(
+                locale,
+                "anomaly-luks-header-changed",
+                &[
+                    ("expected", or_none(expected).into()),
+                    ("found", or_none(found).into()),
+                ],
+            ),
+            DriveAnomaly::SealMissing => tr(locale, "anomaly-seal-missing", &[]),
+            DriveAnomaly::NonceMismatch => tr(locale, "anomaly-nonce-mismatch", &[]),
+            DriveAnomaly::SealTouched { modified_at } => tr(
+                locale,
+                "anomaly-seal-touched",
+                &[("at", modified_at.to_string().into())],
+            ),
+            DriveAnomaly::ModifiedAfterSeal { path, modified_at } => tr(
+                locale,
+                "anomaly-modified-after-seal",
+                &[
+                    ("path", path.as_str().into()),
+                    ("at", modified_at.to_string().into()),
+                ],
+            ),
+        }
+    }
+}
+
+/// Set on a provisioned drive when a check finds it was modified outside
+/// the app. Restores from it are refused until it is re-verified.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveUntrusted {
+    pub detected_at: DateTime<Utc>,
+    pub anomalies: Vec<DriveAnomaly>,
+}
+
+/// The vault's record of a drive it provisioned, persisted in `vault.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvisionedDrive {
+    /// Filesystem UUID, as in [`crate::models::drive::DriveInfo::id`].
+    pub drive_id: String,
+    pub luks_uuid: Option<String>,
+    pub label: Option<String>,
+    pub provisioned_at: DateTime<Utc>,
+    /// The nonce last written to the seal.
+    pub nonce_hex: String,
+    pub sealed_at: DateTime<Utc>,
+    /// The seal's modification time as the drive reported it after the
+    /// write, if the backend can tell.
+    pub seal_modified_at: Option<DateTime<Utc>>,
+    pub checked_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub untrusted: Option<DriveUntrusted>,
+}
+
+/// What `reverify_drive` returns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveReverification {
+    pub drive: ProvisionedDrive,
+    pub backups: Vec<crate::models::backup::BackupVerification>,
+    /// Whether every backup verified and the drive is trusted again.
+    pub trusted: bool,
+}
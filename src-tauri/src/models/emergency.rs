@@ -0,0 +1,125 @@
+use crate::crypto::encryption::Ciphertext;
+use crate::models::item::VaultItem;
+use crate::models::key::KeyEntry;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+/// A designated recovery contact, identified only by public keys: an ML-KEM-1024
+/// encapsulation key the escrow package is sealed to, and an ML-DSA-87 key used
+/// to authenticate their access requests.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyContact {
+    pub name: String,
+    pub kem_public_hex: String,
+    pub sign_public_hex: String,
+}
+
+/// Key material a recovery contact keeps on their own device. Generated once on
+/// the contact's install; only the public [`EmergencyContact`] half is handed to
+/// the vault owner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyContactIdentity {
+    pub contact: EmergencyContact,
+    pub kem_seed_hex: String,
+    pub sign_secret_hex: String,
+}
+
+impl Drop for EmergencyContactIdentity {
+    fn drop(&mut self) {
+        self.kem_seed_hex.zeroize();
+        self.sign_secret_hex.zeroize();
+    }
+}
+
+/// Vault contents sealed to the contact at grant time: the HD master seed plus
+/// a snapshot of the keystore and item store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscrowContents {
+    pub master_seed_hex: String,
+    pub keys: Vec<KeyEntry>,
+    pub items: Vec<VaultItem>,
+}
+
+impl Drop for EscrowContents {
+    fn drop(&mut self) {
+        self.master_seed_hex.zeroize();
+    }
+}
+
+/// [`EscrowContents`] sealed to the contact: an ML-KEM-1024 ciphertext whose
+/// shared secret keys an XChaCha20-Poly1305 payload. Opaque to everyone but the
+/// contact; a grant keeps it under the data key as well, so the contact cannot
+/// skip the waiting period by reading `emergency.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscrowPackage {
+    pub version: u32,
+    pub kem_ciphertext_hex: String,
+    pub payload: Ciphertext,
+}
+
+/// An access request signed by the contact over the grant id and timestamp.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessRequest {
+    pub requested_at: u64,
+    pub signature_hex: String,
+}
+
+/// An owner veto of a pending request, signed with the owner key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VetoRecord {
+    pub requested_at: u64,
+    pub vetoed_at: u64,
+    pub signature_hex: String,
+}
+
+/// An emergency-access grant. Persisted unencrypted (it holds only public keys,
+/// signatures and the package sealed under the data key) so a contact can
+/// request access while the vault is locked. The package itself is released
+/// by an unlocked vault, after the waiting period passed without a veto.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyGrant {
+    pub id: String,
+    pub contact: EmergencyContact,
+    pub waiting_period_secs: u64,
+    pub created_at: u64,
+    /// ML-DSA-87 public key of the owner, derived from the master seed.
+    pub owner_public_hex: String,
+    /// Owner signature over the grant terms and the sealed package.
+    pub grant_signature_hex: String,
+    /// The [`EscrowPackage`] as JSON, encrypted under the vault's data key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sealed_package: Option<Ciphertext>,
+    /// Where grants from before `sealed_package` kept the package, readable by
+    /// anyone with the file. Moved into `sealed_package` by the next emergency
+    /// command that runs unlocked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<EscrowPackage>,
+    pub request: Option<AccessRequest>,
+    #[serde(default)]
+    pub vetoes: Vec<VetoRecord>,
+}
+
+/// Where a grant currently is in its request / waiting / release lifecycle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum EmergencyStatus {
+    Armed,
+    Waiting {
+        requested_at: u64,
+        releasable_at: u64,
+    },
+    Releasable {
+        requested_at: u64,
+    },
+}
+
+/// Grant summary returned over IPC (without the bulky sealed package).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyGrantPublic {
+    pub id: String,
+    pub contact: EmergencyContact,
+    pub waiting_period_secs: u64,
+    pub created_at: u64,
+    pub status: EmergencyStatus,
+    pub veto_count: usize,
+}
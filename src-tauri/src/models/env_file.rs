@@ -0,0 +1,125 @@
+use crate::models::policy::ExportWatermark;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use zeroize::Zeroizing;
+
+/// Longest variable name accepted for a rendered secret.
+pub const MAX_ENV_VAR_CHARS: usize = 128;
+
+/// Layout of a file written by `render_env_file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvFileFormat {
+    /// `NAME="value"` lines, as read by dotenv loaders and `docker --env-file`.
+    Dotenv,
+    /// One flat JSON object of name to value.
+    Json,
+}
+
+/// Variable names must be portable shell identifiers: `[A-Za-z_][A-Za-z0-9_]*`.
+pub fn validate_env_var(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if !valid_start
+        || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        || name.len() > MAX_ENV_VAR_CHARS
+    {
+        return Err(format!("'{name}' is not a valid environment variable name"));
+    }
+    Ok(())
+}
+
+/// Double-quote a dotenv value, escaping what dotenv loaders would otherwise
+/// interpret: backslashes, quotes, `$` expansion and line breaks.
+fn quote_dotenv(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '$' => out.push_str("\\$"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Render `(name, value)` pairs in the given format. Names must be valid and
+/// unique. Pure so the escaping can be tested without touching disk. The
+/// watermark goes into the dotenv header; JSON has no comments to carry it.
+pub fn render_env(
+    entries: &[(&str, &str)],
+    format: EnvFileFormat,
+    watermark: Option<&ExportWatermark>,
+) -> Result<Zeroizing<String>, String> {
+    let mut seen = BTreeMap::new();
+    for (name, value) in entries {
+        validate_env_var(name)?;
+        if seen.insert(*name, *value).is_some() {
+            return Err(format!("variable {name} is set by more than one item"));
+        }
+    }
+    let rendered = match format {
+        EnvFileFormat::Dotenv => {
+            let mut out = String::from("# Written by ZAP Quantum Vault. Do not commit.\n");
+            if let Some(mark) = watermark {
+                out.push_str(&mark.comment_line());
+            }
+            for (name, value) in entries {
+                out.push_str(name);
+                out.push('=');
+                out.push_str(&quote_dotenv(value));
+                out.push('\n');
+            }
+            out
+        }
+        EnvFileFormat::Json => {
+            let mut out = serde_json::to_string_pretty(&seen).map_err(|e| e.to_string())?;
+            out.push('\n');
+            out
+        }
+    };
+    Ok(Zeroizing::new(rendered))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_var_names_are_shell_identifiers() {
+        assert!(validate_env_var("GITHUB_TOKEN").is_ok());
+        assert!(validate_env_var("_x1").is_ok());
+        assert!(validate_env_var("").is_err());
+        assert!(validate_env_var("1TOKEN").is_err());
+        assert!(validate_env_var("API-KEY").is_err());
+        assert!(validate_env_var(&"A".repeat(MAX_ENV_VAR_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn dotenv_values_are_quoted_and_escaped() {
+        let out = render_env(
+            &[("A", "plain"), ("B", "x\"$HOME\\\nend")],
+            EnvFileFormat::Dotenv,
+            None,
+        )
+        .unwrap();
+        assert!(out.contains("A=\"plain\"\n"));
+        assert!(out.contains("B=\"x\\\"\\$HOME\\\\\\nend\"\n"));
+    }
+
+    #[test]
+    fn json_round_trips_and_duplicates_are_rejected() {
+        let out = render_env(&[("A", "1\n2"), ("B", "\"")], EnvFileFormat::Json, None).unwrap();
+        let back: BTreeMap<String, String> = serde_json::from_str(&out).unwrap();
+        assert_eq!(back["A"], "1\n2");
+        assert_eq!(back["B"], "\"");
+        assert!(render_env(&[("A", "1"), ("A", "2")], EnvFileFormat::Json, None).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use crate::i18n::tr;
+use crate::models::drive::DriveInfo;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Backups older than this are left out of the growth rate, so the forecast
+/// follows how the vault is used now.
+pub const FORECAST_WINDOW_DAYS: i64 = 90;
+/// A drive forecast to fill up within this many days is flagged.
+pub const FORECAST_WARNING_DAYS: i64 = 30;
+
+/// Space one backup added to a drive: the objects it stored first, plus its
+/// manifest, in whole filesystem blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupGrowth {
+    pub backup_id: String,
+    pub created_at: DateTime<Utc>,
+    pub added_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CapacityWarning {
+    /// A backup the size of recent ones would not fit in the free space.
+    NextBackupWontFit,
+    /// At the current rate the drive fills up within
+    /// [`FORECAST_WARNING_DAYS`].
+    FillingUp { days_until_full: i64 },
+}
+
+impl CapacityWarning {
+    pub fn describe(&self, locale: &str) -> String {
+        match self {
+            CapacityWarning::NextBackupWontFit => tr(locale, "forecast-next-backup-wont-fit", &[]),
+            CapacityWarning::FillingUp { days_until_full } => tr(
+                locale,
+                "forecast-filling-up",
+                &[("days", (*days_until_full).into())],
+            ),
+        }
+    }
+}
+
+/// When a drive is expected to run out of space for backups.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveForecast {
+    pub drive_id: String,
+    pub size_bytes: u64,
+    /// `None` when the filesystem does not report free space.
+    pub available_bytes: Option<u64>,
+    /// Backups on the drive, and the space they take together.
+    pub backups: usize,
+    pub backup_bytes: u64,
+    /// Average space added per backup and per day over the window. The first
+    /// backup on a drive stores everything and is left out. `None` until
+    /// there is a later backup within the window.
+    pub bytes_per_backup: Option<u64>,
+    pub bytes_per_day: Option<u64>,
+    pub days_until_full: Option<i64>,
+    pub full_on: Option<DateTime<Utc>>,
+    pub warning: Option<CapacityWarning>,
+}
+
+/// Forecast `drive` from the growth of its backups, oldest first.
+pub fn forecast_drive(
+    drive: &DriveInfo,
+    growth: &[BackupGrowth],
+    now: DateTime<Utc>,
+) -> DriveForecast {
+    let since = now - Duration::days(FORECAST_WINDOW_DAYS);
+    // Each recent backup with the one before it, which starts its interval.
+    let recent: Vec<(&BackupGrowth, &BackupGrowth)> = growth
+        .windows(2)
+        .map(|w| (&w[0], &w[1]))
+        .filter(|(_, b)| b.created_at >= since)
+        .collect();
+    let added: u64 = recent.iter().map(|(_, b)| b.added_bytes).sum();
+    let bytes_per_backup = (!recent.is_empty()).then(|| added / recent.len() as u64);
+    let bytes_per_day = match (recent.first(), recent.last()) {
+        (Some((start, _)), Some((_, end))) if end.created_at > start.created_at => {
+            let secs = (end.created_at - start.created_at).num_seconds() as u128;
+            Some((added as u128 * 86_400 / secs) as u64)
+        }
+        _ => None,
+    };
+    let days_until_full = match (drive.available_bytes, bytes_per_day) {
+        (Some(free), Some(rate)) if rate > 0 => Some((free / rate) as i64),
+        _ => None,
+    };
+    let warning = match (drive.available_bytes, bytes_per_backup) {
+        (Some(free), Some(per_backup)) if free < per_backup => {
+            Some(CapacityWarning::NextBackupWontFit)
+        }
+        _ => days_until_full
+            .filter(|days| *days <= FORECAST_WARNING_DAYS)
+            .map(|days_until_full| CapacityWarning::FillingUp { days_until_full }),
+    };
+    DriveForecast {
+        drive_id: drive.id.clone(),
+        size_bytes: drive.size_bytes,
+        available_bytes: drive.available_bytes,
+        backups: growth.len(),
+        backup_bytes: growth.iter().map(|g| g.added_bytes).sum(),
+        bytes_per_backup,
+        bytes_per_day,
+        days_until_full,
+        full_on: days_until_full.map(|days| now + Duration::days(days)),
+        warning,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::drive::DriveStatus;
+
+    fn drive(available: Option<u64>) -> DriveInfo {
+        DriveInfo {
+            id: "usb".to_string(),
+            device: "/dev/sdb1".to_string(),
+            label: None,
+            fs_type: Some("ext4".to_string()),
+            size_bytes: 1 << 30,
+            available_bytes: available,
+            mount_point: Some("/media/usb".to_string()),
+            encrypted: false,
+            encryption: None,
+            luks_uuid: None,
+            status: DriveStatus::Ready,
+        }
+    }
+
+    fn growth(days_ago: i64, added_bytes: u64, now: DateTime<Utc>) -> BackupGrowth {
+        BackupGrowth {
+            backup_id: format!("b{days_ago}"),
+            created_at: now - Duration::days(days_ago),
+            added_bytes,
+        }
+    }
+
+    #[test]
+    fn forecasts_from_recent_growth() {
+        let now = Utc::now();
+        // The first backup stores everything; the one outside the window
+        // only starts the first interval.
+        let history = vec![
+            growth(200, 50_000_000, now),
+            growth(100, 1_000, now),
+            growth(20, 10_000, now),
+            growth(10, 10_000, now),
+        ];
+        let f = forecast_drive(&drive(Some(200_000)), &history, now);
+        assert_eq!(f.backups, 4);
+        assert_eq!(f.backup_bytes, 50_021_000);
+        assert_eq!(f.bytes_per_backup, Some(10_000));
+        assert_eq!(f.bytes_per_day, Some(20_000 / 90));
+        assert_eq!(f.days_until_full, Some(200_000 / 222));
+        assert_eq!(f.warning, None);
+
+        let f = forecast_drive(&drive(Some(4_000)), &history, now);
+        assert_eq!(f.days_until_full, Some(18));
+        assert_eq!(f.warning, Some(CapacityWarning::NextBackupWontFit));
+
+        let daily = vec![
+            growth(3, 1_000_000, now),
+            growth(2, 10_000, now),
+            growth(1, 10_000, now),
+        ];
+        let f = forecast_drive(&drive(Some(100_000)), &daily, now);
+        assert_eq!(f.bytes_per_day, Some(10_000));
+        assert_eq!(
+            f.warning,
+            Some(CapacityWarning::FillingUp {
+                days_until_full: 10
+            })
+        );
+
+        // One backup says nothing about growth.
+        let f = forecast_drive(&drive(Some(100_000)), &daily[..1], now);
+        assert_eq!((f.bytes_per_day, f.warning), (None, None));
+    }
+}
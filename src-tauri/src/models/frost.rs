@@ -0,0 +1,176 @@
+use crate::crypto::encryption::Ciphertext;
+use crate::models::mobile::BundleKdf;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+/// `format` of a [`FrostShareExport`].
+pub const FROST_SHARE_EXPORT_FORMAT: &str = "zap-frost-share";
+
+/// A participant's round-1 broadcast in the distributed key generation: its
+/// polynomial commitments, a proof that it knows the constant term, and the
+/// key round-2 shares for it are encrypted to. Points are compressed SEC1
+/// hex, scalars 32-byte big-endian hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrostDkgRound1 {
+    pub group_id: String,
+    pub threshold: u16,
+    pub participants: u16,
+    /// 1-based participant index.
+    pub index: u16,
+    pub commitments: Vec<String>,
+    pub proof_r_hex: String,
+    pub proof_z_hex: String,
+    pub encryption_key_hex: String,
+}
+
+/// `from`'s secret share for `to`, encrypted to `to`'s round-1 encryption key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrostDkgRound2 {
+    pub group_id: String,
+    pub from: u16,
+    pub to: u16,
+    pub share: Ciphertext,
+}
+
+/// A participant's key generation secrets between rounds, plus every round-1
+/// package once it has them all.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrostDkgSecret {
+    /// This participant's own round-1 broadcast.
+    pub package: FrostDkgRound1,
+    pub coefficients: Vec<String>,
+    pub encryption_secret_hex: String,
+    pub round1: Vec<FrostDkgRound1>,
+}
+
+impl Drop for FrostDkgSecret {
+    fn drop(&mut self) {
+        self.coefficients.zeroize();
+        self.encryption_secret_hex.zeroize();
+    }
+}
+
+/// Public description of a finished group: enough to coordinate signing and
+/// check the result, nothing to sign with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrostGroup {
+    pub group_id: String,
+    pub threshold: u16,
+    pub participants: u16,
+    /// Compressed group public key, before the taproot tweak.
+    pub group_public_hex: String,
+    /// Compressed public share of each participant, in index order.
+    pub verifying_shares: Vec<String>,
+    /// x-only taproot output key for a key-path-only output (BIP-86 tweak).
+    pub taproot_output_key_hex: String,
+}
+
+/// A participant's finished share of the group key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrostKeyShare {
+    pub group: FrostGroup,
+    pub index: u16,
+    pub secret_share_hex: String,
+}
+
+impl Drop for FrostKeyShare {
+    fn drop(&mut self) {
+        self.secret_share_hex.zeroize();
+    }
+}
+
+/// A participant's public nonce commitment for one signing session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrostCommitment {
+    pub index: u16,
+    pub hiding_hex: String,
+    pub binding_hex: String,
+}
+
+/// The secret nonces behind a [`FrostCommitment`]. Used at most once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrostNonces {
+    pub commitment: FrostCommitment,
+    pub hiding_secret_hex: String,
+    pub binding_secret_hex: String,
+}
+
+impl Drop for FrostNonces {
+    fn drop(&mut self) {
+        self.hiding_secret_hex.zeroize();
+        self.binding_secret_hex.zeroize();
+    }
+}
+
+/// What the coordinator sends every chosen signer: the message and one
+/// commitment per signer. `message_hex` is the 32-byte BIP-341 sighash for a
+/// taproot spend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrostSigningPackage {
+    pub group_id: String,
+    pub message_hex: String,
+    pub commitments: Vec<FrostCommitment>,
+    /// Script tree root the output key commits to; `None` for a key-path-only
+    /// output.
+    #[serde(default)]
+    pub merkle_root_hex: Option<String>,
+}
+
+/// One signer's partial signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrostSignatureShare {
+    pub index: u16,
+    pub z_hex: String,
+}
+
+/// The aggregated BIP-340 signature, valid for `output_key_hex`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrostSignature {
+    pub group_id: String,
+    pub message_hex: String,
+    /// x-only taproot output key the signature verifies under.
+    pub output_key_hex: String,
+    pub signature_hex: String,
+}
+
+/// A finished key share sealed under a password, for moving it to another
+/// profile or installation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrostShareExport {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub group_id: String,
+    pub index: u16,
+    pub salt_hex: String,
+    pub kdf: BundleKdf,
+    /// XChaCha20-Poly1305 encrypted [`FrostKeyShare`].
+    pub payload: Ciphertext,
+}
+
+/// Everything FROST this vault holds, encrypted under the session key:
+/// unfinished key generations, finished shares, and unused signing nonces.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FrostStore {
+    #[serde(default)]
+    pub pending: Vec<FrostDkgSecret>,
+    #[serde(default)]
+    pub shares: Vec<FrostKeyShare>,
+    #[serde(default)]
+    pub nonces: Vec<FrostPendingNonces>,
+}
+
+/// Nonces committed for a group, waiting for its signing package.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrostPendingNonces {
+    pub group_id: String,
+    pub nonces: FrostNonces,
+}
+
+/// A finished share as listed to the UI, secret left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrostShareInfo {
+    pub group: FrostGroup,
+    pub index: u16,
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthEntryKind {
+    Key,
+    Item,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthProblem {
+    /// The stored secret does not decode as a key of its type.
+    UndecodableSecret,
+    /// The secret decodes but yields a different public key than recorded.
+    PublicKeyMismatch,
+    /// The recorded address is not the address of the recorded public key.
+    AddressMismatch,
+    /// Re-deriving the key from the master seed at its recorded path gives a
+    /// different key, so a mnemonic restore would not bring it back.
+    DerivationMismatch,
+}
+
+/// One corrupt or inconsistent record. Never carries secret material.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthIssue {
+    pub kind: HealthEntryKind,
+    pub id: String,
+    pub label: Option<String>,
+    pub problem: HealthProblem,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultHealthReport {
+    pub keys_checked: usize,
+    pub items_checked: usize,
+    /// Stores that could not be decrypted from disk; the in-memory copy was
+    /// checked instead.
+    pub unreadable_stores: Vec<String>,
+    pub issues: Vec<HealthIssue>,
+}
+
+impl VaultHealthReport {
+    pub fn is_healthy(&self) -> bool {
+        self.unreadable_stores.is_empty() && self.issues.is_empty()
+    }
+}
@@ -0,0 +1,232 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// `format` of a [`HookDelivery`].
+pub const HOOK_EVENT_FORMAT: &str = "zap-hook-event";
+pub const HOOK_EVENT_VERSION: u32 = 1;
+/// Hooks a vault can hold.
+pub const MAX_HOOKS: usize = 32;
+pub const MAX_HOOK_NAME_CHARS: usize = 64;
+/// A script or webhook still running after this long is stopped.
+pub const HOOK_TIMEOUT_SECS: u64 = 30;
+
+/// Vault events a hook can run on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    BackupCompleted,
+    KeyCreated,
+    /// An item passed its rotation deadline.
+    ItemExpired,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::BackupCompleted => "backup_completed",
+            HookEvent::KeyCreated => "key_created",
+            HookEvent::ItemExpired => "item_expired",
+        }
+    }
+}
+
+/// What a hook does when its event happens.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HookAction {
+    /// Run a local program with the event as JSON on stdin.
+    Script { path: String },
+    /// Write the event as a JSON file into a local directory.
+    EventFile { dir: String },
+    /// POST the event as JSON to an HTTPS endpoint. Off in air-gap mode.
+    Webhook { url: String },
+}
+
+impl HookAction {
+    /// Whether running it sends data off the machine.
+    pub fn is_networked(&self) -> bool {
+        matches!(self, HookAction::Webhook { .. })
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            HookAction::Script { path } if !std::path::Path::new(path).is_absolute() => {
+                Err("hook script path must be absolute".to_string())
+            }
+            HookAction::EventFile { dir } if !std::path::Path::new(dir).is_absolute() => {
+                Err("hook event directory must be absolute".to_string())
+            }
+            HookAction::Webhook { url }
+                if !url.starts_with("https://")
+                    || url.len() <= 8
+                    || url.chars().any(|c| c.is_whitespace() || c.is_control()) =>
+            {
+                Err("webhook must be an https:// URL".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hook {
+    pub id: String,
+    pub name: String,
+    pub events: Vec<HookEvent>,
+    pub action: HookAction,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Hook {
+    pub fn validate(&self) -> Result<(), String> {
+        let name = self.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_HOOK_NAME_CHARS {
+            return Err(format!(
+                "hook name must be 1 to {MAX_HOOK_NAME_CHARS} characters"
+            ));
+        }
+        if self.events.is_empty() {
+            return Err("a hook needs at least one event".to_string());
+        }
+        self.action.validate()
+    }
+
+    /// Whether the hook runs on `event`. Webhooks never run in air-gap mode.
+    pub fn runs_on(&self, event: HookEvent, air_gap_mode: bool) -> bool {
+        self.enabled
+            && self.events.contains(&event)
+            && !(air_gap_mode && self.action.is_networked())
+    }
+}
+
+/// Hooks and the state of their background checks, persisted in the sealed
+/// `hooks.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookSettings {
+    #[serde(default)]
+    pub hooks: Vec<Hook>,
+    /// Items that expired up to this time have had `item_expired` run.
+    /// `None` until the first check, which covers every expired item.
+    #[serde(default)]
+    pub item_expiry_checked_at: Option<DateTime<Utc>>,
+}
+
+impl HookSettings {
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty() && self.item_expiry_checked_at.is_none()
+    }
+
+    pub fn any_runs_on(&self, event: HookEvent, air_gap_mode: bool) -> bool {
+        self.hooks.iter().any(|h| h.runs_on(event, air_gap_mode))
+    }
+}
+
+/// What a hook receives. Ids and counts only: no key material, addresses or
+/// item contents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookDelivery {
+    pub format: String,
+    pub version: u32,
+    /// Unique per event, so a receiver can drop repeats.
+    pub id: String,
+    pub event: HookEvent,
+    pub occurred_at: DateTime<Utc>,
+    /// The backup, key or item the event is about.
+    pub subject_id: String,
+    pub summary: String,
+}
+
+impl HookDelivery {
+    pub fn new(event: HookEvent, subject_id: &str, summary: &str, now: DateTime<Utc>) -> Self {
+        HookDelivery {
+            format: HOOK_EVENT_FORMAT.to_string(),
+            version: HOOK_EVENT_VERSION,
+            id: uuid::Uuid::new_v4().to_string(),
+            event,
+            occurred_at: now,
+            subject_id: subject_id.to_string(),
+            summary: summary.to_string(),
+        }
+    }
+
+    /// Name of the file an `event_file` hook writes, sortable by time.
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}-{}-{}.json",
+            self.occurred_at.format("%Y%m%dT%H%M%S%.3fZ"),
+            self.event.as_str(),
+            &self.id[..8]
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(action: HookAction) -> Hook {
+        Hook {
+            id: "h".to_string(),
+            name: "ops".to_string(),
+            events: vec![HookEvent::BackupCompleted],
+            action,
+            enabled: true,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn validates_actions_and_respects_air_gap() {
+        let script = hook(HookAction::Script {
+            path: "/usr/local/bin/on-backup".to_string(),
+        });
+        assert!(script.validate().is_ok());
+        assert!(hook(HookAction::Script {
+            path: "on-backup".to_string()
+        })
+        .validate()
+        .is_err());
+        assert!(hook(HookAction::EventFile {
+            dir: "events".to_string()
+        })
+        .validate()
+        .is_err());
+        for url in ["http://hooks.example.com", "https://", "https://a b"] {
+            assert!(hook(HookAction::Webhook {
+                url: url.to_string()
+            })
+            .validate()
+            .is_err());
+        }
+
+        let webhook = hook(HookAction::Webhook {
+            url: "https://hooks.example.com/vault".to_string(),
+        });
+        assert!(webhook.validate().is_ok());
+        assert!(webhook.runs_on(HookEvent::BackupCompleted, false));
+        assert!(!webhook.runs_on(HookEvent::BackupCompleted, true));
+        assert!(script.runs_on(HookEvent::BackupCompleted, true));
+        assert!(!script.runs_on(HookEvent::KeyCreated, false));
+        let disabled = Hook {
+            enabled: false,
+            ..script
+        };
+        assert!(!disabled.runs_on(HookEvent::BackupCompleted, false));
+    }
+
+    #[test]
+    fn delivery_file_names_sort_by_time() {
+        let now = Utc::now();
+        let a = HookDelivery::new(HookEvent::KeyCreated, "k1", "key created", now);
+        let b = HookDelivery::new(
+            HookEvent::ItemExpired,
+            "i1",
+            "item expired",
+            now + chrono::Duration::milliseconds(5),
+        );
+        assert!(a.file_name() < b.file_name());
+        assert!(a.file_name().contains("-key_created-"));
+        assert!(a.file_name().ends_with(".json"));
+    }
+}
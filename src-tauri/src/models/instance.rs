@@ -0,0 +1,71 @@
+use crate::crypto::encryption::Ciphertext;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Public half of this installation's signing identity. Unlike the vault
+/// identity, which every installation of a vault derives from the master
+/// seed, it is random per installation, so signatures tell installations of
+/// the same vault apart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceIdentity {
+    /// The installation's sync device id.
+    pub instance_id: String,
+    pub mldsa_public_hex: String,
+    pub ed25519_public_hex: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl InstanceIdentity {
+    /// Short BLAKE3 digest of both public keys, for pinning and display.
+    pub fn key_fingerprint(&self) -> String {
+        let mut h = blake3::Hasher::new();
+        h.update(self.mldsa_public_hex.as_bytes());
+        h.update(b":");
+        h.update(self.ed25519_public_hex.as_bytes());
+        hex::encode(&h.finalize().as_bytes()[..16])
+    }
+}
+
+/// `instance_identity.json`: the public identity plus the ML-DSA seed, wrapped
+/// under a key derived from the master seed. The Ed25519 key is derived from
+/// the same seed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceIdentityFile {
+    pub identity: InstanceIdentity,
+    pub wrapped_secret: Ciphertext,
+}
+
+/// A hybrid ML-DSA-87 + Ed25519 signature by an installation. Both halves
+/// must verify.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceSignature {
+    pub instance_id: String,
+    /// What was signed (`backup`, `sync_package`, `key_attestation`,
+    /// `public_bundle`); part of the signed message, so a signature cannot
+    /// be moved between kinds.
+    pub purpose: String,
+    pub mldsa_public_hex: String,
+    pub ed25519_public_hex: String,
+    pub mldsa_signature_hex: String,
+    pub ed25519_signature_hex: String,
+}
+
+impl InstanceSignature {
+    /// Whether this signature was made by `identity`'s keys.
+    pub fn is_from(&self, identity: &InstanceIdentity) -> bool {
+        self.instance_id == identity.instance_id
+            && self.mldsa_public_hex == identity.mldsa_public_hex
+            && self.ed25519_public_hex == identity.ed25519_public_hex
+    }
+
+    /// The [`InstanceIdentity::key_fingerprint`] of the signing keys.
+    pub fn key_fingerprint(&self) -> String {
+        InstanceIdentity {
+            instance_id: self.instance_id.clone(),
+            mldsa_public_hex: self.mldsa_public_hex.clone(),
+            ed25519_public_hex: self.ed25519_public_hex.clone(),
+            created_at: DateTime::<Utc>::UNIX_EPOCH,
+        }
+        .key_fingerprint()
+    }
+}
@@ -0,0 +1,280 @@
+use crate::models::pairing::PairingStore;
+use crate::models::portfolio::BalanceStore;
+use crate::models::spending::SpendingStore;
+use crate::models::treasury::TreasuryState;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// The kind of row left pointing at a key or item that no longer exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanKind {
+    /// A recorded balance of a key.
+    Balance,
+    /// A spending policy of a key.
+    SpendingPolicy,
+    /// A spend approval for a key. Marked, not removed.
+    SpendApproval,
+    /// A spend counted against a key's daily limit.
+    SpendRecord,
+    /// A browser site approved to be filled from an item.
+    SiteApproval,
+    /// A signer of a treasury policy. Marked, not removed.
+    TreasurySigner,
+}
+
+/// One orphaned row.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Orphan {
+    pub kind: OrphanKind,
+    /// The key or item the row points at.
+    pub missing_id: String,
+    /// What identifies the row itself, such as the asset of a balance or the
+    /// origin of a site approval.
+    pub detail: String,
+}
+
+/// Returned by `repair_orphans`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrphanReport {
+    pub orphans: Vec<Orphan>,
+    /// Whether the orphans were removed (or, for the kinds kept as records,
+    /// marked), or only reported.
+    pub fixed: bool,
+}
+
+/// The keys and items rows may point at: everything in the stores,
+/// trashed entries included, entries a purge removed that can still be
+/// undone, and items sealed in time capsules.
+#[derive(Debug, Clone, Default)]
+pub struct Referents {
+    pub keys: HashSet<String>,
+    pub items: HashSet<String>,
+}
+
+/// Remove the `rows` whose `id` is not in `known`, reporting each as a
+/// `kind` orphan described by `detail`.
+fn prune<T>(
+    rows: &mut Vec<T>,
+    known: &HashSet<String>,
+    kind: OrphanKind,
+    id: impl Fn(&T) -> &str,
+    detail: impl Fn(&T) -> String,
+) -> Vec<Orphan> {
+    let mut orphans = Vec::new();
+    rows.retain(|row| {
+        if known.contains(id(row)) {
+            return true;
+        }
+        orphans.push(Orphan {
+            kind,
+            missing_id: id(row).to_string(),
+            detail: detail(row),
+        });
+        false
+    });
+    orphans
+}
+
+/// Remove balances of keys that no longer exist.
+pub fn prune_balances(store: &mut BalanceStore, known: &Referents) -> Vec<Orphan> {
+    prune(
+        &mut store.balances,
+        &known.keys,
+        OrphanKind::Balance,
+        |b| &b.key_id,
+        |b| b.asset.clone(),
+    )
+}
+
+/// Remove spending policies and spends of keys that no longer exist, and
+/// mark their approvals as orphaned at `now`. Approvals are the record of
+/// co-approvals, so they are kept; ones already marked are not reported
+/// again.
+pub fn prune_spending(
+    store: &mut SpendingStore,
+    known: &Referents,
+    now: DateTime<Utc>,
+) -> Vec<Orphan> {
+    let mut orphans = prune(
+        &mut store.policies,
+        &known.keys,
+        OrphanKind::SpendingPolicy,
+        |p| &p.key_id,
+        |p| p.asset.clone().unwrap_or_default(),
+    );
+    for approval in store
+        .approvals
+        .iter_mut()
+        .filter(|a| a.orphaned_at.is_none() && !known.keys.contains(&a.key_id))
+    {
+        approval.orphaned_at = Some(now);
+        orphans.push(Orphan {
+            kind: OrphanKind::SpendApproval,
+            missing_id: approval.key_id.clone(),
+            detail: approval.id.clone(),
+        });
+    }
+    orphans.extend(prune(
+        &mut store.history,
+        &known.keys,
+        OrphanKind::SpendRecord,
+        |r| &r.key_id,
+        |r| r.signed_at.to_rfc3339(),
+    ));
+    orphans
+}
+
+/// Mark treasury policy signers whose keys no longer exist. The policy
+/// terms every share signs name them, so they stay in `signers`; ones
+/// already marked are not reported again.
+pub fn prune_treasury(state: &mut TreasuryState, known: &Referents) -> Vec<Orphan> {
+    let mut orphans = Vec::new();
+    for policy in &mut state.policies {
+        let gone: Vec<String> = policy
+            .signers
+            .iter()
+            .map(|s| s.key_id.clone())
+            .filter(|id| !known.keys.contains(id) && !policy.orphaned_signers.contains(id))
+            .collect();
+        for id in gone {
+            orphans.push(Orphan {
+                kind: OrphanKind::TreasurySigner,
+                missing_id: id.clone(),
+                detail: policy.name.clone(),
+            });
+            policy.orphaned_signers.push(id);
+        }
+    }
+    orphans
+}
+
+/// Remove site approvals for items that no longer exist.
+pub fn prune_sites(store: &mut PairingStore, known: &Referents) -> Vec<Orphan> {
+    prune(
+        &mut store.sites,
+        &known.items,
+        OrphanKind::SiteApproval,
+        |s| &s.item_id,
+        |s| s.origin.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::pairing::SiteApproval;
+    use crate::models::portfolio::RecordedBalance;
+    use crate::models::spending::SpendApproval;
+    use crate::models::treasury::{TreasuryPolicy, TreasurySigner};
+
+    fn balance(key_id: &str) -> RecordedBalance {
+        RecordedBalance {
+            key_id: key_id.to_string(),
+            asset: "BTC".to_string(),
+            amount: "1".to_string(),
+            recorded_at: Utc::now(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn rows_of_missing_keys_and_items_are_pruned() {
+        let known = Referents {
+            keys: HashSet::from(["k1".to_string()]),
+            items: HashSet::from(["i1".to_string()]),
+        };
+        let mut balances = BalanceStore {
+            balances: vec![balance("k1"), balance("gone")],
+        };
+        let orphans = prune_balances(&mut balances, &known);
+        assert_eq!(
+            orphans,
+            vec![Orphan {
+                kind: OrphanKind::Balance,
+                missing_id: "gone".to_string(),
+                detail: "BTC".to_string(),
+            }]
+        );
+        assert_eq!(balances.balances.len(), 1);
+        assert_eq!(balances.balances[0].key_id, "k1");
+
+        let site = |item_id: &str| SiteApproval {
+            origin: "https://example.com".to_string(),
+            item_id: item_id.to_string(),
+            approved_at: Utc::now(),
+        };
+        let mut pairing = PairingStore {
+            sites: vec![site("i1"), site("i2")],
+            ..Default::default()
+        };
+        let orphans = prune_sites(&mut pairing, &known);
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(orphans[0].missing_id, "i2");
+        assert_eq!(pairing.sites.len(), 1);
+        assert!(prune_spending(&mut SpendingStore::default(), &known, Utc::now()).is_empty());
+    }
+
+    #[test]
+    fn approvals_and_treasury_signers_are_marked_not_removed() {
+        let known = Referents {
+            keys: HashSet::from(["k1".to_string()]),
+            items: HashSet::new(),
+        };
+        let approval = |key_id: &str| SpendApproval {
+            id: format!("a-{key_id}"),
+            key_id: key_id.to_string(),
+            summary_hash_hex: "00".to_string(),
+            amount: None,
+            fiat_value: None,
+            destinations: Vec::new(),
+            reasons: Vec::new(),
+            requested_at: Utc::now(),
+            approved_by: Some("approver".to_string()),
+            approved_at: Some(Utc::now()),
+            used_at: None,
+            orphaned_at: None,
+        };
+        let mut spending = SpendingStore {
+            approvals: vec![approval("k1"), approval("gone")],
+            ..Default::default()
+        };
+        let now = Utc::now();
+        let orphans = prune_spending(&mut spending, &known, now);
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(orphans[0].kind, OrphanKind::SpendApproval);
+        assert_eq!(spending.approvals.len(), 2);
+        assert_eq!(spending.approvals[0].orphaned_at, None);
+        assert_eq!(spending.approvals[1].orphaned_at, Some(now));
+        assert!(prune_spending(&mut spending, &known, now).is_empty());
+
+        let signer = |key_id: &str| TreasurySigner {
+            key_id: key_id.to_string(),
+            public_key_hex: "00".to_string(),
+        };
+        let mut treasury = TreasuryState {
+            policies: vec![TreasuryPolicy {
+                id: "p1".to_string(),
+                name: "Treasury".to_string(),
+                threshold: 1,
+                signers: vec![signer("k1"), signer("gone")],
+                created_at: Utc::now(),
+                orphaned_signers: Vec::new(),
+            }],
+            proposals: Vec::new(),
+        };
+        let orphans = prune_treasury(&mut treasury, &known);
+        assert_eq!(
+            orphans,
+            vec![Orphan {
+                kind: OrphanKind::TreasurySigner,
+                missing_id: "gone".to_string(),
+                detail: "Treasury".to_string(),
+            }]
+        );
+        assert_eq!(treasury.policies[0].signers.len(), 2);
+        assert_eq!(treasury.policies[0].orphaned_signers, vec!["gone"]);
+        assert!(prune_treasury(&mut treasury, &known).is_empty());
+    }
+}
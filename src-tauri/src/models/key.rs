@@ -1,8 +1,12 @@
+use crate::crypto::fingerprint::KeyFingerprint;
+use crate::models::address::AddressStats;
+use crate::models::backup::BackupRecord;
+use crate::models::usage::UsageStats;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use zeroize::Zeroize;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum KeyType {
     Genesis,
     Validator,
@@ -14,6 +18,36 @@ pub enum KeyType {
     Custom,
 }
 
+impl KeyType {
+    /// Parse the frontend's key type name; unknown names map to `Custom`.
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "genesis" => KeyType::Genesis,
+            "validator" => KeyType::Validator,
+            "governance" => KeyType::Governance,
+            "treasury" => KeyType::Treasury,
+            "security" => KeyType::SecurityAdmin,
+            "user" => KeyType::User,
+            "quantum" => KeyType::QuantumSafe,
+            _ => KeyType::Custom,
+        }
+    }
+
+    /// The frontend name of this key type; the inverse of [`KeyType::parse`].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyType::Genesis => "genesis",
+            KeyType::Validator => "validator",
+            KeyType::Governance => "governance",
+            KeyType::Treasury => "treasury",
+            KeyType::SecurityAdmin => "security",
+            KeyType::User => "user",
+            KeyType::QuantumSafe => "quantum",
+            KeyType::Custom => "custom",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyMetadata {
     pub key_type: KeyType,
@@ -23,10 +57,42 @@ pub struct KeyMetadata {
     pub address: String,
     pub created_at: DateTime<Utc>,
     pub label: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
     /// The HD derivation path this key was deterministically derived from
     /// (e.g. `m/44'/9999'/0'/0'/0'`). Empty for non-HD/legacy keys.
     #[serde(default)]
     pub derivation_path: String,
+    /// Whether this key's address has received funds / been handed out. Drives
+    /// the HD gap limit.
+    #[serde(default)]
+    pub used: bool,
+    #[serde(default)]
+    pub used_at: Option<DateTime<Utc>>,
+    /// Favorite flag and usage counters for quick access.
+    #[serde(default)]
+    pub usage: UsageStats,
+    /// The keyset this key was generated as part of, if any.
+    #[serde(default)]
+    pub keyset_id: Option<String>,
+    /// Set for keys derived from a genesis ceremony's combined entropy rather
+    /// than the vault master seed. Such keys sit in a separate HD tree: they
+    /// are restored from the ceremony mnemonic and ignored by the vault's
+    /// path de-duplication and gap-limit window.
+    #[serde(default)]
+    pub ceremony_id: Option<String>,
+    /// Set for keys received in a key escrow package, to the package id.
+    /// Such keys come from the sender's HD tree: they are not restored by
+    /// this vault's mnemonic and are ignored by its path de-duplication and
+    /// gap-limit window.
+    #[serde(default)]
+    pub escrow_id: Option<String>,
+    /// When the key was moved to the trash. Trashed keys cannot sign and are
+    /// hidden from listings until restored or purged.
+    #[serde(default)]
+    pub trashed_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,6 +122,35 @@ pub struct KeyEntryPublic {
     pub id: String,
     pub metadata: KeyMetadata,
     pub public_key_hex: String,
+    /// Computed from `public_key_hex` on every projection, never stored.
+    pub fingerprint: KeyFingerprint,
+}
+
+/// How a key could be restored if this device were lost.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupStatus {
+    /// HD-derived keys are re-derivable from the recovery mnemonic; legacy
+    /// random keys are not and only survive in the encrypted keystore.
+    pub mnemonic_recoverable: bool,
+    /// Whether a gap-limited restore scan of the key's account reaches it.
+    pub within_scan_window: bool,
+    /// The most recent backup this vault wrote since the key was created.
+    /// Every backup carries the whole keystore, so this one holds the key.
+    /// `None` when there is no such backup in the vault's backup history.
+    #[serde(default)]
+    pub last_backup: Option<BackupRecord>,
+}
+
+/// Everything the key detail view needs in one call. The public key fields are
+/// flattened so existing consumers of `KeyEntryPublic` keep working. Accesses
+/// are summarised by `metadata.usage` (use count and last use); no per-access
+/// log is kept, since the audit log only goes to the `audit` tracing target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyDetails {
+    #[serde(flatten)]
+    pub key: KeyEntryPublic,
+    pub address_stats: AddressStats,
+    pub backup: BackupStatus,
 }
 
 impl KeyEntry {
@@ -65,6 +160,7 @@ impl KeyEntry {
             id: self.id.clone(),
             metadata: self.metadata.clone(),
             public_key_hex: self.public_key_hex.clone(),
+            fingerprint: KeyFingerprint::of_hex(&self.public_key_hex),
         }
     }
 
@@ -89,7 +185,16 @@ impl KeyEntry {
                 address: address.to_string(),
                 created_at: Utc::now(),
                 label: None,
+                description: None,
+                tags: Vec::new(),
+                used: false,
+                used_at: None,
                 derivation_path: derivation_path.to_string(),
+                usage: UsageStats::default(),
+                keyset_id: None,
+                ceremony_id: None,
+                escrow_id: None,
+                trashed_at: None,
             },
             public_key_hex: public_key_hex.to_string(),
             encrypted_secret_hex: encrypted_secret_hex.to_string(),
@@ -128,6 +233,7 @@ mod tests {
         let public = e.to_public();
         assert_eq!(public.id, e.id);
         assert_eq!(public.public_key_hex, e.public_key_hex);
+        assert_eq!(public.fingerprint, KeyFingerprint::of(&[0xaa, 0xbb, 0xcc]));
         // The public projection has no field that can carry the secret.
         let json = serde_json::to_string(&public).unwrap();
         assert!(!json.contains(&e.encrypted_secret_hex));
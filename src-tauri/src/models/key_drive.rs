@@ -0,0 +1,79 @@
+use crate::crypto::encryption::Ciphertext;
+use crate::models::drive::{DriveInfo, DriveStatus};
+use crate::models::mobile::BundleKdf;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Directory on the key drive holding unlock files.
+pub const KEY_DRIVE_DIR: &str = "zap-key-drive";
+/// `format` of a [`KeyDriveFile`].
+pub const KEY_DRIVE_FORMAT: &str = "zap-key-drive";
+pub const KEY_DRIVE_VERSION: u32 = 1;
+pub const MIN_KEY_DRIVE_PIN_CHARS: usize = 4;
+/// Wrong PINs in a row after which key-drive unlock is switched off and the
+/// vault needs its password again.
+pub const MAX_KEY_DRIVE_PIN_FAILURES: u32 = 5;
+
+/// The local half of a key-drive enrollment, persisted in `vault.json`. The
+/// unlock secret is wrapped under a key derived from the PIN and
+/// `binding_hex`, and only the drive holds the wrapped copy, so the drive
+/// alone, or this file alone, unlocks nothing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyDriveConfig {
+    pub id: String,
+    pub drive_id: String,
+    pub label: Option<String>,
+    pub salt_hex: String,
+    pub kdf: BundleKdf,
+    /// Random, mixed into the wrapping key alongside the PIN.
+    pub binding_hex: String,
+    pub enrolled_at: DateTime<Utc>,
+    /// Wrong PINs since the last key-drive unlock.
+    #[serde(default)]
+    pub failures: u32,
+}
+
+impl KeyDriveConfig {
+    /// Where the unlock file lives on the drive.
+    pub fn file_path(&self) -> String {
+        format!("{KEY_DRIVE_DIR}/{}.json", self.id)
+    }
+}
+
+/// The file written to the key drive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyDriveFile {
+    pub format: String,
+    pub version: u32,
+    /// Matches [`KeyDriveConfig::id`].
+    pub id: String,
+    /// The vault encryption key, wrapped.
+    pub wrapped: Ciphertext,
+}
+
+/// What `key_drive_status` returns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyDriveStatus {
+    pub enrolled: bool,
+    pub drive_id: Option<String>,
+    pub label: Option<String>,
+    /// Whether the drive is mounted, so a PIN unlock can be tried.
+    pub present: bool,
+    /// Wrong PINs left before key-drive unlock is switched off.
+    pub attempts_left: Option<u32>,
+    /// Whether the current session was opened with the key drive, and so
+    /// locks when it is removed.
+    pub active: bool,
+}
+
+/// A key drive must be an unlocked encrypted container. It need not be
+/// writable once enrolled.
+pub fn check_key_drive(drive: &DriveInfo) -> Result<(), String> {
+    if !drive.encrypted {
+        return Err(format!("drive {} is not encrypted", drive.id));
+    }
+    match (&drive.mount_point, drive.status) {
+        (Some(_), DriveStatus::Ready | DriveStatus::ReadOnly) => Ok(()),
+        _ => Err(format!("drive {} must be unlocked and mounted", drive.id)),
+    }
+}
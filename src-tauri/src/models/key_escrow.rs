@@ -0,0 +1,69 @@
+use crate::crypto::encryption::Ciphertext;
+use crate::crypto::fingerprint::KeyFingerprint;
+use crate::models::contact::ContactCard;
+use crate::models::key::{KeyEntryPublic, KeyType};
+use crate::models::policy::ExportWatermark;
+use serde::{Deserialize, Serialize};
+
+/// Longest conditions text a package may carry.
+pub const MAX_CONDITIONS_CHARS: usize = 2000;
+
+/// The terms a key was escrowed under, signed by the sender. The conditions
+/// are for the recipient to honour; the vault cannot enforce them once the
+/// package is opened.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowPolicy {
+    pub version: u32,
+    pub package_id: String,
+    pub key_id: String,
+    pub key_type: KeyType,
+    pub address: String,
+    pub public_key_hex: String,
+    pub conditions: String,
+    /// Hex fingerprints of the sender's and recipient's contact cards.
+    pub sender_fingerprint: String,
+    pub recipient_fingerprint: String,
+    /// Unix seconds.
+    pub created_at: u64,
+}
+
+/// One key sealed to a contact. The payload key is derived from both an
+/// X25519 exchange and an ML-KEM-1024 encapsulation against the recipient's
+/// card, so it stays secret unless both are broken. The sender's vault
+/// identity signs the policy document together with the sealed payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyEscrowPackage {
+    pub version: u32,
+    pub sender: ContactCard,
+    /// The exact [`EscrowPolicy`] JSON that was signed.
+    pub policy_document: String,
+    pub x25519_ephemeral_hex: String,
+    pub kem_ciphertext_hex: String,
+    pub payload: Ciphertext,
+    pub signature_hex: String,
+    /// Where the package was made. Not covered by the signature; set by
+    /// `create_escrow_package` after sealing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watermark: Option<ExportWatermark>,
+}
+
+/// What opening a package added to the keystore, and who sent it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenedKeyEscrow {
+    pub policy: EscrowPolicy,
+    pub sender_name: String,
+    pub sender_fingerprint: KeyFingerprint,
+    /// The imported contact holding the sender's card, if there is one.
+    pub sender_contact_id: Option<String>,
+    pub sender_verified: bool,
+    pub key: KeyEntryPublic,
+}
+
+pub fn validate_conditions(conditions: &str) -> Result<(), String> {
+    if conditions.chars().count() > MAX_CONDITIONS_CHARS {
+        return Err(format!(
+            "conditions must be at most {MAX_CONDITIONS_CHARS} characters"
+        ));
+    }
+    Ok(())
+}
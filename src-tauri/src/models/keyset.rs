@@ -0,0 +1,243 @@
+use crate::crypto::hd_derivation;
+use crate::models::address::{account_keys, max_derivable_index};
+use crate::models::key::{KeyEntry, KeyType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on the number of keys one keyset may generate.
+pub const MAX_KEYSET_SIZE: u32 = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeysetStatus {
+    /// Recorded before any key is stored; a crash leaves the keyset here so a
+    /// retry with the same idempotency key resumes it.
+    Pending,
+    Complete,
+}
+
+/// `count` keys of one type under one HD purpose.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeysetMemberSpec {
+    pub key_type: String,
+    pub purpose: u32,
+    pub count: u32,
+}
+
+/// A request to generate a set of keys as one unit. Retrying with the same
+/// `idempotency_key` returns (or finishes) the keyset it created.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeysetRequest {
+    pub idempotency_key: String,
+    pub name: String,
+    pub account: u32,
+    pub members: Vec<KeysetMemberSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedKey {
+    pub key_type: KeyType,
+    pub purpose: u32,
+    pub account: u32,
+    pub index: u32,
+    pub derivation_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyset {
+    pub id: String,
+    pub idempotency_key: String,
+    pub name: String,
+    pub account: u32,
+    pub members: Vec<KeysetMemberSpec>,
+    pub planned: Vec<PlannedKey>,
+    pub status: KeysetStatus,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// The genesis ceremony whose combined entropy seeds this keyset; `None`
+    /// for keysets derived from the vault master seed.
+    #[serde(default)]
+    pub ceremony_id: Option<String>,
+    /// Tags given to every key of the keyset when it is generated.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The template this keyset was provisioned from, if any.
+    #[serde(default)]
+    pub template_id: Option<String>,
+}
+
+impl Keyset {
+    /// Whether `request` asks for the same keyset this one was created from.
+    pub fn matches(&self, request: &KeysetRequest) -> bool {
+        self.name == request.name
+            && self.account == request.account
+            && self.members == request.members
+    }
+}
+
+/// Plan a new keyset: assign each member the next free indices in its
+/// `purpose` / `account` after the keys already in `keys`, within the address
+/// gap limit. Indices are fixed up front so a resumed keyset derives exactly
+/// the same paths.
+pub fn plan_keyset(
+    keys: &[KeyEntry],
+    request: &KeysetRequest,
+    gap_limit: u32,
+    now: DateTime<Utc>,
+) -> Result<Keyset, String> {
+    if request.idempotency_key.trim().is_empty() {
+        return Err("idempotency key must not be empty".to_string());
+    }
+    if request.members.is_empty() {
+        return Err("a keyset needs at least one member".to_string());
+    }
+    let total = request
+        .members
+        .iter()
+        .try_fold(0u32, |acc, m| acc.checked_add(m.count))
+        .filter(|t| *t <= MAX_KEYSET_SIZE)
+        .ok_or_else(|| format!("a keyset may contain at most {MAX_KEYSET_SIZE} keys"))?;
+    if total == 0 {
+        return Err("a keyset needs at least one key".to_string());
+    }
+
+    let account = request.account;
+    let mut planned: Vec<PlannedKey> = Vec::with_capacity(total as usize);
+    for member in &request.members {
+        let key_type = KeyType::parse(&member.key_type);
+        for _ in 0..member.count {
+            let index = account_keys(keys, member.purpose, account)
+                .map(|k| k.metadata.index)
+                .chain(
+                    planned
+                        .iter()
+                        .filter(|p| p.purpose == member.purpose)
+                        .map(|p| p.index),
+                )
+                .max()
+                .map_or(0, |i| i + 1);
+            let max_index = max_derivable_index(keys, member.purpose, account, gap_limit);
+            if index > max_index {
+                return Err(format!(
+                    "purpose {} account {account} has no room for index {index} within the \
+                     address gap limit ({gap_limit})",
+                    member.purpose
+                ));
+            }
+            planned.push(PlannedKey {
+                key_type: key_type.clone(),
+                purpose: member.purpose,
+                account,
+                index,
+                derivation_path: hd_derivation::zap_path(member.purpose, account, index)
+                    .to_string(),
+            });
+        }
+    }
+
+    Ok(Keyset {
+        id: uuid::Uuid::new_v4().to_string(),
+        idempotency_key: request.idempotency_key.clone(),
+        name: request.name.clone(),
+        account,
+        members: request.members.clone(),
+        planned,
+        status: KeysetStatus::Pending,
+        created_at: now,
+        completed_at: None,
+        ceremony_id: None,
+        tags: Vec::new(),
+        template_id: None,
+    })
+}
+
+/// The planned keys of `keyset` not yet in `keys`. Fails if a planned path in
+/// the keyset's HD tree was taken by a key outside the keyset since it was
+/// planned.
+pub fn missing_planned<'a>(
+    keys: &[KeyEntry],
+    keyset: &'a Keyset,
+) -> Result<Vec<&'a PlannedKey>, String> {
+    let mut missing = Vec::new();
+    for planned in &keyset.planned {
+        match keys.iter().find(|k| {
+            k.metadata.ceremony_id == keyset.ceremony_id
+                && k.metadata.derivation_path == planned.derivation_path
+        }) {
+            Some(k) if k.metadata.keyset_id.as_deref() != Some(keyset.id.as_str()) => {
+                return Err(format!(
+                    "{} is already used by a key outside this keyset",
+                    planned.derivation_path
+                ));
+            }
+            Some(_) => {}
+            None => missing.push(planned),
+        }
+    }
+    Ok(missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(purpose: u32, account: u32, index: u32) -> KeyEntry {
+        let path = hd_derivation::zap_path(purpose, account, index).to_string();
+        KeyEntry::new(
+            KeyType::User,
+            purpose,
+            account,
+            index,
+            "pk",
+            "sk",
+            "zap1",
+            &path,
+        )
+    }
+
+    fn request(members: &[(&str, u32, u32)]) -> KeysetRequest {
+        KeysetRequest {
+            idempotency_key: "genesis-1".to_string(),
+            name: "Genesis".to_string(),
+            account: 0,
+            members: members
+                .iter()
+                .map(|(t, purpose, count)| KeysetMemberSpec {
+                    key_type: t.to_string(),
+                    purpose: *purpose,
+                    count: *count,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn plan_assigns_next_free_indices_per_purpose() {
+        let keys = vec![key(1, 0, 0), key(1, 0, 1), key(2, 1, 5)];
+        let req = request(&[("validator", 1, 2), ("treasury", 2, 1), ("validator", 1, 1)]);
+        let set = plan_keyset(&keys, &req, 20, Utc::now()).unwrap();
+        let got: Vec<(u32, u32)> = set.planned.iter().map(|p| (p.purpose, p.index)).collect();
+        assert_eq!(got, [(1, 2), (1, 3), (2, 0), (1, 4)]);
+        assert_eq!(set.planned[2].key_type, KeyType::Treasury);
+        assert_eq!(set.status, KeysetStatus::Pending);
+        assert!(set.matches(&req));
+    }
+
+    #[test]
+    fn plan_rejects_gap_limit_and_size_overflow() {
+        assert!(plan_keyset(&[], &request(&[("user", 0, 20)]), 20, Utc::now()).is_ok());
+        assert!(plan_keyset(&[], &request(&[("user", 0, 21)]), 20, Utc::now()).is_err());
+        assert!(plan_keyset(&[], &request(&[("user", 0, 0)]), 20, Utc::now()).is_err());
+        let huge = request(&[("user", 0, u32::MAX), ("user", 1, 2)]);
+        assert!(plan_keyset(&[], &huge, u32::MAX, Utc::now()).is_err());
+    }
+
+    #[test]
+    fn missing_planned_detects_progress_and_conflicts() {
+        let set = plan_keyset(&[], &request(&[("user", 0, 3)]), 20, Utc::now()).unwrap();
+        let mut done = key(0, 0, 0);
+        done.metadata.keyset_id = Some(set.id.clone());
+        assert_eq!(missing_planned(&[done], &set).unwrap().len(), 2);
+        assert!(missing_planned(&[key(0, 0, 1)], &set).is_err());
+    }
+}
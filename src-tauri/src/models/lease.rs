@@ -0,0 +1,117 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The write lease, in the vault's data directory next to `vault.json`.
+pub const WRITE_LEASE_FILE: &str = "write_lease.json";
+/// How often the holder renews its lease.
+pub const LEASE_HEARTBEAT_SECS: u64 = 5;
+/// A lease not renewed for this long is stale and can be taken over.
+pub const LEASE_STALE_SECS: i64 = 30;
+
+/// The claim of one running instance to write the vault's files. Only the
+/// holder may open a session; every other instance is refused until the
+/// holder locks, exits or stops renewing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteLease {
+    /// Random per session, so a lease can be told apart from one taken by
+    /// an earlier run of the same process.
+    pub holder_id: String,
+    pub pid: u32,
+    /// Host name of the holder, empty if it could not be determined.
+    pub host: String,
+    pub acquired_at: DateTime<Utc>,
+    pub heartbeat_at: DateTime<Utc>,
+}
+
+impl WriteLease {
+    pub fn new(holder_id: String, pid: u32, host: String, now: DateTime<Utc>) -> Self {
+        Self {
+            holder_id,
+            pid,
+            host,
+            acquired_at: now,
+            heartbeat_at: now,
+        }
+    }
+
+    /// Whether the holder is gone: it stopped renewing, or it ran on this
+    /// host and `pid_alive` says its process has exited. A process on
+    /// another host can only be judged by its heartbeat.
+    pub fn is_stale(
+        &self,
+        now: DateTime<Utc>,
+        local_host: &str,
+        pid_alive: impl Fn(u32) -> bool,
+    ) -> bool {
+        if now - self.heartbeat_at >= Duration::seconds(LEASE_STALE_SECS) {
+            return true;
+        }
+        !self.host.is_empty() && self.host == local_host && !pid_alive(self.pid)
+    }
+}
+
+/// Returned by `write_lease_status`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteLeaseStatus {
+    /// Whether this instance holds the lease.
+    pub held: bool,
+    /// The lease on disk, if any.
+    pub lease: Option<WriteLease>,
+    /// Whether that lease is stale and would be taken over on unlock.
+    pub stale: bool,
+}
+
+/// Whether `holder_id` may take the lease over `existing`: it is free, its
+/// own, or stale. Otherwise returns the live lease that blocks it.
+pub fn check_claim(
+    existing: Option<&WriteLease>,
+    holder_id: &str,
+    now: DateTime<Utc>,
+    local_host: &str,
+    pid_alive: impl Fn(u32) -> bool,
+) -> Result<(), WriteLease> {
+    match existing {
+        Some(lease)
+            if lease.holder_id != holder_id && !lease.is_stale(now, local_host, pid_alive) =>
+        {
+            Err(lease.clone())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lease(host: &str, now: DateTime<Utc>) -> WriteLease {
+        WriteLease::new("other".to_string(), 42, host.to_string(), now)
+    }
+
+    #[test]
+    fn live_lease_blocks_other_holders() {
+        let now = Utc::now();
+        let held = lease("remote", now);
+        assert!(check_claim(None, "me", now, "local", |_| true).is_ok());
+        assert_eq!(
+            check_claim(Some(&held), "me", now, "local", |_| false),
+            Err(held.clone())
+        );
+        assert!(check_claim(Some(&held), "other", now, "local", |_| true).is_ok());
+    }
+
+    #[test]
+    fn lease_goes_stale_without_heartbeat_or_process() {
+        let now = Utc::now();
+        let remote = lease("remote", now);
+        let late = now + Duration::seconds(LEASE_STALE_SECS);
+        assert!(!remote.is_stale(now, "local", |_| false));
+        assert!(remote.is_stale(late, "local", |_| true));
+
+        let local = lease("local", now);
+        assert!(!local.is_stale(now, "local", |pid| pid == 42));
+        assert!(local.is_stale(now, "local", |_| false));
+        // An unknown host is only judged by its heartbeat.
+        assert!(!lease("", now).is_stale(now, "", |_| false));
+    }
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+
+pub const MAX_LABEL_LEN: usize = 64;
+pub const MAX_DESCRIPTION_LEN: usize = 1024;
+pub const MAX_TAG_LEN: usize = 32;
+pub const MAX_TAGS: usize = 16;
+
+/// A partial edit of user-facing metadata (label, description, tags), applied
+/// to one or many keys / items at once. Absent fields are left unchanged; an
+/// empty `label` / `description` clears it. `tags` replaces the whole set,
+/// while `add_tags` / `remove_tags` edit it (the form used for bulk tagging).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetadataUpdate {
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+}
+
+/// Tags are compared case-insensitively, so store them trimmed and lowercased.
+fn normalize_tag(tag: &str) -> Result<String, String> {
+    let t = tag.trim().to_lowercase();
+    if t.is_empty() {
+        return Err("tags must not be empty".to_string());
+    }
+    if t.chars().count() > MAX_TAG_LEN {
+        return Err(format!("tag longer than {MAX_TAG_LEN} characters: {t}"));
+    }
+    Ok(t)
+}
+
+fn normalize_text(value: &str, max: usize, field: &str) -> Result<Option<String>, String> {
+    let v = value.trim();
+    if v.chars().count() > max {
+        return Err(format!("{field} longer than {max} characters"));
+    }
+    Ok((!v.is_empty()).then(|| v.to_string()))
+}
+
+impl MetadataUpdate {
+    /// Check the update without applying it, so a bulk edit can be rejected
+    /// before any entry is touched.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(label) = &self.label {
+            normalize_text(label, MAX_LABEL_LEN, "label")?;
+        }
+        if let Some(description) = &self.description {
+            normalize_text(description, MAX_DESCRIPTION_LEN, "description")?;
+        }
+        for tag in self
+            .tags
+            .iter()
+            .flatten()
+            .chain(&self.add_tags)
+            .chain(&self.remove_tags)
+        {
+            normalize_tag(tag)?;
+        }
+        Ok(())
+    }
+
+    /// Apply the update to one entry's fields.
+    pub fn apply(
+        &self,
+        label: &mut Option<String>,
+        description: &mut Option<String>,
+        tags: &mut Vec<String>,
+    ) -> Result<(), String> {
+        if let Some(l) = &self.label {
+            *label = normalize_text(l, MAX_LABEL_LEN, "label")?;
+        }
+        if let Some(d) = &self.description {
+            *description = normalize_text(d, MAX_DESCRIPTION_LEN, "description")?;
+        }
+
+        let mut next = match &self.tags {
+            Some(replace) => Vec::with_capacity(replace.len()),
+            None => tags.clone(),
+        };
+        for tag in self.tags.iter().flatten().chain(&self.add_tags) {
+            let t = normalize_tag(tag)?;
+            if !next.contains(&t) {
+                next.push(t);
+            }
+        }
+        let removed = self
+            .remove_tags
+            .iter()
+            .map(|t| normalize_tag(t))
+            .collect::<Result<Vec<_>, _>>()?;
+        next.retain(|t| !removed.contains(t));
+        if next.len() > MAX_TAGS {
+            return Err(format!("at most {MAX_TAGS} tags are allowed"));
+        }
+        *tags = next;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(
+        update: &MetadataUpdate,
+        tags: &[&str],
+    ) -> (Option<String>, Option<String>, Vec<String>) {
+        let mut label = Some("old".to_string());
+        let mut description = None;
+        let mut tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+        update
+            .apply(&mut label, &mut description, &mut tags)
+            .unwrap();
+        (label, description, tags)
+    }
+
+    #[test]
+    fn absent_fields_are_unchanged_and_empty_clears() {
+        let (label, _, tags) = apply(&MetadataUpdate::default(), &["cold"]);
+        assert_eq!(label.as_deref(), Some("old"));
+        assert_eq!(tags, ["cold"]);
+
+        let clear = MetadataUpdate {
+            label: Some("  ".to_string()),
+            description: Some("Treasury signer".to_string()),
+            ..Default::default()
+        };
+        let (label, description, _) = apply(&clear, &[]);
+        assert_eq!(label, None);
+        assert_eq!(description.as_deref(), Some("Treasury signer"));
+    }
+
+    #[test]
+    fn tags_are_normalized_deduped_added_and_removed() {
+        let update = MetadataUpdate {
+            add_tags: vec!["Hot".to_string(), "hot ".to_string(), "ops".to_string()],
+            remove_tags: vec!["COLD".to_string()],
+            ..Default::default()
+        };
+        let (_, _, tags) = apply(&update, &["cold", "audit"]);
+        assert_eq!(tags, ["audit", "hot", "ops"]);
+
+        let replace = MetadataUpdate {
+            tags: Some(vec!["a".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(apply(&replace, &["x", "y"]).2, ["a"]);
+    }
+
+    #[test]
+    fn limits_are_enforced() {
+        let long_label = MetadataUpdate {
+            label: Some("x".repeat(MAX_LABEL_LEN + 1)),
+            ..Default::default()
+        };
+        assert!(long_label.validate().is_err());
+
+        let too_many = MetadataUpdate {
+            add_tags: (0..=MAX_TAGS).map(|i| format!("t{i}")).collect(),
+            ..Default::default()
+        };
+        assert!(too_many.validate().is_ok());
+        let (mut l, mut d, mut t) = (None, None, Vec::new());
+        assert!(too_many.apply(&mut l, &mut d, &mut t).is_err());
+
+        let empty_tag = MetadataUpdate {
+            add_tags: vec![" ".to_string()],
+            ..Default::default()
+        };
+        assert!(empty_tag.validate().is_err());
+    }
+}
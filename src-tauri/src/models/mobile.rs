@@ -0,0 +1,57 @@
+use crate::crypto::encryption::Ciphertext;
+use crate::models::item::VaultItemPublic;
+use crate::models::key::KeyType;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Argon2id parameters the bundle password was stretched with, carried in
+/// the bundle so a reader needs nothing else to derive the key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleKdf {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+/// A read-only vault bundle for a mobile companion, as written to disk.
+/// See `docs/MOBILE_BUNDLE.md` for the format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileBundle {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub salt_hex: String,
+    pub kdf: BundleKdf,
+    /// XChaCha20-Poly1305 encrypted [`MobileBundleContents`].
+    pub payload: Ciphertext,
+    /// The vault's bundle-signing key; the same for every bundle the vault
+    /// exports, so a companion can pin it on first import.
+    pub public_key_hex: String,
+    pub signature_hex: String,
+}
+
+/// A key as the companion sees it: enough to show and receive to it,
+/// nothing to sign with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MobileKey {
+    pub id: String,
+    pub label: Option<String>,
+    pub key_type: KeyType,
+    pub address: String,
+    pub derivation_path: String,
+    pub tags: Vec<String>,
+}
+
+/// Decrypted bundle contents. Holds no secret material.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileBundleContents {
+    pub keys: Vec<MobileKey>,
+    /// Public views of the items chosen at export.
+    pub items: Vec<VaultItemPublic>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MobileExportSummary {
+    pub keys: usize,
+    pub items: usize,
+    pub public_key_hex: String,
+}
@@ -1,9 +1,67 @@
+pub mod address;
+pub mod admin_api;
+pub mod agent;
 pub mod airgap;
+pub mod attestation;
+pub mod backup;
+pub mod capsule;
+pub mod ceremony;
+pub mod compliance;
+pub mod consent;
+pub mod contact;
+pub mod custody;
+pub mod dashboard;
+pub mod display_cache;
+pub mod drive;
+pub mod drive_image;
+pub mod drive_seal;
+pub mod emergency;
+pub mod env_file;
+pub mod forecast;
+pub mod frost;
+pub mod health;
+pub mod hook;
+pub mod instance;
+pub mod integrity;
+pub mod item;
 pub mod key;
+pub mod key_drive;
+pub mod key_escrow;
+pub mod keyset;
+pub mod lease;
+pub mod metadata;
+pub mod mobile;
+pub mod musig2;
+pub mod note;
+pub mod notification;
+pub mod pairing;
+pub mod passkey;
+pub mod plugin;
+pub mod policy;
+pub mod portfolio;
+pub mod price;
+pub mod profile;
+pub mod public_bundle;
+pub mod rate_limit;
+pub mod recovery;
+pub mod remote;
+pub mod selftest;
+pub mod signing_request;
+pub mod spending;
+pub mod sync;
+pub mod template;
 pub mod transaction;
+pub mod trash;
+pub mod treasury;
+pub mod tx_review;
+pub mod undo;
+pub mod usage;
 pub mod vault;
+pub mod worksheet;
 
 pub use airgap::{AirGapEnvelope, TransferType};
+pub use item::{VaultItem, VaultItemPublic};
 pub use key::{KeyEntry, KeyMetadata, KeyType};
 pub use transaction::{SignedTx, UnsignedTx};
+pub use usage::UsageStats;
 pub use vault::VaultState;
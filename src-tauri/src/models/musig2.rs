@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+/// A MuSig2 signing session as stored by one signer. Points are compressed
+/// SEC1 hex, scalars 32-byte big-endian hex.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MuSig2Session {
+    pub session_id: String,
+    /// This signer's individual key.
+    pub public_key_hex: String,
+    /// Every signer's individual key, sorted as BIP-327 `KeySort` does.
+    pub public_keys: Vec<String>,
+    /// The 32-byte BIP-341 sighash this session may sign, and nothing else.
+    pub message_hex: String,
+    #[serde(default)]
+    pub merkle_root_hex: Option<String>,
+    /// This signer's public nonce: two compressed points, 66 bytes.
+    pub public_nonce_hex: String,
+    /// The secret nonces behind `public_nonce_hex`. Cleared once used, so a
+    /// session signs at most once.
+    #[serde(default)]
+    pub secret_nonce_hex: Option<String>,
+    /// Every signer's public nonce, recorded when this signer signs.
+    #[serde(default)]
+    pub public_nonces: Vec<MuSig2PublicNonce>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Drop for MuSig2Session {
+    fn drop(&mut self) {
+        self.secret_nonce_hex.zeroize();
+    }
+}
+
+/// The public side of a session, to share with the other signers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MuSig2SessionInfo {
+    pub session_id: String,
+    /// This signer's individual key.
+    pub public_key_hex: String,
+    pub public_keys: Vec<String>,
+    pub message_hex: String,
+    pub merkle_root_hex: Option<String>,
+    /// x-only taproot output key the final signature verifies under.
+    pub output_key_hex: String,
+    pub public_nonce_hex: String,
+    /// Whether this signer has already produced its partial signature.
+    pub signed: bool,
+}
+
+/// One signer's public nonce for a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MuSig2PublicNonce {
+    pub public_key_hex: String,
+    pub nonce_hex: String,
+}
+
+/// One signer's partial signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MuSig2PartialSignature {
+    pub public_key_hex: String,
+    pub s_hex: String,
+}
+
+/// The final BIP-340 signature for the aggregate output key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MuSig2Signature {
+    pub message_hex: String,
+    pub output_key_hex: String,
+    pub signature_hex: String,
+}
+
+/// Whether `id` has the shape of a MuSig2 output key: 64 hex digits, never a
+/// stored key's UUID. Spending policies are keyed by it.
+pub fn is_output_key(id: &str) -> bool {
+    id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Every MuSig2 session this vault has open, encrypted under the session key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MuSig2Store {
+    #[serde(default)]
+    pub sessions: Vec<MuSig2Session>,
+}
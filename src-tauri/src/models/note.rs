@@ -0,0 +1,119 @@
+use crate::crypto::encryption::Ciphertext;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Longest note text accepted, in bytes.
+pub const MAX_NOTE_BYTES: usize = 4096;
+
+/// What a note is attached to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NoteTarget {
+    Drive { drive_id: String },
+    Backup { drive_id: String, backup_id: String },
+}
+
+impl NoteTarget {
+    /// The drive the note lives with; a mirrored note is written there.
+    pub fn drive_id(&self) -> &str {
+        match self {
+            NoteTarget::Drive { drive_id } | NoteTarget::Backup { drive_id, .. } => drive_id,
+        }
+    }
+
+    pub fn backup_id(&self) -> Option<&str> {
+        match self {
+            NoteTarget::Drive { .. } => None,
+            NoteTarget::Backup { backup_id, .. } => Some(backup_id),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let empty = match self {
+            NoteTarget::Drive { drive_id } => drive_id.trim().is_empty(),
+            NoteTarget::Backup {
+                drive_id,
+                backup_id,
+            } => drive_id.trim().is_empty() || backup_id.trim().is_empty(),
+        };
+        if empty {
+            return Err("note target ids must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+pub fn validate_note_text(text: &str) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err("note must not be empty".to_string());
+    }
+    if text.len() > MAX_NOTE_BYTES {
+        return Err(format!("note exceeds {MAX_NOTE_BYTES} bytes"));
+    }
+    Ok(())
+}
+
+/// What gets encrypted: the target travels inside the ciphertext so a note
+/// copied onto another drive or backup is detected on decryption.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteBody {
+    pub target: NoteTarget,
+    pub text: String,
+}
+
+/// A note as stored in `notes.json` and mirrored onto drives. Only the text
+/// is secret; the target stays readable so notes can be listed while locked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredNote {
+    pub id: String,
+    pub target: NoteTarget,
+    pub ciphertext: Ciphertext,
+    pub created_at: DateTime<Utc>,
+    /// Whether an encrypted copy was written onto the target's drive.
+    pub mirrored: bool,
+}
+
+/// A note as returned to the UI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Note {
+    pub id: String,
+    pub target: NoteTarget,
+    /// `None` while the vault is locked.
+    pub text: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub mirrored: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_names_its_drive_and_rejects_blank_ids() {
+        let backup = NoteTarget::Backup {
+            drive_id: "usb-1".into(),
+            backup_id: "b1".into(),
+        };
+        assert_eq!(backup.drive_id(), "usb-1");
+        assert_eq!(backup.backup_id(), Some("b1"));
+        assert!(backup.validate().is_ok());
+        assert!(NoteTarget::Backup {
+            drive_id: "usb-1".into(),
+            backup_id: " ".into(),
+        }
+        .validate()
+        .is_err());
+        assert!(NoteTarget::Drive {
+            drive_id: String::new()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn note_text_bounds() {
+        assert!(validate_note_text("stored in safe #2").is_ok());
+        assert!(validate_note_text("  ").is_err());
+        assert!(validate_note_text(&"x".repeat(MAX_NOTE_BYTES + 1)).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+/// Consecutive failed unlocks after which every further failure raises a
+/// notification.
+pub const FAILED_UNLOCK_STREAK: u32 = 3;
+
+/// Security events the backend can raise as OS notifications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityEvent {
+    BackupCompleted,
+    BackupFailed,
+    /// A drive holding backups of this vault that have not been verified was
+    /// unplugged.
+    UnverifiedDriveDetached,
+    /// A stored key was used to sign outside the configured normal hours.
+    OffHoursDecrypt,
+    FailedUnlockStreak,
+    /// A provisioned drive was attached with signs of changes made outside
+    /// the app.
+    DriveModifiedExternally,
+    /// A backup drive is forecast to run out of space soon.
+    DriveNearlyFull,
+}
+
+impl SecurityEvent {
+    pub fn title(&self, locale: &str) -> String {
+        let id = match self {
+            SecurityEvent::BackupCompleted => "notification-backup-completed",
+            SecurityEvent::BackupFailed => "notification-backup-failed",
+            SecurityEvent::UnverifiedDriveDetached => "notification-unverified-drive-detached",
+            SecurityEvent::OffHoursDecrypt => "notification-off-hours-decrypt",
+            SecurityEvent::FailedUnlockStreak => "notification-failed-unlock-streak",
+            SecurityEvent::DriveModifiedExternally => "notification-drive-modified-externally",
+            SecurityEvent::DriveNearlyFull => "notification-drive-nearly-full",
+        };
+        crate::i18n::tr(locale, id, &[])
+    }
+}
+
+/// Local-time hours during which signing is expected, `start_hour` inclusive
+/// to `end_hour` exclusive. A range with `start_hour > end_hour` wraps past
+/// midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalHours {
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+impl NormalHours {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start_hour > 23 || self.end_hour > 23 {
+            return Err("hours must be between 0 and 23".to_string());
+        }
+        if self.start_hour == self.end_hour {
+            return Err("normal hours must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start_hour < self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+/// Which security events raise OS notifications, persisted in `vault.json`.
+/// Every event is on by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    #[serde(default)]
+    pub disabled: Vec<SecurityEvent>,
+    /// Signing outside these hours raises `OffHoursDecrypt`; `None` turns
+    /// that check off.
+    #[serde(default)]
+    pub normal_hours: Option<NormalHours>,
+}
+
+impl NotificationSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        self.normal_hours
+            .as_ref()
+            .map_or(Ok(()), NormalHours::validate)
+    }
+
+    pub fn enabled(&self, event: SecurityEvent) -> bool {
+        !self.disabled.contains(&event)
+    }
+
+    /// Whether signing at local `hour` should raise `OffHoursDecrypt`.
+    pub fn is_off_hours(&self, hour: u32) -> bool {
+        self.enabled(SecurityEvent::OffHoursDecrypt)
+            && self.normal_hours.is_some_and(|h| !h.contains(hour))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_hours_wrap_past_midnight() {
+        let day = NormalHours {
+            start_hour: 9,
+            end_hour: 17,
+        };
+        assert!(day.contains(9) && day.contains(16));
+        assert!(!day.contains(17) && !day.contains(3));
+
+        let night = NormalHours {
+            start_hour: 22,
+            end_hour: 6,
+        };
+        assert!(night.contains(23) && night.contains(0) && night.contains(5));
+        assert!(!night.contains(6) && !night.contains(12));
+
+        assert!(NormalHours {
+            start_hour: 8,
+            end_hour: 8
+        }
+        .validate()
+        .is_err());
+        assert!(NormalHours {
+            start_hour: 24,
+            end_hour: 8
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn off_hours_respects_opt_out() {
+        let mut settings = NotificationSettings {
+            disabled: Vec::new(),
+            normal_hours: Some(NormalHours {
+                start_hour: 9,
+                end_hour: 17,
+            }),
+        };
+        assert!(settings.is_off_hours(20));
+        assert!(!settings.is_off_hours(10));
+        settings.disabled.push(SecurityEvent::OffHoursDecrypt);
+        assert!(!settings.is_off_hours(20));
+        assert!(!NotificationSettings::default().is_off_hours(3));
+    }
+}
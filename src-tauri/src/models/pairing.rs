@@ -0,0 +1,332 @@
+use crate::crypto::encryption::Ciphertext;
+use crate::models::passkey::PasskeyAssertion;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+/// Socket file name inside the data directory that the native-messaging
+/// host relays to.
+pub const BROWSER_SOCKET_FILE: &str = "browser.sock";
+/// Largest native-messaging frame either side accepts. Chrome caps messages
+/// from the host at 1 MiB.
+pub const MAX_FRAME_BYTES: usize = 1024 * 1024;
+/// Longest extension name kept from a pairing request.
+pub const MAX_EXTENSION_NAME_CHARS: usize = 64;
+
+/// A browser extension paired with this vault. Its pairing key is wrapped
+/// under a key derived from the master seed, so the file alone cannot be
+/// used to impersonate the extension.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedExtension {
+    pub id: String,
+    pub name: String,
+    pub paired_at: DateTime<Utc>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+    /// Highest request sequence number accepted; older ones are replays.
+    pub last_seq: u64,
+    pub wrapped_key: Ciphertext,
+}
+
+/// Secret-free view of a [`PairedExtension`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedExtensionPublic {
+    pub id: String,
+    pub name: String,
+    pub paired_at: DateTime<Utc>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+}
+
+/// An origin the user allowed to be filled from one item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SiteApproval {
+    pub origin: String,
+    pub item_id: String,
+    pub approved_at: DateTime<Utc>,
+}
+
+/// An origin an extension asked about that has no approval yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SiteRequest {
+    pub origin: String,
+    pub extension_id: String,
+    pub requested_at: DateTime<Utc>,
+}
+
+/// `browser_pairing.json`: paired extensions and per-site approvals.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PairingStore {
+    #[serde(default)]
+    pub extensions: Vec<PairedExtension>,
+    #[serde(default)]
+    pub sites: Vec<SiteApproval>,
+    #[serde(default)]
+    pub pending_sites: Vec<SiteRequest>,
+}
+
+/// What `pairing_list` returns.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingOverview {
+    pub listening: bool,
+    pub extensions: Vec<PairedExtensionPublic>,
+    pub sites: Vec<SiteApproval>,
+    pub pending_sites: Vec<SiteRequest>,
+}
+
+/// Emitted when an extension asks to pair. The user compares `code` with
+/// the one the extension shows before calling `pairing_confirm`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingPrompt {
+    pub pending_id: String,
+    pub name: String,
+    pub code: String,
+}
+
+/// A frame from the extension, as relayed by the native-messaging host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BrowserMessage {
+    /// Start pairing with the extension's ephemeral X25519 public key.
+    PairRequest {
+        extension_public_hex: String,
+        name: String,
+    },
+    /// A [`FillRequest`] sealed under the pairing key.
+    Sealed {
+        extension_id: String,
+        nonce_hex: String,
+        ciphertext_hex: String,
+    },
+}
+
+/// A frame back to the extension.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BrowserReply {
+    /// The extension derives the same code from `app_public_hex` and shows
+    /// it; `pending_id` becomes its extension id once confirmed.
+    PairPending {
+        pending_id: String,
+        app_public_hex: String,
+    },
+    /// A [`FillResponse`] sealed under the pairing key.
+    Sealed {
+        nonce_hex: String,
+        ciphertext_hex: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// What the extension wants for the page it is on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FillAction {
+    /// The API token approved for the origin.
+    ApiToken,
+    /// A WebAuthn assertion from the passkey approved for the origin.
+    PasskeyAssertion { client_data_hash_hex: String },
+}
+
+/// The plaintext inside a sealed request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillRequest {
+    /// Must increase with every request from the extension.
+    pub seq: u64,
+    pub origin: String,
+    pub action: FillAction,
+}
+
+/// The plaintext inside a sealed reply. `seq` echoes the request's.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FillResponse {
+    Token {
+        seq: u64,
+        value: String,
+    },
+    Assertion {
+        seq: u64,
+        assertion: PasskeyAssertion,
+    },
+    /// The user has not approved an item for this origin yet; the request is
+    /// now listed in the app for approval.
+    ApprovalRequired {
+        seq: u64,
+        origin: String,
+    },
+    Denied {
+        seq: u64,
+        reason: String,
+    },
+}
+
+/// Reduce a page URL to its origin, `scheme://host[:port]`, lowercased.
+/// Only `https` is accepted, plus `http` on loopback hosts for local
+/// development.
+pub fn normalize_origin(url: &str) -> Result<String, String> {
+    let invalid = || format!("'{url}' is not a valid web origin");
+    let (scheme, rest) = url.split_once("://").ok_or_else(invalid)?;
+    let scheme = scheme.to_ascii_lowercase();
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if authority.is_empty() || authority.contains('@') {
+        return Err(invalid());
+    }
+    let authority = authority.to_ascii_lowercase();
+    let (host, port) = match authority.find(']') {
+        // Bracketed IPv6 literal.
+        Some(end) if authority.starts_with('[') => {
+            let (host, rest) = authority.split_at(end + 1);
+            let valid = host[1..end]
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() || c == ':');
+            match rest.strip_prefix(':') {
+                Some(p) if valid => (host, Some(p)),
+                None if valid && rest.is_empty() => (host, None),
+                _ => return Err(invalid()),
+            }
+        }
+        Some(_) => return Err(invalid()),
+        None => {
+            let (host, port) = match authority.split_once(':') {
+                Some((h, p)) => (h, Some(p)),
+                None => (authority.as_str(), None),
+            };
+            if host.is_empty()
+                || !host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-'))
+            {
+                return Err(invalid());
+            }
+            (host, port)
+        }
+    };
+    if let Some(p) = port {
+        p.parse::<u16>().map_err(|_| invalid())?;
+    }
+    let loopback = matches!(host, "localhost" | "127.0.0.1" | "[::1]");
+    let default_port = match scheme.as_str() {
+        "https" => "443",
+        "http" if loopback => "80",
+        _ => return Err(format!("'{url}' must use https")),
+    };
+    Ok(match port {
+        Some(p) if p != default_port => format!("{scheme}://{host}:{p}"),
+        _ => format!("{scheme}://{host}"),
+    })
+}
+
+/// Host part of a normalized origin.
+pub fn origin_host(origin: &str) -> &str {
+    let rest = origin.split_once("://").map_or(origin, |(_, r)| r);
+    if rest.starts_with('[') {
+        return rest.split_inclusive(']').next().unwrap_or(rest);
+    }
+    rest.split(':').next().unwrap_or(rest)
+}
+
+/// Whether a passkey for `rp_id` may be used on `origin`, following the
+/// WebAuthn rule that the RP ID is the origin's host or a parent domain.
+pub fn origin_matches_rp_id(origin: &str, rp_id: &str) -> bool {
+    let host = origin_host(origin);
+    host == rp_id
+        || host
+            .strip_suffix(rp_id)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// Read one native-messaging frame: a native-endian `u32` length followed by
+/// that many bytes of UTF-8 JSON. `Ok(None)` on a clean end of input.
+pub fn read_frame(r: &mut impl Read) -> std::io::Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    match r.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let len = u32::from_ne_bytes(len) as usize;
+    if len > MAX_FRAME_BYTES {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("frame of {len} bytes exceeds {MAX_FRAME_BYTES}"),
+        ));
+    }
+    let mut buf = vec![0u8; len];
+    r.read_exact(&mut buf)?;
+    Ok(Some(buf))
+}
+
+/// Write one native-messaging frame and flush it.
+pub fn write_frame(w: &mut impl Write, data: &[u8]) -> std::io::Result<()> {
+    if data.len() > MAX_FRAME_BYTES {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "frame too large",
+        ));
+    }
+    w.write_all(&(data.len() as u32).to_ne_bytes())?;
+    w.write_all(data)?;
+    w.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origins_are_normalized() {
+        assert_eq!(
+            normalize_origin("HTTPS://Login.Example.com/path?q=1").unwrap(),
+            "https://login.example.com"
+        );
+        assert_eq!(
+            normalize_origin("https://example.com:443/").unwrap(),
+            "https://example.com"
+        );
+        assert_eq!(
+            normalize_origin("https://example.com:8443").unwrap(),
+            "https://example.com:8443"
+        );
+        assert_eq!(
+            normalize_origin("http://localhost:3000/app").unwrap(),
+            "http://localhost:3000"
+        );
+        assert!(normalize_origin("http://example.com").is_err());
+        assert!(normalize_origin("https://user@example.com").is_err());
+        assert!(normalize_origin("https://exa mple.com").is_err());
+        assert!(normalize_origin("example.com").is_err());
+        assert!(normalize_origin("https://example.com:99999").is_err());
+    }
+
+    #[test]
+    fn rp_id_matches_host_or_parent_domain() {
+        assert!(origin_matches_rp_id("https://example.com", "example.com"));
+        assert!(origin_matches_rp_id(
+            "https://login.example.com:8443",
+            "example.com"
+        ));
+        assert!(!origin_matches_rp_id(
+            "https://badexample.com",
+            "example.com"
+        ));
+        assert!(!origin_matches_rp_id(
+            "https://example.com",
+            "login.example.com"
+        ));
+    }
+
+    #[test]
+    fn frames_round_trip_and_are_bounded() {
+        let mut buf = Vec::new();
+        write_frame(&mut buf, br#"{"a":1}"#).unwrap();
+        write_frame(&mut buf, b"{}").unwrap();
+        let mut r = std::io::Cursor::new(buf);
+        assert_eq!(read_frame(&mut r).unwrap().unwrap(), br#"{"a":1}"#);
+        assert_eq!(read_frame(&mut r).unwrap().unwrap(), b"{}");
+        assert!(read_frame(&mut r).unwrap().is_none());
+
+        let huge = ((MAX_FRAME_BYTES + 1) as u32).to_ne_bytes();
+        assert!(read_frame(&mut std::io::Cursor::new(huge)).is_err());
+    }
+}
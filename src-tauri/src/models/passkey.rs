@@ -0,0 +1,66 @@
+use crate::models::item::PasskeyAlgorithm;
+use crate::models::policy::ExportWatermark;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+/// `format` value of a passkey export document.
+pub const PASSKEY_EXPORT_FORMAT: &str = "zap-passkeys";
+
+/// One credential in a passkey export. Field names are camelCase to match
+/// WebAuthn; binary values are unpadded base64url. See
+/// `docs/PASSKEYS.md`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedPasskey {
+    pub rp_id: String,
+    #[serde(default)]
+    pub rp_name: Option<String>,
+    pub user_handle: String,
+    pub user_name: String,
+    #[serde(default)]
+    pub user_display_name: Option<String>,
+    pub credential_id: String,
+    pub algorithm: PasskeyAlgorithm,
+    pub private_key: String,
+    #[serde(default)]
+    pub sign_count: u32,
+    #[serde(default = "default_discoverable")]
+    pub discoverable: bool,
+}
+
+fn default_discoverable() -> bool {
+    true
+}
+
+impl Drop for ExportedPasskey {
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+/// A passkey export document, as written by `passkey_export` and read by
+/// `passkey_import`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyExport {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub credentials: Vec<ExportedPasskey>,
+    /// Set by this vault's exports; other providers' documents lack it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watermark: Option<ExportWatermark>,
+}
+
+/// A signed WebAuthn assertion, ready for the client to return as an
+/// `AuthenticatorAssertionResponse`. Binary fields are unpadded base64url.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasskeyAssertion {
+    pub credential_id: String,
+    pub authenticator_data: String,
+    /// DER for ES256, raw 64 bytes for EdDSA.
+    pub signature: String,
+    pub user_handle: String,
+    pub sign_count: u32,
+}
@@ -0,0 +1,386 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// `format` of a [`PluginPackage`].
+pub const PLUGIN_PACKAGE_FORMAT: &str = "zap-plugin";
+pub const PLUGIN_PACKAGE_VERSION: u32 = 1;
+/// Version of the call interface between the vault and a plugin module.
+pub const PLUGIN_API_VERSION: u32 = 1;
+/// Plugins a vault can have installed.
+pub const MAX_PLUGINS: usize = 16;
+pub const MAX_PLUGIN_WASM_BYTES: usize = 16 * 1024 * 1024;
+pub const MAX_PLUGIN_NAME_CHARS: usize = 64;
+/// Fields a plugin item type can declare.
+pub const MAX_PLUGIN_ITEM_FIELDS: usize = 32;
+
+/// Data the vault may hand to a plugin. Private keys and the secrets of
+/// built-in items are never handed out, whatever is granted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginCapability {
+    /// Public keys, addresses and derivation paths of the vault's keys.
+    ReadPublicKeys,
+    /// Ids, kinds, labels, tags and dates of all items, without contents.
+    ReadItemMetadata,
+    /// Full contents, secret fields included, of items of the plugin's own
+    /// item types.
+    ReadPluginItems,
+}
+
+/// A field of a plugin item type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginItemField {
+    pub name: String,
+    pub label: String,
+    /// Stored like other item secrets: never returned by listings, only
+    /// by `plugin_item_reveal`.
+    #[serde(default)]
+    pub secret: bool,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// A custom item type, e.g. a TOTP seed or a database credential.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginItemType {
+    pub name: String,
+    pub label: String,
+    pub fields: Vec<PluginItemField>,
+}
+
+/// An export format the plugin renders, e.g. a tax tool's import file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginExporter {
+    pub name: String,
+    pub label: String,
+    /// Without the dot, e.g. `csv`.
+    pub file_extension: String,
+}
+
+/// A chain whose addresses the plugin derives from the vault's public keys.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginChain {
+    pub name: String,
+    pub label: String,
+}
+
+/// What a plugin is and provides, signed by its publisher together with the
+/// module.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    /// Lowercase letters, digits and dashes, e.g. `totp-items`.
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub publisher: String,
+    pub api_version: u32,
+    #[serde(default)]
+    pub item_types: Vec<PluginItemType>,
+    #[serde(default)]
+    pub exporters: Vec<PluginExporter>,
+    #[serde(default)]
+    pub chains: Vec<PluginChain>,
+    /// Requested capabilities; the ones installed are what the user grants.
+    #[serde(default)]
+    pub capabilities: Vec<PluginCapability>,
+}
+
+fn validate_slug(what: &str, s: &str) -> Result<(), String> {
+    if s.is_empty()
+        || s.len() > MAX_PLUGIN_NAME_CHARS
+        || !s
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "{what} {s:?} must be 1 to {MAX_PLUGIN_NAME_CHARS} lowercase letters, digits, dashes or underscores"
+        ));
+    }
+    Ok(())
+}
+
+fn validate_unique<'a>(what: &str, names: impl Iterator<Item = &'a str>) -> Result<(), String> {
+    let mut seen = std::collections::BTreeSet::new();
+    for name in names {
+        validate_slug(what, name)?;
+        if !seen.insert(name) {
+            return Err(format!("{what} {name:?} is declared twice"));
+        }
+    }
+    Ok(())
+}
+
+impl PluginManifest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_slug("plugin id", &self.id)?;
+        for (what, value) in [
+            ("name", &self.name),
+            ("version", &self.version),
+            ("publisher", &self.publisher),
+        ] {
+            let value = value.trim();
+            if value.is_empty() || value.chars().count() > MAX_PLUGIN_NAME_CHARS {
+                return Err(format!(
+                    "plugin {what} must be 1 to {MAX_PLUGIN_NAME_CHARS} characters"
+                ));
+            }
+        }
+        if self.api_version != PLUGIN_API_VERSION {
+            return Err(format!(
+                "plugin targets interface version {}, this vault supports {PLUGIN_API_VERSION}",
+                self.api_version
+            ));
+        }
+        if self.item_types.is_empty() && self.exporters.is_empty() && self.chains.is_empty() {
+            return Err("plugin provides no item types, exporters or chains".to_string());
+        }
+        validate_unique("item type", self.item_types.iter().map(|t| t.name.as_str()))?;
+        validate_unique("exporter", self.exporters.iter().map(|e| e.name.as_str()))?;
+        validate_unique("chain", self.chains.iter().map(|c| c.name.as_str()))?;
+        for item_type in &self.item_types {
+            if item_type.fields.is_empty() || item_type.fields.len() > MAX_PLUGIN_ITEM_FIELDS {
+                return Err(format!(
+                    "item type {} must have 1 to {MAX_PLUGIN_ITEM_FIELDS} fields",
+                    item_type.name
+                ));
+            }
+            validate_unique("field", item_type.fields.iter().map(|f| f.name.as_str()))?;
+        }
+        for exporter in &self.exporters {
+            validate_slug("file extension", &exporter.file_extension)?;
+        }
+        Ok(())
+    }
+
+    pub fn item_type(&self, name: &str) -> Option<&PluginItemType> {
+        self.item_types.iter().find(|t| t.name == name)
+    }
+}
+
+/// A publisher's hybrid ML-DSA-87 + Ed25519 signature over a plugin's
+/// manifest and module. Both halves must verify.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublisherSignature {
+    pub mldsa_public_hex: String,
+    pub ed25519_public_hex: String,
+    pub mldsa_signature_hex: String,
+    pub ed25519_signature_hex: String,
+}
+
+impl PublisherSignature {
+    /// Short BLAKE3 digest of both public keys, pinned with
+    /// `trust_plugin_publisher`.
+    pub fn key_fingerprint(&self) -> String {
+        let mut h = blake3::Hasher::new();
+        h.update(self.mldsa_public_hex.as_bytes());
+        h.update(b":");
+        h.update(self.ed25519_public_hex.as_bytes());
+        hex::encode(&h.finalize().as_bytes()[..16])
+    }
+}
+
+/// A `.zplugin` file as distributed by a publisher.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginPackage {
+    pub format: String,
+    pub version: u32,
+    pub manifest: PluginManifest,
+    /// The WebAssembly module.
+    pub wasm_base64: String,
+    pub signature: PublisherSignature,
+}
+
+/// A publisher whose plugins may be installed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedPublisher {
+    pub name: String,
+    pub key_fingerprint: String,
+    pub added_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledPlugin {
+    pub manifest: PluginManifest,
+    /// Capabilities the user granted, a subset of the requested ones.
+    pub granted: Vec<PluginCapability>,
+    /// Checked before every run, so a module changed on disk never runs.
+    pub wasm_sha256_hex: String,
+    pub publisher_fingerprint: String,
+    pub installed_at: DateTime<Utc>,
+}
+
+impl InstalledPlugin {
+    pub fn has(&self, capability: PluginCapability) -> bool {
+        self.granted.contains(&capability)
+    }
+
+    /// File name of the module in the vault's `plugins` directory.
+    pub fn wasm_file_name(&self) -> String {
+        format!("{}.wasm", self.manifest.id)
+    }
+}
+
+/// Trusted publishers and installed plugins, persisted in `vault.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginSettings {
+    #[serde(default)]
+    pub trusted_publishers: Vec<TrustedPublisher>,
+    #[serde(default)]
+    pub installed: Vec<InstalledPlugin>,
+}
+
+impl PluginSettings {
+    pub fn find(&self, plugin_id: &str) -> Option<&InstalledPlugin> {
+        self.installed.iter().find(|p| p.manifest.id == plugin_id)
+    }
+
+    pub fn trusts(&self, key_fingerprint: &str) -> Option<&TrustedPublisher> {
+        self.trusted_publishers
+            .iter()
+            .find(|p| p.key_fingerprint == key_fingerprint)
+    }
+}
+
+/// A call into a plugin, passed to its `zap_call` export as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "call", rename_all = "snake_case")]
+pub enum PluginRequest {
+    /// Check the fields of a new item. Answered with `null` or an error.
+    ValidateItem {
+        item_type: String,
+        fields: std::collections::BTreeMap<String, String>,
+    },
+    /// Render an export. Answered with the file contents as a string.
+    Export {
+        exporter: String,
+        /// Present with `read_public_keys`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        keys: Option<Vec<PluginKey>>,
+        /// Present with `read_item_metadata`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        items: Option<Vec<PluginItemMetadata>>,
+        /// Present with `read_plugin_items`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        plugin_items: Option<Vec<PluginItemContents>>,
+    },
+    /// Derive the address of a public key. Answered with the address.
+    DeriveAddress {
+        chain: String,
+        public_key_hex: String,
+        derivation_path: String,
+    },
+}
+
+/// What a plugin answers: exactly one of `ok` and `error`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginResponse {
+    #[serde(default)]
+    pub ok: Option<serde_json::Value>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// A vault key as a plugin with `read_public_keys` sees it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginKey {
+    pub id: String,
+    pub key_type: String,
+    pub label: Option<String>,
+    pub public_key_hex: String,
+    pub address: String,
+    pub derivation_path: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An item as a plugin with `read_item_metadata` sees it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginItemMetadata {
+    pub id: String,
+    pub kind: String,
+    pub label: Option<String>,
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// One of the plugin's own items, as a plugin with `read_plugin_items` sees
+/// it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginItemContents {
+    pub id: String,
+    pub item_type: String,
+    pub label: Option<String>,
+    pub fields: std::collections::BTreeMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> PluginManifest {
+        PluginManifest {
+            id: "totp-items".to_string(),
+            name: "TOTP".to_string(),
+            version: "1.0.0".to_string(),
+            publisher: "Example Labs".to_string(),
+            api_version: PLUGIN_API_VERSION,
+            item_types: vec![PluginItemType {
+                name: "totp".to_string(),
+                label: "TOTP seed".to_string(),
+                fields: vec![
+                    PluginItemField {
+                        name: "issuer".to_string(),
+                        label: "Issuer".to_string(),
+                        secret: false,
+                        required: true,
+                    },
+                    PluginItemField {
+                        name: "seed".to_string(),
+                        label: "Seed".to_string(),
+                        secret: true,
+                        required: true,
+                    },
+                ],
+            }],
+            exporters: Vec::new(),
+            chains: Vec::new(),
+            capabilities: vec![PluginCapability::ReadPluginItems],
+        }
+    }
+
+    #[test]
+    fn validates_manifests() {
+        assert!(manifest().validate().is_ok());
+
+        let mut bad_id = manifest();
+        bad_id.id = "TOTP Items".to_string();
+        assert!(bad_id.validate().is_err());
+
+        let mut future = manifest();
+        future.api_version = PLUGIN_API_VERSION + 1;
+        assert!(future.validate().unwrap_err().contains("interface version"));
+
+        let mut empty = manifest();
+        empty.item_types.clear();
+        assert!(empty.validate().is_err());
+
+        let mut dup = manifest();
+        dup.item_types[0].fields[1].name = "issuer".to_string();
+        assert!(dup.validate().unwrap_err().contains("declared twice"));
+    }
+
+    #[test]
+    fn requests_omit_ungranted_data() {
+        let request = PluginRequest::Export {
+            exporter: "koinly".to_string(),
+            keys: None,
+            items: Some(Vec::new()),
+            plugin_items: None,
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["call"], "export");
+        assert!(json.get("keys").is_none());
+        assert!(json.get("plugin_items").is_none());
+        assert!(json["items"].is_array());
+    }
+}
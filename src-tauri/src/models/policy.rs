@@ -0,0 +1,206 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Longest re-authentication window that can be configured (one hour).
+pub const MAX_REAUTH_WINDOW_SECS: u64 = 60 * 60;
+
+fn default_reauth_window_secs() -> u64 {
+    5 * 60
+}
+
+/// Commands a [`SecurityPolicy`] can refuse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyGate {
+    /// Resolving a stored secret to sign with it.
+    Decrypt,
+    /// Handing secret material out of the vault unencrypted.
+    PlaintextExport,
+    /// Creating new keys or key items.
+    KeyGeneration,
+}
+
+impl PolicyGate {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyGate::Decrypt => "decrypt",
+            PolicyGate::PlaintextExport => "plaintext export",
+            PolicyGate::KeyGeneration => "key generation",
+        }
+    }
+}
+
+/// Vault-wide security rules, persisted in `vault.json`. Everything is off by
+/// default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityPolicy {
+    /// Signing with a stored key requires the password to have been
+    /// re-entered within `reauth_window_secs`.
+    #[serde(default)]
+    pub require_reauth_for_decrypt: bool,
+    #[serde(default = "default_reauth_window_secs")]
+    pub reauth_window_secs: u64,
+    /// Refuse every export that would hand out a secret unencrypted.
+    #[serde(default)]
+    pub deny_plaintext_export: bool,
+    /// Refuse new keys until at least one backup of this vault has been
+    /// verified on its drive.
+    #[serde(default)]
+    pub require_verified_backup: bool,
+}
+
+impl Default for SecurityPolicy {
+    fn default() -> Self {
+        SecurityPolicy {
+            require_reauth_for_decrypt: false,
+            reauth_window_secs: default_reauth_window_secs(),
+            deny_plaintext_export: false,
+            require_verified_backup: false,
+        }
+    }
+}
+
+impl SecurityPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_REAUTH_WINDOW_SECS).contains(&self.reauth_window_secs) {
+            return Err(format!(
+                "reauth_window_secs must be between 1 and {MAX_REAUTH_WINDOW_SECS}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether `gate` may proceed at `now` (unix seconds), given when the
+    /// password was last re-entered and whether a verified backup exists.
+    /// Pure so it is unit-testable.
+    pub fn check(
+        &self,
+        gate: PolicyGate,
+        reauthenticated_at: Option<u64>,
+        has_verified_backup: bool,
+        now: u64,
+    ) -> Result<(), String> {
+        match gate {
+            PolicyGate::Decrypt if self.require_reauth_for_decrypt => {
+                let fresh = reauthenticated_at
+                    .is_some_and(|t| now < t.saturating_add(self.reauth_window_secs));
+                if !fresh {
+                    return Err("re-enter the vault password before signing".to_string());
+                }
+            }
+            PolicyGate::PlaintextExport if self.deny_plaintext_export => {
+                return Err("plaintext export is disabled by the vault policy".to_string());
+            }
+            PolicyGate::KeyGeneration if self.require_verified_backup && !has_verified_backup => {
+                return Err("verify a backup of this vault before generating new keys".to_string());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Who made a plaintext export, where and when. Embedded in the exported
+/// file where its format has room for it, and always repeated in the export's
+/// audit entry, so a leaked secret can be traced back to its export.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportWatermark {
+    /// Name of the profile the export was made from.
+    pub profile: String,
+    /// This installation's sync device id.
+    pub instance_id: String,
+    pub exported_at: DateTime<Utc>,
+}
+
+impl ExportWatermark {
+    /// One line, safe to embed in comments: control characters in the
+    /// profile name are dropped.
+    pub fn summary(&self) -> String {
+        let profile: String = self.profile.chars().filter(|c| !c.is_control()).collect();
+        format!(
+            "exported from profile '{profile}' on instance {} at {}",
+            self.instance_id,
+            self.exported_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+        )
+    }
+
+    /// [`summary`](Self::summary) as a `#` comment line, for formats that
+    /// have them.
+    pub fn comment_line(&self) -> String {
+        format!("# ZAP Quantum Vault: {}\n", self.summary())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_policy_allows_everything() {
+        let policy = SecurityPolicy::default();
+        for gate in [
+            PolicyGate::Decrypt,
+            PolicyGate::PlaintextExport,
+            PolicyGate::KeyGeneration,
+        ] {
+            assert!(policy.check(gate, None, false, 100).is_ok());
+        }
+    }
+
+    #[test]
+    fn reauth_expires_after_window() {
+        let policy = SecurityPolicy {
+            require_reauth_for_decrypt: true,
+            reauth_window_secs: 60,
+            ..Default::default()
+        };
+        assert!(policy.check(PolicyGate::Decrypt, None, true, 100).is_err());
+        assert!(policy
+            .check(PolicyGate::Decrypt, Some(100), true, 159)
+            .is_ok());
+        assert!(policy
+            .check(PolicyGate::Decrypt, Some(100), true, 160)
+            .is_err());
+        assert!(policy
+            .check(PolicyGate::KeyGeneration, None, false, 100)
+            .is_ok());
+    }
+
+    #[test]
+    fn export_and_backup_gates() {
+        let policy = SecurityPolicy {
+            deny_plaintext_export: true,
+            require_verified_backup: true,
+            ..Default::default()
+        };
+        assert!(policy
+            .check(PolicyGate::PlaintextExport, Some(0), true, 1)
+            .is_err());
+        assert!(policy
+            .check(PolicyGate::KeyGeneration, None, false, 1)
+            .is_err());
+        assert!(policy
+            .check(PolicyGate::KeyGeneration, None, true, 1)
+            .is_ok());
+        assert!(SecurityPolicy {
+            reauth_window_secs: 0,
+            ..policy
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn watermark_stays_on_one_line() {
+        let mark = ExportWatermark {
+            profile: "work\n[Interface]".to_string(),
+            instance_id: "device-1".to_string(),
+            exported_at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        };
+        assert_eq!(
+            mark.comment_line(),
+            "# ZAP Quantum Vault: exported from profile 'work[Interface]' on instance device-1 \
+             at 2023-11-14T22:13:20Z\n"
+        );
+    }
+}
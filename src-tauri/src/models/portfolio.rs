@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A recorded balance older than this is flagged in reports.
+pub const BALANCE_STALE_DAYS: i64 = 7;
+
+/// What a key held when the user last looked. The vault does not sync chain
+/// state, so balances are recorded by hand from an explorer or a watch-only
+/// wallet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedBalance {
+    pub key_id: String,
+    /// Ticker, uppercase, as in the price snapshot.
+    pub asset: String,
+    /// In base units (sats, wei), as a decimal string.
+    pub amount: String,
+    pub recorded_at: DateTime<Utc>,
+    /// Where the figure came from.
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Recorded balances, at most one per key and asset. Encrypted under the
+/// session key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BalanceStore {
+    #[serde(default)]
+    pub balances: Vec<RecordedBalance>,
+}
+
+/// Layout of a file written by `generate_portfolio_report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortfolioReportFormat {
+    /// A printable A4 document.
+    Pdf,
+    /// RFC 4180 CSV: one row per asset total, then one per key balance.
+    Csv,
+    /// The [`PortfolioReport`] itself.
+    Json,
+}
+
+/// One asset summed over every key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetHolding {
+    pub asset: String,
+    /// In base units.
+    pub amount: String,
+    /// Keys holding some of it.
+    pub keys: usize,
+    /// In the snapshot's currency, when the snapshot prices the asset.
+    pub value: Option<String>,
+}
+
+/// One recorded balance of a live key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyHolding {
+    pub key_id: String,
+    pub label: Option<String>,
+    pub key_type: String,
+    pub address: String,
+    pub asset: String,
+    pub amount: String,
+    pub value: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// How much of the vault a restore would bring back.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupCoverage {
+    /// Live keys.
+    pub keys: usize,
+    /// Keys derived from the vault's mnemonic.
+    pub mnemonic_recoverable: usize,
+    /// Of those, keys a wallet scan within the gap limit finds again.
+    pub within_scan_window: usize,
+    /// Drives holding a backup of this vault.
+    pub drives: usize,
+    pub last_backup_at: Option<DateTime<Utc>>,
+    pub last_verified_backup_at: Option<DateTime<Utc>>,
+}
+
+/// Everything a portfolio report shows, computed from the unlocked
+/// keystore, the recorded balances, the vault metadata and the stored price
+/// snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortfolioReport {
+    /// Name of the profile the vault belongs to.
+    pub vault_name: String,
+    pub generated_at: DateTime<Utc>,
+    pub total_keys: usize,
+    /// Live keys per key type.
+    pub keys_by_type: BTreeMap<String, usize>,
+    pub backup: BackupCoverage,
+    /// Currency of every value, when a price snapshot is stored.
+    pub currency: Option<String>,
+    pub prices_as_of: Option<DateTime<Utc>>,
+    pub holdings: Vec<AssetHolding>,
+    pub key_holdings: Vec<KeyHolding>,
+    /// Sum of the priced holdings. Unpriced assets are left out, with a
+    /// warning.
+    pub total_value: Option<String>,
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,88 @@
+use crate::crypto::fingerprint::KeyFingerprint;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// `format` of a [`PriceSnapshot`].
+pub const PRICE_SNAPSHOT_FORMAT: &str = "zap-price-snapshot";
+pub const PRICE_SNAPSHOT_VERSION: u32 = 1;
+/// Largest snapshot file accepted.
+pub const MAX_PRICE_SNAPSHOT_BYTES: usize = 1024 * 1024;
+/// A snapshot older than this is flagged as stale.
+pub const PRICE_STALE_HOURS: i64 = 24;
+/// A snapshot older than this is flagged as too old to rely on.
+pub const PRICE_VERY_STALE_DAYS: i64 = 7;
+
+/// The price of one whole unit of an asset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetPrice {
+    /// Ticker, uppercase (`BTC`, `ETH`, `ATOM`).
+    pub asset: String,
+    /// Decimal, in the snapshot's currency (`67000.12`).
+    pub price: String,
+    /// Base units per whole unit, as a power of ten (8 for sats per BTC).
+    pub decimals: u8,
+}
+
+/// Asset prices at one moment, signed by a publisher this vault trusts. The
+/// same snapshot can be written as JSON or CSV; see `docs/PRICE_SNAPSHOTS.md`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceSnapshot {
+    pub format: String,
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    /// ISO 4217 code the prices are in (`USD`).
+    pub currency: String,
+    pub prices: Vec<AssetPrice>,
+    /// The publisher's ML-DSA-87 public key.
+    pub public_key_hex: String,
+    /// ML-DSA-87 signature over every field above.
+    pub signature_hex: String,
+}
+
+/// A publisher whose snapshots this vault accepts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceSigner {
+    pub name: String,
+    pub public_key_hex: String,
+    pub fingerprint: KeyFingerprint,
+    pub added_at: DateTime<Utc>,
+}
+
+/// Trusted publishers and the latest imported snapshot. Encrypted under the
+/// session key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PriceStore {
+    #[serde(default)]
+    pub signers: Vec<PriceSigner>,
+    #[serde(default)]
+    pub snapshot: Option<PriceSnapshot>,
+    #[serde(default)]
+    pub imported_at: Option<DateTime<Utc>>,
+}
+
+/// The stored snapshot as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceSnapshotInfo {
+    pub created_at: DateTime<Utc>,
+    pub imported_at: Option<DateTime<Utc>>,
+    pub currency: String,
+    pub signer_name: String,
+    pub signer_fingerprint: KeyFingerprint,
+    pub prices: Vec<AssetPrice>,
+    pub age_hours: i64,
+    pub warnings: Vec<String>,
+}
+
+/// An amount of an asset valued with the stored snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FiatValue {
+    pub asset: String,
+    /// In base units.
+    pub amount: String,
+    /// Rounded down to two decimals (`1234.56`).
+    pub value: String,
+    pub currency: String,
+    /// When the price was published.
+    pub as_of: DateTime<Utc>,
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Id of the profile that predates profiles. Its files stay directly in the
+/// local data directory, so existing vaults open unchanged.
+pub const DEFAULT_PROFILE_ID: &str = "default";
+/// Subdirectory of the local data directory holding the other profiles.
+pub const PROFILES_DIR: &str = "profiles";
+/// Longest profile name accepted.
+pub const MAX_PROFILE_NAME_CHARS: usize = 64;
+
+/// An independent vault instance: its own files, master key and settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    /// `None` for the default profile.
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// `profiles.json` in the local data directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileStore {
+    pub active: String,
+    pub profiles: Vec<Profile>,
+}
+
+impl Default for ProfileStore {
+    fn default() -> Self {
+        ProfileStore {
+            active: DEFAULT_PROFILE_ID.to_string(),
+            profiles: vec![Profile {
+                id: DEFAULT_PROFILE_ID.to_string(),
+                name: "Default".to_string(),
+                created_at: None,
+            }],
+        }
+    }
+}
+
+impl ProfileStore {
+    pub fn get(&self, id: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.id == id)
+    }
+
+    /// Add a profile named `name`; names are unique ignoring case.
+    pub fn add(&mut self, name: &str, now: DateTime<Utc>) -> Result<Profile, String> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_PROFILE_NAME_CHARS {
+            return Err(format!(
+                "profile name must be 1 to {MAX_PROFILE_NAME_CHARS} characters"
+            ));
+        }
+        if self
+            .profiles
+            .iter()
+            .any(|p| p.name.to_lowercase() == name.to_lowercase())
+        {
+            return Err(format!("a profile named '{name}' already exists"));
+        }
+        let profile = Profile {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            created_at: Some(now),
+        };
+        self.profiles.push(profile.clone());
+        Ok(profile)
+    }
+
+    /// Repair a store read from disk: the default profile always exists and
+    /// an unknown active id falls back to it.
+    pub fn normalized(mut self) -> Self {
+        if self.get(DEFAULT_PROFILE_ID).is_none() {
+            self.profiles
+                .insert(0, ProfileStore::default().profiles.remove(0));
+        }
+        if self.get(&self.active).is_none() {
+            self.active = DEFAULT_PROFILE_ID.to_string();
+        }
+        self
+    }
+}
+
+/// A profile as `list_profiles` returns it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileInfo {
+    #[serde(flatten)]
+    pub profile: Profile,
+    pub active: bool,
+    /// Whether a vault has been created in the profile yet.
+    pub initialized: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_names_are_validated_and_unique() {
+        let mut store = ProfileStore::default();
+        let work = store.add("  Company  ", Utc::now()).unwrap();
+        assert_eq!(work.name, "Company");
+        assert_ne!(work.id, DEFAULT_PROFILE_ID);
+        assert!(store.add("company", Utc::now()).is_err());
+        assert!(store.add("default", Utc::now()).is_err());
+        assert!(store.add(" ", Utc::now()).is_err());
+        assert!(store.add(&"x".repeat(65), Utc::now()).is_err());
+        assert_eq!(store.get(&work.id), Some(&work));
+    }
+
+    #[test]
+    fn normalizing_restores_default_profile() {
+        let store = ProfileStore {
+            active: "gone".to_string(),
+            profiles: Vec::new(),
+        }
+        .normalized();
+        assert_eq!(store, ProfileStore::default());
+    }
+}
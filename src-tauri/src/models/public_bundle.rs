@@ -0,0 +1,207 @@
+use crate::crypto::fingerprint::KeyFingerprint;
+use crate::models::instance::InstanceSignature;
+use crate::models::key::{KeyEntry, KeyType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One key as a public bundle carries it: enough to show it, receive to it
+/// and check its signatures, nothing to sign with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicBundleKey {
+    pub key_id: String,
+    pub label: Option<String>,
+    pub key_type: KeyType,
+    /// Chain the address is on, as in address exports.
+    pub network: String,
+    pub address: String,
+    pub public_key_hex: String,
+    /// Empty for keys that were not HD-derived.
+    pub derivation_path: String,
+    pub account: u32,
+    pub index: u32,
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PublicBundleKey {
+    pub fn of(key: &KeyEntry, network: &str) -> Self {
+        let m = &key.metadata;
+        PublicBundleKey {
+            key_id: key.id.clone(),
+            label: m.label.clone(),
+            key_type: m.key_type.clone(),
+            network: network.to_string(),
+            address: m.address.clone(),
+            public_key_hex: key.public_key_hex.clone(),
+            derivation_path: m.derivation_path.clone(),
+            account: m.account,
+            index: m.index,
+            tags: m.tags.clone(),
+            created_at: m.created_at,
+        }
+    }
+}
+
+/// The public half of a vault's keys, for an accountant's or a watch-only
+/// setup on another vault instance. Contains no private material.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicBundle {
+    pub version: u32,
+    pub bundle_id: String,
+    /// Name of the profile the bundle was exported from.
+    pub source_name: String,
+    pub exported_at: DateTime<Utc>,
+    /// ML-DSA-87 public key of the vault identity that signs the document.
+    pub vault_public_key_hex: String,
+    pub keys: Vec<PublicBundleKey>,
+}
+
+/// A [`PublicBundle`] as written to disk: the exact JSON `document` that
+/// was signed, so importers check the signature over those bytes before
+/// parsing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedPublicBundle {
+    pub document: String,
+    pub signature_hex: String,
+    /// The exporting installation's signature over `document`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_signature: Option<InstanceSignature>,
+}
+
+/// What `export_public_bundle` wrote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicBundleSummary {
+    pub bundle_id: String,
+    pub keys: usize,
+    /// Fingerprint of the signing vault identity, to read out to whoever
+    /// imports the bundle.
+    pub fingerprint: KeyFingerprint,
+}
+
+/// Watch-only keys imported from one vault's bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchOnlySource {
+    pub id: String,
+    pub vault_public_key_hex: String,
+    pub source_name: String,
+    /// The imported bundle's id and export time.
+    pub bundle_id: String,
+    pub exported_at: DateTime<Utc>,
+    pub imported_at: DateTime<Utc>,
+    pub keys: Vec<PublicBundleKey>,
+}
+
+/// A source as listed, with the fingerprint to compare with the exporter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchOnlySourcePublic {
+    #[serde(flatten)]
+    pub source: WatchOnlySource,
+    pub fingerprint: KeyFingerprint,
+}
+
+impl WatchOnlySource {
+    pub fn to_public(&self) -> WatchOnlySourcePublic {
+        WatchOnlySourcePublic {
+            source: self.clone(),
+            fingerprint: KeyFingerprint::of_hex(&self.vault_public_key_hex),
+        }
+    }
+}
+
+/// Watch-only keys, sealed under the session key like recorded balances:
+/// public keys, but which addresses belong to whom is still private.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchOnlyStore {
+    pub sources: Vec<WatchOnlySource>,
+}
+
+impl WatchOnlyStore {
+    /// Add the keys of a verified `bundle`. A later bundle from the same
+    /// vault replaces the earlier import, keeping its id; an older one is
+    /// refused, so a stale copy cannot roll the view back.
+    pub fn import(
+        &mut self,
+        bundle: PublicBundle,
+        now: DateTime<Utc>,
+    ) -> Result<&WatchOnlySource, String> {
+        let existing = self
+            .sources
+            .iter()
+            .position(|s| s.vault_public_key_hex == bundle.vault_public_key_hex);
+        if let Some(i) = existing {
+            if bundle.exported_at < self.sources[i].exported_at {
+                return Err("a newer bundle from this vault is already imported".to_string());
+            }
+        }
+        let source = WatchOnlySource {
+            id: existing
+                .map(|i| self.sources[i].id.clone())
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            vault_public_key_hex: bundle.vault_public_key_hex,
+            source_name: bundle.source_name,
+            bundle_id: bundle.bundle_id,
+            exported_at: bundle.exported_at,
+            imported_at: now,
+            keys: bundle.keys,
+        };
+        let i = match existing {
+            Some(i) => {
+                self.sources[i] = source;
+                i
+            }
+            None => {
+                self.sources.push(source);
+                self.sources.len() - 1
+            }
+        };
+        Ok(&self.sources[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn bundle(vault: &str, exported_at: DateTime<Utc>, keys: usize) -> PublicBundle {
+        PublicBundle {
+            version: 1,
+            bundle_id: uuid::Uuid::new_v4().to_string(),
+            source_name: "Treasury".to_string(),
+            exported_at,
+            vault_public_key_hex: vault.to_string(),
+            keys: (0..keys)
+                .map(|i| PublicBundleKey {
+                    key_id: format!("k{i}"),
+                    label: None,
+                    key_type: KeyType::Treasury,
+                    network: "zap".to_string(),
+                    address: format!("zap1{i}"),
+                    public_key_hex: "ab".to_string(),
+                    derivation_path: format!("m/44'/9999'/0'/0'/{i}'"),
+                    account: 0,
+                    index: i as u32,
+                    tags: Vec::new(),
+                    created_at: exported_at,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn newer_bundle_replaces_and_older_is_refused() {
+        let now = Utc::now();
+        let mut store = WatchOnlyStore::default();
+        let id = store.import(bundle("aa", now, 1), now).unwrap().id.clone();
+        store.import(bundle("bb", now, 3), now).unwrap();
+
+        let later = now + Duration::hours(1);
+        let replaced = store.import(bundle("aa", later, 2), later).unwrap();
+        assert_eq!(replaced.id, id);
+        assert_eq!(replaced.keys.len(), 2);
+        assert_eq!(store.sources.len(), 2);
+
+        assert!(store.import(bundle("aa", now, 1), later).is_err());
+        assert_eq!(store.sources[0].keys.len(), 2);
+    }
+}
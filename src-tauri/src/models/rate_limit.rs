@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+/// Classes of command that handle secret material or destroy data, each with
+/// its own rate limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitiveOp {
+    /// Resolving a stored secret to sign with it.
+    Decrypt,
+    /// Handing secret material out of the vault.
+    Export,
+    Delete,
+}
+
+impl SensitiveOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SensitiveOp::Decrypt => "decrypt",
+            SensitiveOp::Export => "export",
+            SensitiveOp::Delete => "delete",
+        }
+    }
+}
+
+/// At most `max_calls` within any `window_secs` period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub max_calls: u32,
+    pub window_secs: u64,
+}
+
+/// Longest window a limit may be configured with (one day).
+pub const MAX_RATE_WINDOW_SECS: u64 = 24 * 60 * 60;
+
+impl RateLimit {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_calls == 0 {
+            return Err("max_calls must be at least 1".to_string());
+        }
+        if !(1..=MAX_RATE_WINDOW_SECS).contains(&self.window_secs) {
+            return Err(format!(
+                "window_secs must be between 1 and {MAX_RATE_WINDOW_SECS}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Per-operation limits, persisted in `vault.json`. The defaults leave room
+/// for normal interactive use and stop a compromised frontend from draining
+/// every key in a loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimits {
+    pub decrypt: RateLimit,
+    pub export: RateLimit,
+    pub delete: RateLimit,
+}
+
+impl RateLimits {
+    pub fn get(&self, op: SensitiveOp) -> RateLimit {
+        match op {
+            SensitiveOp::Decrypt => self.decrypt,
+            SensitiveOp::Export => self.export,
+            SensitiveOp::Delete => self.delete,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for op in [
+            SensitiveOp::Decrypt,
+            SensitiveOp::Export,
+            SensitiveOp::Delete,
+        ] {
+            self.get(op)
+                .validate()
+                .map_err(|e| format!("{}: {e}", op.as_str()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            decrypt: RateLimit {
+                max_calls: 30,
+                window_secs: 60,
+            },
+            export: RateLimit {
+                max_calls: 5,
+                window_secs: 60,
+            },
+            delete: RateLimit {
+                max_calls: 10,
+                window_secs: 60,
+            },
+        }
+    }
+}
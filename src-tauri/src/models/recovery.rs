@@ -0,0 +1,184 @@
+use crate::crypto::encryption::Ciphertext;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Codes issued per set.
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// One recovery code as kept in `vault.json`. Only a hash of the code is
+/// stored; with escrow, the set's recovery key is also sealed under the
+/// code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryCode {
+    pub id: String,
+    pub hash_hex: String,
+    /// The recovery key sealed under a key derived from the code. `None`
+    /// when the set was issued without escrow.
+    pub escrow: Option<Ciphertext>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+/// The escrow copy of the data key behind a set of codes. A random
+/// recovery key seals the data key, and every code seals the recovery key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryEscrow {
+    /// The data key, sealed under the recovery key. Sets issued before data
+    /// keys call it `vault_key`; it holds the key such a vault adopts.
+    #[serde(alias = "vault_key")]
+    pub data_key: Ciphertext,
+}
+
+/// The vault's current recovery codes. Issuing a new set replaces it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryCodeSet {
+    /// Mixed into every code's hash and key.
+    pub salt_hex: String,
+    pub created_at: DateTime<Utc>,
+    pub codes: Vec<RecoveryCode>,
+    /// `None` when the set was issued without escrow.
+    pub escrow: Option<RecoveryEscrow>,
+}
+
+impl RecoveryCodeSet {
+    /// Codes not used yet.
+    pub fn remaining(&self) -> usize {
+        self.codes.iter().filter(|c| c.used_at.is_none()).count()
+    }
+
+    /// Whether the codes carry an escrow copy of the data key.
+    pub fn escrowed(&self) -> bool {
+        self.escrow.is_some() && self.codes.iter().all(|c| c.escrow.is_some())
+    }
+
+    /// Remove the escrow copy and every code's sealed recovery key, so the
+    /// codes can no longer open the data key. Returns whether there was any.
+    pub fn drop_escrow(&mut self) -> bool {
+        let had = self.escrow.take().is_some();
+        self.codes
+            .iter_mut()
+            .fold(had, |had, c| c.escrow.take().is_some() || had)
+    }
+
+    /// The unused code whose hash is `hash_hex`.
+    pub fn find_unused(&self, hash_hex: &str) -> Option<&RecoveryCode> {
+        self.codes
+            .iter()
+            .find(|c| c.used_at.is_none() && c.hash_hex == hash_hex)
+    }
+
+    /// Mark the code `id` used, so it cannot recover the vault again.
+    pub fn consume(&mut self, id: &str, now: DateTime<Utc>) -> bool {
+        match self
+            .codes
+            .iter_mut()
+            .find(|c| c.id == id && c.used_at.is_none())
+        {
+            Some(code) => {
+                code.used_at = Some(now);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// What `recovery_codes_status` returns. Never the codes themselves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryCodesStatus {
+    pub configured: bool,
+    pub remaining: usize,
+    pub escrowed: bool,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl RecoveryCodesStatus {
+    pub fn of(set: Option<&RecoveryCodeSet>) -> Self {
+        RecoveryCodesStatus {
+            configured: set.is_some(),
+            remaining: set.map_or(0, RecoveryCodeSet::remaining),
+            escrowed: set.is_some_and(RecoveryCodeSet::escrowed),
+            created_at: set.map(|s| s.created_at),
+        }
+    }
+}
+
+/// Data sealed under the data key, which a forgotten password leaves
+/// unreadable when no escrow copy exists.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnrecoverableData {
+    /// What the data is, such as `keystore` or `balances.json`.
+    pub name: String,
+    /// File in the data directory, if it has its own.
+    pub file: Option<String>,
+    /// How to get it back another way, when there is one.
+    pub note: Option<String>,
+}
+
+/// Outcome of `recover_account`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryReport {
+    /// Whether the vault now opens with the new password.
+    pub recovered: bool,
+    /// Unused codes left after this one.
+    pub codes_remaining: usize,
+    /// Empty when `recovered`. Otherwise what cannot be opened without the
+    /// old password. Nothing is changed or deleted either way.
+    pub unrecoverable: Vec<UnrecoverableData>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(id: &str, hash: &str) -> RecoveryCode {
+        RecoveryCode {
+            id: id.to_string(),
+            hash_hex: hash.to_string(),
+            escrow: None,
+            used_at: None,
+        }
+    }
+
+    #[test]
+    fn a_code_recovers_once() {
+        let now = Utc::now();
+        let mut set = RecoveryCodeSet {
+            salt_hex: "00".to_string(),
+            created_at: now,
+            codes: vec![code("a", "aa"), code("b", "bb")],
+            escrow: None,
+        };
+        assert_eq!(set.find_unused("bb").unwrap().id, "b");
+        assert!(set.consume("b", now));
+        assert!(set.find_unused("bb").is_none());
+        assert!(!set.consume("b", now));
+        assert_eq!(set.remaining(), 1);
+
+        let status = RecoveryCodesStatus::of(Some(&set));
+        assert!(status.configured && !status.escrowed);
+        assert!(!RecoveryCodesStatus::of(None).configured);
+    }
+
+    #[test]
+    fn dropping_escrow_clears_every_sealed_copy() {
+        let sealed = || Ciphertext {
+            nonce: vec![0u8; 24],
+            ciphertext: vec![1u8; 48],
+        };
+        let mut set = RecoveryCodeSet {
+            salt_hex: "00".to_string(),
+            created_at: Utc::now(),
+            codes: vec![code("a", "aa"), code("b", "bb")],
+            escrow: Some(RecoveryEscrow { data_key: sealed() }),
+        };
+        for c in &mut set.codes {
+            c.escrow = Some(sealed());
+        }
+        assert!(set.escrowed());
+        assert!(set.drop_escrow());
+        assert!(!set.escrowed());
+        assert!(set.escrow.is_none() && set.codes.iter().all(|c| c.escrow.is_none()));
+        assert!(!set.drop_escrow());
+        assert_eq!(set.remaining(), 2);
+    }
+}
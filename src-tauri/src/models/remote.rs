@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How a remote target is reached. The vault ships no network stack, so
+/// SFTP, S3-compatible and WebDAV endpoints are used through an OS-level
+/// mount (sshfs, rclone mount, davfs2) and written to like a directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RemoteTargetKind {
+    MountedDirectory { path: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteTarget {
+    pub id: String,
+    pub name: String,
+    pub target: RemoteTargetKind,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One backup as recorded in a target's `catalog.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteCatalogEntry {
+    pub backup_id: String,
+    pub source_drive_id: String,
+    pub backup_created_at: DateTime<Utc>,
+    pub uploaded_at: DateTime<Utc>,
+    pub total_bytes: u64,
+    /// Bytes actually sent; objects the target already had are skipped.
+    pub uploaded_bytes: u64,
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// Not applicable on this platform or not determinable; left out of the
+    /// score.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTestCheckId {
+    StoreEncryption,
+    KdfParameters,
+    Swap,
+    CoreDumps,
+    DataDirPermissions,
+    OutdatedFormats,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelfTestCheck {
+    pub id: SelfTestCheckId,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to do about a `Warn` or `Fail`.
+    pub remediation: Option<String>,
+}
+
+impl SelfTestCheck {
+    pub fn pass(id: SelfTestCheckId, detail: impl Into<String>) -> Self {
+        SelfTestCheck {
+            id,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            remediation: None,
+        }
+    }
+
+    pub fn skipped(id: SelfTestCheckId, detail: impl Into<String>) -> Self {
+        SelfTestCheck {
+            id,
+            status: CheckStatus::Skipped,
+            detail: detail.into(),
+            remediation: None,
+        }
+    }
+
+    pub fn problem(
+        id: SelfTestCheckId,
+        status: CheckStatus,
+        detail: impl Into<String>,
+        remediation: impl Into<String>,
+    ) -> Self {
+        SelfTestCheck {
+            id,
+            status,
+            detail: detail.into(),
+            remediation: Some(remediation.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    /// 0–100: a passed check counts fully, a warning half, a failure not at
+    /// all; skipped checks are left out.
+    pub score: u8,
+    pub ran_at: DateTime<Utc>,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    pub fn new(checks: Vec<SelfTestCheck>, ran_at: DateTime<Utc>) -> Self {
+        let (earned, possible) = checks
+            .iter()
+            .fold((0u32, 0u32), |(earned, possible), c| match c.status {
+                CheckStatus::Pass => (earned + 2, possible + 2),
+                CheckStatus::Warn => (earned + 1, possible + 2),
+                CheckStatus::Fail => (earned, possible + 2),
+                CheckStatus::Skipped => (earned, possible),
+            });
+        let score = (earned * 100).checked_div(possible).unwrap_or(100) as u8;
+        SelfTestReport {
+            score,
+            ran_at,
+            checks,
+        }
+    }
+}
+
+/// Active swap devices and files listed in `/proc/swaps`.
+pub fn parse_swaps(proc_swaps: &str) -> Vec<String> {
+    proc_swaps
+        .lines()
+        .skip(1)
+        .filter_map(|l| l.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether a swap device keeps its contents off disk in the clear: zram
+/// lives in memory, and device-mapper targets are treated as dm-crypt.
+pub fn is_protected_swap(device: &str) -> bool {
+    device.starts_with("/dev/zram")
+        || device.starts_with("/dev/dm-")
+        || device.starts_with("/dev/mapper/")
+}
+
+/// The soft core-file size limit from `/proc/self/limits`: `Some(None)` for
+/// unlimited, `None` if the line is missing or unreadable.
+pub fn core_limit(proc_limits: &str) -> Option<Option<u64>> {
+    let line = proc_limits
+        .lines()
+        .find(|l| l.starts_with("Max core file size"))?;
+    let soft = line
+        .trim_start_matches("Max core file size")
+        .split_whitespace()
+        .next()?;
+    match soft {
+        "unlimited" => Some(None),
+        n => n.parse().ok().map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_weights_statuses_and_ignores_skipped() {
+        let check = |status| SelfTestCheck {
+            id: SelfTestCheckId::Swap,
+            status,
+            detail: String::new(),
+            remediation: None,
+        };
+        let now = Utc::now();
+        let report = SelfTestReport::new(
+            vec![
+                check(CheckStatus::Pass),
+                check(CheckStatus::Warn),
+                check(CheckStatus::Fail),
+                check(CheckStatus::Skipped),
+            ],
+            now,
+        );
+        assert_eq!(report.score, 50);
+        assert_eq!(SelfTestReport::new(Vec::new(), now).score, 100);
+    }
+
+    #[test]
+    fn parses_proc_swaps_and_limits() {
+        let swaps = "Filename\tType\tSize\tUsed\tPriority\n\
+                     /dev/zram0 partition 8388604 0 100\n\
+                     /swapfile file 2097148 0 -2\n";
+        let devices = parse_swaps(swaps);
+        assert_eq!(devices, ["/dev/zram0", "/swapfile"]);
+        assert!(is_protected_swap(&devices[0]));
+        assert!(!is_protected_swap(&devices[1]));
+        assert!(parse_swaps("Filename Type Size Used Priority\n").is_empty());
+
+        let limits = "Limit                     Soft Limit           Hard Limit           Units\n\
+                      Max core file size        0                    unlimited            bytes\n";
+        assert_eq!(core_limit(limits), Some(Some(0)));
+        let unlimited =
+            "Max core file size        unlimited            unlimited            bytes\n";
+        assert_eq!(core_limit(unlimited), Some(None));
+        assert_eq!(core_limit(""), None);
+    }
+}
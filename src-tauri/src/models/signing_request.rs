@@ -0,0 +1,176 @@
+use crate::crypto::encryption::Ciphertext;
+use crate::crypto::fingerprint::KeyFingerprint;
+use crate::models::contact::ContactCard;
+use serde::{Deserialize, Serialize};
+
+/// Shortest lifetime a signing request may be given.
+pub const MIN_REQUEST_TTL_SECS: u64 = 60;
+/// Longest lifetime a signing request may be given (seven days), so a
+/// request carried to an air-gapped machine by hand still arrives in time.
+pub const MAX_REQUEST_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// What a signing request carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SigningPayloadKind {
+    /// A BIP-174 partially signed Bitcoin transaction.
+    Psbt,
+    /// An RLP-encoded unsigned Ethereum transaction.
+    EthereumTx,
+    /// A protobuf-encoded Cosmos `SignDoc`.
+    CosmosSignDoc,
+    /// Anything else, signed as raw bytes.
+    Raw,
+}
+
+impl SigningPayloadKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SigningPayloadKind::Psbt => "psbt",
+            SigningPayloadKind::EthereumTx => "ethereum_tx",
+            SigningPayloadKind::CosmosSignDoc => "cosmos_sign_doc",
+            SigningPayloadKind::Raw => "raw",
+        }
+    }
+}
+
+/// The decrypted body of a request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningRequestBody {
+    pub kind: SigningPayloadKind,
+    pub payload_hex: String,
+    /// Free text from the requester, shown next to the payload.
+    #[serde(default)]
+    pub description: String,
+    /// Key the requester expects to sign with, if it knows.
+    #[serde(default)]
+    pub key_id: Option<String>,
+}
+
+/// The decrypted body of a response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningResponseBody {
+    pub approved: bool,
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Key that signed, when approved.
+    #[serde(default)]
+    pub public_key_hex: Option<String>,
+    /// Signature or signed payload, when approved.
+    #[serde(default)]
+    pub result_hex: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvelopeKind {
+    Request,
+    Response,
+}
+
+impl EnvelopeKind {
+    /// Stable tag bound into the envelope signature.
+    pub fn tag(&self) -> u8 {
+        match self {
+            EnvelopeKind::Request => 1,
+            EnvelopeKind::Response => 2,
+        }
+    }
+}
+
+/// A request or response sealed to one vault identity and signed by the
+/// sender's, small enough to move as a file or a QR code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningEnvelope {
+    pub version: u32,
+    pub kind: EnvelopeKind,
+    pub envelope_id: String,
+    /// For a response, the `envelope_id` of the request it answers.
+    #[serde(default)]
+    pub in_reply_to: Option<String>,
+    pub sender: ContactCard,
+    /// Hex fingerprint of the recipient's contact card.
+    pub recipient_fingerprint: String,
+    /// Unix seconds.
+    pub created_at: u64,
+    /// Unix seconds; the envelope is refused after this.
+    pub expires_at: u64,
+    pub x25519_ephemeral_hex: String,
+    pub kem_ciphertext_hex: String,
+    /// XChaCha20-Poly1305 encrypted body.
+    pub payload: Ciphertext,
+    /// ML-DSA-87 signature by the sender's vault identity over every field
+    /// above.
+    pub signature_hex: String,
+}
+
+/// A request this vault sent and is waiting on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentSigningRequest {
+    pub envelope_id: String,
+    pub recipient_fingerprint: String,
+    pub kind: SigningPayloadKind,
+    pub created_at: u64,
+    pub expires_at: u64,
+    /// Unix seconds the response was imported.
+    #[serde(default)]
+    pub answered_at: Option<u64>,
+}
+
+/// A request this vault received.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceivedSigningRequest {
+    pub envelope_id: String,
+    pub sender: ContactCard,
+    pub body: SigningRequestBody,
+    pub received_at: u64,
+    pub expires_at: u64,
+    /// Unix seconds a response was exported.
+    #[serde(default)]
+    pub answered_at: Option<u64>,
+}
+
+/// Requests sent and received, plus every envelope id ever accepted, so a
+/// replayed envelope is refused even after its request is forgotten.
+/// Encrypted under the session key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SigningRequestLedger {
+    #[serde(default)]
+    pub sent: Vec<SentSigningRequest>,
+    #[serde(default)]
+    pub received: Vec<ReceivedSigningRequest>,
+    #[serde(default)]
+    pub seen_envelopes: Vec<String>,
+}
+
+/// A received request as shown for review.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenedSigningRequest {
+    pub envelope_id: String,
+    pub sender_name: String,
+    pub sender_fingerprint: KeyFingerprint,
+    /// Imported contact with the same identity, if any.
+    pub sender_contact_id: Option<String>,
+    pub sender_verified: bool,
+    pub body: SigningRequestBody,
+    pub expires_at: u64,
+    pub answered: bool,
+}
+
+/// An imported response, matched to the request it answers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenedSigningResponse {
+    pub request_id: String,
+    pub sender_name: String,
+    pub sender_fingerprint: KeyFingerprint,
+    pub body: SigningResponseBody,
+}
+
+/// An exported envelope: the JSON to show as a QR code, and where it was
+/// written if a path was given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedEnvelope {
+    pub envelope_id: String,
+    pub envelope_json: String,
+    pub path: Option<String>,
+}
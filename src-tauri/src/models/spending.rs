@@ -0,0 +1,157 @@
+use crate::crypto::encryption::Ciphertext;
+use crate::models::mobile::BundleKdf;
+use crate::models::price::FiatValue;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How long a spend approval stays usable once requested.
+pub const APPROVAL_TTL_HOURS: i64 = 24;
+
+/// Limits on what one stored key may sign. Amounts are decimal strings in
+/// the base unit of the transaction (sats, wei, the Cosmos denomination), so
+/// a wei amount does not lose precision on its way through the UI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendingPolicy {
+    pub key_id: String,
+    #[serde(default)]
+    pub max_per_tx: Option<String>,
+    /// Over any rolling 24 hours.
+    #[serde(default)]
+    pub max_per_day: Option<String>,
+    /// Output scripts (hex), Ethereum addresses or Cosmos addresses a
+    /// signature may send to. Empty allows any destination.
+    #[serde(default)]
+    pub allowed_destinations: Vec<String>,
+    /// Ticker of the asset the amounts are in (`BTC`), for showing them in
+    /// fiat from the stored price snapshot.
+    #[serde(default)]
+    pub asset: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A second person who can co-approve signatures above a key's limits. They
+/// prove themselves with their own passphrase, not the vault password.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Approver {
+    pub id: String,
+    pub name: String,
+    pub salt_hex: String,
+    pub kdf: BundleKdf,
+    /// A fixed string encrypted under the key the passphrase derives.
+    pub verifier: Ciphertext,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An approver as listed in the UI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApproverInfo {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&Approver> for ApproverInfo {
+    fn from(a: &Approver) -> Self {
+        ApproverInfo {
+            id: a.id.clone(),
+            name: a.name.clone(),
+            created_at: a.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Used,
+    Expired,
+}
+
+/// A signature that went over a key's policy, waiting for (or holding) an
+/// approver's co-approval. It covers one reviewed transaction, by its
+/// summary hash, and is used once.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendApproval {
+    pub id: String,
+    pub key_id: String,
+    pub summary_hash_hex: String,
+    /// `None` when the amount could not be measured.
+    pub amount: Option<String>,
+    /// The amount in fiat, when the policy names its asset and a price
+    /// snapshot is stored.
+    #[serde(default)]
+    pub fiat_value: Option<FiatValue>,
+    pub destinations: Vec<String>,
+    /// Why the policy did not allow the signature on its own.
+    pub reasons: Vec<String>,
+    pub requested_at: DateTime<Utc>,
+    #[serde(default)]
+    pub approved_by: Option<String>,
+    #[serde(default)]
+    pub approved_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub used_at: Option<DateTime<Utc>>,
+    /// When the orphan sweep found its key gone. The row is kept as the
+    /// record of the co-approval.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orphaned_at: Option<DateTime<Utc>>,
+}
+
+impl SpendApproval {
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.requested_at + Duration::hours(APPROVAL_TTL_HOURS)
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> ApprovalStatus {
+        if self.used_at.is_some() {
+            ApprovalStatus::Used
+        } else if now >= self.expires_at() {
+            ApprovalStatus::Expired
+        } else if self.approved_at.is_some() {
+            ApprovalStatus::Approved
+        } else {
+            ApprovalStatus::Pending
+        }
+    }
+}
+
+/// An approval as listed in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendApprovalView {
+    #[serde(flatten)]
+    pub approval: SpendApproval,
+    pub status: ApprovalStatus,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A signature counted against a key's daily limit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendRecord {
+    pub key_id: String,
+    pub amount: String,
+    pub signed_at: DateTime<Utc>,
+}
+
+/// Policies, approvers, the approvals table and the last day of spends.
+/// Encrypted under the session key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpendingStore {
+    #[serde(default)]
+    pub policies: Vec<SpendingPolicy>,
+    #[serde(default)]
+    pub approvers: Vec<Approver>,
+    #[serde(default)]
+    pub approvals: Vec<SpendApproval>,
+    #[serde(default)]
+    pub history: Vec<SpendRecord>,
+}
+
+/// An approver proving who they are, for a co-approval or a change to
+/// policies or approvers.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApproverAuth {
+    pub approver_id: String,
+    pub passphrase: String,
+}
@@ -0,0 +1,132 @@
+use crate::crypto::encryption::Ciphertext;
+use crate::models::instance::InstanceSignature;
+use crate::models::item::VaultItem;
+use crate::models::key::KeyEntry;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A sync package as written to removable media: the encrypted delta plus
+/// everything needed to check who produced it and in which order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPackage {
+    pub version: u32,
+    /// Installation that exported the package.
+    pub device_id: String,
+    /// Per-installation export counter; importers only accept increasing values.
+    pub sequence: u64,
+    pub created_at: DateTime<Utc>,
+    /// Whether this is a full snapshot rather than a delta.
+    pub full: bool,
+    /// XChaCha20-Poly1305 encrypted [`SyncDelta`].
+    pub payload: Ciphertext,
+    pub public_key_hex: String,
+    pub signature_hex: String,
+    /// The exporting installation's signature over the same message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_signature: Option<InstanceSignature>,
+}
+
+/// Decrypted package contents: entries added or changed since the last sync,
+/// and ids removed since then.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncDelta {
+    pub keys: Vec<KeyEntry>,
+    pub items: Vec<VaultItem>,
+    pub deleted_keys: Vec<String>,
+    pub deleted_items: Vec<String>,
+}
+
+/// Replication bookkeeping for this installation. Plaintext next to
+/// `vault.json`; the fingerprints are keyed hashes and reveal nothing about
+/// the entries without the master seed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncState {
+    pub device_id: String,
+    /// Sequence number of the last exported package.
+    pub sequence: u64,
+    /// Fingerprint of every entry as both installations last agreed on it,
+    /// keyed `key:<id>` / `item:<id>`. Exports send what differs from it.
+    pub known: BTreeMap<String, String>,
+    /// Highest sequence applied per exporting installation.
+    pub imported: BTreeMap<String, u64>,
+    /// Instance key fingerprint per exporting installation, pinned from its
+    /// first signed package.
+    #[serde(default)]
+    pub instance_keys: BTreeMap<String, String>,
+    pub last_export_at: Option<DateTime<Utc>>,
+    pub last_import_at: Option<DateTime<Utc>>,
+}
+
+impl SyncState {
+    pub fn new() -> Self {
+        Self {
+            device_id: uuid::Uuid::new_v4().to_string(),
+            sequence: 0,
+            known: BTreeMap::new(),
+            imported: BTreeMap::new(),
+            instance_keys: BTreeMap::new(),
+            last_export_at: None,
+            last_import_at: None,
+        }
+    }
+}
+
+impl Default for SyncState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What to do when both installations changed the same entry since they last
+/// synced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    KeepLocal,
+    TakeIncoming,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncEntryKind {
+    Key,
+    Item,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub kind: SyncEntryKind,
+    pub id: String,
+    pub label: Option<String>,
+    /// The incoming package deleted an entry that was edited locally.
+    pub deleted_remotely: bool,
+    pub resolution: ConflictPolicy,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncExportSummary {
+    pub sequence: u64,
+    pub full: bool,
+    pub keys: usize,
+    pub items: usize,
+    pub deleted: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncImportReport {
+    pub device_id: String,
+    pub sequence: u64,
+    pub added: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+    /// Packages from this installation that were never imported here. Their
+    /// changes are missing until the other side exports a full package.
+    pub skipped_packages: u64,
+    pub conflicts: Vec<SyncConflict>,
+    /// Key fingerprint of the installation that signed the package, if it
+    /// was signed.
+    #[serde(default)]
+    pub instance_fingerprint: Option<String>,
+}
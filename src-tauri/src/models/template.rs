@@ -0,0 +1,227 @@
+use crate::models::key::KeyEntry;
+use crate::models::keyset::{plan_keyset, Keyset, KeysetMemberSpec, KeysetRequest};
+use crate::models::metadata::{MetadataUpdate, MAX_DESCRIPTION_LEN, MAX_LABEL_LEN};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on the number of keysets one template may provision.
+pub const MAX_TEMPLATE_KEYSETS: usize = 32;
+
+/// One keyset of a template, e.g. "Validators": its members plus the tags
+/// every generated key receives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateKeyset {
+    pub name: String,
+    pub account: u32,
+    pub members: Vec<KeysetMemberSpec>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// A template as submitted for saving.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeysetTemplateDraft {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub keysets: Vec<TemplateKeyset>,
+}
+
+/// A reusable vault layout ("Treasury", "Validators", "Infrastructure", ...)
+/// that provisions several keysets in one step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeysetTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub keysets: Vec<TemplateKeyset>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Validate `draft` and turn it into a template with trimmed names and
+/// normalized tags.
+pub fn build_template(
+    draft: &KeysetTemplateDraft,
+    now: DateTime<Utc>,
+) -> Result<KeysetTemplate, String> {
+    let name = draft.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_LABEL_LEN {
+        return Err(format!(
+            "template name must be 1 to {MAX_LABEL_LEN} characters"
+        ));
+    }
+    let description = draft
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+    if description.is_some_and(|d| d.chars().count() > MAX_DESCRIPTION_LEN) {
+        return Err(format!(
+            "template description longer than {MAX_DESCRIPTION_LEN} characters"
+        ));
+    }
+    if draft.keysets.is_empty() || draft.keysets.len() > MAX_TEMPLATE_KEYSETS {
+        return Err(format!(
+            "a template needs 1 to {MAX_TEMPLATE_KEYSETS} keysets"
+        ));
+    }
+
+    let mut keysets: Vec<TemplateKeyset> = Vec::with_capacity(draft.keysets.len());
+    for keyset in &draft.keysets {
+        let keyset_name = keyset.name.trim();
+        if keyset_name.is_empty() || keyset_name.chars().count() > MAX_LABEL_LEN {
+            return Err(format!(
+                "keyset names must be 1 to {MAX_LABEL_LEN} characters"
+            ));
+        }
+        if keysets
+            .iter()
+            .any(|k| k.name.eq_ignore_ascii_case(keyset_name))
+        {
+            return Err(format!("duplicate keyset name: {keyset_name}"));
+        }
+        if keyset.members.is_empty() {
+            return Err(format!("keyset {keyset_name} needs at least one member"));
+        }
+        let mut tags = Vec::new();
+        MetadataUpdate {
+            tags: Some(keyset.tags.clone()),
+            ..Default::default()
+        }
+        .apply(&mut None, &mut None, &mut tags)?;
+        keysets.push(TemplateKeyset {
+            name: keyset_name.to_string(),
+            account: keyset.account,
+            members: keyset.members.clone(),
+            tags,
+        });
+    }
+
+    Ok(KeysetTemplate {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        description: description.map(str::to_string),
+        keysets,
+        created_at: now,
+    })
+}
+
+/// The idempotency key of the `position`th keyset provisioned by a template
+/// run started with `idempotency_key`.
+pub fn template_keyset_key(idempotency_key: &str, position: usize) -> String {
+    format!("{idempotency_key}#{position}")
+}
+
+/// Plan every keyset of `template`. Each keyset is planned as if the keys of
+/// the ones before it were already stored, so the keysets never claim the
+/// same derivation path and the whole run respects the gap limit.
+pub fn plan_template(
+    keys: &[KeyEntry],
+    template: &KeysetTemplate,
+    idempotency_key: &str,
+    gap_limit: u32,
+    now: DateTime<Utc>,
+) -> Result<Vec<Keyset>, String> {
+    if idempotency_key.trim().is_empty() {
+        return Err("idempotency key must not be empty".to_string());
+    }
+    let mut scratch = keys.to_vec();
+    let mut planned = Vec::with_capacity(template.keysets.len());
+    for (position, spec) in template.keysets.iter().enumerate() {
+        let request = KeysetRequest {
+            idempotency_key: template_keyset_key(idempotency_key, position),
+            name: spec.name.clone(),
+            account: spec.account,
+            members: spec.members.clone(),
+        };
+        let mut keyset = plan_keyset(&scratch, &request, gap_limit, now)
+            .map_err(|e| format!("keyset {}: {e}", spec.name))?;
+        keyset.tags = spec.tags.clone();
+        keyset.template_id = Some(template.id.clone());
+        scratch.extend(keyset.planned.iter().map(|p| {
+            KeyEntry::new(
+                p.key_type.clone(),
+                p.purpose,
+                p.account,
+                p.index,
+                "",
+                "",
+                "",
+                &p.derivation_path,
+            )
+        }));
+        planned.push(keyset);
+    }
+    Ok(planned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(key_type: &str, purpose: u32, count: u32) -> KeysetMemberSpec {
+        KeysetMemberSpec {
+            key_type: key_type.to_string(),
+            purpose,
+            count,
+        }
+    }
+
+    fn draft() -> KeysetTemplateDraft {
+        KeysetTemplateDraft {
+            name: " Enterprise ".to_string(),
+            description: Some("  ".to_string()),
+            keysets: vec![
+                TemplateKeyset {
+                    name: "Treasury".to_string(),
+                    account: 0,
+                    members: vec![member("treasury", 2, 2)],
+                    tags: vec![" Cold ".to_string(), "cold".to_string()],
+                },
+                TemplateKeyset {
+                    name: "Validators".to_string(),
+                    account: 0,
+                    members: vec![member("validator", 1, 3), member("treasury", 2, 1)],
+                    tags: vec!["staking".to_string()],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn build_normalizes_and_rejects_bad_drafts() {
+        let template = build_template(&draft(), Utc::now()).unwrap();
+        assert_eq!(template.name, "Enterprise");
+        assert_eq!(template.description, None);
+        assert_eq!(template.keysets[0].tags, ["cold"]);
+
+        let mut dup = draft();
+        dup.keysets[1].name = "treasury".to_string();
+        assert!(build_template(&dup, Utc::now()).is_err());
+
+        let mut empty = draft();
+        empty.keysets[0].members.clear();
+        assert!(build_template(&empty, Utc::now()).is_err());
+
+        let mut none = draft();
+        none.keysets.clear();
+        assert!(build_template(&none, Utc::now()).is_err());
+    }
+
+    #[test]
+    fn plan_keeps_keysets_on_distinct_paths() {
+        let template = build_template(&draft(), Utc::now()).unwrap();
+        let sets = plan_template(&[], &template, "run-1", 20, Utc::now()).unwrap();
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[1].idempotency_key, "run-1#1");
+        assert_eq!(sets[1].template_id.as_deref(), Some(template.id.as_str()));
+        let treasury: Vec<u32> = sets
+            .iter()
+            .flat_map(|s| &s.planned)
+            .filter(|p| p.purpose == 2)
+            .map(|p| p.index)
+            .collect();
+        assert_eq!(treasury, [0, 1, 2]);
+        assert!(plan_template(&[], &template, " ", 20, Utc::now()).is_err());
+    }
+}
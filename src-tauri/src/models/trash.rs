@@ -0,0 +1,61 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Longest retention period that can be configured (ten years).
+pub const MAX_TRASH_RETENTION_DAYS: u32 = 3650;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashKind {
+    Key,
+    Item,
+}
+
+/// A trashed key or item as listed, restored or purged. Never carries secret
+/// material.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashedEntry {
+    pub kind: TrashKind,
+    pub id: String,
+    pub label: Option<String>,
+    pub trashed_at: DateTime<Utc>,
+    /// When the retention setting lets the background purge destroy it.
+    pub purge_after: Option<DateTime<Utc>>,
+}
+
+pub fn validate_retention_days(days: u32) -> Result<(), String> {
+    if !(1..=MAX_TRASH_RETENTION_DAYS).contains(&days) {
+        return Err(format!(
+            "trash retention must be between 1 and {MAX_TRASH_RETENTION_DAYS} days"
+        ));
+    }
+    Ok(())
+}
+
+/// When an entry trashed at `trashed_at` becomes purgeable under
+/// `retention_days`.
+pub fn purge_after(
+    trashed_at: DateTime<Utc>,
+    retention_days: Option<u32>,
+) -> Option<DateTime<Utc>> {
+    retention_days.map(|d| trashed_at + Duration::days(d.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retention_bounds() {
+        assert!(validate_retention_days(0).is_err());
+        assert!(validate_retention_days(30).is_ok());
+        assert!(validate_retention_days(MAX_TRASH_RETENTION_DAYS + 1).is_err());
+    }
+
+    #[test]
+    fn purge_after_follows_retention() {
+        let t = Utc::now();
+        assert_eq!(purge_after(t, None), None);
+        assert_eq!(purge_after(t, Some(7)), Some(t + Duration::days(7)));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::crypto::threshold::ThresholdSignature;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An M-of-N signing policy over stored treasury keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreasuryPolicy {
+    pub id: String,
+    pub name: String,
+    /// Signatures required (M).
+    pub threshold: usize,
+    /// The N signers. Public keys are copied from the keystore so shares and
+    /// bundles can be verified while the vault is locked.
+    pub signers: Vec<TreasurySigner>,
+    pub created_at: DateTime<Utc>,
+    /// Signer key ids the orphan sweep found gone from the keystore. They
+    /// stay in `signers`, since the policy terms every share signs name
+    /// them; only the remaining signers can still sign.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub orphaned_signers: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreasurySigner {
+    pub key_id: String,
+    pub public_key_hex: String,
+}
+
+/// One signer's partial signature over a proposal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreasuryShare {
+    pub key_id: String,
+    pub signature_hex: String,
+    pub signed_at: DateTime<Utc>,
+}
+
+/// A payload (e.g. an encoded treasury transaction) awaiting M-of-N approval.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreasuryProposal {
+    pub id: String,
+    pub policy_id: String,
+    pub description: String,
+    pub payload_hex: String,
+    pub shares: Vec<TreasuryShare>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Treasury policies and proposals, stored together in plaintext metadata.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TreasuryState {
+    #[serde(default)]
+    pub policies: Vec<TreasuryPolicy>,
+    #[serde(default)]
+    pub proposals: Vec<TreasuryProposal>,
+}
+
+/// Signing progress of a proposal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreasuryProposalStatus {
+    pub proposal: TreasuryProposal,
+    pub threshold: usize,
+    pub signer_count: usize,
+    /// Whether enough shares are in to combine the signature.
+    pub ready: bool,
+}
+
+/// The combined M-of-N signature over a proposal, for on-chain submission.
+/// `message_hex` is the exact byte string every share signs (the policy terms
+/// plus `payload_hex`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreasurySignatureBundle {
+    pub policy_id: String,
+    pub proposal_id: String,
+    pub threshold: usize,
+    pub signers: Vec<TreasurySigner>,
+    pub payload_hex: String,
+    pub message_hex: String,
+    pub signature: ThresholdSignature,
+}
@@ -0,0 +1,143 @@
+use crate::models::signing_request::SigningPayloadKind;
+use serde::{Deserialize, Serialize};
+
+/// A named value pulled out of a payload, already rendered for display.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedField {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PsbtInput {
+    /// Previous transaction id, in the byte order block explorers show.
+    pub txid: String,
+    pub vout: u32,
+    pub sequence: u32,
+    /// From the input's witness or non-witness UTXO, when the PSBT has one.
+    pub amount_sats: Option<u64>,
+    pub script_type: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PsbtOutput {
+    pub amount_sats: u64,
+    /// `p2tr`, `p2wpkh`, `p2wsh`, `p2pkh`, `p2sh`, `op_return` or `unknown`.
+    pub script_type: String,
+    pub script_hex: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PsbtSummary {
+    pub tx_version: i32,
+    pub lock_time: u32,
+    pub inputs: Vec<PsbtInput>,
+    pub outputs: Vec<PsbtOutput>,
+    /// `None` unless every input carries its UTXO.
+    pub total_in_sats: Option<u64>,
+    pub total_out_sats: u64,
+    pub fee_sats: Option<u64>,
+}
+
+/// A contract call decoded against the built-in and local ABI registry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EthereumCall {
+    /// `0x` and four bytes.
+    pub selector: String,
+    /// `transfer(address,uint256)`, when the selector is known.
+    pub signature: Option<String>,
+    pub args: Vec<DecodedField>,
+    pub data_len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EthereumTxSummary {
+    /// 0 legacy, 1 EIP-2930, 2 EIP-1559.
+    pub tx_type: u8,
+    pub chain_id: Option<u64>,
+    pub nonce: u64,
+    /// `0x`-prefixed; `None` deploys a contract.
+    pub to: Option<String>,
+    /// Decimal wei.
+    pub value_wei: String,
+    pub gas_limit: u64,
+    /// Decimal wei; legacy and EIP-2930 only.
+    pub gas_price_wei: Option<String>,
+    /// Decimal wei; EIP-1559 only.
+    pub max_fee_per_gas_wei: Option<String>,
+    pub max_priority_fee_per_gas_wei: Option<String>,
+    /// Most the transaction can pay in gas, in decimal wei.
+    pub max_gas_cost_wei: Option<String>,
+    pub call: Option<EthereumCall>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CosmosCoin {
+    pub denom: String,
+    pub amount: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CosmosMessage {
+    pub type_url: String,
+    /// Decoded for well-known messages; empty otherwise.
+    pub fields: Vec<DecodedField>,
+    pub value_hex: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CosmosSignDocSummary {
+    pub chain_id: String,
+    pub account_number: u64,
+    pub sequence: Option<u64>,
+    pub messages: Vec<CosmosMessage>,
+    pub memo: String,
+    pub fee: Vec<CosmosCoin>,
+    pub gas_limit: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RawSummary {
+    pub len: usize,
+    /// The payload as text, when it is printable UTF-8.
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TxDetail {
+    Psbt(PsbtSummary),
+    EthereumTx(EthereumTxSummary),
+    CosmosSignDoc(CosmosSignDocSummary),
+    Raw(RawSummary),
+}
+
+/// What a payload will commit the signer to, for review before signing.
+/// Signing commands take `summary_hash_hex` back and refuse to sign a payload
+/// whose summary no longer hashes to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxSummary {
+    pub kind: SigningPayloadKind,
+    pub payload_sha256_hex: String,
+    pub detail: TxDetail,
+    /// Things the reviewer should look at twice.
+    pub warnings: Vec<String>,
+    pub summary_hash_hex: String,
+}
+
+/// A 4-byte selector and the function signature it stands for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiEntry {
+    /// `0x` and four bytes, lowercase.
+    pub selector: String,
+    /// `name(type,type)`, as in the canonical form the selector hashes.
+    pub signature: String,
+}
+
+/// Selectors added on this machine, on top of the built-in ones. Encrypted
+/// under the session key, so a tampered registry cannot relabel a call.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AbiRegistry {
+    #[serde(default)]
+    pub entries: Vec<AbiEntry>,
+}
@@ -0,0 +1,160 @@
+use crate::crypto::encryption::Ciphertext;
+use crate::models::trash::TrashedEntry;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How long a purge can be undone by default.
+pub const DEFAULT_UNDO_WINDOW_SECS: u64 = 5 * 60;
+/// Longest undo window that can be configured (one hour).
+pub const MAX_UNDO_WINDOW_SECS: u64 = 60 * 60;
+
+/// `0` turns undo off; otherwise at most [`MAX_UNDO_WINDOW_SECS`].
+pub fn validate_undo_window(secs: u64) -> Result<(), String> {
+    if secs > MAX_UNDO_WINDOW_SECS {
+        return Err(format!(
+            "the undo window must be at most {MAX_UNDO_WINDOW_SECS} seconds"
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UndoKind {
+    /// Trashed keys and items destroyed by hand or by the retention purge.
+    PurgeTrash,
+    /// The keys of a keyset removed by `rollback_keyset`.
+    RollbackKeyset,
+}
+
+/// A destructive operation that can still be undone, as listed to the UI.
+/// Never carries secret material.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UndoableOperation {
+    pub op_id: String,
+    pub kind: UndoKind,
+    /// What the operation removed.
+    pub entries: Vec<TrashedEntry>,
+    pub performed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct UndoRecord {
+    operation: UndoableOperation,
+    /// The removed rows, encrypted under the session key.
+    sealed: Ciphertext,
+}
+
+/// Rows removed by recent destructive operations, kept encrypted in memory
+/// until their undo window closes. Never written to disk, so the purge's
+/// wiping of old store files still holds. Pure logic taking `now`
+/// explicitly so it is unit-testable.
+#[derive(Debug, Default)]
+pub struct UndoBuffer {
+    records: Vec<UndoRecord>,
+}
+
+impl UndoBuffer {
+    /// Keep `sealed` for `window_secs` and return the listing for it.
+    pub fn push(
+        &mut self,
+        kind: UndoKind,
+        entries: Vec<TrashedEntry>,
+        sealed: Ciphertext,
+        window_secs: u64,
+        now: DateTime<Utc>,
+    ) -> UndoableOperation {
+        let operation = UndoableOperation {
+            op_id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            entries,
+            performed_at: now,
+            expires_at: now + Duration::seconds(window_secs as i64),
+        };
+        self.records.push(UndoRecord {
+            operation: operation.clone(),
+            sealed,
+        });
+        operation
+    }
+
+    /// Operations that can still be undone, most recent first.
+    pub fn list(&self, now: DateTime<Utc>) -> Vec<UndoableOperation> {
+        self.records
+            .iter()
+            .rev()
+            .filter(|r| now < r.operation.expires_at)
+            .map(|r| r.operation.clone())
+            .collect()
+    }
+
+    /// `op_id` and its sealed rows, if its window is still open.
+    pub fn get(&self, op_id: &str, now: DateTime<Utc>) -> Option<(UndoableOperation, Ciphertext)> {
+        self.records
+            .iter()
+            .find(|r| r.operation.op_id == op_id && now < r.operation.expires_at)
+            .map(|r| (r.operation.clone(), r.sealed.clone()))
+    }
+
+    /// Forget `op_id`, once it has been undone.
+    pub fn remove(&mut self, op_id: &str) {
+        self.records.retain(|r| r.operation.op_id != op_id);
+    }
+
+    /// Drop every record whose window has closed and return their listings.
+    pub fn expire(&mut self, now: DateTime<Utc>) -> Vec<UndoableOperation> {
+        let (expired, open): (Vec<UndoRecord>, Vec<UndoRecord>) = std::mem::take(&mut self.records)
+            .into_iter()
+            .partition(|r| now >= r.operation.expires_at);
+        self.records = open;
+        expired.into_iter().map(|r| r.operation).collect()
+    }
+
+    /// Drop everything, when the vault locks or is re-keyed.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sealed() -> Ciphertext {
+        Ciphertext {
+            nonce: vec![0; 12],
+            ciphertext: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn undo_is_possible_only_within_the_window() {
+        let now = Utc::now();
+        let mut buffer = UndoBuffer::default();
+        let first = buffer.push(UndoKind::PurgeTrash, Vec::new(), sealed(), 60, now);
+        let second = buffer.push(UndoKind::PurgeTrash, Vec::new(), sealed(), 300, now);
+        assert_eq!(buffer.list(now), vec![second.clone(), first.clone()]);
+
+        let later = now + Duration::seconds(60);
+        assert_eq!(buffer.list(later), vec![second.clone()]);
+        assert!(buffer.get(&first.op_id, later).is_none());
+        let (found, ciphertext) = buffer.get(&second.op_id, later).unwrap();
+        assert_eq!(found, second);
+        assert_eq!(ciphertext.ciphertext, vec![1, 2, 3]);
+        buffer.remove(&second.op_id);
+        assert!(buffer.get(&second.op_id, later).is_none());
+    }
+
+    #[test]
+    fn expire_reports_what_it_dropped() {
+        let now = Utc::now();
+        let mut buffer = UndoBuffer::default();
+        let op = buffer.push(UndoKind::PurgeTrash, Vec::new(), sealed(), 10, now);
+        assert!(buffer.expire(now).is_empty());
+        assert_eq!(buffer.expire(now + Duration::seconds(10)), vec![op]);
+        assert!(buffer.list(now).is_empty());
+        assert!(validate_undo_window(0).is_ok());
+        assert!(validate_undo_window(MAX_UNDO_WINDOW_SECS + 1).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Half-life, in days, of a use when ranking for quick access: a key used ten
+/// times a week ago ranks like one used five times today.
+pub const USAGE_HALF_LIFE_DAYS: f64 = 7.0;
+
+/// Favorite flag and usage counters shared by keys and vault items. Stored
+/// inside the encrypted keystore / item store, never in plaintext metadata.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageStats {
+    #[serde(default)]
+    pub favorite: bool,
+    #[serde(default)]
+    pub use_count: u64,
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl UsageStats {
+    /// Record one use at `now`.
+    pub fn touch(&mut self, now: DateTime<Utc>) {
+        self.use_count = self.use_count.saturating_add(1);
+        self.last_used_at = Some(now);
+    }
+
+    /// Frecency score: the use count decayed by the time since last use.
+    pub fn score(&self, now: DateTime<Utc>) -> f64 {
+        let Some(last) = self.last_used_at else {
+            return 0.0;
+        };
+        let age_days = (now - last).num_seconds().max(0) as f64 / 86_400.0;
+        self.use_count as f64 * 0.5f64.powf(age_days / USAGE_HALF_LIFE_DAYS)
+    }
+
+    /// Whether this entry belongs on the quick-access list at all.
+    pub fn is_quick_access_candidate(&self) -> bool {
+        self.favorite || self.use_count > 0
+    }
+
+    /// Quick-access ordering: favorites first, then by frecency, then by most
+    /// recent use.
+    pub fn rank_cmp(&self, other: &Self, now: DateTime<Utc>) -> Ordering {
+        other
+            .favorite
+            .cmp(&self.favorite)
+            .then_with(|| {
+                other
+                    .score(now)
+                    .partial_cmp(&self.score(now))
+                    .unwrap_or(Ordering::Equal)
+            })
+            .then_with(|| other.last_used_at.cmp(&self.last_used_at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn used(count: u64, days_ago: i64, now: DateTime<Utc>) -> UsageStats {
+        UsageStats {
+            favorite: false,
+            use_count: count,
+            last_used_at: Some(now - Duration::days(days_ago)),
+        }
+    }
+
+    #[test]
+    fn touch_increments_and_timestamps() {
+        let now = Utc::now();
+        let mut u = UsageStats::default();
+        assert!(!u.is_quick_access_candidate());
+        u.touch(now);
+        u.touch(now);
+        assert_eq!(u.use_count, 2);
+        assert_eq!(u.last_used_at, Some(now));
+        assert!(u.is_quick_access_candidate());
+    }
+
+    #[test]
+    fn score_decays_with_half_life() {
+        let now = Utc::now();
+        let fresh = used(10, 0, now).score(now);
+        let week_old = used(10, 7, now).score(now);
+        assert!((fresh - 10.0).abs() < 1e-9);
+        assert!((week_old - 5.0).abs() < 1e-6);
+        assert_eq!(UsageStats::default().score(now), 0.0);
+    }
+
+    #[test]
+    fn favorites_rank_before_frequent() {
+        let now = Utc::now();
+        let fav = UsageStats {
+            favorite: true,
+            ..Default::default()
+        };
+        let busy = used(100, 0, now);
+        let stale = used(100, 60, now);
+        let mut v = [stale.clone(), busy.clone(), fav.clone()];
+        v.sort_by(|a, b| a.rank_cmp(b, now));
+        assert_eq!(v, [fav, busy, stale]);
+    }
+
+    #[test]
+    fn legacy_json_defaults_to_unused() {
+        let u: UsageStats = serde_json::from_str("{}").unwrap();
+        assert_eq!(u, UsageStats::default());
+    }
+}
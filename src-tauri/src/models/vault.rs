@@ -6,12 +6,32 @@ pub fn default_keys_file() -> String {
     "keys.enc".to_string()
 }
 
+/// Default filename of the encrypted non-chain item store (SSH keys, ...).
+pub fn default_items_file() -> String {
+    "items.enc".to_string()
+}
+
 /// Default YubiKey slot used for HMAC-SHA1 challenge-response. Slot 2 is the
 /// conventional slot for challenge-response (slot 1 typically holds Yubico OTP).
 pub fn default_yubikey_slot() -> u8 {
     2
 }
 
+/// Vaults start (and pre-existing vaults stay) in air-gap mode.
+pub fn default_air_gap_mode() -> bool {
+    true
+}
+
+/// Purges can be undone for five minutes unless configured otherwise.
+pub fn default_undo_window_secs() -> u64 {
+    crate::models::undo::DEFAULT_UNDO_WINDOW_SECS
+}
+
+/// Default HD address gap limit (BIP44's 20).
+pub fn default_address_gap_limit() -> u32 {
+    crate::models::address::DEFAULT_ADDRESS_GAP_LIMIT
+}
+
 /// Serde defaults for the KDF profile block. Vaults written before this block
 /// existed are read back as the legacy 64 MiB / t=3 profile so they still unlock.
 pub fn default_kdf_version() -> u32 {
@@ -27,6 +47,22 @@ pub fn default_argon2_parallelism() -> u32 {
     crate::crypto::kdf::ARGON2_PARALLELISM
 }
 
+/// New passwords must score at least this unless the vault says otherwise.
+pub fn default_min_password_score() -> u8 {
+    crate::crypto::password_strength::DEFAULT_MIN_SCORE
+}
+
+/// A vault's data key as kept in `vault.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WrappedDataKey {
+    /// The data key, encrypted under a key derived from the password key.
+    pub wrapped: crate::crypto::encryption::Ciphertext,
+    /// A known plaintext encrypted under the data key, so a data key from
+    /// elsewhere (a key drive) can be checked without the password.
+    pub verifier: crate::crypto::encryption::Ciphertext,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VaultState {
     pub initialized: bool,
@@ -37,6 +73,10 @@ pub struct VaultState {
     /// password change so the keystore and verifier are always swapped together.
     #[serde(default = "default_keys_file")]
     pub keys_file: String,
+    /// Name of the encrypted item store file, rotated to a new generation
+    /// together with `keys_file` whenever the vault is re-keyed.
+    #[serde(default = "default_items_file")]
+    pub items_file: String,
     /// Whether a YubiKey HMAC-SHA1 challenge-response is required as a second
     /// factor mixed into the key derivation. Defaults to `false` so existing
     /// password-only vaults keep working unchanged.
@@ -69,6 +109,78 @@ pub struct VaultState {
     /// vaults created before HD derivation existed.
     #[serde(default)]
     pub master_seed_enc_hex: String,
+    /// The data key the stores and master seed are encrypted under, wrapped
+    /// under the key derived from the password (and YubiKey). `None` for
+    /// vaults from before data keys, which encrypt directly under the
+    /// password key until their next unlock adopts it as their data key.
+    #[serde(default)]
+    pub data_key: Option<WrappedDataKey>,
+    /// Maximum run of unused addresses past the last used one within an HD
+    /// account. `generate_key` refuses indices beyond it so every key stays
+    /// inside the window a mnemonic restore scans.
+    #[serde(default = "default_address_gap_limit")]
+    pub address_gap_limit: u32,
+    /// Keysets generated as a unit, with their planned derivation paths and
+    /// completion status. Public data only; the keys live in the keystore.
+    #[serde(default)]
+    pub keysets: Vec<crate::models::keyset::Keyset>,
+    /// While set, features that send data off the machine (remote backup
+    /// targets) are refused.
+    #[serde(default = "default_air_gap_mode")]
+    pub air_gap_mode: bool,
+    /// Per-operation limits for signing, secret export and deletion commands.
+    #[serde(default)]
+    pub rate_limits: crate::models::rate_limit::RateLimits,
+    /// Strength score (0 to 4) every new vault password, and every password
+    /// that seals an export, must reach. `0` turns the check off.
+    #[serde(default = "default_min_password_score")]
+    pub min_password_score: u8,
+    /// One-time codes that can reset a forgotten password, hashed, with an
+    /// escrow copy of the data key when issued with escrow.
+    #[serde(default)]
+    pub recovery_codes: Option<crate::models::recovery::RecoveryCodeSet>,
+    /// Days a trashed key or item is kept before the background purge destroys
+    /// it. `None` keeps trash until it is purged by hand.
+    #[serde(default)]
+    pub trash_retention_days: Option<u32>,
+    /// Seconds a purge can be undone for. `0` turns undo off.
+    #[serde(default = "default_undo_window_secs")]
+    pub undo_window_secs: u64,
+    /// Saved layouts that provision several keysets at once.
+    #[serde(default)]
+    pub keyset_templates: Vec<crate::models::template::KeysetTemplate>,
+    #[serde(default)]
+    pub security_policy: crate::models::policy::SecurityPolicy,
+    /// Backups this vault has written, newest last, with their latest
+    /// verification result.
+    #[serde(default)]
+    pub backup_history: Vec<crate::models::backup::BackupRecord>,
+    #[serde(default)]
+    pub notifications: crate::models::notification::NotificationSettings,
+    /// Per-drive auto-lock rules.
+    #[serde(default)]
+    pub drive_trust: Vec<crate::models::drive::DriveTrust>,
+    /// Drives sealed so changes made outside the app can be noticed.
+    #[serde(default)]
+    pub provisioned_drives: Vec<crate::models::drive_seal::ProvisionedDrive>,
+    /// Set while the vault can be unlocked with a PIN and its key drive.
+    #[serde(default)]
+    pub key_drive: Option<crate::models::key_drive::KeyDriveConfig>,
+    /// Hooks from before they moved into the sealed `hooks.json`. Moved
+    /// there, switched off, the first time the hooks are read while
+    /// unlocked; empty afterwards.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::models::hook::HookSettings::is_empty"
+    )]
+    pub hooks: crate::models::hook::HookSettings,
+    /// Trusted plugin publishers and installed plugins.
+    #[serde(default)]
+    pub plugins: crate::models::plugin::PluginSettings,
+    /// Locale for notifications, warnings and reports, such as `de`. `None`
+    /// uses [`crate::i18n::DEFAULT_LOCALE`].
+    #[serde(default)]
+    pub locale: Option<String>,
 }
 
 impl VaultState {
@@ -80,6 +192,164 @@ impl VaultState {
             parallelism: self.argon2_parallelism,
         }
     }
+
+    /// The bundled locale backend text is written in.
+    pub fn locale(&self) -> &'static str {
+        crate::i18n::resolve(self.locale.as_deref())
+    }
+
+    /// The KDF profile this vault was last keyed with.
+    pub fn kdf_profile(&self) -> KdfProfile {
+        KdfProfile {
+            version: self.kdf_version,
+            memory_kib: self.argon2_memory_kib,
+            iterations: self.argon2_iterations,
+            parallelism: self.argon2_parallelism,
+        }
+    }
+
+    /// Whether `name` in the data directory is a keystore or item store
+    /// generation this vault no longer uses, or a temp file left by an
+    /// interrupted write of one (or of `vault.json`).
+    pub fn is_retired_store_file(&self, name: &str) -> bool {
+        if name == self.keys_file || name == self.items_file {
+            return false;
+        }
+        let Some((stem, ext)) = name.rsplit_once('.') else {
+            return false;
+        };
+        let store_stem = |prefix: &str| {
+            stem == prefix
+                || stem
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('-'))
+        };
+        let is_store = store_stem("keys") || store_stem("items");
+        (is_store && ext == "enc") || ((is_store || stem == "vault") && ext.starts_with("tmp-"))
+    }
+
+    /// Remember a backup written by this vault, keeping only the most recent
+    /// [`MAX_BACKUP_RECORDS`](crate::models::backup::MAX_BACKUP_RECORDS).
+    pub fn record_backup(&mut self, record: crate::models::backup::BackupRecord) {
+        self.backup_history.push(record);
+        let excess = self
+            .backup_history
+            .len()
+            .saturating_sub(crate::models::backup::MAX_BACKUP_RECORDS);
+        self.backup_history.drain(..excess);
+    }
+
+    /// Store the outcome of verifying one of this vault's backups. Returns
+    /// `false` if the backup was not written by this vault.
+    pub fn record_backup_verification(
+        &mut self,
+        drive_id: &str,
+        backup_id: &str,
+        ok: bool,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        match self
+            .backup_history
+            .iter_mut()
+            .find(|b| b.drive_id == drive_id && b.backup_id == backup_id)
+        {
+            Some(record) => {
+                record.verified_at = ok.then_some(now);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether `drive_id` holds a backup of this vault that has not passed
+    /// verification.
+    pub fn has_unverified_backup_on(&self, drive_id: &str) -> bool {
+        self.backup_history
+            .iter()
+            .any(|b| b.drive_id == drive_id && b.verified_at.is_none())
+    }
+
+    /// Whether at least one backup of this vault passed its last verification.
+    pub fn has_verified_backup(&self) -> bool {
+        self.backup_history.iter().any(|b| b.verified_at.is_some())
+    }
+
+    /// Whether the vault is keyed with anything other than the profile new
+    /// vaults get, and so should be re-keyed by `migrate_encryption`.
+    pub fn needs_kdf_upgrade(&self) -> bool {
+        self.kdf_profile() != KdfProfile::current()
+    }
+}
+
+/// Serializable view of a KDF profile, for reporting migrations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfProfile {
+    pub version: u32,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl KdfProfile {
+    /// The profile `create_vault` uses today.
+    pub fn current() -> Self {
+        let params = crate::crypto::kdf::KdfParams::high();
+        Self {
+            version: crate::crypto::kdf::KDF_VERSION,
+            memory_kib: params.memory_kib,
+            iterations: params.iterations,
+            parallelism: params.parallelism,
+        }
+    }
+}
+
+/// Stages of `change_master_password`, in order. `RolledBack` takes the place
+/// of `Complete` when the committed change did not verify and the previous
+/// metadata was put back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MasterPasswordStage {
+    VerifyingOldPassword,
+    DerivingNewKey,
+    Rewrapping,
+    Verifying,
+    Committing,
+    Complete,
+    RolledBack,
+}
+
+impl MasterPasswordStage {
+    pub const STEPS: u8 = 6;
+
+    /// 1-based position of the stage, for progress display.
+    pub fn step(self) -> u8 {
+        match self {
+            Self::VerifyingOldPassword => 1,
+            Self::DerivingNewKey => 2,
+            Self::Rewrapping => 3,
+            Self::Verifying => 4,
+            Self::Committing => 5,
+            Self::Complete | Self::RolledBack => Self::STEPS,
+        }
+    }
+}
+
+/// Payload of the `master_password_progress` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MasterPasswordProgress {
+    pub stage: MasterPasswordStage,
+    pub step: u8,
+    pub steps: u8,
+}
+
+impl From<MasterPasswordStage> for MasterPasswordProgress {
+    fn from(stage: MasterPasswordStage) -> Self {
+        Self {
+            stage,
+            step: stage.step(),
+            steps: MasterPasswordStage::STEPS,
+        }
+    }
 }
 
 impl Default for VaultState {
@@ -89,6 +359,7 @@ impl Default for VaultState {
             salt_hex: String::new(),
             verifier_hash_hex: String::new(),
             keys_file: default_keys_file(),
+            items_file: default_items_file(),
             yubikey_enabled: false,
             yubikey_slot: default_yubikey_slot(),
             yubikey_challenge_hex: String::new(),
@@ -97,6 +368,25 @@ impl Default for VaultState {
             argon2_iterations: default_argon2_iterations(),
             argon2_parallelism: default_argon2_parallelism(),
             master_seed_enc_hex: String::new(),
+            data_key: None,
+            address_gap_limit: default_address_gap_limit(),
+            keysets: Vec::new(),
+            air_gap_mode: default_air_gap_mode(),
+            rate_limits: Default::default(),
+            min_password_score: default_min_password_score(),
+            recovery_codes: None,
+            trash_retention_days: None,
+            undo_window_secs: default_undo_window_secs(),
+            keyset_templates: Vec::new(),
+            security_policy: Default::default(),
+            backup_history: Vec::new(),
+            notifications: Default::default(),
+            drive_trust: Vec::new(),
+            provisioned_drives: Vec::new(),
+            key_drive: None,
+            hooks: Default::default(),
+            plugins: Default::default(),
+            locale: None,
         }
     }
 }
@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The operation a worksheet documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorksheetKind {
+    /// A genesis key ceremony.
+    Genesis,
+    /// FROST key generation for a threshold (multisig) group.
+    FrostGroup,
+}
+
+/// Someone who took part, with the fingerprint of the key they took part
+/// with, so it can be read out and compared in the room.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorksheetParticipant {
+    pub name: String,
+    pub role: String,
+    pub fingerprint_hex: String,
+    pub fingerprint_words: String,
+    /// Extra context, such as the operator's entropy commitment.
+    pub detail: Option<String>,
+}
+
+/// One step of the operation. Steps not yet performed are printed as open
+/// checkboxes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorksheetStep {
+    pub description: String,
+    pub done: bool,
+    /// When the step was recorded, if the record keeps a time.
+    pub at: Option<DateTime<Utc>>,
+}
+
+/// A fingerprint the operation produced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorksheetFingerprint {
+    pub label: String,
+    pub hex: String,
+    pub words: String,
+    /// The value itself or what it belongs to, such as an address.
+    pub detail: Option<String>,
+}
+
+/// A printable record of a key ceremony, filled in from what the vault
+/// stored about it. Text is in the vault's locale.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CeremonyWorksheet {
+    pub kind: WorksheetKind,
+    /// The ceremony or group id.
+    pub subject_id: String,
+    pub title: String,
+    pub generated_at: DateTime<Utc>,
+    /// Whether every step has been performed.
+    pub complete: bool,
+    pub participants: Vec<WorksheetParticipant>,
+    pub steps: Vec<WorksheetStep>,
+    pub fingerprints: Vec<WorksheetFingerprint>,
+}
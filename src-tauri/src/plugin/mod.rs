@@ -0,0 +1,180 @@
+//! Third-party plugins: custom item types, exporters and chain integrations.
+//!
+//! A plugin is a WebAssembly module plus a [`PluginManifest`], signed
+//! together by its publisher with a hybrid ML-DSA-87 + Ed25519 signature
+//! ([`open_package`]). Modules run in [`runtime`] with no imports at all, so
+//! they cannot reach files, the network, the clock or the rest of the vault;
+//! everything a plugin sees arrives in its request, and the commands only
+//! put in what the user granted.
+
+pub mod runtime;
+
+use crate::crypto::hybrid_signing::{HybridSignature, HybridSigner};
+use crate::models::plugin::{
+    PluginManifest, PluginPackage, PublisherSignature, MAX_PLUGIN_WASM_BYTES,
+    PLUGIN_PACKAGE_FORMAT, PLUGIN_PACKAGE_VERSION,
+};
+use base64::Engine as _;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// Signature domain for plugin packages.
+const PLUGIN_SIGNATURE_DOMAIN: &[u8] = b"ZAP_PLUGIN_SIGNATURE_V1";
+
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error("malformed plugin package: {0}")]
+    Malformed(String),
+    #[error("plugin signature is invalid: {0}")]
+    BadSignature(String),
+    #[error("plugin publisher {0} is not trusted")]
+    UntrustedPublisher(String),
+    #[error("plugin module is not usable: {0}")]
+    InvalidModule(String),
+    #[error("plugin module changed on disk since it was installed")]
+    Tampered,
+    #[error("plugin failed: {0}")]
+    Failed(String),
+}
+
+/// SHA-256 of a module, as recorded at install.
+pub fn wasm_digest_hex(wasm: &[u8]) -> String {
+    hex::encode(Sha256::digest(wasm))
+}
+
+fn signed_message(manifest: &PluginManifest, wasm: &[u8]) -> Result<Vec<u8>, PluginError> {
+    let manifest_json =
+        serde_json::to_vec(manifest).map_err(|e| PluginError::Malformed(e.to_string()))?;
+    let mut message = PLUGIN_SIGNATURE_DOMAIN.to_vec();
+    message.extend_from_slice(&Sha256::digest(&manifest_json));
+    message.extend_from_slice(&Sha256::digest(wasm));
+    Ok(message)
+}
+
+/// Sign `manifest` and `wasm` into a package, as a publisher's tooling does.
+pub fn sign_package(
+    signer: &HybridSigner,
+    manifest: PluginManifest,
+    wasm: &[u8],
+) -> Result<PluginPackage, PluginError> {
+    let sig = signer
+        .sign(&signed_message(&manifest, wasm)?)
+        .map_err(|e| PluginError::BadSignature(e.to_string()))?;
+    Ok(PluginPackage {
+        format: PLUGIN_PACKAGE_FORMAT.to_string(),
+        version: PLUGIN_PACKAGE_VERSION,
+        manifest,
+        wasm_base64: base64::engine::general_purpose::STANDARD.encode(wasm),
+        signature: PublisherSignature {
+            mldsa_public_hex: hex::encode(&sig.primary_public_key),
+            ed25519_public_hex: hex::encode(sig.secondary_public_key),
+            mldsa_signature_hex: hex::encode(&sig.primary),
+            ed25519_signature_hex: hex::encode(&sig.secondary),
+        },
+    })
+}
+
+/// Check a package's format, manifest, signature and module, and return the
+/// module. Whether to trust the publisher, by
+/// [`PublisherSignature::key_fingerprint`], is up to the caller.
+pub fn open_package(package: &PluginPackage) -> Result<Vec<u8>, PluginError> {
+    if package.format != PLUGIN_PACKAGE_FORMAT || package.version != PLUGIN_PACKAGE_VERSION {
+        return Err(PluginError::Malformed(format!(
+            "unsupported package {} v{}",
+            package.format, package.version
+        )));
+    }
+    package
+        .manifest
+        .validate()
+        .map_err(PluginError::Malformed)?;
+    let wasm = base64::engine::general_purpose::STANDARD
+        .decode(&package.wasm_base64)
+        .map_err(|e| PluginError::Malformed(e.to_string()))?;
+    if wasm.len() > MAX_PLUGIN_WASM_BYTES {
+        return Err(PluginError::Malformed(format!(
+            "module is larger than {MAX_PLUGIN_WASM_BYTES} bytes"
+        )));
+    }
+
+    let sig = &package.signature;
+    let decode = |h: &str| hex::decode(h).map_err(|e| PluginError::BadSignature(e.to_string()));
+    let hybrid = HybridSignature {
+        primary: decode(&sig.mldsa_signature_hex)?,
+        secondary: decode(&sig.ed25519_signature_hex)?,
+        primary_public_key: decode(&sig.mldsa_public_hex)?,
+        secondary_public_key: decode(&sig.ed25519_public_hex)?
+            .try_into()
+            .map_err(|_| PluginError::BadSignature("Ed25519 key must be 32 bytes".to_string()))?,
+        algorithm: String::new(),
+    };
+    HybridSigner::verify(&signed_message(&package.manifest, &wasm)?, &hybrid)
+        .map_err(|e| PluginError::BadSignature(e.to_string()))?;
+
+    runtime::check_module(&wasm)?;
+    Ok(wasm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::plugin::{PluginChain, PLUGIN_API_VERSION};
+
+    fn manifest() -> PluginManifest {
+        PluginManifest {
+            id: "sol-addresses".to_string(),
+            name: "Solana addresses".to_string(),
+            version: "0.1.0".to_string(),
+            publisher: "Example Labs".to_string(),
+            api_version: PLUGIN_API_VERSION,
+            item_types: Vec::new(),
+            exporters: Vec::new(),
+            chains: vec![PluginChain {
+                name: "solana".to_string(),
+                label: "Solana".to_string(),
+            }],
+            capabilities: Vec::new(),
+        }
+    }
+
+    /// A minimal module exporting the call interface; `tag` varies its bytes.
+    fn module(tag: i64) -> Vec<u8> {
+        wat::parse_str(format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (func (export "zap_alloc") (param i32) (result i32) (i32.const 0))
+                (func (export "zap_call") (param i32 i32) (result i64) (i64.const {tag})))"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn packages_verify_and_detect_tampering() {
+        let signer = HybridSigner::generate().unwrap();
+        let wasm = module(0);
+        let package = sign_package(&signer, manifest(), &wasm).unwrap();
+        assert_eq!(open_package(&package).unwrap(), wasm);
+
+        let mut widened = package.clone();
+        widened.manifest.capabilities =
+            vec![crate::models::plugin::PluginCapability::ReadPluginItems];
+        assert!(matches!(
+            open_package(&widened),
+            Err(PluginError::BadSignature(_))
+        ));
+
+        let other = module(1);
+        let mut swapped = package.clone();
+        swapped.wasm_base64 = base64::engine::general_purpose::STANDARD.encode(&other);
+        assert!(matches!(
+            open_package(&swapped),
+            Err(PluginError::BadSignature(_))
+        ));
+
+        let resigned = sign_package(&HybridSigner::generate().unwrap(), manifest(), &wasm).unwrap();
+        assert_ne!(
+            resigned.signature.key_fingerprint(),
+            package.signature.key_fingerprint()
+        );
+    }
+}
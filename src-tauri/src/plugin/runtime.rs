@@ -0,0 +1,207 @@
+//! The sandbox plugins run in.
+//!
+//! A module gets no imports: no WASI, no host functions. It must export a
+//! `memory`, `zap_alloc(len) -> ptr` and `zap_call(ptr, len) -> i64`. The
+//! vault writes a [`PluginRequest`] as JSON into memory from `zap_alloc`,
+//! calls `zap_call`, and reads a [`PluginResponse`] from the pointer in the
+//! high 32 bits and the length in the low 32 bits of its result. Each call
+//! runs in a fresh instance with capped fuel and memory, so nothing carries
+//! over between calls and a runaway plugin is stopped.
+
+use super::PluginError;
+use crate::models::plugin::{PluginRequest, PluginResponse};
+use wasmtime::{
+    Config, Engine, Instance, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc,
+};
+
+/// Instructions, roughly, a single call may run.
+pub const PLUGIN_FUEL: u64 = 2_000_000_000;
+pub const PLUGIN_MEMORY_LIMIT_BYTES: usize = 64 * 1024 * 1024;
+pub const MAX_PLUGIN_OUTPUT_BYTES: usize = 16 * 1024 * 1024;
+
+struct Loaded {
+    store: Store<StoreLimits>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    call: TypedFunc<(i32, i32), i64>,
+}
+
+fn invalid(e: impl std::fmt::Display) -> PluginError {
+    PluginError::InvalidModule(e.to_string())
+}
+
+fn failed(e: impl std::fmt::Display) -> PluginError {
+    PluginError::Failed(e.to_string())
+}
+
+fn load(wasm: &[u8]) -> Result<Loaded, PluginError> {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config).map_err(invalid)?;
+    let module = Module::new(&engine, wasm).map_err(invalid)?;
+    if let Some(import) = module.imports().next() {
+        return Err(PluginError::InvalidModule(format!(
+            "module imports {}::{}; plugins may not import anything",
+            import.module(),
+            import.name()
+        )));
+    }
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(PLUGIN_MEMORY_LIMIT_BYTES)
+        .instances(1)
+        .memories(1)
+        .tables(1)
+        .build();
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits| limits);
+    store.set_fuel(PLUGIN_FUEL).map_err(invalid)?;
+    let instance = Instance::new(&mut store, &module, &[]).map_err(invalid)?;
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or_else(|| PluginError::InvalidModule("module exports no memory".to_string()))?;
+    let alloc = instance
+        .get_typed_func(&mut store, "zap_alloc")
+        .map_err(invalid)?;
+    let call = instance
+        .get_typed_func(&mut store, "zap_call")
+        .map_err(invalid)?;
+    Ok(Loaded {
+        store,
+        memory,
+        alloc,
+        call,
+    })
+}
+
+/// Check that `wasm` compiles, imports nothing and exports the call
+/// interface.
+pub fn check_module(wasm: &[u8]) -> Result<(), PluginError> {
+    load(wasm).map(|_| ())
+}
+
+/// Run one request in a fresh instance of `wasm`. An error answer from the
+/// plugin becomes [`PluginError::Failed`] with its message.
+pub fn call(wasm: &[u8], request: &PluginRequest) -> Result<serde_json::Value, PluginError> {
+    let input = serde_json::to_vec(request).map_err(failed)?;
+    let len = i32::try_from(input.len()).map_err(failed)?;
+    let Loaded {
+        mut store,
+        memory,
+        alloc,
+        call,
+    } = load(wasm)?;
+
+    let ptr = alloc.call(&mut store, len).map_err(failed)?;
+    memory
+        .write(&mut store, ptr as u32 as usize, &input)
+        .map_err(failed)?;
+    let packed = call.call(&mut store, (ptr, len)).map_err(failed)? as u64;
+    let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+    if out_len > MAX_PLUGIN_OUTPUT_BYTES {
+        return Err(PluginError::Failed(format!(
+            "answer is larger than {MAX_PLUGIN_OUTPUT_BYTES} bytes"
+        )));
+    }
+    let mut output = vec![0u8; out_len];
+    memory.read(&store, out_ptr, &mut output).map_err(failed)?;
+
+    let response: PluginResponse = serde_json::from_slice(&output)
+        .map_err(|e| PluginError::Failed(format!("unreadable answer: {e}")))?;
+    match response {
+        PluginResponse {
+            error: Some(message),
+            ..
+        } => Err(PluginError::Failed(message)),
+        PluginResponse { ok, .. } => Ok(ok.unwrap_or(serde_json::Value::Null)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module that answers every call with `answer`.
+    fn answering(answer: &str) -> Vec<u8> {
+        let escaped = answer.replace('\\', "\\\\").replace('"', "\\\"");
+        wat::parse_str(format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (global $next (mut i32) (i32.const 1024))
+                (data (i32.const 0) "{escaped}")
+                (func (export "zap_alloc") (param $len i32) (result i32)
+                    (local $p i32)
+                    (local.set $p (global.get $next))
+                    (global.set $next (i32.add (global.get $next) (local.get $len)))
+                    (local.get $p))
+                (func (export "zap_call") (param i32 i32) (result i64)
+                    (i64.const {})))"#,
+            answer.len()
+        ))
+        .unwrap()
+    }
+
+    fn derive() -> PluginRequest {
+        PluginRequest::DeriveAddress {
+            chain: "solana".to_string(),
+            public_key_hex: "00".repeat(32),
+            derivation_path: "m/44'/501'/0'/0'".to_string(),
+        }
+    }
+
+    #[test]
+    fn calls_and_reads_answers() {
+        let ok = answering(r#"{"ok":"So1ana"}"#);
+        check_module(&ok).unwrap();
+        assert_eq!(call(&ok, &derive()).unwrap(), "So1ana");
+
+        let refused = answering(r#"{"error":"unsupported key"}"#);
+        assert!(matches!(
+            call(&refused, &derive()),
+            Err(PluginError::Failed(m)) if m == "unsupported key"
+        ));
+    }
+
+    #[test]
+    fn sandbox_refuses_imports_and_stops_runaway_code() {
+        let importing = wat::parse_str(
+            r#"(module
+                (import "wasi_snapshot_preview1" "fd_write"
+                    (func (param i32 i32 i32 i32) (result i32)))
+                (memory (export "memory") 1))"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            check_module(&importing),
+            Err(PluginError::InvalidModule(m)) if m.contains("fd_write")
+        ));
+
+        let spinning = wat::parse_str(
+            r#"(module
+                (memory (export "memory") 1)
+                (func (export "zap_alloc") (param i32) (result i32) (i32.const 0))
+                (func (export "zap_call") (param i32 i32) (result i64)
+                    (loop $forever (br $forever))
+                    (i64.const 0)))"#,
+        )
+        .unwrap();
+        check_module(&spinning).unwrap();
+        assert!(matches!(
+            call(&spinning, &derive()),
+            Err(PluginError::Failed(_))
+        ));
+
+        let greedy = wat::parse_str(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 0) "{\"ok\":\"denied\"}")
+                (data (i32.const 32) "{\"ok\":\"grew\"}")
+                (func (export "zap_alloc") (param i32) (result i32) (i32.const 1024))
+                (func (export "zap_call") (param i32 i32) (result i64)
+                    (if (result i64) (i32.eq (memory.grow (i32.const 2048)) (i32.const -1))
+                        (then (i64.const 15))
+                        (else (i64.const 0x200000000d)))))"#,
+        )
+        .unwrap();
+        assert_eq!(call(&greedy, &derive()).unwrap(), "denied");
+    }
+}
@@ -0,0 +1,407 @@
+//! Key ceremony worksheets: printable records of a genesis ceremony or of a
+//! FROST group's key generation.
+//!
+//! [`genesis`] and [`frost_group`] fill a [`CeremonyWorksheet`] in from the
+//! stored records: who took part and with which key, each step and when it
+//! was recorded, and the fingerprints the operation produced. [`render`]
+//! prints it as a PDF checklist with a signature line per participant, to be
+//! signed in the room and filed with the ceremony's paper records.
+
+use super::pdf::{PdfWriter, Style};
+use super::{table_row, timestamp};
+use crate::crypto::ceremony::manifest_message;
+use crate::crypto::fingerprint::KeyFingerprint;
+use crate::i18n::tr;
+use crate::models::ceremony::Ceremony;
+use crate::models::frost::FrostGroup;
+use crate::models::worksheet::{
+    CeremonyWorksheet, WorksheetFingerprint, WorksheetKind, WorksheetParticipant, WorksheetStep,
+};
+use chrono::{DateTime, Utc};
+
+fn fingerprint(label: String, fp: KeyFingerprint, detail: Option<String>) -> WorksheetFingerprint {
+    WorksheetFingerprint {
+        label,
+        hex: fp.hex,
+        words: fp.words,
+        detail,
+    }
+}
+
+/// Worksheet for a genesis ceremony in whatever phase it has reached.
+/// Commitments and signatures not yet submitted are left as open steps.
+pub fn genesis(ceremony: &Ceremony, locale: &str, now: DateTime<Utc>) -> CeremonyWorksheet {
+    let participants = ceremony
+        .operators
+        .iter()
+        .map(|op| {
+            let fp = KeyFingerprint::of_hex(&op.public_key_hex);
+            WorksheetParticipant {
+                name: op.name.clone(),
+                role: tr(locale, "worksheet-role-operator", &[]),
+                fingerprint_hex: fp.hex,
+                fingerprint_words: fp.words,
+                detail: op
+                    .commitment_hex
+                    .as_deref()
+                    .map(|c| tr(locale, "worksheet-commitment", &[("commitment", c.into())])),
+            }
+        })
+        .collect();
+
+    let mut steps = vec![WorksheetStep {
+        description: tr(
+            locale,
+            "worksheet-step-set-up",
+            &[("operators", ceremony.operators.len().into())],
+        ),
+        done: true,
+        at: Some(ceremony.created_at),
+    }];
+    for op in &ceremony.operators {
+        steps.push(WorksheetStep {
+            description: tr(
+                locale,
+                "worksheet-step-committed",
+                &[("name", op.name.as_str().into())],
+            ),
+            done: op.committed_at.is_some(),
+            at: op.committed_at,
+        });
+    }
+    let keys: u32 = ceremony.members.iter().map(|m| m.count).sum();
+    steps.push(WorksheetStep {
+        description: tr(locale, "worksheet-step-generated", &[("keys", keys.into())]),
+        done: ceremony.manifest.is_some(),
+        at: ceremony.manifest.as_ref().map(|m| m.generated_at),
+    });
+    for op in &ceremony.operators {
+        let attestation = ceremony
+            .attestations
+            .iter()
+            .find(|a| a.operator_id == op.id);
+        steps.push(WorksheetStep {
+            description: tr(
+                locale,
+                "worksheet-step-attested",
+                &[("name", op.name.as_str().into())],
+            ),
+            done: attestation.is_some(),
+            at: attestation.map(|a| a.signed_at),
+        });
+    }
+
+    let mut fingerprints = Vec::new();
+    if let Some(manifest) = &ceremony.manifest {
+        fingerprints.push(fingerprint(
+            tr(locale, "worksheet-manifest", &[]),
+            KeyFingerprint::of(&manifest_message(manifest)),
+            None,
+        ));
+        for key in &manifest.keys {
+            fingerprints.push(fingerprint(
+                format!("{} {}", key.key_type.as_str(), key.derivation_path),
+                KeyFingerprint::of_hex(&key.public_key_hex),
+                Some(key.address.clone()),
+            ));
+        }
+    }
+
+    CeremonyWorksheet {
+        kind: WorksheetKind::Genesis,
+        subject_id: ceremony.id.clone(),
+        title: tr(
+            locale,
+            "worksheet-genesis-title",
+            &[("name", ceremony.name.as_str().into())],
+        ),
+        generated_at: now,
+        complete: steps.iter().all(|s| s.done),
+        participants,
+        steps,
+        fingerprints,
+    }
+}
+
+/// Worksheet for a finished FROST group, as seen by the participant holding
+/// share `index`. Key generation keeps no times, so its steps have none.
+pub fn frost_group(
+    group: &FrostGroup,
+    index: u16,
+    locale: &str,
+    now: DateTime<Utc>,
+) -> CeremonyWorksheet {
+    let participants = group
+        .verifying_shares
+        .iter()
+        .zip(1u16..)
+        .map(|(share, n)| {
+            let fp = KeyFingerprint::of_hex(share);
+            let role = if n == index {
+                "worksheet-role-this-vault"
+            } else {
+                "worksheet-role-participant"
+            };
+            WorksheetParticipant {
+                name: tr(locale, "worksheet-participant", &[("index", n.into())]),
+                role: tr(locale, role, &[]),
+                fingerprint_hex: fp.hex,
+                fingerprint_words: fp.words,
+                detail: None,
+            }
+        })
+        .collect();
+    let steps = vec![
+        WorksheetStep {
+            description: tr(
+                locale,
+                "worksheet-step-group-agreed",
+                &[
+                    ("threshold", group.threshold.into()),
+                    ("participants", group.participants.into()),
+                ],
+            ),
+            done: true,
+            at: None,
+        },
+        WorksheetStep {
+            description: tr(
+                locale,
+                "worksheet-step-dkg-finished",
+                &[("index", index.into())],
+            ),
+            done: true,
+            at: None,
+        },
+    ];
+    let fingerprints = vec![
+        fingerprint(
+            tr(locale, "worksheet-group-key", &[]),
+            KeyFingerprint::of_hex(&group.group_public_hex),
+            Some(group.group_public_hex.clone()),
+        ),
+        fingerprint(
+            tr(locale, "worksheet-output-key", &[]),
+            KeyFingerprint::of_hex(&group.taproot_output_key_hex),
+            Some(group.taproot_output_key_hex.clone()),
+        ),
+    ];
+
+    CeremonyWorksheet {
+        kind: WorksheetKind::FrostGroup,
+        subject_id: group.group_id.clone(),
+        title: tr(
+            locale,
+            "worksheet-frost-title",
+            &[
+                ("threshold", group.threshold.into()),
+                ("participants", group.participants.into()),
+            ],
+        ),
+        generated_at: now,
+        complete: true,
+        participants,
+        steps,
+        fingerprints,
+    }
+}
+
+/// Print `worksheet` as a PDF, its headings in `locale`.
+pub fn render(worksheet: &CeremonyWorksheet, locale: &str) -> Vec<u8> {
+    let t = |id: &str| tr(locale, id, &[]);
+    let mut pdf = PdfWriter::new(&worksheet.title);
+    pdf.line(Style::Title, &worksheet.title);
+    pdf.line(
+        Style::Text,
+        &tr(
+            locale,
+            "report-generated",
+            &[("at", timestamp(worksheet.generated_at).into())],
+        ),
+    );
+    pdf.line(
+        Style::Text,
+        &tr(
+            locale,
+            "worksheet-subject",
+            &[("id", worksheet.subject_id.as_str().into())],
+        ),
+    );
+    pdf.line(
+        Style::Text,
+        &t(if worksheet.complete {
+            "worksheet-complete"
+        } else {
+            "worksheet-incomplete"
+        }),
+    );
+
+    pdf.line(Style::Heading, &t("worksheet-participants"));
+    let widths = [24, 14, 21];
+    pdf.line(
+        Style::Table,
+        &table_row(
+            &[
+                &t("worksheet-col-name"),
+                &t("worksheet-col-role"),
+                &t("worksheet-col-fingerprint"),
+                &t("worksheet-col-words"),
+            ],
+            &widths,
+        ),
+    );
+    for p in &worksheet.participants {
+        pdf.line(
+            Style::Table,
+            &table_row(
+                &[&p.name, &p.role, &p.fingerprint_hex, &p.fingerprint_words],
+                &widths,
+            ),
+        );
+        if let Some(detail) = &p.detail {
+            pdf.line(Style::Table, &format!("    {detail}"));
+        }
+    }
+
+    pdf.line(Style::Heading, &t("worksheet-steps"));
+    for step in &worksheet.steps {
+        let at = step.at.map(timestamp).unwrap_or_default();
+        let mark = if step.done { 'x' } else { ' ' };
+        pdf.line(
+            Style::Table,
+            &table_row(&[&format!("[{mark}]"), &at, &step.description], &[3, 20]),
+        );
+    }
+
+    if !worksheet.fingerprints.is_empty() {
+        pdf.line(Style::Heading, &t("worksheet-fingerprints"));
+        for fp in &worksheet.fingerprints {
+            pdf.line(Style::Text, &fp.label);
+            pdf.line(Style::Table, &format!("    {}  {}", fp.hex, fp.words));
+            if let Some(detail) = &fp.detail {
+                pdf.line(Style::Table, &format!("    {detail}"));
+            }
+        }
+    }
+
+    pdf.line(Style::Heading, &t("worksheet-signatures"));
+    pdf.line(Style::Text, &t("worksheet-signature-note"));
+    let witness = t("worksheet-witness");
+    for name in worksheet
+        .participants
+        .iter()
+        .map(|p| p.name.as_str())
+        .chain([witness.as_str()])
+    {
+        pdf.line(Style::Text, "");
+        pdf.line(Style::Table, &t("worksheet-signature-line"));
+        pdf.line(Style::Text, name);
+    }
+    pdf.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ceremony::{
+        CeremonyOperator, CeremonyPhase, GenesisManifest, ManifestKey, OperatorAttestation,
+    };
+    use crate::models::key::KeyType;
+    use crate::models::keyset::KeysetMemberSpec;
+
+    fn operator(id: &str, name: &str, committed: bool) -> CeremonyOperator {
+        CeremonyOperator {
+            id: id.to_string(),
+            name: name.to_string(),
+            public_key_hex: "ab".repeat(32),
+            commitment_hex: committed.then(|| "cd".repeat(32)),
+            committed_at: committed.then(Utc::now),
+        }
+    }
+
+    #[test]
+    fn test_genesis_worksheet_follows_the_ceremony() {
+        let now = Utc::now();
+        let mut ceremony = Ceremony {
+            id: "c1".to_string(),
+            name: "Mainnet".to_string(),
+            account: 0,
+            members: vec![KeysetMemberSpec {
+                key_type: "genesis".to_string(),
+                purpose: 44,
+                count: 1,
+            }],
+            operators: vec![operator("a", "Alice", true), operator("b", "Bob", false)],
+            phase: CeremonyPhase::Commit,
+            keyset_id: None,
+            manifest: None,
+            attestations: Vec::new(),
+            created_at: now,
+        };
+        let sheet = genesis(&ceremony, "en-US", now);
+        assert!(!sheet.complete);
+        assert_eq!(sheet.steps.len(), 6);
+        assert_eq!(sheet.steps.iter().filter(|s| s.done).count(), 2);
+        assert_eq!(sheet.steps[1].description, "Alice committed entropy");
+        assert!(sheet.fingerprints.is_empty());
+        assert!(sheet.participants[0].detail.is_some());
+
+        ceremony.operators[1] = operator("b", "Bob", true);
+        ceremony.phase = CeremonyPhase::Complete;
+        ceremony.manifest = Some(GenesisManifest {
+            ceremony_id: "c1".to_string(),
+            name: "Mainnet".to_string(),
+            account: 0,
+            operators: Vec::new(),
+            keys: vec![ManifestKey {
+                key_type: KeyType::Genesis,
+                derivation_path: "m/44'/0'/0'/0/0".to_string(),
+                public_key_hex: "ef".repeat(33),
+                address: "zap1genesis".to_string(),
+            }],
+            generated_at: now,
+        });
+        ceremony.attestations = ["a", "b"]
+            .map(|id| OperatorAttestation {
+                operator_id: id.to_string(),
+                signature_hex: String::new(),
+                signed_at: now,
+            })
+            .to_vec();
+        let sheet = genesis(&ceremony, "en-US", now);
+        assert!(sheet.complete);
+        assert_eq!(sheet.fingerprints.len(), 2);
+        assert_eq!(
+            sheet.fingerprints[1].hex,
+            KeyFingerprint::of_hex(&"ef".repeat(33)).hex
+        );
+
+        let pdf = render(&sheet, "en-US");
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(text.contains("Bob signed the manifest"));
+        assert!(text.contains("zap1genesis"));
+    }
+
+    #[test]
+    fn test_frost_worksheet_marks_this_vault() {
+        let group = FrostGroup {
+            group_id: "g1".to_string(),
+            threshold: 2,
+            participants: 3,
+            group_public_hex: "02".repeat(33),
+            verifying_shares: vec!["03".repeat(33); 3],
+            taproot_output_key_hex: "aa".repeat(32),
+        };
+        let sheet = frost_group(&group, 2, "en-US", Utc::now());
+        assert!(sheet.complete);
+        let roles: Vec<&str> = sheet.participants.iter().map(|p| p.role.as_str()).collect();
+        assert_eq!(roles, ["Participant", "This vault", "Participant"]);
+        assert_eq!(
+            sheet.fingerprints[1].detail.as_deref(),
+            Some(&*"aa".repeat(32))
+        );
+        let pdf = render(&sheet, "de");
+        assert!(String::from_utf8_lossy(&pdf).contains("Unterschrift"));
+    }
+}
@@ -0,0 +1,298 @@
+//! Compliance reports: aggregate figures for auditors.
+//!
+//! [`build`] counts over the unlocked keystore and item store and the vault
+//! metadata; nothing that identifies a key, address or person goes into the
+//! [`ComplianceReport`]. [`render`] writes it as JSON or CSV.
+
+use crate::models::address::csv_field;
+use crate::models::compliance::{percent, AccessSummary, ComplianceReport, ComplianceReportFormat};
+use crate::models::item::VaultItem;
+use crate::models::key::KeyEntry;
+use crate::models::vault::VaultState;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Access controls over the vaults of every profile. Profiles without a
+/// vault are left out.
+pub fn access_summary<'a>(vaults: impl IntoIterator<Item = &'a VaultState>) -> AccessSummary {
+    let mut access = AccessSummary::default();
+    for vault in vaults.into_iter().filter(|v| v.initialized) {
+        access.profiles += 1;
+        access.profiles_without_2fa += usize::from(!vault.yubikey_enabled);
+        access.profiles_with_outdated_kdf += usize::from(vault.needs_kdf_upgrade());
+    }
+    access
+}
+
+/// Aggregate a report from the unlocked stores. Trashed keys and items are
+/// left out. Pure so it can be tested without an app.
+pub fn build(
+    access: AccessSummary,
+    keys: &[KeyEntry],
+    items: &[VaultItem],
+    vault: &VaultState,
+    now: DateTime<Utc>,
+) -> ComplianceReport {
+    let history = &vault.backup_history;
+    let newest_verified = history
+        .iter()
+        .filter(|b| b.verified_at.is_some())
+        .map(|b| b.created_at)
+        .max();
+
+    let mut keys_by_type = BTreeMap::new();
+    let (mut live_keys, mut keys_in_verified_backup) = (0, 0);
+    for key in keys.iter().filter(|k| k.metadata.trashed_at.is_none()) {
+        live_keys += 1;
+        *keys_by_type
+            .entry(key.metadata.key_type.as_str().to_string())
+            .or_insert(0) += 1;
+        keys_in_verified_backup +=
+            usize::from(newest_verified.is_some_and(|at| key.metadata.created_at <= at));
+    }
+
+    let live_items = items.iter().filter(|i| i.trashed_at.is_none());
+    let (mut items_with_rotation, mut items_past_rotation) = (0, 0);
+    for item in live_items.filter(|i| i.expires_at.is_some()) {
+        items_with_rotation += 1;
+        items_past_rotation += usize::from(item.is_expired(now));
+    }
+
+    let drives: BTreeSet<&str> = history.iter().map(|b| b.drive_id.as_str()).collect();
+    ComplianceReport {
+        generated_at: now,
+        access,
+        keys: live_keys,
+        keys_by_type,
+        keys_in_verified_backup,
+        keys_in_verified_backup_percent: percent(keys_in_verified_backup, live_keys),
+        items_with_rotation,
+        items_past_rotation,
+        backups: history.len(),
+        verified_backups: history.iter().filter(|b| b.verified_at.is_some()).count(),
+        backup_drives: drives.len(),
+        last_verified_backup_at: history.iter().filter_map(|b| b.verified_at).max(),
+        policy: vault.security_policy,
+        verified_backup_requirement_met: vault
+            .security_policy
+            .require_verified_backup
+            .then(|| vault.has_verified_backup()),
+    }
+}
+
+fn render_csv(report: &ComplianceReport) -> String {
+    let at = |t: Option<DateTime<Utc>>| {
+        t.map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+            .unwrap_or_default()
+    };
+    let opt = |v: Option<String>| v.unwrap_or_default();
+    let a = &report.access;
+    let p = &report.policy;
+    let mut rows = vec![
+        ("generated_at".to_string(), at(Some(report.generated_at))),
+        ("profiles".to_string(), a.profiles.to_string()),
+        (
+            "profiles_without_2fa".to_string(),
+            a.profiles_without_2fa.to_string(),
+        ),
+        (
+            "profiles_with_outdated_kdf".to_string(),
+            a.profiles_with_outdated_kdf.to_string(),
+        ),
+        ("keys".to_string(), report.keys.to_string()),
+    ];
+    for (key_type, count) in &report.keys_by_type {
+        rows.push((format!("keys.{key_type}"), count.to_string()));
+    }
+    for (name, value) in [
+        (
+            "keys_in_verified_backup",
+            report.keys_in_verified_backup.to_string(),
+        ),
+        (
+            "keys_in_verified_backup_percent",
+            opt(report
+                .keys_in_verified_backup_percent
+                .map(|p| p.to_string())),
+        ),
+        (
+            "items_with_rotation",
+            report.items_with_rotation.to_string(),
+        ),
+        (
+            "items_past_rotation",
+            report.items_past_rotation.to_string(),
+        ),
+        ("backups", report.backups.to_string()),
+        ("verified_backups", report.verified_backups.to_string()),
+        ("backup_drives", report.backup_drives.to_string()),
+        (
+            "last_verified_backup_at",
+            at(report.last_verified_backup_at),
+        ),
+        (
+            "policy.require_reauth_for_decrypt",
+            p.require_reauth_for_decrypt.to_string(),
+        ),
+        (
+            "policy.reauth_window_secs",
+            p.reauth_window_secs.to_string(),
+        ),
+        (
+            "policy.deny_plaintext_export",
+            p.deny_plaintext_export.to_string(),
+        ),
+        (
+            "policy.require_verified_backup",
+            p.require_verified_backup.to_string(),
+        ),
+        (
+            "verified_backup_requirement_met",
+            opt(report
+                .verified_backup_requirement_met
+                .map(|m| m.to_string())),
+        ),
+    ] {
+        rows.push((name.to_string(), value));
+    }
+
+    let mut out = String::from("metric,value\r\n");
+    for (name, value) in &rows {
+        out.push_str(&format!("{},{}\r\n", csv_field(name), csv_field(value)));
+    }
+    out
+}
+
+pub fn render(
+    report: &ComplianceReport,
+    format: ComplianceReportFormat,
+) -> Result<Vec<u8>, serde_json::Error> {
+    Ok(match format {
+        ComplianceReportFormat::Json => serde_json::to_vec_pretty(report)?,
+        ComplianceReportFormat::Csv => render_csv(report).into_bytes(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::backup::BackupRecord;
+    use crate::models::item::{ApiTokenItem, ItemPayload};
+    use crate::models::key::KeyType;
+    use chrono::Duration;
+
+    fn key(index: u32, created_at: DateTime<Utc>) -> KeyEntry {
+        let mut key = KeyEntry::new(
+            KeyType::User,
+            44,
+            0,
+            index,
+            "pk",
+            "sk",
+            "zap1secretaddress",
+            "m/44'/0'/0'/0/0",
+        );
+        key.metadata.created_at = created_at;
+        key.metadata.label = Some("treasury cold key".to_string());
+        key
+    }
+
+    fn token(expires_at: Option<DateTime<Utc>>) -> VaultItem {
+        let mut item = VaultItem::new(
+            None,
+            ItemPayload::ApiToken(ApiTokenItem {
+                provider: "github".into(),
+                token: "ghp_secret".into(),
+                scopes: Vec::new(),
+                environment: None,
+                env_var: "T".into(),
+            }),
+        );
+        item.expires_at = expires_at;
+        item
+    }
+
+    #[test]
+    fn test_counts_without_identifying_data() {
+        let now = Utc::now();
+        let mut vault = VaultState {
+            initialized: true,
+            backup_history: vec![
+                BackupRecord {
+                    drive_id: "d1".to_string(),
+                    backup_id: "b1".to_string(),
+                    created_at: now - Duration::days(2),
+                    verified_at: Some(now - Duration::days(1)),
+                },
+                BackupRecord {
+                    drive_id: "d2".to_string(),
+                    backup_id: "b2".to_string(),
+                    created_at: now,
+                    verified_at: None,
+                },
+            ],
+            ..VaultState::default()
+        };
+        vault.security_policy.require_verified_backup = true;
+        let mut trashed = key(3, now - Duration::days(5));
+        trashed.metadata.trashed_at = Some(now);
+        let keys = [
+            key(0, now - Duration::days(5)),
+            key(1, now - Duration::days(3)),
+            key(2, now - Duration::hours(1)),
+            trashed,
+        ];
+        let items = [
+            token(Some(now - Duration::days(1))),
+            token(Some(now + Duration::days(10))),
+            token(None),
+        ];
+        let other = VaultState {
+            initialized: true,
+            yubikey_enabled: true,
+            ..VaultState::default()
+        };
+        let access = access_summary([&vault, &other, &VaultState::default()]);
+        let report = build(access, &keys, &items, &vault, now);
+
+        assert_eq!(
+            (report.access.profiles, report.access.profiles_without_2fa),
+            (2, 1)
+        );
+        assert_eq!(report.keys, 3);
+        assert_eq!(report.keys_in_verified_backup, 2);
+        assert_eq!(report.keys_in_verified_backup_percent, Some(66));
+        assert_eq!(
+            (report.items_with_rotation, report.items_past_rotation),
+            (2, 1)
+        );
+        assert_eq!(
+            (
+                report.backups,
+                report.verified_backups,
+                report.backup_drives
+            ),
+            (2, 1, 2)
+        );
+        assert_eq!(report.verified_backup_requirement_met, Some(true));
+
+        for format in [ComplianceReportFormat::Csv, ComplianceReportFormat::Json] {
+            let out = String::from_utf8(render(&report, format).unwrap()).unwrap();
+            for secret in [
+                "zap1secretaddress",
+                "treasury",
+                "ghp_secret",
+                "d1",
+                "b1",
+                "pk",
+            ] {
+                assert!(!out.contains(secret), "{format:?} leaks {secret}");
+            }
+        }
+        let csv = String::from_utf8(render(&report, ComplianceReportFormat::Csv).unwrap()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "metric,value");
+        assert_eq!(lines[6], "keys.user,3");
+        assert!(lines.contains(&"keys_in_verified_backup_percent,66"));
+    }
+}
@@ -0,0 +1,540 @@
+//! Portfolio reports: what the vault holds and how well it is backed up.
+//!
+//! [`build`] aggregates the live keys, the balances recorded for them, the
+//! backup history and the stored price snapshot into a [`PortfolioReport`].
+//! [`render`] writes it as JSON, CSV or a PDF drawn by [`pdf`], all without
+//! leaving the process. Reports for auditors live in [`compliance`], and
+//! printable key ceremony worksheets in [`ceremony`].
+
+pub mod ceremony;
+pub mod compliance;
+pub mod pdf;
+
+use crate::crypto::price;
+use crate::i18n::tr;
+use crate::models::address::{backup_status, csv_field};
+use crate::models::key::KeyEntry;
+use crate::models::portfolio::{
+    AssetHolding, BackupCoverage, KeyHolding, PortfolioReport, PortfolioReportFormat,
+    RecordedBalance, BALANCE_STALE_DAYS,
+};
+use crate::models::price::{AssetPrice, PriceSnapshot};
+use crate::models::vault::VaultState;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use pdf::{PdfWriter, Style};
+use std::collections::BTreeMap;
+
+fn coverage(keys: &[KeyEntry], live: &[&KeyEntry], vault: &VaultState) -> BackupCoverage {
+    let mut coverage = BackupCoverage {
+        keys: live.len(),
+        ..BackupCoverage::default()
+    };
+    for key in live {
+        let status = backup_status(keys, key, vault.address_gap_limit, &vault.backup_history);
+        coverage.mnemonic_recoverable += usize::from(status.mnemonic_recoverable);
+        coverage.within_scan_window += usize::from(status.within_scan_window);
+    }
+    let history = &vault.backup_history;
+    let mut drives: Vec<&str> = history.iter().map(|b| b.drive_id.as_str()).collect();
+    drives.sort_unstable();
+    drives.dedup();
+    coverage.drives = drives.len();
+    coverage.last_backup_at = history.iter().map(|b| b.created_at).max();
+    coverage.last_verified_backup_at = history.iter().filter_map(|b| b.verified_at).max();
+    coverage
+}
+
+/// Aggregate a report from the unlocked keystore and recorded balances.
+/// Balances of trashed or deleted keys are left out, and warnings are
+/// written in the vault's locale. Pure so it can be tested without an app.
+pub fn build(
+    vault_name: &str,
+    keys: &[KeyEntry],
+    balances: &[RecordedBalance],
+    vault: &VaultState,
+    prices: Option<&PriceSnapshot>,
+    now: DateTime<Utc>,
+) -> PortfolioReport {
+    let live: Vec<&KeyEntry> = keys
+        .iter()
+        .filter(|k| k.metadata.trashed_at.is_none())
+        .collect();
+    let mut keys_by_type = BTreeMap::new();
+    for key in &live {
+        *keys_by_type
+            .entry(key.metadata.key_type.as_str().to_string())
+            .or_insert(0) += 1;
+    }
+    let backup = coverage(keys, &live, vault);
+    let locale = vault.locale();
+    let price_of = |asset: &str| -> Option<&AssetPrice> {
+        prices.and_then(|p| p.prices.iter().find(|x| x.asset == asset))
+    };
+
+    let mut warnings = Vec::new();
+    let mut key_holdings = Vec::new();
+    let mut totals: BTreeMap<&str, (u128, usize)> = BTreeMap::new();
+    let mut stale_balances = 0;
+    for balance in balances {
+        let Some(key) = live.iter().find(|k| k.id == balance.key_id) else {
+            continue;
+        };
+        let Ok(amount) = balance.amount.parse::<u128>() else {
+            continue;
+        };
+        key_holdings.push(KeyHolding {
+            key_id: key.id.clone(),
+            label: key.metadata.label.clone(),
+            key_type: key.metadata.key_type.as_str().to_string(),
+            address: key.metadata.address.clone(),
+            asset: balance.asset.clone(),
+            amount: balance.amount.clone(),
+            value: price_of(&balance.asset).and_then(|p| price::fiat_value(amount, p)),
+            recorded_at: balance.recorded_at,
+        });
+        let total = totals.entry(&balance.asset).or_default();
+        total.0 = total.0.saturating_add(amount);
+        total.1 += 1;
+        if now - balance.recorded_at > Duration::days(BALANCE_STALE_DAYS) {
+            stale_balances += 1;
+        }
+    }
+    key_holdings.sort_by(|a, b| (&a.asset, &a.key_id).cmp(&(&b.asset, &b.key_id)));
+
+    let mut total_cents: Option<u128> = None;
+    let holdings: Vec<AssetHolding> = totals
+        .into_iter()
+        .map(|(asset, (amount, keys))| {
+            let cents = price_of(asset).and_then(|p| price::fiat_cents(amount, p));
+            match cents {
+                Some(c) => total_cents = Some(total_cents.unwrap_or(0).saturating_add(c)),
+                None if prices.is_some() => {
+                    warnings.push(tr(locale, "warning-no-price", &[("asset", asset.into())]))
+                }
+                None => {}
+            }
+            AssetHolding {
+                asset: asset.to_string(),
+                amount: amount.to_string(),
+                keys,
+                value: cents.map(price::format_cents),
+            }
+        })
+        .collect();
+
+    match prices {
+        Some(snapshot) => warnings.extend(price::age_warnings(snapshot.created_at, now, locale)),
+        None if !holdings.is_empty() => warnings.push(tr(locale, "warning-no-price-snapshot", &[])),
+        None => {}
+    }
+    if stale_balances > 0 {
+        warnings.push(tr(
+            locale,
+            "warning-stale-balances",
+            &[
+                ("count", stale_balances.into()),
+                ("days", BALANCE_STALE_DAYS.into()),
+            ],
+        ));
+    }
+    if backup.last_verified_backup_at.is_none() {
+        warnings.push(tr(locale, "warning-no-verified-backup", &[]));
+    }
+    let not_hd = backup.keys - backup.mnemonic_recoverable;
+    if not_hd > 0 {
+        warnings.push(tr(
+            locale,
+            "warning-keys-not-hd",
+            &[("count", not_hd.into())],
+        ));
+    }
+    let beyond_gap = backup.mnemonic_recoverable - backup.within_scan_window;
+    if beyond_gap > 0 {
+        warnings.push(tr(
+            locale,
+            "warning-keys-beyond-gap",
+            &[("count", beyond_gap.into())],
+        ));
+    }
+
+    PortfolioReport {
+        vault_name: vault_name.to_string(),
+        generated_at: now,
+        total_keys: live.len(),
+        keys_by_type,
+        backup,
+        currency: prices.map(|p| p.currency.clone()),
+        prices_as_of: prices.map(|p| p.created_at),
+        holdings,
+        key_holdings,
+        total_value: total_cents.map(price::format_cents),
+        warnings,
+    }
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn render_csv(report: &PortfolioReport) -> String {
+    let currency = report.currency.as_deref().unwrap_or_default();
+    let mut out = String::from(
+        "row,asset,key_id,label,key_type,address,amount,keys,value,currency,recorded_at\r\n",
+    );
+    for h in &report.holdings {
+        let row = [
+            "total".to_string(),
+            csv_field(&h.asset),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            h.amount.clone(),
+            h.keys.to_string(),
+            h.value.clone().unwrap_or_default(),
+            csv_field(currency),
+            String::new(),
+        ];
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    for h in &report.key_holdings {
+        let row = [
+            "key".to_string(),
+            csv_field(&h.asset),
+            csv_field(&h.key_id),
+            csv_field(h.label.as_deref().unwrap_or_default()),
+            csv_field(&h.key_type),
+            csv_field(&h.address),
+            h.amount.clone(),
+            String::new(),
+            h.value.clone().unwrap_or_default(),
+            csv_field(currency),
+            timestamp(h.recorded_at),
+        ];
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// `cells` padded to `widths`, the last cell left as it is.
+fn table_row(cells: &[&str], widths: &[usize]) -> String {
+    let mut row = String::new();
+    for (i, cell) in cells.iter().enumerate() {
+        match widths.get(i) {
+            Some(width) => row.push_str(&format!("{cell:<width$} ")),
+            None => row.push_str(cell),
+        }
+    }
+    row.trim_end().to_string()
+}
+
+fn render_pdf(report: &PortfolioReport, locale: &str) -> Vec<u8> {
+    let t = |id: &str| tr(locale, id, &[]);
+    let title = tr(
+        locale,
+        "report-title",
+        &[("vault", report.vault_name.as_str().into())],
+    );
+    let mut pdf = PdfWriter::new(&title);
+    let currency = report.currency.as_deref().unwrap_or("-");
+    pdf.line(Style::Title, &title);
+    pdf.line(
+        Style::Text,
+        &tr(
+            locale,
+            "report-generated",
+            &[("at", timestamp(report.generated_at).into())],
+        ),
+    );
+    match report.prices_as_of {
+        Some(at) => pdf.line(
+            Style::Text,
+            &tr(
+                locale,
+                "report-values-in",
+                &[("currency", currency.into()), ("at", timestamp(at).into())],
+            ),
+        ),
+        None => pdf.line(Style::Text, &t("report-no-prices")),
+    }
+
+    pdf.line(Style::Heading, &t("report-holdings"));
+    if report.holdings.is_empty() {
+        pdf.line(Style::Text, &t("report-no-balances"));
+    } else {
+        let widths = [12, 40, 6];
+        pdf.line(
+            Style::Table,
+            &table_row(
+                &[
+                    &t("report-col-asset"),
+                    &t("report-col-amount-base"),
+                    &t("report-col-keys"),
+                    &t("report-col-value"),
+                ],
+                &widths,
+            ),
+        );
+        for h in &report.holdings {
+            let keys = h.keys.to_string();
+            pdf.line(
+                Style::Table,
+                &table_row(
+                    &[
+                        &h.asset,
+                        &h.amount,
+                        &keys,
+                        h.value.as_deref().unwrap_or("-"),
+                    ],
+                    &widths,
+                ),
+            );
+        }
+        if let Some(total) = &report.total_value {
+            pdf.line(
+                Style::Text,
+                &tr(
+                    locale,
+                    "report-total-value",
+                    &[
+                        ("total", total.as_str().into()),
+                        ("currency", currency.into()),
+                    ],
+                ),
+            );
+        }
+    }
+
+    pdf.line(Style::Heading, &t("report-keys"));
+    pdf.line(
+        Style::Text,
+        &tr(
+            locale,
+            "report-live-keys",
+            &[("count", report.total_keys.into())],
+        ),
+    );
+    for (key_type, count) in &report.keys_by_type {
+        pdf.line(
+            Style::Table,
+            &table_row(&[key_type, &count.to_string()], &[20]),
+        );
+    }
+
+    let b = &report.backup;
+    let at_or_never = |at: Option<DateTime<Utc>>| at.map_or_else(|| t("report-never"), timestamp);
+    pdf.line(Style::Heading, &t("report-backup-coverage"));
+    for line in [
+        tr(
+            locale,
+            "report-mnemonic-recoverable",
+            &[
+                ("recoverable", b.mnemonic_recoverable.into()),
+                ("keys", b.keys.into()),
+            ],
+        ),
+        tr(
+            locale,
+            "report-within-gap",
+            &[("count", b.within_scan_window.into())],
+        ),
+        tr(
+            locale,
+            "report-backup-drives",
+            &[("count", b.drives.into())],
+        ),
+        tr(
+            locale,
+            "report-last-backup",
+            &[("at", at_or_never(b.last_backup_at).into())],
+        ),
+        tr(
+            locale,
+            "report-last-verified-backup",
+            &[("at", at_or_never(b.last_verified_backup_at).into())],
+        ),
+    ] {
+        pdf.line(Style::Text, &line);
+    }
+
+    if !report.key_holdings.is_empty() {
+        pdf.line(Style::Heading, &t("report-balances-by-key"));
+        let widths = [24, 8, 28, 14];
+        pdf.line(
+            Style::Table,
+            &table_row(
+                &[
+                    &t("report-col-key"),
+                    &t("report-col-asset"),
+                    &t("report-col-amount"),
+                    &t("report-col-value"),
+                    &t("report-col-recorded"),
+                ],
+                &widths,
+            ),
+        );
+        for h in &report.key_holdings {
+            let recorded = h.recorded_at.format("%Y-%m-%d").to_string();
+            pdf.line(
+                Style::Table,
+                &table_row(
+                    &[
+                        h.label.as_deref().unwrap_or(&h.key_id),
+                        &h.asset,
+                        &h.amount,
+                        h.value.as_deref().unwrap_or("-"),
+                        &recorded,
+                    ],
+                    &widths,
+                ),
+            );
+        }
+    }
+
+    if !report.warnings.is_empty() {
+        pdf.line(Style::Heading, &t("report-warnings"));
+        for warning in &report.warnings {
+            pdf.line(Style::Text, &format!("- {warning}"));
+        }
+    }
+    pdf.finish()
+}
+
+/// Write `report` in `format`. PDF text is written in `locale`; JSON and
+/// CSV carry only data.
+pub fn render(
+    report: &PortfolioReport,
+    format: PortfolioReportFormat,
+    locale: &str,
+) -> Result<Vec<u8>, serde_json::Error> {
+    Ok(match format {
+        PortfolioReportFormat::Json => serde_json::to_vec_pretty(report)?,
+        PortfolioReportFormat::Csv => render_csv(report).into_bytes(),
+        PortfolioReportFormat::Pdf => render_pdf(report, locale),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::backup::BackupRecord;
+    use crate::models::key::KeyType;
+
+    fn key(index: u32, derivation_path: &str) -> KeyEntry {
+        KeyEntry::new(
+            KeyType::User,
+            44,
+            0,
+            index,
+            "pk",
+            "sk",
+            "zap1",
+            derivation_path,
+        )
+    }
+
+    fn balance(key: &KeyEntry, asset: &str, amount: &str, at: DateTime<Utc>) -> RecordedBalance {
+        RecordedBalance {
+            key_id: key.id.clone(),
+            asset: asset.to_string(),
+            amount: amount.to_string(),
+            recorded_at: at,
+            note: None,
+        }
+    }
+
+    fn snapshot(at: DateTime<Utc>) -> PriceSnapshot {
+        PriceSnapshot {
+            format: "zap-price-snapshot".to_string(),
+            version: 1,
+            created_at: at,
+            currency: "USD".to_string(),
+            prices: vec![AssetPrice {
+                asset: "BTC".to_string(),
+                price: "60000".to_string(),
+                decimals: 8,
+            }],
+            public_key_hex: String::new(),
+            signature_hex: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_totals_values_and_warnings() {
+        let now = Utc::now();
+        let a = key(0, "m/44'/0'/0'/0/0");
+        let b = key(1, "m/44'/0'/0'/0/1");
+        let imported = key(0, "");
+        let mut trashed = key(2, "m/44'/0'/0'/0/2");
+        trashed.metadata.trashed_at = Some(now);
+        let balances = vec![
+            balance(&a, "BTC", "150000000", now),
+            balance(&b, "BTC", "50000000", now - Duration::days(10)),
+            balance(&imported, "ATOM", "5000000", now),
+            balance(&trashed, "BTC", "100000000", now),
+        ];
+        let vault = VaultState {
+            backup_history: vec![BackupRecord {
+                drive_id: "d".to_string(),
+                backup_id: "b".to_string(),
+                created_at: now,
+                verified_at: Some(now),
+            }],
+            ..VaultState::default()
+        };
+        let keys = [a, b, imported, trashed];
+        let prices = snapshot(now);
+        let report = build("Main", &keys, &balances, &vault, Some(&prices), now);
+
+        assert_eq!(report.total_keys, 3);
+        assert_eq!(report.backup.mnemonic_recoverable, 2);
+        assert_eq!(report.backup.drives, 1);
+        assert_eq!(report.holdings.len(), 2);
+        let btc = report.holdings.iter().find(|h| h.asset == "BTC").unwrap();
+        assert_eq!(
+            (btc.amount.as_str(), btc.keys, btc.value.as_deref()),
+            ("200000000", 2, Some("120000.00"))
+        );
+        assert_eq!(report.total_value.as_deref(), Some("120000.00"));
+        let warnings = report.warnings.join("\n");
+        assert!(warnings.contains("no price for ATOM"));
+        assert!(warnings.contains("1 balances were recorded"));
+        assert!(warnings.contains("1 keys are not derived"));
+        assert!(!warnings.contains("no verified backup"));
+    }
+
+    #[test]
+    fn test_renders_every_format() {
+        let now = Utc::now();
+        let a = key(0, "m/44'/0'/0'/0/0");
+        let balances = vec![balance(&a, "BTC", "100000000", now)];
+        let report = build(
+            "Main",
+            &[a],
+            &balances,
+            &VaultState::default(),
+            Some(&snapshot(now)),
+            now,
+        );
+
+        let csv = String::from_utf8(render(&report, PortfolioReportFormat::Csv, "en-US").unwrap())
+            .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("total,BTC,,,,,100000000,1,60000.00,USD,"));
+        assert!(lines[2].starts_with("key,BTC,"));
+
+        let json = render(&report, PortfolioReportFormat::Json, "en-US").unwrap();
+        let back: PortfolioReport = serde_json::from_slice(&json).unwrap();
+        assert_eq!(back, report);
+
+        let pdf = render(&report, PortfolioReportFormat::Pdf, "en-US").unwrap();
+        assert!(pdf.starts_with(b"%PDF-"));
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("(Total value: 60000.00 USD)"));
+        assert!(text
+            .contains("(BTC          100000000                                1      60000.00)"));
+    }
+}
@@ -0,0 +1,260 @@
+//! A small PDF writer for text reports.
+//!
+//! Pages are A4 and hold lines of the standard Helvetica and Courier fonts,
+//! which every reader has, so nothing is embedded. There are no images and
+//! no compression: enough for a report, with no PDF dependency.
+
+use std::fmt::Write as _;
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+/// Fonts every page can use, by resource name.
+const FONTS: [(&str, &str); 3] = [
+    ("F1", "Helvetica"),
+    ("F2", "Helvetica-Bold"),
+    ("F3", "Courier"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Title,
+    Heading,
+    Text,
+    /// Monospaced, so columns padded with spaces line up.
+    Table,
+}
+
+impl Style {
+    fn font(self) -> &'static str {
+        match self {
+            Style::Title | Style::Heading => "F2",
+            Style::Text => "F1",
+            Style::Table => "F3",
+        }
+    }
+
+    fn size(self) -> f32 {
+        match self {
+            Style::Title => 16.0,
+            Style::Heading => 12.0,
+            Style::Text => 10.0,
+            Style::Table => 8.0,
+        }
+    }
+
+    /// Vertical space a line takes, with room above titles and headings.
+    fn leading(self) -> f32 {
+        match self {
+            Style::Title | Style::Heading => self.size() * 2.0,
+            Style::Text | Style::Table => self.size() * 1.4,
+        }
+    }
+
+    /// Most characters that fit across the page. Courier is 0.6 em wide;
+    /// Helvetica averages well under that, so the same bound is safe.
+    pub fn max_chars(self) -> usize {
+        ((PAGE_WIDTH - 2.0 * MARGIN) / (self.size() * 0.6)) as usize
+    }
+}
+
+/// Break `text` into lines of at most `width` characters, at spaces where
+/// possible.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split(' ') {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > width {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+        let word: String = word.into_iter().collect();
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// `text` as a PDF string literal in WinAnsi encoding, which matches
+/// Latin-1 for the characters it covers. Anything else becomes `?`.
+fn literal(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('(');
+    for c in text.chars() {
+        match c {
+            '\\' | '(' | ')' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            '\u{a0}'..='\u{ff}' => {
+                let _ = write!(out, "\\{:03o}", c as u32);
+            }
+            _ => out.push('?'),
+        }
+    }
+    out.push(')');
+    out
+}
+
+/// Lines laid out top to bottom, starting a new page when one fills up.
+pub struct PdfWriter {
+    title: String,
+    pages: Vec<String>,
+    y: f32,
+}
+
+impl PdfWriter {
+    pub fn new(title: &str) -> Self {
+        PdfWriter {
+            title: title.to_string(),
+            pages: vec![String::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    /// Write `text`, wrapped to the page width.
+    pub fn line(&mut self, style: Style, text: &str) {
+        for line in wrap(text, style.max_chars()) {
+            if self.y - style.leading() < MARGIN {
+                self.pages.push(String::new());
+                self.y = PAGE_HEIGHT - MARGIN;
+            }
+            self.y -= style.leading();
+            let page = self.pages.last_mut().expect("there is always a page");
+            let _ = writeln!(
+                page,
+                "BT /{} {} Tf {} {:.1} Td {} Tj ET",
+                style.font(),
+                style.size(),
+                MARGIN,
+                self.y,
+                literal(&line)
+            );
+        }
+    }
+
+    /// The finished document, with a page number at the foot of each page.
+    pub fn finish(self) -> Vec<u8> {
+        let count = self.pages.len();
+        let mut objects: Vec<String> = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {count} >>",
+                (0..count)
+                    .map(|i| format!("{} 0 R", 7 + 2 * i))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        ];
+        for (_, name) in FONTS {
+            objects.push(format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{name} /Encoding /WinAnsiEncoding >>"
+            ));
+        }
+        objects.push(format!(
+            "<< /Title {} /Producer (ZAP Quantum Vault) >>",
+            literal(&self.title)
+        ));
+        let fonts: String = FONTS
+            .iter()
+            .enumerate()
+            .map(|(i, (res, _))| format!("/{res} {} 0 R ", 3 + i))
+            .collect();
+        for (i, mut content) in self.pages.into_iter().enumerate() {
+            let _ = writeln!(
+                content,
+                "BT /F1 8 Tf {} {} Td {} Tj ET",
+                MARGIN,
+                MARGIN / 2.0,
+                literal(&format!("{} - page {} of {count}", self.title, i + 1))
+            );
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << {fonts}>> >> /Contents {} 0 R >>",
+                8 + 2 * i
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{content}endstream",
+                content.len()
+            ));
+        }
+
+        let mut out = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n{object}\nendobj\n", i + 1).as_bytes());
+        }
+        let xref = out.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(table, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            table,
+            "trailer\n<< /Size {} /Root 1 0 R /Info 6 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+        out.extend_from_slice(table.as_bytes());
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xref_points_at_objects() {
+        let mut pdf = PdfWriter::new("Report");
+        for i in 0..120 {
+            pdf.line(Style::Text, &format!("line {i}"));
+        }
+        let out = pdf.finish();
+        let text = String::from_utf8_lossy(&out);
+        assert!(out.starts_with(b"%PDF-1.4"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("/Count 3"));
+
+        let xref: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(out[xref..].starts_with(b"xref"));
+        for (n, entry) in text
+            .lines()
+            .skip_while(|l| *l != "xref")
+            .skip(3)
+            .take(12)
+            .enumerate()
+        {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(out[offset..].starts_with(format!("{} 0 obj", n + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_text_is_escaped_and_wrapped() {
+        assert_eq!(literal("a (b) \\ é ✓"), "(a \\(b\\) \\\\ \\351 ?)");
+        let lines = wrap(&format!("short {}", "x".repeat(25)), 10);
+        assert_eq!(lines, vec!["short", "xxxxxxxxxx", "xxxxxxxxxx", "xxxxx"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+}
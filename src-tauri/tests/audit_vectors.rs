@@ -0,0 +1,123 @@
+// Known-answer vectors for every key generator. Run with
+// `cargo test --features audit-vectors --test audit_vectors`.
+#![cfg(feature = "audit-vectors")]
+
+use zap_quantum_vault_lib::crypto::{audit, mnemonic, ssh, wireguard};
+use zap_quantum_vault_lib::models::item::SshKeyAlgorithm;
+
+fn unhex32(s: &str) -> [u8; 32] {
+    hex::decode(s).unwrap().try_into().unwrap()
+}
+
+// ==================== BIP39 (Trezor reference vectors) ====================
+
+#[test]
+fn bip39_reference_vectors() {
+    let vectors = [
+        (
+            "00000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+        ),
+        (
+            "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+        ),
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+            "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd3097170af7a4d73245cafa9c3cca8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8",
+        ),
+    ];
+    for (entropy, phrase, seed) in vectors {
+        let generated = mnemonic::mnemonic_from_entropy(
+            &hex::decode(entropy).unwrap(),
+            mnemonic::MnemonicLanguage::English,
+        )
+        .unwrap();
+        assert_eq!(generated, phrase);
+        let derived = mnemonic::mnemonic_to_seed_with_passphrase(phrase, "TREZOR").unwrap();
+        assert_eq!(hex::encode(derived), seed);
+    }
+}
+
+// ==================== HD derivation ====================
+
+#[test]
+fn hd_derivation_vector() {
+    // ZAP's own derivation has no external reference, so these values pin it
+    // against regressions. ML-DSA-87 keygen from the key seed is covered by
+    // the ml-dsa crate's FIPS 204 vectors.
+    let v = audit::hd_vector(&[0u8; 32], "", 0, 0, 0).unwrap();
+    assert_eq!(v.path, "m/44'/9999'/0'/0'/0'");
+    assert_eq!(
+        v.key_seed_hex,
+        "193092dbb25eba14f01f9c09b4deed1cdfd35f762d1b6ed0fc4126f10324b6fd"
+    );
+    assert_eq!(
+        v.hybrid_ed25519_public_hex,
+        "63138564ddd7adeefb0608f412f22e1ce662175e56190b34f479ff166866ca89"
+    );
+    assert!(v.address.starts_with("zap1"));
+
+    // Every path component changes the key seed.
+    let other = audit::hd_vector(&[0u8; 32], "", 0, 0, 1).unwrap();
+    assert_ne!(other.key_seed_hex, v.key_seed_hex);
+    let with_pass = audit::hd_vector(&[0u8; 32], "TREZOR", 0, 0, 0).unwrap();
+    assert_ne!(with_pass.seed_hex, v.seed_hex);
+    assert_eq!(audit::hd_vector(&[0u8; 32], "", 0, 0, 0).unwrap(), v);
+}
+
+// ==================== WireGuard (RFC 7748 §6.1) ====================
+
+#[test]
+fn wireguard_x25519_vectors() {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    let vectors = [
+        (
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+            "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a",
+        ),
+        (
+            "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb",
+            "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f",
+        ),
+    ];
+    for (private, public) in vectors {
+        let pair = wireguard::keypair_from_entropy(&unhex32(private));
+        assert_eq!(
+            STANDARD.decode(&pair.public_key).unwrap(),
+            hex::decode(public).unwrap()
+        );
+    }
+}
+
+// ==================== SSH ====================
+
+#[test]
+fn ssh_ed25519_rfc8032_vector() {
+    let secret = unhex32("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60");
+    let pair =
+        ssh::generate_from_entropy(SshKeyAlgorithm::Ed25519, "audit", None, &secret).unwrap();
+    let public = ssh_key::PublicKey::from_openssh(&pair.public_key_openssh).unwrap();
+    assert_eq!(
+        hex::encode(public.key_data().ed25519().unwrap().0),
+        "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+    );
+}
+
+#[test]
+fn ssh_seeded_keys_are_reproducible() {
+    let entropy = [7u8; 32];
+    for (algorithm, bits) in [
+        (SshKeyAlgorithm::EcdsaP256, None),
+        (SshKeyAlgorithm::Rsa, Some(2048)),
+    ] {
+        let a = ssh::generate_from_entropy(algorithm, "audit", bits, &entropy).unwrap();
+        let b = ssh::generate_from_entropy(algorithm, "audit", bits, &entropy).unwrap();
+        assert_eq!(a.fingerprint, b.fingerprint);
+        let c = ssh::generate_from_entropy(algorithm, "audit", bits, &[8u8; 32]).unwrap();
+        assert_ne!(a.fingerprint, c.fingerprint);
+    }
+}
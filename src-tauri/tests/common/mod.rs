@@ -0,0 +1,169 @@
+//! Shared fixtures for integration tests: an in-memory vault that mirrors what
+//! the Tauri commands keep in managed state and write to disk, without an
+//! `AppHandle` or a data directory.
+
+#![allow(dead_code)]
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use zap_quantum_vault_lib::commands::items::{decrypt_items, encrypt_items};
+use zap_quantum_vault_lib::commands::keys::{decrypt_keys, encrypt_keys};
+use zap_quantum_vault_lib::crypto::{address, hd_derivation, mldsa87};
+use zap_quantum_vault_lib::models::item::{ItemPayload, VaultItem, WireGuardItem};
+use zap_quantum_vault_lib::models::key::{KeyEntry, KeyType};
+use zap_quantum_vault_lib::models::vault::VaultState;
+
+/// An unlocked vault held entirely in memory: the session key and master
+/// seed the real commands keep in `SessionKey` / `MasterSeed`, the plaintext
+/// `vault.json` state, and the decrypted keystore and item store.
+pub struct VaultFixture {
+    pub session_key: [u8; 32],
+    pub master_seed: [u8; 64],
+    pub vault: VaultState,
+    pub keys: Vec<KeyEntry>,
+    pub items: Vec<VaultItem>,
+}
+
+/// The encrypted on-disk form of a [`VaultFixture`]: `vault.json` text plus the
+/// keystore and item store blobs.
+pub struct PersistedVault {
+    pub vault_json: String,
+    pub keys_blob: Vec<u8>,
+    pub items_blob: Vec<u8>,
+}
+
+impl VaultFixture {
+    /// An empty, initialized vault whose secrets are derived from `seed`, so
+    /// fixtures are reproducible.
+    pub fn new(seed: u8) -> Self {
+        Self {
+            session_key: [seed; 32],
+            master_seed: [seed.wrapping_add(1); 64],
+            vault: VaultState {
+                initialized: true,
+                ..VaultState::default()
+            },
+            keys: Vec::new(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Derive the HD key at `purpose/account/index` from the fixture's master
+    /// seed, as `generate_key` does, and add it to the keystore.
+    pub fn derive_key(
+        &mut self,
+        key_type: KeyType,
+        purpose: u32,
+        account: u32,
+        index: u32,
+    ) -> &KeyEntry {
+        let path = hd_derivation::zap_path(purpose, account, index);
+        let (pk, sk) = mldsa87::from_seed(&hd_derivation::derive_seed_from_master(
+            &self.master_seed,
+            &path,
+        ));
+        self.keys.push(KeyEntry::new(
+            key_type,
+            purpose,
+            account,
+            index,
+            &pk.to_hex(),
+            &sk.to_hex(),
+            &address::derive_address(pk.as_bytes()),
+            &path.to_string(),
+        ));
+        self.keys.last().unwrap()
+    }
+
+    pub fn with_hd_keys(mut self, n: u32) -> Self {
+        for i in 0..n {
+            self.derive_key(KeyType::User, 0, 0, i);
+        }
+        self
+    }
+
+    pub fn with_item(mut self, item: VaultItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// Encrypt everything the way the commands persist it.
+    pub fn persist(&self) -> PersistedVault {
+        PersistedVault {
+            vault_json: serde_json::to_string_pretty(&self.vault).unwrap(),
+            keys_blob: encrypt_keys(&self.session_key, &self.keys).unwrap(),
+            items_blob: encrypt_items(&self.session_key, &self.items).unwrap(),
+        }
+    }
+
+    /// Load a persisted vault back, as an app restart plus unlock would.
+    pub fn reopen(
+        persisted: &PersistedVault,
+        session_key: [u8; 32],
+        master_seed: [u8; 64],
+    ) -> Result<Self, String> {
+        Ok(Self {
+            session_key,
+            master_seed,
+            vault: serde_json::from_str(&persisted.vault_json).map_err(|e| e.to_string())?,
+            keys: decrypt_keys(&session_key, &persisted.keys_blob).map_err(|e| e.to_string())?,
+            items: decrypt_items(&session_key, &persisted.items_blob).map_err(|e| e.to_string())?,
+        })
+    }
+}
+
+/// A small WireGuard item with recognisable secret material.
+pub fn sample_wireguard_item(label: &str) -> VaultItem {
+    VaultItem::new(
+        Some(label.to_string()),
+        ItemPayload::WireGuard(WireGuardItem {
+            private_key: format!("PRIVATE-{label}"),
+            public_key: format!("PUBLIC-{label}"),
+            addresses: vec!["10.0.0.2/32".to_string()],
+            dns: Vec::new(),
+            listen_port: None,
+            mtu: None,
+            peers: Vec::new(),
+        }),
+    )
+}
+
+/// Deterministic RNG for property-style tests: a failing case can be replayed
+/// from the printed seed.
+pub fn rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// A random printable string of up to `max` characters, including non-ASCII.
+pub fn random_text(rng: &mut StdRng, max: usize) -> String {
+    const ALPHABET: &[char] = &['a', 'Z', '0', ' ', '-', '_', 'é', 'ß', '鍵', '🔑'];
+    let len = rng.gen_range(0..=max);
+    (0..len)
+        .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())])
+        .collect()
+}
+
+/// A keystore entry as written by the first release: no description, tags,
+/// derivation path, usage or keyset fields.
+pub const LEGACY_KEY_JSON: &str = r#"[{
+    "id": "legacy-1",
+    "metadata": {
+        "key_type": "Validator",
+        "purpose": 0,
+        "account": 0,
+        "index": 7,
+        "address": "zap1legacy",
+        "created_at": "2025-01-01T00:00:00Z",
+        "label": "old validator"
+    },
+    "public_key_hex": "aa",
+    "encrypted_secret_hex": "bb"
+}]"#;
+
+/// `vault.json` as written by the first release: no item store, YubiKey, KDF
+/// parameters, master seed, gap limit or keysets.
+pub const LEGACY_VAULT_JSON: &str = r#"{
+    "initialized": true,
+    "salt_hex": "00112233",
+    "verifier_hash_hex": "aa:bb"
+}"#;
@@ -4,15 +4,32 @@ use zap_quantum_vault_lib::commands::airgap::{
     record_nonce, secret_to_public_hex, signing_message, verify_envelope, QrRequest,
     ENVELOPE_VERSION, MAX_AGE_SECS, MAX_SKEW_SECS, NONCE_SIZE,
 };
+use zap_quantum_vault_lib::commands::health::{check_item, check_key};
+use zap_quantum_vault_lib::commands::items::{collect_expired, decrypt_items, encrypt_items};
 use zap_quantum_vault_lib::commands::keys::{decrypt_keys, encrypt_keys};
+use zap_quantum_vault_lib::commands::limits::RateWindow;
+use zap_quantum_vault_lib::commands::quick_access::{rank_quick_access, QuickAccessItem};
+use zap_quantum_vault_lib::commands::selftest::run_selftest;
 use zap_quantum_vault_lib::commands::signing::{SignRequest, VerifyRequest};
+use zap_quantum_vault_lib::commands::trash::{collect_trash, take_purgeable};
 use zap_quantum_vault_lib::commands::vault::{
     UnlockThrottle, BASE_LOCKOUT_SECS, MAX_LOCKOUT_SECS, MAX_UNLOCK_ATTEMPTS,
 };
-use zap_quantum_vault_lib::crypto::{address, encryption, hd_derivation, kdf, mldsa87, mnemonic};
+use zap_quantum_vault_lib::crypto::{
+    address, data_key, emergency, encryption, hd_derivation, kdf, mldsa87, mnemonic, ssh, wireguard,
+};
+use zap_quantum_vault_lib::error::VaultError;
 use zap_quantum_vault_lib::models::airgap::{AirGapEnvelope, TransferType};
+use zap_quantum_vault_lib::models::emergency::{EmergencyGrant, EmergencyStatus, EscrowContents};
+use zap_quantum_vault_lib::models::health::HealthProblem;
+use zap_quantum_vault_lib::models::item::{
+    ItemPayload, RotationHook, SshKeyAlgorithm, SshKeyItem, VaultItem, WireGuardItem, WireGuardPeer,
+};
 use zap_quantum_vault_lib::models::key::{KeyEntry, KeyEntryPublic, KeyType};
-use zap_quantum_vault_lib::models::vault::VaultState;
+use zap_quantum_vault_lib::models::rate_limit::{RateLimit, RateLimits, SensitiveOp};
+use zap_quantum_vault_lib::models::selftest::{CheckStatus, SelfTestCheckId};
+use zap_quantum_vault_lib::models::trash::TrashKind;
+use zap_quantum_vault_lib::models::vault::{KdfProfile, VaultState};
 
 /// Helper: build a small set of key entries for keystore tests.
 fn sample_key_entries(n: usize) -> Vec<KeyEntry> {
@@ -544,6 +561,7 @@ fn e2e_vault_state_kdf_and_seed_defaults_for_legacy_json() {
     assert_eq!(state.argon2_memory_kib, kdf::ARGON2_MEMORY_KIB);
     assert_eq!(state.argon2_iterations, kdf::ARGON2_ITERATIONS);
     assert!(state.master_seed_enc_hex.is_empty());
+    assert!(state.data_key.is_none());
 }
 
 #[test]
@@ -759,6 +777,23 @@ fn e2e_change_password_fresh_salt_changes_verifier() {
     assert!(vault.unlock("password").unwrap());
 }
 
+#[test]
+fn e2e_change_password_rewraps_data_key() {
+    // Stores are sealed under the data key; a password change only re-wraps
+    // it, so the keystore blob written before the change still loads.
+    let old_enc = derive_enc_key("old_password", &kdf::generate_salt());
+    let new_enc = derive_enc_key("new_password", &kdf::generate_salt());
+    let dek = data_key::generate_data_key();
+    let wrapped = data_key::wrap(&old_enc, &dek, chrono::Utc::now()).unwrap();
+    let blob = encrypt_keys(&dek, &sample_key_entries(2)).unwrap();
+
+    let unwrapped = data_key::unwrap(&old_enc, &wrapped).unwrap();
+    let rewrapped = data_key::wrap(&new_enc, &unwrapped, chrono::Utc::now()).unwrap();
+    let reloaded = data_key::unwrap(&new_enc, &rewrapped).unwrap();
+    assert_eq!(decrypt_keys(&reloaded, &blob).unwrap().len(), 2);
+    assert!(data_key::unwrap(&old_enc, &rewrapped).is_err());
+}
+
 // ==================== IPC Secret Redaction E2E ====================
 
 #[test]
@@ -1054,6 +1089,58 @@ fn e2e_unlock_throttle_success_resets() {
     assert!(t.check(now).is_ok());
 }
 
+// ==================== Sensitive Command Rate Limiting E2E ====================
+
+#[test]
+fn e2e_rate_window_rejects_past_limit_and_reopens() {
+    let mut w = RateWindow::default();
+    let limit = RateLimit {
+        max_calls: 3,
+        window_secs: 60,
+    };
+    for t in [1_000u64, 1_010, 1_020] {
+        assert!(w.check(SensitiveOp::Decrypt, limit, t).is_ok());
+    }
+    match w.check(SensitiveOp::Decrypt, limit, 1_030) {
+        Err(VaultError::RateLimited {
+            operation,
+            retry_after_secs,
+        }) => {
+            assert_eq!(operation, "decrypt");
+            assert_eq!(retry_after_secs, 30);
+        }
+        other => panic!("expected RateLimited, got {other:?}"),
+    }
+    // Rejected calls are not recorded: the oldest call ages out on schedule.
+    assert!(w.check(SensitiveOp::Decrypt, limit, 1_060).is_ok());
+    assert!(w.check(SensitiveOp::Decrypt, limit, 1_061).is_err());
+}
+
+#[test]
+fn e2e_rate_window_tracks_operations_separately() {
+    let mut w = RateWindow::default();
+    let one = RateLimit {
+        max_calls: 1,
+        window_secs: 60,
+    };
+    assert!(w.check(SensitiveOp::Export, one, 1_000).is_ok());
+    assert!(w.check(SensitiveOp::Export, one, 1_001).is_err());
+    assert!(w.check(SensitiveOp::Delete, one, 1_001).is_ok());
+}
+
+#[test]
+fn e2e_rate_limits_default_for_legacy_vault_json_and_validate() {
+    let v: VaultState =
+        serde_json::from_str(r#"{"initialized":true,"salt_hex":"00","verifier_hash_hex":"00"}"#)
+            .unwrap();
+    assert_eq!(v.rate_limits, RateLimits::default());
+    assert!(v.rate_limits.validate().is_ok());
+
+    let mut bad = RateLimits::default();
+    bad.export.max_calls = 0;
+    assert!(bad.validate().unwrap_err().starts_with("export"));
+}
+
 // ==================== On-Disk File Permissions E2E ====================
 
 #[cfg(unix)]
@@ -1091,6 +1178,45 @@ fn e2e_atomic_write_overwrite_keeps_owner_only() {
     assert_eq!(contents, b"second, longer contents");
 }
 
+#[test]
+fn e2e_secure_remove_wipes_and_unlinks() {
+    use zap_quantum_vault_lib::commands::keys::{atomic_write, secure_remove};
+
+    let path = std::env::temp_dir().join(format!("zqv-wipe-{}.enc", uuid::Uuid::new_v4()));
+    atomic_write(&path, &[0xA5u8; 10_000]).unwrap();
+    secure_remove(&path).unwrap();
+    assert!(!path.exists());
+    assert!(secure_remove(&path).is_err());
+}
+
+#[test]
+fn e2e_retired_store_files_exclude_the_live_generation() {
+    let state = VaultState {
+        keys_file: "keys-new.enc".to_string(),
+        items_file: "items-new.enc".to_string(),
+        ..Default::default()
+    };
+    for retired in [
+        "keys.enc",
+        "keys-old.enc",
+        "items-old.enc",
+        "keys-new.tmp-1234",
+        "vault.tmp-1234",
+    ] {
+        assert!(state.is_retired_store_file(retired), "{retired}");
+    }
+    for kept in [
+        "keys-new.enc",
+        "items-new.enc",
+        "vault.json",
+        "keysets.enc",
+        "remote_targets.tmp-1234",
+        "sync_state.json",
+    ] {
+        assert!(!state.is_retired_store_file(kept), "{kept}");
+    }
+}
+
 // ==================== KDF Params Persistence E2E ====================
 
 #[test]
@@ -1121,6 +1247,27 @@ fn e2e_vault_state_serde_defaults_are_legacy_profile() {
     assert_eq!(state.kdf_params(), kdf::KdfParams::legacy());
 }
 
+#[test]
+fn e2e_only_legacy_kdf_vaults_need_encryption_migration() {
+    // Legacy vaults (serde defaults) are migrated; a vault already on the profile
+    // `create_vault` uses is left alone.
+    let legacy = VaultState::default();
+    assert!(legacy.needs_kdf_upgrade());
+    assert_eq!(legacy.kdf_profile().memory_kib, kdf::ARGON2_MEMORY_KIB);
+
+    let current = KdfProfile::current();
+    let high = kdf::KdfParams::high();
+    let state = VaultState {
+        kdf_version: current.version,
+        argon2_memory_kib: high.memory_kib,
+        argon2_iterations: high.iterations,
+        argon2_parallelism: high.parallelism,
+        ..Default::default()
+    };
+    assert!(!state.needs_kdf_upgrade());
+    assert_eq!(state.kdf_profile(), current);
+}
+
 // ==================== Full Mnemonic Recovery E2E ====================
 
 #[test]
@@ -1194,3 +1341,653 @@ fn e2e_invalid_recovery_phrase_rejected() {
 zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo";
     assert!(mnemonic::validate_mnemonic(bad).is_err());
 }
+
+// ==================== SSH Key Item Store E2E ====================
+
+fn sample_ssh_item() -> VaultItem {
+    let kp = ssh::generate(SshKeyAlgorithm::Ed25519, "ops@zap", None).unwrap();
+    VaultItem::new(
+        Some("deploy".to_string()),
+        ItemPayload::SshKey(SshKeyItem {
+            algorithm: SshKeyAlgorithm::Ed25519,
+            comment: "ops@zap".to_string(),
+            public_key_openssh: kp.public_key_openssh,
+            fingerprint: kp.fingerprint,
+            private_key_openssh: kp.private_key_openssh.to_string(),
+        }),
+    )
+}
+
+#[test]
+fn e2e_item_store_ssh_roundtrip_and_sign() {
+    let key = [11u8; 32];
+    let item = sample_ssh_item();
+    let blob = encrypt_items(&key, std::slice::from_ref(&item)).unwrap();
+    let loaded = decrypt_items(&key, &blob).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].id, item.id);
+
+    // The reloaded key still signs agent-style challenges for its public key.
+    let ItemPayload::SshKey(k) = &loaded[0].payload else {
+        panic!("expected an SSH key item");
+    };
+    let sig = ssh::sign_challenge(&k.private_key_openssh, b"challenge").unwrap();
+    assert!(ssh::verify_challenge(&k.public_key_openssh, b"challenge", &sig).unwrap());
+}
+
+#[test]
+fn e2e_item_store_blob_is_not_plaintext_and_needs_key() {
+    let key = [12u8; 32];
+    let item = sample_ssh_item();
+    let blob = encrypt_items(&key, std::slice::from_ref(&item)).unwrap();
+    assert!(!String::from_utf8_lossy(&blob).contains("OPENSSH PRIVATE KEY"));
+    assert!(decrypt_items(&[13u8; 32], &blob).is_err());
+}
+
+#[test]
+fn e2e_vault_state_items_file_and_gap_limit_default_for_legacy_json() {
+    let legacy = r#"{"initialized":true,"salt_hex":"00","verifier_hash_hex":"aa:bb"}"#;
+    let state: VaultState = serde_json::from_str(legacy).unwrap();
+    assert_eq!(state.items_file, "items.enc");
+    assert_eq!(state.address_gap_limit, 20);
+}
+
+// ==================== WireGuard Item E2E ====================
+
+#[test]
+fn e2e_wireguard_item_store_roundtrip_and_provisioning_config() {
+    let key = [14u8; 32];
+    let kp = wireguard::generate_keypair();
+    let server = wireguard::generate_keypair();
+    let psk = wireguard::generate_preshared_key();
+    let item = VaultItem::new(
+        Some("home vpn".to_string()),
+        ItemPayload::WireGuard(WireGuardItem {
+            private_key: kp.private_key.to_string(),
+            public_key: kp.public_key.clone(),
+            addresses: vec!["10.8.0.3/32".to_string()],
+            dns: vec!["10.8.0.1".to_string()],
+            listen_port: None,
+            mtu: None,
+            peers: vec![WireGuardPeer {
+                public_key: server.public_key.clone(),
+                preshared_key: Some(psk.to_string()),
+                endpoint: Some("vpn.example.com:51820".to_string()),
+                allowed_ips: vec!["0.0.0.0/0".to_string()],
+                persistent_keepalive: Some(25),
+            }],
+        }),
+    );
+
+    // Neither the interface key nor the PSK appears in the encrypted blob.
+    let blob = encrypt_items(&key, std::slice::from_ref(&item)).unwrap();
+    let text = String::from_utf8_lossy(&blob);
+    assert!(!text.contains(kp.private_key.as_str()));
+    assert!(!text.contains(psk.as_str()));
+
+    let loaded = decrypt_items(&key, &blob).unwrap();
+    let ItemPayload::WireGuard(wg) = &loaded[0].payload else {
+        panic!("expected a WireGuard item");
+    };
+
+    // The provisioning config re-parses to the same tunnel.
+    let config = wireguard::render_config(wg);
+    let reparsed = wireguard::parse_config(&config).unwrap();
+    assert_eq!(reparsed.public_key, kp.public_key);
+    assert_eq!(reparsed.peers[0].public_key, server.public_key);
+    assert_eq!(
+        reparsed.peers[0].preshared_key.as_deref(),
+        Some(psk.as_str())
+    );
+}
+
+// ==================== Emergency Access E2E ====================
+
+#[test]
+fn e2e_emergency_escrow_request_wait_release_open() {
+    let t0 = 1_800_000_000u64;
+    let day = 24 * 60 * 60;
+    let master_seed = [21u8; 64];
+    let contact = emergency::generate_contact_identity("executor");
+
+    // Owner seals the master seed and keystore to the contact, keeps that
+    // package under the data key and signs the grant.
+    let data_key = [22u8; 32];
+    let contents = EscrowContents {
+        master_seed_hex: hex::encode(master_seed),
+        keys: sample_key_entries(1),
+        items: vec![sample_ssh_item()],
+    };
+    let package =
+        emergency::seal_escrow(&contact.contact, &serde_json::to_vec(&contents).unwrap()).unwrap();
+    let (owner_pk, owner_sk) = emergency::owner_keypair(&master_seed);
+    let mut grant = EmergencyGrant {
+        id: "g1".to_string(),
+        contact: contact.contact.clone(),
+        waiting_period_secs: 3 * day,
+        created_at: t0,
+        owner_public_hex: owner_pk.to_hex(),
+        grant_signature_hex: String::new(),
+        sealed_package: Some(emergency::seal_package(&data_key, &package).unwrap()),
+        package: None,
+        request: None,
+        vetoes: Vec::new(),
+    };
+    grant.grant_signature_hex = mldsa87::sign(&owner_sk, &emergency::grant_message(&grant))
+        .unwrap()
+        .to_hex();
+
+    // Persisted as plaintext JSON; neither the seed nor the contact's KEM
+    // ciphertext may be visible in it.
+    let stored = serde_json::to_string(&grant).unwrap();
+    assert!(!stored.contains(&hex::encode(master_seed)));
+    assert!(!stored.contains(&package.kem_ciphertext_hex));
+    let mut grant: EmergencyGrant = serde_json::from_str(&stored).unwrap();
+
+    let contact_sk = mldsa87::SecretKey::from_hex(&contact.sign_secret_hex).unwrap();
+    let req = emergency::sign_request(&contact_sk, &grant.id, t0 + day).unwrap();
+    emergency::record_request(&mut grant, req, t0 + day).unwrap();
+    assert!(emergency::check_releasable(&grant, t0 + 2 * day).is_err());
+    assert_eq!(
+        emergency::status(&grant, t0 + 4 * day),
+        EmergencyStatus::Releasable {
+            requested_at: t0 + day
+        }
+    );
+    emergency::check_releasable(&grant, t0 + 4 * day).unwrap();
+
+    let kem_seed = hex::decode(&contact.kem_seed_hex).unwrap();
+    let released = emergency::unseal_package(&data_key, &grant).unwrap();
+    assert!(emergency::unseal_package(&[0u8; 32], &grant).is_err());
+    let opened = emergency::open_escrow(&kem_seed, &released).unwrap();
+    let recovered: EscrowContents = serde_json::from_slice(&opened).unwrap();
+    assert_eq!(recovered.master_seed_hex, hex::encode(master_seed));
+    assert_eq!(recovered.keys.len(), 1);
+    assert_eq!(recovered.items.len(), 1);
+}
+
+// ==================== Item Expiry E2E ====================
+
+#[test]
+fn e2e_expired_items_are_listed_marked_once_and_survive_encryption() {
+    let now = chrono::Utc::now();
+    let mut stale = sample_ssh_item();
+    stale.expires_at = Some(now - chrono::Duration::days(3));
+    stale.rotation_hook = Some(RotationHook::Script {
+        path: "/opt/rotate/ssh.sh".to_string(),
+    });
+    let mut recent = sample_ssh_item();
+    recent.expires_at = Some(now - chrono::Duration::hours(1));
+    let mut current = sample_ssh_item();
+    current.expires_at = Some(now + chrono::Duration::days(30));
+    let mut items = vec![recent, current, stale.clone(), sample_ssh_item()];
+
+    let listed = collect_expired(&mut items, now, false);
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0].item.id, stale.id);
+    assert_eq!(listed[0].item.rotation_hook, stale.rotation_hook);
+    assert!(items.iter().all(|i| i.expired_marked_at.is_none()));
+
+    let marked = collect_expired(&mut items, now, true);
+    assert!(marked.iter().all(|e| e.newly_marked));
+    let again = collect_expired(&mut items, now, true);
+    assert!(again.iter().all(|e| !e.newly_marked));
+
+    let key = [9u8; 32];
+    let restored = decrypt_items(&key, &encrypt_items(&key, &items).unwrap()).unwrap();
+    let stale_back = restored.iter().find(|i| i.id == stale.id).unwrap();
+    assert_eq!(stale_back.expires_at, stale.expires_at);
+    assert_eq!(stale_back.expired_marked_at, Some(now));
+}
+
+// ==================== Trash Purge E2E ====================
+
+#[test]
+fn e2e_trash_purge_destroys_only_entries_past_the_cutoff() {
+    let now = chrono::Utc::now();
+    let mut keys = sample_key_entries(3);
+    keys[0].metadata.trashed_at = Some(now - chrono::Duration::days(40));
+    keys[1].metadata.trashed_at = Some(now - chrono::Duration::days(2));
+    let mut old_item = sample_ssh_item();
+    old_item.trashed_at = Some(now - chrono::Duration::days(31));
+    old_item.expires_at = Some(now - chrono::Duration::days(1));
+    assert!(!old_item.is_expired(now), "trashed items are not rotated");
+    let mut items = vec![old_item.clone(), sample_ssh_item()];
+    let (old_key, recent_key, live_key) =
+        (keys[0].id.clone(), keys[1].id.clone(), keys[2].id.clone());
+
+    let trash = collect_trash(&keys, &items, Some(30));
+    let ids: Vec<&str> = trash.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        [old_key.as_str(), old_item.id.as_str(), recent_key.as_str()]
+    );
+    assert_eq!(trash[0].purge_after, Some(now - chrono::Duration::days(10)));
+
+    let purged = take_purgeable(
+        &mut keys,
+        &mut items,
+        now - chrono::Duration::days(30),
+        Some(30),
+    );
+    assert_eq!(
+        purged.iter().map(|e| e.kind).collect::<Vec<_>>(),
+        [TrashKind::Key, TrashKind::Item]
+    );
+    let remaining: Vec<&str> = keys.iter().map(|k| k.id.as_str()).collect();
+    assert_eq!(remaining, [recent_key.as_str(), live_key.as_str()]);
+    assert_eq!(items.len(), 1);
+    assert!(items[0].trashed_at.is_none());
+
+    // Emptying the trash never touches live entries.
+    take_purgeable(&mut keys, &mut items, now, None);
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0].id, live_key);
+    assert_eq!(items.len(), 1);
+}
+
+// ==================== Key Health Check E2E ====================
+
+#[test]
+fn e2e_health_check_flags_tampered_keys_and_items() {
+    let mut keys = sample_key_entries(4);
+    keys[1].public_key_hex = keys[0].public_key_hex.clone();
+    keys[2].metadata.address = "zap1qtampered".to_string();
+    keys[3].encrypted_secret_hex = "not-hex".to_string();
+    let problems: Vec<_> = keys
+        .iter()
+        .map(|k| check_key(k, None).map(|i| i.problem))
+        .collect();
+    assert_eq!(
+        problems,
+        vec![
+            None,
+            Some(HealthProblem::PublicKeyMismatch),
+            Some(HealthProblem::AddressMismatch),
+            Some(HealthProblem::UndecodableSecret),
+        ]
+    );
+
+    let good = sample_ssh_item();
+    assert!(check_item(&good).is_none());
+    let mut swapped = sample_ssh_item();
+    if let (ItemPayload::SshKey(s), ItemPayload::SshKey(g)) = (&mut swapped.payload, &good.payload)
+    {
+        s.fingerprint = g.fingerprint.clone();
+    }
+    let issue = check_item(&swapped).unwrap();
+    assert_eq!(issue.problem, HealthProblem::PublicKeyMismatch);
+    assert!(!issue.detail.contains("PRIVATE KEY"));
+}
+
+#[test]
+fn e2e_health_check_rederives_hd_keys_from_master_seed() {
+    let seed = mnemonic::mnemonic_to_seed(&mnemonic::generate_mnemonic()).unwrap();
+    let path = hd_derivation::zap_path(44, 0, 3);
+    let (pk, sk) = mldsa87::from_seed(&hd_derivation::derive_seed_from_master(&seed, &path));
+    let entry = KeyEntry::new(
+        KeyType::User,
+        44,
+        0,
+        3,
+        &pk.to_hex(),
+        &sk.to_hex(),
+        &address::derive_address(pk.as_bytes()),
+        &path.to_string(),
+    );
+    assert!(check_key(&entry, Some(&seed)).is_none());
+
+    // A self-consistent key recorded under the wrong path would not come back
+    // from a mnemonic restore.
+    let mut moved = entry.clone();
+    moved.metadata.derivation_path = hd_derivation::zap_path(44, 0, 4).to_string();
+    assert!(check_key(&moved, None).is_none());
+    assert_eq!(
+        check_key(&moved, Some(&seed)).unwrap().problem,
+        HealthProblem::DerivationMismatch
+    );
+}
+
+#[test]
+fn e2e_health_check_skips_rederiving_ceremony_keys() {
+    let vault_seed = mnemonic::mnemonic_to_seed(&mnemonic::generate_mnemonic()).unwrap();
+    let ceremony_seed = mnemonic::mnemonic_to_seed(&mnemonic::generate_mnemonic()).unwrap();
+    let path = hd_derivation::zap_path(44, 0, 0);
+    let (pk, sk) = mldsa87::from_seed(&hd_derivation::derive_seed_from_master(
+        &ceremony_seed,
+        &path,
+    ));
+    let mut entry = KeyEntry::new(
+        KeyType::User,
+        44,
+        0,
+        0,
+        &pk.to_hex(),
+        &sk.to_hex(),
+        &address::derive_address(pk.as_bytes()),
+        &path.to_string(),
+    );
+    assert_eq!(
+        check_key(&entry, Some(&vault_seed)).unwrap().problem,
+        HealthProblem::DerivationMismatch
+    );
+
+    // Its path is under the ceremony's seed, not the vault's.
+    entry.metadata.ceremony_id = Some("ceremony-1".to_string());
+    assert!(check_key(&entry, Some(&vault_seed)).is_none());
+
+    // The secret is still checked against the public key.
+    entry.public_key_hex = mldsa87::from_seed(&[7u8; mldsa87::SEED_SIZE]).0.to_hex();
+    assert_eq!(
+        check_key(&entry, Some(&vault_seed)).unwrap().problem,
+        HealthProblem::PublicKeyMismatch
+    );
+}
+
+// ==================== Favorites & Quick Access E2E ====================
+
+#[test]
+fn e2e_quick_access_ranks_favorites_then_usage_across_keys_and_items() {
+    let now = chrono::Utc::now();
+    let mut keys = sample_key_entries(3);
+    let mut items = vec![sample_ssh_item(), sample_ssh_item()];
+
+    // keys[0]: never used -> excluded. keys[1]: used 3x. keys[2]: favorite.
+    for _ in 0..3 {
+        keys[1].metadata.usage.touch(now);
+    }
+    keys[2].metadata.usage.favorite = true;
+    // items[0]: used 5x today. items[1]: used 50x two months ago.
+    for _ in 0..5 {
+        items[0].usage.touch(now);
+    }
+    for _ in 0..50 {
+        items[1].usage.touch(now - chrono::Duration::days(60));
+    }
+
+    // Usage survives the encrypted keystore round-trip.
+    let blob = encrypt_keys(&[31u8; 32], &keys).unwrap();
+    let keys = decrypt_keys(&[31u8; 32], &blob).unwrap();
+    assert_eq!(keys[1].metadata.usage.use_count, 3);
+
+    let ranked = rank_quick_access(&keys, &items, 10, now);
+    let ids: Vec<&str> = ranked
+        .iter()
+        .map(|e| match e {
+            QuickAccessItem::Key(k) => k.id.as_str(),
+            QuickAccessItem::Item(i) => i.id.as_str(),
+        })
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            keys[2].id.as_str(),
+            items[0].id.as_str(),
+            keys[1].id.as_str(),
+            items[1].id.as_str()
+        ]
+    );
+    assert_eq!(rank_quick_access(&keys, &items, 2, now).len(), 2);
+}
+
+#[test]
+fn e2e_legacy_key_entry_without_usage_or_tags_deserializes() {
+    let mut json = serde_json::to_value(&sample_key_entries(1)[0]).unwrap();
+    let metadata = json["metadata"].as_object_mut().unwrap();
+    for field in ["usage", "description", "tags"] {
+        metadata.remove(field);
+    }
+    let entry: KeyEntry = serde_json::from_value(json).unwrap();
+    assert!(entry.metadata.description.is_none());
+    assert!(entry.metadata.tags.is_empty());
+    assert!(!entry.metadata.usage.favorite);
+    assert_eq!(entry.metadata.usage.use_count, 0);
+}
+
+// ==================== Security Self-Test E2E ====================
+
+#[test]
+fn e2e_selftest_flags_plaintext_store_and_retired_files() {
+    let dir = std::env::temp_dir().join(format!("zqv-selftest-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut vault = VaultState {
+        initialized: true,
+        verifier_hash_hex: "00".to_string(),
+        master_seed_enc_hex: "00:00".to_string(),
+        data_key: Some(data_key::wrap(&[1u8; 32], &[5u8; 32], chrono::Utc::now()).unwrap()),
+        ..VaultState::default()
+    };
+    let status = |report: &zap_quantum_vault_lib::models::selftest::SelfTestReport,
+                  id: SelfTestCheckId| {
+        report.checks.iter().find(|c| c.id == id).unwrap().status
+    };
+
+    let keys = sample_key_entries(1);
+    std::fs::write(
+        dir.join(&vault.keys_file),
+        encrypt_keys(&[5u8; 32], &keys).unwrap(),
+    )
+    .unwrap();
+    let report = run_selftest(&dir, &vault);
+    assert_eq!(
+        status(&report, SelfTestCheckId::StoreEncryption),
+        CheckStatus::Pass
+    );
+    assert_eq!(
+        status(&report, SelfTestCheckId::OutdatedFormats),
+        CheckStatus::Pass
+    );
+
+    std::fs::write(
+        dir.join(&vault.keys_file),
+        serde_json::to_vec(&keys).unwrap(),
+    )
+    .unwrap();
+    std::fs::write(dir.join("keys-old.enc"), b"stale").unwrap();
+    let report = run_selftest(&dir, &vault);
+    assert_eq!(
+        status(&report, SelfTestCheckId::StoreEncryption),
+        CheckStatus::Fail
+    );
+    let formats = report
+        .checks
+        .iter()
+        .find(|c| c.id == SelfTestCheckId::OutdatedFormats)
+        .unwrap();
+    assert_eq!(formats.status, CheckStatus::Warn);
+    assert!(formats.remediation.is_some());
+    assert!(report.score < 100);
+
+    vault.initialized = false;
+    let report = run_selftest(&dir, &vault);
+    assert_eq!(
+        status(&report, SelfTestCheckId::KdfParameters),
+        CheckStatus::Skipped
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+// ==================== Drive Notes E2E ====================
+
+#[test]
+fn e2e_mirrored_note_round_trips_and_rejects_retargeting() {
+    use zap_quantum_vault_lib::commands::notes::{
+        mirror_path, open_note, read_mirrored_notes, seal_note,
+    };
+    use zap_quantum_vault_lib::drive::mock::MockBackend;
+    use zap_quantum_vault_lib::drive::DriveBackend;
+    use zap_quantum_vault_lib::models::note::NoteTarget;
+
+    let key = [9u8; 32];
+    let target = NoteTarget::Backup {
+        drive_id: "usb-a".into(),
+        backup_id: "b1".into(),
+    };
+    let mut note = seal_note(
+        &key,
+        target,
+        "in safe #2, combination with lawyer",
+        chrono::Utc::now(),
+    )
+    .unwrap();
+    note.mirrored = true;
+    assert_eq!(
+        open_note(&key, &note).unwrap(),
+        "in safe #2, combination with lawyer"
+    );
+    assert!(open_note(&[1u8; 32], &note).is_err());
+
+    let backend = MockBackend::new();
+    backend.add_drive(MockBackend::ready_drive("usb-a", 1 << 20));
+    backend
+        .write_file(
+            "usb-a",
+            &mirror_path(&note.id),
+            &serde_json::to_vec(&note).unwrap(),
+        )
+        .unwrap();
+    let mirrored = read_mirrored_notes(&backend, "usb-a").unwrap();
+    assert_eq!(mirrored.len(), 1);
+    assert_eq!(
+        open_note(&key, &mirrored[0]).unwrap(),
+        "in safe #2, combination with lawyer"
+    );
+
+    // Filing the ciphertext under another backup is caught on decryption.
+    let mut moved = note.clone();
+    moved.target = NoteTarget::Backup {
+        drive_id: "usb-a".into(),
+        backup_id: "b2".into(),
+    };
+    assert!(open_note(&key, &moved).is_err());
+}
+
+// ==================== Mobile Bundle E2E ====================
+
+#[test]
+fn e2e_mobile_bundle_round_trips_and_detects_tampering() {
+    use zap_quantum_vault_lib::crypto::mobile::{self, MobileBundleError};
+    use zap_quantum_vault_lib::models::mobile::{MobileBundleContents, MobileKey};
+
+    let seed = [3u8; 64];
+    let contents = MobileBundleContents {
+        keys: vec![MobileKey {
+            id: "k1".into(),
+            label: Some("Savings".into()),
+            key_type: KeyType::Treasury,
+            address: "zap1abc".into(),
+            derivation_path: "m/44'/9999'/0'/0'/0'".into(),
+            tags: vec!["cold".into()],
+        }],
+        items: Vec::new(),
+    };
+    assert!(matches!(
+        mobile::seal_bundle(&seed, "short", &contents, chrono::Utc::now()),
+        Err(MobileBundleError::WeakPassword)
+    ));
+    let password = "correct horse battery";
+    let bundle = mobile::seal_bundle(&seed, password, &contents, chrono::Utc::now()).unwrap();
+    assert_eq!(
+        bundle.public_key_hex,
+        mobile::signing_keypair(&seed).0.to_hex()
+    );
+
+    let opened = mobile::open_bundle(password, &bundle).unwrap();
+    assert_eq!(opened.keys, contents.keys);
+    assert!(matches!(
+        mobile::open_bundle("wrong password!!", &bundle),
+        Err(MobileBundleError::WrongPassword)
+    ));
+
+    let mut tampered = bundle.clone();
+    tampered.kdf.iterations = 1;
+    assert!(matches!(
+        mobile::open_bundle(password, &tampered),
+        Err(MobileBundleError::BadSignature)
+    ));
+}
+
+// ==================== Contact Cards E2E ====================
+
+#[test]
+fn e2e_contact_card_exchange_and_fingerprint_check() {
+    use zap_quantum_vault_lib::crypto::contact;
+    use zap_quantum_vault_lib::models::contact::ContactCard;
+
+    // Alice issues her card; Bob receives it as JSON over any channel.
+    let alice_seed = [11u8; 64];
+    let card = contact::issue_card(&alice_seed, "Alice", 1_700_000_000).unwrap();
+    let wire = serde_json::to_string(&card).unwrap();
+    let received: ContactCard = serde_json::from_str(&wire).unwrap();
+    contact::verify_card(&received).unwrap();
+
+    // Alice reads her fingerprint out; Bob compares it in either form.
+    let spoken = contact::fingerprint(&card).unwrap();
+    let stored = contact::fingerprint(&received).unwrap();
+    assert!(stored.matches(&spoken.hex.to_uppercase()));
+    assert!(stored.matches(&spoken.words.replace('-', " ")));
+
+    // A card re-issued with Mallory's keys under Alice's name fails the
+    // comparison even though its own signature is valid.
+    let forged = contact::issue_card(&[12u8; 64], "Alice", 1_700_000_000).unwrap();
+    contact::verify_card(&forged).unwrap();
+    assert!(!contact::fingerprint(&forged).unwrap().matches(&spoken.hex));
+}
+
+// ==================== Passkeys E2E ====================
+
+#[test]
+fn e2e_passkey_survives_store_round_trip_and_keeps_counting() {
+    use zap_quantum_vault_lib::crypto::passkey;
+    use zap_quantum_vault_lib::models::item::PasskeyAlgorithm;
+
+    let pk = passkey::generate(
+        PasskeyAlgorithm::Es256,
+        "example.com",
+        None,
+        "dXNlci00Mg",
+        "alice",
+        None,
+    )
+    .unwrap();
+    let registered = pk.public_key_cose.clone();
+    let key = [3u8; 32];
+    let mut item = VaultItem::new(Some("example.com".into()), ItemPayload::Passkey(pk));
+    let ItemPayload::Passkey(pk) = &mut item.payload else {
+        unreachable!()
+    };
+    assert_eq!(
+        passkey::sign_assertion(pk, &[0u8; 32]).unwrap().sign_count,
+        1
+    );
+
+    // The bumped counter is what gets persisted and reloaded.
+    let data = encrypt_items(&key, std::slice::from_ref(&item)).unwrap();
+    let mut loaded = decrypt_items(&key, &data).unwrap();
+    assert!(check_item(&loaded[0]).is_none());
+    let ItemPayload::Passkey(pk) = &mut loaded[0].payload else {
+        panic!("expected a passkey");
+    };
+    assert_eq!(pk.public_key_cose, registered);
+    assert_eq!(
+        passkey::sign_assertion(pk, &[0u8; 32]).unwrap().sign_count,
+        2
+    );
+
+    // A private key swapped under the registered public key is caught.
+    pk.private_key = passkey::to_export(
+        &passkey::generate(
+            PasskeyAlgorithm::Es256,
+            "example.com",
+            None,
+            "dQ",
+            "a",
+            None,
+        )
+        .unwrap(),
+    )
+    .private_key
+    .clone();
+    let issue = check_item(&loaded[0]).unwrap();
+    assert_eq!(issue.problem, HealthProblem::PublicKeyMismatch);
+}
@@ -0,0 +1,451 @@
+mod common;
+
+use common::{
+    random_text, rng, sample_wireguard_item, VaultFixture, LEGACY_KEY_JSON, LEGACY_VAULT_JSON,
+};
+use rand::Rng;
+use std::collections::HashSet;
+use zap_quantum_vault_lib::commands::backup::{
+    inspect_backup_files, select_full_restore, select_partial_restore, METADATA_FILES,
+};
+use zap_quantum_vault_lib::commands::items::decrypt_items;
+use zap_quantum_vault_lib::commands::keys::decrypt_keys;
+use zap_quantum_vault_lib::crypto::{emergency, encryption, mldsa87};
+use zap_quantum_vault_lib::drive::backup::{self, VaultFile};
+use zap_quantum_vault_lib::drive::mock::MockBackend;
+use zap_quantum_vault_lib::models::address::DEFAULT_ADDRESS_GAP_LIMIT;
+use zap_quantum_vault_lib::models::backup::FileCheckStatus;
+use zap_quantum_vault_lib::models::emergency::EmergencyGrant;
+use zap_quantum_vault_lib::models::item::ItemPayload;
+use zap_quantum_vault_lib::models::key::{KeyEntry, KeyType};
+use zap_quantum_vault_lib::models::keyset::{plan_keyset, KeysetMemberSpec, KeysetRequest};
+use zap_quantum_vault_lib::models::metadata::MetadataUpdate;
+use zap_quantum_vault_lib::models::vault::VaultState;
+
+const CASES: u64 = 24;
+
+// ==================== Persist / Reopen Round Trips ====================
+
+#[test]
+fn harness_persist_reopen_preserves_everything() {
+    let mut fx = VaultFixture::new(1)
+        .with_hd_keys(3)
+        .with_item(sample_wireguard_item("office"));
+    fx.keys[1].metadata.used = true;
+    fx.keys[2].metadata.usage.favorite = true;
+    fx.vault.address_gap_limit = 50;
+
+    let persisted = fx.persist();
+    let back = VaultFixture::reopen(&persisted, fx.session_key, fx.master_seed).unwrap();
+    assert_eq!(back.vault.address_gap_limit, 50);
+    assert_eq!(back.keys.len(), 3);
+    for (a, b) in fx.keys.iter().zip(&back.keys) {
+        assert_eq!(a.id, b.id);
+        assert_eq!(a.public_key_hex, b.public_key_hex);
+        assert_eq!(a.encrypted_secret_hex, b.encrypted_secret_hex);
+        assert_eq!(a.metadata.used, b.metadata.used);
+        assert_eq!(a.metadata.usage, b.metadata.usage);
+    }
+    let ItemPayload::WireGuard(wg) = &back.items[0].payload else {
+        panic!("expected a WireGuard item");
+    };
+    assert_eq!(wg.private_key, "PRIVATE-office");
+}
+
+#[test]
+fn harness_blobs_reject_wrong_key_and_tampering() {
+    let fx = VaultFixture::new(2)
+        .with_hd_keys(1)
+        .with_item(sample_wireguard_item("home"));
+    let persisted = fx.persist();
+    assert!(VaultFixture::reopen(&persisted, [9u8; 32], fx.master_seed).is_err());
+
+    let tamper = |blob: &[u8]| {
+        let mut ct: encryption::Ciphertext = serde_json::from_slice(blob).unwrap();
+        let last = ct.ciphertext.len() - 1;
+        ct.ciphertext[last] ^= 1;
+        serde_json::to_vec(&ct).unwrap()
+    };
+    assert!(decrypt_keys(&fx.session_key, &persisted.keys_blob).is_ok());
+    assert!(decrypt_keys(&fx.session_key, &tamper(&persisted.keys_blob)).is_err());
+    assert!(decrypt_items(&fx.session_key, &persisted.items_blob).is_ok());
+    assert!(decrypt_items(&fx.session_key, &tamper(&persisted.items_blob)).is_err());
+}
+
+#[test]
+fn property_metadata_edits_survive_reopen() {
+    for case in 0..CASES {
+        let mut r = rng(case);
+        let mut fx = VaultFixture::new(3).with_hd_keys(2);
+        let update = MetadataUpdate {
+            label: Some(random_text(&mut r, 64)),
+            description: Some(random_text(&mut r, 200)),
+            add_tags: (0..r.gen_range(0..5))
+                .map(|_| format!("t{}", random_text(&mut r, 20)))
+                .collect(),
+            ..Default::default()
+        };
+        let m = &mut fx.keys[0].metadata;
+        update
+            .apply(&mut m.label, &mut m.description, &mut m.tags)
+            .unwrap_or_else(|e| panic!("case {case}: {e}"));
+        let expected = fx.keys[0].metadata.clone();
+
+        let back = VaultFixture::reopen(&fx.persist(), fx.session_key, fx.master_seed).unwrap();
+        let got = &back.keys[0].metadata;
+        assert_eq!(got.label, expected.label, "case {case}");
+        assert_eq!(got.description, expected.description, "case {case}");
+        assert_eq!(got.tags, expected.tags, "case {case}");
+    }
+}
+
+#[test]
+fn property_hd_keys_rederive_identically_after_reopen() {
+    for case in 0..CASES {
+        let mut r = rng(1000 + case);
+        let seed: u8 = r.gen();
+        let (purpose, account, index) = (r.gen_range(0..8), r.gen_range(0..4), r.gen_range(0..64));
+        let mut fx = VaultFixture::new(seed);
+        let original = fx
+            .derive_key(KeyType::User, purpose, account, index)
+            .clone();
+
+        // A restore derives from the same master seed into an empty vault.
+        let mut restored = VaultFixture::new(seed);
+        let again = restored.derive_key(KeyType::User, purpose, account, index);
+        assert_eq!(original.public_key_hex, again.public_key_hex, "case {case}");
+        assert_eq!(
+            original.metadata.address, again.metadata.address,
+            "case {case}"
+        );
+    }
+}
+
+// ==================== Keyset Planning Properties ====================
+
+#[test]
+fn property_planned_keysets_never_collide() {
+    for case in 0..CASES {
+        let mut r = rng(2000 + case);
+        let mut fx = VaultFixture::new(4);
+        for i in 0..r.gen_range(0..6) {
+            fx.derive_key(KeyType::User, r.gen_range(0..3), 0, i);
+        }
+        let members: Vec<KeysetMemberSpec> = (0..r.gen_range(1..4))
+            .map(|_| KeysetMemberSpec {
+                key_type: ["validator", "treasury", "governance"][r.gen_range(0..3)].to_string(),
+                purpose: r.gen_range(0..3),
+                count: r.gen_range(1..5),
+            })
+            .collect();
+        let request = KeysetRequest {
+            idempotency_key: format!("case-{case}"),
+            name: "set".to_string(),
+            account: 0,
+            members,
+        };
+        let keyset = plan_keyset(
+            &fx.keys,
+            &request,
+            DEFAULT_ADDRESS_GAP_LIMIT,
+            chrono::Utc::now(),
+        )
+        .unwrap_or_else(|e| panic!("case {case}: {e}"));
+
+        let existing: HashSet<&str> = fx
+            .keys
+            .iter()
+            .map(|k| k.metadata.derivation_path.as_str())
+            .collect();
+        let mut planned = HashSet::new();
+        for p in &keyset.planned {
+            assert!(
+                !existing.contains(p.derivation_path.as_str()),
+                "case {case}"
+            );
+            assert!(planned.insert(p.derivation_path.clone()), "case {case}");
+        }
+        let total: u32 = request.members.iter().map(|m| m.count).sum();
+        assert_eq!(keyset.planned.len() as u32, total, "case {case}");
+    }
+}
+
+// ==================== Drive Backups ====================
+
+#[test]
+fn harness_backup_to_mock_drive_and_reopen() {
+    let fx = VaultFixture::new(6)
+        .with_hd_keys(2)
+        .with_item(sample_wireguard_item("lab"));
+    let persisted = fx.persist();
+    let files = vec![
+        VaultFile {
+            name: "vault.json".to_string(),
+            data: persisted.vault_json.clone().into_bytes(),
+        },
+        VaultFile {
+            name: fx.vault.keys_file.clone(),
+            data: persisted.keys_blob.clone(),
+        },
+        VaultFile {
+            name: fx.vault.items_file.clone(),
+            data: persisted.items_blob.clone(),
+        },
+    ];
+
+    let drives = MockBackend::new();
+    drives.add_drive(MockBackend::ready_drive("usb", 1 << 20));
+    let manifest = backup::create_backup(&drives, "usb", &files, chrono::Utc::now(), None).unwrap();
+    assert!(
+        backup::verify_backup(&drives, "usb", &manifest.id)
+            .unwrap()
+            .ok
+    );
+
+    let read = backup::read_backup(&drives, "usb", &manifest.id, None).unwrap();
+    let blob = |name: &str| read.iter().find(|f| f.name == name).unwrap().data.clone();
+    let restored = common::PersistedVault {
+        vault_json: String::from_utf8(blob("vault.json")).unwrap(),
+        keys_blob: blob(&fx.vault.keys_file),
+        items_blob: blob(&fx.vault.items_file),
+    };
+    let back = VaultFixture::reopen(&restored, fx.session_key, fx.master_seed).unwrap();
+    assert_eq!(back.keys.len(), 2);
+    assert_eq!(back.keys[1].public_key_hex, fx.keys[1].public_key_hex);
+    assert_eq!(back.items.len(), 1);
+}
+
+#[test]
+fn harness_inspect_foreign_backup_without_importing() {
+    // A backup from another instance: inspected with no session key at all.
+    let fx = VaultFixture::new(8)
+        .with_hd_keys(1)
+        .with_item(sample_wireguard_item("lab"));
+    let persisted = fx.persist();
+
+    let contact = emergency::generate_contact_identity("executor");
+    let (owner_pk, owner_sk) = emergency::owner_keypair(&fx.master_seed);
+    let mut grant = EmergencyGrant {
+        id: "g1".to_string(),
+        contact: contact.contact.clone(),
+        waiting_period_secs: 86_400,
+        created_at: 1_800_000_000,
+        owner_public_hex: owner_pk.to_hex(),
+        grant_signature_hex: String::new(),
+        sealed_package: Some(
+            emergency::seal_package(
+                &[3u8; 32],
+                &emergency::seal_escrow(&contact.contact, b"escrow").unwrap(),
+            )
+            .unwrap(),
+        ),
+        package: None,
+        request: None,
+        vetoes: Vec::new(),
+    };
+    grant.grant_signature_hex = mldsa87::sign(&owner_sk, &emergency::grant_message(&grant))
+        .unwrap()
+        .to_hex();
+    let mut tampered = grant.clone();
+    tampered.id = "g2".to_string();
+    tampered.waiting_period_secs = 60;
+
+    let files = vec![
+        VaultFile {
+            name: "vault.json".to_string(),
+            data: persisted.vault_json.clone().into_bytes(),
+        },
+        VaultFile {
+            name: fx.vault.keys_file.clone(),
+            data: persisted.keys_blob.clone(),
+        },
+        VaultFile {
+            name: fx.vault.items_file.clone(),
+            data: persisted.items_blob.clone(),
+        },
+        VaultFile {
+            name: "emergency.json".to_string(),
+            data: serde_json::to_vec(&[grant, tampered]).unwrap(),
+        },
+        VaultFile {
+            name: "ceremonies.json".to_string(),
+            data: b"{not json".to_vec(),
+        },
+    ];
+    let drives = MockBackend::new();
+    drives.add_drive(MockBackend::ready_drive("usb", 1 << 20));
+    let manifest = backup::create_backup(&drives, "usb", &files, chrono::Utc::now(), None).unwrap();
+    let keys_entry = manifest
+        .files
+        .iter()
+        .find(|f| f.name == fx.vault.keys_file)
+        .unwrap();
+    drives.put_raw(
+        "usb",
+        &backup::object_path(&keys_entry.blake3_hex).unwrap(),
+        b"bit rot".to_vec(),
+    );
+
+    let (manifest, verification, intact) =
+        backup::read_backup_checked(&drives, "usb", &manifest.id).unwrap();
+    let report = inspect_backup_files(manifest, verification, &intact);
+    assert!(!report.verification.ok);
+    assert!(report
+        .verification
+        .files
+        .iter()
+        .any(|f| f.name == fx.vault.keys_file && f.status == FileCheckStatus::Corrupt));
+
+    let vault = report.vault.expect("vault.json is intact");
+    assert!(vault.initialized);
+    assert!(!vault.keystore_intact);
+    assert!(vault.items_intact);
+
+    let validity: Vec<(&str, bool)> = report
+        .signatures
+        .iter()
+        .map(|c| (c.record_id.as_str(), c.valid))
+        .collect();
+    assert_eq!(validity, [("g1", true), ("g2", false)]);
+    assert_eq!(report.problems.len(), 1);
+    assert!(report.problems[0].starts_with("ceremonies.json"));
+
+    // Intact stores restore on their own despite the corrupt keystore.
+    let pick = |names: &[&str]| {
+        let names: Vec<String> = names.iter().map(|n| n.to_string()).collect();
+        select_partial_restore(&fx.vault, &report.verification, &intact, &names)
+    };
+    let restored = pick(&[&fx.vault.items_file, "emergency.json"]).unwrap();
+    assert_eq!(restored.len(), 2);
+    assert_eq!(restored[0].data, persisted.items_blob);
+    let err = pick(&[&fx.vault.keys_file]).unwrap_err().to_string();
+    assert!(err.contains("corrupt"), "{err}");
+    assert!(pick(&["vault.json"]).is_err());
+    assert!(pick(&["keys-other-generation.enc"]).is_err());
+    assert!(pick(&[]).is_err());
+}
+
+#[test]
+fn harness_full_restore_takes_only_vault_files() {
+    let fx = VaultFixture::new(4).with_hd_keys(1);
+    let persisted = fx.persist();
+    let file = |name: &str, data: &[u8]| VaultFile {
+        name: name.to_string(),
+        data: data.to_vec(),
+    };
+    let mut files = vec![
+        file("vault.json", persisted.vault_json.as_bytes()),
+        file(&fx.vault.keys_file, &persisted.keys_blob),
+        file(&fx.vault.items_file, &persisted.items_blob),
+    ];
+    let restored = select_full_restore(&files).unwrap();
+    assert_eq!(restored.keys_file, fx.vault.keys_file);
+
+    // A planted file outside the vault's stores refuses the whole restore.
+    files.push(file("admin_api.json", b"{}"));
+    let err = select_full_restore(&files).unwrap_err().to_string();
+    assert!(err.contains("admin_api.json"), "{err}");
+    files.pop();
+
+    // So does a vault.json naming a store outside the data directory.
+    let mut escaping = fx.vault.clone();
+    escaping.keys_file = "../keys.enc".to_string();
+    files[0] = file("vault.json", &serde_json::to_vec(&escaping).unwrap());
+    files[1] = file("../keys.enc", &persisted.keys_blob);
+    assert!(select_full_restore(&files).is_err());
+}
+
+/// Every `*_FILE` constant in the source tree, as `(name, file name)`.
+fn file_constants(dir: &std::path::Path, found: &mut Vec<(String, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            file_constants(&path, found);
+            continue;
+        }
+        if path.extension().and_then(|e| e.to_str()) != Some("rs") {
+            continue;
+        }
+        for line in std::fs::read_to_string(&path).unwrap().lines() {
+            let Some(rest) = line.trim().strip_prefix("pub const ") else {
+                continue;
+            };
+            let Some((name, value)) = rest.split_once(": &str = \"") else {
+                continue;
+            };
+            if name.ends_with("_FILE") {
+                let value = value.trim_end_matches("\";");
+                found.push((name.to_string(), value.to_string()));
+            }
+        }
+    }
+}
+
+#[test]
+fn harness_every_data_file_is_backed_up_or_excluded() {
+    // Files that are not part of the vault: per-installation state, files
+    // outside the vault's data directory, sockets, and the drive's own files.
+    const EXCLUDED: &[&str] = &[
+        "INSTANCE_FILE",
+        "SYNC_FILE",
+        "WRITE_LEASE_FILE",
+        "ADMIN_API_FILE",
+        "PROFILES_FILE",
+        "PORTABLE_FILE",
+        "ADMIN_SOCKET_FILE",
+        "AGENT_SOCKET_FILE",
+        "BROWSER_SOCKET_FILE",
+        "DRIVE_SEAL_FILE",
+        "MANIFEST_FILE",
+        "INDEX_FILE",
+    ];
+    let mut found = Vec::new();
+    file_constants(
+        &std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+        &mut found,
+    );
+    assert!(found.len() > METADATA_FILES.len());
+    for (name, value) in &found {
+        let listed = name == "VAULT_FILE" || METADATA_FILES.contains(&value.as_str());
+        let excluded = EXCLUDED.contains(&name.as_str());
+        assert!(
+            listed != excluded,
+            "{name} ({value}) must be either backed up or excluded, not {}",
+            if listed { "both" } else { "neither" }
+        );
+    }
+}
+
+// ==================== Format Compatibility ====================
+
+#[test]
+fn compat_legacy_keystore_decrypts_with_defaults() {
+    let key = [5u8; 32];
+    let ct = encryption::encrypt_vault(&key, LEGACY_KEY_JSON.as_bytes()).unwrap();
+    let blob = serde_json::to_vec(&ct).unwrap();
+
+    let keys: Vec<KeyEntry> = decrypt_keys(&key, &blob).unwrap();
+    let m = &keys[0].metadata;
+    assert_eq!(m.key_type, KeyType::Validator);
+    assert_eq!(m.label.as_deref(), Some("old validator"));
+    assert!(m.description.is_none() && m.tags.is_empty());
+    assert!(m.derivation_path.is_empty() && !m.used);
+    assert!(m.keyset_id.is_none() && m.ceremony_id.is_none());
+    assert_eq!(m.usage.use_count, 0);
+}
+
+#[test]
+fn compat_legacy_vault_json_gets_current_defaults() {
+    let state: VaultState = serde_json::from_str(LEGACY_VAULT_JSON).unwrap();
+    assert_eq!(state.keys_file, "keys.enc");
+    assert_eq!(state.items_file, "items.enc");
+    assert!(!state.yubikey_enabled);
+    assert_eq!(state.address_gap_limit, DEFAULT_ADDRESS_GAP_LIMIT);
+    assert!(state.keysets.is_empty());
+
+    // Round-tripping through the current schema keeps the legacy values.
+    let json = serde_json::to_string(&state).unwrap();
+    let again: VaultState = serde_json::from_str(&json).unwrap();
+    assert_eq!(again.salt_hex, "00112233");
+    assert_eq!(again.verifier_hash_hex, "aa:bb");
+}
@@ -1,5 +1,18 @@
 import { invoke } from "@tauri-apps/api/core";
 
+/** Short, human-comparable fingerprint of a public key. */
+export interface KeyFingerprint {
+  /** `a1b2:c3d4:e5f6:0718`. */
+  hex: string;
+  /** Two BIP39 English words, e.g. `orbit-velvet`. */
+  words: string;
+  /** A 5×5 grid mirrored left to right; `cells` is row-major. */
+  identicon: {
+    color: string;
+    cells: boolean[];
+  };
+}
+
 export interface KeyEntry {
   id: string;
   metadata: {
@@ -10,14 +23,110 @@ export interface KeyEntry {
     address: string;
     created_at: string;
     label: string | null;
+    description: string | null;
+    tags: string[];
     derivation_path: string;
+    used: boolean;
+    used_at: string | null;
+    usage: {
+      favorite: boolean;
+      use_count: number;
+      last_used_at: string | null;
+    };
+    keyset_id: string | null;
+    ceremony_id: string | null;
   };
   public_key_hex: string;
+  fingerprint: KeyFingerprint;
+}
+
+export interface AddressStats {
+  purpose: number;
+  account: number;
+  total: number;
+  used: number;
+  unused: number;
+  highest_index: number | null;
+  highest_used_index: number | null;
+  trailing_unused: number;
+  max_derivable_index: number;
+  gap_limit: number;
+}
+
+/** `get_key_detail` result: the key plus account address stats and backup coverage. */
+export interface KeyDetails extends KeyEntry {
+  address_stats: AddressStats;
+  backup: {
+    mnemonic_recoverable: boolean;
+    within_scan_window: boolean;
+    /** The latest backup written since the key was created, if any. */
+    last_backup: {
+      drive_id: string;
+      backup_id: string;
+      created_at: string;
+      verified_at: string | null;
+    } | null;
+  };
 }
 
 export interface CreateVaultResult {
   /** The 24-word BIP39 recovery phrase. Shown once; never retrievable again. */
   mnemonic: string;
+  /** One-time codes that can reset a forgotten password. Shown once. */
+  recovery_codes: string[];
+}
+
+export interface RecoveryCodesStatus {
+  configured: boolean;
+  remaining: number;
+  escrowed: boolean;
+  created_at: string | null;
+}
+
+export interface UnrecoverableData {
+  name: string;
+  file: string | null;
+  note: string | null;
+}
+
+export interface RecoveryReport {
+  recovered: boolean;
+  codes_remaining: number;
+  /** What cannot be opened without the old password, when not recovered. */
+  unrecoverable: UnrecoverableData[];
+}
+
+export interface PasswordPattern {
+  kind:
+    | "dictionary"
+    | "context"
+    | "sequence"
+    | "repeat"
+    | "keyboard"
+    | "year"
+    | "bruteforce";
+  start: number;
+  len: number;
+  guesses_log10: number;
+}
+
+export interface PasswordFeedback {
+  /** 0 (guessed at once) to 4. */
+  score: number;
+  min_score: number;
+  acceptable: boolean;
+  guesses_log10: number;
+  warning: string | null;
+  suggestions: string[];
+  patterns: PasswordPattern[];
+}
+
+export interface MasterPasswordChange {
+  /** Keys and items the new password opened in the verification pass. */
+  keys: number;
+  items: number;
+  master_seed: boolean;
+  key_drive_dropped: boolean;
 }
 
 export interface AirGapEnvelope {
@@ -53,6 +162,17 @@ export interface HybridSignatureHex {
   algorithm: string;
 }
 
+/** Decoded payload shown for review before signing. */
+export interface TxSummary {
+  kind: "psbt" | "ethereum_tx" | "cosmos_sign_doc" | "raw";
+  payload_sha256_hex: string;
+  /** Tagged by `type`; shape depends on `kind`. */
+  detail: { type: string } & Record<string, unknown>;
+  warnings: string[];
+  /** Echo back to the signing command to confirm the review. */
+  summary_hash_hex: string;
+}
+
 export interface QrRequest {
   payload_hex: string;
   transfer_type: string;
@@ -79,6 +199,45 @@ export interface YubiKeyStatus {
   slot: number;
 }
 
+/** One SLIP-39 group: `member_threshold` of its `member_count` shares. */
+export interface Slip39Group {
+  member_threshold: number;
+  member_count: number;
+}
+
+/** What a consent token is issued for, with the arguments it will run with. */
+export type ConsentOperation =
+  | { kind: "purge_trash"; older_than_days: number }
+  | { kind: "ssh_private_key_export"; item_id: string }
+  | { kind: "wireguard_config_export"; item_id: string }
+  | { kind: "passkey_export"; item_ids: string[] }
+  | { kind: "api_token_reveal"; item_id: string }
+  | { kind: "env_file_render"; item_ids: string[]; path: string }
+  | { kind: "plugin_item_reveal"; item_id: string }
+  | { kind: "plugin_export"; plugin_id: string; exporter: string; path: string }
+  | { kind: "slip39_shares"; group_threshold: number; groups: Slip39Group[] }
+  | { kind: "frost_share_export"; group_id: string; path: string }
+  | { kind: "agent_start"; grants: { item_id: string; reveal?: boolean; sign?: boolean }[] }
+  | { kind: "repair_orphans" }
+  | { kind: "rollback_keyset"; keyset_id: string }
+  | { kind: "key_escrow"; key_id: string; recipient_contact_id: string };
+
+/** `request_consent` result. Show `description`, then pass `token` on. */
+export interface ConsentToken {
+  token: string;
+  operation: ConsentOperation;
+  description: string;
+  expires_at: string;
+}
+
+export interface TrashedEntry {
+  kind: "key" | "item";
+  id: string;
+  label: string | null;
+  trashed_at: string;
+  purge_after: string | null;
+}
+
 export const api = {
   vaultStatus: () => invoke<boolean>("vault_status"),
 
@@ -92,12 +251,67 @@ export const api = {
   restoreFromMnemonic: (mnemonicPhrase: string, password: string) =>
     invoke<string>("restore_from_mnemonic", { mnemonicPhrase, password }),
 
+  // Split the master seed into SLIP-39 shares, one list per group. Needs a
+  // consent token for the same groups.
+  generateSlip39Shares: (
+    password: string,
+    groupThreshold: number,
+    groups: Slip39Group[],
+    passphrase: string | null,
+    consentToken: string,
+  ) =>
+    invoke<string[][]>("generate_slip39_shares", {
+      password,
+      groupThreshold,
+      groups,
+      passphrase,
+      consentToken,
+    }),
+
+  // Restore a vault from SLIP-39 shares. Fails if a vault already exists on
+  // disk, like restoreFromMnemonic.
+  restoreFromSlip39: (
+    shares: string[],
+    password: string,
+    passphrase: string | null,
+  ) =>
+    invoke<string>("restore_from_slip39", { shares, password, passphrase }),
+
+  // Score a new password against the vault's strength floor. Works while
+  // locked and before a vault exists.
+  checkPasswordStrength: (password: string) =>
+    invoke<PasswordFeedback>("check_password_strength", { password }),
+
+  // Change the score (0 to 4) new passwords must reach.
+  setMinPasswordScore: (password: string, minScore: number) =>
+    invoke<number>("set_min_password_score", { password, minScore }),
+
   unlockVault: (password: string) =>
     invoke<boolean>("unlock_vault", { password }),
 
   changePassword: (oldPassword: string, newPassword: string) =>
     invoke<string>("change_password", { oldPassword, newPassword }),
 
+  // Re-wrap the data key under a new password as one verified step.
+  // Progress arrives on the "master_password_progress" event.
+  changeMasterPassword: (oldPassword: string, newPassword: string) =>
+    invoke<MasterPasswordChange>("change_master_password", {
+      oldPassword,
+      newPassword,
+    }),
+
+  // Replace the recovery codes. Without escrow, a code cannot reset the
+  // password, only report what a reset would lose.
+  generateRecoveryCodes: (password: string, escrow?: boolean) =>
+    invoke<string[]>("generate_recovery_codes", { password, escrow }),
+
+  recoveryCodesStatus: () =>
+    invoke<RecoveryCodesStatus>("recovery_codes_status"),
+
+  // Reset a forgotten password with a recovery code. Works while locked.
+  recoverAccount: (code: string, newPassword: string) =>
+    invoke<RecoveryReport>("recover_account", { code, newPassword }),
+
   lockVault: () => invoke<void>("lock_vault"),
 
   // Current YubiKey enrollment state for the vault.
@@ -141,19 +355,27 @@ export const api = {
   listKeys: () => invoke<KeyEntry[]>("list_keys"),
 
   getKeyDetail: (keyId: string) =>
-    invoke<KeyEntry>("get_key_detail", { keyId }),
+    invoke<KeyDetails>("get_key_detail", { keyId }),
 
   signMessage: (request: SignRequest) =>
     invoke<string>("sign_message", { request }),
 
+  // Decode a payload for review; pass summary_hash_hex to the signing call.
+  reviewTransaction: (payloadHex: string, kind?: TxSummary["kind"]) =>
+    invoke<TxSummary>("review_transaction", { payloadHex, kind: kind ?? null }),
+
   // Preferred: sign with a stored key; the secret never leaves the backend.
-  signMessageWithKey: (keyId: string, messageHex: string) =>
-    invoke<string>("sign_message_with_key", { keyId, messageHex }),
+  signMessageWithKey: (keyId: string, messageHex: string, summaryHashHex: string) =>
+    invoke<string>("sign_message_with_key", { keyId, messageHex, summaryHashHex }),
 
   // Hybrid sign (post-quantum ML-DSA-87 + classical Ed25519) with a stored key.
   // Both signatures must verify; secret never leaves the backend.
-  signMessageHybridWithKey: (keyId: string, messageHex: string) =>
-    invoke<HybridSignatureHex>("sign_message_hybrid_with_key", { keyId, messageHex }),
+  signMessageHybridWithKey: (keyId: string, messageHex: string, summaryHashHex: string) =>
+    invoke<HybridSignatureHex>("sign_message_hybrid_with_key", {
+      keyId,
+      messageHex,
+      summaryHashHex,
+    }),
 
   verifyMessage: (request: VerifyRequest) =>
     invoke<boolean>("verify_message", { request }),
@@ -177,4 +399,74 @@ export const api = {
   // Throws if the signature, checksum, version, freshness, or replay check fails.
   verifyQr: (qrJson: string) =>
     invoke<AirGapEnvelope>("verify_qr", { qrJson }),
+
+  // First step of a destructive or exposing command: describe it and get a
+  // single-use token, valid for a minute, to pass as `consentToken`.
+  requestConsent: (operation: ConsentOperation) =>
+    invoke<ConsentToken>("request_consent", { operation }),
+
+  purgeTrash: (olderThanDays: number, consentToken: string) =>
+    invoke<TrashedEntry[]>("purge_trash", { olderThanDays, consentToken }),
+
+  // Without a passphrase the key is exported unencrypted.
+  sshExportPrivateKey: (
+    itemId: string,
+    password: string,
+    passphrase: string | null,
+    consentToken: string,
+  ) =>
+    invoke<string>("ssh_export_private_key", {
+      itemId,
+      password,
+      passphrase,
+      consentToken,
+    }),
+
+  wireguardExportConfig: (itemId: string, password: string, consentToken: string) =>
+    invoke<string>("wireguard_export_config", { itemId, password, consentToken }),
+
+  passkeyExport: (itemIds: string[], password: string, consentToken: string) =>
+    invoke<string>("passkey_export", { itemIds, password, consentToken }),
+
+  apiTokenReveal: (itemId: string, password: string, consentToken: string) =>
+    invoke<string>("api_token_reveal", { itemId, password, consentToken }),
+
+  // Returns how many tokens were written. `path` must be absolute.
+  renderEnvFile: (
+    itemIds: string[],
+    path: string,
+    format: "dotenv" | "json",
+    password: string,
+    consentToken: string,
+  ) =>
+    invoke<number>("render_env_file", {
+      itemIds,
+      path,
+      format,
+      password,
+      consentToken,
+    }),
+
+  pluginItemReveal: (itemId: string, password: string, consentToken: string) =>
+    invoke<Record<string, string>>("plugin_item_reveal", {
+      itemId,
+      password,
+      consentToken,
+    }),
+
+  // Returns the number of bytes written. `path` must be absolute.
+  runPluginExporter: (
+    password: string,
+    pluginId: string,
+    exporter: string,
+    path: string,
+    consentToken: string,
+  ) =>
+    invoke<number>("run_plugin_exporter", {
+      password,
+      pluginId,
+      exporter,
+      path,
+      consentToken,
+    }),
 };
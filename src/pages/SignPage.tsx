@@ -46,13 +46,25 @@ export function SignPage() {
     setVerified(null);
     try {
       const messageHex = toHex(message);
+      const review = await api.reviewTransaction(messageHex);
+      if (review.warnings.length > 0 && !window.confirm(review.warnings.join("\n"))) {
+        return;
+      }
       if (hybridMode) {
-        const sig = await api.signMessageHybridWithKey(selectedKey.id, messageHex);
+        const sig = await api.signMessageHybridWithKey(
+          selectedKey.id,
+          messageHex,
+          review.summary_hash_hex,
+        );
         setHybridSig(sig);
         setSignature("");
         toast.success("Message signed with ML-DSA-87 + Ed25519");
       } else {
-        const sig = await api.signMessageWithKey(selectedKey.id, messageHex);
+        const sig = await api.signMessageWithKey(
+          selectedKey.id,
+          messageHex,
+          review.summary_hash_hex,
+        );
         setSignature(sig);
         setHybridSig(null);
         toast.success("Message signed with ML-DSA-87");